@@ -5,6 +5,9 @@
 #include <stdint.h>
 #include <string.h>
 
+// Generated with: solang --target generic --emit c-header incrementer.sol
+#include "incrementer.h"
+
 // Hex printer for helping us
 void dump_hex(uint8_t *data, uint32_t size)
 {
@@ -12,15 +15,24 @@ void dump_hex(uint8_t *data, uint32_t size)
 		printf("%02x", *data++);
 }
 
-// A solang wasm module exports these two functions
-extern int solang_constructor(uint8_t *data, uint32_t size);
-extern int solang_function(uint8_t *data, uint32_t size);
-
-// These functions can be called from a solang module
-void* solang_malloc(uint32_t size) {
+// These functions can be called from a solang module. All of them are
+// declared in the generated header; a host program must implement all of them
+// so that it can be linked with any contract.
+void *solang_malloc(uint32_t size)
+{
 	return malloc(size);
 }
 
+void *solang_realloc(void *ptr, uint32_t size)
+{
+	return realloc(ptr, size);
+}
+
+void solang_free(void *ptr)
+{
+	free(ptr);
+}
+
 struct storage_entry {
 	struct storage_entry *next;
 	uint8_t key[32];
@@ -118,11 +130,137 @@ void solang_set_return(uint8_t *data, uint32_t size)
 	printf("\n");
 }
 
+void solang_print(uint8_t *data, uint32_t size)
+{
+	printf("solang_print: %.*s\n", (int)size, data);
+}
+
+void solang_emit_event(uint8_t *data, uint32_t data_size, uint8_t *topics, uint32_t topic_count)
+{
+	printf("solang_emit_event: data:");
+	dump_hex(data, data_size);
+	while (topic_count--) {
+		printf(" topic:");
+		dump_hex(topics, 32);
+		topics += 32;
+	}
+	printf("\n");
+}
+
+// This example has no accounts or balances; no value is ever transferred.
+void solang_value_transferred(uint8_t value[SOLANG_VALUE_LENGTH])
+{
+	memset(value, 0, SOLANG_VALUE_LENGTH);
+}
+
+void solang_balance(uint8_t address[SOLANG_ADDRESS_LENGTH], uint8_t value[SOLANG_VALUE_LENGTH])
+{
+	memset(value, 0, SOLANG_VALUE_LENGTH);
+}
+
+uint32_t solang_transfer(uint8_t address[SOLANG_ADDRESS_LENGTH], uint8_t value[SOLANG_VALUE_LENGTH])
+{
+	printf("solang_transfer: not supported\n");
+	return 1;
+}
+
+void solang_address(uint8_t address[SOLANG_ADDRESS_LENGTH])
+{
+	memset(address, 0x01, SOLANG_ADDRESS_LENGTH);
+}
+
+void solang_caller(uint8_t address[SOLANG_ADDRESS_LENGTH])
+{
+	memset(address, 0x02, SOLANG_ADDRESS_LENGTH);
+}
+
+void solang_tx_origin(uint8_t address[SOLANG_ADDRESS_LENGTH])
+{
+	memset(address, 0x02, SOLANG_ADDRESS_LENGTH);
+}
+
+uint64_t solang_block_number(void)
+{
+	return 1;
+}
+
+uint64_t solang_timestamp(void)
+{
+	return 1609459200;
+}
+
+uint64_t solang_gasleft(void)
+{
+	return UINT64_MAX;
+}
+
+void solang_gasprice(uint8_t value[SOLANG_VALUE_LENGTH])
+{
+	memset(value, 0, SOLANG_VALUE_LENGTH);
+}
+
+// There are no other contracts in this example, so calling or creating
+// contracts always fails.
+uint32_t solang_call(uint32_t call_type, uint8_t address[SOLANG_ADDRESS_LENGTH], uint8_t value[SOLANG_VALUE_LENGTH], uint64_t gas, uint8_t *input, uint32_t input_size)
+{
+	printf("solang_call: not supported\n");
+	return 1;
+}
+
+uint32_t solang_create(uint8_t *contract_name, uint32_t contract_name_size, uint8_t *input, uint32_t input_size, uint8_t value[SOLANG_VALUE_LENGTH], uint8_t *salt, uint8_t address[SOLANG_ADDRESS_LENGTH])
+{
+	printf("solang_create: %.*s not supported\n", (int)contract_name_size, contract_name);
+	return 1;
+}
+
+uint32_t solang_return_data_size(void)
+{
+	return 0;
+}
+
+void solang_return_data_copy(uint8_t *data)
+{
+}
+
+void solang_selfdestruct(uint8_t recipient[SOLANG_ADDRESS_LENGTH])
+{
+	printf("solang_selfdestruct\n");
+	exit(0);
+}
+
+// Hash functions are not provided by this example
+static void no_hash(const char *name)
+{
+	printf("%s: not supported\n", name);
+	abort();
+}
+
+void solang_sha256(uint8_t *input, uint32_t size, uint8_t *hash)
+{
+	no_hash("sha256");
+}
+
+void solang_ripemd160(uint8_t *input, uint32_t size, uint8_t *hash)
+{
+	no_hash("ripemd160");
+}
+
+void solang_blake2_128(uint8_t *input, uint32_t size, uint8_t *hash)
+{
+	no_hash("blake2_128");
+}
+
+void solang_blake2_256(uint8_t *input, uint32_t size, uint8_t *hash)
+{
+	no_hash("blake2_256");
+}
+
 // incrementer constructor expects single uint32 as argument
 // ethabi encode params -l -v uint32 102 | sed 's/.\{2\}/,0x&/g'
 static uint8_t constructor_arg[] = { 0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x66 };
 // inc expects a value to increment by
 // ethabi encode function incrementer.abi inc -l -p 102 | sed 's/.\{2\}/,0x&/g'
+// The first four bytes are INCREMENTER_INC_SELECTOR
 static uint8_t inc_function_arg[] = { 0xdd,0x5d,0x52,0x11,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x66 };
 // get retrieves the value
 // ethabi encode function incrementer.abi get  -l  | sed 's/.\{2\}/,0x&/g'
@@ -137,7 +275,7 @@ int main(int argc, char *argv[])
 	printf("Calling incrementer constructor with 102 arg.\n");
 
 	int ret = solang_constructor(constructor_arg, sizeof(constructor_arg));
-	if (ret) {
+	if (ret != SOLANG_SUCCESS) {
 		printf("error: solang_constructor returned %d\n", ret);
 		exit(1);
 	}
@@ -145,7 +283,7 @@ int main(int argc, char *argv[])
 	// Call the inc() function with the argument 102
 	printf("Calling incrementer function inc 102 arg.\n");
 	ret = solang_function(inc_function_arg, sizeof(inc_function_arg));
-	if (ret) {
+	if (ret != SOLANG_SUCCESS) {
 		printf("error: solang_function returned %d\n", ret);
 		exit(1);
 	}
@@ -155,7 +293,7 @@ int main(int argc, char *argv[])
 	// set via solang_set_return
 	printf("Calling incrementer function get\n");
 	ret = solang_function(get_function_arg, sizeof(get_function_arg));
-	if (ret) {
+	if (ret != SOLANG_SUCCESS) {
 		printf("error: solang_function returned %d\n", ret);
 		exit(1);
 	}
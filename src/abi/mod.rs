@@ -2,6 +2,7 @@ use crate::sema::ast::Namespace;
 use crate::Target;
 
 pub mod ethereum;
+pub mod storage_layout;
 pub mod substrate;
 
 pub fn generate_abi(
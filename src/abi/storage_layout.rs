@@ -0,0 +1,583 @@
+// solc style storage layout
+use crate::parser::pt;
+use crate::sema::ast::{Diagnostic, Namespace, Type};
+use crate::sema::codes;
+use crate::sema::contracts::visit_bases;
+use crate::Target;
+use num_bigint::BigInt;
+use num_traits::Zero;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+#[derive(Serialize, Deserialize)]
+pub struct StorageLayout {
+    pub storage: Vec<StorageEntry>,
+    pub types: BTreeMap<String, StorageType>,
+    /// On Solana, the number of bytes of account data needed for the fixed layout
+    #[serde(rename = "accountDataSize", skip_serializing_if = "Option::is_none")]
+    pub account_data_size: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct StorageEntry {
+    pub label: String,
+    /// The contract which declares the variable, as `file:Name`
+    pub contract: String,
+    /// The slot as a decimal string, since it may not fit in 64 bits. On Solana, this is
+    /// the offset in the account data.
+    pub slot: String,
+    pub offset: usize,
+    #[serde(rename = "type")]
+    pub ty: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct StorageType {
+    pub encoding: String,
+    pub label: String,
+    #[serde(rename = "numberOfBytes")]
+    pub number_of_bytes: String,
+    /// The element type of an array
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    /// The fields of a struct; the slot is relative to the start of the struct
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub members: Option<Vec<StorageEntry>>,
+}
+
+/// Generate the storage layout of a contract, including the variables of its base contracts
+pub fn storage_layout(contract_no: usize, ns: &Namespace) -> StorageLayout {
+    let mut storage = Vec::new();
+    let mut types = BTreeMap::new();
+
+    for layout in &ns.contracts[contract_no].layout {
+        let contract = &ns.contracts[layout.contract_no];
+        let var = &contract.variables[layout.var_no];
+
+        storage.push(StorageEntry {
+            label: var.name.to_owned(),
+            contract: format!("{}:{}", ns.files[contract.loc.0].display(), contract.name),
+            slot: layout.slot.to_string(),
+            offset: 0,
+            ty: add_type(&layout.ty, &mut types, ns),
+        });
+    }
+
+    let account_data_size = if ns.target == Target::Solana {
+        Some(ns.contracts[contract_no].fixed_layout_size.to_string())
+    } else {
+        None
+    };
+
+    StorageLayout {
+        storage,
+        types,
+        account_data_size,
+    }
+}
+
+/// Add the type and the types it contains to the types table, and return its identifier
+fn add_type(ty: &Type, types: &mut BTreeMap<String, StorageType>, ns: &Namespace) -> String {
+    let label = ty.to_string(ns);
+    let id = type_id(ty, ns);
+
+    if types.contains_key(&id) {
+        return id;
+    }
+
+    // structs can contain themselves through a mapping or dynamic array, so add the entry
+    // before visiting the contained types
+    types.insert(
+        id.clone(),
+        StorageType {
+            encoding: encoding(ty).to_owned(),
+            number_of_bytes: number_of_bytes(ty, ns).to_string(),
+            label,
+            base: None,
+            key: None,
+            value: None,
+            members: None,
+        },
+    );
+
+    match ty {
+        Type::Mapping(key, value) => {
+            let key = add_type(key, types, ns);
+            let value = add_type(value, types, ns);
+
+            let entry = types.get_mut(&id).unwrap();
+            entry.key = Some(key);
+            entry.value = Some(value);
+        }
+        Type::Array(..) => {
+            let base = add_type(&ty.array_elem(), types, ns);
+
+            types.get_mut(&id).unwrap().base = Some(base);
+        }
+        Type::Struct(struct_no) => {
+            let def = &ns.structs[*struct_no];
+            let mut members = Vec::new();
+            let mut slot = BigInt::zero();
+
+            let contract = match &def.contract {
+                Some(contract) => format!("{}:{}", ns.files[def.loc.0].display(), contract),
+                None => format!("{}", ns.files[def.loc.0].display()),
+            };
+
+            for (field_no, field) in def.fields.iter().enumerate() {
+                // On Solana, fields are laid out like in memory
+                if ns.target == Target::Solana {
+                    slot = def.offsets[field_no].clone();
+                }
+
+                members.push(StorageEntry {
+                    label: field.name.to_owned(),
+                    contract: contract.clone(),
+                    slot: slot.to_string(),
+                    offset: 0,
+                    ty: add_type(&field.ty, types, ns),
+                });
+
+                if ns.target != Target::Solana {
+                    slot += field.ty.storage_slots(ns);
+                }
+            }
+
+            types.get_mut(&id).unwrap().members = Some(members);
+        }
+        _ => (),
+    }
+
+    id
+}
+
+/// Type identifiers are built from the structure of the type like solc does, e.g.
+/// `t_mapping(t_address,t_array(t_uint256)dyn_storage)`. Types which are not behind a
+/// reference are in storage.
+pub(crate) fn type_id(ty: &Type, ns: &Namespace) -> String {
+    located_type_id(ty, "storage", ns)
+}
+
+fn located_type_id(ty: &Type, location: &str, ns: &Namespace) -> String {
+    match ty {
+        Type::Address(false) => String::from("t_address"),
+        Type::Address(true) => String::from("t_address_payable"),
+        Type::Bool => String::from("t_bool"),
+        Type::Int(n) => format!("t_int{}", n),
+        Type::Uint(n) => format!("t_uint{}", n),
+        Type::Value => format!("t_uint{}", ns.value_length * 8),
+        Type::Bytes(n) => format!("t_bytes{}", n),
+        Type::DynamicBytes => format!("t_bytes_{}", location),
+        Type::String => format!("t_string_{}", location),
+        Type::Array(_, dims) => {
+            let len = match dims.last().unwrap() {
+                Some(len) => len.to_string(),
+                None => String::from("dyn"),
+            };
+
+            // the elements of an array behind a storage pointer are in storage
+            let elem_location = if location == "storage_ptr" {
+                "storage"
+            } else {
+                location
+            };
+
+            format!(
+                "t_array({}){}_{}",
+                located_type_id(&ty.array_elem(), elem_location, ns),
+                len,
+                location
+            )
+        }
+        Type::Enum(n) => format!("t_enum({}){}", ns.enums[*n].name, n),
+        Type::Struct(n) => format!("t_struct({}){}_{}", ns.structs[*n].name, n, location),
+        Type::Mapping(key, value) => format!(
+            "t_mapping({},{})",
+            located_type_id(key, "memory_ptr", ns),
+            type_id(value, ns)
+        ),
+        Type::Contract(n) => format!("t_contract({}){}", ns.contracts[*n].name, n),
+        Type::Ref(ty) => located_type_id(ty, "memory_ptr", ns),
+        Type::StorageRef(ty) => located_type_id(ty, "storage_ptr", ns),
+        Type::InternalFunction {
+            mutability,
+            params,
+            returns,
+        }
+        | Type::ExternalFunction {
+            mutability,
+            params,
+            returns,
+        } => format!(
+            "t_function_{}_{}({})returns({})",
+            if matches!(ty, Type::InternalFunction { .. }) {
+                "internal"
+            } else {
+                "external"
+            },
+            match mutability {
+                Some(mutability) => mutability.to_string(),
+                None => String::from("nonpayable"),
+            },
+            list_type_id(params, ns),
+            list_type_id(returns, ns)
+        ),
+        Type::Void => String::from("t_void"),
+        Type::Unreachable => String::from("t_unreachable"),
+        Type::Slice => String::from("t_slice"),
+    }
+}
+
+/// The identifiers of a list of types, like function parameters or the values of a tuple
+pub(crate) fn list_type_id(tys: &[Type], ns: &Namespace) -> String {
+    tys.iter()
+        .map(|ty| located_type_id(ty, "memory_ptr", ns))
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+fn encoding(ty: &Type) -> &'static str {
+    match ty {
+        Type::Mapping(..) => "mapping",
+        Type::String | Type::DynamicBytes => "bytes",
+        Type::Array(_, dims) if dims.last() == Some(&None) => "dynamic_array",
+        _ => "inplace",
+    }
+}
+
+/// The number of bytes the type occupies in storage. Except on Solana, every variable
+/// starts in a new slot of 32 bytes.
+fn number_of_bytes(ty: &Type, ns: &Namespace) -> BigInt {
+    if ns.target == Target::Solana {
+        ty.storage_slots(ns)
+    } else {
+        ty.storage_slots(ns) * 32
+    }
+}
+
+/// Compare the storage layout of a contract with the layout of its previous version. Changes
+/// which would make the upgraded contract misread the existing storage are errors; variables
+/// appended after the existing ones are fine.
+pub fn check_upgrade(previous: &StorageLayout, contract_no: usize, ns: &mut Namespace) {
+    let current = storage_layout(contract_no, ns);
+    let contract_loc = ns.contracts[contract_no].loc;
+    let mut diagnostics = Vec::new();
+
+    check_base_order(previous, &current, contract_no, ns, &mut diagnostics);
+
+    // variables are identified by the contract which declares them and their name
+    let mut matched = vec![false; current.storage.len()];
+
+    for (old_no, old) in previous.storage.iter().enumerate() {
+        let found = current
+            .storage
+            .iter()
+            .position(|new| new.label == old.label && contract_name(new) == contract_name(old));
+
+        if let Some(new_no) = found {
+            matched[new_no] = true;
+
+            let new = &current.storage[new_no];
+            let loc = variable_loc(contract_no, new_no, ns);
+
+            if new.slot != old.slot {
+                diagnostics.push(storage_error(
+                    loc,
+                    format!(
+                        "variable ‘{}’ of ‘{}’ moved from slot {} to slot {}",
+                        old.label,
+                        contract_name(old),
+                        old.slot,
+                        new.slot
+                    ),
+                ));
+            } else if let Some(change) = type_change(&old.ty, previous, &new.ty, &current) {
+                diagnostics.push(storage_error(
+                    loc,
+                    format!(
+                        "variable ‘{}’ of ‘{}’: {}",
+                        old.label,
+                        contract_name(old),
+                        change
+                    ),
+                ));
+            }
+
+            continue;
+        }
+
+        // a variable in the same place with the same type, which does not exist in the
+        // previous version, is probably a rename
+        let renamed = current.storage.get(old_no).filter(|new| {
+            new.slot == old.slot
+                && contract_name(new) == contract_name(old)
+                && type_change(&old.ty, previous, &new.ty, &current).is_none()
+                && !previous
+                    .storage
+                    .iter()
+                    .any(|e| e.label == new.label && contract_name(e) == contract_name(new))
+        });
+
+        if let Some(new) = renamed {
+            matched[old_no] = true;
+
+            diagnostics.push(
+                Diagnostic::warning(
+                    variable_loc(contract_no, old_no, ns),
+                    format!(
+                        "variable ‘{}’ of ‘{}’ in slot {} was previously named ‘{}’",
+                        new.label,
+                        contract_name(new),
+                        new.slot,
+                        old.label
+                    ),
+                )
+                .with_code(codes::STORAGE_RENAMED),
+            );
+        } else {
+            diagnostics.push(storage_error(
+                contract_loc,
+                format!(
+                    "variable ‘{}’ of ‘{}’ in slot {} was removed",
+                    old.label,
+                    contract_name(old),
+                    old.slot
+                ),
+            ));
+        }
+    }
+
+    // new variables must come after all the existing ones
+    let last_existing = matched.iter().rposition(|m| *m);
+
+    for (new_no, new) in current.storage.iter().enumerate() {
+        if matched[new_no] {
+            continue;
+        }
+
+        let loc = variable_loc(contract_no, new_no, ns);
+
+        if last_existing.map_or(true, |last| new_no > last) {
+            diagnostics.push(Diagnostic::info(
+                loc,
+                format!(
+                    "variable ‘{}’ of ‘{}’ is appended in slot {}",
+                    new.label,
+                    contract_name(new),
+                    new.slot
+                ),
+            ));
+        } else {
+            diagnostics.push(storage_error(
+                loc,
+                format!(
+                    "variable ‘{}’ of ‘{}’ is inserted in slot {} before existing variables",
+                    new.label,
+                    contract_name(new),
+                    new.slot
+                ),
+            ));
+        }
+    }
+
+    ns.diagnostics.extend(diagnostics);
+}
+
+/// The variables of base contracts come first, in the order of the linearization of the
+/// bases. If this order changes, the variables of whole base contracts move.
+fn check_base_order(
+    previous: &StorageLayout,
+    current: &StorageLayout,
+    contract_no: usize,
+    ns: &Namespace,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let mut previous_bases: Vec<&str> = Vec::new();
+
+    for entry in &previous.storage {
+        let name = contract_name(entry);
+
+        if !previous_bases.contains(&name) {
+            previous_bases.push(name);
+        }
+    }
+
+    // the bases which declare state variables, in layout order
+    let current_bases: Vec<usize> = visit_bases(contract_no, ns)
+        .into_iter()
+        .filter(|base_no| {
+            let name = &ns.contracts[*base_no].name;
+
+            current
+                .storage
+                .iter()
+                .any(|e| contract_name(e) == name.as_str())
+        })
+        .collect();
+
+    let existing: Vec<&str> = current_bases
+        .iter()
+        .map(|base_no| ns.contracts[*base_no].name.as_str())
+        .filter(|name| previous_bases.contains(name))
+        .collect();
+
+    let retained: Vec<&str> = previous_bases
+        .iter()
+        .copied()
+        .filter(|name| existing.contains(name))
+        .collect();
+
+    if existing != retained {
+        diagnostics.push(storage_error(
+            ns.contracts[contract_no].loc,
+            format!(
+                "order of base contracts with state variables changed from ‘{}’ to ‘{}’",
+                retained.join(", "),
+                existing.join(", ")
+            ),
+        ));
+    }
+
+    // a new base contract with state variables is only allowed after the existing ones
+    if let Some(last) = current_bases
+        .iter()
+        .rposition(|base_no| previous_bases.contains(&ns.contracts[*base_no].name.as_str()))
+    {
+        for base_no in &current_bases[..last] {
+            let base = &ns.contracts[*base_no];
+
+            if !previous_bases.contains(&base.name.as_str()) {
+                diagnostics.push(storage_error(
+                    base.loc,
+                    format!(
+                        "base contract ‘{}’ with state variables is inserted before existing variables of ‘{}’",
+                        base.name,
+                        ns.contracts[current_bases[last]].name
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+/// Describe how a type changed, if this makes existing storage unreadable
+fn type_change(
+    old: &str,
+    previous: &StorageLayout,
+    new: &str,
+    current: &StorageLayout,
+) -> Option<String> {
+    compare_types(old, previous, new, current, &mut HashSet::new())
+}
+
+/// Types can contain themselves, through a mapping or dynamic array or a hand-written layout,
+/// so each pair of types is only compared once; a pair which is already being compared has
+/// no further changes
+fn compare_types<'a>(
+    old: &'a str,
+    previous: &'a StorageLayout,
+    new: &'a str,
+    current: &'a StorageLayout,
+    visited: &mut HashSet<(&'a str, &'a str)>,
+) -> Option<String> {
+    let (old_ty, new_ty) = match (previous.types.get(old), current.types.get(new)) {
+        (Some(old_ty), Some(new_ty)) => (old_ty, new_ty),
+        _ => return Some(format!("type changed from ‘{}’ to ‘{}’", old, new)),
+    };
+
+    if !visited.insert((old, new)) {
+        return None;
+    }
+
+    let old_bytes = old_ty.number_of_bytes.parse::<BigInt>().ok();
+    let new_bytes = new_ty.number_of_bytes.parse::<BigInt>().ok();
+
+    if old_ty.label != new_ty.label {
+        return if old_ty.encoding == new_ty.encoding && new_bytes < old_bytes {
+            Some(format!(
+                "type shrunk from ‘{}’ of {} bytes to ‘{}’ of {} bytes",
+                old_ty.label, old_ty.number_of_bytes, new_ty.label, new_ty.number_of_bytes
+            ))
+        } else {
+            Some(format!(
+                "type changed from ‘{}’ to ‘{}’",
+                old_ty.label, new_ty.label
+            ))
+        };
+    }
+
+    if let (Some(old_value), Some(new_value)) = (&old_ty.value, &new_ty.value) {
+        if let Some(change) = compare_types(old_value, previous, new_value, current, visited) {
+            return Some(format!("mapping value {}", change));
+        }
+    }
+
+    if let (Some(old_base), Some(new_base)) = (&old_ty.base, &new_ty.base) {
+        if let Some(change) = compare_types(old_base, previous, new_base, current, visited) {
+            return Some(format!("array element {}", change));
+        }
+
+        // the elements are stored one after another, so they cannot grow
+        let old_size = &previous.types[old_base].number_of_bytes;
+        let new_size = &current.types[new_base].number_of_bytes;
+
+        if old_size != new_size {
+            return Some(format!(
+                "size of array element ‘{}’ changed from {} to {} bytes",
+                old_ty.label, old_size, new_size
+            ));
+        }
+    }
+
+    if let (Some(old_members), Some(new_members)) = (&old_ty.members, &new_ty.members) {
+        for (field_no, old_field) in old_members.iter().enumerate() {
+            let new_field = match new_members.get(field_no) {
+                Some(new_field) if new_field.label == old_field.label => new_field,
+                _ => {
+                    return Some(format!(
+                        "field ‘{}’ of ‘{}’ was removed or moved",
+                        old_field.label, old_ty.label
+                    ))
+                }
+            };
+
+            if new_field.slot != old_field.slot {
+                return Some(format!(
+                    "field ‘{}’ of ‘{}’ moved from {} to {}",
+                    old_field.label, old_ty.label, old_field.slot, new_field.slot
+                ));
+            }
+
+            if let Some(change) =
+                compare_types(&old_field.ty, previous, &new_field.ty, current, visited)
+            {
+                return Some(format!(
+                    "field ‘{}’ of ‘{}’: {}",
+                    old_field.label, old_ty.label, change
+                ));
+            }
+        }
+    }
+
+    None
+}
+
+/// The name of the contract which declares the variable; the file name is not compared, since
+/// the previous version may be in another directory
+fn contract_name(entry: &StorageEntry) -> &str {
+    entry.contract.rsplit(':').next().unwrap()
+}
+
+fn variable_loc(contract_no: usize, layout_no: usize, ns: &Namespace) -> pt::Loc {
+    let layout = &ns.contracts[contract_no].layout[layout_no];
+
+    ns.contracts[layout.contract_no].variables[layout.var_no].loc
+}
+
+fn storage_error(loc: pt::Loc, message: String) -> Diagnostic {
+    Diagnostic::error(loc, message).with_code(codes::STORAGE_LAYOUT)
+}
@@ -0,0 +1,91 @@
+use super::print_messages;
+use clap::ArgMatches;
+use solang::file_cache::FileCache;
+use solang::parser::formatter::{format, FormatOptions};
+use solang::sema::ast::Namespace;
+use std::fs;
+use std::path::PathBuf;
+
+/// Format the input files in place, or with `--check`, report the files which are not
+/// formatted
+pub fn format_files(matches: &ArgMatches) {
+    let options = FormatOptions {
+        indent: parse_number(matches, "INDENT"),
+        tabs: matches.is_present("TABS"),
+        line_width: parse_number(matches, "LINEWIDTH"),
+    };
+    let check = matches.is_present("CHECK");
+    let mut success = true;
+
+    for filename in matches.values_of("INPUT").unwrap() {
+        let src = match fs::read_to_string(filename) {
+            Ok(src) => src,
+            Err(e) => {
+                eprintln!("error: cannot read file ‘{}’: {}", filename, e);
+                success = false;
+                continue;
+            }
+        };
+
+        let formatted = match format(&src, &options) {
+            Ok(formatted) => formatted,
+            Err(errors) => {
+                let mut cache = FileCache::new();
+
+                cache.set_file_contents(filename, src);
+
+                // the namespace is only needed to print the syntax errors
+                let mut ns = Namespace::new(solang::Target::Generic, 20, 16);
+
+                ns.files.push(PathBuf::from(filename));
+                ns.diagnostics = errors;
+
+                print_messages(&mut cache, &ns, matches);
+                success = false;
+                continue;
+            }
+        };
+
+        if formatted == src {
+            continue;
+        }
+
+        if check {
+            match first_difference(&src, &formatted) {
+                Some(line) => eprintln!("{}:{}: error: file is not formatted", filename, line),
+                None => eprintln!("{}: error: file is not formatted", filename),
+            }
+
+            success = false;
+        } else if let Err(e) = fs::write(filename, formatted) {
+            eprintln!("error: cannot write file ‘{}’: {}", filename, e);
+            success = false;
+        } else if matches.is_present("VERBOSE") {
+            eprintln!("info: formatted ‘{}’", filename);
+        }
+    }
+
+    if !success {
+        std::process::exit(1);
+    }
+}
+
+fn parse_number(matches: &ArgMatches, name: &str) -> usize {
+    let value = matches.value_of(name).unwrap();
+
+    match value.parse() {
+        Ok(n) => n,
+        Err(_) => {
+            eprintln!("error: ‘{}’ is not a valid number", value);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// The first line which is changed by formatting, counting from 1
+fn first_difference(src: &str, formatted: &str) -> Option<usize> {
+    src.lines()
+        .zip(formatted.lines())
+        .position(|(a, b)| a != b)
+        .map(|no| no + 1)
+}
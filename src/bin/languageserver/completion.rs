@@ -0,0 +1,890 @@
+use solang::parser::pt;
+use solang::sema::ast::{Namespace, Symbol, Type};
+use solang::sema::builtin::{available_builtins, Prototype};
+use solang::sema::contracts::visit_bases;
+use solang::sema::tags::render;
+use std::collections::HashSet;
+use tower_lsp::lsp_types::{CompletionItem, CompletionItemKind, Documentation};
+
+/// What the expression before the `.` refers to
+pub enum Receiver {
+    /// Builtin namespace like `msg` or `abi`
+    Builtin(String),
+    /// A value of this type
+    Value(Type),
+    /// The name of a contract or library
+    Contract(usize),
+    /// The name of an enum
+    Enum(usize),
+    /// An imported file, like `import "foo.sol" as foo`
+    File(usize),
+}
+
+/// One element of a member access chain like `a.b(c)[1]`
+pub struct Segment {
+    pub name: String,
+    call: bool,
+    subscripts: usize,
+}
+
+/// The position of the cursor within the first file of the namespace
+pub struct Context<'a> {
+    pub ns: &'a Namespace,
+    text: &'a str,
+    offset: usize,
+    /// The contract the cursor is in, followed by its bases
+    pub contracts: Vec<usize>,
+    function_no: Option<usize>,
+}
+
+/// List the completions at the offset in the text. The namespace should be the result of
+/// resolving the same text, but the user may have typed since.
+pub fn completions(ns: &Namespace, text: &str, offset: usize) -> Vec<CompletionItem> {
+    let offset = offset.min(text.len());
+
+    let cx = Context::new(ns, text, offset);
+
+    // skip over the identifier being typed
+    let start = text[..offset]
+        .rfind(|c: char| !is_identifier(c))
+        .map(|pos| pos + 1)
+        .unwrap_or(0);
+
+    let items = if text[..start].ends_with('.') {
+        match member_chain(text, start - 1).and_then(|chain| cx.resolve(&chain)) {
+            Some(receiver) => cx.members(&receiver),
+            None => Vec::new(),
+        }
+    } else {
+        cx.scope()
+    };
+
+    // overloaded functions and variables in different scopes should be listed once
+    let mut seen = HashSet::new();
+
+    items
+        .into_iter()
+        .filter(|item| seen.insert(item.label.clone()))
+        .collect()
+}
+
+impl<'a> Context<'a> {
+    /// Find the contract and function which the offset is in
+    pub fn new(ns: &'a Namespace, text: &'a str, offset: usize) -> Self {
+        let contract_no = ns
+            .contracts
+            .iter()
+            .enumerate()
+            .filter(|(_, contract)| contract.loc.0 == 0 && contract.loc.1 <= offset)
+            .max_by_key(|(_, contract)| contract.loc.1)
+            .filter(|(_, contract)| is_inside(text, contract.loc.1, offset))
+            .map(|(contract_no, _)| contract_no);
+
+        let function_no = ns
+            .functions
+            .iter()
+            .enumerate()
+            .filter(|(_, func)| {
+                func.loc.0 == 0
+                    && func.loc.1 <= offset
+                    && !func.is_accessor
+                    && func.contract_no == contract_no
+            })
+            .max_by_key(|(_, func)| func.loc.1)
+            .filter(|(_, func)| is_inside(text, func.loc.1, offset))
+            .map(|(function_no, _)| function_no);
+
+        let contracts = match contract_no {
+            Some(contract_no) => {
+                let mut bases = visit_bases(contract_no, ns);
+                bases.reverse();
+                bases
+            }
+            None => Vec::new(),
+        };
+
+        Context {
+            ns,
+            text,
+            offset,
+            contracts,
+            function_no,
+        }
+    }
+}
+
+fn is_identifier(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '$'
+}
+
+/// Is the offset within the first block which starts after `start`
+fn is_inside(text: &str, start: usize, offset: usize) -> bool {
+    let mut depth = 0;
+
+    for c in text.get(start..offset).unwrap_or_default().chars() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+
+                if depth == 0 {
+                    return false;
+                }
+            }
+            _ => (),
+        }
+    }
+
+    depth > 0
+}
+
+/// Is a variable declared at `start` still in scope at the offset, i.e. has its
+/// enclosing block not been closed
+fn in_scope(text: &str, start: usize, offset: usize) -> bool {
+    let mut depth = 0;
+
+    for c in text.get(start..offset).unwrap_or_default().chars() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+
+                if depth < 0 {
+                    return false;
+                }
+            }
+            _ => (),
+        }
+    }
+
+    true
+}
+
+/// Parse the member access chain which ends at `end` backwards, e.g. `a.b(1)[2]`
+pub fn member_chain(text: &str, end: usize) -> Option<Vec<Segment>> {
+    let bytes = text.as_bytes();
+    let mut pos = end;
+    let mut chain = Vec::new();
+
+    loop {
+        let mut call = false;
+        let mut subscripts = 0;
+
+        loop {
+            match pos.checked_sub(1).map(|p| bytes[p]) {
+                Some(b']') => {
+                    pos = skip_brackets(bytes, pos, b'[', b']')?;
+                    subscripts += 1;
+                }
+                Some(b')') => {
+                    pos = skip_brackets(bytes, pos, b'(', b')')?;
+                    call = true;
+                }
+                _ => break,
+            }
+        }
+
+        let start = text[..pos]
+            .rfind(|c: char| !is_identifier(c))
+            .map(|p| p + 1)
+            .unwrap_or(0);
+
+        if start == pos {
+            return None;
+        }
+
+        chain.push(Segment {
+            name: text[start..pos].to_owned(),
+            call,
+            subscripts,
+        });
+
+        if start > 0 && bytes[start - 1] == b'.' {
+            pos = start - 1;
+        } else {
+            break;
+        }
+    }
+
+    chain.reverse();
+
+    Some(chain)
+}
+
+/// Skip backwards over a bracketed expression which ends just before `end`
+fn skip_brackets(bytes: &[u8], end: usize, open: u8, close: u8) -> Option<usize> {
+    let mut depth = 0;
+    let mut pos = end;
+
+    while pos > 0 {
+        pos -= 1;
+
+        if bytes[pos] == close {
+            depth += 1;
+        } else if bytes[pos] == open {
+            depth -= 1;
+
+            if depth == 0 {
+                return Some(pos);
+            }
+        }
+    }
+
+    None
+}
+
+impl<'a> Context<'a> {
+    /// Completions for an identifier without any `.`
+    fn scope(&self) -> Vec<CompletionItem> {
+        let ns = self.ns;
+        let mut items = Vec::new();
+
+        if let Some(function_no) = self.function_no {
+            let mut vars: Vec<_> = ns.functions[function_no]
+                .symtable
+                .vars
+                .values()
+                .filter(|var| {
+                    !var.id.name.is_empty()
+                        && var.id.loc.2 <= self.offset
+                        && in_scope(self.text, var.id.loc.2, self.offset)
+                })
+                .collect();
+
+            // the most recently declared comes first, in case of shadowing
+            vars.sort_by_key(|var| std::cmp::Reverse(var.id.loc.1));
+
+            for var in vars {
+                items.push(item(
+                    &var.id.name,
+                    CompletionItemKind::Variable,
+                    var.ty.to_string(ns),
+                    String::new(),
+                ));
+            }
+        }
+
+        for ((file_no, contract_no, name), symbol) in
+            ns.variable_symbols.iter().chain(ns.function_symbols.iter())
+        {
+            let visible = match contract_no {
+                None => *file_no == 0,
+                Some(contract_no) => self.contracts.contains(contract_no),
+            };
+
+            if visible {
+                items.extend(self.symbol_item(name, symbol));
+            }
+        }
+
+        let mut namespaces = HashSet::new();
+
+        for prototype in available_builtins(ns) {
+            match prototype.namespace {
+                None => items.push(builtin_item(prototype, ns)),
+                Some(namespace) => {
+                    if namespaces.insert(namespace) {
+                        items.push(item(
+                            namespace,
+                            CompletionItemKind::Module,
+                            String::from("builtin"),
+                            String::new(),
+                        ));
+                    }
+                }
+            }
+        }
+
+        if !self.contracts.is_empty() {
+            items.push(item(
+                "this",
+                CompletionItemKind::Keyword,
+                Type::Contract(self.contracts[0]).to_string(ns),
+                String::new(),
+            ));
+        }
+
+        items
+    }
+
+    /// Completions for the members of the receiver
+    fn members(&self, receiver: &Receiver) -> Vec<CompletionItem> {
+        let ns = self.ns;
+        let mut items = Vec::new();
+
+        match receiver {
+            Receiver::Builtin(namespace) => {
+                for prototype in available_builtins(ns) {
+                    if prototype.namespace == Some(namespace.as_str()) {
+                        items.push(builtin_item(prototype, ns));
+                    }
+                }
+            }
+            Receiver::Enum(enum_no) => {
+                let decl = &ns.enums[*enum_no];
+
+                let mut values: Vec<_> = decl.values.iter().collect();
+                values.sort_by_key(|(_, (_, value))| *value);
+
+                for (name, _) in values {
+                    items.push(item(
+                        name,
+                        CompletionItemKind::EnumMember,
+                        decl.to_string(),
+                        render(&decl.tags),
+                    ));
+                }
+            }
+            Receiver::Contract(contract_no) => {
+                for ((_, c, name), symbol) in
+                    ns.variable_symbols.iter().chain(ns.function_symbols.iter())
+                {
+                    if *c == Some(*contract_no) {
+                        items.extend(self.symbol_item(name, symbol));
+                    }
+                }
+            }
+            Receiver::File(file_no) => {
+                for ((f, c, name), symbol) in
+                    ns.variable_symbols.iter().chain(ns.function_symbols.iter())
+                {
+                    if f == file_no && c.is_none() {
+                        items.extend(self.symbol_item(name, symbol));
+                    }
+                }
+            }
+            Receiver::Value(ty) => {
+                match ty.deref_any() {
+                    Type::Struct(struct_no) => {
+                        for field in &ns.structs[*struct_no].fields {
+                            items.push(item(
+                                &field.name,
+                                CompletionItemKind::Field,
+                                field.ty.to_string(ns),
+                                String::new(),
+                            ));
+                        }
+                    }
+                    Type::Contract(contract_no) => {
+                        for function_no in ns.contracts[*contract_no].all_functions.keys() {
+                            let func = &ns.functions[*function_no];
+
+                            if func.ty == pt::FunctionTy::Function && func.is_public() {
+                                items.push(item(
+                                    &func.name,
+                                    CompletionItemKind::Method,
+                                    func.signature.clone(),
+                                    render(&func.tags),
+                                ));
+                            }
+                        }
+                    }
+                    Type::Address(payable) => {
+                        let mut members = vec!["balance", "call", "delegatecall", "staticcall"];
+
+                        if *payable {
+                            members.extend(&["transfer", "send"]);
+                        }
+
+                        for name in members {
+                            items.push(item(
+                                name,
+                                if name == "balance" {
+                                    CompletionItemKind::Property
+                                } else {
+                                    CompletionItemKind::Method
+                                },
+                                ty.to_string(ns),
+                                String::new(),
+                            ));
+                        }
+                    }
+                    Type::Array(_, dims) => {
+                        items.push(item(
+                            "length",
+                            CompletionItemKind::Property,
+                            String::from("uint32"),
+                            String::new(),
+                        ));
+
+                        if dims.last() == Some(&None) {
+                            for name in &["push", "pop"] {
+                                items.push(item(
+                                    name,
+                                    CompletionItemKind::Method,
+                                    ty.to_string(ns),
+                                    String::new(),
+                                ));
+                            }
+                        }
+                    }
+                    Type::DynamicBytes => {
+                        for (name, kind) in &[
+                            ("length", CompletionItemKind::Property),
+                            ("push", CompletionItemKind::Method),
+                            ("pop", CompletionItemKind::Method),
+                        ] {
+                            items.push(item(name, *kind, ty.to_string(ns), String::new()));
+                        }
+                    }
+                    Type::Bytes(_) => {
+                        items.push(item(
+                            "length",
+                            CompletionItemKind::Property,
+                            String::from("uint8"),
+                            String::new(),
+                        ));
+                    }
+                    _ => (),
+                }
+
+                // functions from libraries attached with `using for`
+                for function_no in self.using_functions(ty) {
+                    let func = &ns.functions[function_no];
+
+                    items.push(item(
+                        &func.name,
+                        CompletionItemKind::Method,
+                        func.signature.clone(),
+                        render(&func.tags),
+                    ));
+                }
+            }
+        }
+
+        items
+    }
+
+    /// Library functions which can be called as a method on a value of the given type
+    pub fn using_functions(&self, ty: &Type) -> Vec<usize> {
+        let ns = self.ns;
+        let mut functions = Vec::new();
+
+        if let Some(contract_no) = self.contracts.first() {
+            for (library_no, using_ty) in &ns.contracts[*contract_no].using {
+                if let Some(using_ty) = using_ty {
+                    if using_ty.deref_any() != ty.deref_any() {
+                        continue;
+                    }
+                }
+
+                for function_no in &ns.contracts[*library_no].functions {
+                    let func = &ns.functions[*function_no];
+
+                    if func.ty == pt::FunctionTy::Function
+                        && func
+                            .params
+                            .first()
+                            .map_or(false, |param| param.ty.deref_any() == ty.deref_any())
+                    {
+                        functions.push(*function_no);
+                    }
+                }
+            }
+        }
+
+        functions
+    }
+
+    /// Find what the member access chain refers to
+    pub fn resolve(&self, chain: &[Segment]) -> Option<Receiver> {
+        let (first, rest) = chain.split_first()?;
+
+        let mut receiver = self.resolve_name(first)?;
+
+        receiver = subscript(receiver, first.subscripts)?;
+
+        for segment in rest {
+            receiver = self.resolve_member(&receiver, segment)?;
+            receiver = subscript(receiver, segment.subscripts)?;
+        }
+
+        Some(receiver)
+    }
+
+    /// Resolve the first name in a member access chain
+    fn resolve_name(&self, segment: &Segment) -> Option<Receiver> {
+        let ns = self.ns;
+        let name = segment.name.as_str();
+
+        if !segment.call {
+            if let Some(function_no) = self.function_no {
+                if let Some(var) = ns.functions[function_no]
+                    .symtable
+                    .vars
+                    .values()
+                    .filter(|var| {
+                        var.id.name == name
+                            && var.id.loc.2 <= self.offset
+                            && in_scope(self.text, var.id.loc.2, self.offset)
+                    })
+                    .max_by_key(|var| var.id.loc.1)
+                {
+                    return Some(Receiver::Value(var.ty.clone()));
+                }
+            }
+
+            if name == "this" {
+                return self
+                    .contracts
+                    .first()
+                    .map(|contract_no| Receiver::Value(Type::Contract(*contract_no)));
+            }
+        } else if name == "address" || name == "payable" {
+            return Some(Receiver::Value(Type::Address(name == "payable")));
+        }
+
+        let symbol = self
+            .contracts
+            .iter()
+            .map(Some)
+            .chain(std::iter::once(None))
+            .find_map(|contract_no| {
+                let file_no = match contract_no {
+                    Some(contract_no) => ns.contracts[*contract_no].loc.0,
+                    None => 0,
+                };
+
+                let key = (file_no, contract_no.cloned(), name.to_owned());
+
+                ns.variable_symbols
+                    .get(&key)
+                    .or_else(|| ns.function_symbols.get(&key))
+            });
+
+        match symbol {
+            Some(symbol) => self.resolve_symbol(symbol, segment.call),
+            None if !segment.call
+                && available_builtins(ns)
+                    .iter()
+                    .any(|prototype| prototype.namespace == Some(name)) =>
+            {
+                Some(Receiver::Builtin(name.to_owned()))
+            }
+            None => None,
+        }
+    }
+
+    /// Resolve a member of the receiver
+    fn resolve_member(&self, receiver: &Receiver, segment: &Segment) -> Option<Receiver> {
+        let ns = self.ns;
+        let name = segment.name.as_str();
+
+        match receiver {
+            Receiver::Builtin(namespace) => available_builtins(ns)
+                .into_iter()
+                .find(|prototype| {
+                    prototype.namespace == Some(namespace.as_str()) && prototype.name == name
+                })
+                .and_then(|prototype| prototype.ret.first())
+                .map(|ty| Receiver::Value(ty.clone())),
+            Receiver::Contract(contract_no) => ns
+                .variable_symbols
+                .iter()
+                .chain(ns.function_symbols.iter())
+                .find(|((_, c, n), _)| *c == Some(*contract_no) && n == name)
+                .and_then(|(_, symbol)| self.resolve_symbol(symbol, segment.call)),
+            Receiver::File(file_no) => {
+                let key = (*file_no, None, name.to_owned());
+
+                ns.variable_symbols
+                    .get(&key)
+                    .or_else(|| ns.function_symbols.get(&key))
+                    .and_then(|symbol| self.resolve_symbol(symbol, segment.call))
+            }
+            Receiver::Enum(_) => None,
+            Receiver::Value(ty) => {
+                let function_returns = |function_no: usize| {
+                    ns.functions[function_no]
+                        .returns
+                        .first()
+                        .map(|param| Receiver::Value(param.ty.clone()))
+                };
+
+                match ty.deref_any() {
+                    Type::Struct(struct_no) if !segment.call => ns.structs[*struct_no]
+                        .fields
+                        .iter()
+                        .find(|field| field.name == name)
+                        .map(|field| Receiver::Value(field.ty.clone())),
+                    Type::Contract(contract_no) if segment.call => ns.contracts[*contract_no]
+                        .all_functions
+                        .keys()
+                        .find(|function_no| ns.functions[**function_no].name == name)
+                        .and_then(|function_no| function_returns(*function_no)),
+                    _ if segment.call => self
+                        .using_functions(ty)
+                        .into_iter()
+                        .find(|function_no| ns.functions[*function_no].name == name)
+                        .and_then(function_returns),
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    fn resolve_symbol(&self, symbol: &Symbol, call: bool) -> Option<Receiver> {
+        let ns = self.ns;
+
+        match symbol {
+            Symbol::Variable(_, Some(contract_no), var_no) if !call => Some(Receiver::Value(
+                ns.contracts[*contract_no].variables[*var_no].ty.clone(),
+            )),
+            Symbol::Variable(_, None, var_no) if !call => {
+                Some(Receiver::Value(ns.constants[*var_no].ty.clone()))
+            }
+            Symbol::Contract(_, contract_no) if call => {
+                Some(Receiver::Value(Type::Contract(*contract_no)))
+            }
+            Symbol::Contract(_, contract_no) => Some(Receiver::Contract(*contract_no)),
+            Symbol::Struct(_, struct_no) if call => Some(Receiver::Value(Type::Struct(*struct_no))),
+            Symbol::Enum(_, enum_no) if !call => Some(Receiver::Enum(*enum_no)),
+            Symbol::Import(_, file_no) if !call => Some(Receiver::File(*file_no)),
+            Symbol::Function(list) if call => list.first().and_then(|(_, function_no)| {
+                ns.functions[*function_no]
+                    .returns
+                    .first()
+                    .map(|param| Receiver::Value(param.ty.clone()))
+            }),
+            _ => None,
+        }
+    }
+
+    fn symbol_item(&self, name: &str, symbol: &Symbol) -> Option<CompletionItem> {
+        let ns = self.ns;
+
+        let (kind, detail, doc) = match symbol {
+            Symbol::Variable(_, Some(contract_no), var_no) => {
+                let var = &ns.contracts[*contract_no].variables[*var_no];
+
+                (
+                    CompletionItemKind::Variable,
+                    var.ty.to_string(ns),
+                    render(&var.tags),
+                )
+            }
+            Symbol::Variable(_, None, var_no) => {
+                let var = &ns.constants[*var_no];
+
+                (
+                    CompletionItemKind::Constant,
+                    var.ty.to_string(ns),
+                    render(&var.tags),
+                )
+            }
+            Symbol::Function(list) => {
+                let func = &ns.functions[list.first()?.1];
+
+                if func.ty != pt::FunctionTy::Function && func.ty != pt::FunctionTy::Modifier {
+                    return None;
+                }
+
+                (
+                    CompletionItemKind::Function,
+                    func.signature.clone(),
+                    render(&func.tags),
+                )
+            }
+            Symbol::Struct(_, struct_no) => {
+                let decl = &ns.structs[*struct_no];
+
+                (
+                    CompletionItemKind::Struct,
+                    decl.to_string(),
+                    render(&decl.tags),
+                )
+            }
+            Symbol::Enum(_, enum_no) => {
+                let decl = &ns.enums[*enum_no];
+
+                (
+                    CompletionItemKind::Enum,
+                    decl.to_string(),
+                    render(&decl.tags),
+                )
+            }
+            Symbol::Event(events) => {
+                let decl = &ns.events[events.first()?.1];
+
+                (
+                    CompletionItemKind::Event,
+                    decl.signature.clone(),
+                    render(&decl.tags),
+                )
+            }
+            Symbol::Contract(_, contract_no) => {
+                let contract = &ns.contracts[*contract_no];
+
+                let kind = if contract.is_interface() {
+                    CompletionItemKind::Interface
+                } else {
+                    CompletionItemKind::Class
+                };
+
+                (kind, contract.ty.to_string(), render(&contract.tags))
+            }
+            Symbol::Import(_, file_no) => (
+                CompletionItemKind::Module,
+                ns.files[*file_no].to_string_lossy().to_string(),
+                String::new(),
+            ),
+        };
+
+        Some(item(name, kind, detail, doc))
+    }
+}
+
+/// Apply subscripts like `a[1][2]` to the receiver
+fn subscript(receiver: Receiver, subscripts: usize) -> Option<Receiver> {
+    if subscripts == 0 {
+        return Some(receiver);
+    }
+
+    let mut ty = match receiver {
+        Receiver::Value(ty) => ty,
+        _ => return None,
+    };
+
+    for _ in 0..subscripts {
+        ty = match ty.deref_any() {
+            Type::Mapping(_, value) => *value.clone(),
+            Type::Array(..) => ty.deref_any().array_elem(),
+            Type::DynamicBytes | Type::Bytes(_) => Type::Bytes(1),
+            _ => return None,
+        };
+    }
+
+    Some(Receiver::Value(ty))
+}
+
+fn builtin_item(prototype: &Prototype, ns: &Namespace) -> CompletionItem {
+    let ret = prototype
+        .ret
+        .iter()
+        .map(|ty| ty.to_string(ns))
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    // If the prototype has arguments, it is a function else it is a variable
+    let (kind, detail) = if prototype.args.is_empty() {
+        (CompletionItemKind::Property, ret)
+    } else {
+        let args = prototype
+            .args
+            .iter()
+            .map(|ty| ty.to_string(ns))
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        (
+            CompletionItemKind::Function,
+            format!("{}({}) returns ({})", prototype.name, args, ret),
+        )
+    };
+
+    item(prototype.name, kind, detail, prototype.doc.to_owned())
+}
+
+fn item(label: &str, kind: CompletionItemKind, detail: String, doc: String) -> CompletionItem {
+    CompletionItem {
+        label: label.to_owned(),
+        kind: Some(kind),
+        detail: Some(detail),
+        documentation: if doc.is_empty() {
+            None
+        } else {
+            Some(Documentation::String(doc))
+        },
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{completions, member_chain};
+    use crate::languageserver::test::resolve;
+
+    const SRC: &str = r#"
+contract c {
+    struct S {
+        uint64 f;
+        bool g;
+    }
+
+    enum E { A, B }
+
+    S s;
+    uint64[] arr;
+
+    function set(uint64 x) public {
+        uint64 y = x;
+        s.f = y;
+        arr.push(y);
+        E e = E.A;
+        uint64 z = 1;
+    }
+}
+"#;
+
+    /// The labels of the completions after the text
+    fn labels(after: &str) -> Vec<String> {
+        let (ns, _) = resolve(SRC);
+
+        let offset = SRC.find(after).unwrap() + after.len();
+
+        completions(&ns, SRC, offset)
+            .into_iter()
+            .map(|item| item.label)
+            .collect()
+    }
+
+    #[test]
+    fn members() {
+        assert_eq!(labels("s."), vec!["f", "g"]);
+        assert_eq!(labels("arr."), vec!["length", "push", "pop"]);
+        assert_eq!(labels("E."), vec!["A", "B"]);
+        // the identifier being typed is ignored
+        assert_eq!(labels("s.f"), vec!["f", "g"]);
+    }
+
+    #[test]
+    fn scope() {
+        let labels = labels("        s");
+
+        for label in &["x", "y", "s", "arr", "set", "this", "msg", "require"] {
+            assert!(labels.contains(&label.to_string()), "{} missing", label);
+        }
+
+        // declared after the cursor
+        assert!(!labels.contains(&String::from("z")));
+        assert!(!labels.contains(&String::from("e")));
+    }
+
+    #[test]
+    fn member_chains() {
+        let text = "a.b(1, (2))[3][x].c";
+
+        let chain = member_chain(text, text.len()).unwrap();
+
+        assert_eq!(
+            chain
+                .iter()
+                .map(|segment| (segment.name.as_str(), segment.call, segment.subscripts))
+                .collect::<Vec<_>>(),
+            vec![("a", false, 0), ("b", true, 2), ("c", false, 0)]
+        );
+
+        // only the last operand is part of the chain
+        let text = "x + foo.bar";
+
+        let chain = member_chain(text, text.len()).unwrap();
+
+        assert_eq!(chain.len(), 2);
+        assert_eq!(chain[0].name, "foo");
+
+        // unbalanced brackets
+        assert!(member_chain("a)", 2).is_none());
+        // nothing before the dot
+        assert!(member_chain("(", 1).is_none());
+    }
+}
@@ -0,0 +1,146 @@
+use serde::Deserialize;
+use serde_json::Value;
+use solang::codegen::Options;
+use solang::file_cache::Remapping;
+use solang::parser::formatter::FormatOptions;
+use solang::Target;
+use std::path::{Path, PathBuf};
+
+/// The settings of the language server. The client sends these as `initializationOptions`,
+/// and in the `solang` section of `workspace/didChangeConfiguration`. Other settings, like
+/// `mathOverflow`, only affect the generated code and are ignored.
+#[derive(Deserialize, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Settings {
+    /// The target to resolve for; if not set, the `--target` command line argument is used
+    pub target: Option<String>,
+    /// Directories to search for imports, relative to the first workspace folder
+    pub import_paths: Vec<PathBuf>,
+    /// Import remappings like `@openzeppelin/=lib/openzeppelin-contracts/`, optionally with
+    /// a context like `lib/old:@openzeppelin/=lib/openzeppelin-v3/`
+    pub remappings: Vec<String>,
+    pub constant_folding: bool,
+    pub strength_reduce: bool,
+    pub dead_storage: bool,
+    pub vector_to_slice: bool,
+    /// Lines longer than this are broken when formatting a document
+    pub format_line_width: usize,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        let opt = Options::default();
+
+        Settings {
+            target: None,
+            import_paths: Vec::new(),
+            remappings: Vec::new(),
+            constant_folding: opt.constant_folding,
+            strength_reduce: opt.strength_reduce,
+            dead_storage: opt.dead_storage,
+            vector_to_slice: opt.vector_to_slice,
+            format_line_width: FormatOptions::default().line_width,
+        }
+    }
+}
+
+impl Settings {
+    /// Read the settings from the client. The settings may be wrapped in a `solang` section.
+    /// A missing value means the defaults.
+    pub fn from_value(value: Option<Value>) -> Result<Settings, String> {
+        let value = match value {
+            Some(Value::Object(mut map)) if map.contains_key("solang") => map.remove("solang"),
+            value => value,
+        };
+
+        let settings: Settings = match value {
+            None | Some(Value::Null) => Settings::default(),
+            Some(value) => serde_json::from_value(value).map_err(|e| e.to_string())?,
+        };
+
+        // check the settings now, so that the user is told about mistakes once
+        settings.target(Target::Substrate)?;
+
+        for remapping in &settings.remappings {
+            remapping.parse::<Remapping>()?;
+        }
+
+        Ok(settings)
+    }
+
+    /// The target to resolve for
+    pub fn target(&self, default: Target) -> Result<Target, String> {
+        match self.target.as_deref() {
+            None => Ok(default),
+            Some("substrate") => Ok(Target::Substrate),
+            Some("ewasm") => Ok(Target::Ewasm),
+            Some("sabre") => Ok(Target::Sabre),
+            Some("generic") => Ok(Target::Generic),
+            Some("solana") => Ok(Target::Solana),
+            Some("evm") => Ok(Target::Evm),
+            Some(target) => Err(format!("unknown target ‘{}’", target)),
+        }
+    }
+
+    /// The import paths, canonicalized. Relative paths are relative to the workspace folder.
+    /// Paths which do not exist are left out.
+    pub fn import_paths(&self, folders: &[PathBuf]) -> Vec<PathBuf> {
+        let root = folders
+            .first()
+            .map(PathBuf::as_path)
+            .unwrap_or(Path::new("."));
+
+        self.import_paths
+            .iter()
+            .chain(folders.iter())
+            .filter_map(|path| root.join(path).canonicalize().ok())
+            .collect()
+    }
+
+    /// The directories with library code which is not part of the workspace: the import paths
+    /// and remapping targets outside the workspace folders. Symbols declared there are not
+    /// renamed.
+    pub fn library_dirs(&self, folders: &[PathBuf]) -> Vec<PathBuf> {
+        let import_paths = self.import_paths(folders);
+
+        let folders: Vec<PathBuf> = folders
+            .iter()
+            .map(|folder| folder.canonicalize().unwrap_or_else(|_| folder.clone()))
+            .collect();
+
+        let remapped: Vec<PathBuf> = self
+            .remappings()
+            .iter()
+            .flat_map(|remapping| {
+                import_paths
+                    .iter()
+                    .filter_map(move |path| path.join(&remapping.target).canonicalize().ok())
+            })
+            .collect();
+
+        import_paths
+            .iter()
+            .cloned()
+            .chain(remapped)
+            .filter(|dir| !folders.iter().any(|folder| dir.starts_with(folder)))
+            .collect()
+    }
+
+    /// The remappings; invalid ones were reported when the settings were received
+    pub fn remappings(&self) -> Vec<Remapping> {
+        self.remappings
+            .iter()
+            .filter_map(|remapping| remapping.parse().ok())
+            .collect()
+    }
+
+    /// The codegen passes which are enabled
+    pub fn options(&self) -> Options {
+        Options {
+            dead_storage: self.dead_storage,
+            constant_folding: self.constant_folding,
+            strength_reduce: self.strength_reduce,
+            vector_to_slice: self.vector_to_slice,
+        }
+    }
+}
@@ -1,11 +1,11 @@
 use serde_json::Value;
-use tower_lsp::jsonrpc::Result;
+use tower_lsp::jsonrpc::{Error, Result};
 use tower_lsp::lsp_types::*;
 use tower_lsp::{Client, LanguageServer};
 use tower_lsp::{LspService, Server};
 
-use solang::codegen::codegen;
-use solang::file_cache::FileCache;
+use solang::codegen::{codegen, Options};
+use solang::file_cache::{FileCache, ParseTrees};
 use solang::parse_and_resolve;
 use solang::parser::pt;
 use solang::Target;
@@ -18,7 +18,10 @@ use tower_lsp::lsp_types::{
 use solang::*;
 use std::cmp::Ordering;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{self, AtomicBool};
+use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::Mutex;
 
 use solang::sema::ast::*;
@@ -26,17 +29,48 @@ use solang::sema::tags::*;
 
 use solang::sema::builtin::get_prototype;
 
+mod completion;
+mod config;
+mod outline;
+mod semantic;
+mod signature;
+mod symbols;
+
+use config::Settings;
+use symbols::{SymbolIndex, SymbolKind};
+
 pub struct Hovers {
+    /// The text of the file as it was checked
+    text: Arc<str>,
     offsets: sema::diagnostics::FileOffsets,
     lookup: Vec<(usize, usize, String)>,
+    /// Declarations and uses of all the symbols in the file and its imports
+    symbols: SymbolIndex,
+    /// The resolved file and its imports
+    ns: ast::Namespace,
 }
 
 pub struct SolangServer {
     client: Client,
     target: Target,
     files: Mutex<HashMap<PathBuf, Hovers>>,
+    /// Contents of the documents open in the editor, which may not be saved yet
+    documents: Mutex<HashMap<PathBuf, String>>,
+    /// The workspace folders reported by the client
+    workspace_folders: Mutex<Vec<PathBuf>>,
+    /// The settings from the client
+    settings: Mutex<Settings>,
+    /// Parse trees of each checked file and its imports, reused for unchanged files
+    parse_trees: Mutex<HashMap<PathBuf, ParseTrees>>,
+    /// Incremented for each check of a file, so that checks which were overtaken can be dropped
+    generations: Mutex<HashMap<PathBuf, usize>>,
+    /// Set to stop the running check of a file once it is overtaken
+    cancel_flags: Mutex<HashMap<PathBuf, Arc<AtomicBool>>>,
 }
 
+/// How long to wait for more changes before checking a file
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
 #[tokio::main(flavor = "current_thread")]
 pub async fn start_server(target: Target) {
     let stdin = tokio::io::stdin();
@@ -46,6 +80,12 @@ pub async fn start_server(target: Target) {
         client,
         target,
         files: Mutex::new(HashMap::new()),
+        documents: Mutex::new(HashMap::new()),
+        workspace_folders: Mutex::new(Vec::new()),
+        settings: Mutex::new(Settings::default()),
+        parse_trees: Mutex::new(HashMap::new()),
+        generations: Mutex::new(HashMap::new()),
+        cancel_flags: Mutex::new(HashMap::new()),
     });
 
     Server::new(stdin, stdout)
@@ -57,96 +97,310 @@ pub async fn start_server(target: Target) {
 }
 
 impl SolangServer {
-    /// Parse file
-    async fn parse_file(&self, uri: Url) {
-        if let Ok(path) = uri.to_file_path() {
-            let mut filecache = FileCache::new();
+    /// Check a file and publish its diagnostics. Without codegen, the check waits for the
+    /// typing to pause first. The check runs on a separate thread so that requests are
+    /// still answered. If the file changes again in the meantime, the check is cancelled
+    /// and its result is dropped.
+    async fn parse_file(&self, uri: Url, run_codegen: bool) {
+        let path = match uri.to_file_path() {
+            Ok(path) => path,
+            Err(_) => return,
+        };
+
+        let generation = {
+            let mut generations = self.generations.lock().await;
+            let generation = generations.entry(path.clone()).or_insert(0);
+            *generation += 1;
+            *generation
+        };
+
+        let cancel = Arc::new(AtomicBool::new(false));
+
+        if let Some(previous) = self
+            .cancel_flags
+            .lock()
+            .await
+            .insert(path.clone(), cancel.clone())
+        {
+            previous.store(true, atomic::Ordering::Relaxed);
+        }
 
-            let dir = path.parent().unwrap();
+        if !run_codegen {
+            tokio::time::sleep(DEBOUNCE).await;
 
-            if let Ok(dir) = dir.canonicalize() {
-                filecache.add_import_path(dir);
+            if !self.is_current(&path, generation).await {
+                return;
             }
+        }
 
-            let os_str = path.file_name().unwrap();
+        // the file cache only deals with unicode paths
+        let filename = match path.file_name().and_then(|name| name.to_str()) {
+            Some(filename) => filename.to_owned(),
+            None => {
+                self.client
+                    .log_message(
+                        MessageType::Warning,
+                        format!("cannot check {}: not a unicode path", path.display()),
+                    )
+                    .await;
+                return;
+            }
+        };
 
-            let mut ns = parse_and_resolve(os_str.to_str().unwrap(), &mut filecache, self.target);
+        let mut filecache = FileCache::new();
 
-            // codegen all the contracts; some additional errors/warnings will be detected here
-            for contract_no in 0..ns.contracts.len() {
-                codegen(contract_no, &mut ns, &Default::default());
+        filecache.set_cancel_flag(cancel);
+
+        // open documents take priority over the files on disk
+        for (path, contents) in self.documents.lock().await.iter() {
+            if let Some(path) = path.to_str() {
+                filecache.set_file_contents(path, contents.to_owned());
             }
+        }
 
-            let offsets = ns.file_offset(&mut filecache);
+        // files which have not changed since the last check are not parsed again
+        if let Some(trees) = self.parse_trees.lock().await.remove(&path) {
+            filecache.set_parse_trees(trees);
+        }
 
-            let diags = ns
-                .diagnostics
-                .iter()
-                .filter_map(|diag| {
-                    let pos = diag.pos.unwrap();
+        let dir = path.parent().unwrap();
+
+        if let Ok(dir) = dir.canonicalize() {
+            filecache.add_import_path(dir);
+        }
+
+        let settings = self.settings.lock().await.clone();
+
+        for import_path in settings.import_paths(&self.workspace_folders.lock().await) {
+            filecache.add_import_path(import_path);
+        }
+
+        for remapping in settings.remappings() {
+            filecache.add_remapping(remapping);
+        }
+
+        // the settings were checked when they were received
+        let target = settings.target(self.target).unwrap_or(self.target);
+        let options = settings.options();
 
-                    if pos.0 != 0 {
-                        // The first file is the one we wanted to parse; others are imported
+        let analysis = tokio::task::spawn_blocking(move || {
+            let hovers =
+                SolangServer::analyze(&filename, &mut filecache, target, run_codegen, &options);
+
+            (hovers, filecache.take_parse_trees())
+        })
+        .await;
+
+        let (hovers, trees) = match analysis {
+            Ok(analysis) => analysis,
+            Err(_) => return,
+        };
+
+        self.parse_trees.lock().await.insert(path.clone(), trees);
+
+        let hovers = match hovers {
+            Some(hovers) if self.is_current(&path, generation).await => hovers,
+            _ => return,
+        };
+
+        let ns = &hovers.ns;
+        let offsets = &hovers.offsets;
+
+        let diags = ns
+            .diagnostics
+            .iter()
+            .filter_map(|diag| {
+                let pos = diag.pos.unwrap();
+
+                if pos.0 != 0 {
+                    // The first file is the one we wanted to parse; others are imported
+                    return None;
+                }
+
+                let related_information = if diag.notes.is_empty() {
+                    None
+                } else {
+                    Some(
+                        diag.notes
+                            .iter()
+                            .map(|note| DiagnosticRelatedInformation {
+                                message: note.message.to_string(),
+                                location: Location {
+                                    uri: Url::from_file_path(&ns.files[note.pos.0]).unwrap(),
+                                    range: SolangServer::loc_to_range(&note.pos, offsets),
+                                },
+                            })
+                            .collect(),
+                    )
+                };
+
+                let sev = match diag.level {
+                    ast::Level::Info => DiagnosticSeverity::Information,
+                    ast::Level::Warning => DiagnosticSeverity::Warning,
+                    ast::Level::Error => DiagnosticSeverity::Error,
+                    ast::Level::Debug => {
                         return None;
                     }
+                };
+
+                let range = SolangServer::loc_to_range(&pos, offsets);
+
+                Some(Diagnostic {
+                    range,
+                    message: diag.message.to_string(),
+                    severity: Some(sev),
+                    source: None,
+                    code: Some(NumberOrString::String(diag.code.to_owned())),
+                    code_description: None,
+                    related_information,
+                    tags: None,
+                    data: None,
+                })
+            })
+            .collect();
 
-                    let related_information = if diag.notes.is_empty() {
-                        None
-                    } else {
-                        Some(
-                            diag.notes
-                                .iter()
-                                .map(|note| DiagnosticRelatedInformation {
-                                    message: note.message.to_string(),
-                                    location: Location {
-                                        uri: Url::from_file_path(&ns.files[note.pos.0]).unwrap(),
-                                        range: SolangServer::loc_to_range(&note.pos, &offsets),
-                                    },
-                                })
-                                .collect(),
-                        )
-                    };
+        self.files.lock().await.insert(path, hovers);
 
-                    let sev = match diag.level {
-                        ast::Level::Info => DiagnosticSeverity::Information,
-                        ast::Level::Warning => DiagnosticSeverity::Warning,
-                        ast::Level::Error => DiagnosticSeverity::Error,
-                        ast::Level::Debug => {
-                            return None;
-                        }
-                    };
+        self.client.publish_diagnostics(uri, diags, None).await;
+    }
 
-                    let range = SolangServer::loc_to_range(&pos, &offsets);
-
-                    Some(Diagnostic {
-                        range,
-                        message: diag.message.to_string(),
-                        severity: Some(sev),
-                        source: None,
-                        code: None,
-                        code_description: None,
-                        related_information,
-                        tags: None,
-                        data: None,
-                    })
-                })
-                .collect();
+    /// Resolve the file, and index it for answering requests about it. Returns `None` if
+    /// the check was cancelled.
+    fn analyze(
+        filename: &str,
+        filecache: &mut FileCache,
+        target: Target,
+        run_codegen: bool,
+        options: &Options,
+    ) -> Option<Hovers> {
+        let mut ns = parse_and_resolve(filename, filecache, target);
+
+        // codegen all the contracts; some additional errors/warnings will be detected here
+        if run_codegen {
+            for contract_no in 0..ns.contracts.len() {
+                if filecache.is_cancelled() {
+                    break;
+                }
 
-            let res = self.client.publish_diagnostics(uri, diags, None);
+                codegen(contract_no, &mut ns, options);
+            }
+        }
 
-            let mut lookup: Vec<(usize, usize, String)> = Vec::new();
-            let mut fnc_map: HashMap<String, String> = HashMap::new();
+        // the namespace may be incomplete
+        if filecache.is_cancelled() {
+            return None;
+        }
 
-            SolangServer::traverse(&ns, &mut lookup, &mut fnc_map);
+        let offsets = ns.file_offset(filecache);
 
-            lookup.sort_by_key(|k| k.0);
+        let mut lookup: Vec<(usize, usize, String)> = Vec::new();
+        let mut fnc_map: HashMap<String, String> = HashMap::new();
 
-            self.files
-                .lock()
-                .await
-                .insert(path, Hovers { offsets, lookup });
+        SolangServer::traverse(&ns, &mut lookup, &mut fnc_map);
+
+        lookup.sort_by_key(|k| k.0);
+
+        let contents: Vec<_> = ns
+            .files
+            .iter()
+            .map(|file| filecache.get_file_contents(file))
+            .collect();
+
+        let symbols = SymbolIndex::new(&ns, &contents);
+
+        Some(Hovers {
+            text: contents[0].clone(),
+            offsets,
+            lookup,
+            symbols,
+            ns,
+        })
+    }
+
+    /// The library directories from the settings; see `Settings::library_dirs`
+    async fn library_dirs(&self) -> Vec<PathBuf> {
+        let folders = self.workspace_folders.lock().await.clone();
+
+        self.settings.lock().await.library_dirs(&folders)
+    }
+
+    /// Has the file not been changed since the check with the generation was started
+    async fn is_current(&self, path: &Path, generation: usize) -> bool {
+        self.generations.lock().await.get(path) == Some(&generation)
+    }
+
+    /// Check the file, and then the other checked files which import it
+    async fn parse_file_and_dependents(&self, uri: Url, run_codegen: bool) {
+        let changed = SolangServer::document_path(&uri);
+
+        self.parse_file(uri, run_codegen).await;
+
+        let changed = match changed {
+            Some(changed) => changed,
+            None => return,
+        };
+
+        let dependents: Vec<PathBuf> = self
+            .files
+            .lock()
+            .await
+            .iter()
+            .filter(|(_, hovers)| hovers.ns.files.iter().skip(1).any(|f| *f == changed))
+            .map(|(path, _)| path.clone())
+            .collect();
+
+        for path in dependents {
+            if let Ok(uri) = Url::from_file_path(path) {
+                self.parse_file(uri, run_codegen).await;
+            }
+        }
+    }
+
+    /// The path of a document as used as key in the documents store and file cache
+    fn document_path(uri: &Url) -> Option<PathBuf> {
+        let path = uri.to_file_path().ok()?;
+
+        Some(path.canonicalize().unwrap_or(path))
+    }
+
+    /// Convert a position in the editor to a byte offset in the text. The character
+    /// in the position counts UTF-16 code units.
+    fn position_to_offset(text: &str, pos: Position) -> usize {
+        let mut offset = 0;
+
+        for (line_no, line) in text.split_inclusive('\n').enumerate() {
+            if line_no == pos.line as usize {
+                let mut units = 0;
+
+                for (i, c) in line.char_indices() {
+                    if units >= pos.character as usize || c == '\n' {
+                        return offset + i;
+                    }
+
+                    units += c.len_utf16();
+                }
+
+                return offset + line.len();
+            }
+
+            offset += line.len();
+        }
+
+        text.len()
+    }
+
+    /// Apply the changes from the editor to the document text
+    fn apply_changes(text: &mut String, changes: Vec<TextDocumentContentChangeEvent>) {
+        for change in changes {
+            match change.range {
+                Some(range) => {
+                    let start = SolangServer::position_to_offset(text, range.start);
+                    let end = SolangServer::position_to_offset(text, range.end);
 
-            res.await;
+                    text.replace_range(start..end.max(start), &change.text);
+                }
+                None => *text = change.text,
+            }
         }
     }
 
@@ -160,6 +414,134 @@ impl SolangServer {
         Range::new(start, end)
     }
 
+    /// Find the symbol at the position in the document, along with the index it belongs to
+    fn symbol_at<'a>(
+        files: &'a HashMap<PathBuf, Hovers>,
+        uri: &Url,
+        pos: Position,
+    ) -> Option<(&'a Hovers, SymbolKind)> {
+        let path = uri.to_file_path().ok()?;
+        let hovers = files.get(&path)?;
+
+        let offset = SolangServer::position_to_offset(&hovers.text, pos);
+
+        let symbol = hovers.symbols.symbol_at(0, offset)?;
+
+        Some((hovers, symbol))
+    }
+
+    /// Convert a location in any of the files of the namespace to a location for the editor
+    fn loc_to_location(loc: &pt::Loc, hovers: &Hovers) -> Option<Location> {
+        let uri = Url::from_file_path(hovers.ns.files.get(loc.0)?).ok()?;
+
+        Some(Location {
+            uri,
+            range: SolangServer::loc_to_range(loc, &hovers.offsets),
+        })
+    }
+
+    /// Find the name at the position which can be renamed, and the symbol it refers to. Symbols
+    /// declared in the library directories cannot be renamed.
+    fn rename_target<'a>(
+        files: &'a HashMap<PathBuf, Hovers>,
+        uri: &Url,
+        pos: Position,
+        libraries: &[PathBuf],
+    ) -> Result<Option<(&'a Hovers, pt::Loc, SymbolKind)>> {
+        let hovers = match uri.to_file_path().ok().and_then(|path| files.get(&path)) {
+            Some(hovers) => hovers,
+            None => return Ok(None),
+        };
+
+        let offset = SolangServer::position_to_offset(&hovers.text, pos);
+
+        let (loc, symbol) = match hovers.symbols.name_at(0, offset) {
+            Some(name) => name,
+            None => return Ok(None),
+        };
+
+        if let SymbolKind::File(_) = symbol {
+            return Err(Error::invalid_params("imported files cannot be renamed"));
+        }
+
+        if let Some(definition) = hovers.symbols.definition(symbol) {
+            let path = &hovers.ns.files[definition.0];
+
+            if libraries.iter().any(|dir| path.starts_with(dir)) {
+                return Err(Error::invalid_params(format!(
+                    "cannot rename symbol declared in library file {}",
+                    path.display()
+                )));
+            }
+        }
+
+        Ok(Some((hovers, loc, symbol)))
+    }
+
+    /// The edits for renaming the symbol in the index, in all the checked files which declare
+    /// or use it
+    fn rename_edits(
+        files: &HashMap<PathBuf, Hovers>,
+        hovers: &Hovers,
+        symbol: SymbolKind,
+        new_name: &str,
+    ) -> HashMap<Url, Vec<TextEdit>> {
+        // The declarations to rename, identified by file and location, so they can be found
+        // in the index of every open document which imports them
+        let declarations: Vec<(&PathBuf, pt::Loc)> = symbols::related(&hovers.ns, symbol)
+            .into_iter()
+            .filter_map(|kind| hovers.symbols.definition(kind))
+            .map(|loc| (&hovers.ns.files[loc.0], loc))
+            .collect();
+
+        let mut changes: HashMap<Url, Vec<TextEdit>> = HashMap::new();
+
+        for hovers in files.values() {
+            // other documents may have overrides which are not visible from this one
+            let kinds: Vec<SymbolKind> = declarations
+                .iter()
+                .filter_map(|(path, loc)| {
+                    let file_no = hovers.ns.files.iter().position(|file| file == *path)?;
+
+                    hovers.symbols.declared_at(pt::Loc(file_no, loc.1, loc.2))
+                })
+                .flat_map(|kind| symbols::related(&hovers.ns, kind))
+                .collect();
+
+            for kind in kinds {
+                for loc in hovers.symbols.references(kind, true) {
+                    let uri = match Url::from_file_path(&hovers.ns.files[loc.0]) {
+                        Ok(uri) => uri,
+                        Err(_) => continue,
+                    };
+
+                    let edit = TextEdit {
+                        range: SolangServer::loc_to_range(&loc, &hovers.offsets),
+                        new_text: new_name.to_owned(),
+                    };
+
+                    let edits = changes.entry(uri).or_default();
+
+                    if !edits.contains(&edit) {
+                        edits.push(edit);
+                    }
+                }
+            }
+        }
+
+        changes
+    }
+
+    /// Is the new name a valid identifier, which is not a keyword or builtin
+    fn valid_name(name: &str) -> bool {
+        let mut tokens = parser::lexer::Lexer::new(name);
+
+        matches!(
+            (tokens.next(), tokens.next()),
+            (Some(Ok((_, parser::lexer::Token::Identifier(id), _))), None) if id == name
+        ) && !builtin::is_reserved(name)
+    }
+
     fn construct_builtins(bltn: &sema::ast::Builtin, ns: &ast::Namespace) -> String {
         let mut msg = "[built-in] ".to_string();
         let prot = get_prototype(*bltn);
@@ -964,7 +1346,29 @@ impl SolangServer {
 
 #[tower_lsp::async_trait]
 impl LanguageServer for SolangServer {
-    async fn initialize(&self, _: InitializeParams) -> Result<InitializeResult> {
+    async fn initialize(&self, params: InitializeParams) -> Result<InitializeResult> {
+        let folders = match params.workspace_folders {
+            Some(folders) => folders.into_iter().map(|folder| folder.uri).collect(),
+            None => params.root_uri.into_iter().collect::<Vec<Url>>(),
+        };
+
+        *self.workspace_folders.lock().await = folders
+            .iter()
+            .filter_map(|uri| uri.to_file_path().ok())
+            .collect();
+
+        match Settings::from_value(params.initialization_options) {
+            Ok(settings) => *self.settings.lock().await = settings,
+            Err(message) => {
+                self.client
+                    .show_message(
+                        MessageType::Error,
+                        format!("invalid solang settings: {}", message),
+                    )
+                    .await;
+            }
+        }
+
         Ok(InitializeResult {
             server_info: None,
             capabilities: ServerCapabilities {
@@ -979,11 +1383,30 @@ impl LanguageServer for SolangServer {
                     work_done_progress_options: Default::default(),
                 }),
                 signature_help_provider: Some(SignatureHelpOptions {
-                    trigger_characters: None,
+                    trigger_characters: Some(vec!["(".to_string(), ",".to_string()]),
                     retrigger_characters: None,
                     work_done_progress_options: Default::default(),
                 }),
-                document_highlight_provider: None,
+                definition_provider: Some(OneOf::Left(true)),
+                references_provider: Some(OneOf::Left(true)),
+                document_highlight_provider: Some(OneOf::Left(true)),
+                rename_provider: Some(OneOf::Right(RenameOptions {
+                    prepare_provider: Some(true),
+                    work_done_progress_options: Default::default(),
+                })),
+                document_symbol_provider: Some(OneOf::Left(true)),
+                document_formatting_provider: Some(OneOf::Left(true)),
+                code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
+                semantic_tokens_provider: Some(
+                    SemanticTokensServerCapabilities::SemanticTokensOptions(
+                        SemanticTokensOptions {
+                            work_done_progress_options: Default::default(),
+                            legend: semantic::legend(),
+                            range: Some(true),
+                            full: Some(SemanticTokensFullOptions::Bool(true)),
+                        },
+                    ),
+                ),
                 workspace_symbol_provider: Some(OneOf::Left(true)),
                 execute_command_provider: Some(ExecuteCommandOptions {
                     commands: vec!["dummy.do_something".to_string()],
@@ -1011,16 +1434,47 @@ impl LanguageServer for SolangServer {
         Ok(())
     }
 
-    async fn did_change_workspace_folders(&self, _: DidChangeWorkspaceFoldersParams) {
-        self.client
-            .log_message(MessageType::Info, "workspace folders changed!")
-            .await;
+    async fn did_change_workspace_folders(&self, params: DidChangeWorkspaceFoldersParams) {
+        let mut folders = self.workspace_folders.lock().await;
+
+        for folder in params.event.removed {
+            if let Ok(path) = folder.uri.to_file_path() {
+                folders.retain(|f| *f != path);
+            }
+        }
+
+        for folder in params.event.added {
+            if let Ok(path) = folder.uri.to_file_path() {
+                if !folders.contains(&path) {
+                    folders.push(path);
+                }
+            }
+        }
     }
 
-    async fn did_change_configuration(&self, _: DidChangeConfigurationParams) {
-        self.client
-            .log_message(MessageType::Info, "configuration changed!")
-            .await;
+    async fn did_change_configuration(&self, params: DidChangeConfigurationParams) {
+        match Settings::from_value(Some(params.settings)) {
+            Ok(settings) => *self.settings.lock().await = settings,
+            Err(message) => {
+                self.client
+                    .show_message(
+                        MessageType::Error,
+                        format!("invalid solang settings: {}", message),
+                    )
+                    .await;
+
+                return;
+            }
+        }
+
+        // the target, imports and passes may have changed, so check everything again
+        let paths: Vec<PathBuf> = self.files.lock().await.keys().cloned().collect();
+
+        for path in paths {
+            if let Ok(uri) = Url::from_file_path(path) {
+                self.parse_file(uri, true).await;
+            }
+        }
     }
 
     async fn did_change_watched_files(&self, _: DidChangeWatchedFilesParams) {
@@ -1039,19 +1493,35 @@ impl LanguageServer for SolangServer {
     async fn did_open(&self, params: DidOpenTextDocumentParams) {
         let uri = params.text_document.uri;
 
-        self.parse_file(uri).await;
+        if let Some(path) = SolangServer::document_path(&uri) {
+            self.documents
+                .lock()
+                .await
+                .insert(path, params.text_document.text);
+        }
+
+        self.parse_file(uri, true).await;
     }
 
     async fn did_change(&self, params: DidChangeTextDocumentParams) {
         let uri = params.text_document.uri;
 
-        self.parse_file(uri).await;
+        if let Some(path) = SolangServer::document_path(&uri) {
+            let mut documents = self.documents.lock().await;
+
+            let text = documents.entry(path).or_default();
+
+            SolangServer::apply_changes(text, params.content_changes);
+        }
+
+        // codegen only runs when the file is saved
+        self.parse_file_and_dependents(uri, false).await;
     }
 
     async fn did_save(&self, params: DidSaveTextDocumentParams) {
         let uri = params.text_document.uri;
 
-        self.parse_file(uri).await;
+        self.parse_file_and_dependents(uri, true).await;
     }
 
     async fn did_close(&self, params: DidCloseTextDocumentParams) {
@@ -1059,10 +1529,113 @@ impl LanguageServer for SolangServer {
 
         if let Ok(path) = uri.to_file_path() {
             self.files.lock().await.remove(&path);
+            self.parse_trees.lock().await.remove(&path);
+            self.generations.lock().await.remove(&path);
+
+            if let Some(cancel) = self.cancel_flags.lock().await.remove(&path) {
+                cancel.store(true, atomic::Ordering::Relaxed);
+            }
+        }
+
+        if let Some(path) = SolangServer::document_path(&uri) {
+            self.documents.lock().await.remove(&path);
+        }
+    }
+
+    async fn completion(&self, params: CompletionParams) -> Result<Option<CompletionResponse>> {
+        let uri = params.text_document_position.text_document.uri;
+        let pos = params.text_document_position.position;
+
+        let text = match SolangServer::document_path(&uri) {
+            Some(path) => self.documents.lock().await.get(&path).cloned(),
+            None => None,
+        };
+
+        if let (Some(text), Ok(path)) = (text, uri.to_file_path()) {
+            if let Some(hovers) = self.files.lock().await.get(&path) {
+                let offset = SolangServer::position_to_offset(&text, pos);
+
+                let items = completion::completions(&hovers.ns, &text, offset);
+
+                return Ok(Some(CompletionResponse::Array(items)));
+            }
+        }
+
+        Ok(None)
+    }
+
+    async fn signature_help(&self, params: SignatureHelpParams) -> Result<Option<SignatureHelp>> {
+        let uri = params.text_document_position_params.text_document.uri;
+        let pos = params.text_document_position_params.position;
+
+        let text = match SolangServer::document_path(&uri) {
+            Some(path) => self.documents.lock().await.get(&path).cloned(),
+            None => None,
+        };
+
+        if let (Some(text), Ok(path)) = (text, uri.to_file_path()) {
+            if let Some(hovers) = self.files.lock().await.get(&path) {
+                let offset = SolangServer::position_to_offset(&text, pos);
+
+                return Ok(signature::signature_help(&hovers.ns, &text, offset));
+            }
+        }
+
+        Ok(None)
+    }
+
+    async fn semantic_tokens_full(
+        &self,
+        params: SemanticTokensParams,
+    ) -> Result<Option<SemanticTokensResult>> {
+        if let Ok(path) = params.text_document.uri.to_file_path() {
+            if let Some(hovers) = self.files.lock().await.get(&path) {
+                let data = semantic::semantic_tokens(
+                    &hovers.ns,
+                    &hovers.symbols,
+                    &hovers.offsets,
+                    0,
+                    usize::MAX,
+                );
+
+                return Ok(Some(SemanticTokensResult::Tokens(SemanticTokens {
+                    result_id: None,
+                    data,
+                })));
+            }
         }
+
+        Ok(None)
     }
 
-    async fn completion(&self, _: CompletionParams) -> Result<Option<CompletionResponse>> {
+    async fn semantic_tokens_range(
+        &self,
+        params: SemanticTokensRangeParams,
+    ) -> Result<Option<SemanticTokensRangeResult>> {
+        if let Ok(path) = params.text_document.uri.to_file_path() {
+            if let Some(hovers) = self.files.lock().await.get(&path) {
+                let start = params.range.start;
+                let end = params.range.end;
+
+                let data = semantic::semantic_tokens(
+                    &hovers.ns,
+                    &hovers.symbols,
+                    &hovers.offsets,
+                    hovers
+                        .offsets
+                        .get_offset(0, start.line as usize, start.character as usize),
+                    hovers
+                        .offsets
+                        .get_offset(0, end.line as usize, end.character as usize),
+                );
+
+                return Ok(Some(SemanticTokensRangeResult::Tokens(SemanticTokens {
+                    result_id: None,
+                    data,
+                })));
+            }
+        }
+
         Ok(None)
     }
 
@@ -1103,4 +1676,556 @@ impl LanguageServer for SolangServer {
 
         Ok(None)
     }
+
+    async fn goto_definition(
+        &self,
+        params: GotoDefinitionParams,
+    ) -> Result<Option<GotoDefinitionResponse>> {
+        let uri = params.text_document_position_params.text_document.uri;
+        let pos = params.text_document_position_params.position;
+
+        let files = self.files.lock().await;
+
+        if let Some((hovers, symbol)) = SolangServer::symbol_at(&files, &uri, pos) {
+            if let Some(location) = hovers
+                .symbols
+                .definition(symbol)
+                .and_then(|loc| SolangServer::loc_to_location(&loc, hovers))
+            {
+                return Ok(Some(GotoDefinitionResponse::Scalar(location)));
+            }
+        }
+
+        Ok(None)
+    }
+
+    async fn references(&self, params: ReferenceParams) -> Result<Option<Vec<Location>>> {
+        let uri = params.text_document_position.text_document.uri;
+        let pos = params.text_document_position.position;
+
+        let files = self.files.lock().await;
+
+        if let Some((hovers, symbol)) = SolangServer::symbol_at(&files, &uri, pos) {
+            let locations = hovers
+                .symbols
+                .references(symbol, params.context.include_declaration)
+                .iter()
+                .filter_map(|loc| SolangServer::loc_to_location(loc, hovers))
+                .collect();
+
+            return Ok(Some(locations));
+        }
+
+        Ok(None)
+    }
+
+    async fn document_highlight(
+        &self,
+        params: DocumentHighlightParams,
+    ) -> Result<Option<Vec<DocumentHighlight>>> {
+        let uri = params.text_document_position_params.text_document.uri;
+        let pos = params.text_document_position_params.position;
+
+        let files = self.files.lock().await;
+
+        if let Some((hovers, symbol)) = SolangServer::symbol_at(&files, &uri, pos) {
+            let highlights = hovers
+                .symbols
+                .references(symbol, true)
+                .iter()
+                .filter(|loc| loc.0 == 0)
+                .map(|loc| DocumentHighlight {
+                    range: SolangServer::loc_to_range(loc, &hovers.offsets),
+                    kind: Some(DocumentHighlightKind::Text),
+                })
+                .collect();
+
+            return Ok(Some(highlights));
+        }
+
+        Ok(None)
+    }
+
+    async fn prepare_rename(
+        &self,
+        params: TextDocumentPositionParams,
+    ) -> Result<Option<PrepareRenameResponse>> {
+        let libraries = self.library_dirs().await;
+
+        let files = self.files.lock().await;
+
+        Ok(SolangServer::rename_target(
+            &files,
+            &params.text_document.uri,
+            params.position,
+            &libraries,
+        )?
+        .map(|(hovers, loc, _)| {
+            PrepareRenameResponse::Range(SolangServer::loc_to_range(&loc, &hovers.offsets))
+        }))
+    }
+
+    async fn rename(&self, params: RenameParams) -> Result<Option<WorkspaceEdit>> {
+        let uri = params.text_document_position.text_document.uri;
+        let pos = params.text_document_position.position;
+        let new_name = params.new_name;
+
+        if !SolangServer::valid_name(&new_name) {
+            return Err(Error::invalid_params(format!(
+                "‘{}’ is not a valid name",
+                new_name
+            )));
+        }
+
+        let libraries = self.library_dirs().await;
+
+        let files = self.files.lock().await;
+
+        let (hovers, _, symbol) = match SolangServer::rename_target(&files, &uri, pos, &libraries)?
+        {
+            Some(target) => target,
+            None => return Ok(None),
+        };
+
+        Ok(Some(WorkspaceEdit {
+            changes: Some(SolangServer::rename_edits(
+                &files, hovers, symbol, &new_name,
+            )),
+            ..Default::default()
+        }))
+    }
+
+    async fn code_action(&self, params: CodeActionParams) -> Result<Option<CodeActionResponse>> {
+        let files = self.files.lock().await;
+
+        let hovers = match params
+            .text_document
+            .uri
+            .to_file_path()
+            .ok()
+            .and_then(|path| files.get(&path))
+        {
+            Some(hovers) => hovers,
+            None => return Ok(None),
+        };
+
+        let start = params.range.start;
+        let end = params.range.end;
+
+        let start = hovers
+            .offsets
+            .get_offset(0, start.line as usize, start.character as usize);
+        let end = hovers
+            .offsets
+            .get_offset(0, end.line as usize, end.character as usize);
+
+        let mut actions = Vec::new();
+
+        for diag in &hovers.ns.diagnostics {
+            match diag.pos {
+                Some(pos) if pos.0 == 0 && pos.1 <= end && start <= pos.2 => (),
+                _ => continue,
+            }
+
+            for fix in &diag.fixes {
+                let mut changes: HashMap<Url, Vec<TextEdit>> = HashMap::new();
+
+                for (loc, text) in &fix.edits {
+                    if let Some(location) = SolangServer::loc_to_location(loc, hovers) {
+                        changes.entry(location.uri).or_default().push(TextEdit {
+                            range: location.range,
+                            new_text: text.to_owned(),
+                        });
+                    }
+                }
+
+                actions.push(CodeActionOrCommand::CodeAction(CodeAction {
+                    title: fix.message.to_owned(),
+                    kind: Some(CodeActionKind::QUICKFIX),
+                    edit: Some(WorkspaceEdit {
+                        changes: Some(changes),
+                        ..Default::default()
+                    }),
+                    is_preferred: Some(diag.fixes.len() == 1),
+                    ..Default::default()
+                }));
+            }
+        }
+
+        Ok(Some(actions))
+    }
+
+    async fn document_symbol(
+        &self,
+        params: DocumentSymbolParams,
+    ) -> Result<Option<DocumentSymbolResponse>> {
+        let uri = params.text_document.uri;
+
+        let text = match SolangServer::document_path(&uri) {
+            Some(path) => match self.documents.lock().await.get(&path) {
+                Some(text) => Some(text.clone()),
+                None => std::fs::read_to_string(&path).ok(),
+            },
+            None => None,
+        };
+
+        if let Some(text) = text {
+            if let Ok((tree, _, _)) = parser::parse(&text, 0) {
+                return Ok(Some(DocumentSymbolResponse::Nested(
+                    outline::document_symbols(&tree, &text),
+                )));
+            }
+        }
+
+        Ok(None)
+    }
+
+    async fn symbol(
+        &self,
+        params: WorkspaceSymbolParams,
+    ) -> Result<Option<Vec<SymbolInformation>>> {
+        let folders = self.workspace_folders.lock().await.clone();
+
+        Ok(Some(outline::workspace_symbols(&folders, &params.query)))
+    }
+
+    /// Format the whole document. A document with syntax errors is not formatted.
+    async fn formatting(&self, params: DocumentFormattingParams) -> Result<Option<Vec<TextEdit>>> {
+        let uri = params.text_document.uri;
+
+        let text = match SolangServer::document_path(&uri) {
+            Some(path) => match self.documents.lock().await.get(&path) {
+                Some(text) => Some(text.clone()),
+                None => std::fs::read_to_string(&path).ok(),
+            },
+            None => None,
+        };
+
+        let text = match text {
+            Some(text) => text,
+            None => return Ok(None),
+        };
+
+        let options = parser::formatter::FormatOptions {
+            indent: params.options.tab_size as usize,
+            tabs: !params.options.insert_spaces,
+            line_width: self.settings.lock().await.format_line_width,
+        };
+
+        let formatted = match parser::formatter::format(&text, &options) {
+            Ok(formatted) => formatted,
+            Err(_) => return Ok(None),
+        };
+
+        if formatted == text {
+            return Ok(Some(Vec::new()));
+        }
+
+        // replace the whole document; the end is in UTF-16 code units, like all positions
+        let last_line = text.rsplit('\n').next().unwrap_or("");
+
+        let end = Position::new(
+            text.matches('\n').count() as u32,
+            last_line.encode_utf16().count() as u32,
+        );
+
+        Ok(Some(vec![TextEdit {
+            range: Range::new(Position::new(0, 0), end),
+            new_text: formatted,
+        }]))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Hovers, Settings, SolangServer};
+    use solang::codegen::Options;
+    use solang::file_cache::FileCache;
+    use solang::sema::ast::Namespace;
+    use solang::{parse_and_resolve, Target};
+    use std::collections::HashMap;
+    use std::fs;
+    use std::path::Path;
+    use std::sync::Arc;
+    use tower_lsp::lsp_types::{Position, Range, TextDocumentContentChangeEvent, Url};
+
+    /// Resolve the source for Substrate, and return the namespace along with the contents
+    /// of its files
+    pub(super) fn resolve(src: &str) -> (Namespace, Vec<Arc<str>>) {
+        let mut cache = FileCache::new();
+
+        cache.set_file_contents("test.sol", src.to_string());
+
+        let ns = parse_and_resolve("test.sol", &mut cache, Target::Substrate);
+
+        let contents = ns
+            .files
+            .iter()
+            .map(|file| cache.get_file_contents(file))
+            .collect();
+
+        (ns, contents)
+    }
+
+    #[test]
+    fn position_to_offset() {
+        let text = "ab\n\u{e9}\u{1f600}x\nlast";
+
+        assert_eq!(
+            SolangServer::position_to_offset(text, Position::new(0, 1)),
+            1
+        );
+        // past the end of the line
+        assert_eq!(
+            SolangServer::position_to_offset(text, Position::new(0, 7)),
+            2
+        );
+        // é is one UTF-16 code unit and two bytes; the emoji is two code units and four bytes
+        assert_eq!(
+            SolangServer::position_to_offset(text, Position::new(1, 1)),
+            5
+        );
+        assert_eq!(
+            SolangServer::position_to_offset(text, Position::new(1, 3)),
+            9
+        );
+        assert_eq!(&text[9..10], "x");
+        assert_eq!(
+            SolangServer::position_to_offset(text, Position::new(2, 4)),
+            text.len()
+        );
+        // past the last line
+        assert_eq!(
+            SolangServer::position_to_offset(text, Position::new(5, 0)),
+            text.len()
+        );
+    }
+
+    #[test]
+    fn apply_changes() {
+        let mut text = String::from("contract c {\n\t// \u{1f600} x\n}\n");
+
+        SolangServer::apply_changes(
+            &mut text,
+            vec![
+                // replace x after the emoji
+                TextDocumentContentChangeEvent {
+                    range: Some(Range::new(Position::new(1, 7), Position::new(1, 8))),
+                    range_length: None,
+                    text: String::from("y"),
+                },
+                // insert a line; this applies to the text after the first change
+                TextDocumentContentChangeEvent {
+                    range: Some(Range::new(Position::new(1, 0), Position::new(1, 0))),
+                    range_length: None,
+                    text: String::from("\tint x;\n"),
+                },
+                // delete across lines
+                TextDocumentContentChangeEvent {
+                    range: Some(Range::new(Position::new(0, 10), Position::new(1, 1))),
+                    range_length: None,
+                    text: String::new(),
+                },
+            ],
+        );
+
+        assert_eq!(text, "contract cint x;\n\t// \u{1f600} y\n}\n");
+
+        SolangServer::apply_changes(
+            &mut text,
+            vec![TextDocumentContentChangeEvent {
+                range: None,
+                range_length: None,
+                text: String::from("contract d {}"),
+            }],
+        );
+
+        assert_eq!(text, "contract d {}");
+    }
+
+    /// Check the file in the directory like the language server does
+    fn check(dir: &Path, filename: &str) -> Hovers {
+        let mut filecache = FileCache::new();
+
+        filecache.add_import_path(dir.to_path_buf());
+
+        SolangServer::analyze(
+            filename,
+            &mut filecache,
+            Target::Substrate,
+            false,
+            &Options::default(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn symbol_at_utf16() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path().canonicalize().unwrap();
+
+        let src = "contract c {\n    function foo() public returns (uint64) {\n        /* \u{1f600} */ return bar();\n    }\n\n    function bar() public returns (uint64) {\n        return 1;\n    }\n}\n";
+
+        fs::write(dir.join("c.sol"), src).unwrap();
+
+        let mut files = HashMap::new();
+
+        files.insert(dir.join("c.sol"), check(&dir, "c.sol"));
+
+        let uri = Url::from_file_path(dir.join("c.sol")).unwrap();
+
+        // the editor counts UTF-16 code units, and the emoji before the call is two of them
+        let line = src.lines().nth(2).unwrap();
+        let character = line[..line.find("bar()").unwrap()].encode_utf16().count();
+
+        let (hovers, symbol) =
+            SolangServer::symbol_at(&files, &uri, Position::new(2, character as u32)).unwrap();
+
+        let loc = hovers.symbols.definition(symbol).unwrap();
+
+        assert!(src[loc.1..loc.2].starts_with("function bar()"));
+    }
+
+    #[test]
+    fn rename() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path().canonicalize().unwrap();
+
+        let base = "contract base {\n    function foo() public virtual returns (uint64) {\n        return 1;\n    }\n}\n";
+        let child = "import \"base.sol\";\n\ncontract child is base {\n    function foo() public override returns (uint64) {\n        return 2;\n    }\n\n    function bar() public returns (uint64) {\n        return foo();\n    }\n}\n";
+
+        fs::write(dir.join("base.sol"), base).unwrap();
+        fs::write(dir.join("child.sol"), child).unwrap();
+
+        let mut files = HashMap::new();
+
+        files.insert(dir.join("base.sol"), check(&dir, "base.sol"));
+        files.insert(dir.join("child.sol"), check(&dir, "child.sol"));
+
+        let base_uri = Url::from_file_path(dir.join("base.sol")).unwrap();
+        let child_uri = Url::from_file_path(dir.join("child.sol")).unwrap();
+
+        // prepareRename gives the range of the name
+        let (hovers, loc, symbol) =
+            SolangServer::rename_target(&files, &base_uri, Position::new(1, 14), &[])
+                .unwrap()
+                .unwrap();
+
+        assert_eq!(
+            SolangServer::loc_to_range(&loc, &hovers.offsets),
+            Range::new(Position::new(1, 13), Position::new(1, 16))
+        );
+
+        // nothing to rename on a keyword
+        assert!(
+            SolangServer::rename_target(&files, &base_uri, Position::new(1, 5), &[])
+                .unwrap()
+                .is_none()
+        );
+
+        // renaming the base function also renames the override in the importing file,
+        // and the call to it
+        let changes = SolangServer::rename_edits(&files, hovers, symbol, "baz");
+
+        let ranges = |uri: &Url| {
+            let mut ranges: Vec<Range> = changes[uri]
+                .iter()
+                .map(|edit| {
+                    assert_eq!(edit.new_text, "baz");
+                    edit.range
+                })
+                .collect();
+
+            ranges.sort_by_key(|range| (range.start.line, range.start.character));
+
+            ranges
+        };
+
+        assert_eq!(changes.len(), 2);
+        assert_eq!(
+            ranges(&base_uri),
+            vec![Range::new(Position::new(1, 13), Position::new(1, 16))]
+        );
+        assert_eq!(
+            ranges(&child_uri),
+            vec![
+                Range::new(Position::new(3, 13), Position::new(3, 16)),
+                Range::new(Position::new(8, 15), Position::new(8, 18))
+            ]
+        );
+
+        assert!(SolangServer::valid_name("baz"));
+        assert!(!SolangServer::valid_name("contract"));
+        assert!(!SolangServer::valid_name("foo bar"));
+        assert!(!SolangServer::valid_name("1foo"));
+    }
+
+    #[test]
+    fn rename_library() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path().canonicalize().unwrap();
+
+        let workspace = dir.join("workspace");
+        let lib = dir.join("lib");
+
+        fs::create_dir(&workspace).unwrap();
+        fs::create_dir(&lib).unwrap();
+
+        let base = "contract base {\n    function foo() public virtual returns (uint64) {\n        return 1;\n    }\n}\n";
+        let child = "import \"base.sol\";\n\ncontract child is base {\n    function bar() public returns (uint64) {\n        /* \u{1f600} */ return foo();\n    }\n}\n";
+
+        // the library file is writable, but outside the workspace
+        fs::write(lib.join("base.sol"), base).unwrap();
+        fs::write(workspace.join("child.sol"), child).unwrap();
+
+        let settings = Settings {
+            import_paths: vec![lib.clone()],
+            ..Default::default()
+        };
+
+        let folders = vec![workspace.clone()];
+        let libraries = settings.library_dirs(&folders);
+
+        assert_eq!(libraries, vec![lib.clone()]);
+
+        let mut filecache = FileCache::new();
+
+        for import_path in settings.import_paths(&folders) {
+            filecache.add_import_path(import_path);
+        }
+
+        let hovers = SolangServer::analyze(
+            "child.sol",
+            &mut filecache,
+            Target::Substrate,
+            false,
+            &Options::default(),
+        )
+        .unwrap();
+
+        let mut files = HashMap::new();
+
+        files.insert(workspace.join("child.sol"), hovers);
+
+        let child_uri = Url::from_file_path(workspace.join("child.sol")).unwrap();
+
+        // the editor counts UTF-16 code units, and the emoji before the call is two of them
+        let line = child.lines().nth(4).unwrap();
+        let character = line[..line.find("foo()").unwrap()].encode_utf16().count();
+        let pos = Position::new(4, character as u32);
+
+        match SolangServer::rename_target(&files, &child_uri, pos, &libraries) {
+            Err(err) => assert!(err
+                .message
+                .starts_with("cannot rename symbol declared in library file")),
+            Ok(_) => panic!("symbol in library file should not be renamed"),
+        }
+
+        // without library directories, foo can be renamed
+        let (hovers, loc, _) = SolangServer::rename_target(&files, &child_uri, pos, &[])
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(&hovers.text[loc.1..loc.2], "foo");
+    }
 }
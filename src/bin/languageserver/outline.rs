@@ -0,0 +1,513 @@
+use solang::parser::{parse, pt};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tower_lsp::lsp_types::{
+    DocumentSymbol, Location, Position, Range, SymbolInformation, SymbolKind, Url,
+};
+
+/// Build the outline of a file from its parse tree: contracts with their functions,
+/// modifiers, state variables, events, structs and enums
+pub fn document_symbols(tree: &pt::SourceUnit, text: &str) -> Vec<DocumentSymbol> {
+    let outline = Outline { text };
+
+    tree.0
+        .iter()
+        .filter_map(|part| match part {
+            pt::SourceUnitPart::ContractDefinition(def) => Some(outline.contract(def)),
+            pt::SourceUnitPart::EnumDefinition(def) => Some(outline.enum_decl(def)),
+            pt::SourceUnitPart::StructDefinition(def) => Some(outline.struct_decl(def)),
+            pt::SourceUnitPart::EventDefinition(def) => Some(outline.event(def)),
+            pt::SourceUnitPart::FunctionDefinition(def) => {
+                Some(outline.function(def, SymbolKind::Function))
+            }
+            pt::SourceUnitPart::VariableDefinition(def) => {
+                Some(outline.variable(def, SymbolKind::Constant))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Search all the Solidity files in the folders for symbols which contain the query
+pub fn workspace_symbols(folders: &[PathBuf], query: &str) -> Vec<SymbolInformation> {
+    let query = query.to_lowercase();
+    let mut files = Vec::new();
+    let mut symbols = Vec::new();
+
+    for folder in folders {
+        solidity_files(folder, &mut files);
+    }
+
+    files.sort();
+    files.dedup();
+
+    for path in files {
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(_) => continue,
+        };
+
+        let uri = match Url::from_file_path(&path) {
+            Ok(uri) => uri,
+            Err(_) => continue,
+        };
+
+        if let Ok((tree, _, _)) = parse(&text, 0) {
+            flatten(
+                document_symbols(&tree, &text),
+                None,
+                &uri,
+                &query,
+                &mut symbols,
+            );
+        }
+    }
+
+    symbols
+}
+
+/// Find all the `.sol` files in the directory and its subdirectories, skipping hidden
+/// directories
+fn solidity_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+
+        let hidden = path
+            .file_name()
+            .map_or(false, |name| name.to_string_lossy().starts_with('.'));
+
+        if hidden {
+            continue;
+        }
+
+        if path.is_dir() {
+            solidity_files(&path, files);
+        } else if path.extension().map_or(false, |ext| ext == "sol") {
+            files.push(path);
+        }
+    }
+}
+
+#[allow(deprecated)]
+fn flatten(
+    outline: Vec<DocumentSymbol>,
+    container_name: Option<&str>,
+    uri: &Url,
+    query: &str,
+    symbols: &mut Vec<SymbolInformation>,
+) {
+    for symbol in outline {
+        if symbol.name.to_lowercase().contains(query) {
+            symbols.push(SymbolInformation {
+                name: symbol.name.clone(),
+                kind: symbol.kind,
+                tags: None,
+                deprecated: None,
+                location: Location {
+                    uri: uri.clone(),
+                    range: symbol.selection_range,
+                },
+                container_name: container_name.map(|name| name.to_owned()),
+            });
+        }
+
+        if let Some(children) = symbol.children {
+            flatten(children, Some(symbol.name.as_str()), uri, query, symbols);
+        }
+    }
+}
+
+struct Outline<'a> {
+    text: &'a str,
+}
+
+impl<'a> Outline<'a> {
+    /// Convert a byte offset to a position for the editor, which counts UTF-16 code units
+    fn position(&self, offset: usize) -> Position {
+        let offset = offset.min(self.text.len());
+        let before = &self.text[..offset];
+
+        let line = before.matches('\n').count();
+        let line_start = before.rfind('\n').map_or(0, |pos| pos + 1);
+        let character: usize = before[line_start..].chars().map(char::len_utf16).sum();
+
+        Position::new(line as u32, character as u32)
+    }
+
+    fn range(&self, start: usize, end: usize) -> Range {
+        Range::new(self.position(start), self.position(end))
+    }
+
+    /// The end of the block which starts after the offset, including the closing brace
+    fn block_end(&self, offset: usize) -> usize {
+        let mut depth = 0;
+
+        for (i, c) in self.text[offset.min(self.text.len())..].char_indices() {
+            match c {
+                '{' => depth += 1,
+                '}' => {
+                    depth -= 1;
+
+                    if depth == 0 {
+                        return offset + i + 1;
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        offset
+    }
+
+    /// The source text of a type
+    fn type_text(&self, ty: &pt::Expression) -> Option<String> {
+        let loc = ty.loc();
+
+        self.text.get(loc.1..loc.2).map(|s| s.to_owned())
+    }
+
+    #[allow(deprecated)]
+    fn symbol(
+        &self,
+        name: &str,
+        detail: Option<String>,
+        kind: SymbolKind,
+        range: (usize, usize),
+        name_loc: &pt::Loc,
+        children: Vec<DocumentSymbol>,
+    ) -> DocumentSymbol {
+        DocumentSymbol {
+            name: name.to_owned(),
+            detail,
+            kind,
+            tags: None,
+            deprecated: None,
+            range: self.range(range.0, range.1),
+            selection_range: self.range(name_loc.1, name_loc.2),
+            children: if children.is_empty() {
+                None
+            } else {
+                Some(children)
+            },
+        }
+    }
+
+    fn contract(&self, def: &pt::ContractDefinition) -> DocumentSymbol {
+        let kind = match def.ty {
+            pt::ContractTy::Interface(_) => SymbolKind::Interface,
+            pt::ContractTy::Library(_) => SymbolKind::Module,
+            _ => SymbolKind::Class,
+        };
+
+        let children = def
+            .parts
+            .iter()
+            .filter_map(|part| match part {
+                pt::ContractPart::StructDefinition(def) => Some(self.struct_decl(def)),
+                pt::ContractPart::EventDefinition(def) => Some(self.event(def)),
+                pt::ContractPart::EnumDefinition(def) => Some(self.enum_decl(def)),
+                pt::ContractPart::VariableDefinition(def) => {
+                    Some(self.variable(def, SymbolKind::Field))
+                }
+                pt::ContractPart::FunctionDefinition(def) => {
+                    Some(self.function(def, SymbolKind::Method))
+                }
+                _ => None,
+            })
+            .collect();
+
+        self.symbol(
+            &def.name.name,
+            Some(def.ty.to_string()),
+            kind,
+            (def.loc.1, self.block_end(def.loc.2)),
+            &def.name.loc,
+            children,
+        )
+    }
+
+    fn function(&self, def: &pt::FunctionDefinition, kind: SymbolKind) -> DocumentSymbol {
+        let (name, kind) = match (&def.name, def.ty) {
+            (Some(name), pt::FunctionTy::Modifier) => (name.name.as_str(), SymbolKind::Method),
+            (Some(name), _) => (name.name.as_str(), kind),
+            (None, pt::FunctionTy::Constructor) => ("constructor", SymbolKind::Constructor),
+            (None, pt::FunctionTy::Fallback) => ("fallback", kind),
+            (None, pt::FunctionTy::Receive) => ("receive", kind),
+            (None, _) => ("function", kind),
+        };
+
+        let end = match &def.body {
+            Some(body) => body.loc().2,
+            None => def.loc.2,
+        };
+
+        self.symbol(
+            name,
+            Some(def.ty.to_string()),
+            kind,
+            (def.loc.1, end),
+            &def.name_loc,
+            Vec::new(),
+        )
+    }
+
+    fn variable(&self, def: &pt::VariableDefinition, kind: SymbolKind) -> DocumentSymbol {
+        let constant = def
+            .attrs
+            .iter()
+            .any(|attr| matches!(attr, pt::VariableAttribute::Constant(_)));
+
+        self.symbol(
+            &def.name.name,
+            self.type_text(&def.ty),
+            if constant { SymbolKind::Constant } else { kind },
+            (def.loc.1, def.loc.2),
+            &def.name.loc,
+            Vec::new(),
+        )
+    }
+
+    fn event(&self, def: &pt::EventDefinition) -> DocumentSymbol {
+        self.symbol(
+            &def.name.name,
+            None,
+            SymbolKind::Event,
+            (def.loc.1, def.loc.2),
+            &def.name.loc,
+            Vec::new(),
+        )
+    }
+
+    fn struct_decl(&self, def: &pt::StructDefinition) -> DocumentSymbol {
+        let fields = def
+            .fields
+            .iter()
+            .map(|field| {
+                self.symbol(
+                    &field.name.name,
+                    self.type_text(&field.ty),
+                    SymbolKind::Field,
+                    (field.loc.1, field.loc.2),
+                    &field.name.loc,
+                    Vec::new(),
+                )
+            })
+            .collect();
+
+        self.symbol(
+            &def.name.name,
+            None,
+            SymbolKind::Struct,
+            (def.loc.1, def.loc.2),
+            &def.name.loc,
+            fields,
+        )
+    }
+
+    fn enum_decl(&self, def: &pt::EnumDefinition) -> DocumentSymbol {
+        let values = def
+            .values
+            .iter()
+            .map(|value| {
+                self.symbol(
+                    &value.name,
+                    None,
+                    SymbolKind::EnumMember,
+                    (value.loc.1, value.loc.2),
+                    &value.loc,
+                    Vec::new(),
+                )
+            })
+            .collect();
+
+        self.symbol(
+            &def.name.name,
+            None,
+            SymbolKind::Enum,
+            (def.loc.1, def.loc.2),
+            &def.name.loc,
+            values,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{document_symbols, workspace_symbols};
+    use solang::parser::parse;
+    use std::fs;
+    use tower_lsp::lsp_types::{Position, Range, SymbolKind};
+
+    const SRC: &str = r#"// 🦀
+contract counter {
+    struct S { uint64 f; }
+    enum State { Off, On }
+    event Counted(uint64 n);
+    uint64 constant MAX = 10;
+    uint64 count;
+
+    constructor() {}
+
+    function inc(uint64 by) public {
+        count += by;
+    }
+}
+
+library Lib {}
+"#;
+
+    #[test]
+    fn document() {
+        let (tree, _, _) = parse(SRC, 0).unwrap();
+
+        let outline = document_symbols(&tree, SRC);
+
+        let names: Vec<(&str, SymbolKind)> = outline
+            .iter()
+            .map(|symbol| (symbol.name.as_str(), symbol.kind))
+            .collect();
+
+        assert_eq!(
+            names,
+            vec![("counter", SymbolKind::Class), ("Lib", SymbolKind::Module)]
+        );
+
+        let counter = &outline[0];
+
+        assert_eq!(counter.detail.as_deref(), Some("contract"));
+        assert_eq!(
+            counter.selection_range,
+            Range::new(Position::new(1, 9), Position::new(1, 16))
+        );
+        // the range runs up to the closing brace of the contract
+        assert_eq!(counter.range.start, Position::new(1, 0));
+        assert_eq!(counter.range.end, Position::new(13, 1));
+
+        let children = counter.children.as_ref().unwrap();
+
+        let names: Vec<(&str, SymbolKind)> = children
+            .iter()
+            .map(|symbol| (symbol.name.as_str(), symbol.kind))
+            .collect();
+
+        assert_eq!(
+            names,
+            vec![
+                ("S", SymbolKind::Struct),
+                ("State", SymbolKind::Enum),
+                ("Counted", SymbolKind::Event),
+                ("MAX", SymbolKind::Constant),
+                ("count", SymbolKind::Field),
+                ("constructor", SymbolKind::Constructor),
+                ("inc", SymbolKind::Method),
+            ]
+        );
+
+        let fields = children[0].children.as_ref().unwrap();
+
+        assert_eq!(fields[0].name, "f");
+        assert_eq!(fields[0].detail.as_deref(), Some("uint64"));
+
+        let values = children[1].children.as_ref().unwrap();
+
+        assert_eq!(values.len(), 2);
+        assert_eq!(values[1].name, "On");
+        assert_eq!(values[1].kind, SymbolKind::EnumMember);
+
+        assert_eq!(children[4].detail.as_deref(), Some("uint64"));
+        assert_eq!(
+            children[6].selection_range,
+            Range::new(Position::new(10, 13), Position::new(10, 16))
+        );
+        assert_eq!(children[6].range.end, Position::new(12, 5));
+    }
+
+    #[test]
+    fn utf16_columns() {
+        let src = "/* 🦀 */ contract c {}";
+
+        let (tree, _, _) = parse(src, 0).unwrap();
+
+        let outline = document_symbols(&tree, src);
+
+        // the crab is two UTF-16 code units but four bytes
+        assert_eq!(
+            outline[0].selection_range,
+            Range::new(Position::new(0, 18), Position::new(0, 19))
+        );
+    }
+
+    #[test]
+    fn workspace() {
+        let dir = tempfile::tempdir().unwrap();
+
+        fs::create_dir(dir.path().join("lib")).unwrap();
+        fs::create_dir(dir.path().join(".git")).unwrap();
+
+        fs::write(
+            dir.path().join("a.sol"),
+            "contract Counter { function increment() public {} }",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("lib").join("b.sol"),
+            "library Count { function count() public {} }",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join(".git").join("c.sol"),
+            "contract CountHidden {}",
+        )
+        .unwrap();
+        fs::write(dir.path().join("d.txt"), "contract CountText {}").unwrap();
+
+        let folders = vec![dir.path().to_path_buf()];
+
+        let mut found: Vec<(String, Option<String>, String)> = workspace_symbols(&folders, "COUNT")
+            .into_iter()
+            .map(|symbol| {
+                let file = symbol
+                    .location
+                    .uri
+                    .to_file_path()
+                    .unwrap()
+                    .file_name()
+                    .unwrap()
+                    .to_string_lossy()
+                    .to_string();
+
+                (symbol.name, symbol.container_name, file)
+            })
+            .collect();
+
+        found.sort();
+
+        assert_eq!(
+            found,
+            vec![
+                ("Count".to_owned(), None, "b.sol".to_owned()),
+                ("Counter".to_owned(), None, "a.sol".to_owned()),
+                (
+                    "count".to_owned(),
+                    Some("Count".to_owned()),
+                    "b.sol".to_owned()
+                ),
+            ]
+        );
+
+        let found = workspace_symbols(&folders, "incr");
+
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].name, "increment");
+        assert_eq!(found[0].container_name.as_deref(), Some("Counter"));
+        assert_eq!(
+            found[0].location.range,
+            Range::new(Position::new(0, 28), Position::new(0, 37))
+        );
+    }
+}
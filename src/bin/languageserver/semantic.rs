@@ -0,0 +1,304 @@
+use super::symbols::{SymbolIndex, SymbolKind};
+use solang::parser::pt;
+use solang::sema::ast::{Namespace, Type};
+use solang::sema::diagnostics::FileOffsets;
+use tower_lsp::lsp_types::{
+    SemanticToken, SemanticTokenModifier, SemanticTokenType, SemanticTokensLegend,
+};
+
+/// The token types, in the order of the legend
+const TOKEN_TYPES: [SemanticTokenType; 13] = [
+    SemanticTokenType::NAMESPACE,
+    SemanticTokenType::CLASS,
+    SemanticTokenType::INTERFACE,
+    SemanticTokenType::STRUCT,
+    SemanticTokenType::ENUM,
+    SemanticTokenType::ENUM_MEMBER,
+    SemanticTokenType::EVENT,
+    SemanticTokenType::FUNCTION,
+    SemanticTokenType::METHOD,
+    SemanticTokenType::MODIFIER,
+    SemanticTokenType::PARAMETER,
+    SemanticTokenType::VARIABLE,
+    SemanticTokenType::PROPERTY,
+];
+
+/// The token modifiers, in the order of the legend; the bit of each modifier is its position
+const TOKEN_MODIFIERS: [SemanticTokenModifier; 7] = [
+    SemanticTokenModifier::DECLARATION,
+    SemanticTokenModifier::READONLY,
+    SemanticTokenModifier::new("payable"),
+    SemanticTokenModifier::new("view"),
+    SemanticTokenModifier::new("pure"),
+    SemanticTokenModifier::new("storage"),
+    SemanticTokenModifier::new("memory"),
+];
+
+pub fn legend() -> SemanticTokensLegend {
+    SemanticTokensLegend {
+        token_types: TOKEN_TYPES.to_vec(),
+        token_modifiers: TOKEN_MODIFIERS.to_vec(),
+    }
+}
+
+/// Classify every name in the first file between the start and end offsets, encoded
+/// relative to the previous token as the protocol requires
+pub fn semantic_tokens(
+    ns: &Namespace,
+    symbols: &SymbolIndex,
+    offsets: &FileOffsets,
+    start: usize,
+    end: usize,
+) -> Vec<SemanticToken> {
+    let mut tokens = Vec::new();
+    let mut prev_line = 0;
+    let mut prev_start = 0;
+    let mut prev_end = 0;
+
+    for (loc, kind, declaration) in symbols.names(0) {
+        // the same name may refer to more than one symbol, e.g. a public state variable
+        // and its accessor function; tokens must not overlap
+        if loc.1 < prev_end || loc.1 < start || loc.2 > end || loc.1 == loc.2 {
+            continue;
+        }
+
+        let (line, column) = offsets.convert(0, loc.1);
+        let (end_line, _) = offsets.convert(0, loc.2);
+
+        if line != end_line {
+            continue;
+        }
+
+        let (token_type, mut modifiers) = classify(ns, kind);
+
+        if declaration {
+            modifiers.push(SemanticTokenModifier::DECLARATION);
+        }
+
+        let delta_start = if line == prev_line {
+            column - prev_start
+        } else {
+            column
+        };
+
+        tokens.push(SemanticToken {
+            delta_line: (line - prev_line) as u32,
+            delta_start: delta_start as u32,
+            length: (loc.2 - loc.1) as u32,
+            token_type: TOKEN_TYPES.iter().position(|ty| *ty == token_type).unwrap() as u32,
+            token_modifiers_bitset: modifiers
+                .iter()
+                .filter_map(|modifier| TOKEN_MODIFIERS.iter().position(|m| m == modifier))
+                .fold(0, |bitset, bit| bitset | (1 << bit)),
+        });
+
+        prev_line = line;
+        prev_start = column;
+        prev_end = loc.2;
+    }
+
+    tokens
+}
+
+/// The token type and modifiers for the symbol
+fn classify(ns: &Namespace, kind: SymbolKind) -> (SemanticTokenType, Vec<SemanticTokenModifier>) {
+    match kind {
+        SymbolKind::Variable(function_no, var_no) => {
+            let symtable = &ns.functions[function_no].symtable;
+
+            let token_type = if symtable.arguments.contains(&Some(var_no))
+                || symtable.returns.contains(&var_no)
+            {
+                SemanticTokenType::PARAMETER
+            } else {
+                SemanticTokenType::VARIABLE
+            };
+
+            let modifiers = match symtable.vars.get(&var_no) {
+                Some(var) => type_modifiers(&var.ty),
+                None => Vec::new(),
+            };
+
+            (token_type, modifiers)
+        }
+        SymbolKind::StorageVariable(contract_no, var_no) => {
+            let var = &ns.contracts[contract_no].variables[var_no];
+
+            let mut modifiers = payable(&var.ty);
+
+            modifiers.push(if var.constant {
+                SemanticTokenModifier::READONLY
+            } else {
+                SemanticTokenModifier::new("storage")
+            });
+
+            (SemanticTokenType::PROPERTY, modifiers)
+        }
+        SymbolKind::Constant(var_no) => {
+            let mut modifiers = payable(&ns.constants[var_no].ty);
+
+            modifiers.push(SemanticTokenModifier::READONLY);
+
+            (SemanticTokenType::VARIABLE, modifiers)
+        }
+        SymbolKind::Function(function_no) => {
+            let func = &ns.functions[function_no];
+
+            let token_type = if func.ty == pt::FunctionTy::Modifier {
+                SemanticTokenType::MODIFIER
+            } else if func.contract_no.is_some() {
+                SemanticTokenType::METHOD
+            } else {
+                SemanticTokenType::FUNCTION
+            };
+
+            let modifiers = match func.mutability {
+                Some(pt::StateMutability::Payable(_)) => {
+                    vec![SemanticTokenModifier::new("payable")]
+                }
+                Some(pt::StateMutability::View(_)) | Some(pt::StateMutability::Constant(_)) => {
+                    vec![SemanticTokenModifier::new("view")]
+                }
+                Some(pt::StateMutability::Pure(_)) => vec![SemanticTokenModifier::new("pure")],
+                None => Vec::new(),
+            };
+
+            (token_type, modifiers)
+        }
+        SymbolKind::Struct(_) => (SemanticTokenType::STRUCT, Vec::new()),
+        SymbolKind::StructField(struct_no, field_no) => (
+            SemanticTokenType::PROPERTY,
+            payable(&ns.structs[struct_no].fields[field_no].ty),
+        ),
+        SymbolKind::Enum(_) => (SemanticTokenType::ENUM, Vec::new()),
+        SymbolKind::EnumValue(..) => (
+            SemanticTokenType::ENUM_MEMBER,
+            vec![SemanticTokenModifier::READONLY],
+        ),
+        SymbolKind::Event(_) => (SemanticTokenType::EVENT, Vec::new()),
+        SymbolKind::Contract(contract_no) => {
+            if ns.contracts[contract_no].is_interface() {
+                (SemanticTokenType::INTERFACE, Vec::new())
+            } else {
+                (SemanticTokenType::CLASS, Vec::new())
+            }
+        }
+        SymbolKind::File(_) => (SemanticTokenType::NAMESPACE, Vec::new()),
+    }
+}
+
+/// Modifiers which follow from the type of a variable in a function: `address payable`,
+/// and whether a reference type refers to contract storage or memory
+fn type_modifiers(ty: &Type) -> Vec<SemanticTokenModifier> {
+    match ty {
+        Type::StorageRef(_) => vec![SemanticTokenModifier::new("storage")],
+        Type::Ref(ty) => type_modifiers(ty),
+        _ if ty.is_reference_type() => vec![SemanticTokenModifier::new("memory")],
+        _ => payable(ty),
+    }
+}
+
+fn payable(ty: &Type) -> Vec<SemanticTokenModifier> {
+    if *ty == Type::Address(true) {
+        vec![SemanticTokenModifier::new("payable")]
+    } else {
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{legend, semantic_tokens};
+    use crate::languageserver::symbols::SymbolIndex;
+    use crate::languageserver::test::resolve;
+    use solang::file_cache::FileCache;
+
+    const SRC: &str = r#"
+contract c {
+    uint64 count;
+
+    function inc(uint64 by) public {
+        count += by;
+    }
+}
+"#;
+
+    /// The tokens between the offsets as (delta line, delta start, length, type, modifiers)
+    fn tokens(start: usize, end: usize) -> Vec<(u32, u32, u32, u32, u32)> {
+        let (ns, contents) = resolve(SRC);
+        let symbols = SymbolIndex::new(&ns, &contents);
+
+        let mut cache = FileCache::new();
+
+        cache.set_file_contents("test.sol", SRC.to_string());
+
+        let offsets = ns.file_offset(&mut cache);
+
+        semantic_tokens(&ns, &symbols, &offsets, start, end)
+            .into_iter()
+            .map(|token| {
+                (
+                    token.delta_line,
+                    token.delta_start,
+                    token.length,
+                    token.token_type,
+                    token.token_modifiers_bitset,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn encoding() {
+        let legend = legend();
+
+        let ty = |name: &str| {
+            legend
+                .token_types
+                .iter()
+                .position(|ty| ty.as_str() == name)
+                .unwrap() as u32
+        };
+        let modifier = |name: &str| {
+            1u32 << legend
+                .token_modifiers
+                .iter()
+                .position(|modifier| modifier.as_str() == name)
+                .unwrap()
+        };
+
+        let declaration = modifier("declaration");
+        let storage = modifier("storage");
+
+        assert_eq!(
+            tokens(0, usize::MAX),
+            vec![
+                // c on line 1
+                (1, 9, 1, ty("class"), declaration),
+                // count on the next line, so the start is from the beginning of the line
+                (1, 11, 5, ty("property"), declaration | storage),
+                // inc two lines further down
+                (2, 13, 3, ty("method"), declaration),
+                // by on the same line, so the start is relative to inc
+                (0, 11, 2, ty("parameter"), declaration),
+                (1, 8, 5, ty("property"), storage),
+                (0, 9, 2, ty("parameter"), 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn range() {
+        let start = SRC.find("        count +=").unwrap();
+        let end = start + SRC[start..].find('\n').unwrap();
+
+        // the first token is still relative to the start of the document
+        assert_eq!(
+            tokens(start, end),
+            vec![(5, 8, 5, 12, 32), (0, 9, 2, 10, 0)]
+        );
+
+        // a name which is only partly in the range is left out
+        assert_eq!(tokens(start, start + 10), Vec::new());
+    }
+}
@@ -0,0 +1,497 @@
+use super::completion::{member_chain, Context, Receiver};
+use solang::parser::pt;
+use solang::sema::ast::{Function, Namespace, Parameter, Symbol, Tag, Type};
+use solang::sema::builtin::{available_builtins, Prototype};
+use solang::sema::tags::render;
+use tower_lsp::lsp_types::{
+    Documentation, ParameterInformation, ParameterLabel, SignatureHelp, SignatureInformation,
+};
+
+/// The keyword before the callee, if any
+#[derive(PartialEq, Clone, Copy)]
+enum Call {
+    Function,
+    New,
+    Emit,
+}
+
+/// Find the signatures of the function, constructor, event or modifier being called at the
+/// offset. Overloaded functions get a signature for each overload.
+pub fn signature_help(ns: &Namespace, text: &str, offset: usize) -> Option<SignatureHelp> {
+    let offset = offset.min(text.len());
+
+    let (open, active) = open_call(text, offset)?;
+
+    // there may be whitespace between the callee and the opening bracket
+    let end = text[..open].trim_end().len();
+
+    let chain = member_chain(text, end)?;
+    let (last, receiver) = chain.split_last()?;
+
+    let call = if receiver.is_empty() {
+        let before = text[..end - last.name.len()].trim_end();
+
+        if keyword(before, "new") {
+            Call::New
+        } else if keyword(before, "emit") {
+            Call::Emit
+        } else {
+            Call::Function
+        }
+    } else {
+        Call::Function
+    };
+
+    let cx = Context::new(ns, text, offset);
+    let name = last.name.as_str();
+    let mut signatures = Vec::new();
+
+    if receiver.is_empty() {
+        for symbol in visible_symbols(&cx, name) {
+            signatures.extend(symbol_signatures(ns, symbol, call));
+        }
+
+        if call == Call::Function {
+            for prototype in available_builtins(ns) {
+                if prototype.namespace.is_none() && prototype.name == name {
+                    signatures.push(builtin_signature(ns, prototype));
+                }
+            }
+        }
+    } else {
+        match cx.resolve(receiver)? {
+            Receiver::Builtin(namespace) => {
+                for prototype in available_builtins(ns) {
+                    if prototype.namespace == Some(namespace.as_str()) && prototype.name == name {
+                        signatures.push(builtin_signature(ns, prototype));
+                    }
+                }
+            }
+            Receiver::Contract(contract_no) => {
+                let key = (
+                    ns.contracts[contract_no].loc.0,
+                    Some(contract_no),
+                    name.to_owned(),
+                );
+
+                for symbol in symbols(ns, &key) {
+                    signatures.extend(symbol_signatures(ns, symbol, call));
+                }
+            }
+            Receiver::File(file_no) => {
+                let key = (file_no, None, name.to_owned());
+
+                for symbol in symbols(ns, &key) {
+                    signatures.extend(symbol_signatures(ns, symbol, call));
+                }
+            }
+            Receiver::Value(ty) => {
+                if let Type::Contract(contract_no) = ty.deref_any() {
+                    for function_no in ns.contracts[*contract_no].all_functions.keys() {
+                        let func = &ns.functions[*function_no];
+
+                        if func.name == name
+                            && func.ty == pt::FunctionTy::Function
+                            && func.is_public()
+                        {
+                            signatures.push(function_signature(ns, func, 0));
+                        }
+                    }
+                }
+
+                // the value is passed as the first argument of library functions
+                for function_no in cx.using_functions(&ty) {
+                    let func = &ns.functions[function_no];
+
+                    if func.name == name {
+                        signatures.push(function_signature(ns, func, 1));
+                    }
+                }
+            }
+            Receiver::Enum(_) => (),
+        }
+    }
+
+    // overridden functions have the same signature as the functions they override
+    let mut labels = Vec::new();
+    signatures.retain(|signature| {
+        if labels.contains(&signature.label) {
+            false
+        } else {
+            labels.push(signature.label.clone());
+            true
+        }
+    });
+
+    if signatures.is_empty() {
+        return None;
+    }
+
+    // select the first overload which has enough parameters for the arguments so far
+    let active_signature = signatures
+        .iter()
+        .position(|signature| {
+            let params = signature
+                .parameters
+                .as_ref()
+                .map_or(0, |params| params.len());
+
+            params > active || (params == 0 && active == 0)
+        })
+        .unwrap_or(0);
+
+    Some(SignatureHelp {
+        signatures,
+        active_signature: Some(active_signature as u32),
+        active_parameter: Some(active as u32),
+    })
+}
+
+/// Find the opening bracket of the call which the offset is in, and the number of
+/// arguments before the offset
+fn open_call(text: &str, offset: usize) -> Option<(usize, usize)> {
+    let mut depth = 0;
+    let mut commas = 0;
+
+    for (pos, c) in text[..offset].char_indices().rev() {
+        match c {
+            ')' | ']' | '}' => depth += 1,
+            '(' if depth == 0 => return Some((pos, commas)),
+            '[' | '{' if depth == 0 => return None,
+            '(' | '[' | '{' => depth -= 1,
+            ',' if depth == 0 => commas += 1,
+            ';' => return None,
+            _ => (),
+        }
+    }
+
+    None
+}
+
+/// Does the text end with the keyword, as a whole word
+fn keyword(text: &str, keyword: &str) -> bool {
+    text.strip_suffix(keyword).map_or(false, |before| {
+        !before.ends_with(|c: char| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+    })
+}
+
+/// All the symbols with the name which are visible from the cursor, the most derived contract
+/// first
+fn visible_symbols<'a>(cx: &Context<'a>, name: &str) -> Vec<&'a Symbol> {
+    let ns = cx.ns;
+
+    cx.contracts
+        .iter()
+        .map(|contract_no| (ns.contracts[*contract_no].loc.0, Some(*contract_no)))
+        .chain(std::iter::once((0, None)))
+        .flat_map(|(file_no, contract_no)| symbols(ns, &(file_no, contract_no, name.to_owned())))
+        .collect()
+}
+
+/// The symbols with the key, both functions and other declarations
+fn symbols<'a>(ns: &'a Namespace, key: &(usize, Option<usize>, String)) -> Vec<&'a Symbol> {
+    ns.variable_symbols
+        .get(key)
+        .into_iter()
+        .chain(ns.function_symbols.get(key))
+        .collect()
+}
+
+fn symbol_signatures(ns: &Namespace, symbol: &Symbol, call: Call) -> Vec<SignatureInformation> {
+    match (symbol, call) {
+        (Symbol::Function(list), Call::Function) => list
+            .iter()
+            .map(|(_, function_no)| &ns.functions[*function_no])
+            .filter(|func| {
+                func.ty == pt::FunctionTy::Function || func.ty == pt::FunctionTy::Modifier
+            })
+            .map(|func| function_signature(ns, func, 0))
+            .collect(),
+        (Symbol::Struct(_, struct_no), Call::Function) => {
+            let decl = &ns.structs[*struct_no];
+
+            vec![signature(ns, &decl.name, &decl.fields, &[], &decl.tags)]
+        }
+        (Symbol::Event(events), Call::Emit) => events
+            .iter()
+            .map(|(_, event_no)| {
+                let decl = &ns.events[*event_no];
+
+                signature(ns, &decl.name, &decl.fields, &[], &decl.tags)
+            })
+            .collect(),
+        (Symbol::Contract(_, contract_no), Call::New) => {
+            let contract = &ns.contracts[*contract_no];
+
+            let constructors: Vec<_> = contract
+                .functions
+                .iter()
+                .map(|function_no| &ns.functions[*function_no])
+                .filter(|func| func.is_constructor())
+                .map(|func| signature(ns, &contract.name, &func.params, &[], &func.tags))
+                .collect();
+
+            // without a constructor, there is a default one without arguments
+            if constructors.is_empty() {
+                vec![signature(ns, &contract.name, &[], &[], &contract.tags)]
+            } else {
+                constructors
+            }
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// The signature of a function, leaving out the first `skip` parameters
+fn function_signature(ns: &Namespace, func: &Function, skip: usize) -> SignatureInformation {
+    let mut info = signature(
+        ns,
+        &func.name,
+        &func.params[skip..],
+        &func.returns,
+        &func.tags,
+    );
+
+    // the parameter tags are numbered from the first parameter
+    if let Some(params) = &mut info.parameters {
+        for (no, param) in params.iter_mut().enumerate() {
+            param.documentation = param_doc(&func.tags, no + skip);
+        }
+    }
+
+    info
+}
+
+fn signature(
+    ns: &Namespace,
+    name: &str,
+    params: &[Parameter],
+    returns: &[Parameter],
+    tags: &[Tag],
+) -> SignatureInformation {
+    let mut label = format!("{}(", name);
+    let mut parameters = Vec::new();
+
+    for (no, param) in params.iter().enumerate() {
+        if no > 0 {
+            label.push_str(", ");
+        }
+
+        let start = label.len();
+
+        label.push_str(&param.ty.to_string(ns));
+
+        if !param.name.is_empty() {
+            label.push(' ');
+            label.push_str(&param.name);
+        }
+
+        parameters.push(ParameterInformation {
+            label: ParameterLabel::LabelOffsets([start as u32, label.len() as u32]),
+            documentation: param_doc(tags, no),
+        });
+    }
+
+    label.push(')');
+
+    if !returns.is_empty() {
+        let returns = returns
+            .iter()
+            .map(|param| param.ty.to_string(ns))
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        label.push_str(&format!(" returns ({})", returns));
+    }
+
+    let doc = render(tags);
+
+    SignatureInformation {
+        label,
+        documentation: if doc.is_empty() {
+            None
+        } else {
+            Some(Documentation::String(doc))
+        },
+        parameters: Some(parameters),
+        active_parameter: None,
+    }
+}
+
+fn builtin_signature(ns: &Namespace, prototype: &Prototype) -> SignatureInformation {
+    let mut label = match prototype.namespace {
+        Some(namespace) => format!("{}.{}(", namespace, prototype.name),
+        None => format!("{}(", prototype.name),
+    };
+    let mut parameters = Vec::new();
+
+    for (no, ty) in prototype.args.iter().enumerate() {
+        if no > 0 {
+            label.push_str(", ");
+        }
+
+        let start = label.len();
+
+        label.push_str(&ty.to_string(ns));
+
+        parameters.push(ParameterInformation {
+            label: ParameterLabel::LabelOffsets([start as u32, label.len() as u32]),
+            documentation: None,
+        });
+    }
+
+    label.push(')');
+
+    if !prototype.ret.is_empty() {
+        let returns = prototype
+            .ret
+            .iter()
+            .map(|ty| ty.to_string(ns))
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        label.push_str(&format!(" returns ({})", returns));
+    }
+
+    SignatureInformation {
+        label,
+        documentation: Some(Documentation::String(prototype.doc.to_owned())),
+        parameters: Some(parameters),
+        active_parameter: None,
+    }
+}
+
+/// The `@param` documentation of the parameter
+fn param_doc(tags: &[Tag], no: usize) -> Option<Documentation> {
+    tags.iter()
+        .find(|tag| tag.tag == "param" && tag.no == no)
+        .map(|tag| Documentation::String(tag.value.clone()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::signature_help;
+    use crate::languageserver::test::resolve;
+    use tower_lsp::lsp_types::{Documentation, ParameterLabel, SignatureHelp};
+
+    const SRC: &str = r#"
+library L {
+    function add(uint64 a, uint64 b) internal pure returns (uint64) {
+        return a + b;
+    }
+}
+
+contract other {
+    constructor(uint64 x, bool y) {}
+}
+
+contract c {
+    using L for uint64;
+
+    event Counted(uint64 n, bool up);
+
+    /// @param a the first
+    function f(uint64 a) public pure returns (uint64) {
+        return a;
+    }
+
+    function f(uint64 a, uint64 b) public pure returns (uint64) {
+        return a + b;
+    }
+
+    function test() public {
+        uint64 v = f(f(1), 2);
+        other o = new other(1, true);
+        emit Counted(v, true);
+        v = v.add(3);
+        require(v > 1, "small");
+    }
+}
+"#;
+
+    /// The signature help after the text
+    fn help(after: &str) -> Option<SignatureHelp> {
+        let (ns, _) = resolve(SRC);
+
+        let offset = SRC.find(after).unwrap() + after.len();
+
+        signature_help(&ns, SRC, offset)
+    }
+
+    /// The labels of the signatures, the active signature and the active parameter
+    fn summary(after: &str) -> (Vec<String>, u32, u32) {
+        let help = help(after).unwrap();
+
+        (
+            help.signatures
+                .into_iter()
+                .map(|signature| signature.label)
+                .collect(),
+            help.active_signature.unwrap(),
+            help.active_parameter.unwrap(),
+        )
+    }
+
+    #[test]
+    fn overloads() {
+        let overloads = vec![
+            String::from("f(uint64 a) returns (uint64)"),
+            String::from("f(uint64 a, uint64 b) returns (uint64)"),
+        ];
+
+        // the outer call, after the first argument: only the second overload fits
+        assert_eq!(summary("f(f(1), "), (overloads.clone(), 1, 1));
+        // the inner call
+        assert_eq!(summary("f(f("), (overloads.clone(), 0, 0));
+        // after the closing bracket of the inner call, the outer call is active again
+        assert_eq!(summary("f(f(1)"), (overloads, 0, 0));
+
+        let help = help("f(f(").unwrap();
+        let params = help.signatures[0].parameters.as_ref().unwrap();
+
+        assert_eq!(params[0].label, ParameterLabel::LabelOffsets([2, 10]));
+        assert_eq!(
+            params[0].documentation,
+            Some(Documentation::String(String::from("the first")))
+        );
+    }
+
+    #[test]
+    fn keywords() {
+        assert_eq!(
+            summary("new other(1, "),
+            (vec![String::from("other(uint64 x, bool y)")], 0, 1)
+        );
+        assert_eq!(
+            summary("emit Counted("),
+            (vec![String::from("Counted(uint64 n, bool up)")], 0, 0)
+        );
+    }
+
+    #[test]
+    fn using_for() {
+        // the value is the first argument, so it is left out
+        assert_eq!(
+            summary("v.add("),
+            (vec![String::from("add(uint64 b) returns (uint64)")], 0, 0)
+        );
+    }
+
+    #[test]
+    fn builtins() {
+        let (labels, active_signature, active_parameter) = summary("require(v > 1, ");
+
+        assert_eq!(labels.len(), 2);
+        assert!(labels[0].starts_with("require(bool)"));
+        assert!(labels[1].starts_with("require(bool, string)"));
+        assert_eq!(active_signature, 1);
+        assert_eq!(active_parameter, 1);
+    }
+
+    #[test]
+    fn outside_call() {
+        assert_eq!(help("uint64 v = "), None);
+        // a statement ends the search for the opening bracket
+        assert_eq!(help("f(f(1), 2);"), None);
+    }
+}
@@ -0,0 +1,653 @@
+use num_traits::ToPrimitive;
+use solang::parser::pt;
+use solang::sema::ast::{
+    DestructureField, Expression, Namespace, Statement, Symbol, Type, Variable,
+};
+use solang::sema::contracts::visit_bases;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Anything which is declared once and can be referenced by name
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum SymbolKind {
+    /// Local variable, parameter or return value of a function
+    Variable(usize, usize),
+    StorageVariable(usize, usize),
+    Constant(usize),
+    Function(usize),
+    Struct(usize),
+    StructField(usize, usize),
+    Enum(usize),
+    EnumValue(usize, usize),
+    Event(usize),
+    Contract(usize),
+    File(usize),
+}
+
+/// Index of all the declarations in a namespace and the locations where they are named,
+/// across all the files in the namespace
+pub struct SymbolIndex {
+    /// Where each symbol is declared
+    definitions: HashMap<SymbolKind, pt::Loc>,
+    /// The name of the symbol in its declaration
+    declarations: HashMap<SymbolKind, pt::Loc>,
+    /// Every location which names a symbol, including the name in its declaration, sorted
+    /// by file and offset
+    references: Vec<(pt::Loc, SymbolKind)>,
+}
+
+impl SymbolIndex {
+    /// Build the index from the resolved namespace. The contents of the files are needed to
+    /// find the name within locations which span a larger expression or declaration.
+    pub fn new(ns: &Namespace, files: &[Arc<str>]) -> Self {
+        let mut builder = Builder {
+            ns,
+            files,
+            function_no: 0,
+            definitions: HashMap::new(),
+            declarations: HashMap::new(),
+            references: Vec::new(),
+        };
+
+        builder.declarations();
+        builder.uses();
+
+        let mut references = builder.references;
+
+        references.sort_by_key(|(loc, _)| (loc.0, loc.1, loc.2));
+        references.dedup();
+
+        SymbolIndex {
+            definitions: builder.definitions,
+            declarations: builder.declarations,
+            references,
+        }
+    }
+
+    /// Find the symbol named at the given offset. If names overlap, the shortest one wins.
+    pub fn symbol_at(&self, file_no: usize, offset: usize) -> Option<SymbolKind> {
+        self.name_at(file_no, offset).map(|(_, kind)| kind)
+    }
+
+    /// Find the name at the given offset, and the symbol it refers to
+    pub fn name_at(&self, file_no: usize, offset: usize) -> Option<(pt::Loc, SymbolKind)> {
+        self.references
+            .iter()
+            .filter(|(loc, _)| loc.0 == file_no && loc.1 <= offset && offset <= loc.2)
+            .min_by_key(|(loc, _)| loc.2 - loc.1)
+            .cloned()
+    }
+
+    /// Find the symbol which is declared at the location. This is used to find the same
+    /// symbol in the index of another file.
+    pub fn declared_at(&self, loc: pt::Loc) -> Option<SymbolKind> {
+        self.definitions
+            .iter()
+            .find(|(_, definition)| **definition == loc)
+            .map(|(kind, _)| *kind)
+    }
+
+    /// Location of the declaration of the symbol
+    pub fn definition(&self, kind: SymbolKind) -> Option<pt::Loc> {
+        self.definitions.get(&kind).cloned()
+    }
+
+    /// All the locations where the symbol is named, optionally including its declaration
+    pub fn references(&self, kind: SymbolKind, include_declaration: bool) -> Vec<pt::Loc> {
+        let declaration = self.declarations.get(&kind);
+
+        self.references
+            .iter()
+            .filter(|(loc, k)| *k == kind && (include_declaration || Some(loc) != declaration))
+            .map(|(loc, _)| *loc)
+            .collect()
+    }
+
+    /// All the names in the file in order, with the symbol they refer to and whether the
+    /// name is the declaration of the symbol
+    pub fn names(&self, file_no: usize) -> Vec<(pt::Loc, SymbolKind, bool)> {
+        self.references
+            .iter()
+            .filter(|(loc, _)| loc.0 == file_no)
+            .map(|(loc, kind)| (*loc, *kind, self.declarations.get(kind) == Some(loc)))
+            .collect()
+    }
+}
+
+/// Symbols which have to be renamed together: a function along with the functions it
+/// overrides and the functions which override it, and a public state variable along with
+/// its accessor function.
+pub fn related(ns: &Namespace, kind: SymbolKind) -> Vec<SymbolKind> {
+    let mut functions = match kind {
+        SymbolKind::Function(function_no) => vec![function_no],
+        SymbolKind::StorageVariable(contract_no, var_no) => {
+            let name = &ns.contracts[contract_no].variables[var_no].name;
+
+            ns.functions
+                .iter()
+                .position(|func| {
+                    func.is_accessor && func.contract_no == Some(contract_no) && func.name == *name
+                })
+                .into_iter()
+                .collect()
+        }
+        _ => return vec![kind],
+    };
+
+    let mut i = 0;
+
+    while i < functions.len() {
+        let func = &ns.functions[functions[i]];
+
+        if let Some(contract_no) = func.contract_no {
+            let bases = visit_bases(contract_no, ns);
+
+            for (function_no, other) in ns.functions.iter().enumerate() {
+                if functions.contains(&function_no)
+                    || other.name != func.name
+                    || other.signature != func.signature
+                {
+                    continue;
+                }
+
+                if let Some(other_contract_no) = other.contract_no {
+                    if bases.contains(&other_contract_no)
+                        || visit_bases(other_contract_no, ns).contains(&contract_no)
+                    {
+                        functions.push(function_no);
+                    }
+                }
+            }
+        }
+
+        i += 1;
+    }
+
+    let mut kinds = vec![kind];
+
+    for function_no in functions {
+        let func = &ns.functions[function_no];
+
+        let mut add = |kind| {
+            if !kinds.contains(&kind) {
+                kinds.push(kind);
+            }
+        };
+
+        add(SymbolKind::Function(function_no));
+
+        if func.is_accessor {
+            let contract_no = func.contract_no.unwrap();
+
+            if let Some(var_no) = ns.contracts[contract_no]
+                .variables
+                .iter()
+                .position(|var| var.name == func.name)
+            {
+                add(SymbolKind::StorageVariable(contract_no, var_no));
+            }
+        }
+    }
+
+    kinds
+}
+
+struct Builder<'a> {
+    ns: &'a Namespace,
+    files: &'a [Arc<str>],
+    /// The function whose body is being walked; local variables are numbered per function
+    function_no: usize,
+    definitions: HashMap<SymbolKind, pt::Loc>,
+    declarations: HashMap<SymbolKind, pt::Loc>,
+    references: Vec<(pt::Loc, SymbolKind)>,
+}
+
+impl<'a> Builder<'a> {
+    /// Find the name as a whole identifier within the location. If `last` is set, the last
+    /// occurrence before any arguments is used, so that `a.b.name(x)` resolves to the member.
+    fn find_name(&self, loc: pt::Loc, name: &str, last: bool) -> Option<pt::Loc> {
+        if name.is_empty() {
+            return None;
+        }
+
+        let text = self.files.get(loc.0)?.get(loc.1..loc.2)?;
+
+        let find = |region: &str| {
+            let is_ident = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '$';
+
+            let mut matches = region.match_indices(name).filter(|(offset, _)| {
+                !region[..*offset].ends_with(is_ident)
+                    && !region[offset + name.len()..].starts_with(is_ident)
+            });
+
+            let found = if last { matches.last() } else { matches.next() };
+
+            found.map(|(offset, _)| offset)
+        };
+
+        text.find('(')
+            .and_then(|end| find(&text[..end]))
+            .or_else(|| find(text))
+            .map(|offset| pt::Loc(loc.0, loc.1 + offset, loc.1 + offset + name.len()))
+    }
+
+    /// Record the declaration of a symbol. The location may cover the whole declaration, in
+    /// which case the name is searched for.
+    fn declaration(&mut self, kind: SymbolKind, loc: pt::Loc, name: &str) {
+        self.definitions.insert(kind, loc);
+
+        let name_loc = if loc.2 - loc.1 == name.len() {
+            Some(loc)
+        } else {
+            self.find_name(loc, name, false)
+        };
+
+        if let Some(name_loc) = name_loc {
+            self.declarations.insert(kind, name_loc);
+            self.references.push((name_loc, kind));
+        }
+    }
+
+    /// Record a use of a symbol, named somewhere within the location
+    fn reference(&mut self, kind: SymbolKind, loc: pt::Loc) {
+        let ns = self.ns;
+
+        let name = match kind {
+            SymbolKind::Variable(function_no, var_no) => {
+                &ns.functions[function_no].symtable.vars[&var_no].id.name
+            }
+            SymbolKind::StorageVariable(contract_no, var_no) => {
+                &ns.contracts[contract_no].variables[var_no].name
+            }
+            SymbolKind::Constant(var_no) => &ns.constants[var_no].name,
+            SymbolKind::Function(function_no) => &ns.functions[function_no].name,
+            SymbolKind::Struct(n) => &ns.structs[n].name,
+            SymbolKind::StructField(n, field_no) => &ns.structs[n].fields[field_no].name,
+            SymbolKind::Enum(n) => &ns.enums[n].name,
+            SymbolKind::EnumValue(n, value) => {
+                match ns.enums[n].values.iter().find(|(_, (_, v))| *v == value) {
+                    Some((name, _)) => name,
+                    None => return,
+                }
+            }
+            SymbolKind::Event(n) => &ns.events[n].name,
+            SymbolKind::Contract(n) => &ns.contracts[n].name,
+            SymbolKind::File(_) => {
+                self.references.push((loc, kind));
+                return;
+            }
+        };
+
+        if let Some(name_loc) = self.find_name(loc, name, true) {
+            self.references.push((name_loc, kind));
+        }
+    }
+
+    /// Walk over all the declarations in the namespace
+    fn declarations(&mut self) {
+        let ns = self.ns;
+
+        for file_no in 0..ns.files.len() {
+            self.definitions
+                .insert(SymbolKind::File(file_no), pt::Loc(file_no, 0, 0));
+        }
+
+        for (n, decl) in ns.structs.iter().enumerate() {
+            self.declaration(SymbolKind::Struct(n), decl.loc, &decl.name);
+
+            for (field_no, field) in decl.fields.iter().enumerate() {
+                if let Some(name_loc) = field.name_loc {
+                    self.declaration(SymbolKind::StructField(n, field_no), name_loc, &field.name);
+                }
+            }
+        }
+
+        for (n, decl) in ns.enums.iter().enumerate() {
+            self.declaration(SymbolKind::Enum(n), decl.loc, &decl.name);
+
+            for (name, (loc, value)) in &decl.values {
+                self.declaration(SymbolKind::EnumValue(n, *value), *loc, name);
+            }
+        }
+
+        for (n, decl) in ns.events.iter().enumerate() {
+            self.declaration(SymbolKind::Event(n), decl.loc, &decl.name);
+        }
+
+        for (n, contract) in ns.contracts.iter().enumerate() {
+            self.declaration(SymbolKind::Contract(n), contract.loc, &contract.name);
+
+            for (var_no, var) in contract.variables.iter().enumerate() {
+                self.variable(SymbolKind::StorageVariable(n, var_no), var);
+            }
+        }
+
+        for (var_no, var) in ns.constants.iter().enumerate() {
+            self.variable(SymbolKind::Constant(var_no), var);
+        }
+
+        for (function_no, func) in ns.functions.iter().enumerate() {
+            if func.is_accessor {
+                // the name of the accessor is the name of the storage variable
+                self.definitions
+                    .insert(SymbolKind::Function(function_no), func.loc);
+            } else {
+                self.declaration(SymbolKind::Function(function_no), func.loc, &func.name);
+            }
+
+            for (var_no, var) in &func.symtable.vars {
+                self.declaration(
+                    SymbolKind::Variable(function_no, *var_no),
+                    var.id.loc,
+                    &var.id.name,
+                );
+            }
+        }
+    }
+
+    /// Storage variables and constants only record the location of the entire declaration,
+    /// so the name is found via the symbol table
+    fn variable(&mut self, kind: SymbolKind, var: &Variable) {
+        let (contract_no, var_no) = match kind {
+            SymbolKind::StorageVariable(contract_no, var_no) => (Some(contract_no), var_no),
+            SymbolKind::Constant(var_no) => (None, var_no),
+            _ => unreachable!(),
+        };
+
+        let name_loc = self
+            .ns
+            .variable_symbols
+            .values()
+            .find_map(|symbol| match symbol {
+                Symbol::Variable(loc, c, v)
+                    if *c == contract_no && *v == var_no && loc.0 == var.loc.0 =>
+                {
+                    Some(*loc)
+                }
+                _ => None,
+            });
+
+        self.definitions.insert(kind, var.loc);
+
+        if let Some(loc) = name_loc.or_else(|| self.find_name(var.loc, &var.name, false)) {
+            self.declarations.insert(kind, loc);
+            self.references.push((loc, kind));
+        }
+    }
+
+    /// Walk over all the function bodies and initializers
+    fn uses(&mut self) {
+        let ns = self.ns;
+
+        for (function_no, func) in ns.functions.iter().enumerate() {
+            self.function_no = function_no;
+
+            for modifier in &func.modifiers {
+                modifier.recurse(self, expression);
+            }
+
+            for (_, _, args) in func.bases.values() {
+                for arg in args {
+                    arg.recurse(self, expression);
+                }
+            }
+
+            for stmt in &func.body {
+                stmt.recurse(self, statement);
+            }
+        }
+
+        for var in ns
+            .contracts
+            .iter()
+            .flat_map(|contract| contract.variables.iter())
+            .chain(ns.constants.iter())
+        {
+            if let Some(init) = &var.initializer {
+                init.recurse(self, expression);
+            }
+        }
+
+        for (loc, symbol) in &ns.name_references {
+            let kind = match symbol {
+                Symbol::Enum(_, n) => SymbolKind::Enum(*n),
+                Symbol::Struct(_, n) => SymbolKind::Struct(*n),
+                Symbol::Contract(_, n) => SymbolKind::Contract(*n),
+                Symbol::Import(_, file_no) => SymbolKind::File(*file_no),
+                _ => continue,
+            };
+
+            self.references.push((*loc, kind));
+        }
+    }
+}
+
+fn statement(stmt: &Statement, builder: &mut Builder) -> bool {
+    match stmt {
+        Statement::VariableDecl(_, _, _, init) => {
+            if let Some(init) = init {
+                init.recurse(builder, expression);
+            }
+        }
+        Statement::If(_, _, expr, _, _)
+        | Statement::While(_, _, expr, _)
+        | Statement::DoWhile(_, _, _, expr)
+        | Statement::Expression(_, _, expr)
+        | Statement::Delete(_, _, expr) => expr.recurse(builder, expression),
+        Statement::For { cond, .. } => {
+            if let Some(cond) = cond {
+                cond.recurse(builder, expression);
+            }
+        }
+        Statement::Destructure(_, fields, expr) => {
+            for field in fields {
+                if let DestructureField::Expression(expr) = field {
+                    expr.recurse(builder, expression);
+                }
+            }
+
+            expr.recurse(builder, expression);
+        }
+        Statement::Return(_, exprs) => {
+            for expr in exprs {
+                expr.recurse(builder, expression);
+            }
+        }
+        Statement::Emit {
+            event_no,
+            event_loc,
+            args,
+            ..
+        } => {
+            builder.reference(SymbolKind::Event(*event_no), *event_loc);
+
+            for arg in args {
+                arg.recurse(builder, expression);
+            }
+        }
+        Statement::TryCatch { expr, .. } => expr.recurse(builder, expression),
+        _ => (),
+    }
+
+    true
+}
+
+fn expression(expr: &Expression, builder: &mut Builder) -> bool {
+    match expr {
+        Expression::Variable(loc, _, var_no) => {
+            let kind = SymbolKind::Variable(builder.function_no, *var_no);
+
+            builder.references.push((*loc, kind));
+        }
+        Expression::StorageVariable(loc, _, contract_no, var_no)
+        | Expression::ConstantVariable(loc, _, Some(contract_no), var_no) => {
+            builder.reference(SymbolKind::StorageVariable(*contract_no, *var_no), *loc);
+        }
+        Expression::ConstantVariable(loc, _, None, var_no) => {
+            builder.reference(SymbolKind::Constant(*var_no), *loc);
+        }
+        Expression::InternalFunction {
+            loc, function_no, ..
+        }
+        | Expression::ExternalFunction {
+            loc, function_no, ..
+        } => {
+            builder.reference(SymbolKind::Function(*function_no), *loc);
+        }
+        Expression::StructMember(loc, _, expr, field_no) => {
+            if let Type::Struct(n) = expr.ty().deref_any() {
+                builder.reference(SymbolKind::StructField(*n, *field_no), *loc);
+            }
+        }
+        Expression::StructLiteral(loc, Type::Struct(n), _) => {
+            builder.reference(SymbolKind::Struct(*n), *loc);
+        }
+        Expression::NumberLiteral(loc, Type::Enum(n), value) => {
+            builder.reference(SymbolKind::Enum(*n), *loc);
+
+            if let Some(value) = value.to_usize() {
+                builder.reference(SymbolKind::EnumValue(*n, value), *loc);
+            }
+        }
+        Expression::Constructor {
+            loc, contract_no, ..
+        } => {
+            builder.reference(SymbolKind::Contract(*contract_no), *loc);
+        }
+        _ => (),
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod test {
+    use super::{related, SymbolIndex, SymbolKind};
+    use crate::languageserver::test::resolve;
+
+    const SRC: &str = r#"
+contract base {
+    struct S {
+        uint64 f;
+    }
+
+    uint64 public count;
+
+    event Counted(uint64 n);
+
+    function inc(uint64 by) public virtual returns (uint64) {
+        S memory s = S({ f: by });
+        count += s.f;
+        emit Counted(count);
+        return count;
+    }
+}
+
+contract child is base {
+    function inc(uint64 by) public override returns (uint64) {
+        return by + count;
+    }
+}
+"#;
+
+    /// Offsets of all the occurrences of the text
+    fn offsets(text: &str) -> Vec<usize> {
+        SRC.match_indices(text).map(|(offset, _)| offset).collect()
+    }
+
+    #[test]
+    fn definitions_and_references() {
+        let (ns, contents) = resolve(SRC);
+        let index = SymbolIndex::new(&ns, &contents);
+
+        // count in `count += s.f`
+        let count = index.symbol_at(0, offsets("count +=")[0] + 2).unwrap();
+
+        assert_eq!(count, SymbolKind::StorageVariable(0, 0));
+
+        let definition = index.definition(count).unwrap();
+
+        assert_eq!(&SRC[definition.1..definition.2], "uint64 public count");
+
+        let mut references: Vec<usize> = index
+            .references(count, true)
+            .iter()
+            .map(|loc| loc.1)
+            .collect();
+
+        references.sort_unstable();
+
+        assert_eq!(references, offsets("count"));
+
+        // without the declaration
+        assert_eq!(index.references(count, false).len(), references.len() - 1);
+
+        // the local variable and the struct field in `s.f`
+        let s_dot_f = offsets("s.f")[0];
+
+        let s = index.symbol_at(0, s_dot_f).unwrap();
+
+        assert!(matches!(s, SymbolKind::Variable(_, _)));
+        assert_eq!(
+            index
+                .references(s, true)
+                .iter()
+                .map(|loc| loc.1)
+                .collect::<Vec<usize>>(),
+            vec![offsets("s =")[0], s_dot_f]
+        );
+
+        let f = index.symbol_at(0, s_dot_f + 2).unwrap();
+
+        assert_eq!(f, SymbolKind::StructField(0, 0));
+        assert_eq!(index.declared_at(index.definition(f).unwrap()), Some(f));
+
+        let event = index.symbol_at(0, offsets("Counted(count)")[0]).unwrap();
+
+        assert_eq!(event, SymbolKind::Event(0));
+        assert_eq!(index.references(event, true).len(), 2);
+
+        // nothing at the keyword
+        assert_eq!(index.symbol_at(0, offsets("emit")[0] + 1), None);
+    }
+
+    #[test]
+    fn related_symbols() {
+        let (ns, _) = resolve(SRC);
+
+        let inc = |contract_no| {
+            ns.functions
+                .iter()
+                .position(|func| func.name == "inc" && func.contract_no == Some(contract_no))
+                .unwrap()
+        };
+
+        // an override and the function it overrides are renamed together
+        let kinds = related(&ns, SymbolKind::Function(inc(1)));
+
+        assert_eq!(
+            kinds,
+            vec![SymbolKind::Function(inc(1)), SymbolKind::Function(inc(0))]
+        );
+
+        // a public state variable and its accessor
+        let accessor = ns
+            .functions
+            .iter()
+            .position(|func| func.is_accessor && func.name == "count")
+            .unwrap();
+
+        assert_eq!(
+            related(&ns, SymbolKind::StorageVariable(0, 0)),
+            vec![
+                SymbolKind::StorageVariable(0, 0),
+                SymbolKind::Function(accessor)
+            ]
+        );
+
+        assert_eq!(
+            related(&ns, SymbolKind::Event(0)),
+            vec![SymbolKind::Event(0)]
+        );
+    }
+}
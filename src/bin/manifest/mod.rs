@@ -0,0 +1,324 @@
+use super::{add_remapping, add_search_paths, compile, parse_opt, parse_target, standard_json};
+use clap::ArgMatches;
+use serde::Deserialize;
+use solang::codegen::Options;
+use solang::file_cache::FileCache;
+use std::path::{Path, PathBuf};
+
+const MANIFEST: &str = "solang.toml";
+
+/// The project manifest, `solang.toml`. Paths are relative to the directory of the manifest.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Manifest {
+    /// Globs which match the Solidity files to compile, e.g. `contracts/**/*.sol`
+    sources: Vec<String>,
+    #[serde(default)]
+    import_paths: Vec<PathBuf>,
+    #[serde(default)]
+    remappings: Vec<String>,
+    /// The targets to build, each in a `[[target]]` table
+    #[serde(rename = "target")]
+    targets: Vec<TargetConfig>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct TargetConfig {
+    name: String,
+    /// The output directory; the default is the directory of the manifest
+    output: Option<PathBuf>,
+    /// The llvm optimization level
+    opt: Option<String>,
+    #[serde(default)]
+    math_overflow: bool,
+    constant_folding: Option<bool>,
+    strength_reduce: Option<bool>,
+    dead_storage: Option<bool>,
+    vector_to_slice: Option<bool>,
+}
+
+impl TargetConfig {
+    fn new(name: &str) -> Self {
+        TargetConfig {
+            name: name.to_owned(),
+            output: None,
+            opt: None,
+            math_overflow: false,
+            constant_folding: None,
+            strength_reduce: None,
+            dead_storage: None,
+            vector_to_slice: None,
+        }
+    }
+}
+
+/// Find the manifest in the directory or one of its parents
+fn find(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors()
+        .map(|dir| dir.join(MANIFEST))
+        .find(|path| path.is_file())
+}
+
+impl Manifest {
+    fn load(path: &Path) -> Result<Self, String> {
+        let contents = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+
+        let manifest: Manifest = toml::from_str(&contents).map_err(|e| e.to_string())?;
+
+        if manifest.targets.is_empty() {
+            return Err("no targets configured".to_owned());
+        }
+
+        for config in &manifest.targets {
+            if parse_target(&config.name).is_none() {
+                return Err(format!("unknown target ‘{}’", config.name));
+            }
+
+            if let Some(opt) = &config.opt {
+                if parse_opt(opt).is_none() {
+                    return Err(format!(
+                        "target ‘{}’: unknown optimization level ‘{}’",
+                        config.name, opt
+                    ));
+                }
+            }
+        }
+
+        Ok(manifest)
+    }
+
+    /// Expand the source globs, in sorted order
+    fn sources(&self, root: &Path) -> Result<Vec<String>, String> {
+        let mut sources = Vec::new();
+
+        for pattern in &self.sources {
+            let full_pattern = root.join(pattern);
+
+            let paths = glob::glob(&full_pattern.to_string_lossy())
+                .map_err(|e| format!("source ‘{}’: {}", pattern, e))?;
+
+            let before = sources.len();
+
+            for path in paths {
+                let path = path.map_err(|e| e.to_string())?;
+
+                sources.push(path.to_string_lossy().to_string());
+            }
+
+            if sources.len() == before {
+                return Err(format!("source ‘{}’ does not match any files", pattern));
+            }
+        }
+
+        sources.sort();
+        sources.dedup();
+
+        Ok(sources)
+    }
+
+    /// The targets to build. The `--target` command line option selects one of the
+    /// configured targets, or builds another target with the default settings.
+    fn targets(&self, matches: &ArgMatches) -> Vec<TargetConfig> {
+        if matches.occurrences_of("TARGET") == 0 {
+            return self.targets.clone();
+        }
+
+        let name = matches.value_of("TARGET").unwrap();
+
+        let targets: Vec<TargetConfig> = self
+            .targets
+            .iter()
+            .filter(|config| config.name == name)
+            .cloned()
+            .collect();
+
+        if targets.is_empty() {
+            vec![TargetConfig::new(name)]
+        } else {
+            targets
+        }
+    }
+}
+
+/// The output directory of each target. With more than one target, `--output` gets a
+/// subdirectory for each target, named after the last component of its configured output
+/// directory, or else after the target. Targets must not share an output directory, since
+/// they would overwrite each other's files.
+fn output_dirs(
+    targets: &[TargetConfig],
+    root: &Path,
+    matches: &ArgMatches,
+) -> Result<Vec<PathBuf>, String> {
+    let mut dirs: Vec<PathBuf> = Vec::new();
+
+    for config in targets {
+        let dir = match matches.value_of("OUTPUT") {
+            Some(output) if targets.len() > 1 => {
+                let subdir = config
+                    .output
+                    .as_ref()
+                    .and_then(|output| output.file_name())
+                    .map_or_else(|| PathBuf::from(&config.name), PathBuf::from);
+
+                Path::new(output).join(subdir)
+            }
+            Some(output) => PathBuf::from(output),
+            None => match &config.output {
+                Some(output) => root.join(output),
+                None => root.to_path_buf(),
+            },
+        };
+
+        if let Some(other) = dirs.iter().position(|other| *other == dir) {
+            return Err(format!(
+                "targets ‘{}’ and ‘{}’ are both written to ‘{}’; give each target its own output",
+                targets[other].name,
+                config.name,
+                dir.display()
+            ));
+        }
+
+        dirs.push(dir);
+    }
+
+    Ok(dirs)
+}
+
+/// Build every target configured in the manifest. Command line options override the
+/// settings in the manifest.
+pub fn build(matches: &ArgMatches) {
+    if matches.is_present("DOC") || matches.is_present("FIX") {
+        eprintln!("error: --doc and --fix require input files");
+        std::process::exit(1);
+    }
+
+    let manifest_path = match std::env::current_dir().ok().and_then(|dir| find(&dir)) {
+        Some(path) => path,
+        None => {
+            eprintln!(
+                "error: no input files, and no {} found in the current directory or its parents",
+                MANIFEST
+            );
+            std::process::exit(1);
+        }
+    };
+
+    let manifest = match Manifest::load(&manifest_path) {
+        Ok(manifest) => manifest,
+        Err(message) => {
+            eprintln!("{}: error: {}", manifest_path.display(), message);
+            std::process::exit(1);
+        }
+    };
+
+    let root = manifest_path.parent().unwrap();
+
+    let sources = match manifest.sources(root) {
+        Ok(sources) => sources,
+        Err(message) => {
+            eprintln!("{}: error: {}", manifest_path.display(), message);
+            std::process::exit(1);
+        }
+    };
+
+    let filenames: Vec<&str> = sources.iter().map(String::as_str).collect();
+    let targets = manifest.targets(matches);
+    let verbose = matches.is_present("VERBOSE");
+
+    let output_dirs = match output_dirs(&targets, root, matches) {
+        Ok(dirs) => dirs,
+        Err(message) => {
+            eprintln!("{}: error: {}", manifest_path.display(), message);
+            std::process::exit(1);
+        }
+    };
+
+    for (config, output) in targets.iter().zip(output_dirs) {
+        // the target names were checked when the manifest was loaded
+        let target = parse_target(&config.name).unwrap();
+
+        let mut cache = FileCache::new();
+
+        for source in &sources {
+            if let Ok(path) = PathBuf::from(source).canonicalize() {
+                cache.add_import_path(path.parent().unwrap().to_path_buf());
+            }
+        }
+
+        if let Ok(root) = root.canonicalize() {
+            cache.add_import_path(root);
+        }
+
+        for import_path in &manifest.import_paths {
+            match root.join(import_path).canonicalize() {
+                Ok(path) => cache.add_import_path(path),
+                Err(e) => {
+                    eprintln!(
+                        "{}: error: import path ‘{}’: {}",
+                        manifest_path.display(),
+                        import_path.display(),
+                        e
+                    );
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        for remapping in &manifest.remappings {
+            add_remapping(&mut cache, remapping);
+        }
+
+        // the import paths and remappings on the command line come after those in the manifest
+        add_search_paths(&mut cache, matches);
+
+        let opt_level = if matches.occurrences_of("OPT") > 0 {
+            matches.value_of("OPT").unwrap()
+        } else {
+            config.opt.as_deref().unwrap_or("default")
+        };
+
+        let llvm_opt = parse_opt(opt_level).unwrap();
+
+        let opt = Options {
+            dead_storage: !matches.is_present("DEADSTORAGE") && config.dead_storage.unwrap_or(true),
+            strength_reduce: !matches.is_present("STRENGTHREDUCE")
+                && config.strength_reduce.unwrap_or(true),
+            constant_folding: !matches.is_present("CONSTANTFOLDING")
+                && config.constant_folding.unwrap_or(true),
+            vector_to_slice: !matches.is_present("VECTORTOSLICE")
+                && config.vector_to_slice.unwrap_or(true),
+        };
+
+        let math_overflow_check = matches.is_present("MATHOVERFLOW") || config.math_overflow;
+
+        if let Err(e) = std::fs::create_dir_all(&output) {
+            eprintln!(
+                "error: cannot create output directory ‘{}’: {}",
+                output.display(),
+                e
+            );
+            std::process::exit(1);
+        }
+
+        if verbose {
+            eprintln!(
+                "info: Building target {} in {}",
+                config.name,
+                output.display()
+            );
+        }
+
+        compile(
+            &filenames,
+            &mut cache,
+            target,
+            matches,
+            &output,
+            &mut standard_json::Output::default(),
+            math_overflow_check,
+            &opt,
+            llvm_opt,
+        );
+    }
+}
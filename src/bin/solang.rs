@@ -1,35 +1,24 @@
-use clap::{App, Arg, ArgMatches};
+use clap::{App, Arg, ArgMatches, SubCommand};
 use itertools::Itertools;
-use serde::Serialize;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::prelude::*;
 use std::path::{Path, PathBuf};
 
 use solang::abi;
 use solang::codegen::{codegen, Options};
-use solang::file_cache::FileCache;
-use solang::sema::{ast::Namespace, diagnostics};
+use solang::file_cache::{FileCache, Remapping};
+use solang::sema::ast_json::{ast_json, NodeIds};
+use solang::sema::{ast::Namespace, codes, diagnostics};
 
 mod doc;
+mod format;
 mod languageserver;
+mod manifest;
+mod standard_json;
+mod upgrade;
 
-#[derive(Serialize)]
-pub struct EwasmContract {
-    pub wasm: String,
-}
-
-#[derive(Serialize)]
-pub struct JsonContract {
-    abi: Vec<abi::ethereum::ABI>,
-    ewasm: EwasmContract,
-}
-
-#[derive(Serialize)]
-pub struct JsonResult {
-    pub errors: Vec<diagnostics::OutputJson>,
-    pub contracts: HashMap<String, HashMap<String, JsonContract>>,
-}
+use standard_json::{Code, ContractOutput};
 
 fn main() {
     let matches = App::new("solang")
@@ -38,8 +27,7 @@ fn main() {
         .about(env!("CARGO_PKG_DESCRIPTION"))
         .arg(
             Arg::with_name("INPUT")
-                .help("Solidity input files")
-                .required(true)
+                .help("Solidity input files; without input files, the project in solang.toml is built")
                 .conflicts_with("LANGUAGESERVER")
                 .multiple(true),
         )
@@ -48,7 +36,17 @@ fn main() {
                 .help("Emit compiler state at early stage")
                 .long("emit")
                 .takes_value(true)
-                .possible_values(&["ast", "cfg", "llvm-ir", "llvm-bc", "object"]),
+                .possible_values(&[
+                    "ast",
+                    "ast-json",
+                    "cfg",
+                    "llvm-ir",
+                    "llvm-bc",
+                    "object",
+                    "c-header",
+                    "storage-layout",
+                ])
+                .global(true),
         )
         .arg(
             Arg::with_name("OPT")
@@ -56,33 +54,64 @@ fn main() {
                 .short("O")
                 .takes_value(true)
                 .possible_values(&["none", "less", "default", "aggressive"])
-                .default_value("default"),
+                .default_value("default")
+                .global(true),
         )
         .arg(
             Arg::with_name("TARGET")
                 .help("Target to build for")
                 .long("target")
                 .takes_value(true)
-                .possible_values(&["substrate", "ewasm", "sabre", "generic", "solana"])
-                .default_value("substrate"),
+                .possible_values(&["substrate", "ewasm", "sabre", "generic", "solana", "evm"])
+                .default_value("substrate")
+                .global(true),
         )
         .arg(
             Arg::with_name("STD-JSON")
-                .help("mimic solidity json output on stdout")
+                .help("mimic solidity json output on stdout; without input files, read solidity json input from stdin")
                 .long("standard-json"),
         )
         .arg(
             Arg::with_name("VERBOSE")
                 .help("show debug messages")
                 .short("v")
-                .long("verbose"),
+                .long("verbose")
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("MESSAGEFORMAT")
+                .help("Print diagnostics with source snippets, or one line each for tools")
+                .long("message-format")
+                .takes_value(true)
+                .possible_values(&["human", "plain"])
+                .default_value("human")
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("WARNINGS")
+                .help("Control warnings: no-<warning> disables, error makes all warnings errors, error=<warning> makes it an error. A warning is a code like S5004, a category, or all")
+                .short("W")
+                .takes_value(true)
+                .value_name("WARNING")
+                .multiple(true)
+                .number_of_values(1)
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("EXPLAIN")
+                .help("Explain the diagnostic with the given code, e.g. S5001")
+                .long("explain")
+                .takes_value(true)
+                .value_name("CODE")
+                .conflicts_with_all(&["INPUT", "LANGUAGESERVER", "STD-JSON"]),
         )
         .arg(
             Arg::with_name("OUTPUT")
                 .help("output directory")
                 .short("o")
                 .long("output")
-                .takes_value(true),
+                .takes_value(true)
+                .global(true),
         )
         .arg(
             Arg::with_name("IMPORTPATH")
@@ -90,37 +119,59 @@ fn main() {
                 .short("I")
                 .long("importpath")
                 .takes_value(true)
-                .multiple(true),
+                .multiple(true)
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("REMAPPING")
+                .help("Remap imports which start with prefix to target, optionally only in files within context")
+                .long("remapping")
+                .value_name("[context:]prefix=target")
+                .takes_value(true)
+                .multiple(true)
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("REMAPPINGSFILE")
+                .help("File with an import remapping on each line")
+                .long("remappings-file")
+                .takes_value(true)
+                .global(true),
         )
         .arg(
             Arg::with_name("CONSTANTFOLDING")
                 .help("Disable constant folding codegen optimization")
                 .long("no-constant-folding")
-                .display_order(1),
+                .display_order(1)
+                .global(true),
         )
         .arg(
             Arg::with_name("STRENGTHREDUCE")
                 .help("Disable strength reduce codegen optimization")
                 .long("no-strength-reduce")
-                .display_order(2),
+                .display_order(2)
+                .global(true),
         )
         .arg(
             Arg::with_name("DEADSTORAGE")
                 .help("Disable dead storage codegen optimization")
                 .long("no-dead-storage")
-                .display_order(3),
+                .display_order(3)
+                .global(true),
         )
         .arg(
             Arg::with_name("VECTORTOSLICE")
                 .help("Disable vector to slice codegen optimization")
                 .long("no-vector-to-slice")
-                .display_order(4),
+                .display_order(4)
+                .global(true),
         )
         .arg(
             Arg::with_name("MATHOVERFLOW")
                 .help("Enable math overflow checking")
                 .long("math-overflow")
-                .display_order(5),
+                .display_order(5)
+                .global(true),
         )
         .arg(
             Arg::with_name("LANGUAGESERVER")
@@ -133,26 +184,120 @@ fn main() {
                 .help("Generate documention for contracts using doc comments")
                 .long("doc"),
         )
+        .arg(
+            Arg::with_name("FIX")
+                .help("Apply the suggested fixes for diagnostics to the source files")
+                .conflicts_with_all(&["STD-JSON", "EMIT", "DOC"])
+                .long("fix"),
+        )
+        .subcommand(
+            SubCommand::with_name("build")
+                .about("Build the project described by solang.toml in this directory or a parent"),
+        )
+        .subcommand(
+            SubCommand::with_name("check-upgrade")
+                .about("Check that the storage layout of contracts is compatible with a previous version")
+                .arg(
+                    Arg::with_name("PREVIOUS")
+                        .help("Source file or directory of the previous version, or its storage layout saved with --emit storage-layout")
+                        .long("previous")
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("CONTRACT")
+                        .help("Only check this contract")
+                        .long("contract")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("INPUT")
+                        .help("Solidity input files of the new version")
+                        .required(true)
+                        .multiple(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("fmt")
+                .about("Format Solidity source files in place")
+                .arg(
+                    Arg::with_name("CHECK")
+                        .help("Do not change the files, fail if any file is not formatted")
+                        .long("check"),
+                )
+                .arg(
+                    Arg::with_name("INDENT")
+                        .help("Number of spaces for each level of indentation")
+                        .long("indent")
+                        .takes_value(true)
+                        .default_value("4"),
+                )
+                .arg(
+                    Arg::with_name("TABS")
+                        .help("Indent with tabs rather than spaces")
+                        .long("tabs"),
+                )
+                .arg(
+                    Arg::with_name("LINEWIDTH")
+                        .help("Break lines which are longer than this, where possible")
+                        .long("line-width")
+                        .takes_value(true)
+                        .default_value("80"),
+                )
+                .arg(
+                    Arg::with_name("INPUT")
+                        .help("Solidity input files")
+                        .required(true)
+                        .multiple(true),
+                ),
+        )
         .get_matches();
 
-    let target = match matches.value_of("TARGET") {
-        Some("substrate") => solang::Target::Substrate,
-        Some("ewasm") => solang::Target::Ewasm,
-        Some("sabre") => solang::Target::Sabre,
-        Some("generic") => solang::Target::Generic,
-        Some("solana") => solang::Target::Solana,
-        _ => unreachable!(),
-    };
+    if let Some(code) = matches.value_of("EXPLAIN") {
+        match codes::explain(code) {
+            Some(explanation) => {
+                println!(
+                    "{}: {}\n\n{}",
+                    explanation.code, explanation.summary, explanation.description
+                );
+                return;
+            }
+            None => {
+                eprintln!("error: ‘{}’ is not a diagnostic code", code);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let target = parse_target(matches.value_of("TARGET").unwrap()).unwrap();
 
     if matches.is_present("LANGUAGESERVER") {
         languageserver::start_server(target);
     }
 
+    // without input files, build the project described by the manifest
+    if let Some(matches) = matches.subcommand_matches("build") {
+        manifest::build(matches);
+        return;
+    }
+
+    if let Some(matches) = matches.subcommand_matches("check-upgrade") {
+        upgrade::check(matches);
+        return;
+    }
+
+    if let Some(matches) = matches.subcommand_matches("fmt") {
+        format::format_files(matches);
+        return;
+    }
+
+    if !matches.is_present("INPUT") && !matches.is_present("STD-JSON") {
+        manifest::build(&matches);
+        return;
+    }
+
     let verbose = matches.is_present("VERBOSE");
-    let mut json = JsonResult {
-        errors: Vec::new(),
-        contracts: HashMap::new(),
-    };
+    let mut json = standard_json::Output::default();
 
     if verbose {
         eprintln!("info: Solang version {}", env!("GIT_HASH"));
@@ -160,6 +305,20 @@ fn main() {
 
     let math_overflow_check = matches.is_present("MATHOVERFLOW");
 
+    let llvm_opt = parse_opt(matches.value_of("OPT").unwrap()).unwrap();
+
+    let opt = Options {
+        dead_storage: !matches.is_present("DEADSTORAGE"),
+        strength_reduce: !matches.is_present("STRENGTHREDUCE"),
+        constant_folding: !matches.is_present("CONSTANTFOLDING"),
+        vector_to_slice: !matches.is_present("VECTORTOSLICE"),
+    };
+
+    if matches.is_present("STD-JSON") && !matches.is_present("INPUT") {
+        standard_json::compile(target, &opt, llvm_opt, math_overflow_check);
+        return;
+    }
+
     let mut cache = FileCache::new();
 
     for filename in matches.values_of("INPUT").unwrap() {
@@ -179,18 +338,7 @@ fn main() {
         }
     }
 
-    if let Some(paths) = matches.values_of("IMPORTPATH") {
-        for p in paths {
-            let path = PathBuf::from(p);
-            match path.canonicalize() {
-                Ok(p) => cache.add_import_path(p),
-                Err(e) => {
-                    eprintln!("error: import path ‘{}’: {}", p, e.to_string());
-                    std::process::exit(1);
-                }
-            }
-        }
-    }
+    add_search_paths(&mut cache, &matches);
 
     if matches.is_present("DOC") {
         let verbose = matches.is_present("VERBOSE");
@@ -198,9 +346,11 @@ fn main() {
         let mut files = Vec::new();
 
         for filename in matches.values_of("INPUT").unwrap() {
-            let ns = solang::parse_and_resolve(filename, &mut cache, target);
+            let mut ns = solang::parse_and_resolve(filename, &mut cache, target);
 
-            diagnostics::print_messages(&mut cache, &ns, verbose);
+            warning_control(&matches).apply(&mut ns);
+
+            print_messages(&mut cache, &ns, &matches);
 
             if ns.contracts.is_empty() {
                 eprintln!("{}: error: no contracts found", filename);
@@ -216,102 +366,232 @@ fn main() {
             // generate docs
             doc::generate_docs(matches.value_of("OUTPUT").unwrap_or("."), &files, verbose);
         }
+    } else if matches.is_present("FIX") {
+        for filename in matches.values_of("INPUT").unwrap() {
+            let mut ns = solang::parse_and_resolve(filename, &mut cache, target);
+
+            warning_control(&matches).apply(&mut ns);
+
+            print_messages(&mut cache, &ns, &matches);
+
+            for (path, contents) in diagnostics::apply_fixes(&mut cache, &ns) {
+                if let Err(e) = std::fs::write(&path, contents) {
+                    eprintln!("error: cannot write ‘{}’: {}", path.display(), e);
+                    std::process::exit(1);
+                }
+
+                eprintln!("info: applied fixes to ‘{}’", path.display());
+            }
+        }
     } else {
-        let llvm_opt = match matches.value_of("OPT").unwrap() {
-            "none" => inkwell::OptimizationLevel::None,
-            "less" => inkwell::OptimizationLevel::Less,
-            "default" => inkwell::OptimizationLevel::Default,
-            "aggressive" => inkwell::OptimizationLevel::Aggressive,
-            _ => unreachable!(),
-        };
+        let filenames: Vec<&str> = matches.values_of("INPUT").unwrap().collect();
+        let output = Path::new(matches.value_of("OUTPUT").unwrap_or("."));
+
+        compile(
+            &filenames,
+            &mut cache,
+            target,
+            &matches,
+            output,
+            &mut json,
+            math_overflow_check,
+            &opt,
+            llvm_opt,
+        );
 
-        let opt = Options {
-            dead_storage: !matches.is_present("DEADSTORAGE"),
-            strength_reduce: !matches.is_present("STRENGTHREDUCE"),
-            constant_folding: !matches.is_present("CONSTANTFOLDING"),
-            vector_to_slice: !matches.is_present("VECTORTOSLICE"),
+        if matches.is_present("STD-JSON") {
+            println!("{}", serde_json::to_string(&json).unwrap());
+        }
+    }
+}
+
+/// Print the diagnostics in the format selected on the command line
+fn print_messages(cache: &mut FileCache, ns: &Namespace, matches: &ArgMatches) {
+    let format = match matches.value_of("MESSAGEFORMAT") {
+        Some("plain") => diagnostics::MessageFormat::Plain,
+        _ => diagnostics::MessageFormat::Human,
+    };
+
+    diagnostics::print_messages_with_format(cache, ns, matches.is_present("VERBOSE"), format);
+}
+
+/// The warning options from the command line
+fn warning_control(matches: &ArgMatches) -> diagnostics::WarningControl {
+    let mut control = diagnostics::WarningControl::default();
+
+    if let Some(options) = matches.values_of("WARNINGS") {
+        for option in options {
+            if let Err(message) = control.add(option) {
+                eprintln!("error: -W {}: {}", option, message);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    control
+}
+
+/// Add the import paths and remappings from the command line
+fn add_search_paths(cache: &mut FileCache, matches: &ArgMatches) {
+    if let Some(paths) = matches.values_of("IMPORTPATH") {
+        for p in paths {
+            let path = PathBuf::from(p);
+            match path.canonicalize() {
+                Ok(p) => cache.add_import_path(p),
+                Err(e) => {
+                    eprintln!("error: import path ‘{}’: {}", p, e.to_string());
+                    std::process::exit(1);
+                }
+            }
+        }
+    }
+
+    add_remappings(cache, matches);
+}
+
+/// Add the remappings from the remappings file and the command line
+fn add_remappings(cache: &mut FileCache, matches: &ArgMatches) {
+    if let Some(filename) = matches.value_of("REMAPPINGSFILE") {
+        let contents = match std::fs::read_to_string(filename) {
+            Ok(contents) => contents,
+            Err(e) => {
+                eprintln!("error: remappings file ‘{}’: {}", filename, e.to_string());
+                std::process::exit(1);
+            }
         };
 
-        let mut namespaces = Vec::new();
+        for line in contents.lines().map(str::trim) {
+            if !line.is_empty() && !line.starts_with('#') {
+                add_remapping(cache, line);
+            }
+        }
+    }
 
-        for filename in matches.values_of("INPUT").unwrap() {
-            namespaces.push(process_filename(
-                filename,
-                &mut cache,
-                target,
-                &matches,
-                &mut json,
-                math_overflow_check,
-                &opt,
-                llvm_opt,
-            ));
+    // remappings on the command line are added last, so they win over the file
+    if let Some(remappings) = matches.values_of("REMAPPING") {
+        for remapping in remappings {
+            add_remapping(cache, remapping);
         }
+    }
+}
 
-        if target == solang::Target::Solana {
-            let context = inkwell::context::Context::create();
-
-            let binary = solang::compile_many(
-                &context,
-                &namespaces,
-                "bundle.sol",
-                llvm_opt,
-                math_overflow_check,
-            );
+fn parse_target(name: &str) -> Option<solang::Target> {
+    match name {
+        "substrate" => Some(solang::Target::Substrate),
+        "ewasm" => Some(solang::Target::Ewasm),
+        "sabre" => Some(solang::Target::Sabre),
+        "generic" => Some(solang::Target::Generic),
+        "solana" => Some(solang::Target::Solana),
+        "evm" => Some(solang::Target::Evm),
+        _ => None,
+    }
+}
 
-            if !save_intermediates(&binary, &matches) {
-                let bin_filename = output_file(&matches, "bundle", target.file_extension());
+fn parse_opt(level: &str) -> Option<inkwell::OptimizationLevel> {
+    match level {
+        "none" => Some(inkwell::OptimizationLevel::None),
+        "less" => Some(inkwell::OptimizationLevel::Less),
+        "default" => Some(inkwell::OptimizationLevel::Default),
+        "aggressive" => Some(inkwell::OptimizationLevel::Aggressive),
+        _ => None,
+    }
+}
 
-                if matches.is_present("VERBOSE") {
-                    eprintln!(
-                        "info: Saving binary {} for contracts: {}",
-                        bin_filename.display(),
-                        namespaces
-                            .iter()
-                            .flat_map(|ns| ns
-                                .contracts
-                                .iter()
-                                .map(|contract| contract.name.as_str()))
-                            .join(", "),
-                    );
-                }
+fn add_remapping(cache: &mut FileCache, remapping: &str) {
+    match remapping.parse::<Remapping>() {
+        Ok(remapping) => cache.add_remapping(remapping),
+        Err(message) => {
+            eprintln!("error: {}", message);
+            std::process::exit(1);
+        }
+    }
+}
 
-                let code = binary.code(true).expect("llvm code emit should work");
+/// Compile the files for the target, and write the output files to the directory
+fn compile(
+    filenames: &[&str],
+    cache: &mut FileCache,
+    target: solang::Target,
+    matches: &ArgMatches,
+    output: &Path,
+    json: &mut standard_json::Output,
+    math_overflow_check: bool,
+    opt: &Options,
+    llvm_opt: inkwell::OptimizationLevel,
+) {
+    let verbose = matches.is_present("VERBOSE");
+    let mut namespaces = Vec::new();
+
+    for filename in filenames {
+        namespaces.push(process_filename(
+            filename,
+            cache,
+            target,
+            matches,
+            output,
+            json,
+            math_overflow_check,
+            opt,
+            llvm_opt,
+        ));
+    }
 
-                let mut file = File::create(bin_filename).unwrap();
-                file.write_all(&code).unwrap();
+    if target == solang::Target::Solana {
+        let context = inkwell::context::Context::create();
+
+        let binary = solang::compile_many(
+            &context,
+            &namespaces,
+            "bundle.sol",
+            llvm_opt,
+            math_overflow_check,
+        );
+
+        if !save_intermediates(&binary, matches, output) {
+            let bin_filename = output_file(output, "bundle", target.file_extension());
 
-                // Write all ABI files
-                for ns in &namespaces {
-                    for contract_no in 0..ns.contracts.len() {
-                        let contract = &ns.contracts[contract_no];
-
-                        let (abi_bytes, abi_ext) =
-                            abi::generate_abi(contract_no, &ns, &code, verbose);
-                        let abi_filename = output_file(&matches, &contract.name, abi_ext);
-
-                        if verbose {
-                            eprintln!(
-                                "info: Saving ABI {} for contract {}",
-                                abi_filename.display(),
-                                contract.name
-                            );
-                        }
-
-                        let mut file = File::create(abi_filename).unwrap();
-                        file.write_all(&abi_bytes.as_bytes()).unwrap();
+            if matches.is_present("VERBOSE") {
+                eprintln!(
+                    "info: Saving binary {} for contracts: {}",
+                    bin_filename.display(),
+                    namespaces
+                        .iter()
+                        .flat_map(|ns| ns.contracts.iter().map(|contract| contract.name.as_str()))
+                        .join(", "),
+                );
+            }
+
+            let code = binary.code(true).expect("llvm code emit should work");
+
+            let mut file = File::create(bin_filename).unwrap();
+            file.write_all(&code).unwrap();
+
+            // Write all ABI files
+            for ns in &namespaces {
+                for contract_no in 0..ns.contracts.len() {
+                    let contract = &ns.contracts[contract_no];
+
+                    let (abi_bytes, abi_ext) = abi::generate_abi(contract_no, &ns, &code, verbose);
+                    let abi_filename = output_file(output, &contract.name, abi_ext);
+
+                    if verbose {
+                        eprintln!(
+                            "info: Saving ABI {} for contract {}",
+                            abi_filename.display(),
+                            contract.name
+                        );
                     }
+
+                    let mut file = File::create(abi_filename).unwrap();
+                    file.write_all(&abi_bytes.as_bytes()).unwrap();
                 }
             }
         }
-
-        if matches.is_present("STD-JSON") {
-            println!("{}", serde_json::to_string(&json).unwrap());
-        }
     }
 }
 
-fn output_file(matches: &ArgMatches, stem: &str, ext: &str) -> PathBuf {
-    Path::new(matches.value_of("OUTPUT").unwrap_or(".")).join(format!("{}.{}", stem, ext))
+fn output_file(output: &Path, stem: &str, ext: &str) -> PathBuf {
+    output.join(format!("{}.{}", stem, ext))
 }
 
 fn process_filename(
@@ -319,14 +599,15 @@ fn process_filename(
     cache: &mut FileCache,
     target: solang::Target,
     matches: &ArgMatches,
-    json: &mut JsonResult,
+    output: &Path,
+    json: &mut standard_json::Output,
     math_overflow_check: bool,
     opt: &Options,
     llvm_opt: inkwell::OptimizationLevel,
 ) -> Namespace {
     let verbose = matches.is_present("VERBOSE");
 
-    let mut json_contracts = HashMap::new();
+    let mut json_contracts = BTreeMap::new();
 
     // resolve phase
     let mut ns = solang::parse_and_resolve(filename, cache, target);
@@ -336,11 +617,13 @@ fn process_filename(
         codegen(contract_no, &mut ns, &opt);
     }
 
+    warning_control(matches).apply(&mut ns);
+
     if matches.is_present("STD-JSON") {
         let mut out = diagnostics::message_as_json(cache, &ns);
         json.errors.append(&mut out);
     } else {
-        diagnostics::print_messages(cache, &ns, verbose);
+        print_messages(cache, &ns, matches);
     }
 
     if ns.contracts.is_empty() || diagnostics::any_errors(&ns.diagnostics) {
@@ -353,6 +636,26 @@ fn process_filename(
         return ns;
     }
 
+    if let Some("ast-json") = matches.value_of("EMIT") {
+        let stem = Path::new(filename).file_stem().unwrap().to_string_lossy();
+        let ast_filename = output_file(output, &stem, "ast.json");
+
+        if verbose {
+            eprintln!(
+                "info: Saving AST {} for {}",
+                ast_filename.display(),
+                filename
+            );
+        }
+
+        let ast = ast_json(0, 0, &ns, &mut NodeIds::default());
+
+        let mut file = File::create(ast_filename).unwrap();
+        file.write_all(serde_json::to_string_pretty(&ast).unwrap().as_bytes())
+            .unwrap();
+        return ns;
+    }
+
     // emit phase
     for contract_no in 0..ns.contracts.len() {
         let resolved_contract = &ns.contracts[contract_no];
@@ -366,6 +669,47 @@ fn process_filename(
             continue;
         }
 
+        if let Some("c-header") = matches.value_of("EMIT") {
+            if target != solang::Target::Generic {
+                eprintln!("error: a C header can only be generated for the generic target");
+                std::process::exit(1);
+            }
+
+            let header_filename = output_file(output, &resolved_contract.name, "h");
+
+            if verbose {
+                eprintln!(
+                    "info: Saving C header {} for contract {}",
+                    header_filename.display(),
+                    resolved_contract.name
+                );
+            }
+
+            let mut file = File::create(header_filename).unwrap();
+            file.write_all(resolved_contract.c_header(&ns).as_bytes())
+                .unwrap();
+            continue;
+        }
+
+        if let Some("storage-layout") = matches.value_of("EMIT") {
+            let layout_filename = output_file(output, &resolved_contract.name, "storage.json");
+
+            if verbose {
+                eprintln!(
+                    "info: Saving storage layout {} for contract {}",
+                    layout_filename.display(),
+                    resolved_contract.name
+                );
+            }
+
+            let layout = abi::storage_layout::storage_layout(contract_no, &ns);
+
+            let mut file = File::create(layout_filename).unwrap();
+            file.write_all(serde_json::to_string_pretty(&layout).unwrap().as_bytes())
+                .unwrap();
+            continue;
+        }
+
         if target == solang::Target::Solana {
             if verbose {
                 eprintln!(
@@ -377,6 +721,67 @@ fn process_filename(
             return ns;
         }
 
+        if target == solang::Target::Evm {
+            if verbose {
+                eprintln!(
+                    "info: Generating EVM bytecode for contract {}",
+                    resolved_contract.name
+                );
+            }
+
+            let code = match solang::emit::evm::build(contract_no, &ns, math_overflow_check) {
+                Ok(code) => code,
+                Err(errors) => {
+                    let mut errors = errors;
+                    ns.diagnostics.append(&mut errors);
+                    print_messages(cache, &ns, matches);
+                    std::process::exit(1);
+                }
+            };
+
+            let name = &ns.contracts[contract_no].name;
+
+            if matches.is_present("STD-JSON") {
+                json_contracts.insert(
+                    name.to_owned(),
+                    ContractOutput {
+                        abi: Some(abi::ethereum::gen_abi(contract_no, &ns)),
+                        code: Some(Code::new(target, &code)),
+                        ..Default::default()
+                    },
+                );
+            } else {
+                let bin_filename = output_file(output, name, target.file_extension());
+
+                if verbose {
+                    eprintln!(
+                        "info: Saving binary {} for contract {}",
+                        bin_filename.display(),
+                        name
+                    );
+                }
+
+                let mut file = File::create(bin_filename).unwrap();
+                file.write_all(hex::encode(&code).as_bytes()).unwrap();
+
+                let (abi_bytes, abi_ext) = abi::generate_abi(contract_no, &ns, &code, verbose);
+                let abi_filename = output_file(output, name, abi_ext);
+
+                if verbose {
+                    eprintln!(
+                        "info: Saving ABI {} for contract {}",
+                        abi_filename.display(),
+                        name
+                    );
+                }
+
+                let mut file = File::create(abi_filename).unwrap();
+                file.write_all(&abi_bytes.as_bytes()).unwrap();
+            }
+
+            continue;
+        }
+
         if verbose {
             eprintln!(
                 "info: Generating LLVM IR for contract {} with target {}",
@@ -389,7 +794,7 @@ fn process_filename(
         let binary =
             resolved_contract.emit(&ns, &context, &filename, llvm_opt, math_overflow_check);
 
-        if save_intermediates(&binary, matches) {
+        if save_intermediates(&binary, matches, output) {
             continue;
         }
 
@@ -404,11 +809,10 @@ fn process_filename(
         if matches.is_present("STD-JSON") {
             json_contracts.insert(
                 binary.name.to_owned(),
-                JsonContract {
-                    abi: abi::ethereum::gen_abi(contract_no, &ns),
-                    ewasm: EwasmContract {
-                        wasm: hex::encode_upper(code),
-                    },
+                ContractOutput {
+                    abi: Some(abi::ethereum::gen_abi(contract_no, &ns)),
+                    code: Some(Code::new(target, &code)),
+                    ..Default::default()
                 },
             );
         } else {
@@ -416,7 +820,7 @@ fn process_filename(
             if target == solang::Target::Substrate {
                 let (contract_bs, contract_ext) =
                     abi::generate_abi(contract_no, &ns, &code, verbose);
-                let contract_filename = output_file(matches, &binary.name, contract_ext);
+                let contract_filename = output_file(output, &binary.name, contract_ext);
 
                 if verbose {
                     eprintln!(
@@ -429,7 +833,7 @@ fn process_filename(
                 let mut file = File::create(contract_filename).unwrap();
                 file.write_all(&contract_bs.as_bytes()).unwrap();
             } else {
-                let bin_filename = output_file(matches, &binary.name, target.file_extension());
+                let bin_filename = output_file(output, &binary.name, target.file_extension());
 
                 if verbose {
                     eprintln!(
@@ -444,7 +848,7 @@ fn process_filename(
 
                 if target != solang::Target::Solana {
                     let (abi_bytes, abi_ext) = abi::generate_abi(contract_no, &ns, &code, verbose);
-                    let abi_filename = output_file(matches, &binary.name, abi_ext);
+                    let abi_filename = output_file(output, &binary.name, abi_ext);
 
                     if verbose {
                         eprintln!(
@@ -466,14 +870,14 @@ fn process_filename(
     ns
 }
 
-fn save_intermediates(binary: &solang::emit::Binary, matches: &ArgMatches) -> bool {
+fn save_intermediates(binary: &solang::emit::Binary, matches: &ArgMatches, output: &Path) -> bool {
     let verbose = matches.is_present("VERBOSE");
 
     if let Some("llvm-ir") = matches.value_of("EMIT") {
         if let Some(runtime) = &binary.runtime {
             // In Ethereum, an ewasm contract has two parts, deployer and runtime. The deployer code returns the runtime wasm
             // as a byte string
-            let llvm_filename = output_file(matches, &format!("{}_deploy", binary.name), "ll");
+            let llvm_filename = output_file(output, &format!("{}_deploy", binary.name), "ll");
 
             if verbose {
                 eprintln!(
@@ -485,7 +889,7 @@ fn save_intermediates(binary: &solang::emit::Binary, matches: &ArgMatches) -> bo
 
             binary.dump_llvm(&llvm_filename).unwrap();
 
-            let llvm_filename = output_file(matches, &format!("{}_runtime", binary.name), "ll");
+            let llvm_filename = output_file(output, &format!("{}_runtime", binary.name), "ll");
 
             if verbose {
                 eprintln!(
@@ -497,7 +901,7 @@ fn save_intermediates(binary: &solang::emit::Binary, matches: &ArgMatches) -> bo
 
             runtime.dump_llvm(&llvm_filename).unwrap();
         } else {
-            let llvm_filename = output_file(matches, &binary.name, "ll");
+            let llvm_filename = output_file(output, &binary.name, "ll");
 
             if verbose {
                 eprintln!(
@@ -516,7 +920,7 @@ fn save_intermediates(binary: &solang::emit::Binary, matches: &ArgMatches) -> bo
         // In Ethereum, an ewasm contract has two parts, deployer and runtime. The deployer code returns the runtime wasm
         // as a byte string
         if let Some(runtime) = &binary.runtime {
-            let bc_filename = output_file(matches, &format!("{}_deploy", binary.name), "bc");
+            let bc_filename = output_file(output, &format!("{}_deploy", binary.name), "bc");
 
             if verbose {
                 eprintln!(
@@ -528,7 +932,7 @@ fn save_intermediates(binary: &solang::emit::Binary, matches: &ArgMatches) -> bo
 
             binary.bitcode(&bc_filename);
 
-            let bc_filename = output_file(matches, &format!("{}_runtime", binary.name), "bc");
+            let bc_filename = output_file(output, &format!("{}_runtime", binary.name), "bc");
 
             if verbose {
                 eprintln!(
@@ -540,7 +944,7 @@ fn save_intermediates(binary: &solang::emit::Binary, matches: &ArgMatches) -> bo
 
             runtime.bitcode(&bc_filename);
         } else {
-            let bc_filename = output_file(matches, &binary.name, "bc");
+            let bc_filename = output_file(output, &binary.name, "bc");
 
             if verbose {
                 eprintln!(
@@ -564,7 +968,7 @@ fn save_intermediates(binary: &solang::emit::Binary, matches: &ArgMatches) -> bo
             }
         };
 
-        let obj_filename = output_file(matches, &binary.name, "o");
+        let obj_filename = output_file(output, &binary.name, "o");
 
         if verbose {
             eprintln!(
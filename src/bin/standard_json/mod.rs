@@ -0,0 +1,601 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use solang::abi;
+use solang::abi::storage_layout::{storage_layout, StorageLayout};
+use solang::codegen::{codegen, Options};
+use solang::file_cache::{FileCache, Remapping};
+use solang::parser::pt;
+use solang::sema::ast::{Level, Namespace, Parameter, Tag};
+use solang::sema::ast_json::{ast_json, NodeIds};
+use solang::sema::diagnostics::{self, OutputJson};
+use solang::Target;
+use std::collections::{BTreeMap, HashMap};
+use std::io::Read;
+use tiny_keccak::{Hasher, Keccak};
+
+/// A standard JSON input document, as build tools send it to solc
+#[derive(Deserialize)]
+pub struct Input {
+    #[serde(default)]
+    language: Option<String>,
+    sources: BTreeMap<String, Source>,
+    #[serde(default)]
+    settings: Settings,
+}
+
+/// A source, either inline or read from the first of its urls which can be read
+#[derive(Deserialize)]
+struct Source {
+    content: Option<String>,
+    #[serde(default)]
+    urls: Vec<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+struct Settings {
+    remappings: Vec<String>,
+    optimizer: Option<Optimizer>,
+    /// The outputs requested for each file and contract; `*` matches any file or contract
+    output_selection: HashMap<String, HashMap<String, Vec<String>>>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct Optimizer {
+    enabled: Option<bool>,
+}
+
+/// The standard JSON output. This is also printed when `--standard-json` is given with
+/// input files on the command line.
+#[derive(Serialize, Default)]
+pub struct Output {
+    pub errors: Vec<OutputJson>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub sources: BTreeMap<String, SourceOutput>,
+    pub contracts: BTreeMap<String, BTreeMap<String, ContractOutput>>,
+}
+
+#[derive(Serialize)]
+pub struct SourceOutput {
+    pub id: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ast: Option<Value>,
+}
+
+#[derive(Serialize, Default)]
+pub struct ContractOutput {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub abi: Option<Vec<abi::ethereum::ABI>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub devdoc: Option<DevDoc>,
+    #[serde(rename = "storageLayout", skip_serializing_if = "Option::is_none")]
+    pub storage_layout: Option<StorageLayout>,
+    #[serde(flatten)]
+    pub code: Option<Code>,
+}
+
+/// The generated code, in a section named after the target
+#[derive(Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Code {
+    Evm {
+        bytecode: Bytecode,
+    },
+    Ewasm {
+        wasm: String,
+    },
+    Substrate {
+        wasm: String,
+    },
+    Sabre {
+        wasm: String,
+    },
+    Generic {
+        wasm: String,
+    },
+    /// On Solana, all contracts are compiled into one program
+    Solana {
+        bundle: String,
+    },
+}
+
+#[derive(Serialize)]
+pub struct Bytecode {
+    pub object: String,
+}
+
+impl Code {
+    pub fn new(target: Target, code: &[u8]) -> Self {
+        match target {
+            Target::Evm => Code::Evm {
+                bytecode: Bytecode {
+                    object: hex::encode(code),
+                },
+            },
+            Target::Ewasm => Code::Ewasm {
+                wasm: hex::encode_upper(code),
+            },
+            Target::Substrate => Code::Substrate {
+                wasm: hex::encode(code),
+            },
+            Target::Sabre => Code::Sabre {
+                wasm: hex::encode(code),
+            },
+            Target::Generic => Code::Generic {
+                wasm: hex::encode(code),
+            },
+            Target::Solana => Code::Solana {
+                bundle: hex::encode(code),
+            },
+        }
+    }
+
+    /// The name of the output selection for the code of the target
+    fn selection(target: Target) -> &'static str {
+        match target {
+            Target::Evm => "evm.bytecode.object",
+            Target::Ewasm => "ewasm.wasm",
+            Target::Substrate => "substrate.wasm",
+            Target::Sabre => "sabre.wasm",
+            Target::Generic => "generic.wasm",
+            Target::Solana => "solana.bundle",
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct DevDoc {
+    kind: &'static str,
+    version: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    author: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    details: Option<String>,
+    methods: BTreeMap<String, MethodDoc>,
+}
+
+#[derive(Serialize)]
+struct MethodDoc {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    details: Option<String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    params: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    returns: BTreeMap<String, String>,
+}
+
+/// Read a standard JSON input document from stdin, compile it and print the standard
+/// JSON output on stdout
+pub fn compile(
+    target: Target,
+    opt: &Options,
+    llvm_opt: inkwell::OptimizationLevel,
+    math_overflow_check: bool,
+) {
+    let mut input = String::new();
+    let mut output = Output::default();
+
+    if let Err(e) = std::io::stdin().read_to_string(&mut input) {
+        output
+            .errors
+            .push(input_error("IOError", format!("cannot read stdin: {}", e)));
+    } else {
+        match serde_json::from_str::<Input>(&input) {
+            Ok(input) => compile_input(
+                &input,
+                target,
+                opt,
+                llvm_opt,
+                math_overflow_check,
+                &mut output,
+            ),
+            Err(e) => output.errors.push(input_error("JSONError", e.to_string())),
+        }
+    }
+
+    println!("{}", serde_json::to_string(&output).unwrap());
+}
+
+fn compile_input(
+    input: &Input,
+    target: Target,
+    opt: &Options,
+    llvm_opt: inkwell::OptimizationLevel,
+    math_overflow_check: bool,
+    output: &mut Output,
+) {
+    if let Some(language) = &input.language {
+        if language != "Solidity" {
+            output.errors.push(input_error(
+                "JSONError",
+                format!("only Solidity is supported, not ‘{}’", language),
+            ));
+            return;
+        }
+    }
+
+    // the sources are looked up by name, so no import paths are used
+    let mut cache = FileCache::new();
+
+    for (name, source) in &input.sources {
+        let content = match &source.content {
+            Some(content) => Some(content.to_owned()),
+            None => source
+                .urls
+                .iter()
+                .find_map(|url| std::fs::read_to_string(url).ok()),
+        };
+
+        match content {
+            Some(content) => cache.set_file_contents(name, content),
+            None => {
+                output.errors.push(input_error(
+                    "IOError",
+                    format!("cannot read source ‘{}’", name),
+                ));
+                return;
+            }
+        }
+    }
+
+    for remapping in &input.settings.remappings {
+        match remapping.parse::<Remapping>() {
+            Ok(remapping) => cache.add_remapping(remapping),
+            Err(message) => {
+                output.errors.push(input_error("JSONError", message));
+                return;
+            }
+        }
+    }
+
+    let llvm_opt = match &input.settings.optimizer {
+        Some(Optimizer {
+            enabled: Some(false),
+        }) => inkwell::OptimizationLevel::None,
+        _ => llvm_opt,
+    };
+
+    let code_selection = Code::selection(target);
+
+    let mut namespaces = Vec::new();
+    // node ids are shared by the ASTs of all sources
+    let mut node_ids = NodeIds::default();
+    // contracts for which the Solana bundle was selected
+    let mut bundle_contracts = Vec::new();
+
+    for (id, name) in input.sources.keys().enumerate() {
+        output
+            .sources
+            .insert(name.to_owned(), SourceOutput { id, ast: None });
+
+        let mut ns = solang::parse_and_resolve(name, &mut cache, target);
+
+        for contract_no in 0..ns.contracts.len() {
+            codegen(contract_no, &mut ns, opt);
+        }
+
+        // diagnostics in imported files are reported for the source they are in
+        output.errors.extend(
+            diagnostics::message_as_json(&mut cache, &ns)
+                .into_iter()
+                .filter(|error| match &error.sourceLocation {
+                    Some(loc) => loc.file == *name,
+                    None => true,
+                }),
+        );
+
+        if diagnostics::any_errors(&ns.diagnostics) {
+            continue;
+        }
+
+        // file level outputs are selected with an empty contract name
+        if wants(&input.settings.selected(name, ""), "ast") {
+            if let Some(source) = output.sources.get_mut(name) {
+                source.ast = Some(ast_json(0, id, &ns, &mut node_ids));
+            }
+        }
+
+        let mut contracts = BTreeMap::new();
+
+        for contract_no in 0..ns.contracts.len() {
+            let contract = &ns.contracts[contract_no];
+
+            // contracts in imported files are listed under their own source
+            if contract.loc.0 != 0 {
+                continue;
+            }
+
+            let selection = input.settings.selected(name, &contract.name);
+
+            if selection.is_empty() {
+                continue;
+            }
+
+            let mut contract_output = ContractOutput::default();
+
+            if wants(&selection, "abi") {
+                contract_output.abi = Some(abi::ethereum::gen_abi(contract_no, &ns));
+            }
+
+            if wants(&selection, "devdoc") {
+                contract_output.devdoc = Some(devdoc(contract_no, &ns));
+            }
+
+            if wants(&selection, "storageLayout") {
+                contract_output.storage_layout = Some(storage_layout(contract_no, &ns));
+            }
+
+            let want_code = wants(&selection, code_selection) && contract.is_concrete();
+            let want_metadata = wants(&selection, "metadata");
+            // the Substrate metadata includes the hash of the code
+            let substrate_metadata =
+                want_metadata && target == Target::Substrate && contract.is_concrete();
+
+            if target == Target::Solana {
+                if want_code {
+                    bundle_contracts.push((name.to_owned(), contract.name.to_owned()));
+                }
+            } else if want_code || substrate_metadata {
+                if let Some(code) = contract_code(
+                    contract_no,
+                    &mut ns,
+                    &mut cache,
+                    llvm_opt,
+                    math_overflow_check,
+                    output,
+                ) {
+                    if substrate_metadata {
+                        contract_output.metadata = Some(
+                            serde_json::to_string(&abi::substrate::metadata(
+                                contract_no,
+                                &code,
+                                &ns,
+                            ))
+                            .unwrap(),
+                        );
+                    }
+
+                    if want_code {
+                        contract_output.code = Some(Code::new(target, &code));
+                    }
+                }
+            }
+
+            if want_metadata && target != Target::Substrate {
+                contract_output.metadata = Some(metadata(contract_no, &ns, &mut cache, input));
+            }
+
+            contracts.insert(ns.contracts[contract_no].name.to_owned(), contract_output);
+        }
+
+        output.contracts.insert(name.to_owned(), contracts);
+
+        namespaces.push(ns);
+    }
+
+    if !bundle_contracts.is_empty() {
+        let context = inkwell::context::Context::create();
+
+        let binary = solang::compile_many(
+            &context,
+            &namespaces,
+            "bundle.sol",
+            llvm_opt,
+            math_overflow_check,
+        );
+
+        match binary.code(true) {
+            Ok(code) => {
+                for (file, contract) in bundle_contracts {
+                    if let Some(contract_output) = output
+                        .contracts
+                        .get_mut(&file)
+                        .and_then(|contracts| contracts.get_mut(&contract))
+                    {
+                        contract_output.code = Some(Code::new(target, &code));
+                    }
+                }
+            }
+            Err(s) => output.errors.push(input_error("CodegenError", s)),
+        }
+    }
+}
+
+/// Generate the code for a contract. Errors are added to the output.
+fn contract_code(
+    contract_no: usize,
+    ns: &mut Namespace,
+    cache: &mut FileCache,
+    llvm_opt: inkwell::OptimizationLevel,
+    math_overflow_check: bool,
+    output: &mut Output,
+) -> Option<Vec<u8>> {
+    if ns.target == Target::Evm {
+        return match solang::emit::evm::build(contract_no, ns, math_overflow_check) {
+            Ok(code) => Some(code),
+            Err(mut errors) => {
+                let before = ns.diagnostics.len();
+
+                ns.diagnostics.append(&mut errors);
+
+                output.errors.extend(
+                    diagnostics::message_as_json(cache, ns)
+                        .into_iter()
+                        .skip(before),
+                );
+
+                None
+            }
+        };
+    }
+
+    let context = inkwell::context::Context::create();
+    let contract = &ns.contracts[contract_no];
+    let filename = ns.files[0].to_string_lossy().to_string();
+
+    let binary = contract.emit(ns, &context, &filename, llvm_opt, math_overflow_check);
+
+    match binary.code(true) {
+        Ok(code) => Some(code),
+        Err(s) => {
+            output.errors.push(input_error("CodegenError", s));
+            None
+        }
+    }
+}
+
+impl Settings {
+    /// All the outputs selected for the contract
+    fn selected(&self, file: &str, contract: &str) -> Vec<String> {
+        let mut selection = Vec::new();
+
+        for (file_pattern, contracts) in &self.output_selection {
+            if file_pattern != "*" && file_pattern != file {
+                continue;
+            }
+
+            for (contract_pattern, outputs) in contracts {
+                if (contract_pattern == "*" && !contract.is_empty()) || contract_pattern == contract
+                {
+                    selection.extend(outputs.iter().cloned());
+                }
+            }
+        }
+
+        selection
+    }
+}
+
+/// Is the output selected, either by name, by one of its parents like `evm` for
+/// `evm.bytecode.object`, or by `*`
+fn wants(selection: &[String], output: &str) -> bool {
+    selection
+        .iter()
+        .any(|s| s == "*" || s == output || output.starts_with(&format!("{}.", s)))
+}
+
+/// An error about the input document itself, which has no location
+fn input_error(ty: &str, message: String) -> OutputJson {
+    OutputJson {
+        sourceLocation: None,
+        ty: ty.to_owned(),
+        component: "general".to_owned(),
+        severity: Level::Error.to_string().to_owned(),
+        errorCode: None,
+        formattedMessage: format!("{}: {}", ty, message),
+        message,
+    }
+}
+
+/// The developer documentation from the `@title`, `@author`, `@dev`, `@param` and `@return`
+/// tags of the contract and its public functions
+fn devdoc(contract_no: usize, ns: &Namespace) -> DevDoc {
+    let contract = &ns.contracts[contract_no];
+
+    let mut methods = BTreeMap::new();
+
+    for function_no in contract.all_functions.keys() {
+        let func = &ns.functions[*function_no];
+
+        if !func.is_public() || !(func.is_constructor() || func.ty == pt::FunctionTy::Function) {
+            continue;
+        }
+
+        let doc = MethodDoc {
+            details: tag(&func.tags, "dev"),
+            params: named_tags(&func.tags, "param", &func.params),
+            returns: named_tags(&func.tags, "return", &func.returns),
+        };
+
+        if doc.details.is_none() && doc.params.is_empty() && doc.returns.is_empty() {
+            continue;
+        }
+
+        let key = if func.is_constructor() {
+            String::from("constructor")
+        } else {
+            func.signature.to_owned()
+        };
+
+        methods.insert(key, doc);
+    }
+
+    DevDoc {
+        kind: "dev",
+        version: 1,
+        title: tag(&contract.tags, "title"),
+        author: tag(&contract.tags, "author"),
+        details: tag(&contract.tags, "dev"),
+        methods,
+    }
+}
+
+fn tag(tags: &[Tag], name: &str) -> Option<String> {
+    tags.iter()
+        .find(|tag| tag.tag == name)
+        .map(|tag| tag.value.to_owned())
+}
+
+/// The tags for parameters or return values by name; unnamed ones are called `_0`, `_1` etc
+fn named_tags(tags: &[Tag], name: &str, params: &[Parameter]) -> BTreeMap<String, String> {
+    tags.iter()
+        .filter(|tag| tag.tag == name)
+        .map(|tag| {
+            let param_name = match params.get(tag.no) {
+                Some(param) if !param.name.is_empty() => param.name.to_owned(),
+                _ => format!("_{}", tag.no),
+            };
+
+            (param_name, tag.value.to_owned())
+        })
+        .collect()
+}
+
+/// The solc style metadata: the compiler, the settings, the hashes of the sources and the
+/// abi and developer documentation
+fn metadata(contract_no: usize, ns: &Namespace, cache: &mut FileCache, input: &Input) -> String {
+    let mut sources = BTreeMap::new();
+
+    for file in &ns.files {
+        let mut hasher = Keccak::v256();
+        let mut hash = [0u8; 32];
+
+        hasher.update(cache.get_file_contents(file).as_bytes());
+        hasher.finalize(&mut hash);
+
+        sources.insert(
+            file.to_string_lossy().to_string(),
+            json!({ "keccak256": format!("0x{}", hex::encode(hash)) }),
+        );
+    }
+
+    let mut compilation_target = BTreeMap::new();
+
+    compilation_target.insert(
+        ns.files[0].to_string_lossy().to_string(),
+        ns.contracts[contract_no].name.to_owned(),
+    );
+
+    let metadata: Value = json!({
+        "compiler": { "version": env!("GIT_HASH") },
+        "language": "Solidity",
+        "output": {
+            "abi": abi::ethereum::gen_abi(contract_no, ns),
+            "devdoc": devdoc(contract_no, ns),
+        },
+        "settings": {
+            "compilationTarget": compilation_target,
+            "remappings": input.settings.remappings,
+            "target": ns.target.to_string(),
+        },
+        "sources": sources,
+        "version": 1,
+    });
+
+    metadata.to_string()
+}
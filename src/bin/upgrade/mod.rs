@@ -0,0 +1,176 @@
+use super::{add_remappings, parse_target, print_messages, warning_control};
+use clap::ArgMatches;
+use solang::abi::storage_layout::{check_upgrade, storage_layout, StorageLayout};
+use solang::file_cache::FileCache;
+use solang::sema::diagnostics;
+use std::path::Path;
+
+/// Check that the storage layout of the contracts in the input files is compatible with the
+/// previous version, which is either a source file, a source tree with the input files at
+/// the same relative paths, or a storage layout saved with `--emit storage-layout`
+pub fn check(matches: &ArgMatches) {
+    let target = parse_target(matches.value_of("TARGET").unwrap()).unwrap();
+    let previous = Path::new(matches.value_of("PREVIOUS").unwrap());
+    let only_contract = matches.value_of("CONTRACT");
+    let mut success = true;
+
+    for filename in matches.values_of("INPUT").unwrap() {
+        let mut cache = FileCache::new();
+
+        add_import_paths(&mut cache, Path::new(filename), Path::new("."), matches);
+
+        let mut ns = solang::parse_and_resolve(filename, &mut cache, target);
+
+        if diagnostics::any_errors(&ns.diagnostics) {
+            print_messages(&mut cache, &ns, matches);
+            success = false;
+            continue;
+        }
+
+        let layouts = match previous_layouts(previous, filename, only_contract, matches, target) {
+            Some(layouts) => layouts,
+            None => {
+                success = false;
+                continue;
+            }
+        };
+
+        for (name, layout) in layouts {
+            if only_contract.map_or(false, |only| only != name) {
+                continue;
+            }
+
+            match ns.contracts.iter().position(|c| c.name == name) {
+                Some(contract_no) => check_upgrade(&layout, contract_no, &mut ns),
+                None => {
+                    eprintln!(
+                        "{}: error: contract ‘{}’ of the previous version not found",
+                        filename, name
+                    );
+                    success = false;
+                }
+            }
+        }
+
+        warning_control(matches).apply(&mut ns);
+
+        print_messages(&mut cache, &ns, matches);
+
+        if diagnostics::any_errors(&ns.diagnostics) {
+            success = false;
+        }
+    }
+
+    if !success {
+        std::process::exit(1);
+    }
+}
+
+/// Load or generate the storage layouts of the contracts in the previous version
+fn previous_layouts(
+    previous: &Path,
+    filename: &str,
+    only_contract: Option<&str>,
+    matches: &ArgMatches,
+    target: solang::Target,
+) -> Option<Vec<(String, StorageLayout)>> {
+    if previous.extension().map_or(false, |ext| ext == "json") {
+        let layout = std::fs::read(previous)
+            .map_err(|e| e.to_string())
+            .and_then(|contents| serde_json::from_slice(&contents).map_err(|e| e.to_string()));
+
+        let layout = match layout {
+            Ok(layout) => layout,
+            Err(e) => {
+                eprintln!("error: storage layout ‘{}’: {}", previous.display(), e);
+                return None;
+            }
+        };
+
+        // the file is named after the contract by --emit storage-layout
+        let name = match only_contract {
+            Some(name) => name.to_owned(),
+            None => {
+                let stem = previous.file_stem().unwrap().to_string_lossy();
+
+                stem.trim_end_matches(".storage").to_owned()
+            }
+        };
+
+        return Some(vec![(name, layout)]);
+    }
+
+    let (root, previous_file) = if previous.is_dir() {
+        if Path::new(filename).is_absolute() {
+            eprintln!(
+                "error: ‘{}’ must be a relative path to find it in the previous version ‘{}’",
+                filename,
+                previous.display()
+            );
+            return None;
+        }
+
+        (previous.to_path_buf(), previous.join(filename))
+    } else {
+        (
+            previous
+                .parent()
+                .unwrap_or_else(|| Path::new("."))
+                .to_path_buf(),
+            previous.to_path_buf(),
+        )
+    };
+
+    let mut cache = FileCache::new();
+
+    add_import_paths(&mut cache, &previous_file, &root, matches);
+
+    let previous_filename = previous_file.to_string_lossy();
+
+    let ns = solang::parse_and_resolve(&previous_filename, &mut cache, target);
+
+    if diagnostics::any_errors(&ns.diagnostics) {
+        print_messages(&mut cache, &ns, matches);
+        eprintln!(
+            "{}: error: the previous version does not compile",
+            previous_filename
+        );
+        return None;
+    }
+
+    Some(
+        ns.contracts
+            .iter()
+            .enumerate()
+            .filter(|(_, contract)| contract.is_concrete())
+            .map(|(contract_no, contract)| {
+                (contract.name.to_owned(), storage_layout(contract_no, &ns))
+            })
+            .collect(),
+    )
+}
+
+/// Imports are found relative to the file, the root of its source tree and the import
+/// paths, which are relative to the root of the source tree as well
+fn add_import_paths(cache: &mut FileCache, filename: &Path, root: &Path, matches: &ArgMatches) {
+    let mut paths = Vec::new();
+
+    if let Some(parent) = filename.parent() {
+        paths.push(parent.to_path_buf());
+    }
+
+    paths.push(root.to_path_buf());
+
+    if let Some(import_paths) = matches.values_of("IMPORTPATH") {
+        paths.extend(import_paths.map(|path| root.join(path)));
+    }
+
+    for path in paths {
+        // the previous version may not have all the directories
+        if let Ok(path) = path.canonicalize() {
+            cache.add_import_path(path);
+        }
+    }
+
+    add_remappings(cache, matches);
+}
@@ -2,6 +2,7 @@ use super::cfg::{ControlFlowGraph, Instr};
 use super::reaching_definitions;
 use crate::parser::pt::Loc;
 use crate::sema::ast::{Builtin, Diagnostic, Expression, Namespace, StringLocation, Type};
+use crate::sema::codes;
 use num_bigint::{BigInt, Sign};
 use num_traits::{ToPrimitive, Zero};
 use ripemd160::Ripemd160;
@@ -391,10 +392,10 @@ fn expression(
                 (&left.0, &right.0)
             {
                 if right.sign() == Sign::Minus || right >= &BigInt::from(left_expr.ty().bits(ns)) {
-                    ns.diagnostics.push(Diagnostic::error(
-                        *loc,
-                        format!("left shift by {} is not possible", right),
-                    ));
+                    ns.diagnostics.push(
+                        Diagnostic::error(*loc, format!("left shift by {} is not possible", right))
+                            .with_code(codes::OUT_OF_RANGE),
+                    );
                 } else {
                     let right: u64 = right.to_u64().unwrap();
 
@@ -414,10 +415,13 @@ fn expression(
                 (&left.0, &right.0)
             {
                 if right.sign() == Sign::Minus || right >= &BigInt::from(left_expr.ty().bits(ns)) {
-                    ns.diagnostics.push(Diagnostic::error(
-                        *loc,
-                        format!("right shift by {} is not possible", right),
-                    ));
+                    ns.diagnostics.push(
+                        Diagnostic::error(
+                            *loc,
+                            format!("right shift by {} is not possible", right),
+                        )
+                        .with_code(codes::OUT_OF_RANGE),
+                    );
                 } else {
                     let right: u64 = right.to_u64().unwrap();
 
@@ -444,10 +448,10 @@ fn expression(
                 (&left.0, &right.0)
             {
                 if right.sign() == Sign::Minus || right >= &BigInt::from(u32::MAX) {
-                    ns.diagnostics.push(Diagnostic::error(
-                        *loc,
-                        format!("power {} not possible", right),
-                    ));
+                    ns.diagnostics.push(
+                        Diagnostic::error(*loc, format!("power {} not possible", right))
+                            .with_code(codes::OUT_OF_RANGE),
+                    );
                 } else {
                     let right: u32 = right.to_u32().unwrap();
 
@@ -466,8 +470,10 @@ fn expression(
 
             if let Expression::NumberLiteral(_, _, right) = &right.0 {
                 if right.is_zero() {
-                    ns.diagnostics
-                        .push(Diagnostic::error(*loc, String::from("divide by zero")));
+                    ns.diagnostics.push(
+                        Diagnostic::error(*loc, String::from("divide by zero"))
+                            .with_code(codes::DIVIDE_BY_ZERO),
+                    );
                 } else if let Expression::NumberLiteral(_, _, left) = &left.0 {
                     return bigint_to_expression(loc, ty, left.div(right));
                 }
@@ -484,8 +490,10 @@ fn expression(
 
             if let Expression::NumberLiteral(_, _, right) = &right.0 {
                 if right.is_zero() {
-                    ns.diagnostics
-                        .push(Diagnostic::error(*loc, String::from("divide by zero")));
+                    ns.diagnostics.push(
+                        Diagnostic::error(*loc, String::from("divide by zero"))
+                            .with_code(codes::DIVIDE_BY_ZERO),
+                    );
                 } else if let Expression::NumberLiteral(_, _, left) = &left.0 {
                     return bigint_to_expression(loc, ty, left.rem(right));
                 }
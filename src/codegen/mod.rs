@@ -3,6 +3,7 @@ mod constant_folding;
 mod dead_storage;
 mod expression;
 mod external_functions;
+pub mod reachable;
 mod reaching_definitions;
 mod statements;
 mod storage;
@@ -0,0 +1,156 @@
+use super::cfg::{ControlFlowGraph, Instr, InternalCallTy};
+use crate::sema::ast::{Contract, Expression};
+use std::collections::HashSet;
+
+/// Walk the call graph of a contract, starting from the given cfgs. The result is
+/// the set of all cfgs which can be called, directly or via a function pointer,
+/// from any of the roots (including the roots themselves).
+///
+/// This is used to split the deployer code from the runtime code; the deployer only
+/// needs the constructor, the storage initializer and whatever they call.
+pub fn reachable_cfgs(contract: &Contract, roots: &[usize]) -> HashSet<usize> {
+    let mut reachable = HashSet::new();
+    let mut worklist: Vec<usize> = roots.to_vec();
+
+    while let Some(cfg_no) = worklist.pop() {
+        if !reachable.insert(cfg_no) {
+            continue;
+        }
+
+        for callee in callees(&contract.cfg[cfg_no]) {
+            if !reachable.contains(&callee) {
+                worklist.push(callee);
+            }
+        }
+    }
+
+    reachable
+}
+
+/// The cfgs whose address is taken in any of the given cfgs. Such a function pointer may be
+/// written to storage and called by other code later.
+pub fn function_pointers_taken(contract: &Contract, cfgs: &HashSet<usize>) -> HashSet<usize> {
+    let mut taken = HashSet::new();
+
+    for cfg_no in cfgs {
+        for block in &contract.cfg[*cfg_no].blocks {
+            for instr in &block.instr {
+                for expr in instr_expressions(instr) {
+                    expr.recurse(&mut taken, function_pointers);
+                }
+            }
+        }
+    }
+
+    taken
+}
+
+/// Find all the cfgs referenced from the given cfg, either as a static call or as a function
+/// pointer in any expression.
+fn callees(cfg: &ControlFlowGraph) -> HashSet<usize> {
+    let mut callees = HashSet::new();
+
+    for block in &cfg.blocks {
+        for instr in &block.instr {
+            if let Instr::Call {
+                call: InternalCallTy::Static(cfg_no),
+                ..
+            } = instr
+            {
+                callees.insert(*cfg_no);
+            }
+
+            for expr in instr_expressions(instr) {
+                expr.recurse(&mut callees, function_pointers);
+            }
+        }
+    }
+
+    callees
+}
+
+fn function_pointers(expr: &Expression, callees: &mut HashSet<usize>) -> bool {
+    if let Expression::InternalFunctionCfg(cfg_no) = expr {
+        callees.insert(*cfg_no);
+    }
+
+    true
+}
+
+/// All the expressions used by an instruction
+fn instr_expressions(instr: &Instr) -> Vec<&Expression> {
+    match instr {
+        Instr::Set { expr, .. } => vec![expr],
+        Instr::Call { call, args, .. } => {
+            let mut exprs: Vec<&Expression> = args.iter().collect();
+
+            if let InternalCallTy::Dynamic(expr) = call {
+                exprs.push(expr);
+            }
+
+            exprs
+        }
+        Instr::Return { value } => value.iter().collect(),
+        Instr::BranchCond { cond, .. } => vec![cond],
+        Instr::Store { dest, .. } => vec![dest],
+        Instr::AssertFailure { expr: Some(expr) } => vec![expr],
+        Instr::Print { expr } => vec![expr],
+        Instr::LoadStorage { storage, .. }
+        | Instr::ClearStorage { storage, .. }
+        | Instr::PopStorage { storage, .. } => vec![storage],
+        Instr::SetStorage { value, storage, .. } | Instr::PushStorage { value, storage, .. } => {
+            vec![value, storage]
+        }
+        Instr::SetStorageBytes {
+            value,
+            storage,
+            offset,
+        } => vec![value, storage, offset],
+        Instr::PushMemory { value, .. } => vec![value.as_ref()],
+        Instr::Constructor {
+            args,
+            value,
+            gas,
+            salt,
+            ..
+        } => {
+            let mut exprs: Vec<&Expression> = args.iter().collect();
+
+            exprs.push(gas);
+
+            if let Some(value) = value {
+                exprs.push(value);
+            }
+
+            if let Some(salt) = salt {
+                exprs.push(salt);
+            }
+
+            exprs
+        }
+        Instr::ExternalCall {
+            address,
+            payload,
+            value,
+            gas,
+            ..
+        } => {
+            let mut exprs = vec![payload, value, gas];
+
+            if let Some(address) = address {
+                exprs.push(address);
+            }
+
+            exprs
+        }
+        Instr::ValueTransfer { address, value, .. } => vec![address, value],
+        Instr::AbiDecode { data, .. } => vec![data],
+        Instr::SelfDestruct { recipient } => vec![recipient],
+        Instr::EmitEvent { data, topics, .. } => data.iter().chain(topics.iter()).collect(),
+        Instr::AssertFailure { expr: None }
+        | Instr::Branch { .. }
+        | Instr::PopMemory { .. }
+        | Instr::Unreachable
+        | Instr::Nop => Vec::new(),
+    }
+}
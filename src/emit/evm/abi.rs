@@ -0,0 +1,657 @@
+//! Ethereum ABI encoding and decoding. The values being encoded live in frame slots, and the
+//! encoded data is written at the free memory pointer.
+use super::assembler::*;
+use super::expression::is_byte_vector;
+use super::{EvmEmitter, Word, FREE_PTR};
+use crate::parser::pt;
+use crate::sema::ast::{Parameter, Type};
+use num_traits::ToPrimitive;
+
+impl<'a> EvmEmitter<'a> {
+    /// Convert the value on the stack to its ABI encoded word; bytesN is left-aligned
+    pub(super) fn abi_word(&mut self, ty: &Type) {
+        if let Type::Bytes(n) = ty.deref_any() {
+            if *n < 32 {
+                self.asm.push_u64(256 - *n as u64 * 8);
+                self.asm.op(SHL);
+            }
+        }
+    }
+
+    /// Size of the type in the head of the encoding
+    fn abi_head_size(&self, ty: &Type) -> u64 {
+        if ty.is_dynamic(self.ns) {
+            return 32;
+        }
+
+        match ty.deref_any() {
+            Type::Struct(n) => self.ns.structs[*n]
+                .fields
+                .iter()
+                .map(|f| self.abi_head_size(&f.ty))
+                .sum(),
+            Type::Array(_, dims) => {
+                let len = dims.last().unwrap().as_ref().unwrap().to_u64().unwrap();
+
+                len * self.abi_head_size(&ty.deref_any().array_elem())
+            }
+            _ => 32,
+        }
+    }
+
+    /// ABI encode the values in the given frame slots, each with the location of the expression
+    /// it came from. The packed values are encoded first, without any padding, followed by the
+    /// regular ABI encoding of the values.
+    ///
+    /// Returns slots holding the pointer to the encoded data and its length. If `vector` is set,
+    /// the encoded data is preceded by a length word, and the pointer is to the length word.
+    pub(super) fn abi_encode(
+        &mut self,
+        packed: &[(usize, Type, pt::Loc)],
+        values: &[(usize, Type, pt::Loc)],
+        vector: bool,
+    ) -> (usize, usize) {
+        self.asm.push_u64(FREE_PTR);
+        self.asm.op(MLOAD);
+        let start = self.temp();
+
+        let data = if vector {
+            self.push_word(Word::SlotPlus(start, 32));
+            self.temp()
+        } else {
+            start
+        };
+
+        self.load_slot(data);
+        let cursor = self.temp();
+
+        for (value, ty, loc) in packed {
+            if is_byte_vector(ty) {
+                self.load_slot(*value);
+                self.asm.op(MLOAD);
+                let len = self.temp();
+
+                self.memcpy(
+                    Word::Slot(cursor),
+                    Word::SlotPlus(*value, 32),
+                    Word::Slot(len),
+                );
+
+                self.load_slot(cursor);
+                self.load_slot(len);
+                self.asm.op(ADD);
+                self.store_slot(cursor);
+            } else if let Some(bits) = self.value_bits(ty) {
+                // big endian, only the bytes of the type
+                self.load_slot(*value);
+                self.mask(bits);
+                self.asm.push_u64(256 - bits as u64);
+                self.asm.op(SHL);
+                self.load_slot(cursor);
+                self.asm.op(MSTORE);
+
+                self.push_word(Word::SlotPlus(cursor, bits as u64 / 8));
+                self.store_slot(cursor);
+            } else {
+                self.unsupported(*loc, "packed encoding of reference types");
+            }
+        }
+
+        if !values.is_empty() {
+            // the tail is where dynamic data is written
+            let tail = self.new_slot();
+            let head_size: u64 = values.iter().map(|(_, ty, _)| self.abi_head_size(ty)).sum();
+
+            self.push_word(Word::SlotPlus(cursor, head_size));
+            self.store_slot(tail);
+
+            let mut offset = 0;
+
+            for (value, ty, loc) in values {
+                self.push_word(Word::SlotPlus(cursor, offset));
+                let dest = self.temp();
+
+                self.encode_value(*loc, *value, ty, dest, cursor, tail);
+
+                offset += self.abi_head_size(ty);
+            }
+
+            self.load_slot(tail);
+            self.store_slot(cursor);
+        }
+
+        self.load_slot(data);
+        self.load_slot(cursor);
+        self.asm.op(SUB);
+        let len = self.temp();
+
+        if vector {
+            self.load_slot(len);
+            self.load_slot(start);
+            self.asm.op(MSTORE);
+        }
+
+        // round up the free pointer
+        self.push_word(Word::SlotPlus(cursor, 31));
+        self.asm.push_u64(5);
+        self.asm.op(SHR);
+        self.asm.push_u64(5);
+        self.asm.op(SHL);
+        self.asm.push_u64(FREE_PTR);
+        self.asm.op(MSTORE);
+
+        (start, len)
+    }
+
+    /// Encode a value into the head at dest. Dynamic values are written at the tail, and the head
+    /// gets the offset from base.
+    fn encode_value(
+        &mut self,
+        loc: pt::Loc,
+        value: usize,
+        ty: &Type,
+        dest: usize,
+        base: usize,
+        tail: usize,
+    ) {
+        if ty.is_dynamic(self.ns) {
+            self.load_slot(base);
+            self.load_slot(tail);
+            self.asm.op(SUB);
+            self.load_slot(dest);
+            self.asm.op(MSTORE);
+
+            self.encode_dynamic(loc, value, ty, tail);
+        } else {
+            self.encode_static(loc, value, ty, dest);
+        }
+    }
+
+    /// Encode a value which is not dynamic at dest
+    fn encode_static(&mut self, loc: pt::Loc, value: usize, ty: &Type, dest: usize) {
+        let ns = self.ns;
+
+        match ty.deref_any() {
+            Type::Struct(n) => {
+                let mut offset = 0;
+
+                for (i, field) in ns.structs[*n].fields.iter().enumerate() {
+                    self.push_word(Word::SlotPlus(value, i as u64 * 32));
+                    self.asm.op(MLOAD);
+                    let field_value = self.temp();
+
+                    self.push_word(Word::SlotPlus(dest, offset));
+                    let field_dest = self.temp();
+
+                    self.encode_static(loc, field_value, &field.ty, field_dest);
+
+                    offset += self.abi_head_size(&field.ty);
+                }
+            }
+            Type::Array(_, dims) => {
+                let len = dims.last().unwrap().as_ref().unwrap().to_u64().unwrap();
+                let elem_ty = ty.deref_any().array_elem();
+                let elem_size = self.abi_head_size(&elem_ty);
+
+                let counter = self.new_slot();
+
+                self.emit_loop(counter, Word::Const(len), |emitter| {
+                    emitter.load_slot(counter);
+                    emitter.asm.push_u64(32);
+                    emitter.asm.op(MUL);
+                    emitter.load_slot(value);
+                    emitter.asm.op(ADD);
+                    emitter.asm.op(MLOAD);
+                    let elem = emitter.temp();
+
+                    emitter.load_slot(counter);
+                    emitter.asm.push_u64(elem_size);
+                    emitter.asm.op(MUL);
+                    emitter.load_slot(dest);
+                    emitter.asm.op(ADD);
+                    let elem_dest = emitter.temp();
+
+                    emitter.encode_static(loc, elem, &elem_ty, elem_dest);
+                });
+            }
+            Type::InternalFunction { .. } | Type::ExternalFunction { .. } => {
+                self.unsupported(loc, "ABI encoding of function types");
+            }
+            ty => {
+                self.load_slot(value);
+                self.abi_word(ty);
+                self.load_slot(dest);
+                self.asm.op(MSTORE);
+            }
+        }
+    }
+
+    /// Encode a dynamic value at the tail, and advance the tail
+    fn encode_dynamic(&mut self, loc: pt::Loc, value: usize, ty: &Type, tail: usize) {
+        let ns = self.ns;
+
+        match ty.deref_any() {
+            Type::String | Type::DynamicBytes | Type::Slice => {
+                self.load_slot(value);
+                self.asm.op(MLOAD);
+                let len = self.temp();
+
+                self.load_slot(len);
+                self.load_slot(tail);
+                self.asm.op(MSTORE);
+
+                self.memcpy(
+                    Word::SlotPlus(tail, 32),
+                    Word::SlotPlus(value, 32),
+                    Word::Slot(len),
+                );
+
+                // zero the padding
+                self.asm.push_u64(0);
+                self.push_word(Word::SlotPlus(tail, 32));
+                self.load_slot(len);
+                self.asm.op(ADD);
+                self.asm.op(MSTORE);
+
+                self.push_word(Word::SlotPlus(len, 31 + 32));
+                self.asm.push_u64(5);
+                self.asm.op(SHR);
+                self.asm.push_u64(5);
+                self.asm.op(SHL);
+                self.load_slot(tail);
+                self.asm.op(ADD);
+                self.store_slot(tail);
+            }
+            Type::Struct(n) => {
+                let fields = &ns.structs[*n].fields;
+
+                self.load_slot(tail);
+                let base = self.temp();
+
+                let head_size: u64 = fields.iter().map(|f| self.abi_head_size(&f.ty)).sum();
+
+                self.push_word(Word::SlotPlus(base, head_size));
+                self.store_slot(tail);
+
+                let mut offset = 0;
+
+                for (i, field) in fields.iter().enumerate() {
+                    self.push_word(Word::SlotPlus(value, i as u64 * 32));
+                    self.asm.op(MLOAD);
+                    let field_value = self.temp();
+
+                    self.push_word(Word::SlotPlus(base, offset));
+                    let field_dest = self.temp();
+
+                    self.encode_value(loc, field_value, &field.ty, field_dest, base, tail);
+
+                    offset += self.abi_head_size(&field.ty);
+                }
+            }
+            Type::Array(_, dims) => {
+                let elem_ty = ty.deref_any().array_elem();
+                let elem_size = self.abi_head_size(&elem_ty);
+
+                let (len, elems) = match dims.last().unwrap() {
+                    Some(len) => {
+                        self.asm.push_u64(len.to_u64().unwrap());
+                        (self.temp(), Word::Slot(value))
+                    }
+                    None => {
+                        self.load_slot(value);
+                        self.asm.op(MLOAD);
+                        let len = self.temp();
+
+                        self.load_slot(len);
+                        self.load_slot(tail);
+                        self.asm.op(MSTORE);
+
+                        self.push_word(Word::SlotPlus(tail, 32));
+                        self.store_slot(tail);
+
+                        (len, Word::SlotPlus(value, 32))
+                    }
+                };
+
+                self.load_slot(tail);
+                let base = self.temp();
+
+                self.load_slot(len);
+                self.asm.push_u64(elem_size);
+                self.asm.op(MUL);
+                self.load_slot(base);
+                self.asm.op(ADD);
+                self.store_slot(tail);
+
+                let counter = self.new_slot();
+
+                self.emit_loop(counter, Word::Slot(len), |emitter| {
+                    emitter.load_slot(counter);
+                    emitter.asm.push_u64(32);
+                    emitter.asm.op(MUL);
+                    emitter.push_word(elems);
+                    emitter.asm.op(ADD);
+                    emitter.asm.op(MLOAD);
+                    let elem = emitter.temp();
+
+                    emitter.load_slot(counter);
+                    emitter.asm.push_u64(elem_size);
+                    emitter.asm.op(MUL);
+                    emitter.load_slot(base);
+                    emitter.asm.op(ADD);
+                    let elem_dest = emitter.temp();
+
+                    emitter.encode_value(loc, elem, &elem_ty, elem_dest, base, tail);
+                });
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    /// Revert if pos + size is beyond end
+    fn decode_bounds_check(&mut self, pos: usize, size: u64, end: usize) {
+        self.load_slot(end);
+        self.push_word(Word::SlotPlus(pos, size));
+        self.asm.op(GT);
+        self.revert_if();
+    }
+
+    /// Decode ABI encoded data. Returns the frame slots of the decoded values.
+    pub(super) fn abi_decode(&mut self, params: &[Parameter], data: Word, len: Word) -> Vec<usize> {
+        self.push_word(data);
+        let base = self.temp();
+
+        self.push_word(data);
+        self.push_word(len);
+        self.asm.op(ADD);
+        let end = self.temp();
+
+        let head_size: u64 = params.iter().map(|p| self.abi_head_size(&p.ty)).sum();
+
+        self.decode_bounds_check(base, head_size, end);
+
+        let mut offset = 0;
+        let mut values = Vec::new();
+
+        for param in params {
+            self.push_word(Word::SlotPlus(base, offset));
+            let pos = self.temp();
+
+            values.push(self.decode_value(param.loc, &param.ty, base, pos, end));
+
+            offset += self.abi_head_size(&param.ty);
+        }
+
+        values
+    }
+
+    /// Decode the value in the head at pos; offsets of dynamic values are relative to base
+    fn decode_value(
+        &mut self,
+        loc: pt::Loc,
+        ty: &Type,
+        base: usize,
+        pos: usize,
+        end: usize,
+    ) -> usize {
+        let ns = self.ns;
+
+        if ty.is_dynamic(ns) {
+            self.decode_bounds_check(pos, 32, end);
+
+            // the offset should not point beyond the end
+            self.load_slot(pos);
+            self.asm.op(MLOAD);
+            self.load_slot(base);
+            self.load_slot(end);
+            self.asm.op(SUB);
+            self.asm.dup(2);
+            self.asm.op(GT);
+            self.revert_if();
+            self.load_slot(base);
+            self.asm.op(ADD);
+            let start = self.temp();
+
+            return self.decode_dynamic(loc, ty, start, end);
+        }
+
+        match ty.deref_any() {
+            Type::Struct(n) => {
+                let fields = &ns.structs[*n].fields;
+
+                self.alloc_const(fields.len() as u64 * 32);
+                let ptr = self.temp();
+
+                let mut offset = 0;
+
+                for (i, field) in fields.iter().enumerate() {
+                    self.push_word(Word::SlotPlus(pos, offset));
+                    let field_pos = self.temp();
+
+                    let field_value = self.decode_value(loc, &field.ty, base, field_pos, end);
+
+                    self.load_slot(field_value);
+                    self.push_word(Word::SlotPlus(ptr, i as u64 * 32));
+                    self.asm.op(MSTORE);
+
+                    offset += self.abi_head_size(&field.ty);
+                }
+
+                ptr
+            }
+            Type::Array(_, dims) => {
+                let len = dims.last().unwrap().as_ref().unwrap().to_u64().unwrap();
+                let elem_ty = ty.deref_any().array_elem();
+                let elem_size = self.abi_head_size(&elem_ty);
+
+                self.alloc_const(len * 32);
+                let ptr = self.temp();
+
+                let counter = self.new_slot();
+
+                self.emit_loop(counter, Word::Const(len), |emitter| {
+                    emitter.load_slot(counter);
+                    emitter.asm.push_u64(elem_size);
+                    emitter.asm.op(MUL);
+                    emitter.load_slot(pos);
+                    emitter.asm.op(ADD);
+                    let elem_pos = emitter.temp();
+
+                    let elem = emitter.decode_value(loc, &elem_ty, base, elem_pos, end);
+
+                    emitter.load_slot(elem);
+                    emitter.load_slot(counter);
+                    emitter.asm.push_u64(32);
+                    emitter.asm.op(MUL);
+                    emitter.load_slot(ptr);
+                    emitter.asm.op(ADD);
+                    emitter.asm.op(MSTORE);
+                });
+
+                ptr
+            }
+            ty => {
+                self.decode_bounds_check(pos, 32, end);
+
+                self.load_slot(pos);
+                self.asm.op(MLOAD);
+                self.decode_validate(loc, ty);
+                self.temp()
+            }
+        }
+    }
+
+    /// Check that the ABI word on the stack is valid for the type, and convert it to our
+    /// representation
+    fn decode_validate(&mut self, loc: pt::Loc, ty: &Type) {
+        match ty {
+            Type::Bool => {
+                self.asm.push_u64(1);
+                self.asm.dup(2);
+                self.asm.op(GT);
+                self.revert_if();
+            }
+            Type::Int(n) if *n < 256 => {
+                self.asm.op(DUP1);
+                self.asm.op(DUP1);
+                self.asm.push_u64(*n as u64 / 8 - 1);
+                self.asm.op(SIGNEXTEND);
+                self.asm.op(EQ);
+                self.asm.op(ISZERO);
+                self.revert_if();
+            }
+            Type::Bytes(n) if *n < 32 => {
+                let bits = 256 - *n as u64 * 8;
+
+                self.asm.op(DUP1);
+                self.mask(bits as u16);
+                self.revert_if();
+                self.asm.push_u64(bits);
+                self.asm.op(SHR);
+            }
+            Type::Enum(n) => {
+                let count = self.ns.enums[*n].values.len() as u64;
+
+                self.asm.push_u64(count);
+                self.asm.dup(2);
+                self.asm.op(LT);
+                self.asm.op(ISZERO);
+                self.revert_if();
+            }
+            Type::InternalFunction { .. } | Type::ExternalFunction { .. } => {
+                self.unsupported(loc, "ABI decoding of function types");
+            }
+            _ => {
+                if let Some(bits) = self.value_bits(ty) {
+                    if bits < 256 {
+                        self.asm.op(DUP1);
+                        self.asm.push_u64(bits as u64);
+                        self.asm.op(SHR);
+                        self.revert_if();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Decode a dynamic value which starts at start. Strings and bytes are not copied.
+    fn decode_dynamic(&mut self, loc: pt::Loc, ty: &Type, start: usize, end: usize) -> usize {
+        let ns = self.ns;
+
+        match ty.deref_any() {
+            Type::String | Type::DynamicBytes => {
+                self.decode_bounds_check(start, 32, end);
+
+                // the length should not go beyond the end
+                self.push_word(Word::SlotPlus(start, 32));
+                self.load_slot(end);
+                self.asm.op(SUB);
+                self.load_slot(start);
+                self.asm.op(MLOAD);
+                self.asm.op(GT);
+                self.revert_if();
+
+                start
+            }
+            Type::Struct(n) => {
+                let fields = &ns.structs[*n].fields;
+
+                let head_size: u64 = fields.iter().map(|f| self.abi_head_size(&f.ty)).sum();
+
+                self.decode_bounds_check(start, head_size, end);
+
+                self.alloc_const(fields.len() as u64 * 32);
+                let ptr = self.temp();
+
+                let mut offset = 0;
+
+                for (i, field) in fields.iter().enumerate() {
+                    self.push_word(Word::SlotPlus(start, offset));
+                    let field_pos = self.temp();
+
+                    let field_value = self.decode_value(loc, &field.ty, start, field_pos, end);
+
+                    self.load_slot(field_value);
+                    self.push_word(Word::SlotPlus(ptr, i as u64 * 32));
+                    self.asm.op(MSTORE);
+
+                    offset += self.abi_head_size(&field.ty);
+                }
+
+                ptr
+            }
+            Type::Array(_, dims) => {
+                let elem_ty = ty.deref_any().array_elem();
+                let elem_size = self.abi_head_size(&elem_ty);
+
+                let (len, base, ptr, elems) = match dims.last().unwrap() {
+                    Some(len) => {
+                        let len = len.to_u64().unwrap();
+
+                        self.decode_bounds_check(start, len * elem_size, end);
+
+                        self.asm.push_u64(len);
+                        let len = self.temp();
+
+                        self.load_slot(len);
+                        self.asm.push_u64(32);
+                        self.asm.op(MUL);
+                        self.alloc();
+                        let ptr = self.temp();
+
+                        (len, start, ptr, Word::Slot(ptr))
+                    }
+                    None => {
+                        self.decode_bounds_check(start, 32, end);
+
+                        self.load_slot(start);
+                        self.asm.op(MLOAD);
+                        let len = self.temp();
+
+                        self.push_word(Word::SlotPlus(start, 32));
+                        let base = self.temp();
+
+                        // the elements should fit before the end
+                        self.asm.push_u64(elem_size);
+                        self.load_slot(base);
+                        self.load_slot(end);
+                        self.asm.op(SUB);
+                        self.asm.op(DIV);
+                        self.load_slot(len);
+                        self.asm.op(GT);
+                        self.revert_if();
+
+                        self.dynamic_array(&Type::Uint(256), len);
+                        let ptr = self.temp();
+
+                        (len, base, ptr, Word::SlotPlus(ptr, 32))
+                    }
+                };
+
+                let counter = self.new_slot();
+
+                self.emit_loop(counter, Word::Slot(len), |emitter| {
+                    emitter.load_slot(counter);
+                    emitter.asm.push_u64(elem_size);
+                    emitter.asm.op(MUL);
+                    emitter.load_slot(base);
+                    emitter.asm.op(ADD);
+                    let elem_pos = emitter.temp();
+
+                    let elem = emitter.decode_value(loc, &elem_ty, base, elem_pos, end);
+
+                    emitter.load_slot(elem);
+                    emitter.load_slot(counter);
+                    emitter.asm.push_u64(32);
+                    emitter.asm.op(MUL);
+                    emitter.push_word(elems);
+                    emitter.asm.op(ADD);
+                    emitter.asm.op(MSTORE);
+                });
+
+                ptr
+            }
+            _ => unreachable!(),
+        }
+    }
+}
@@ -0,0 +1,274 @@
+//! A tiny EVM assembler. Code is generated as a list of items; jump targets
+//! are symbolic labels which are resolved once all the code is known.
+use num_bigint::{BigInt, Sign};
+
+pub const STOP: u8 = 0x00;
+pub const ADD: u8 = 0x01;
+pub const MUL: u8 = 0x02;
+pub const SUB: u8 = 0x03;
+pub const DIV: u8 = 0x04;
+pub const SDIV: u8 = 0x05;
+pub const MOD: u8 = 0x06;
+pub const SMOD: u8 = 0x07;
+pub const ADDMOD: u8 = 0x08;
+pub const MULMOD: u8 = 0x09;
+pub const EXP: u8 = 0x0a;
+pub const SIGNEXTEND: u8 = 0x0b;
+pub const LT: u8 = 0x10;
+pub const GT: u8 = 0x11;
+pub const SLT: u8 = 0x12;
+pub const SGT: u8 = 0x13;
+pub const EQ: u8 = 0x14;
+pub const ISZERO: u8 = 0x15;
+pub const AND: u8 = 0x16;
+pub const OR: u8 = 0x17;
+pub const XOR: u8 = 0x18;
+pub const NOT: u8 = 0x19;
+pub const BYTE: u8 = 0x1a;
+pub const SHL: u8 = 0x1b;
+pub const SHR: u8 = 0x1c;
+pub const SAR: u8 = 0x1d;
+pub const SHA3: u8 = 0x20;
+pub const ADDRESS: u8 = 0x30;
+pub const BALANCE: u8 = 0x31;
+pub const ORIGIN: u8 = 0x32;
+pub const CALLER: u8 = 0x33;
+pub const CALLVALUE: u8 = 0x34;
+pub const CALLDATALOAD: u8 = 0x35;
+pub const CALLDATASIZE: u8 = 0x36;
+pub const CALLDATACOPY: u8 = 0x37;
+pub const CODESIZE: u8 = 0x38;
+pub const CODECOPY: u8 = 0x39;
+pub const GASPRICE: u8 = 0x3a;
+pub const RETURNDATASIZE: u8 = 0x3d;
+pub const RETURNDATACOPY: u8 = 0x3e;
+pub const BLOCKHASH: u8 = 0x40;
+pub const COINBASE: u8 = 0x41;
+pub const TIMESTAMP: u8 = 0x42;
+pub const NUMBER: u8 = 0x43;
+pub const DIFFICULTY: u8 = 0x44;
+pub const GASLIMIT: u8 = 0x45;
+pub const SELFBALANCE: u8 = 0x47;
+pub const POP: u8 = 0x50;
+pub const MLOAD: u8 = 0x51;
+pub const MSTORE: u8 = 0x52;
+pub const MSTORE8: u8 = 0x53;
+pub const SLOAD: u8 = 0x54;
+pub const SSTORE: u8 = 0x55;
+pub const JUMP: u8 = 0x56;
+pub const JUMPI: u8 = 0x57;
+pub const GAS: u8 = 0x5a;
+pub const JUMPDEST: u8 = 0x5b;
+pub const PUSH1: u8 = 0x60;
+pub const PUSH2: u8 = 0x61;
+pub const DUP1: u8 = 0x80;
+pub const SWAP1: u8 = 0x90;
+pub const LOG0: u8 = 0xa0;
+pub const CREATE: u8 = 0xf0;
+pub const CALL: u8 = 0xf1;
+pub const RETURN: u8 = 0xf3;
+pub const DELEGATECALL: u8 = 0xf4;
+pub const CREATE2: u8 = 0xf5;
+pub const STATICCALL: u8 = 0xfa;
+pub const REVERT: u8 = 0xfd;
+pub const INVALID: u8 = 0xfe;
+pub const SELFDESTRUCT: u8 = 0xff;
+
+/// Labels and data offsets are pushed with PUSH2, so code plus data is limited to 64KB
+const MAX_CODE_SIZE: usize = 0xffff;
+
+#[derive(Clone)]
+enum Item {
+    Op(u8),
+    Push(Vec<u8>),
+    Label(usize),
+    PushLabel(usize),
+    PushData(usize),
+    PushCodeSize,
+}
+
+#[derive(Default)]
+pub struct Assembler {
+    items: Vec<Item>,
+    labels: usize,
+    data: Vec<Vec<u8>>,
+}
+
+impl Assembler {
+    pub fn op(&mut self, op: u8) {
+        self.items.push(Item::Op(op));
+    }
+
+    pub fn dup(&mut self, n: u8) {
+        assert!((1..=16).contains(&n));
+        self.op(DUP1 + n - 1);
+    }
+
+    pub fn swap(&mut self, n: u8) {
+        assert!((1..=16).contains(&n));
+        self.op(SWAP1 + n - 1);
+    }
+
+    /// Push a constant. Negative numbers are pushed in two's complement
+    pub fn push(&mut self, n: &BigInt) {
+        let n = if n.sign() == Sign::Minus {
+            (BigInt::from(1) << 256) + n
+        } else {
+            n.clone()
+        };
+
+        let (_, mut bs) = n.to_bytes_be();
+
+        assert!(bs.len() <= 32);
+
+        // strip leading zeros, but keep at least one byte
+        while bs.len() > 1 && bs[0] == 0 {
+            bs.remove(0);
+        }
+
+        self.items.push(Item::Push(bs));
+    }
+
+    pub fn push_u64(&mut self, n: u64) {
+        self.push(&BigInt::from(n));
+    }
+
+    /// Allocate a new label
+    pub fn new_label(&mut self) -> usize {
+        self.labels += 1;
+        self.labels
+    }
+
+    /// Place a label (a JUMPDEST) at the current position
+    pub fn label(&mut self, label: usize) {
+        self.items.push(Item::Label(label));
+    }
+
+    /// Push the code offset of the label
+    pub fn push_label(&mut self, label: usize) {
+        self.items.push(Item::PushLabel(label));
+    }
+
+    /// Unconditional jump to label
+    pub fn jump(&mut self, label: usize) {
+        self.push_label(label);
+        self.op(JUMP);
+    }
+
+    /// Jump to label if the top of the stack is non-zero
+    pub fn jumpi(&mut self, label: usize) {
+        self.push_label(label);
+        self.op(JUMPI);
+    }
+
+    /// Add a blob of data to end of the code. Returns the data number
+    pub fn add_data(&mut self, data: &[u8]) -> usize {
+        if let Some(no) = self.data.iter().position(|d| d == data) {
+            return no;
+        }
+
+        self.data.push(data.to_vec());
+        self.data.len() - 1
+    }
+
+    /// Push the code offset of the data blob
+    pub fn push_data(&mut self, data_no: usize) {
+        self.items.push(Item::PushData(data_no));
+    }
+
+    /// Push the size of the code including all the data. Anything after this offset
+    /// in the code is not ours, e.g. constructor arguments.
+    pub fn push_code_size(&mut self) {
+        self.items.push(Item::PushCodeSize);
+    }
+
+    /// Current position in the item list
+    pub fn position(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Move the code generated from `prologue_start` onwards in front of the code generated
+    /// from `start`. This is used to generate the function prologue after the body, once
+    /// the size of the stack frame is known.
+    pub fn move_prologue(&mut self, start: usize, prologue_start: usize) {
+        let prologue: Vec<Item> = self.items.drain(prologue_start..).collect();
+        let body: Vec<Item> = self.items.drain(start..).collect();
+
+        self.items.extend(prologue);
+        self.items.extend(body);
+    }
+
+    /// The code offset of every label, and the offset of the end of the code
+    fn layout(&self) -> (Vec<usize>, usize) {
+        let mut label_offsets = vec![0usize; self.labels + 1];
+        let mut offset = 0;
+
+        for item in &self.items {
+            if let Item::Label(label) = item {
+                label_offsets[*label] = offset;
+            }
+
+            offset += match item {
+                Item::Op(_) | Item::Label(_) => 1,
+                Item::Push(bs) => 1 + bs.len(),
+                Item::PushLabel(_) | Item::PushData(_) | Item::PushCodeSize => 3,
+            };
+        }
+
+        (label_offsets, offset)
+    }
+
+    /// The code offset of the label. Code generated later does not move it.
+    pub fn label_offset(&self, label: usize) -> usize {
+        self.layout().0[label]
+    }
+
+    /// Resolve all labels and data offsets, and return the final bytecode
+    pub fn assemble(&self) -> Result<Vec<u8>, String> {
+        let (label_offsets, mut offset) = self.layout();
+
+        let mut data_offsets = Vec::new();
+
+        for data in &self.data {
+            data_offsets.push(offset);
+            offset += data.len();
+        }
+
+        if offset > MAX_CODE_SIZE {
+            return Err(format!(
+                "code size of {} bytes exceeds maximum of {} bytes",
+                offset, MAX_CODE_SIZE
+            ));
+        }
+
+        let code_size = offset;
+
+        let mut code = Vec::with_capacity(code_size);
+
+        let push2 = |code: &mut Vec<u8>, v: usize| {
+            code.push(PUSH2);
+            code.push((v >> 8) as u8);
+            code.push(v as u8);
+        };
+
+        for item in &self.items {
+            match item {
+                Item::Op(op) => code.push(*op),
+                Item::Label(_) => code.push(JUMPDEST),
+                Item::Push(bs) => {
+                    code.push(PUSH1 + bs.len() as u8 - 1);
+                    code.extend_from_slice(bs);
+                }
+                Item::PushLabel(label) => push2(&mut code, label_offsets[*label]),
+                Item::PushData(data_no) => push2(&mut code, data_offsets[*data_no]),
+                Item::PushCodeSize => push2(&mut code, code_size),
+            }
+        }
+
+        for data in &self.data {
+            code.extend_from_slice(data);
+        }
+
+        Ok(code)
+    }
+}
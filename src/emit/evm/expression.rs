@@ -0,0 +1,1138 @@
+use super::assembler::*;
+use super::{EvmEmitter, Word};
+use crate::parser::pt;
+use crate::sema::ast::{Builtin, Expression, StringLocation, Type};
+use num_bigint::{BigInt, Sign};
+use num_traits::ToPrimitive;
+
+impl<'a> EvmEmitter<'a> {
+    /// Evaluate an expression, leaving the result on the stack
+    pub(super) fn expression(&mut self, e: &Expression) {
+        match e {
+            Expression::BoolLiteral(_, b) => self.asm.push_u64(*b as u64),
+            Expression::NumberLiteral(_, _, n) => self.asm.push(n),
+            Expression::BytesLiteral(_, Type::Bytes(_), bs) => {
+                self.asm.push(&BigInt::from_bytes_be(Sign::Plus, bs));
+            }
+            Expression::BytesLiteral(_, _, bs) => self.vector_literal(bs),
+            Expression::CodeLiteral(loc, contract_no, runtime) => {
+                let (deployer, runtime_code) = self.contract_code(*contract_no, *loc);
+
+                self.vector_literal(if *runtime { &runtime_code } else { &deployer });
+            }
+            Expression::StructLiteral(_, ty, fields) if fields.is_empty() => {
+                self.default_value(ty);
+            }
+            Expression::StructLiteral(_, _, fields) => {
+                self.alloc_const(fields.len() as u64 * 32);
+                let ptr = self.temp();
+
+                for (i, field) in fields.iter().enumerate() {
+                    self.expression(field);
+                    self.push_word(Word::SlotPlus(ptr, i as u64 * 32));
+                    self.asm.op(MSTORE);
+                }
+
+                self.load_slot(ptr);
+            }
+            Expression::ArrayLiteral(_, ty, _, values)
+            | Expression::ConstArrayLiteral(_, ty, _, values) => {
+                self.default_value(ty);
+
+                if !values.is_empty() {
+                    self.array_literal(ty, values);
+                }
+            }
+            Expression::Add(loc, ty, l, r)
+            | Expression::Subtract(loc, ty, l, r)
+            | Expression::Multiply(loc, ty, l, r)
+                if self.overflow_check(*loc) =>
+            {
+                let op = match e {
+                    Expression::Add(..) => ADD,
+                    Expression::Subtract(..) => SUB,
+                    _ => MUL,
+                };
+
+                self.expression(l);
+                let l = self.temp();
+                self.expression(r);
+                let r = self.temp();
+
+                self.checked_arithmetic(op, ty, l, r);
+            }
+            Expression::Add(_, ty, l, r) => {
+                self.expression(l);
+                self.expression(r);
+                self.asm.op(ADD);
+                self.normalize(ty);
+            }
+            Expression::Subtract(_, ty, l, r) => {
+                self.expression(r);
+                self.expression(l);
+                self.asm.op(SUB);
+                self.normalize(ty);
+            }
+            Expression::Multiply(_, ty, l, r) => {
+                self.expression(l);
+                self.expression(r);
+                self.asm.op(MUL);
+                self.normalize(ty);
+            }
+            Expression::Divide(_, ty, l, r) | Expression::Modulo(_, ty, l, r) => {
+                self.expression(r);
+                self.asm.op(DUP1);
+                self.asm.op(ISZERO);
+                self.revert_if();
+                self.expression(l);
+
+                let signed = ty.is_signed_int();
+
+                self.asm.op(match (e, signed) {
+                    (Expression::Divide(..), false) => DIV,
+                    (Expression::Divide(..), true) => SDIV,
+                    (_, false) => MOD,
+                    (_, true) => SMOD,
+                });
+
+                self.normalize(ty);
+            }
+            Expression::Power(loc, ty, l, r) if self.overflow_check(*loc) => {
+                self.expression(l);
+                let base = self.temp();
+                self.expression(r);
+                let exp = self.temp();
+
+                self.checked_power(ty, base, exp);
+            }
+            Expression::Power(_, ty, l, r) => {
+                self.expression(r);
+                self.expression(l);
+                self.asm.op(EXP);
+                self.normalize(ty);
+            }
+            Expression::BitwiseOr(_, _, l, r) => {
+                self.expression(l);
+                self.expression(r);
+                self.asm.op(OR);
+            }
+            Expression::BitwiseAnd(_, _, l, r) => {
+                self.expression(l);
+                self.expression(r);
+                self.asm.op(AND);
+            }
+            Expression::BitwiseXor(_, _, l, r) => {
+                self.expression(l);
+                self.expression(r);
+                self.asm.op(XOR);
+            }
+            Expression::ShiftLeft(_, ty, l, r) => {
+                self.expression(l);
+                self.expression(r);
+                self.asm.op(SHL);
+                self.normalize(ty);
+            }
+            Expression::ShiftRight(_, _, l, r, signed) => {
+                self.expression(l);
+                self.expression(r);
+                self.asm.op(if *signed { SAR } else { SHR });
+            }
+            Expression::Or(_, l, r) => {
+                self.expression(l);
+                self.expression(r);
+                self.asm.op(OR);
+            }
+            Expression::And(_, l, r) => {
+                self.expression(l);
+                self.expression(r);
+                self.asm.op(AND);
+            }
+            Expression::Not(_, e) => {
+                self.expression(e);
+                self.asm.op(ISZERO);
+            }
+            Expression::Complement(_, ty, e) => {
+                self.expression(e);
+                self.asm.op(NOT);
+                self.normalize(ty);
+            }
+            Expression::UnaryMinus(_, ty, e) => {
+                self.expression(e);
+                self.asm.push_u64(0);
+                self.asm.op(SUB);
+                self.normalize(ty);
+            }
+            Expression::More(_, l, r) | Expression::LessEqual(_, l, r) => {
+                let signed = l.ty().is_signed_int();
+
+                self.expression(r);
+                self.expression(l);
+                self.asm.op(if signed { SGT } else { GT });
+
+                if matches!(e, Expression::LessEqual(..)) {
+                    self.asm.op(ISZERO);
+                }
+            }
+            Expression::Less(_, l, r) | Expression::MoreEqual(_, l, r) => {
+                let signed = l.ty().is_signed_int();
+
+                self.expression(r);
+                self.expression(l);
+                self.asm.op(if signed { SLT } else { LT });
+
+                if matches!(e, Expression::MoreEqual(..)) {
+                    self.asm.op(ISZERO);
+                }
+            }
+            Expression::Equal(_, l, r) => {
+                self.expression(l);
+                self.expression(r);
+                self.asm.op(EQ);
+            }
+            Expression::NotEqual(_, l, r) => {
+                self.expression(l);
+                self.expression(r);
+                self.asm.op(EQ);
+                self.asm.op(ISZERO);
+            }
+            Expression::Ternary(_, _, cond, l, r) => {
+                let true_label = self.asm.new_label();
+                let end_label = self.asm.new_label();
+
+                self.expression(cond);
+                self.asm.jumpi(true_label);
+                self.expression(r);
+                self.asm.jump(end_label);
+                self.asm.label(true_label);
+                self.expression(l);
+                self.asm.label(end_label);
+            }
+            Expression::Variable(_, _, var_no) => self.load_var(*var_no),
+            Expression::FunctionArg(_, _, arg_no) => self.load_slot(*arg_no),
+            Expression::ZeroExt(_, _, e) => {
+                self.expression(e);
+
+                let from = e.ty();
+
+                if from.is_signed_int() {
+                    let bits = self.value_bits(&from).unwrap();
+                    self.mask(bits);
+                }
+            }
+            Expression::SignExt(_, ty, e) => {
+                self.expression(e);
+
+                let from = e.ty();
+
+                if !from.is_signed_int() {
+                    if let Some(bits) = self.value_bits(&from) {
+                        if bits < 256 {
+                            self.asm.push_u64(bits as u64 / 8 - 1);
+                            self.asm.op(SIGNEXTEND);
+                        }
+                    }
+                }
+
+                self.normalize(ty);
+            }
+            Expression::Trunc(_, ty, e) | Expression::Cast(_, ty, e) => {
+                self.expression(e);
+                self.normalize(ty);
+            }
+            Expression::BytesCast(_, Type::Bytes(n), _, e) => {
+                // bytesN to bytes
+                self.expression(e);
+                self.asm.push_u64(256 - *n as u64 * 8);
+                self.asm.op(SHL);
+                let value = self.temp();
+
+                self.alloc_const(32 + *n as u64);
+                let ptr = self.temp();
+
+                self.asm.push_u64(*n as u64);
+                self.load_slot(ptr);
+                self.asm.op(MSTORE);
+
+                self.load_slot(value);
+                self.push_word(Word::SlotPlus(ptr, 32));
+                self.asm.op(MSTORE);
+
+                self.load_slot(ptr);
+            }
+            Expression::BytesCast(_, _, Type::Bytes(n), e) => {
+                // bytes to bytesN; the length must match
+                self.expression(e);
+                let ptr = self.temp();
+
+                self.load_slot(ptr);
+                self.asm.op(MLOAD);
+                self.asm.push_u64(*n as u64);
+                self.asm.op(EQ);
+                self.asm.op(ISZERO);
+                self.revert_if();
+
+                self.push_word(Word::SlotPlus(ptr, 32));
+                self.asm.op(MLOAD);
+                self.asm.push_u64(256 - *n as u64 * 8);
+                self.asm.op(SHR);
+            }
+            Expression::Load(_, ty, e) => {
+                self.expression(e);
+                self.load_memory(ty);
+            }
+            Expression::Subscript(_, _, a, i) => {
+                // fixed length array in memory
+                self.expression(i);
+                self.asm.push_u64(32);
+                self.asm.op(MUL);
+                self.expression(a);
+                self.asm.op(ADD);
+            }
+            Expression::StructMember(_, _, a, field) => {
+                self.expression(a);
+                if *field > 0 {
+                    self.asm.push_u64(*field as u64 * 32);
+                    self.asm.op(ADD);
+                }
+            }
+            Expression::DynamicArrayLength(_, a) => {
+                self.expression(a);
+                self.asm.op(MLOAD);
+            }
+            Expression::DynamicArraySubscript(_, _, a, i) => {
+                if is_byte_vector(&a.ty()) {
+                    // byte elements are addressed with a tagged pointer
+                    self.expression(i);
+                    self.expression(a);
+                    self.asm.push_u64(32);
+                    self.asm.op(ADD);
+                    self.asm.op(ADD);
+                    self.asm.push_u64(1);
+                    self.asm.push_u64(255);
+                    self.asm.op(SHL);
+                    self.asm.op(OR);
+                } else {
+                    self.expression(i);
+                    self.asm.push_u64(32);
+                    self.asm.op(MUL);
+                    self.expression(a);
+                    self.asm.push_u64(32);
+                    self.asm.op(ADD);
+                    self.asm.op(ADD);
+                }
+            }
+            Expression::AllocDynamicArray(_, ty, size, init) => {
+                if is_byte_vector(ty) {
+                    if let Some(init) = init {
+                        self.vector_literal(init);
+                    } else {
+                        self.expression(size);
+                        let len = self.temp();
+
+                        self.push_word(Word::SlotPlus(len, 32));
+                        self.alloc();
+                        self.asm.op(DUP1);
+                        self.load_slot(len);
+                        self.asm.op(SWAP1);
+                        self.asm.op(MSTORE);
+                    }
+                } else {
+                    self.expression(size);
+                    let len = self.temp();
+
+                    self.dynamic_array(&ty.array_elem(), len);
+                }
+            }
+            Expression::StorageBytesSubscript(_, a, i) => {
+                self.expression(a);
+                let slot = self.temp();
+                self.expression(i);
+                let index = self.temp();
+
+                self.storage_bytes_bounds_check(slot, index);
+                self.storage_bytes_get(slot, index);
+            }
+            Expression::StorageArrayLength { array, .. } => {
+                self.expression(array);
+                self.asm.op(SLOAD);
+            }
+            Expression::StringCompare(_, l, r) => {
+                self.string_location(l);
+                self.vector_hash();
+                self.string_location(r);
+                self.vector_hash();
+                self.asm.op(EQ);
+            }
+            Expression::StringConcat(_, _, l, r) => {
+                self.string_location(l);
+                let l = self.temp();
+                self.string_location(r);
+                let r = self.temp();
+
+                self.load_slot(l);
+                self.asm.op(MLOAD);
+                let l_len = self.temp();
+
+                self.load_slot(r);
+                self.asm.op(MLOAD);
+                self.load_slot(l_len);
+                self.asm.op(ADD);
+                let len = self.temp();
+
+                self.push_word(Word::SlotPlus(len, 32));
+                self.alloc();
+                let ptr = self.temp();
+
+                self.load_slot(len);
+                self.load_slot(ptr);
+                self.asm.op(MSTORE);
+
+                self.memcpy(
+                    Word::SlotPlus(ptr, 32),
+                    Word::SlotPlus(l, 32),
+                    Word::Slot(l_len),
+                );
+
+                self.push_word(Word::SlotPlus(ptr, 32));
+                self.load_slot(l_len);
+                self.asm.op(ADD);
+                let dest = self.temp();
+
+                self.load_slot(r);
+                self.asm.op(MLOAD);
+                let r_len = self.temp();
+
+                self.memcpy(Word::Slot(dest), Word::SlotPlus(r, 32), Word::Slot(r_len));
+
+                self.load_slot(ptr);
+            }
+            Expression::InternalFunctionCfg(cfg_no) => {
+                if let Some(offset) = self.runtime_functions.get(cfg_no) {
+                    self.asm.push_u64(*offset);
+                } else {
+                    let label = self.function_label(*cfg_no);
+                    self.asm.push_label(label);
+                }
+            }
+            Expression::ExternalFunction {
+                address,
+                function_no,
+                ..
+            } => {
+                // external function values are a pair of address and selector
+                self.expression(address);
+                let address = self.temp();
+
+                self.alloc_const(64);
+                let ptr = self.temp();
+
+                self.load_slot(address);
+                self.load_slot(ptr);
+                self.asm.op(MSTORE);
+
+                let selector = self.ns.functions[*function_no].selector();
+
+                self.asm.push_u64(selector as u64);
+                self.push_word(Word::SlotPlus(ptr, 32));
+                self.asm.op(MSTORE);
+
+                self.load_slot(ptr);
+            }
+            Expression::Keccak256(_, _, exprs) => self.keccak256(exprs),
+            Expression::ReturnData(_) => {
+                self.asm.op(RETURNDATASIZE);
+                self.asm.push_u64(32);
+                self.asm.op(ADD);
+                self.alloc();
+                let ptr = self.temp();
+
+                self.asm.op(RETURNDATASIZE);
+                self.load_slot(ptr);
+                self.asm.op(MSTORE);
+
+                self.asm.op(RETURNDATASIZE);
+                self.asm.push_u64(0);
+                self.push_word(Word::SlotPlus(ptr, 32));
+                self.asm.op(RETURNDATACOPY);
+
+                self.load_slot(ptr);
+            }
+            Expression::AbiEncode {
+                tys, packed, args, ..
+            } => {
+                let mut tys = tys.iter();
+                let mut packed_values = Vec::new();
+                let mut values = Vec::new();
+
+                for e in packed {
+                    self.expression(e);
+                    packed_values.push((
+                        self.temp(),
+                        tys.next().unwrap().clone(),
+                        self.expr_loc(e),
+                    ));
+                }
+
+                for e in args {
+                    self.expression(e);
+                    values.push((self.temp(), tys.next().unwrap().clone(), self.expr_loc(e)));
+                }
+
+                let (vector, _) = self.abi_encode(&packed_values, &values, true);
+
+                self.load_slot(vector);
+            }
+            Expression::Builtin(loc, _, builtin, args) => self.builtin(*loc, builtin, args),
+            Expression::Poison => self.asm.push_u64(0),
+            _ => {
+                self.unsupported(e.loc(), "expression");
+                self.asm.push_u64(0);
+            }
+        }
+    }
+
+    fn builtin(&mut self, loc: pt::Loc, builtin: &Builtin, args: &[Expression]) {
+        match builtin {
+            Builtin::GetAddress => self.asm.op(ADDRESS),
+            Builtin::Balance => {
+                self.expression(&args[0]);
+                self.asm.op(BALANCE);
+            }
+            Builtin::Sender => self.asm.op(CALLER),
+            Builtin::Value => self.asm.op(CALLVALUE),
+            Builtin::Origin => self.asm.op(ORIGIN),
+            Builtin::Gasprice if args.is_empty() => self.asm.op(GASPRICE),
+            Builtin::Gasleft => self.asm.op(GAS),
+            Builtin::BlockCoinbase => self.asm.op(COINBASE),
+            Builtin::BlockDifficulty => self.asm.op(DIFFICULTY),
+            Builtin::GasLimit => self.asm.op(GASLIMIT),
+            Builtin::BlockNumber => self.asm.op(NUMBER),
+            Builtin::Timestamp => self.asm.op(TIMESTAMP),
+            Builtin::BlockHash => {
+                self.expression(&args[0]);
+                self.asm.op(BLOCKHASH);
+            }
+            Builtin::Signature => {
+                self.asm.push_u64(0);
+                self.asm.op(CALLDATALOAD);
+                self.asm.push_u64(224);
+                self.asm.op(SHR);
+            }
+            Builtin::Calldata => {
+                self.asm.op(CALLDATASIZE);
+                self.asm.push_u64(32);
+                self.asm.op(ADD);
+                self.alloc();
+                let ptr = self.temp();
+
+                self.asm.op(CALLDATASIZE);
+                self.load_slot(ptr);
+                self.asm.op(MSTORE);
+
+                self.asm.op(CALLDATASIZE);
+                self.asm.push_u64(0);
+                self.push_word(Word::SlotPlus(ptr, 32));
+                self.asm.op(CALLDATACOPY);
+
+                self.load_slot(ptr);
+            }
+            Builtin::AddMod | Builtin::MulMod => {
+                self.expression(&args[2]);
+                self.asm.op(DUP1);
+                self.asm.op(ISZERO);
+                self.revert_if();
+                self.expression(&args[1]);
+                self.expression(&args[0]);
+                self.asm.op(if *builtin == Builtin::AddMod {
+                    ADDMOD
+                } else {
+                    MULMOD
+                });
+            }
+            Builtin::Keccak256 => {
+                self.expression(&args[0]);
+                self.vector_hash();
+            }
+            Builtin::Sha256 | Builtin::Ripemd160 => {
+                self.expression(&args[0]);
+                let ptr = self.temp();
+
+                self.asm.push_u64(32);
+                self.asm.push_u64(0);
+                self.load_slot(ptr);
+                self.asm.op(MLOAD);
+                self.push_word(Word::SlotPlus(ptr, 32));
+                self.asm
+                    .push_u64(if *builtin == Builtin::Sha256 { 2 } else { 3 });
+                self.asm.op(GAS);
+                self.asm.op(STATICCALL);
+                self.asm.op(POP);
+
+                self.asm.push_u64(0);
+                self.asm.op(MLOAD);
+            }
+            Builtin::ExternalFunctionAddress => {
+                self.expression(&args[0]);
+                self.asm.op(MLOAD);
+            }
+            Builtin::ExternalFunctionSelector => {
+                self.expression(&args[0]);
+                self.asm.push_u64(32);
+                self.asm.op(ADD);
+                self.asm.op(MLOAD);
+            }
+            Builtin::PredictAddress => {
+                // keccak256(0xff ++ address ++ salt ++ keccak256(init_code))
+                self.expression(&args[0]);
+                let codehash = self.temp();
+                self.expression(&args[1]);
+                let salt = self.temp();
+
+                self.asm.push_u64(super::FREE_PTR);
+                self.asm.op(MLOAD);
+                let buf = self.temp();
+
+                self.asm.op(ADDRESS);
+                self.load_slot(buf);
+                self.asm.op(MSTORE);
+
+                self.asm.push_u64(0xff);
+                self.push_word(Word::SlotPlus(buf, 11));
+                self.asm.op(MSTORE8);
+
+                self.load_slot(salt);
+                self.push_word(Word::SlotPlus(buf, 32));
+                self.asm.op(MSTORE);
+
+                self.load_slot(codehash);
+                self.push_word(Word::SlotPlus(buf, 64));
+                self.asm.op(MSTORE);
+
+                self.asm.push_u64(85);
+                self.push_word(Word::SlotPlus(buf, 11));
+                self.asm.op(SHA3);
+                self.mask(160);
+            }
+            _ => {
+                self.unsupported(loc, &format!("builtin {:?}", builtin));
+                self.asm.push_u64(0);
+            }
+        }
+    }
+
+    /// Number of bits in a value type, or None if it is not a value type
+    pub(super) fn value_bits(&self, ty: &Type) -> Option<u16> {
+        match ty {
+            Type::Int(n) | Type::Uint(n) => Some(*n),
+            Type::Bytes(n) => Some(*n as u16 * 8),
+            Type::Address(_) | Type::Contract(_) => Some(self.ns.address_length as u16 * 8),
+            Type::Value => Some(self.ns.value_length as u16 * 8),
+            Type::Enum(n) => self.value_bits(&self.ns.enums[*n].ty),
+            Type::Bool => Some(8),
+            _ => None,
+        }
+    }
+
+    /// Keep only the lower bits of the value on the stack
+    pub(super) fn mask(&mut self, bits: u16) {
+        if bits < 256 {
+            self.asm.push(&((BigInt::from(1) << bits) - 1));
+            self.asm.op(AND);
+        }
+    }
+
+    /// Arithmetic reverts on overflow from Solidity 0.8, or with --math-overflow
+    fn overflow_check(&self, loc: pt::Loc) -> bool {
+        self.math_overflow_check || self.ns.checked_arithmetic(loc.0)
+    }
+
+    /// Add, subtract or multiply the values in the two frame slots, leaving the result on the
+    /// stack. Revert if the result does not fit in the type.
+    fn checked_arithmetic(&mut self, op: u8, ty: &Type, l: usize, r: usize) {
+        let signed = ty.is_signed_int();
+        let bits = self.value_bits(ty).unwrap_or(256);
+
+        self.load_slot(r);
+        self.load_slot(l);
+        self.asm.op(op);
+        let res = self.temp();
+
+        match op {
+            // narrower values cannot wrap around 256 bits
+            ADD | SUB if bits == 256 => {
+                if signed {
+                    // the result moved in the wrong direction compared to the sign of r
+                    self.asm.push_u64(0);
+                    self.load_slot(r);
+                    self.asm.op(SLT);
+                    self.load_slot(l);
+                    self.load_slot(res);
+                    self.asm.op(if op == ADD { SLT } else { SGT });
+                    self.asm.op(XOR);
+                } else if op == ADD {
+                    self.load_slot(l);
+                    self.load_slot(res);
+                    self.asm.op(LT);
+                } else {
+                    self.load_slot(r);
+                    self.load_slot(l);
+                    self.asm.op(LT);
+                }
+
+                self.revert_if();
+            }
+            MUL if bits > 128 => {
+                // l != 0 && res / l != r
+                self.load_slot(r);
+                self.load_slot(l);
+                self.load_slot(res);
+                self.asm.op(if signed { SDIV } else { DIV });
+                self.asm.op(EQ);
+                self.asm.op(ISZERO);
+                self.load_slot(l);
+                self.asm.op(ISZERO);
+                self.asm.op(ISZERO);
+                self.asm.op(AND);
+                self.revert_if();
+
+                if signed && bits == 256 {
+                    // the division above does not catch -1 * -2**255
+                    self.load_slot(l);
+                    self.asm.op(NOT);
+                    self.asm.op(ISZERO);
+                    self.asm.push(&(BigInt::from(1) << 255));
+                    self.load_slot(r);
+                    self.asm.op(EQ);
+                    self.asm.op(AND);
+                    self.revert_if();
+                }
+            }
+            _ => (),
+        }
+
+        self.load_slot(res);
+
+        if bits < 256 {
+            self.asm.op(DUP1);
+            self.asm.op(DUP1);
+            self.normalize(ty);
+            self.asm.op(EQ);
+            self.asm.op(ISZERO);
+            self.revert_if();
+        }
+    }
+
+    /// Raise the value in the base slot to the power of the value in the exp slot by
+    /// squaring and multiplying, leaving the result on the stack. Revert on overflow.
+    fn checked_power(&mut self, ty: &Type, base: usize, exp: usize) {
+        let cond = self.asm.new_label();
+        let square = self.asm.new_label();
+        let end = self.asm.new_label();
+
+        self.asm.push_u64(1);
+        let res = self.temp();
+
+        self.asm.label(cond);
+        self.load_slot(exp);
+        self.asm.op(ISZERO);
+        self.asm.jumpi(end);
+
+        // multiply if the lowest bit of the exponent is set
+        self.load_slot(exp);
+        self.asm.push_u64(1);
+        self.asm.op(AND);
+        self.asm.op(ISZERO);
+        self.asm.jumpi(square);
+        self.checked_arithmetic(MUL, ty, res, base);
+        self.store_slot(res);
+
+        // only square the base if there are more bits left, as it may overflow
+        self.asm.label(square);
+        self.load_slot(exp);
+        self.asm.push_u64(1);
+        self.asm.op(SHR);
+        self.asm.op(DUP1);
+        self.store_slot(exp);
+        self.asm.op(ISZERO);
+        self.asm.jumpi(cond);
+        self.checked_arithmetic(MUL, ty, base, base);
+        self.store_slot(base);
+        self.asm.jump(cond);
+
+        self.asm.label(end);
+        self.load_slot(res);
+    }
+
+    /// After arithmetic, the value on the stack might have overflowed the bits of its type.
+    /// Truncate it, and sign extend signed integers.
+    pub(super) fn normalize(&mut self, ty: &Type) {
+        match ty {
+            Type::Bool => (),
+            Type::Int(n) => {
+                if *n < 256 {
+                    self.asm.push_u64(*n as u64 / 8 - 1);
+                    self.asm.op(SIGNEXTEND);
+                }
+            }
+            _ => {
+                if let Some(bits) = self.value_bits(ty) {
+                    self.mask(bits);
+                }
+            }
+        }
+    }
+
+    /// Load a value from memory; the address is on the stack. Elements of bytes and string are
+    /// loaded through tagged pointers.
+    pub(super) fn load_memory(&mut self, ty: &Type) {
+        if is_byte_elem(ty) {
+            // shift the word right by 248 bits if tagged
+            self.asm.op(DUP1);
+            self.asm.push_u64(255);
+            self.asm.op(SHR);
+            self.asm.push_u64(248);
+            self.asm.op(MUL);
+            self.asm.op(SWAP1);
+            self.untag();
+            self.asm.op(MLOAD);
+            self.asm.op(SWAP1);
+            self.asm.op(SHR);
+        } else {
+            self.asm.op(MLOAD);
+        }
+    }
+
+    /// Store a value to memory; the stack holds the value and the address
+    pub(super) fn store_memory(&mut self, ty: &Type) {
+        if is_byte_elem(ty) {
+            let byte_store = self.asm.new_label();
+            let done = self.asm.new_label();
+
+            self.asm.op(DUP1);
+            self.asm.push_u64(255);
+            self.asm.op(SHR);
+            self.asm.jumpi(byte_store);
+            self.asm.op(MSTORE);
+            self.asm.jump(done);
+            self.asm.label(byte_store);
+            self.untag();
+            self.asm.op(MSTORE8);
+            self.asm.label(done);
+        } else {
+            self.asm.op(MSTORE);
+        }
+    }
+
+    /// Remove the tag bit from a pointer
+    fn untag(&mut self) {
+        self.asm.push_u64(1);
+        self.asm.op(SHL);
+        self.asm.push_u64(1);
+        self.asm.op(SHR);
+    }
+
+    /// Push a new vector with the given contents
+    pub(super) fn vector_literal(&mut self, bs: &[u8]) {
+        let len = bs.len() as u64;
+
+        self.alloc_const(32 + len);
+
+        self.asm.push_u64(len);
+        self.asm.dup(2);
+        self.asm.op(MSTORE);
+
+        if !bs.is_empty() {
+            let data_no = self.asm.add_data(bs);
+
+            self.asm.push_u64(len);
+            self.asm.push_data(data_no);
+            self.asm.dup(3);
+            self.asm.push_u64(32);
+            self.asm.op(ADD);
+            self.asm.op(CODECOPY);
+        }
+    }
+
+    fn string_location(&mut self, loc: &StringLocation) {
+        match loc {
+            StringLocation::CompileTime(bs) => self.vector_literal(bs),
+            StringLocation::RunTime(e) => self.expression(e),
+        }
+    }
+
+    /// Replace the vector on the stack with the keccak256 hash of its contents
+    pub(super) fn vector_hash(&mut self) {
+        self.asm.op(DUP1);
+        self.asm.op(MLOAD);
+        self.asm.op(SWAP1);
+        self.asm.push_u64(32);
+        self.asm.op(ADD);
+        self.asm.op(SHA3);
+    }
+
+    /// Hash the concatenation of the expressions. Strings and bytes are included as their
+    /// contents, other values as 32 byte words.
+    fn keccak256(&mut self, exprs: &[Expression]) {
+        let mut values = Vec::new();
+
+        for e in exprs {
+            self.expression(e);
+            values.push((self.temp(), e.ty()));
+        }
+
+        self.asm.push_u64(super::FREE_PTR);
+        self.asm.op(MLOAD);
+        let start = self.temp();
+
+        self.load_slot(start);
+        let cursor = self.temp();
+
+        for (value, ty) in values {
+            if is_byte_vector(&ty) {
+                self.load_slot(value);
+                self.asm.op(MLOAD);
+                let len = self.temp();
+
+                self.memcpy(
+                    Word::Slot(cursor),
+                    Word::SlotPlus(value, 32),
+                    Word::Slot(len),
+                );
+
+                self.load_slot(cursor);
+                self.load_slot(len);
+                self.asm.op(ADD);
+                self.store_slot(cursor);
+            } else {
+                self.load_slot(value);
+                self.abi_word(&ty);
+                self.load_slot(cursor);
+                self.asm.op(MSTORE);
+
+                self.push_word(Word::SlotPlus(cursor, 32));
+                self.store_slot(cursor);
+            }
+        }
+
+        self.load_slot(start);
+        self.load_slot(cursor);
+        self.asm.op(SUB);
+        self.load_slot(start);
+        self.asm.op(SHA3);
+    }
+
+    /// Push the default value for a type. Reference types are allocated, including any
+    /// nested structs and fixed length arrays.
+    pub(super) fn default_value(&mut self, ty: &Type) {
+        match ty {
+            Type::Ref(ty) => self.default_value(ty),
+            Type::String | Type::DynamicBytes | Type::Slice => self.alloc_const(32),
+            Type::Struct(n) => {
+                let ns = self.ns;
+                let fields = &ns.structs[*n].fields;
+
+                self.alloc_const(fields.len() as u64 * 32);
+
+                for (i, field) in fields.iter().enumerate() {
+                    if field.ty.is_reference_type() {
+                        self.default_value(&field.ty);
+                        self.asm.dup(2);
+                        if i > 0 {
+                            self.asm.push_u64(i as u64 * 32);
+                            self.asm.op(ADD);
+                        }
+                        self.asm.op(MSTORE);
+                    }
+                }
+            }
+            Type::Array(_, dims) => match dims.last().unwrap() {
+                None => self.alloc_const(32),
+                Some(len) => {
+                    let len = len.to_u64().unwrap();
+                    let elem_ty = ty.array_elem();
+
+                    self.alloc_const(len * 32);
+
+                    if elem_ty.is_reference_type() {
+                        let ptr = self.temp();
+                        let counter = self.new_slot();
+
+                        self.emit_loop(counter, Word::Const(len), |emitter| {
+                            emitter.default_value(&elem_ty);
+                            emitter.load_slot(counter);
+                            emitter.asm.push_u64(32);
+                            emitter.asm.op(MUL);
+                            emitter.load_slot(ptr);
+                            emitter.asm.op(ADD);
+                            emitter.asm.op(MSTORE);
+                        });
+
+                        self.load_slot(ptr);
+                    }
+                }
+            },
+            _ => self.asm.push_u64(0),
+        }
+    }
+
+    /// Allocate a dynamic array with the given length
+    pub(super) fn dynamic_array(&mut self, elem_ty: &Type, len: usize) {
+        self.load_slot(len);
+        self.asm.push_u64(32);
+        self.asm.op(MUL);
+        self.asm.push_u64(32);
+        self.asm.op(ADD);
+        self.alloc();
+        let ptr = self.temp();
+
+        self.load_slot(len);
+        self.load_slot(ptr);
+        self.asm.op(MSTORE);
+
+        if elem_ty.is_reference_type() {
+            let counter = self.new_slot();
+
+            self.emit_loop(counter, Word::Slot(len), |emitter| {
+                emitter.default_value(elem_ty);
+                emitter.load_slot(counter);
+                emitter.asm.push_u64(32);
+                emitter.asm.op(MUL);
+                emitter.push_word(Word::SlotPlus(ptr, 32));
+                emitter.asm.op(ADD);
+                emitter.asm.op(MSTORE);
+            });
+        }
+
+        self.load_slot(ptr);
+    }
+
+    /// Store the values of an array literal into the array on the stack. The values are in
+    /// row-major order.
+    fn array_literal(&mut self, ty: &Type, values: &[Expression]) {
+        let ptr = self.temp();
+
+        let dims: Vec<u64> = match ty.deref_any() {
+            Type::Array(_, dims) => dims
+                .iter()
+                .map(|d| d.as_ref().unwrap().to_u64().unwrap())
+                .collect(),
+            _ => unreachable!(),
+        };
+
+        for (i, value) in values.iter().enumerate() {
+            // dimensions are stored innermost first
+            let mut index = Vec::new();
+            let mut rest = i as u64;
+
+            for d in &dims {
+                index.push(rest % d);
+                rest /= d;
+            }
+
+            self.expression(value);
+            self.load_slot(ptr);
+
+            for idx in index.iter().skip(1).rev() {
+                self.asm.push_u64(idx * 32);
+                self.asm.op(ADD);
+                self.asm.op(MLOAD);
+            }
+
+            self.asm.push_u64(index[0] * 32);
+            self.asm.op(ADD);
+            self.asm.op(MSTORE);
+        }
+
+        self.load_slot(ptr);
+    }
+
+    /// Push a value onto a memory array. The array is reallocated, so the variable is updated.
+    pub(super) fn memory_push(&mut self, ty: &Type, array: usize, value: usize) {
+        let stride = if is_byte_vector(ty) { 1 } else { 32 };
+
+        self.load_var(array);
+        let old = self.temp();
+
+        self.load_slot(old);
+        self.asm.op(MLOAD);
+        let len = self.temp();
+
+        self.load_slot(len);
+        self.asm.push_u64(stride);
+        self.asm.op(MUL);
+        let size = self.temp();
+
+        self.push_word(Word::SlotPlus(size, 32 + stride));
+        self.alloc();
+        let new = self.temp();
+
+        self.push_word(Word::SlotPlus(len, 1));
+        self.load_slot(new);
+        self.asm.op(MSTORE);
+
+        self.memcpy(
+            Word::SlotPlus(new, 32),
+            Word::SlotPlus(old, 32),
+            Word::Slot(size),
+        );
+
+        self.load_slot(value);
+        self.push_word(Word::SlotPlus(new, 32));
+        self.load_slot(size);
+        self.asm.op(ADD);
+        self.asm.op(if stride == 1 { MSTORE8 } else { MSTORE });
+
+        self.load_slot(new);
+        self.store_var(array);
+    }
+
+    /// Pop a value from a memory array and push it onto the stack. The array is shrunk in place.
+    pub(super) fn memory_pop(&mut self, ty: &Type, array: usize) {
+        let stride = if is_byte_vector(ty) { 1 } else { 32 };
+
+        self.load_var(array);
+        let ptr = self.temp();
+
+        self.load_slot(ptr);
+        self.asm.op(MLOAD);
+        self.asm.op(DUP1);
+        self.asm.op(ISZERO);
+        self.revert_if();
+        self.asm.push_u64(1);
+        self.asm.op(SWAP1);
+        self.asm.op(SUB);
+        let len = self.temp();
+
+        self.load_slot(len);
+        self.load_slot(ptr);
+        self.asm.op(MSTORE);
+
+        self.load_slot(len);
+        self.asm.push_u64(stride);
+        self.asm.op(MUL);
+        self.push_word(Word::SlotPlus(ptr, 32));
+        self.asm.op(ADD);
+        self.asm.op(MLOAD);
+
+        if stride == 1 {
+            self.asm.push_u64(248);
+            self.asm.op(SHR);
+        }
+    }
+}
+
+/// Is this type a vector of bytes, i.e. string or bytes
+pub(super) fn is_byte_vector(ty: &Type) -> bool {
+    matches!(
+        ty.deref_any(),
+        Type::String | Type::DynamicBytes | Type::Slice
+    )
+}
+
+/// Elements of byte vectors are loaded and stored as single bytes
+fn is_byte_elem(ty: &Type) -> bool {
+    matches!(
+        ty.deref_any(),
+        Type::Uint(8) | Type::Int(8) | Type::Bytes(1)
+    )
+}
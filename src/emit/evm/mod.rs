@@ -0,0 +1,1330 @@
+//! EVM backend. Unlike the other targets, this does not go via LLVM: the control flow
+//! graph from codegen is lowered straight to EVM bytecode.
+//!
+//! Memory layout:
+//!
+//! - `0x00`-`0x3f`: scratch space, e.g. for hashing storage slots
+//! - `0x40`: the free memory pointer. Memory is allocated by bumping it and never freed, so
+//!   newly allocated memory is always zero
+//! - `0x60`: the frame pointer of the current function
+//! - `0x80`: return values of the last internal function call, one word each
+//! - after that, the heap
+//!
+//! Every function call allocates a frame on the heap with one word for each parameter,
+//! variable and temporary. Expressions are evaluated on the stack, and the only values
+//! which live on the stack across instructions are the return address and the caller's
+//! frame pointer.
+//!
+//! All values are 256 bit words; signed integers are sign extended, and fixed length
+//! bytes are stored like integers (right-aligned). Reference types are pointers into
+//! memory. Strings and bytes are a length word followed by the bytes; dynamic arrays are
+//! a length word followed by one word per element, and fixed length arrays and structs
+//! are one word per element or field. Elements which are reference types are pointers to
+//! their own allocation.
+mod abi;
+mod assembler;
+mod expression;
+mod storage;
+
+use self::assembler::*;
+use crate::codegen::cfg::{ControlFlowGraph, Instr, InternalCallTy};
+use crate::codegen::reachable::{function_pointers_taken, reachable_cfgs};
+use crate::parser::pt;
+use crate::sema::ast::{CallTy, Contract, Diagnostic, Expression, Namespace, Parameter, Type};
+use crate::sema::codes;
+use num_bigint::BigInt;
+use std::collections::HashMap;
+use tiny_keccak::{Hasher, Keccak};
+
+const FREE_PTR: u64 = 0x40;
+const FRAME_PTR: u64 = 0x60;
+const RETURN_AREA: u64 = 0x80;
+
+/// Selector of `Error(string)`, used for revert reasons
+const ERROR_SELECTOR: u32 = 0x08c3_79a0;
+
+/// Compile a contract. The result is the deployer code, which returns the runtime code
+/// once the constructor has run. With `math_overflow_check`, arithmetic reverts on overflow
+/// even in files written for Solidity before 0.8.
+pub fn build(
+    contract_no: usize,
+    ns: &Namespace,
+    math_overflow_check: bool,
+) -> Result<Vec<u8>, Vec<Diagnostic>> {
+    build_contract(contract_no, ns, math_overflow_check).map(|(deployer, _)| deployer)
+}
+
+/// Compile a contract, returning both the deployer and runtime code
+fn build_contract(
+    contract_no: usize,
+    ns: &Namespace,
+    math_overflow_check: bool,
+) -> Result<(Vec<u8>, Vec<u8>), Vec<Diagnostic>> {
+    let contract = &ns.contracts[contract_no];
+
+    let mut deployer_roots: Vec<usize> = contract.initializer.iter().cloned().collect();
+
+    if let Some(cfg_no) = constructor_cfg(contract) {
+        deployer_roots.push(cfg_no);
+    }
+
+    // Function pointers taken in the constructor may end up in storage and be called by
+    // the runtime code, so those functions must exist in the runtime code too
+    let deployer_pointers =
+        function_pointers_taken(contract, &reachable_cfgs(contract, &deployer_roots));
+
+    let mut runtime_roots: Vec<usize> = contract
+        .cfg
+        .iter()
+        .enumerate()
+        .filter(|(_, cfg)| {
+            cfg.public
+                && matches!(
+                    cfg.ty,
+                    pt::FunctionTy::Function | pt::FunctionTy::Fallback | pt::FunctionTy::Receive
+                )
+        })
+        .map(|(cfg_no, _)| cfg_no)
+        .collect();
+
+    runtime_roots.extend(deployer_pointers.iter().cloned());
+
+    let mut runtime = EvmEmitter::new(contract_no, ns, math_overflow_check);
+
+    runtime.runtime_dispatch();
+    runtime.emit_functions(&runtime_roots);
+
+    // The value of a function pointer is the code offset of the function in the runtime code,
+    // also when it is taken in the deployer
+    let runtime_functions = deployer_pointers
+        .iter()
+        .map(|cfg_no| {
+            let label = runtime.function_label(*cfg_no);
+
+            (*cfg_no, runtime.asm.label_offset(label) as u64)
+        })
+        .collect();
+
+    let runtime_code = runtime.finish()?;
+
+    let mut deployer = EvmEmitter::new(contract_no, ns, math_overflow_check);
+
+    deployer.runtime_functions = runtime_functions;
+
+    deployer.deployer_dispatch(&runtime_code);
+    deployer.emit_functions(&deployer_roots);
+
+    let deployer_code = deployer.finish()?;
+
+    Ok((deployer_code, runtime_code))
+}
+
+/// Find the cfg for the constructor; on ethereum there is at most one. Constructors of base
+/// contracts are not public.
+fn constructor_cfg(contract: &Contract) -> Option<usize> {
+    contract
+        .cfg
+        .iter()
+        .position(|cfg| cfg.public && cfg.ty == pt::FunctionTy::Constructor)
+}
+
+/// A word which can be pushed onto the stack
+#[derive(Clone, Copy)]
+enum Word {
+    /// A constant
+    Const(u64),
+    /// The value in a frame slot
+    Slot(usize),
+    /// The value in a frame slot plus a constant
+    SlotPlus(usize, u64),
+}
+
+struct EvmEmitter<'a> {
+    contract_no: usize,
+    contract: &'a Contract,
+    ns: &'a Namespace,
+    /// Check for overflow regardless of the Solidity version
+    math_overflow_check: bool,
+    asm: Assembler,
+    /// Entry labels of the functions, by cfg number
+    functions: HashMap<usize, usize>,
+    /// Runtime code offsets of the functions whose address the deployer takes, by cfg number
+    runtime_functions: HashMap<usize, u64>,
+    /// Deployer code which jumps to the function with the runtime code offset on the stack
+    pointer_dispatch: Option<usize>,
+    /// Labels of the basic blocks in the current function
+    blocks: Vec<usize>,
+    /// Frame slots of the variables in the current function
+    vars: HashMap<usize, usize>,
+    /// Size of the current frame in words
+    frame_size: usize,
+    /// Start of the heap
+    heap_start: u64,
+    /// Revert without any return data
+    revert_label: usize,
+    /// Revert with the return data of the last external call
+    bubble_label: usize,
+    /// Deployer and runtime code of contracts which this contract creates
+    created: HashMap<usize, (Vec<u8>, Vec<u8>)>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> EvmEmitter<'a> {
+    fn new(contract_no: usize, ns: &'a Namespace, math_overflow_check: bool) -> Self {
+        let contract = &ns.contracts[contract_no];
+
+        let max_returns = contract
+            .cfg
+            .iter()
+            .map(|cfg| cfg.returns.len())
+            .max()
+            .unwrap_or(0) as u64;
+
+        let mut asm = Assembler::default();
+
+        let revert_label = asm.new_label();
+        let bubble_label = asm.new_label();
+
+        EvmEmitter {
+            contract_no,
+            contract,
+            ns,
+            math_overflow_check,
+            asm,
+            functions: HashMap::new(),
+            runtime_functions: HashMap::new(),
+            pointer_dispatch: None,
+            blocks: Vec::new(),
+            vars: HashMap::new(),
+            frame_size: 0,
+            heap_start: RETURN_AREA + max_returns * 32,
+            revert_label,
+            bubble_label,
+            created: HashMap::new(),
+            diagnostics: Vec::new(),
+        }
+    }
+
+    /// Add the shared revert code and assemble the result
+    fn finish(mut self) -> Result<Vec<u8>, Vec<Diagnostic>> {
+        if let Some(dispatch) = self.pointer_dispatch {
+            self.emit_pointer_dispatch(dispatch);
+        }
+
+        self.asm.label(self.revert_label);
+        self.asm.push_u64(0);
+        self.asm.op(DUP1);
+        self.asm.op(REVERT);
+
+        self.asm.label(self.bubble_label);
+        self.asm.op(RETURNDATASIZE);
+        self.asm.push_u64(0);
+        self.asm.op(DUP1);
+        self.asm.op(RETURNDATACOPY);
+        self.asm.op(RETURNDATASIZE);
+        self.asm.push_u64(0);
+        self.asm.op(REVERT);
+
+        if !self.diagnostics.is_empty() {
+            return Err(self.diagnostics);
+        }
+
+        let loc = self.contract.loc;
+
+        self.asm
+            .assemble()
+            .map_err(|message| vec![Diagnostic::error(loc, message)])
+    }
+
+    /// In the deployer, a function pointer holds the runtime code offset of the function. Compare
+    /// it against each function whose address the deployer takes, and jump to its deployer copy.
+    fn emit_pointer_dispatch(&mut self, dispatch: usize) {
+        let mut functions: Vec<(usize, u64)> = self
+            .runtime_functions
+            .iter()
+            .map(|(cfg_no, offset)| (*cfg_no, *offset))
+            .collect();
+
+        functions.sort_unstable();
+
+        let targets: Vec<usize> = functions.iter().map(|_| self.asm.new_label()).collect();
+
+        self.asm.label(dispatch);
+
+        for ((_, offset), target) in functions.iter().zip(&targets) {
+            self.asm.op(DUP1);
+            self.asm.push_u64(*offset);
+            self.asm.op(EQ);
+            self.asm.jumpi(*target);
+        }
+
+        let revert = self.revert_label;
+        self.asm.jump(revert);
+
+        for ((cfg_no, _), target) in functions.iter().zip(&targets) {
+            self.asm.label(*target);
+            self.asm.op(POP);
+
+            let label = self.function_label(*cfg_no);
+            self.asm.jump(label);
+        }
+    }
+
+    /// Location of an expression for diagnostics. Codegen replaces references to functions with
+    /// their cfg, which has no location of its own, so use the function declaration.
+    fn expr_loc(&self, expr: &Expression) -> pt::Loc {
+        if let Expression::InternalFunctionCfg(cfg_no) = expr {
+            match self.contract.cfg[*cfg_no].function_no {
+                Some(function_no) => self.ns.functions[function_no].loc,
+                None => self.contract.loc,
+            }
+        } else {
+            expr.loc()
+        }
+    }
+
+    /// Report a construct which cannot be compiled for the EVM
+    fn unsupported(&mut self, loc: pt::Loc, what: &str) {
+        self.diagnostics.push(
+            Diagnostic::error(loc, format!("{} not supported on EVM", what))
+                .with_code(codes::TARGET_NOT_SUPPORTED),
+        );
+    }
+
+    /// Entry label of a function
+    fn function_label(&mut self, cfg_no: usize) -> usize {
+        if let Some(label) = self.functions.get(&cfg_no) {
+            *label
+        } else {
+            let label = self.asm.new_label();
+            self.functions.insert(cfg_no, label);
+            label
+        }
+    }
+
+    /// Start a new frame with the given number of parameters
+    fn begin_frame(&mut self, params: usize) {
+        self.vars.clear();
+        self.frame_size = params;
+    }
+
+    /// Allocate a new slot in the current frame
+    fn new_slot(&mut self) -> usize {
+        self.frame_size += 1;
+        self.frame_size - 1
+    }
+
+    /// Frame slot of a variable
+    fn var_slot(&mut self, var_no: usize) -> usize {
+        if let Some(slot) = self.vars.get(&var_no) {
+            *slot
+        } else {
+            let slot = self.new_slot();
+            self.vars.insert(var_no, slot);
+            slot
+        }
+    }
+
+    /// Push the memory address of a frame slot
+    fn slot_addr(&mut self, slot: usize) {
+        self.asm.push_u64(FRAME_PTR);
+        self.asm.op(MLOAD);
+
+        if slot > 0 {
+            self.asm.push_u64(slot as u64 * 32);
+            self.asm.op(ADD);
+        }
+    }
+
+    /// Push the value in a frame slot
+    fn load_slot(&mut self, slot: usize) {
+        self.slot_addr(slot);
+        self.asm.op(MLOAD);
+    }
+
+    /// Pop the top of the stack into a frame slot
+    fn store_slot(&mut self, slot: usize) {
+        self.slot_addr(slot);
+        self.asm.op(MSTORE);
+    }
+
+    /// Pop the top of the stack into a new frame slot
+    fn temp(&mut self) -> usize {
+        let slot = self.new_slot();
+        self.store_slot(slot);
+        slot
+    }
+
+    fn load_var(&mut self, var_no: usize) {
+        let slot = self.var_slot(var_no);
+        self.load_slot(slot);
+    }
+
+    fn store_var(&mut self, var_no: usize) {
+        let slot = self.var_slot(var_no);
+        self.store_slot(slot);
+    }
+
+    fn push_word(&mut self, word: Word) {
+        match word {
+            Word::Const(n) => self.asm.push_u64(n),
+            Word::Slot(slot) => self.load_slot(slot),
+            Word::SlotPlus(slot, n) => {
+                self.load_slot(slot);
+                if n > 0 {
+                    self.asm.push_u64(n);
+                    self.asm.op(ADD);
+                }
+            }
+        }
+    }
+
+    /// Allocate memory; the size is on the stack and is replaced with the pointer
+    fn alloc(&mut self) {
+        // round up to multiple of 32
+        self.asm.push_u64(31);
+        self.asm.op(ADD);
+        self.asm.push_u64(5);
+        self.asm.op(SHR);
+        self.asm.push_u64(5);
+        self.asm.op(SHL);
+
+        self.asm.push_u64(FREE_PTR);
+        self.asm.op(MLOAD);
+        self.asm.op(SWAP1);
+        self.asm.dup(2);
+        self.asm.op(ADD);
+        self.asm.push_u64(FREE_PTR);
+        self.asm.op(MSTORE);
+    }
+
+    /// Allocate memory of a fixed size, and push the pointer
+    fn alloc_const(&mut self, size: u64) {
+        let size = (size + 31) & !31;
+
+        self.asm.push_u64(FREE_PTR);
+        self.asm.op(MLOAD);
+        self.asm.op(DUP1);
+        self.asm.push_u64(size);
+        self.asm.op(ADD);
+        self.asm.push_u64(FREE_PTR);
+        self.asm.op(MSTORE);
+    }
+
+    /// Copy memory using the identity precompile
+    fn memcpy(&mut self, dest: Word, src: Word, len: Word) {
+        self.push_word(len);
+        self.push_word(dest);
+        self.push_word(len);
+        self.push_word(src);
+        self.asm.push_u64(4);
+        self.asm.op(GAS);
+        self.asm.op(STATICCALL);
+        self.asm.op(POP);
+    }
+
+    /// Revert if the top of the stack is non-zero
+    fn revert_if(&mut self) {
+        let label = self.revert_label;
+        self.asm.jumpi(label);
+    }
+
+    /// Generate a loop which runs the body with the counter slot going from 0 to the value
+    /// in the limit slot
+    fn emit_loop<F>(&mut self, counter: usize, limit: Word, body: F)
+    where
+        F: FnOnce(&mut Self),
+    {
+        let cond = self.asm.new_label();
+        let end = self.asm.new_label();
+
+        self.asm.push_u64(0);
+        self.store_slot(counter);
+
+        self.asm.label(cond);
+        self.push_word(limit);
+        self.load_slot(counter);
+        self.asm.op(LT);
+        self.asm.op(ISZERO);
+        self.asm.jumpi(end);
+
+        body(self);
+
+        self.load_slot(counter);
+        self.asm.push_u64(1);
+        self.asm.op(ADD);
+        self.store_slot(counter);
+        self.asm.jump(cond);
+
+        self.asm.label(end);
+    }
+
+    /// Emit the runtime code entry point, which dispatches on the function selector
+    fn runtime_dispatch(&mut self) {
+        let start = self.asm.position();
+
+        self.begin_frame(0);
+
+        let fallback = self.asm.new_label();
+
+        self.asm.push_u64(4);
+        self.asm.op(CALLDATASIZE);
+        self.asm.op(LT);
+        self.asm.jumpi(fallback);
+
+        self.asm.push_u64(0);
+        self.asm.op(CALLDATALOAD);
+        self.asm.push_u64(224);
+        self.asm.op(SHR);
+
+        let mut cases = Vec::new();
+
+        let contract = self.contract;
+
+        for (cfg_no, cfg) in contract.cfg.iter().enumerate() {
+            if cfg.public && cfg.ty == pt::FunctionTy::Function {
+                let label = self.asm.new_label();
+
+                self.asm.op(DUP1);
+                self.asm.push_u64(cfg.selector as u64);
+                self.asm.op(EQ);
+                self.asm.jumpi(label);
+
+                cases.push((cfg_no, label));
+            }
+        }
+
+        self.asm.op(POP);
+        self.asm.jump(fallback);
+
+        for (cfg_no, label) in cases {
+            self.asm.label(label);
+            self.asm.op(POP);
+            self.dispatch_function(cfg_no);
+        }
+
+        self.asm.label(fallback);
+        self.dispatch_fallback();
+
+        self.frame_prologue(start);
+    }
+
+    /// Decode the calldata, call the function and return the ABI encoded return values
+    fn dispatch_function(&mut self, cfg_no: usize) {
+        let contract = self.contract;
+        let cfg = &contract.cfg[cfg_no];
+
+        if cfg.nonpayable {
+            self.asm.op(CALLVALUE);
+            self.revert_if();
+        }
+
+        // copy the calldata to memory
+        self.asm.op(CALLDATASIZE);
+        self.alloc();
+        let buf = self.temp();
+
+        self.asm.op(CALLDATASIZE);
+        self.asm.push_u64(0);
+        self.load_slot(buf);
+        self.asm.op(CALLDATACOPY);
+
+        self.asm.op(CALLDATASIZE);
+        self.asm.push_u64(4);
+        self.asm.op(SWAP1);
+        self.asm.op(SUB);
+        let len = self.temp();
+
+        let args = self.abi_decode(&cfg.params, Word::SlotPlus(buf, 4), Word::Slot(len));
+
+        self.call_function(cfg_no, &args);
+
+        self.return_abi(&cfg.returns);
+    }
+
+    /// Call a function from the dispatcher with arguments in frame slots
+    fn call_function(&mut self, cfg_no: usize, args: &[usize]) {
+        let ret = self.asm.new_label();
+
+        self.asm.push_label(ret);
+
+        for arg in args {
+            self.load_slot(*arg);
+        }
+
+        let label = self.function_label(cfg_no);
+        self.asm.jump(label);
+        self.asm.label(ret);
+    }
+
+    /// ABI encode the values in the return area, and return them
+    fn return_abi(&mut self, returns: &[Parameter]) {
+        if returns.is_empty() {
+            self.asm.op(STOP);
+            return;
+        }
+
+        let mut values = Vec::new();
+
+        for (i, ret) in returns.iter().enumerate() {
+            self.asm.push_u64(RETURN_AREA + i as u64 * 32);
+            self.asm.op(MLOAD);
+            let slot = self.temp();
+
+            values.push((slot, ret.ty.clone(), ret.loc));
+        }
+
+        let (data, len) = self.abi_encode(&[], &values, false);
+
+        self.load_slot(len);
+        self.load_slot(data);
+        self.asm.op(RETURN);
+    }
+
+    /// Call the receive function if there is no calldata, else the fallback function
+    fn dispatch_fallback(&mut self) {
+        let contract = self.contract;
+        let find = |ty| {
+            contract
+                .cfg
+                .iter()
+                .position(|cfg| cfg.public && cfg.ty == ty)
+        };
+
+        let receive = find(pt::FunctionTy::Receive);
+        let fallback = find(pt::FunctionTy::Fallback);
+
+        if let Some(cfg_no) = receive {
+            let label = self.asm.new_label();
+
+            self.asm.op(CALLDATASIZE);
+            self.asm.op(ISZERO);
+            self.asm.jumpi(label);
+
+            self.dispatch_fallback_function(fallback);
+
+            self.asm.label(label);
+            self.dispatch_fallback_function(Some(cfg_no));
+        } else {
+            self.dispatch_fallback_function(fallback);
+        }
+    }
+
+    fn dispatch_fallback_function(&mut self, cfg_no: Option<usize>) {
+        if let Some(cfg_no) = cfg_no {
+            if self.contract.cfg[cfg_no].nonpayable {
+                self.asm.op(CALLVALUE);
+                self.revert_if();
+            }
+
+            self.call_function(cfg_no, &[]);
+            self.asm.op(STOP);
+        } else {
+            let label = self.revert_label;
+            self.asm.jump(label);
+        }
+    }
+
+    /// Emit the deployer entry point, which runs the storage initializer and constructor,
+    /// and then returns the runtime code
+    fn deployer_dispatch(&mut self, runtime_code: &[u8]) {
+        let start = self.asm.position();
+
+        self.begin_frame(0);
+
+        let constructor = constructor_cfg(self.contract);
+
+        if let Some(cfg_no) = constructor {
+            if self.contract.cfg[cfg_no].nonpayable {
+                self.asm.op(CALLVALUE);
+                self.revert_if();
+            }
+        }
+
+        // the constructor arguments are appended to the code
+        self.asm.push_code_size();
+        self.asm.op(CODESIZE);
+        self.asm.op(SUB);
+        let len = self.temp();
+
+        self.load_slot(len);
+        self.alloc();
+        let buf = self.temp();
+
+        self.load_slot(len);
+        self.asm.push_code_size();
+        self.load_slot(buf);
+        self.asm.op(CODECOPY);
+
+        if let Some(cfg_no) = self.contract.initializer {
+            self.call_function(cfg_no, &[]);
+        }
+
+        if let Some(cfg_no) = constructor {
+            let contract = self.contract;
+
+            let args = self.abi_decode(
+                &contract.cfg[cfg_no].params,
+                Word::Slot(buf),
+                Word::Slot(len),
+            );
+
+            self.call_function(cfg_no, &args);
+        }
+
+        let data_no = self.asm.add_data(runtime_code);
+
+        self.asm.push_u64(runtime_code.len() as u64);
+        self.asm.push_data(data_no);
+        self.asm.push_u64(0);
+        self.asm.op(CODECOPY);
+
+        self.asm.push_u64(runtime_code.len() as u64);
+        self.asm.push_u64(0);
+        self.asm.op(RETURN);
+
+        self.frame_prologue(start);
+    }
+
+    /// Generate the code which sets up the memory for the entry point, now that the size of its
+    /// frame is known, and move it to the start.
+    fn frame_prologue(&mut self, start: usize) {
+        let prologue_start = self.asm.position();
+
+        let frame = self.heap_start;
+
+        self.asm.push_u64(frame);
+        self.asm.push_u64(FRAME_PTR);
+        self.asm.op(MSTORE);
+
+        self.asm.push_u64(frame + self.frame_size as u64 * 32);
+        self.asm.push_u64(FREE_PTR);
+        self.asm.op(MSTORE);
+
+        self.asm.move_prologue(start, prologue_start);
+    }
+
+    /// Emit all the functions reachable from the roots
+    fn emit_functions(&mut self, roots: &[usize]) {
+        let mut cfgs: Vec<usize> = reachable_cfgs(self.contract, roots).into_iter().collect();
+
+        cfgs.sort_unstable();
+
+        for cfg_no in cfgs {
+            if !self.contract.cfg[cfg_no].is_placeholder() {
+                self.emit_function(cfg_no);
+            }
+        }
+    }
+
+    /// Emit a function. On entry, the stack holds the return address followed by the arguments.
+    fn emit_function(&mut self, cfg_no: usize) {
+        let contract = self.contract;
+        let cfg = &contract.cfg[cfg_no];
+
+        let entry = self.function_label(cfg_no);
+
+        self.asm.label(entry);
+
+        let start = self.asm.position();
+
+        self.begin_frame(cfg.params.len());
+
+        let mut var_nos: Vec<usize> = cfg.vars.keys().cloned().collect();
+        var_nos.sort_unstable();
+
+        for var_no in var_nos {
+            self.var_slot(var_no);
+        }
+
+        self.blocks.clear();
+
+        for _ in &cfg.blocks {
+            let label = self.asm.new_label();
+            self.blocks.push(label);
+        }
+
+        for (block_no, block) in cfg.blocks.iter().enumerate() {
+            self.asm.label(self.blocks[block_no]);
+
+            for instr in &block.instr {
+                self.instr(cfg, instr);
+            }
+        }
+
+        // The prologue allocates the frame and stores the arguments. After the prologue,
+        // the stack holds the return address and the frame pointer of the caller.
+        let prologue_start = self.asm.position();
+
+        let params = cfg.params.len();
+
+        self.asm.push_u64(FREE_PTR);
+        self.asm.op(MLOAD);
+        self.asm.op(DUP1);
+        self.asm.push_u64(self.frame_size as u64 * 32);
+        self.asm.op(ADD);
+        self.asm.push_u64(FREE_PTR);
+        self.asm.op(MSTORE);
+
+        // stack: ret, args..., frame
+        self.asm.push_u64(FRAME_PTR);
+        self.asm.op(MLOAD);
+        self.asm.op(SWAP1);
+        self.asm.push_u64(FRAME_PTR);
+        self.asm.op(MSTORE);
+
+        // stack: ret, args..., old frame
+        for i in (0..params).rev() {
+            self.asm.op(SWAP1);
+            self.store_slot(i);
+        }
+
+        self.asm.move_prologue(start, prologue_start);
+    }
+
+    fn instr(&mut self, cfg: &ControlFlowGraph, instr: &Instr) {
+        match instr {
+            Instr::Nop | Instr::Print { .. } => (),
+            Instr::Set { res, expr, .. } => {
+                self.expression(expr);
+                self.store_var(*res);
+            }
+            Instr::Branch { block } => {
+                let label = self.blocks[*block];
+                self.asm.jump(label);
+            }
+            Instr::BranchCond {
+                cond,
+                true_block,
+                false_block,
+            } => {
+                self.expression(cond);
+
+                let label = self.blocks[*true_block];
+                self.asm.jumpi(label);
+
+                let label = self.blocks[*false_block];
+                self.asm.jump(label);
+            }
+            Instr::Return { value } => {
+                for (i, v) in value.iter().enumerate() {
+                    self.expression(v);
+                    self.asm.push_u64(RETURN_AREA + i as u64 * 32);
+                    self.asm.op(MSTORE);
+                }
+
+                // restore the frame pointer of the caller
+                self.asm.push_u64(FRAME_PTR);
+                self.asm.op(MSTORE);
+                self.asm.op(JUMP);
+            }
+            Instr::Call {
+                res, call, args, ..
+            } => {
+                let ret = self.asm.new_label();
+
+                self.asm.push_label(ret);
+
+                for arg in args {
+                    self.expression(arg);
+                }
+
+                match call {
+                    InternalCallTy::Static(cfg_no) => {
+                        let label = self.function_label(*cfg_no);
+                        self.asm.jump(label);
+                    }
+                    InternalCallTy::Dynamic(expr) => {
+                        self.expression(expr);
+
+                        if self.runtime_functions.is_empty() {
+                            self.asm.op(JUMP);
+                        } else {
+                            let dispatch = match self.pointer_dispatch {
+                                Some(label) => label,
+                                None => {
+                                    let label = self.asm.new_label();
+                                    self.pointer_dispatch = Some(label);
+                                    label
+                                }
+                            };
+
+                            self.asm.jump(dispatch);
+                        }
+                    }
+                }
+
+                self.asm.label(ret);
+
+                for (i, res) in res.iter().enumerate() {
+                    self.asm.push_u64(RETURN_AREA + i as u64 * 32);
+                    self.asm.op(MLOAD);
+                    self.store_var(*res);
+                }
+            }
+            Instr::Store { dest, pos } => {
+                let ty = cfg.vars[pos].ty.clone();
+
+                self.load_var(*pos);
+                self.expression(dest);
+                self.store_memory(&ty);
+            }
+            Instr::AssertFailure { expr: None } => {
+                let label = self.revert_label;
+                self.asm.jump(label);
+            }
+            Instr::AssertFailure { expr: Some(expr) } => {
+                self.expression(expr);
+                let reason = self.temp();
+
+                self.asm.push_u64(ERROR_SELECTOR as u64);
+                let selector = self.temp();
+
+                let loc = expr.loc();
+
+                let (data, len) = self.abi_encode(
+                    &[(selector, Type::Bytes(4), loc)],
+                    &[(reason, Type::String, loc)],
+                    false,
+                );
+
+                self.load_slot(len);
+                self.load_slot(data);
+                self.asm.op(REVERT);
+            }
+            Instr::Unreachable => {
+                self.asm.op(INVALID);
+            }
+            Instr::SelfDestruct { recipient } => {
+                self.expression(recipient);
+                self.asm.op(SELFDESTRUCT);
+            }
+            Instr::LoadStorage { res, ty, storage } => {
+                self.expression(storage);
+                self.storage_load(storage.loc(), ty);
+                self.store_var(*res);
+            }
+            Instr::ClearStorage { ty, storage } => {
+                self.expression(storage);
+                self.storage_clear(ty);
+            }
+            Instr::SetStorage { ty, value, storage } => {
+                self.expression(value);
+                self.expression(storage);
+                self.storage_store(storage.loc(), ty);
+            }
+            Instr::SetStorageBytes {
+                value,
+                storage,
+                offset,
+            } => {
+                self.expression(storage);
+                let slot = self.temp();
+                self.expression(offset);
+                let index = self.temp();
+                self.expression(value);
+                let value = self.temp();
+
+                self.storage_bytes_bounds_check(slot, index);
+                self.storage_bytes_set(slot, index, value);
+            }
+            Instr::PushStorage {
+                res,
+                value,
+                storage,
+            } => {
+                self.expression(storage);
+                let slot = self.temp();
+                self.expression(value);
+                let value = self.temp();
+
+                self.load_slot(slot);
+                self.asm.op(SLOAD);
+                let index = self.temp();
+
+                self.storage_bytes_set(slot, index, value);
+
+                self.load_slot(index);
+                self.asm.push_u64(1);
+                self.asm.op(ADD);
+                self.load_slot(slot);
+                self.asm.op(SSTORE);
+
+                self.load_slot(value);
+                self.store_var(*res);
+            }
+            Instr::PopStorage { res, storage, .. } => {
+                self.expression(storage);
+                let slot = self.temp();
+
+                self.load_slot(slot);
+                self.asm.op(SLOAD);
+                self.asm.op(DUP1);
+                self.asm.op(ISZERO);
+                self.revert_if();
+                self.asm.push_u64(1);
+                self.asm.op(SWAP1);
+                self.asm.op(SUB);
+                let index = self.temp();
+
+                self.storage_bytes_get(slot, index);
+                self.store_var(*res);
+
+                self.asm.push_u64(0);
+                let zero = self.temp();
+                self.storage_bytes_set(slot, index, zero);
+
+                self.load_slot(index);
+                self.load_slot(slot);
+                self.asm.op(SSTORE);
+            }
+            Instr::PushMemory {
+                res,
+                ty,
+                array,
+                value,
+            } => {
+                self.expression(value);
+                let value = self.temp();
+
+                self.memory_push(ty, *array, value);
+
+                self.load_slot(value);
+                self.store_var(*res);
+            }
+            Instr::PopMemory { res, ty, array } => {
+                self.memory_pop(ty, *array);
+                self.store_var(*res);
+            }
+            Instr::AbiDecode {
+                res,
+                selector,
+                exception_block,
+                tys,
+                data,
+            } => {
+                self.expression(data);
+                let vector = self.temp();
+
+                self.load_slot(vector);
+                self.asm.op(MLOAD);
+                let mut len = self.temp();
+                let mut start = Word::SlotPlus(vector, 32);
+
+                if let Some(selector) = selector {
+                    let fail = match exception_block {
+                        Some(block) => self.blocks[*block],
+                        None => self.revert_label,
+                    };
+
+                    self.asm.push_u64(4);
+                    self.load_slot(len);
+                    self.asm.op(LT);
+                    self.asm.jumpi(fail);
+
+                    self.asm.push_u64(*selector as u64);
+                    self.push_word(start);
+                    self.asm.op(MLOAD);
+                    self.asm.push_u64(224);
+                    self.asm.op(SHR);
+                    self.asm.op(EQ);
+                    self.asm.op(ISZERO);
+                    self.asm.jumpi(fail);
+
+                    self.asm.push_u64(4);
+                    self.load_slot(len);
+                    self.asm.op(SUB);
+                    len = self.temp();
+                    start = Word::SlotPlus(vector, 36);
+                }
+
+                let values = self.abi_decode(tys, start, Word::Slot(len));
+
+                for (res, value) in res.iter().zip(values.into_iter()) {
+                    self.load_slot(value);
+                    self.store_var(*res);
+                }
+            }
+            Instr::ExternalCall {
+                success,
+                address,
+                payload,
+                value,
+                gas,
+                callty,
+            } => {
+                let address = match address {
+                    Some(address) => address,
+                    None => {
+                        self.unsupported(payload.loc(), "call without address");
+                        return;
+                    }
+                };
+
+                self.expression(payload);
+                let payload = self.temp();
+
+                // no return data is copied; the caller uses returndatacopy
+                self.asm.push_u64(0);
+                self.asm.push_u64(0);
+                self.load_slot(payload);
+                self.asm.op(MLOAD);
+                self.push_word(Word::SlotPlus(payload, 32));
+
+                if *callty == CallTy::Regular {
+                    self.expression(value);
+                }
+
+                self.expression(address);
+                self.expression(gas);
+
+                self.asm.op(match callty {
+                    CallTy::Regular => CALL,
+                    CallTy::Static => STATICCALL,
+                    CallTy::Delegate => DELEGATECALL,
+                });
+
+                self.call_result(*success);
+            }
+            Instr::ValueTransfer {
+                success,
+                address,
+                value,
+            } => {
+                self.asm.push_u64(0);
+                self.asm.op(DUP1);
+                self.asm.op(DUP1);
+                self.asm.op(DUP1);
+                self.expression(value);
+                self.expression(address);
+                self.asm.op(GAS);
+                self.asm.op(CALL);
+
+                self.call_result(*success);
+            }
+            Instr::Constructor {
+                success,
+                res,
+                contract_no,
+                constructor_no,
+                args,
+                value,
+                salt,
+                ..
+            } => {
+                self.create_contract(
+                    *success,
+                    *res,
+                    *contract_no,
+                    *constructor_no,
+                    args,
+                    value.as_ref(),
+                    salt.as_ref(),
+                );
+            }
+            Instr::EmitEvent {
+                event_no,
+                data,
+                data_tys,
+                topics,
+                topic_tys,
+            } => {
+                self.emit_event(*event_no, data, data_tys, topics, topic_tys);
+            }
+        }
+    }
+
+    /// Either store the success flag of a call, or revert with the return data
+    fn call_result(&mut self, success: Option<usize>) {
+        if let Some(success) = success {
+            self.store_var(success);
+        } else {
+            self.asm.op(ISZERO);
+            let label = self.bubble_label;
+            self.asm.jumpi(label);
+        }
+    }
+
+    /// Deploy code of another contract
+    fn contract_code(&mut self, contract_no: usize, loc: pt::Loc) -> (Vec<u8>, Vec<u8>) {
+        if contract_no == self.contract_no {
+            self.unsupported(loc, "contract creating itself");
+            return (Vec::new(), Vec::new());
+        }
+
+        if let Some(code) = self.created.get(&contract_no) {
+            return code.clone();
+        }
+
+        match build_contract(contract_no, self.ns, self.math_overflow_check) {
+            Ok(code) => {
+                self.created.insert(contract_no, code.clone());
+                code
+            }
+            Err(diagnostics) => {
+                self.diagnostics.extend(diagnostics);
+                (Vec::new(), Vec::new())
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn create_contract(
+        &mut self,
+        success: Option<usize>,
+        res: usize,
+        contract_no: usize,
+        constructor_no: Option<usize>,
+        args: &[Expression],
+        value: Option<&Expression>,
+        salt: Option<&Expression>,
+    ) {
+        let (code, _) = self.contract_code(contract_no, self.ns.contracts[contract_no].loc);
+
+        let ns = self.ns;
+
+        let tys: Vec<Type> = match constructor_no {
+            Some(function_no) => ns.functions[function_no]
+                .params
+                .iter()
+                .map(|p| p.ty.clone())
+                .collect(),
+            None => Vec::new(),
+        };
+
+        let mut values = Vec::new();
+
+        for (arg, ty) in args.iter().zip(tys.into_iter()) {
+            self.expression(arg);
+            let slot = self.temp();
+            values.push((slot, ty, self.expr_loc(arg)));
+        }
+
+        match value {
+            Some(value) => self.expression(value),
+            None => self.asm.push_u64(0),
+        }
+        let value = self.temp();
+
+        let salt = salt.map(|salt| {
+            self.expression(salt);
+            self.temp()
+        });
+
+        // copy the code to memory, and append the encoded constructor arguments
+        let code_len = code.len() as u64;
+        let data_no = self.asm.add_data(&code);
+
+        self.asm.push_u64(FREE_PTR);
+        self.asm.op(MLOAD);
+        let start = self.temp();
+
+        self.asm.push_u64(code_len);
+        self.asm.push_data(data_no);
+        self.load_slot(start);
+        self.asm.op(CODECOPY);
+
+        self.push_word(Word::SlotPlus(start, code_len));
+        self.asm.push_u64(FREE_PTR);
+        self.asm.op(MSTORE);
+
+        let (_, len) = self.abi_encode(&[], &values, false);
+
+        if let Some(salt) = salt {
+            self.load_slot(salt);
+        }
+
+        self.push_word(Word::SlotPlus(len, code_len));
+        self.load_slot(start);
+        self.load_slot(value);
+
+        self.asm.op(if salt.is_some() { CREATE2 } else { CREATE });
+
+        if let Some(success) = success {
+            self.asm.op(DUP1);
+            self.asm.op(ISZERO);
+            self.asm.op(ISZERO);
+            self.store_var(success);
+        } else {
+            self.asm.op(DUP1);
+            self.asm.op(ISZERO);
+            let label = self.bubble_label;
+            self.asm.jumpi(label);
+        }
+
+        self.store_var(res);
+    }
+
+    fn emit_event(
+        &mut self,
+        event_no: usize,
+        data: &[Expression],
+        data_tys: &[Parameter],
+        topics: &[Expression],
+        topic_tys: &[Parameter],
+    ) {
+        let ns = self.ns;
+        let event = &ns.events[event_no];
+
+        let mut topic_slots = Vec::new();
+
+        for (topic, ty) in topics.iter().zip(topic_tys.iter()) {
+            self.expression(topic);
+
+            match &ty.ty {
+                Type::String | Type::DynamicBytes => {
+                    let value = self.temp();
+
+                    self.load_slot(value);
+                    self.asm.op(MLOAD);
+                    self.push_word(Word::SlotPlus(value, 32));
+                    self.asm.op(SHA3);
+                }
+                ty if ty.is_reference_type() => {
+                    // indexed reference types are hashed in their ABI encoded form
+                    let value = self.temp();
+
+                    let (data, len) =
+                        self.abi_encode(&[], &[(value, ty.clone(), self.expr_loc(topic))], false);
+
+                    self.load_slot(len);
+                    self.load_slot(data);
+                    self.asm.op(SHA3);
+                }
+                ty => self.abi_word(ty),
+            }
+
+            topic_slots.push(self.temp());
+        }
+
+        let mut values = Vec::new();
+
+        for (e, ty) in data.iter().zip(data_tys.iter()) {
+            self.expression(e);
+            values.push((self.temp(), ty.ty.clone(), self.expr_loc(e)));
+        }
+
+        let (data, len) = self.abi_encode(&[], &values, false);
+
+        for topic in topic_slots.iter().rev() {
+            self.load_slot(*topic);
+        }
+
+        let mut count = topic_slots.len() as u8;
+
+        if !event.anonymous {
+            let mut hasher = Keccak::v256();
+            let mut hash = [0u8; 32];
+            hasher.update(event.signature.as_bytes());
+            hasher.finalize(&mut hash);
+
+            self.asm
+                .push(&BigInt::from_bytes_be(num_bigint::Sign::Plus, &hash));
+            count += 1;
+        }
+
+        self.load_slot(len);
+        self.load_slot(data);
+        self.asm.op(LOG0 + count);
+    }
+}
@@ -0,0 +1,439 @@
+use super::assembler::*;
+use super::{EvmEmitter, Word};
+use crate::parser::pt;
+use crate::sema::ast::Type;
+use num_traits::ToPrimitive;
+
+impl<'a> EvmEmitter<'a> {
+    /// Replace the slot on the stack with the keccak256 hash of the slot; this is where the
+    /// contents of dynamic arrays, strings and bytes are stored
+    fn slot_hash(&mut self) {
+        self.asm.push_u64(0);
+        self.asm.op(MSTORE);
+        self.asm.push_u64(32);
+        self.asm.push_u64(0);
+        self.asm.op(SHA3);
+    }
+
+    /// Load a value from contract storage. The slot is on the stack, and is replaced with the value.
+    /// The location is of the storage reference, for diagnostics.
+    pub(super) fn storage_load(&mut self, loc: pt::Loc, ty: &Type) {
+        let ns = self.ns;
+
+        match ty.deref_any() {
+            Type::String | Type::DynamicBytes => {
+                let slot = self.temp();
+
+                self.load_slot(slot);
+                self.asm.op(SLOAD);
+                let len = self.temp();
+
+                self.push_word(Word::SlotPlus(len, 32));
+                self.alloc();
+                let ptr = self.temp();
+
+                self.load_slot(len);
+                self.load_slot(ptr);
+                self.asm.op(MSTORE);
+
+                self.load_slot(slot);
+                self.slot_hash();
+                let data = self.temp();
+
+                // number of words
+                self.push_word(Word::SlotPlus(len, 31));
+                self.asm.push_u64(5);
+                self.asm.op(SHR);
+                let words = self.temp();
+
+                let counter = self.new_slot();
+
+                self.emit_loop(counter, Word::Slot(words), |emitter| {
+                    emitter.load_slot(counter);
+                    emitter.load_slot(data);
+                    emitter.asm.op(ADD);
+                    emitter.asm.op(SLOAD);
+
+                    emitter.load_slot(counter);
+                    emitter.asm.push_u64(32);
+                    emitter.asm.op(MUL);
+                    emitter.push_word(Word::SlotPlus(ptr, 32));
+                    emitter.asm.op(ADD);
+                    emitter.asm.op(MSTORE);
+                });
+
+                self.load_slot(ptr);
+            }
+            Type::Struct(n) => {
+                let slot = self.temp();
+
+                let fields = &ns.structs[*n].fields;
+
+                self.alloc_const(fields.len() as u64 * 32);
+                let ptr = self.temp();
+
+                let mut offset = 0;
+
+                for (i, field) in fields.iter().enumerate() {
+                    self.push_word(Word::SlotPlus(slot, offset));
+                    self.storage_load(loc, &field.ty);
+                    self.push_word(Word::SlotPlus(ptr, i as u64 * 32));
+                    self.asm.op(MSTORE);
+
+                    offset += field.ty.storage_slots(ns).to_u64().unwrap();
+                }
+
+                self.load_slot(ptr);
+            }
+            Type::Array(_, dims) => {
+                let elem_ty = ty.deref_any().array_elem();
+                let elem_slots = elem_ty.storage_slots(ns).to_u64().unwrap();
+
+                let slot = self.temp();
+
+                let (len, data) = match dims.last().unwrap() {
+                    Some(len) => {
+                        self.asm.push_u64(len.to_u64().unwrap());
+                        let len = self.temp();
+
+                        self.push_word(Word::Slot(len));
+                        self.asm.push_u64(32);
+                        self.asm.op(MUL);
+                        self.alloc();
+
+                        (len, Word::Slot(slot))
+                    }
+                    None => {
+                        self.load_slot(slot);
+                        self.asm.op(SLOAD);
+                        let len = self.temp();
+
+                        self.load_slot(slot);
+                        self.slot_hash();
+                        let data = self.temp();
+
+                        self.load_slot(len);
+                        self.asm.push_u64(32);
+                        self.asm.op(MUL);
+                        self.asm.push_u64(32);
+                        self.asm.op(ADD);
+                        self.alloc();
+
+                        self.asm.op(DUP1);
+                        self.load_slot(len);
+                        self.asm.op(SWAP1);
+                        self.asm.op(MSTORE);
+                        self.asm.push_u64(32);
+                        self.asm.op(ADD);
+
+                        (len, Word::Slot(data))
+                    }
+                };
+
+                // pointer to the first element
+                let elems = self.temp();
+
+                let counter = self.new_slot();
+
+                self.emit_loop(counter, Word::Slot(len), |emitter| {
+                    emitter.load_slot(counter);
+                    emitter.asm.push_u64(elem_slots);
+                    emitter.asm.op(MUL);
+                    emitter.push_word(data);
+                    emitter.asm.op(ADD);
+                    emitter.storage_load(loc, &elem_ty);
+
+                    emitter.load_slot(counter);
+                    emitter.asm.push_u64(32);
+                    emitter.asm.op(MUL);
+                    emitter.load_slot(elems);
+                    emitter.asm.op(ADD);
+                    emitter.asm.op(MSTORE);
+                });
+
+                self.load_slot(elems);
+
+                if dims.last().unwrap().is_none() {
+                    // point to the length word
+                    self.asm.push_u64(32);
+                    self.asm.op(SWAP1);
+                    self.asm.op(SUB);
+                }
+            }
+            Type::ExternalFunction { .. } => {
+                self.unsupported(loc, "external function in storage");
+            }
+            _ => self.asm.op(SLOAD),
+        }
+    }
+
+    /// Store a value to contract storage. The stack holds the value and the slot. The location is
+    /// of the storage reference, for diagnostics.
+    pub(super) fn storage_store(&mut self, loc: pt::Loc, ty: &Type) {
+        let ns = self.ns;
+
+        match ty.deref_any() {
+            Type::String | Type::DynamicBytes => {
+                let slot = self.temp();
+                let ptr = self.temp();
+
+                self.load_slot(ptr);
+                self.asm.op(MLOAD);
+                let len = self.temp();
+
+                self.load_slot(len);
+                self.load_slot(slot);
+                self.asm.op(SSTORE);
+
+                self.load_slot(slot);
+                self.slot_hash();
+                let data = self.temp();
+
+                self.push_word(Word::SlotPlus(len, 31));
+                self.asm.push_u64(5);
+                self.asm.op(SHR);
+                let words = self.temp();
+
+                let counter = self.new_slot();
+
+                self.emit_loop(counter, Word::Slot(words), |emitter| {
+                    emitter.load_slot(counter);
+                    emitter.asm.push_u64(32);
+                    emitter.asm.op(MUL);
+                    emitter.push_word(Word::SlotPlus(ptr, 32));
+                    emitter.asm.op(ADD);
+                    emitter.asm.op(MLOAD);
+
+                    emitter.load_slot(counter);
+                    emitter.load_slot(data);
+                    emitter.asm.op(ADD);
+                    emitter.asm.op(SSTORE);
+                });
+
+                // memory after the end of the string might not be zero; clear the bytes
+                // beyond the end in the last word
+                let done = self.asm.new_label();
+
+                self.asm.push_u64(31);
+                self.load_slot(len);
+                self.asm.op(AND);
+                self.asm.op(ISZERO);
+                self.asm.jumpi(done);
+
+                // shift = 8 * (32 - len % 32)
+                self.asm.push_u64(31);
+                self.load_slot(len);
+                self.asm.op(AND);
+                self.asm.push_u64(32);
+                self.asm.op(SUB);
+                self.asm.push_u64(8);
+                self.asm.op(MUL);
+                let shift = self.temp();
+
+                self.load_slot(len);
+                self.asm.push_u64(5);
+                self.asm.op(SHR);
+                self.load_slot(data);
+                self.asm.op(ADD);
+                self.asm.op(SLOAD);
+                self.load_slot(shift);
+                self.asm.op(SHR);
+                self.load_slot(shift);
+                self.asm.op(SHL);
+
+                self.load_slot(len);
+                self.asm.push_u64(5);
+                self.asm.op(SHR);
+                self.load_slot(data);
+                self.asm.op(ADD);
+                self.asm.op(SSTORE);
+
+                self.asm.label(done);
+            }
+            Type::Struct(n) => {
+                let slot = self.temp();
+                let ptr = self.temp();
+
+                let mut offset = 0;
+
+                for (i, field) in ns.structs[*n].fields.iter().enumerate() {
+                    self.push_word(Word::SlotPlus(ptr, i as u64 * 32));
+                    self.asm.op(MLOAD);
+                    self.push_word(Word::SlotPlus(slot, offset));
+                    self.storage_store(loc, &field.ty);
+
+                    offset += field.ty.storage_slots(ns).to_u64().unwrap();
+                }
+            }
+            Type::Array(_, dims) => {
+                let elem_ty = ty.deref_any().array_elem();
+                let elem_slots = elem_ty.storage_slots(ns).to_u64().unwrap();
+
+                let slot = self.temp();
+                let ptr = self.temp();
+
+                let (len, data, elems) = match dims.last().unwrap() {
+                    Some(len) => {
+                        self.asm.push_u64(len.to_u64().unwrap());
+                        let len = self.temp();
+
+                        (len, Word::Slot(slot), Word::Slot(ptr))
+                    }
+                    None => {
+                        self.load_slot(ptr);
+                        self.asm.op(MLOAD);
+                        let len = self.temp();
+
+                        self.load_slot(len);
+                        self.load_slot(slot);
+                        self.asm.op(SSTORE);
+
+                        self.load_slot(slot);
+                        self.slot_hash();
+                        let data = self.temp();
+
+                        (len, Word::Slot(data), Word::SlotPlus(ptr, 32))
+                    }
+                };
+
+                let counter = self.new_slot();
+
+                self.emit_loop(counter, Word::Slot(len), |emitter| {
+                    emitter.load_slot(counter);
+                    emitter.asm.push_u64(32);
+                    emitter.asm.op(MUL);
+                    emitter.push_word(elems);
+                    emitter.asm.op(ADD);
+                    emitter.asm.op(MLOAD);
+
+                    emitter.load_slot(counter);
+                    emitter.asm.push_u64(elem_slots);
+                    emitter.asm.op(MUL);
+                    emitter.push_word(data);
+                    emitter.asm.op(ADD);
+
+                    emitter.storage_store(loc, &elem_ty);
+                });
+            }
+            Type::ExternalFunction { .. } => {
+                self.unsupported(loc, "external function in storage");
+            }
+            _ => self.asm.op(SSTORE),
+        }
+    }
+
+    /// Clear contract storage; the slot is on the stack
+    pub(super) fn storage_clear(&mut self, ty: &Type) {
+        let ns = self.ns;
+
+        match ty.deref_any() {
+            Type::Struct(n) => {
+                let slot = self.temp();
+
+                let mut offset = 0;
+
+                for field in &ns.structs[*n].fields {
+                    self.push_word(Word::SlotPlus(slot, offset));
+                    self.storage_clear(&field.ty);
+
+                    offset += field.ty.storage_slots(ns).to_u64().unwrap();
+                }
+            }
+            Type::Array(_, dims) if dims.last().unwrap().is_some() => {
+                let elem_ty = ty.deref_any().array_elem();
+                let elem_slots = elem_ty.storage_slots(ns).to_u64().unwrap();
+                let len = dims.last().unwrap().as_ref().unwrap().to_u64().unwrap();
+
+                let slot = self.temp();
+                let counter = self.new_slot();
+
+                self.emit_loop(counter, Word::Const(len), |emitter| {
+                    emitter.load_slot(counter);
+                    emitter.asm.push_u64(elem_slots);
+                    emitter.asm.op(MUL);
+                    emitter.load_slot(slot);
+                    emitter.asm.op(ADD);
+
+                    emitter.storage_clear(&elem_ty);
+                });
+            }
+            Type::Mapping(..) => {
+                // mappings cannot be cleared
+                self.asm.op(POP);
+            }
+            _ => {
+                // for dynamic arrays, string and bytes this sets the length to zero
+                self.asm.push_u64(0);
+                self.asm.op(SWAP1);
+                self.asm.op(SSTORE);
+            }
+        }
+    }
+
+    /// Revert if the index is out of bounds for bytes in storage
+    pub(super) fn storage_bytes_bounds_check(&mut self, slot: usize, index: usize) {
+        self.load_slot(slot);
+        self.asm.op(SLOAD);
+        self.load_slot(index);
+        self.asm.op(LT);
+        self.asm.op(ISZERO);
+        self.revert_if();
+    }
+
+    /// Push the byte at the index of bytes in storage
+    pub(super) fn storage_bytes_get(&mut self, slot: usize, index: usize) {
+        self.load_slot(index);
+        self.asm.push_u64(5);
+        self.asm.op(SHR);
+        self.load_slot(slot);
+        self.slot_hash();
+        self.asm.op(ADD);
+        self.asm.op(SLOAD);
+
+        self.load_slot(index);
+        self.asm.push_u64(31);
+        self.asm.op(AND);
+        self.asm.op(BYTE);
+    }
+
+    /// Set the byte at the index of bytes in storage
+    pub(super) fn storage_bytes_set(&mut self, slot: usize, index: usize, value: usize) {
+        self.load_slot(index);
+        self.asm.push_u64(5);
+        self.asm.op(SHR);
+        self.load_slot(slot);
+        self.slot_hash();
+        self.asm.op(ADD);
+        let word = self.temp();
+
+        // shift = 8 * (31 - index % 32)
+        self.load_slot(index);
+        self.asm.push_u64(31);
+        self.asm.op(AND);
+        self.asm.push_u64(31);
+        self.asm.op(SUB);
+        self.asm.push_u64(8);
+        self.asm.op(MUL);
+        let shift = self.temp();
+
+        // the existing word with the byte cleared
+        self.load_slot(shift);
+        self.asm.push_u64(0xff);
+        self.asm.op(SWAP1);
+        self.asm.op(SHL);
+        self.asm.op(NOT);
+        self.load_slot(word);
+        self.asm.op(SLOAD);
+        self.asm.op(AND);
+
+        self.load_slot(value);
+        self.asm.push_u64(0xff);
+        self.asm.op(AND);
+        self.load_slot(shift);
+        self.asm.op(SHL);
+        self.asm.op(OR);
+
+        self.load_slot(word);
+        self.asm.op(SSTORE);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::codegen::cfg::HashTy;
+use crate::codegen::reachable::{function_pointers_taken, reachable_cfgs};
 use crate::parser::pt;
 use crate::sema::ast;
 use std::cell::RefCell;
@@ -79,10 +80,28 @@ impl EwasmTarget {
         // externals
         b.declare_externals(&mut deploy_code);
 
-        // FIXME: this emits the constructors, as well as the functions. In Ethereum Solidity,
-        // no functions can be called from the constructor. We should either disallow this too
-        // and not emit functions, or use lto linking to optimize any unused functions away.
-        b.emit_functions(&mut deploy_code, contract, ns);
+        // The deployer only needs the constructor, the storage initializer and any functions
+        // they call; everything else lives in the runtime code.
+        let mut roots: Vec<usize> = contract
+            .cfg
+            .iter()
+            .enumerate()
+            .filter(|(_, cfg)| cfg.ty == pt::FunctionTy::Constructor)
+            .map(|(cfg_no, _)| cfg_no)
+            .collect();
+
+        roots.extend(contract.initializer);
+
+        let deployer_cfgs = reachable_cfgs(contract, &roots);
+
+        // An internal function pointer is an index in the function table. If the constructor
+        // takes one, it may store it for the runtime code to call, so the deployer must have
+        // the same functions, and so the same table, as the runtime code.
+        if !function_pointers_taken(contract, &deployer_cfgs).is_empty() {
+            b.emit_functions(&mut deploy_code, contract, ns);
+        } else {
+            b.emit_selected_functions(&mut deploy_code, contract, &deployer_cfgs, ns);
+        }
 
         b.deployer_dispatch(&mut deploy_code, contract, &runtime_bs, ns);
 
@@ -101,6 +120,7 @@ impl EwasmTarget {
             "callStatic",
             "callDelegate",
             "create",
+            "create2",
             "getReturnDataSize",
             "returnDataCopy",
             "getCallValue",
@@ -369,6 +389,21 @@ impl EwasmTarget {
             Some(Linkage::External),
         );
 
+        binary.module.add_function(
+            "create2",
+            u32_ty.fn_type(
+                &[
+                    u8_ptr_ty.into(), // valueOffset
+                    u8_ptr_ty.into(), // input offset
+                    u32_ty.into(),    // input length
+                    u8_ptr_ty.into(), // salt offset
+                    u8_ptr_ty.into(), // address result
+                ],
+                false,
+            ),
+            Some(Linkage::External),
+        );
+
         binary.module.add_function(
             "call",
             u32_ty.fn_type(
@@ -1018,6 +1053,35 @@ impl<'a> TargetRuntime<'a> for EwasmTarget {
             .into_int_value()
     }
 
+    /// Store an integer in big endian format at the destination, which is how ewasm
+    /// expects 256 bit values like salts and hashes
+    fn store_big_endian(&self, binary: &Binary, value: IntValue, dest: PointerValue) {
+        let temp = binary.builder.build_alloca(value.get_type(), "le_value");
+
+        binary.builder.build_store(temp, value);
+
+        binary.builder.build_call(
+            binary.module.get_function("__leNtobeN").unwrap(),
+            &[
+                binary
+                    .builder
+                    .build_pointer_cast(
+                        temp,
+                        binary.context.i8_type().ptr_type(AddressSpace::Generic),
+                        "",
+                    )
+                    .into(),
+                dest.into(),
+                binary
+                    .context
+                    .i32_type()
+                    .const_int(value.get_type().get_bit_width() as u64 / 8, false)
+                    .into(),
+            ],
+            "",
+        );
+    }
+
     /// ewasm has no keccak256 host function, so call our implementation
     fn keccak256_hash(
         &self,
@@ -1223,7 +1287,7 @@ impl<'a> TargetRuntime<'a> for EwasmTarget {
         args: &[BasicValueEnum<'b>],
         _gas: IntValue<'b>,
         value: Option<IntValue<'b>>,
-        _salt: Option<IntValue<'b>>,
+        salt: Option<IntValue<'b>>,
         ns: &ast::Namespace,
     ) {
         let resolver_binary = &ns.contracts[contract_no];
@@ -1285,49 +1349,60 @@ impl<'a> TargetRuntime<'a> for EwasmTarget {
             .builder
             .build_alloca(binary.address_type(ns), "be_address");
 
-        // call create
-        let ret = binary
-            .builder
-            .build_call(
+        let value_ptr = binary.builder.build_pointer_cast(
+            value_ptr,
+            binary.context.i8_type().ptr_type(AddressSpace::Generic),
+            "value_transfer",
+        );
+
+        let be_address = binary.builder.build_pointer_cast(
+            be_address,
+            binary.context.i8_type().ptr_type(AddressSpace::Generic),
+            "be_address",
+        );
+
+        // call create, or create2 if we have a salt
+        let ret = if let Some(salt) = salt {
+            let salt_buf = binary.builder.build_array_alloca(
+                binary.context.i8_type(),
+                binary.context.i32_type().const_int(32, false),
+                "salt",
+            );
+
+            self.store_big_endian(binary, salt, salt_buf);
+
+            binary.builder.build_call(
+                binary.module.get_function("create2").unwrap(),
+                &[
+                    value_ptr.into(),
+                    input.into(),
+                    input_len.into(),
+                    salt_buf.into(),
+                    be_address.into(),
+                ],
+                "",
+            )
+        } else {
+            binary.builder.build_call(
                 binary.module.get_function("create").unwrap(),
                 &[
-                    binary
-                        .builder
-                        .build_pointer_cast(
-                            value_ptr,
-                            binary.context.i8_type().ptr_type(AddressSpace::Generic),
-                            "value_transfer",
-                        )
-                        .into(),
+                    value_ptr.into(),
                     input.into(),
                     input_len.into(),
-                    binary
-                        .builder
-                        .build_pointer_cast(
-                            be_address,
-                            binary.context.i8_type().ptr_type(AddressSpace::Generic),
-                            "be_address",
-                        )
-                        .into(),
+                    be_address.into(),
                 ],
                 "",
             )
-            .try_as_basic_value()
-            .left()
-            .unwrap()
-            .into_int_value();
+        }
+        .try_as_basic_value()
+        .left()
+        .unwrap()
+        .into_int_value();
 
         binary.builder.build_call(
             binary.module.get_function("__beNtoleN").unwrap(),
             &[
-                binary
-                    .builder
-                    .build_pointer_cast(
-                        be_address,
-                        binary.context.i8_type().ptr_type(AddressSpace::Generic),
-                        "",
-                    )
-                    .into(),
+                be_address.into(),
                 binary
                     .builder
                     .build_pointer_cast(
@@ -2014,6 +2089,120 @@ impl<'a> TargetRuntime<'a> for EwasmTarget {
 
                 binary.builder.build_load(balance, "balance")
             }
+            ast::Expression::Builtin(_, _, ast::Builtin::PredictAddress, args) => {
+                let codehash = self
+                    .expression(binary, &args[0], vartab, function, ns)
+                    .into_int_value();
+                let salt = self
+                    .expression(binary, &args[1], vartab, function, ns)
+                    .into_int_value();
+
+                // keccak256(0xff ++ address ++ salt ++ keccak256(init_code))
+                let input_len = 1 + ns.address_length as u64 + 32 + 32;
+
+                let input = binary.builder.build_array_alloca(
+                    binary.context.i8_type(),
+                    binary.context.i32_type().const_int(input_len, false),
+                    "create2_input",
+                );
+
+                binary
+                    .builder
+                    .build_store(input, binary.context.i8_type().const_int(0xff, false));
+
+                let address_offset = unsafe {
+                    binary.builder.build_gep(
+                        input,
+                        &[binary.context.i32_type().const_int(1, false)],
+                        "address_offset",
+                    )
+                };
+
+                binary.builder.build_call(
+                    binary.module.get_function("getAddress").unwrap(),
+                    &[address_offset.into()],
+                    "self_address",
+                );
+
+                let salt_offset = unsafe {
+                    binary.builder.build_gep(
+                        input,
+                        &[binary
+                            .context
+                            .i32_type()
+                            .const_int(1 + ns.address_length as u64, false)],
+                        "salt_offset",
+                    )
+                };
+
+                self.store_big_endian(binary, salt, salt_offset);
+
+                let codehash_offset = unsafe {
+                    binary.builder.build_gep(
+                        input,
+                        &[binary
+                            .context
+                            .i32_type()
+                            .const_int(1 + ns.address_length as u64 + 32, false)],
+                        "codehash_offset",
+                    )
+                };
+
+                self.store_big_endian(binary, codehash, codehash_offset);
+
+                let hash = binary.builder.build_array_alloca(
+                    binary.context.i8_type(),
+                    binary.context.i32_type().const_int(32, false),
+                    "create2_hash",
+                );
+
+                self.keccak256_hash(
+                    binary,
+                    input,
+                    binary.context.i32_type().const_int(input_len, false),
+                    hash,
+                    ns,
+                );
+
+                // the address is the last 20 bytes of the hash
+                let hash_address = unsafe {
+                    binary.builder.build_gep(
+                        hash,
+                        &[binary
+                            .context
+                            .i32_type()
+                            .const_int(32 - ns.address_length as u64, false)],
+                        "hash_address",
+                    )
+                };
+
+                let address = binary
+                    .builder
+                    .build_alloca(binary.address_type(ns), "address");
+
+                binary.builder.build_call(
+                    binary.module.get_function("__beNtoleN").unwrap(),
+                    &[
+                        hash_address.into(),
+                        binary
+                            .builder
+                            .build_pointer_cast(
+                                address,
+                                binary.context.i8_type().ptr_type(AddressSpace::Generic),
+                                "",
+                            )
+                            .into(),
+                        binary
+                            .context
+                            .i32_type()
+                            .const_int(ns.address_length as u64, false)
+                            .into(),
+                    ],
+                    "",
+                );
+
+                binary.builder.build_load(address, "address")
+            }
             _ => unimplemented!(),
         }
     }
@@ -2,11 +2,13 @@ use crate::codegen::cfg::HashTy;
 use crate::parser::pt;
 use crate::sema::ast;
 use std::collections::HashMap;
+use std::fmt::Write;
 use std::str;
+use tiny_keccak::{Hasher, Keccak};
 
 use inkwell::context::Context;
 use inkwell::module::Linkage;
-use inkwell::types::IntType;
+use inkwell::types::{BasicTypeEnum, IntType};
 use inkwell::values::{BasicValueEnum, FunctionValue, IntValue, PointerValue};
 use inkwell::AddressSpace;
 use inkwell::IntPredicate;
@@ -15,6 +17,392 @@ use inkwell::OptimizationLevel;
 use super::ethabiencoder;
 use super::{Binary, TargetRuntime, Variable};
 
+const ADDRESS_LENGTH: usize = 20;
+const VALUE_LENGTH: usize = 16;
+
+/// Types used in the host interface of the generic target
+enum HostTy {
+    Void,
+    U32,
+    U64,
+    /// Pointer to a heap allocation
+    Mem,
+    /// Pointer to a buffer
+    Ptr,
+    /// Pointer to a 32 bytes storage key
+    Key,
+    /// Pointer to an address
+    Address,
+    /// Pointer to a value
+    Value,
+}
+
+impl HostTy {
+    /// C declaration of a parameter or return type
+    fn c_decl(&self, name: &str) -> String {
+        match self {
+            HostTy::Void => "void".to_string(),
+            HostTy::U32 => format!("uint32_t {}", name),
+            HostTy::U64 => format!("uint64_t {}", name),
+            HostTy::Mem => format!("void *{}", name),
+            HostTy::Ptr => format!("uint8_t *{}", name),
+            HostTy::Key => format!("uint8_t {}[32]", name),
+            HostTy::Address => format!("uint8_t {}[SOLANG_ADDRESS_LENGTH]", name),
+            HostTy::Value => format!("uint8_t {}[SOLANG_VALUE_LENGTH]", name),
+        }
+    }
+}
+
+/// A function which the host program must provide
+struct HostFunction {
+    name: &'static str,
+    ret: HostTy,
+    params: &'static [(HostTy, &'static str)],
+    doc: &'static str,
+}
+
+/// The host interface of the generic target. This is a stable interface; functions may be
+/// added but existing ones must not change.
+static HOST_FUNCTIONS: &[HostFunction] = &[
+    HostFunction {
+        name: "solang_malloc",
+        ret: HostTy::Mem,
+        params: &[(HostTy::U32, "size")],
+        doc: "Allocate memory. Solang never expects allocation to fail",
+    },
+    HostFunction {
+        name: "solang_realloc",
+        ret: HostTy::Mem,
+        params: &[(HostTy::Mem, "ptr"), (HostTy::U32, "size")],
+        doc: "Resize a memory allocation",
+    },
+    HostFunction {
+        name: "solang_free",
+        ret: HostTy::Void,
+        params: &[(HostTy::Mem, "ptr")],
+        doc: "Free memory allocation",
+    },
+    HostFunction {
+        name: "solang_storage_size",
+        ret: HostTy::U32,
+        params: &[(HostTy::Key, "key")],
+        doc: "Size of the storage entry, or 0 if it does not exist. Storage entries of length 0 are never set",
+    },
+    HostFunction {
+        name: "solang_storage_get",
+        ret: HostTy::Void,
+        params: &[(HostTy::Key, "key"), (HostTy::Ptr, "data")],
+        doc: "Copy the storage entry to data. Only called for entries which exist; the size is known from solang_storage_size()",
+    },
+    HostFunction {
+        name: "solang_storage_set",
+        ret: HostTy::Void,
+        params: &[(HostTy::Key, "key"), (HostTy::Ptr, "data"), (HostTy::U32, "size")],
+        doc: "Set the storage entry, replacing any existing entry",
+    },
+    HostFunction {
+        name: "solang_storage_delete",
+        ret: HostTy::Void,
+        params: &[(HostTy::Key, "key")],
+        doc: "Remove the storage entry, if it exists",
+    },
+    HostFunction {
+        name: "solang_set_return",
+        ret: HostTy::Void,
+        params: &[(HostTy::Ptr, "data"), (HostTy::U32, "size")],
+        doc: "Set the ABI encoded return data, or the revert reason",
+    },
+    HostFunction {
+        name: "solang_print",
+        ret: HostTy::Void,
+        params: &[(HostTy::Ptr, "data"), (HostTy::U32, "size")],
+        doc: "Debug print; the string is not zero terminated",
+    },
+    HostFunction {
+        name: "solang_emit_event",
+        ret: HostTy::Void,
+        params: &[
+            (HostTy::Ptr, "data"),
+            (HostTy::U32, "data_size"),
+            (HostTy::Ptr, "topics"),
+            (HostTy::U32, "topic_count"),
+        ],
+        doc: "Emit event with ABI encoded data. Each topic is 32 bytes",
+    },
+    HostFunction {
+        name: "solang_value_transferred",
+        ret: HostTy::Void,
+        params: &[(HostTy::Value, "value")],
+        doc: "Value transferred with the current call",
+    },
+    HostFunction {
+        name: "solang_balance",
+        ret: HostTy::Void,
+        params: &[(HostTy::Address, "address"), (HostTy::Value, "value")],
+        doc: "Balance of the given account",
+    },
+    HostFunction {
+        name: "solang_transfer",
+        ret: HostTy::U32,
+        params: &[(HostTy::Address, "address"), (HostTy::Value, "value")],
+        doc: "Transfer value to account. Returns 0 on success",
+    },
+    HostFunction {
+        name: "solang_address",
+        ret: HostTy::Void,
+        params: &[(HostTy::Address, "address")],
+        doc: "Address of this contract",
+    },
+    HostFunction {
+        name: "solang_caller",
+        ret: HostTy::Void,
+        params: &[(HostTy::Address, "address")],
+        doc: "Address of the caller",
+    },
+    HostFunction {
+        name: "solang_tx_origin",
+        ret: HostTy::Void,
+        params: &[(HostTy::Address, "address")],
+        doc: "Address of the account which signed the transaction",
+    },
+    HostFunction {
+        name: "solang_block_number",
+        ret: HostTy::U64,
+        params: &[],
+        doc: "Current block number",
+    },
+    HostFunction {
+        name: "solang_timestamp",
+        ret: HostTy::U64,
+        params: &[],
+        doc: "Timestamp of the current block",
+    },
+    HostFunction {
+        name: "solang_gasleft",
+        ret: HostTy::U64,
+        params: &[],
+        doc: "Gas remaining",
+    },
+    HostFunction {
+        name: "solang_gasprice",
+        ret: HostTy::Void,
+        params: &[(HostTy::Value, "value")],
+        doc: "Price of one unit of gas",
+    },
+    HostFunction {
+        name: "solang_call",
+        ret: HostTy::U32,
+        params: &[
+            (HostTy::U32, "call_type"),
+            (HostTy::Address, "address"),
+            (HostTy::Value, "value"),
+            (HostTy::U64, "gas"),
+            (HostTy::Ptr, "input"),
+            (HostTy::U32, "input_size"),
+        ],
+        doc: "Call another contract with ABI encoded input. The call type is one of SOLANG_CALL_*. Returns 0 on success",
+    },
+    HostFunction {
+        name: "solang_create",
+        ret: HostTy::U32,
+        params: &[
+            (HostTy::Ptr, "contract_name"),
+            (HostTy::U32, "contract_name_size"),
+            (HostTy::Ptr, "input"),
+            (HostTy::U32, "input_size"),
+            (HostTy::Value, "value"),
+            (HostTy::Ptr, "salt"),
+            (HostTy::Address, "address"),
+        ],
+        doc: "Create contract and call its constructor with ABI encoded input. The salt is NULL or 32 bytes. Returns 0 on success",
+    },
+    HostFunction {
+        name: "solang_return_data_size",
+        ret: HostTy::U32,
+        params: &[],
+        doc: "Size of the return data of the last call or create",
+    },
+    HostFunction {
+        name: "solang_return_data_copy",
+        ret: HostTy::Void,
+        params: &[(HostTy::Ptr, "data")],
+        doc: "Copy the return data of the last call or create",
+    },
+    HostFunction {
+        name: "solang_selfdestruct",
+        ret: HostTy::Void,
+        params: &[(HostTy::Address, "recipient")],
+        doc: "Destroy this contract and send its balance to recipient",
+    },
+    HostFunction {
+        name: "solang_sha256",
+        ret: HostTy::Void,
+        params: &[(HostTy::Ptr, "input"), (HostTy::U32, "size"), (HostTy::Ptr, "hash")],
+        doc: "Calculate sha256 hash (32 bytes)",
+    },
+    HostFunction {
+        name: "solang_ripemd160",
+        ret: HostTy::Void,
+        params: &[(HostTy::Ptr, "input"), (HostTy::U32, "size"), (HostTy::Ptr, "hash")],
+        doc: "Calculate ripemd160 hash (20 bytes)",
+    },
+    HostFunction {
+        name: "solang_blake2_128",
+        ret: HostTy::Void,
+        params: &[(HostTy::Ptr, "input"), (HostTy::U32, "size"), (HostTy::Ptr, "hash")],
+        doc: "Calculate blake2 128 bit hash (16 bytes)",
+    },
+    HostFunction {
+        name: "solang_blake2_256",
+        ret: HostTy::Void,
+        params: &[(HostTy::Ptr, "input"), (HostTy::U32, "size"), (HostTy::Ptr, "hash")],
+        doc: "Calculate blake2 256 bit hash (32 bytes)",
+    },
+];
+
+/// Generate a C header for a contract compiled for the generic target. This declares the
+/// functions exported by the contract, the function selectors and the host interface.
+pub fn c_header(contract: &ast::Contract, ns: &ast::Namespace) -> String {
+    let mut out = String::new();
+    let guard = format!("SOLANG_{}_H", contract.name.to_uppercase());
+
+    writeln!(
+        out,
+        "/* Generated by Solang for contract {}. Do not edit. */",
+        contract.name
+    )
+    .unwrap();
+    writeln!(out, "#ifndef {}\n#define {}\n", guard, guard).unwrap();
+    writeln!(out, "#include <stdint.h>\n").unwrap();
+
+    writeln!(out, "#define SOLANG_ADDRESS_LENGTH {}", ADDRESS_LENGTH).unwrap();
+    writeln!(out, "#define SOLANG_VALUE_LENGTH {}\n", VALUE_LENGTH).unwrap();
+
+    writeln!(
+        out,
+        "/* Return codes of solang_constructor() and solang_function() */"
+    )
+    .unwrap();
+    writeln!(out, "#define SOLANG_SUCCESS 0").unwrap();
+    writeln!(out, "#define SOLANG_REVERT 1").unwrap();
+    writeln!(out, "#define SOLANG_ABI_DECODING_INVALID 2").unwrap();
+    writeln!(out, "#define SOLANG_FUNCTION_SELECTOR_INVALID 3\n").unwrap();
+
+    writeln!(out, "/* Call types for solang_call() */").unwrap();
+    writeln!(out, "#define SOLANG_CALL_REGULAR 0").unwrap();
+    writeln!(out, "#define SOLANG_CALL_STATIC 1").unwrap();
+    writeln!(out, "#define SOLANG_CALL_DELEGATE 2\n").unwrap();
+
+    writeln!(
+        out,
+        "/* Entry points of the contract. The input is ABI encoded; the return data is\n   passed to solang_set_return() */"
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "int32_t solang_constructor(uint8_t *input, uint32_t input_size);"
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "int32_t solang_function(uint8_t *input, uint32_t input_size);\n"
+    )
+    .unwrap();
+
+    // public functions in the order of their cfg, which is deterministic
+    let mut functions: Vec<(usize, usize)> = contract
+        .all_functions
+        .iter()
+        .map(|(function_no, cfg_no)| (*cfg_no, *function_no))
+        .filter(|(cfg_no, _)| {
+            let cfg = &contract.cfg[*cfg_no];
+
+            cfg.public && cfg.ty == pt::FunctionTy::Function
+        })
+        .collect();
+
+    functions.sort_unstable();
+
+    writeln!(
+        out,
+        "/* Function selectors; the first four bytes of the input of solang_function(), big endian */"
+    )
+    .unwrap();
+
+    let mut selectors = Vec::new();
+
+    for (_, function_no) in functions {
+        let func = &ns.functions[function_no];
+        let selector = func.selector();
+
+        if selectors.contains(&selector) {
+            continue;
+        }
+
+        selectors.push(selector);
+
+        // overloaded functions are named after their signature
+        let name = if ns.functions.iter().enumerate().any(|(no, f)| {
+            no != function_no && f.name == func.name && contract.all_functions.contains_key(&no)
+        }) {
+            func.signature
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                .collect::<String>()
+                .trim_end_matches('_')
+                .to_string()
+        } else {
+            func.name.to_owned()
+        };
+
+        writeln!(
+            out,
+            "#define {}_{}_SELECTOR 0x{:08x} /* {} */",
+            contract.name.to_uppercase(),
+            name.to_uppercase(),
+            selector,
+            func.signature
+        )
+        .unwrap();
+    }
+
+    writeln!(
+        out,
+        "\n/* Functions which the host must provide. Addresses, values and salts are little endian */"
+    )
+    .unwrap();
+
+    for host in HOST_FUNCTIONS {
+        let params = if host.params.is_empty() {
+            String::from("void")
+        } else {
+            host.params
+                .iter()
+                .map(|(ty, name)| ty.c_decl(name))
+                .collect::<Vec<String>>()
+                .join(", ")
+        };
+
+        let ret = host.ret.c_decl("");
+        let ret = ret.trim_end();
+
+        writeln!(
+            out,
+            "\n/* {} */\n{}{}{}({});",
+            host.doc,
+            ret,
+            if ret.ends_with('*') { "" } else { " " },
+            host.name,
+            params
+        )
+        .unwrap();
+    }
+
+    writeln!(out, "\n#endif").unwrap();
+
+    out
+}
+
 pub struct GenericTarget {
     abi: ethabiencoder::EthAbiDecoder,
 }
@@ -43,7 +431,9 @@ impl GenericTarget {
         );
 
         // externals
-        b.declare_externals(&mut binary);
+        b.declare_externals(&mut binary, ns);
+
+        b.use_host_heap(&binary);
 
         b.emit_functions(&mut binary, contract, ns);
 
@@ -53,53 +443,72 @@ impl GenericTarget {
         binary
     }
 
-    fn declare_externals(&self, contract: &mut Binary) {
-        let void_ty = contract.context.void_type();
-        let u8_ptr = contract.context.i8_type().ptr_type(AddressSpace::Generic);
-        let u32_ty = contract.context.i32_type();
+    /// Declare the host interface; these are the functions listed in HOST_FUNCTIONS
+    fn declare_externals(&self, binary: &mut Binary, ns: &ast::Namespace) {
+        let void_ty = binary.context.void_type();
+        let u8_ptr = binary.context.i8_type().ptr_type(AddressSpace::Generic);
+        let u32_ty = binary.context.i32_type();
+        let u64_ty = binary.context.i64_type();
+
+        for host in HOST_FUNCTIONS {
+            let params: Vec<BasicTypeEnum> = host
+                .params
+                .iter()
+                .map(|(ty, _)| match ty {
+                    HostTy::U32 => u32_ty.into(),
+                    HostTy::U64 => u64_ty.into(),
+                    _ => u8_ptr.into(),
+                })
+                .collect();
+
+            let ftype = match host.ret {
+                HostTy::Void => void_ty.fn_type(&params, false),
+                HostTy::U32 => u32_ty.fn_type(&params, false),
+                HostTy::U64 => u64_ty.fn_type(&params, false),
+                _ => u8_ptr.fn_type(&params, false),
+            };
 
-        contract.module.add_function(
-            "solang_storage_delete",
-            void_ty.fn_type(&[u8_ptr.into()], false),
-            Some(Linkage::External),
-        );
-        contract.module.add_function(
-            "solang_storage_set",
-            void_ty.fn_type(
-                &[
-                    u8_ptr.into(),
-                    u8_ptr.into(),
-                    contract.context.i32_type().into(),
-                ],
-                false,
-            ),
-            Some(Linkage::External),
-        );
-        contract.module.add_function(
-            "solang_storage_size",
-            u32_ty.fn_type(&[u8_ptr.into()], false),
-            Some(Linkage::External),
-        );
-        contract.module.add_function(
-            "solang_storage_get",
-            void_ty.fn_type(&[u8_ptr.into(), u8_ptr.into()], false),
-            Some(Linkage::External),
-        );
-        contract.module.add_function(
-            "solang_malloc",
-            u8_ptr.fn_type(&[contract.context.i32_type().into()], false),
-            Some(Linkage::External),
-        );
-        contract.module.add_function(
-            "solang_print",
-            void_ty.fn_type(&[u8_ptr.into(), u32_ty.into()], false),
-            Some(Linkage::External),
-        );
-        contract.module.add_function(
-            "solang_set_return",
-            void_ty.fn_type(&[u8_ptr.into(), u32_ty.into()], false),
-            Some(Linkage::External),
-        );
+            binary
+                .module
+                .add_function(host.name, ftype, Some(Linkage::External));
+        }
+
+        // the C header has fixed address and value lengths
+        assert_eq!(ns.address_length, ADDRESS_LENGTH);
+        assert_eq!(ns.value_length, VALUE_LENGTH);
+    }
+
+    /// The generic target does not manage its own heap; the stdlib heap functions are
+    /// replaced with calls to the host heap functions.
+    fn use_host_heap(&self, binary: &Binary) {
+        for (stdlib, host) in &[
+            ("__malloc", "solang_malloc"),
+            ("__realloc", "solang_realloc"),
+            ("__free", "solang_free"),
+        ] {
+            let function = binary.module.get_function(stdlib).unwrap();
+
+            for bb in function.get_basic_blocks() {
+                unsafe { bb.delete() }.unwrap();
+            }
+
+            let entry = binary.context.append_basic_block(function, "entry");
+
+            binary.builder.position_at_end(entry);
+
+            let args: Vec<BasicValueEnum> = function.get_params();
+
+            let ret = binary
+                .builder
+                .build_call(binary.module.get_function(host).unwrap(), &args, "")
+                .try_as_basic_value()
+                .left();
+
+            match ret {
+                Some(ret) => binary.builder.build_return(Some(&ret)),
+                None => binary.builder.build_return(None),
+            };
+        }
     }
 
     fn emit_constructor(
@@ -123,19 +532,17 @@ impl GenericTarget {
 
         binary.builder.position_at_end(entry);
 
-        // we should not use our heap; use sabre provided heap instead
         let argsdata = function.get_nth_param(0).unwrap().into_pointer_value();
         let argslen = function.get_nth_param(1).unwrap().into_int_value();
 
         // init our storage vars
         binary.builder.build_call(initializer, &[], "");
 
-        if let Some((cfg_no, con)) = contract
-            .functions
+        if let Some((cfg_no, cfg)) = contract
+            .cfg
             .iter()
             .enumerate()
-            .map(|(cfg_no, function_no)| (cfg_no, &ns.functions[*function_no]))
-            .find(|(_, f)| f.is_constructor())
+            .find(|(_, cfg)| cfg.ty == pt::FunctionTy::Constructor)
         {
             let mut args = Vec::new();
 
@@ -146,7 +553,7 @@ impl GenericTarget {
                 &mut args,
                 argsdata,
                 argslen,
-                &con.params,
+                &cfg.params,
                 ns,
             );
 
@@ -179,7 +586,6 @@ impl GenericTarget {
 
         binary.builder.position_at_end(entry);
 
-        // we should not use our heap; use sabre provided heap instead
         let argsdata = function.get_nth_param(0).unwrap().into_pointer_value();
         let argslen = function.get_nth_param(1).unwrap().into_int_value();
 
@@ -202,21 +608,253 @@ impl GenericTarget {
             |_| false,
         );
     }
+
+    /// Get the size of a storage entry
+    fn storage_size<'b>(&self, binary: &Binary<'b>, slot: PointerValue<'b>) -> IntValue<'b> {
+        binary
+            .builder
+            .build_call(
+                binary.module.get_function("solang_storage_size").unwrap(),
+                &[binary
+                    .builder
+                    .build_pointer_cast(
+                        slot,
+                        binary.context.i8_type().ptr_type(AddressSpace::Generic),
+                        "",
+                    )
+                    .into()],
+                "storage_size",
+            )
+            .try_as_basic_value()
+            .left()
+            .unwrap()
+            .into_int_value()
+    }
+
+    /// Read a storage entry into a newly allocated buffer, which has room for `extra`
+    /// additional bytes. Returns the buffer and the size of the storage entry, which
+    /// is 0 if it does not exist.
+    fn storage_read<'b>(
+        &self,
+        binary: &Binary<'b>,
+        function: FunctionValue,
+        slot: PointerValue<'b>,
+        extra: u64,
+    ) -> (PointerValue<'b>, IntValue<'b>) {
+        let size = self.storage_size(binary, slot);
+
+        let buf = binary
+            .builder
+            .build_call(
+                binary.module.get_function("__malloc").unwrap(),
+                &[binary
+                    .builder
+                    .build_int_add(size, binary.context.i32_type().const_int(extra, false), "")
+                    .into()],
+                "",
+            )
+            .try_as_basic_value()
+            .left()
+            .unwrap()
+            .into_pointer_value();
+
+        let exists = binary.builder.build_int_compare(
+            IntPredicate::NE,
+            size,
+            binary.context.i32_type().const_zero(),
+            "storage_exists",
+        );
+
+        let retrieve_block = binary.context.append_basic_block(function, "in_storage");
+        let done_storage = binary.context.append_basic_block(function, "done_storage");
+
+        binary
+            .builder
+            .build_conditional_branch(exists, retrieve_block, done_storage);
+
+        binary.builder.position_at_end(retrieve_block);
+
+        binary.builder.build_call(
+            binary.module.get_function("solang_storage_get").unwrap(),
+            &[
+                binary
+                    .builder
+                    .build_pointer_cast(
+                        slot,
+                        binary.context.i8_type().ptr_type(AddressSpace::Generic),
+                        "",
+                    )
+                    .into(),
+                buf.into(),
+            ],
+            "",
+        );
+
+        binary.builder.build_unconditional_branch(done_storage);
+
+        binary.builder.position_at_end(done_storage);
+
+        (buf, size)
+    }
+
+    /// Write a storage entry. If the size is 0, the entry is removed
+    fn storage_write(
+        &self,
+        binary: &Binary,
+        function: FunctionValue,
+        slot: PointerValue,
+        data: PointerValue,
+        size: IntValue,
+    ) {
+        let exists = binary.builder.build_int_compare(
+            IntPredicate::NE,
+            size,
+            binary.context.i32_type().const_zero(),
+            "exists",
+        );
+
+        let delete_block = binary.context.append_basic_block(function, "delete_block");
+        let set_block = binary.context.append_basic_block(function, "set_block");
+        let done_storage = binary.context.append_basic_block(function, "done_storage");
+
+        binary
+            .builder
+            .build_conditional_branch(exists, set_block, delete_block);
+
+        binary.builder.position_at_end(set_block);
+
+        let slot = binary.builder.build_pointer_cast(
+            slot,
+            binary.context.i8_type().ptr_type(AddressSpace::Generic),
+            "slot",
+        );
+
+        binary.builder.build_call(
+            binary.module.get_function("solang_storage_set").unwrap(),
+            &[
+                slot.into(),
+                binary
+                    .builder
+                    .build_pointer_cast(
+                        data,
+                        binary.context.i8_type().ptr_type(AddressSpace::Generic),
+                        "",
+                    )
+                    .into(),
+                size.into(),
+            ],
+            "",
+        );
+
+        binary.builder.build_unconditional_branch(done_storage);
+
+        binary.builder.position_at_end(delete_block);
+
+        binary.builder.build_call(
+            binary.module.get_function("solang_storage_delete").unwrap(),
+            &[slot.into()],
+            "",
+        );
+
+        binary.builder.build_unconditional_branch(done_storage);
+
+        binary.builder.position_at_end(done_storage);
+    }
+
+    /// Abort with assert failure unless the index is less than the length
+    fn bounds_check(
+        &self,
+        binary: &Binary,
+        function: FunctionValue,
+        index: IntValue,
+        length: IntValue,
+    ) {
+        let in_range =
+            binary
+                .builder
+                .build_int_compare(IntPredicate::ULT, index, length, "index_in_range");
+
+        let retrieve_block = binary.context.append_basic_block(function, "in_range");
+        let bang_block = binary.context.append_basic_block(function, "bang_block");
+
+        binary
+            .builder
+            .build_conditional_branch(in_range, retrieve_block, bang_block);
+
+        binary.builder.position_at_end(bang_block);
+
+        self.assert_failure(
+            binary,
+            binary
+                .context
+                .i8_type()
+                .ptr_type(AddressSpace::Generic)
+                .const_null(),
+            binary.context.i32_type().const_zero(),
+        );
+
+        binary.builder.position_at_end(retrieve_block);
+    }
+
+    /// Abort with assert failure if the host call did not return 0, unless we are in a
+    /// try statement; then set the success variable instead.
+    fn check_return<'b>(
+        &self,
+        binary: &Binary<'b>,
+        function: FunctionValue,
+        success: Option<&mut BasicValueEnum<'b>>,
+        ret: IntValue<'b>,
+    ) {
+        let is_success = binary.builder.build_int_compare(
+            IntPredicate::EQ,
+            ret,
+            binary.context.i32_type().const_zero(),
+            "success",
+        );
+
+        if let Some(success) = success {
+            *success = is_success.into();
+        } else {
+            let success_block = binary.context.append_basic_block(function, "success");
+            let bail_block = binary.context.append_basic_block(function, "bail");
+            binary
+                .builder
+                .build_conditional_branch(is_success, success_block, bail_block);
+
+            binary.builder.position_at_end(bail_block);
+
+            self.assert_failure(
+                binary,
+                binary
+                    .context
+                    .i8_type()
+                    .ptr_type(AddressSpace::Generic)
+                    .const_null(),
+                binary.context.i32_type().const_zero(),
+            );
+
+            binary.builder.position_at_end(success_block);
+        }
+    }
 }
 
 impl<'a> TargetRuntime<'a> for GenericTarget {
     fn storage_delete_single_slot(
         &self,
-        contract: &Binary,
+        binary: &Binary,
         _function: FunctionValue,
         slot: PointerValue,
     ) {
-        contract.builder.build_call(
-            contract
-                .module
-                .get_function("solang_storage_delete")
-                .unwrap(),
-            &[slot.into()],
+        binary.builder.build_call(
+            binary.module.get_function("solang_storage_delete").unwrap(),
+            &[binary
+                .builder
+                .build_pointer_cast(
+                    slot,
+                    binary.context.i8_type().ptr_type(AddressSpace::Generic),
+                    "",
+                )
+                .into()],
             "",
         );
     }
@@ -228,7 +866,6 @@ impl<'a> TargetRuntime<'a> for GenericTarget {
         slot: PointerValue,
         dest: PointerValue,
     ) {
-        // TODO: check for non-zero
         contract.builder.build_call(
             contract.module.get_function("solang_storage_set").unwrap(),
             &[
@@ -259,98 +896,343 @@ impl<'a> TargetRuntime<'a> for GenericTarget {
         );
     }
 
-    fn set_storage_string(
-        &self,
-        contract: &Binary<'a>,
-        _function: FunctionValue<'a>,
-        slot: PointerValue<'a>,
-        dest: BasicValueEnum<'a>,
-    ) {
-        // TODO: check for non-zero
-        contract.builder.build_call(
-            contract.module.get_function("solang_storage_set").unwrap(),
+    fn set_storage_string(
+        &self,
+        binary: &Binary<'a>,
+        function: FunctionValue<'a>,
+        slot: PointerValue<'a>,
+        dest: BasicValueEnum<'a>,
+    ) {
+        // an empty string or bytes is not stored
+        self.storage_write(
+            binary,
+            function,
+            slot,
+            binary.vector_bytes(dest),
+            binary.vector_len(dest),
+        );
+    }
+
+    fn get_storage_string(
+        &self,
+        binary: &Binary<'a>,
+        function: FunctionValue,
+        slot: PointerValue<'a>,
+    ) -> PointerValue<'a> {
+        let size = self.storage_size(binary, slot);
+
+        // vector_new() clears the vector if the initializer is -1
+        let v = binary
+            .builder
+            .build_call(
+                binary.module.get_function("vector_new").unwrap(),
+                &[
+                    size.into(),
+                    binary.context.i32_type().const_int(1, false).into(),
+                    binary
+                        .context
+                        .i32_type()
+                        .const_all_ones()
+                        .const_to_pointer(binary.context.i8_type().ptr_type(AddressSpace::Generic))
+                        .into(),
+                ],
+                "",
+            )
+            .try_as_basic_value()
+            .left()
+            .unwrap()
+            .into_pointer_value();
+
+        let exists = binary.builder.build_int_compare(
+            IntPredicate::NE,
+            size,
+            binary.context.i32_type().const_zero(),
+            "storage_exists",
+        );
+
+        let retrieve_block = binary.context.append_basic_block(function, "in_storage");
+        let done_storage = binary.context.append_basic_block(function, "done_storage");
+
+        binary
+            .builder
+            .build_conditional_branch(exists, retrieve_block, done_storage);
+
+        binary.builder.position_at_end(retrieve_block);
+
+        binary.builder.build_call(
+            binary.module.get_function("solang_storage_get").unwrap(),
+            &[
+                binary
+                    .builder
+                    .build_pointer_cast(
+                        slot,
+                        binary.context.i8_type().ptr_type(AddressSpace::Generic),
+                        "",
+                    )
+                    .into(),
+                binary.vector_bytes(v.into()).into(),
+            ],
+            "",
+        );
+
+        binary.builder.build_unconditional_branch(done_storage);
+
+        binary.builder.position_at_end(done_storage);
+
+        v
+    }
+
+    fn get_storage_bytes_subscript(
+        &self,
+        binary: &Binary<'a>,
+        function: FunctionValue,
+        slot: IntValue<'a>,
+        index: IntValue<'a>,
+    ) -> IntValue<'a> {
+        let slot_ptr = binary.builder.build_alloca(slot.get_type(), "slot");
+        binary.builder.build_store(slot_ptr, slot);
+
+        let (data, size) = self.storage_read(binary, function, slot_ptr, 0);
+
+        self.bounds_check(binary, function, index, size);
+
+        let offset = unsafe { binary.builder.build_gep(data, &[index], "data_offset") };
+
+        let val = binary.builder.build_load(offset, "value").into_int_value();
+
+        binary.builder.build_call(
+            binary.module.get_function("__free").unwrap(),
+            &[data.into()],
+            "",
+        );
+
+        val
+    }
+
+    fn set_storage_bytes_subscript(
+        &self,
+        binary: &Binary<'a>,
+        function: FunctionValue,
+        slot: IntValue<'a>,
+        index: IntValue<'a>,
+        val: IntValue<'a>,
+    ) {
+        let slot_ptr = binary.builder.build_alloca(slot.get_type(), "slot");
+        binary.builder.build_store(slot_ptr, slot);
+
+        let (data, size) = self.storage_read(binary, function, slot_ptr, 0);
+
+        self.bounds_check(binary, function, index, size);
+
+        let offset = unsafe { binary.builder.build_gep(data, &[index], "data_offset") };
+
+        binary.builder.build_store(offset, val);
+
+        self.storage_write(binary, function, slot_ptr, data, size);
+
+        binary.builder.build_call(
+            binary.module.get_function("__free").unwrap(),
+            &[data.into()],
+            "",
+        );
+    }
+
+    fn set_storage_extfunc(
+        &self,
+        binary: &Binary,
+        _function: FunctionValue,
+        slot: PointerValue,
+        dest: PointerValue,
+    ) {
+        binary.builder.build_call(
+            binary.module.get_function("solang_storage_set").unwrap(),
+            &[
+                binary
+                    .builder
+                    .build_pointer_cast(
+                        slot,
+                        binary.context.i8_type().ptr_type(AddressSpace::Generic),
+                        "",
+                    )
+                    .into(),
+                binary
+                    .builder
+                    .build_pointer_cast(
+                        dest,
+                        binary.context.i8_type().ptr_type(AddressSpace::Generic),
+                        "",
+                    )
+                    .into(),
+                dest.get_type()
+                    .get_element_type()
+                    .size_of()
+                    .unwrap()
+                    .const_cast(binary.context.i32_type(), false)
+                    .into(),
+            ],
+            "",
+        );
+    }
+
+    fn get_storage_extfunc(
+        &self,
+        binary: &Binary<'a>,
+        function: FunctionValue,
+        slot: PointerValue<'a>,
+        ns: &ast::Namespace,
+    ) -> PointerValue<'a> {
+        let ty = binary.llvm_type(
+            &ast::Type::ExternalFunction {
+                params: Vec::new(),
+                mutability: None,
+                returns: Vec::new(),
+            },
+            ns,
+        );
+
+        let len = ty
+            .into_pointer_type()
+            .get_element_type()
+            .size_of()
+            .unwrap()
+            .const_cast(binary.context.i32_type(), false);
+
+        let (data, size) = self.storage_read(binary, function, slot, 0);
+
+        // an external function which was never set is all zeros
+        let exists = binary
+            .builder
+            .build_int_compare(IntPredicate::EQ, size, len, "exists");
+
+        let entry = binary.builder.get_insert_block().unwrap();
+        let clear_block = binary.context.append_basic_block(function, "clear");
+        let done_block = binary.context.append_basic_block(function, "done");
+
+        binary
+            .builder
+            .build_conditional_branch(exists, done_block, clear_block);
+
+        binary.builder.position_at_end(clear_block);
+
+        let cleared = binary
+            .builder
+            .build_call(
+                binary.module.get_function("__realloc").unwrap(),
+                &[data.into(), len.into()],
+                "",
+            )
+            .try_as_basic_value()
+            .left()
+            .unwrap()
+            .into_pointer_value();
+
+        binary.builder.build_call(
+            binary.module.get_function("__bzero8").unwrap(),
             &[
-                contract
+                cleared.into(),
+                binary
                     .builder
-                    .build_pointer_cast(
-                        slot,
-                        contract.context.i8_type().ptr_type(AddressSpace::Generic),
-                        "",
-                    )
+                    .build_int_unsigned_div(len, binary.context.i32_type().const_int(8, false), "")
                     .into(),
-                contract.vector_bytes(dest).into(),
-                contract.vector_len(dest).into(),
             ],
             "",
         );
-    }
 
-    fn get_storage_string(
-        &self,
-        _contract: &Binary<'a>,
-        _function: FunctionValue,
-        _slot: PointerValue<'a>,
-    ) -> PointerValue<'a> {
-        unimplemented!();
-    }
-    fn get_storage_bytes_subscript(
-        &self,
-        _contract: &Binary<'a>,
-        _function: FunctionValue,
-        _slot: IntValue<'a>,
-        _index: IntValue<'a>,
-    ) -> IntValue<'a> {
-        unimplemented!();
-    }
-    fn set_storage_extfunc(
-        &self,
-        _contract: &Binary,
-        _function: FunctionValue,
-        _slot: PointerValue,
-        _dest: PointerValue,
-    ) {
-        unimplemented!();
-    }
-    fn get_storage_extfunc(
-        &self,
-        _contract: &Binary<'a>,
-        _function: FunctionValue,
-        _slot: PointerValue<'a>,
-        _ns: &ast::Namespace,
-    ) -> PointerValue<'a> {
-        unimplemented!();
-    }
-    fn set_storage_bytes_subscript(
-        &self,
-        _contract: &Binary,
-        _function: FunctionValue,
-        _slot: IntValue,
-        _index: IntValue,
-        _val: IntValue,
-    ) {
-        unimplemented!();
+        binary.builder.build_unconditional_branch(done_block);
+
+        binary.builder.position_at_end(done_block);
+
+        let ef = binary.builder.build_phi(
+            binary.context.i8_type().ptr_type(AddressSpace::Generic),
+            "ef",
+        );
+
+        ef.add_incoming(&[(&data, entry), (&cleared, clear_block)]);
+
+        binary.builder.build_pointer_cast(
+            ef.as_basic_value().into_pointer_value(),
+            ty.into_pointer_type(),
+            "function_type",
+        )
     }
+
     fn storage_push(
         &self,
-        _contract: &Binary<'a>,
-        _function: FunctionValue,
+        binary: &Binary<'a>,
+        function: FunctionValue,
         _ty: &ast::Type,
-        _slot: IntValue<'a>,
-        _val: BasicValueEnum<'a>,
+        slot: IntValue<'a>,
+        val: BasicValueEnum<'a>,
         _ns: &ast::Namespace,
     ) -> BasicValueEnum<'a> {
-        unimplemented!();
+        let slot_ptr = binary.builder.build_alloca(slot.get_type(), "slot");
+        binary.builder.build_store(slot_ptr, slot);
+
+        let (data, size) = self.storage_read(binary, function, slot_ptr, 1);
+
+        let offset = unsafe { binary.builder.build_gep(data, &[size], "data_offset") };
+
+        binary.builder.build_store(offset, val);
+
+        let new_size = binary.builder.build_int_add(
+            size,
+            binary.context.i32_type().const_int(1, false),
+            "new_size",
+        );
+
+        self.storage_write(binary, function, slot_ptr, data, new_size);
+
+        binary.builder.build_call(
+            binary.module.get_function("__free").unwrap(),
+            &[data.into()],
+            "",
+        );
+
+        val
     }
+
     fn storage_pop(
         &self,
-        _contract: &Binary<'a>,
-        _function: FunctionValue,
+        binary: &Binary<'a>,
+        function: FunctionValue,
         _ty: &ast::Type,
-        _slot: IntValue<'a>,
+        slot: IntValue<'a>,
         _ns: &ast::Namespace,
     ) -> BasicValueEnum<'a> {
-        unimplemented!();
+        let slot_ptr = binary.builder.build_alloca(slot.get_type(), "slot");
+        binary.builder.build_store(slot_ptr, slot);
+
+        let (data, size) = self.storage_read(binary, function, slot_ptr, 0);
+
+        // pop from empty bytes fails
+        self.bounds_check(
+            binary,
+            function,
+            binary.context.i32_type().const_zero(),
+            size,
+        );
+
+        let new_size = binary.builder.build_int_sub(
+            size,
+            binary.context.i32_type().const_int(1, false),
+            "new_size",
+        );
+
+        let offset = unsafe { binary.builder.build_gep(data, &[new_size], "data_offset") };
+
+        let val = binary.builder.build_load(offset, "popped_value");
+
+        self.storage_write(binary, function, slot_ptr, data, new_size);
+
+        binary.builder.build_call(
+            binary.module.get_function("__free").unwrap(),
+            &[data.into()],
+            "",
+        );
+
+        val
     }
+
     fn storage_array_length(
         &self,
         contract: &Binary<'a>,
@@ -466,7 +1348,7 @@ impl<'a> TargetRuntime<'a> for GenericTarget {
         res.as_basic_value().into_int_value()
     }
 
-    /// sabre has no keccak256 host function, so call our implementation
+    /// the host does not provide keccak256, so call our implementation
     fn keccak256_hash(
         &self,
         contract: &Binary,
@@ -524,9 +1406,10 @@ impl<'a> TargetRuntime<'a> for GenericTarget {
             &[data.into(), length.into()],
             "",
         );
+        // return 1 for revert
         contract
             .builder
-            .build_return(Some(&contract.context.i32_type().const_int(2, false)));
+            .build_return(Some(&contract.context.i32_type().const_int(1, false)));
     }
 
     /// ABI encode into a vector for abi.encode* style builtin functions
@@ -609,91 +1492,588 @@ impl<'a> TargetRuntime<'a> for GenericTarget {
     /// Create new contract
     fn create_contract<'b>(
         &mut self,
-        _binary: &Binary<'b>,
-        _function: FunctionValue,
-        _success: Option<&mut BasicValueEnum<'b>>,
-        _contract_no: usize,
-        _constructor_no: Option<usize>,
-        _address: PointerValue<'b>,
-        _args: &[BasicValueEnum],
+        binary: &Binary<'b>,
+        function: FunctionValue<'b>,
+        success: Option<&mut BasicValueEnum<'b>>,
+        contract_no: usize,
+        constructor_no: Option<usize>,
+        address: PointerValue<'b>,
+        args: &[BasicValueEnum<'b>],
         _gas: IntValue<'b>,
-        _value: Option<IntValue<'b>>,
-        _salt: Option<IntValue<'b>>,
-        _ns: &ast::Namespace,
+        value: Option<IntValue<'b>>,
+        salt: Option<IntValue<'b>>,
+        ns: &ast::Namespace,
     ) {
-        panic!("generic cannot create new contracts");
+        let tys: Vec<ast::Type> = match constructor_no {
+            Some(function_no) => ns.functions[function_no]
+                .params
+                .iter()
+                .map(|p| p.ty.clone())
+                .collect(),
+            None => Vec::new(),
+        };
+
+        let (input, input_len) = self.abi_encode(binary, None, false, function, args, &tys, ns);
+
+        // the host knows the contract by name
+        let name = &ns.contracts[contract_no].name;
+
+        let contract_name =
+            binary.emit_global_string(&format!("contract_{}_name", name), name.as_bytes(), true);
+
+        let value_ptr = binary.builder.build_alloca(binary.value_type(ns), "value");
+
+        binary.builder.build_store(
+            value_ptr,
+            match value {
+                Some(v) => v,
+                None => binary.value_type(ns).const_zero(),
+            },
+        );
+
+        let salt_ptr = match salt {
+            Some(salt) => {
+                let salt_ptr = binary.builder.build_alloca(salt.get_type(), "salt");
+
+                binary.builder.build_store(salt_ptr, salt);
+
+                binary.builder.build_pointer_cast(
+                    salt_ptr,
+                    binary.context.i8_type().ptr_type(AddressSpace::Generic),
+                    "salt",
+                )
+            }
+            None => binary
+                .context
+                .i8_type()
+                .ptr_type(AddressSpace::Generic)
+                .const_null(),
+        };
+
+        let ret = binary
+            .builder
+            .build_call(
+                binary.module.get_function("solang_create").unwrap(),
+                &[
+                    contract_name.into(),
+                    binary
+                        .context
+                        .i32_type()
+                        .const_int(name.len() as u64, false)
+                        .into(),
+                    input.into(),
+                    input_len.into(),
+                    binary
+                        .builder
+                        .build_pointer_cast(
+                            value_ptr,
+                            binary.context.i8_type().ptr_type(AddressSpace::Generic),
+                            "value",
+                        )
+                        .into(),
+                    salt_ptr.into(),
+                    binary
+                        .builder
+                        .build_pointer_cast(
+                            address,
+                            binary.context.i8_type().ptr_type(AddressSpace::Generic),
+                            "address",
+                        )
+                        .into(),
+                ],
+                "",
+            )
+            .try_as_basic_value()
+            .left()
+            .unwrap()
+            .into_int_value();
+
+        self.check_return(binary, function, success, ret);
     }
 
     /// Call external contract
     fn external_call<'b>(
         &self,
-        _binary: &Binary<'b>,
-        _function: FunctionValue,
-        _success: Option<&mut BasicValueEnum<'b>>,
-        _payload: PointerValue<'b>,
-        _payload_len: IntValue<'b>,
-        _address: Option<PointerValue<'b>>,
-        _gas: IntValue<'b>,
-        _value: IntValue<'b>,
-        _ty: ast::CallTy,
-        _ns: &ast::Namespace,
+        binary: &Binary<'b>,
+        function: FunctionValue,
+        success: Option<&mut BasicValueEnum<'b>>,
+        payload: PointerValue<'b>,
+        payload_len: IntValue<'b>,
+        address: Option<PointerValue<'b>>,
+        gas: IntValue<'b>,
+        value: IntValue<'b>,
+        ty: ast::CallTy,
+        ns: &ast::Namespace,
+    ) {
+        let value_ptr = binary.builder.build_alloca(binary.value_type(ns), "value");
+
+        binary.builder.build_store(value_ptr, value);
+
+        let call_type = match ty {
+            ast::CallTy::Regular => 0,
+            ast::CallTy::Static => 1,
+            ast::CallTy::Delegate => 2,
+        };
+
+        let ret = binary
+            .builder
+            .build_call(
+                binary.module.get_function("solang_call").unwrap(),
+                &[
+                    binary.context.i32_type().const_int(call_type, false).into(),
+                    binary
+                        .builder
+                        .build_pointer_cast(
+                            address.unwrap(),
+                            binary.context.i8_type().ptr_type(AddressSpace::Generic),
+                            "address",
+                        )
+                        .into(),
+                    binary
+                        .builder
+                        .build_pointer_cast(
+                            value_ptr,
+                            binary.context.i8_type().ptr_type(AddressSpace::Generic),
+                            "value",
+                        )
+                        .into(),
+                    gas.into(),
+                    payload.into(),
+                    payload_len.into(),
+                ],
+                "",
+            )
+            .try_as_basic_value()
+            .left()
+            .unwrap()
+            .into_int_value();
+
+        self.check_return(binary, function, success, ret);
+    }
+
+    /// Send value to address
+    fn value_transfer<'b>(
+        &self,
+        binary: &Binary<'b>,
+        function: FunctionValue,
+        success: Option<&mut BasicValueEnum<'b>>,
+        address: PointerValue<'b>,
+        value: IntValue<'b>,
+        ns: &ast::Namespace,
     ) {
-        panic!("generic cannot call other contracts");
+        let value_ptr = binary.builder.build_alloca(binary.value_type(ns), "value");
+
+        binary.builder.build_store(value_ptr, value);
+
+        let ret = binary
+            .builder
+            .build_call(
+                binary.module.get_function("solang_transfer").unwrap(),
+                &[
+                    binary
+                        .builder
+                        .build_pointer_cast(
+                            address,
+                            binary.context.i8_type().ptr_type(AddressSpace::Generic),
+                            "address",
+                        )
+                        .into(),
+                    binary
+                        .builder
+                        .build_pointer_cast(
+                            value_ptr,
+                            binary.context.i8_type().ptr_type(AddressSpace::Generic),
+                            "value",
+                        )
+                        .into(),
+                ],
+                "",
+            )
+            .try_as_basic_value()
+            .left()
+            .unwrap()
+            .into_int_value();
+
+        self.check_return(binary, function, success, ret);
     }
 
     /// Get return buffer for external call
-    fn return_data<'b>(&self, _binary: &Binary<'b>) -> PointerValue<'b> {
-        panic!("generic cannot call other contracts");
+    fn return_data<'b>(&self, binary: &Binary<'b>) -> PointerValue<'b> {
+        let length = binary
+            .builder
+            .build_call(
+                binary
+                    .module
+                    .get_function("solang_return_data_size")
+                    .unwrap(),
+                &[],
+                "returndatasize",
+            )
+            .try_as_basic_value()
+            .left()
+            .unwrap()
+            .into_int_value();
+
+        let v = binary
+            .builder
+            .build_call(
+                binary.module.get_function("vector_new").unwrap(),
+                &[
+                    length.into(),
+                    binary.context.i32_type().const_int(1, false).into(),
+                    binary
+                        .context
+                        .i32_type()
+                        .const_all_ones()
+                        .const_to_pointer(binary.context.i8_type().ptr_type(AddressSpace::Generic))
+                        .into(),
+                ],
+                "",
+            )
+            .try_as_basic_value()
+            .left()
+            .unwrap()
+            .into_pointer_value();
+
+        binary.builder.build_call(
+            binary
+                .module
+                .get_function("solang_return_data_copy")
+                .unwrap(),
+            &[binary.vector_bytes(v.into()).into()],
+            "",
+        );
+
+        v
     }
 
     fn return_code<'b>(&self, binary: &'b Binary, ret: IntValue<'b>) {
         binary.builder.build_return(Some(&ret));
     }
 
-    /// Sabre does not know about balances
+    /// Value is passed as a 128 bit little endian integer
     fn value_transferred<'b>(&self, binary: &Binary<'b>, ns: &ast::Namespace) -> IntValue<'b> {
-        binary.value_type(ns).const_zero()
+        let value = binary
+            .builder
+            .build_alloca(binary.value_type(ns), "value_transferred");
+
+        binary.builder.build_call(
+            binary
+                .module
+                .get_function("solang_value_transferred")
+                .unwrap(),
+            &[binary
+                .builder
+                .build_pointer_cast(
+                    value,
+                    binary.context.i8_type().ptr_type(AddressSpace::Generic),
+                    "",
+                )
+                .into()],
+            "",
+        );
+
+        binary
+            .builder
+            .build_load(value, "value_transferred")
+            .into_int_value()
     }
 
     /// Terminate execution, destroy contract and send remaining funds to addr
-    fn selfdestruct<'b>(&self, _binary: &Binary<'b>, _addr: IntValue<'b>, _ns: &ast::Namespace) {
-        panic!("generic does not have the concept of selfdestruct");
+    fn selfdestruct<'b>(&self, binary: &Binary<'b>, addr: IntValue<'b>, ns: &ast::Namespace) {
+        let address = binary
+            .builder
+            .build_alloca(binary.address_type(ns), "address");
+
+        binary.builder.build_store(address, addr);
+
+        binary.builder.build_call(
+            binary.module.get_function("solang_selfdestruct").unwrap(),
+            &[binary
+                .builder
+                .build_pointer_cast(
+                    address,
+                    binary.context.i8_type().ptr_type(AddressSpace::Generic),
+                    "",
+                )
+                .into()],
+            "",
+        );
     }
 
-    /// Send event
+    /// Send event. The topics are passed to the host as a single buffer of 32 byte topics
     fn send_event<'b>(
         &self,
-        _binary: &Binary<'b>,
-        _event_no: usize,
-        _data: PointerValue<'b>,
-        _data_len: IntValue<'b>,
-        _topics: Vec<(PointerValue<'b>, IntValue<'b>)>,
-        _ns: &ast::Namespace,
+        binary: &Binary<'b>,
+        event_no: usize,
+        data: PointerValue<'b>,
+        data_len: IntValue<'b>,
+        topics: Vec<(PointerValue<'b>, IntValue<'b>)>,
+        ns: &ast::Namespace,
     ) {
-        unimplemented!();
+        let event = &ns.events[event_no];
+
+        let topic_count = topics.len() + if event.anonymous { 0 } else { 1 };
+
+        let topic_buf = binary.builder.build_array_alloca(
+            binary.context.i8_type(),
+            binary
+                .context
+                .i32_type()
+                .const_int(topic_count as u64 * 32, false),
+            "topics",
+        );
+
+        let mut offset = 0;
+
+        if !event.anonymous {
+            let mut hasher = Keccak::v256();
+            hasher.update(event.signature.as_bytes());
+            let mut hash = [0u8; 32];
+            hasher.finalize(&mut hash);
+
+            let signature =
+                binary.emit_global_string(&format!("event_{}_signature", event), &hash, true);
+
+            binary.builder.build_call(
+                binary.module.get_function("__memcpy").unwrap(),
+                &[
+                    topic_buf.into(),
+                    signature.into(),
+                    binary.context.i32_type().const_int(32, false).into(),
+                ],
+                "",
+            );
+
+            offset += 32;
+        }
+
+        for (ptr, len) in topics.into_iter() {
+            let dest = unsafe {
+                binary.builder.build_gep(
+                    topic_buf,
+                    &[binary.context.i32_type().const_int(offset, false)],
+                    "topic",
+                )
+            };
+
+            if let Some(32) = len.get_zero_extended_constant() {
+                binary.builder.build_call(
+                    binary.module.get_function("__memcpy").unwrap(),
+                    &[
+                        dest.into(),
+                        binary
+                            .builder
+                            .build_pointer_cast(
+                                ptr,
+                                binary.context.i8_type().ptr_type(AddressSpace::Generic),
+                                "",
+                            )
+                            .into(),
+                        len.into(),
+                    ],
+                    "",
+                );
+            } else {
+                self.keccak256_hash(binary, ptr, len, dest, ns);
+            }
+
+            offset += 32;
+        }
+
+        binary.builder.build_call(
+            binary.module.get_function("solang_emit_event").unwrap(),
+            &[
+                binary
+                    .builder
+                    .build_pointer_cast(
+                        data,
+                        binary.context.i8_type().ptr_type(AddressSpace::Generic),
+                        "",
+                    )
+                    .into(),
+                data_len.into(),
+                topic_buf.into(),
+                binary
+                    .context
+                    .i32_type()
+                    .const_int(topic_count as u64, false)
+                    .into(),
+            ],
+            "",
+        );
     }
 
     /// builtin expressions
     fn builtin<'b>(
         &self,
-        _binary: &Binary<'b>,
-        _expr: &ast::Expression,
-        _vartab: &HashMap<usize, Variable<'b>>,
-        _function: FunctionValue<'b>,
-        _ns: &ast::Namespace,
+        binary: &Binary<'b>,
+        expr: &ast::Expression,
+        vartab: &HashMap<usize, Variable<'b>>,
+        function: FunctionValue<'b>,
+        ns: &ast::Namespace,
     ) -> BasicValueEnum<'b> {
-        unimplemented!();
+        macro_rules! straight_call {
+            ($name:literal, $func:literal) => {{
+                binary
+                    .builder
+                    .build_call(binary.module.get_function($func).unwrap(), &[], $name)
+                    .try_as_basic_value()
+                    .left()
+                    .unwrap()
+            }};
+        }
+
+        macro_rules! single_value_stack {
+            ($name:literal, $func:literal, $width:expr) => {{
+                let value = binary
+                    .builder
+                    .build_alloca(binary.context.custom_width_int_type($width), $name);
+
+                binary.builder.build_call(
+                    binary.module.get_function($func).unwrap(),
+                    &[binary
+                        .builder
+                        .build_pointer_cast(
+                            value,
+                            binary.context.i8_type().ptr_type(AddressSpace::Generic),
+                            "",
+                        )
+                        .into()],
+                    $name,
+                );
+
+                binary.builder.build_load(value, $name)
+            }};
+        }
+
+        match expr {
+            ast::Expression::Builtin(_, _, ast::Builtin::BlockNumber, _) => {
+                straight_call!("block_number", "solang_block_number")
+            }
+            ast::Expression::Builtin(_, _, ast::Builtin::Timestamp, _) => {
+                straight_call!("time_stamp", "solang_timestamp")
+            }
+            ast::Expression::Builtin(_, _, ast::Builtin::Gasleft, _) => {
+                straight_call!("gas_left", "solang_gasleft")
+            }
+            ast::Expression::Builtin(_, _, ast::Builtin::Gasprice, _) => {
+                // tx.gasprice(uint64) is multiplied out in codegen
+                single_value_stack!("gas_price", "solang_gasprice", ns.value_length as u32 * 8)
+            }
+            ast::Expression::Builtin(_, _, ast::Builtin::Sender, _) => {
+                single_value_stack!("caller", "solang_caller", ns.address_length as u32 * 8)
+            }
+            ast::Expression::Builtin(_, _, ast::Builtin::Origin, _) => {
+                single_value_stack!("origin", "solang_tx_origin", ns.address_length as u32 * 8)
+            }
+            ast::Expression::Builtin(_, _, ast::Builtin::GetAddress, _) => {
+                single_value_stack!(
+                    "self_address",
+                    "solang_address",
+                    ns.address_length as u32 * 8
+                )
+            }
+            ast::Expression::Builtin(_, _, ast::Builtin::Value, _) => {
+                self.value_transferred(binary, ns).into()
+            }
+            ast::Expression::Builtin(_, _, ast::Builtin::Balance, addr) => {
+                let addr = self
+                    .expression(binary, &addr[0], vartab, function, ns)
+                    .into_int_value();
+
+                let address = binary
+                    .builder
+                    .build_alloca(binary.address_type(ns), "address");
+
+                binary.builder.build_store(address, addr);
+
+                let balance = binary
+                    .builder
+                    .build_alloca(binary.value_type(ns), "balance");
+
+                binary.builder.build_call(
+                    binary.module.get_function("solang_balance").unwrap(),
+                    &[
+                        binary
+                            .builder
+                            .build_pointer_cast(
+                                address,
+                                binary.context.i8_type().ptr_type(AddressSpace::Generic),
+                                "",
+                            )
+                            .into(),
+                        binary
+                            .builder
+                            .build_pointer_cast(
+                                balance,
+                                binary.context.i8_type().ptr_type(AddressSpace::Generic),
+                                "",
+                            )
+                            .into(),
+                    ],
+                    "balance",
+                );
+
+                binary.builder.build_load(balance, "balance")
+            }
+            // sema only allows the builtins which are available on the generic target
+            _ => unreachable!(),
+        }
     }
 
     /// Crypto Hash
     fn hash<'b>(
         &self,
-        _contract: &Binary<'b>,
-        _hash: HashTy,
-        _input: PointerValue<'b>,
-        _input_len: IntValue<'b>,
-        _ns: &ast::Namespace,
+        binary: &Binary<'b>,
+        hash: HashTy,
+        input: PointerValue<'b>,
+        input_len: IntValue<'b>,
+        ns: &ast::Namespace,
     ) -> IntValue<'b> {
-        unimplemented!()
+        let (fname, hashlen) = match hash {
+            HashTy::Keccak256 => ("keccak256", 32),
+            HashTy::Ripemd160 => ("solang_ripemd160", 20),
+            HashTy::Sha256 => ("solang_sha256", 32),
+            HashTy::Blake2_128 => ("solang_blake2_128", 16),
+            HashTy::Blake2_256 => ("solang_blake2_256", 32),
+        };
+
+        let res = binary.builder.build_array_alloca(
+            binary.context.i8_type(),
+            binary.context.i32_type().const_int(hashlen, false),
+            "res",
+        );
+
+        binary.builder.build_call(
+            binary.module.get_function(fname).unwrap(),
+            &[input.into(), input_len.into(), res.into()],
+            "hash",
+        );
+
+        // bytes32 needs to reverse bytes
+        let temp = binary.builder.build_alloca(
+            binary.llvm_type(&ast::Type::Bytes(hashlen as u8), ns),
+            "hash",
+        );
+
+        binary.builder.build_call(
+            binary.module.get_function("__beNtoleN").unwrap(),
+            &[
+                res.into(),
+                binary
+                    .builder
+                    .build_pointer_cast(
+                        temp,
+                        binary.context.i8_type().ptr_type(AddressSpace::Generic),
+                        "",
+                    )
+                    .into(),
+                binary.context.i32_type().const_int(hashlen, false).into(),
+            ],
+            "",
+        );
+
+        binary.builder.build_load(temp, "hash").into_int_value()
     }
 }
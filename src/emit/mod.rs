@@ -11,6 +11,7 @@ use num_bigint::BigInt;
 use num_traits::One;
 use num_traits::ToPrimitive;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::collections::VecDeque;
 
 use crate::Target;
@@ -29,8 +30,9 @@ use inkwell::IntPredicate;
 use inkwell::OptimizationLevel;
 
 mod ethabiencoder;
+pub mod evm;
 mod ewasm;
-mod generic;
+pub(crate) mod generic;
 mod loop_builder;
 mod sabre;
 mod solana;
@@ -1214,7 +1216,7 @@ pub trait TargetRuntime<'a> {
                     )
                     .into()
             }
-            Expression::Add(_, _, l, r) => {
+            Expression::Add(loc, _, l, r) => {
                 let left = self
                     .expression(bin, l, vartab, function, ns)
                     .into_int_value();
@@ -1222,7 +1224,7 @@ pub trait TargetRuntime<'a> {
                     .expression(bin, r, vartab, function, ns)
                     .into_int_value();
 
-                if bin.math_overflow_check {
+                if bin.math_overflow_check || ns.checked_arithmetic(loc.0) {
                     let signed = l.ty().is_signed_int();
                     self.build_binary_op_with_overflow_check(
                         bin,
@@ -1237,7 +1239,7 @@ pub trait TargetRuntime<'a> {
                     bin.builder.build_int_add(left, right, "").into()
                 }
             }
-            Expression::Subtract(_, _, l, r) => {
+            Expression::Subtract(loc, _, l, r) => {
                 let left = self
                     .expression(bin, l, vartab, function, ns)
                     .into_int_value();
@@ -1245,7 +1247,7 @@ pub trait TargetRuntime<'a> {
                     .expression(bin, r, vartab, function, ns)
                     .into_int_value();
 
-                if bin.math_overflow_check {
+                if bin.math_overflow_check || ns.checked_arithmetic(loc.0) {
                     let signed = l.ty().is_signed_int();
                     self.build_binary_op_with_overflow_check(
                         bin,
@@ -1260,7 +1262,7 @@ pub trait TargetRuntime<'a> {
                     bin.builder.build_int_sub(left, right, "").into()
                 }
             }
-            Expression::Multiply(_, res_ty, l, r) => {
+            Expression::Multiply(loc, res_ty, l, r) => {
                 let left = self
                     .expression(bin, l, vartab, function, ns)
                     .into_int_value();
@@ -1268,8 +1270,15 @@ pub trait TargetRuntime<'a> {
                     .expression(bin, r, vartab, function, ns)
                     .into_int_value();
 
-                self.mul(bin, function, left, right, res_ty.is_signed_int())
-                    .into()
+                self.mul(
+                    bin,
+                    function,
+                    left,
+                    right,
+                    res_ty.is_signed_int(),
+                    bin.math_overflow_check || ns.checked_arithmetic(loc.0),
+                )
+                .into()
             }
             Expression::Divide(_, _, l, r) if !l.ty().is_signed_int() => {
                 let left = self
@@ -1753,13 +1762,18 @@ pub trait TargetRuntime<'a> {
                     bin.builder.build_int_signed_rem(left, right, "").into()
                 }
             }
-            Expression::Power(_, res_ty, l, r) => {
+            Expression::Power(loc, res_ty, l, r) => {
                 let left = self.expression(bin, l, vartab, function, ns);
                 let right = self.expression(bin, r, vartab, function, ns);
 
                 let bits = left.into_int_value().get_type().get_bit_width();
 
-                let f = self.power(bin, bits, res_ty.is_signed_int());
+                let f = self.power(
+                    bin,
+                    bits,
+                    res_ty.is_signed_int(),
+                    bin.math_overflow_check || ns.checked_arithmetic(loc.0),
+                );
 
                 bin.builder
                     .build_call(f, &[left, right], "power")
@@ -4362,11 +4376,25 @@ pub trait TargetRuntime<'a> {
         bin: &mut Binary<'a>,
         contract: &ast::Contract,
         ns: &ast::Namespace,
+    ) {
+        let all_cfgs: HashSet<usize> = (0..contract.cfg.len()).collect();
+
+        self.emit_selected_functions(bin, contract, &all_cfgs, ns);
+    }
+
+    /// Emit only the given functions. The caller must ensure that every function called
+    /// from the selected set is in the set too, e.g. by using `reachable_cfgs()`.
+    fn emit_selected_functions(
+        &mut self,
+        bin: &mut Binary<'a>,
+        contract: &ast::Contract,
+        selected: &HashSet<usize>,
+        ns: &ast::Namespace,
     ) {
         let mut defines = Vec::new();
 
         for (cfg_no, cfg) in contract.cfg.iter().enumerate() {
-            if !cfg.is_placeholder() {
+            if !cfg.is_placeholder() && selected.contains(&cfg_no) {
                 let ftype = bin.function_type(
                     &cfg.params
                         .iter()
@@ -4926,6 +4954,7 @@ pub trait TargetRuntime<'a> {
         left: IntValue<'a>,
         right: IntValue<'a>,
         signed: bool,
+        checked: bool,
     ) -> IntValue<'a> {
         let bits = left.get_type().get_bit_width();
 
@@ -4994,7 +5023,7 @@ pub trait TargetRuntime<'a> {
                 bin.builder
                     .build_int_truncate(res.into_int_value(), left.get_type(), "")
             }
-        } else if bin.math_overflow_check {
+        } else if checked {
             self.build_binary_op_with_overflow_check(
                 bin,
                 function,
@@ -5008,7 +5037,7 @@ pub trait TargetRuntime<'a> {
         }
     }
 
-    fn power(&self, bin: &Binary<'a>, bits: u32, signed: bool) -> FunctionValue<'a> {
+    fn power(&self, bin: &Binary<'a>, bits: u32, signed: bool, checked: bool) -> FunctionValue<'a> {
         /*
             int ipow(int base, int exp)
             {
@@ -5026,7 +5055,12 @@ pub trait TargetRuntime<'a> {
                 return result;
             }
         */
-        let name = format!("__{}power{}", if signed { 's' } else { 'u' }, bits);
+        let name = format!(
+            "__{}power{}{}",
+            if signed { 's' } else { 'u' },
+            bits,
+            if checked { "_checked" } else { "" }
+        );
         let ty = bin.context.custom_width_int_type(bits);
 
         if let Some(f) = bin.module.get_function(&name) {
@@ -5078,6 +5112,7 @@ pub trait TargetRuntime<'a> {
             result.as_basic_value().into_int_value(),
             base.as_basic_value().into_int_value(),
             signed,
+            checked,
         );
 
         bin.builder.build_unconditional_branch(nomultiply);
@@ -5109,6 +5144,7 @@ pub trait TargetRuntime<'a> {
             base.as_basic_value().into_int_value(),
             base.as_basic_value().into_int_value(),
             signed,
+            checked,
         );
 
         base.add_incoming(&[(&base2, notdone)]);
@@ -5251,6 +5287,7 @@ impl<'a> Binary<'a> {
                 opt,
                 math_overflow_check,
             ),
+            Target::Evm => unreachable!("evm bytecode is not generated via llvm"),
         }
     }
 
@@ -6342,6 +6379,8 @@ impl Target {
             Target::Solana => "so",
             // Generic target produces object file for linking
             Target::Generic => "o",
+            // EVM bytecode
+            Target::Evm => "bin",
             // Everything else generates webassembly
             _ => "wasm",
         }
@@ -15,6 +15,9 @@ use inkwell::OptimizationLevel;
 use super::ethabiencoder;
 use super::{Binary, TargetRuntime, Variable};
 
+/// Event type used for passing return data back to the caller
+const RETURN_EVENT: &[u8] = b"solang/return";
+
 pub struct SabreTarget {
     abi: ethabiencoder::EthAbiDecoder,
 }
@@ -57,6 +60,7 @@ impl SabreTarget {
             "create_collection",
             "add_to_collection",
             "alloc",
+            "add_event",
             "log_buffer",
         ]);
 
@@ -100,6 +104,14 @@ impl SabreTarget {
             u8_ptr.fn_type(&[binary.context.i32_type().into()], false),
             Some(Linkage::External),
         );
+        binary.module.add_function(
+            "add_event",
+            binary
+                .context
+                .i32_type()
+                .fn_type(&[u8_ptr.into(), u8_ptr.into(), u8_ptr.into()], false),
+            Some(Linkage::External),
+        );
         binary.module.add_function(
             "log_buffer",
             binary.context.void_type().fn_type(
@@ -136,7 +148,12 @@ impl SabreTarget {
 
         binary.builder.position_at_end(entry);
 
-        // we should not use our heap; use sabre provided heap instead
+        // Our heap is used for vectors and other temporaries. Anything passed to the host
+        // must be allocated with sabre's alloc()
+        binary
+            .builder
+            .build_call(binary.module.get_function("__init_heap").unwrap(), &[], "");
+
         let argsdata = function.get_first_param().unwrap().into_pointer_value();
         let argslen = binary
             .builder
@@ -185,12 +202,12 @@ impl SabreTarget {
         // init our storage vars
         binary.builder.build_call(initializer, &[], "");
 
-        if let Some((cfg_no, con)) = contract
-            .functions
+        // sabre only allows one constructor, hence find()
+        if let Some((cfg_no, cfg)) = contract
+            .cfg
             .iter()
             .enumerate()
-            .map(|(cfg_no, function_no)| (cfg_no, &ns.functions[*function_no]))
-            .find(|(_, f)| f.is_constructor())
+            .find(|(_, cfg)| cfg.ty == pt::FunctionTy::Constructor)
         {
             let mut args = Vec::new();
 
@@ -201,7 +218,7 @@ impl SabreTarget {
                 &mut args,
                 argsdata,
                 argslen,
-                &con.params,
+                &cfg.params,
                 ns,
             );
 
@@ -230,26 +247,26 @@ impl SabreTarget {
             |_| false,
         );
     }
-}
 
-impl<'a> TargetRuntime<'a> for SabreTarget {
-    fn storage_delete_single_slot(
-        &self,
-        binary: &Binary,
-        _function: FunctionValue,
-        slot: PointerValue,
-    ) {
-        let address = binary
+    /// Allocate a buffer with sabre's alloc(). Anything passed to the host must be
+    /// allocated this way, so that the host knows its length.
+    fn alloc<'b>(&self, binary: &Binary<'b>, length: IntValue) -> PointerValue<'b> {
+        binary
             .builder
             .build_call(
                 binary.module.get_function("alloc").unwrap(),
-                &[binary.context.i32_type().const_int(64, false).into()],
-                "address",
+                &[length.into()],
+                "",
             )
             .try_as_basic_value()
             .left()
             .unwrap()
-            .into_pointer_value();
+            .into_pointer_value()
+    }
+
+    /// Sabre state addresses are hex strings, so convert the slot to hex
+    fn slot_address<'b>(&self, binary: &Binary<'b>, slot: PointerValue) -> PointerValue<'b> {
+        let address = self.alloc(binary, binary.context.i32_type().const_int(64, false));
 
         // convert slot to address
         binary.builder.build_call(
@@ -268,7 +285,97 @@ impl<'a> TargetRuntime<'a> for SabreTarget {
             "address_from_slot",
         );
 
-        // create collection for delete_state
+        address
+    }
+
+    /// Sabre state address for a slot which is passed by value
+    fn slot_value_address<'b>(&self, binary: &Binary<'b>, slot: IntValue<'b>) -> PointerValue<'b> {
+        let slot_ptr = binary.builder.build_alloca(slot.get_type(), "slot");
+
+        binary.builder.build_store(slot_ptr, slot);
+
+        self.slot_address(binary, slot_ptr)
+    }
+
+    /// Read the state at the given address. Returns the data and its length. If there is
+    /// no state at the address, the length is zero.
+    fn get_state<'b>(
+        &self,
+        binary: &Binary<'b>,
+        address: PointerValue<'b>,
+    ) -> (PointerValue<'b>, IntValue<'b>) {
+        binary.builder.build_call(
+            binary.module.get_function("create_collection").unwrap(),
+            &[address.into()],
+            "",
+        );
+
+        let data = binary
+            .builder
+            .build_call(
+                binary.module.get_function("get_state").unwrap(),
+                &[address.into()],
+                "",
+            )
+            .try_as_basic_value()
+            .left()
+            .unwrap()
+            .into_pointer_value();
+
+        let state_size = binary
+            .builder
+            .build_call(
+                binary.module.get_function("get_ptr_len").unwrap(),
+                &[data.into()],
+                "",
+            )
+            .try_as_basic_value()
+            .left()
+            .unwrap()
+            .into_int_value();
+
+        // get_ptr_len() returns -1 for unknown pointers
+        let exists = binary.builder.build_int_compare(
+            IntPredicate::SGT,
+            state_size,
+            binary.context.i32_type().const_zero(),
+            "storage_exists",
+        );
+
+        let length = binary
+            .builder
+            .build_select(
+                exists,
+                state_size,
+                binary.context.i32_type().const_zero(),
+                "state_length",
+            )
+            .into_int_value();
+
+        (data, length)
+    }
+
+    /// Write the data to the state address. The data must be allocated with alloc()
+    fn set_state(&self, binary: &Binary, address: PointerValue, data: PointerValue) {
+        binary.builder.build_call(
+            binary.module.get_function("create_collection").unwrap(),
+            &[address.into()],
+            "",
+        );
+        binary.builder.build_call(
+            binary.module.get_function("add_to_collection").unwrap(),
+            &[address.into(), data.into()],
+            "",
+        );
+        binary.builder.build_call(
+            binary.module.get_function("set_state").unwrap(),
+            &[address.into()],
+            "",
+        );
+    }
+
+    /// Remove the state at the address
+    fn delete_state(&self, binary: &Binary, address: PointerValue) {
         binary.builder.build_call(
             binary.module.get_function("create_collection").unwrap(),
             &[address.into()],
@@ -282,48 +389,15 @@ impl<'a> TargetRuntime<'a> for SabreTarget {
         );
     }
 
-    fn set_storage(
+    /// Copy the value pointed to by dest into sabre state
+    fn set_storage_value(
         &self,
         binary: &Binary,
-        _function: FunctionValue,
         slot: PointerValue,
         dest: PointerValue,
+        data_size: IntValue,
     ) {
-        let address = binary
-            .builder
-            .build_call(
-                binary.module.get_function("alloc").unwrap(),
-                &[binary.context.i32_type().const_int(64, false).into()],
-                "address",
-            )
-            .try_as_basic_value()
-            .left()
-            .unwrap()
-            .into_pointer_value();
-
-        // convert slot to address
-        binary.builder.build_call(
-            binary.module.get_function("__u256ptohex").unwrap(),
-            &[
-                binary
-                    .builder
-                    .build_pointer_cast(
-                        slot,
-                        binary.context.i8_type().ptr_type(AddressSpace::Generic),
-                        "slot",
-                    )
-                    .into(),
-                address.into(),
-            ],
-            "address_from_slot",
-        );
-
-        let data_size = dest
-            .get_type()
-            .get_element_type()
-            .into_int_type()
-            .size_of()
-            .const_cast(binary.context.i32_type(), false);
+        let address = self.slot_address(binary, slot);
 
         let data = binary
             .builder
@@ -350,100 +424,401 @@ impl<'a> TargetRuntime<'a> for SabreTarget {
             "destdata",
         );
 
-        // create collection for set_state
-        binary.builder.build_call(
-            binary.module.get_function("create_collection").unwrap(),
-            &[address.into()],
-            "",
-        );
-        binary.builder.build_call(
-            binary.module.get_function("add_to_collection").unwrap(),
-            &[address.into(), data.into()],
-            "",
-        );
-        binary.builder.build_call(
-            binary.module.get_function("set_state").unwrap(),
-            &[address.into()],
-            "",
+        self.set_state(binary, address, data);
+    }
+
+    /// Abort if the index is not less than the length
+    fn bounds_check(
+        &self,
+        binary: &Binary,
+        function: FunctionValue,
+        index: IntValue,
+        length: IntValue,
+    ) {
+        let in_range =
+            binary
+                .builder
+                .build_int_compare(IntPredicate::ULT, index, length, "index_in_range");
+
+        let retrieve_block = binary.context.append_basic_block(function, "in_range");
+        let bang_block = binary.context.append_basic_block(function, "bang_block");
+
+        binary
+            .builder
+            .build_conditional_branch(in_range, retrieve_block, bang_block);
+
+        binary.builder.position_at_end(bang_block);
+        self.assert_failure(
+            binary,
+            binary
+                .context
+                .i8_type()
+                .ptr_type(AddressSpace::Generic)
+                .const_null(),
+            binary.context.i32_type().const_zero(),
         );
+
+        binary.builder.position_at_end(retrieve_block);
+    }
+}
+
+impl<'a> TargetRuntime<'a> for SabreTarget {
+    fn storage_delete_single_slot(
+        &self,
+        binary: &Binary,
+        _function: FunctionValue,
+        slot: PointerValue,
+    ) {
+        let address = self.slot_address(binary, slot);
+
+        self.delete_state(binary, address);
+    }
+
+    fn set_storage(
+        &self,
+        binary: &Binary,
+        _function: FunctionValue,
+        slot: PointerValue,
+        dest: PointerValue,
+    ) {
+        let data_size = dest
+            .get_type()
+            .get_element_type()
+            .into_int_type()
+            .size_of()
+            .const_cast(binary.context.i32_type(), false);
+
+        self.set_storage_value(binary, slot, dest, data_size);
     }
 
     fn set_storage_extfunc(
         &self,
-        _binary: &Binary,
+        binary: &Binary,
         _function: FunctionValue,
-        _slot: PointerValue,
-        _dest: PointerValue,
+        slot: PointerValue,
+        dest: PointerValue,
     ) {
-        unimplemented!();
+        let data_size = dest
+            .get_type()
+            .get_element_type()
+            .size_of()
+            .unwrap()
+            .const_cast(binary.context.i32_type(), false);
+
+        self.set_storage_value(binary, slot, dest, data_size);
     }
+
     fn get_storage_extfunc(
         &self,
-        _binary: &Binary<'a>,
+        binary: &Binary<'a>,
         _function: FunctionValue,
-        _slot: PointerValue<'a>,
-        _ns: &ast::Namespace,
+        slot: PointerValue<'a>,
+        ns: &ast::Namespace,
     ) -> PointerValue<'a> {
-        unimplemented!();
+        let ty = binary.llvm_type(
+            &ast::Type::ExternalFunction {
+                params: Vec::new(),
+                mutability: None,
+                returns: Vec::new(),
+            },
+            ns,
+        );
+
+        let len = ty
+            .into_pointer_type()
+            .get_element_type()
+            .size_of()
+            .unwrap()
+            .const_cast(binary.context.i32_type(), false);
+
+        let ef = binary
+            .builder
+            .build_call(
+                binary.module.get_function("__malloc").unwrap(),
+                &[len.into()],
+                "",
+            )
+            .try_as_basic_value()
+            .left()
+            .unwrap()
+            .into_pointer_value();
+
+        let address = self.slot_address(binary, slot);
+
+        let (data, state_len) = self.get_state(binary, address);
+
+        // if there is no state, the external function is all zeros
+        let copy_len = binary
+            .builder
+            .build_select(
+                binary.builder.build_int_compare(
+                    IntPredicate::EQ,
+                    state_len,
+                    len,
+                    "storage_exists",
+                ),
+                len,
+                binary.context.i32_type().const_zero(),
+                "copy_len",
+            )
+            .into_int_value();
+
+        binary.builder.build_call(
+            binary.module.get_function("__bzero8").unwrap(),
+            &[
+                ef.into(),
+                binary
+                    .builder
+                    .build_int_unsigned_div(len, binary.context.i32_type().const_int(8, false), "")
+                    .into(),
+            ],
+            "",
+        );
+
+        binary.builder.build_call(
+            binary.module.get_function("__memcpy").unwrap(),
+            &[ef.into(), data.into(), copy_len.into()],
+            "",
+        );
+
+        binary
+            .builder
+            .build_pointer_cast(ef, ty.into_pointer_type(), "function_type")
     }
 
     fn set_storage_string(
         &self,
-        _binary: &Binary<'a>,
-        _function: FunctionValue<'a>,
-        _slot: PointerValue<'a>,
-        _dest: BasicValueEnum<'a>,
+        binary: &Binary<'a>,
+        function: FunctionValue<'a>,
+        slot: PointerValue<'a>,
+        dest: BasicValueEnum<'a>,
     ) {
-        unimplemented!();
+        let len = binary.vector_len(dest);
+        let data = binary.vector_bytes(dest);
+
+        let exists = binary.builder.build_int_compare(
+            IntPredicate::NE,
+            len,
+            binary.context.i32_type().const_zero(),
+            "exists",
+        );
+
+        let delete_block = binary.context.append_basic_block(function, "delete_block");
+
+        let set_block = binary.context.append_basic_block(function, "set_block");
+
+        let done_storage = binary.context.append_basic_block(function, "done_storage");
+
+        binary
+            .builder
+            .build_conditional_branch(exists, set_block, delete_block);
+
+        binary.builder.position_at_end(set_block);
+
+        self.set_storage_value(binary, slot, data, len);
+
+        binary.builder.build_unconditional_branch(done_storage);
+
+        binary.builder.position_at_end(delete_block);
+
+        let address = self.slot_address(binary, slot);
+
+        self.delete_state(binary, address);
+
+        binary.builder.build_unconditional_branch(done_storage);
+
+        binary.builder.position_at_end(done_storage);
     }
 
     fn get_storage_string(
         &self,
-        _binary: &Binary<'a>,
+        binary: &Binary<'a>,
         _function: FunctionValue,
-        _slot: PointerValue<'a>,
+        slot: PointerValue<'a>,
     ) -> PointerValue<'a> {
-        unimplemented!();
+        let address = self.slot_address(binary, slot);
+
+        let (data, length) = self.get_state(binary, address);
+
+        // if there is no state, the length is zero and we get an empty string
+        binary
+            .builder
+            .build_call(
+                binary.module.get_function("vector_new").unwrap(),
+                &[
+                    length.into(),
+                    binary.context.i32_type().const_int(1, false).into(),
+                    data.into(),
+                ],
+                "",
+            )
+            .try_as_basic_value()
+            .left()
+            .unwrap()
+            .into_pointer_value()
     }
+
     fn get_storage_bytes_subscript(
         &self,
-        _binary: &Binary<'a>,
-        _function: FunctionValue,
-        _slot: IntValue<'a>,
-        _index: IntValue<'a>,
+        binary: &Binary<'a>,
+        function: FunctionValue,
+        slot: IntValue<'a>,
+        index: IntValue<'a>,
     ) -> IntValue<'a> {
-        unimplemented!();
+        let address = self.slot_value_address(binary, slot);
+
+        let (data, length) = self.get_state(binary, address);
+
+        self.bounds_check(binary, function, index, length);
+
+        let offset = unsafe { binary.builder.build_gep(data, &[index], "data_offset") };
+
+        binary.builder.build_load(offset, "value").into_int_value()
     }
+
     fn set_storage_bytes_subscript(
         &self,
-        _binary: &Binary,
-        _function: FunctionValue,
-        _slot: IntValue,
-        _index: IntValue,
-        _val: IntValue,
+        binary: &Binary<'a>,
+        function: FunctionValue,
+        slot: IntValue<'a>,
+        index: IntValue<'a>,
+        val: IntValue<'a>,
     ) {
-        unimplemented!();
+        let address = self.slot_value_address(binary, slot);
+
+        let (data, length) = self.get_state(binary, address);
+
+        self.bounds_check(binary, function, index, length);
+
+        // the state was allocated by the host, so we can modify it in place and write it back
+        let offset = unsafe { binary.builder.build_gep(data, &[index], "data_offset") };
+
+        binary.builder.build_store(offset, val);
+
+        self.set_state(binary, address, data);
     }
+
     fn storage_push(
         &self,
-        _binary: &Binary<'a>,
+        binary: &Binary<'a>,
         _function: FunctionValue,
         _ty: &ast::Type,
-        _slot: IntValue<'a>,
-        _val: BasicValueEnum<'a>,
+        slot: IntValue<'a>,
+        val: BasicValueEnum<'a>,
         _ns: &ast::Namespace,
     ) -> BasicValueEnum<'a> {
-        unimplemented!();
+        let address = self.slot_value_address(binary, slot);
+
+        let (data, length) = self.get_state(binary, address);
+
+        let new_length = binary.builder.build_int_add(
+            length,
+            binary.context.i32_type().const_int(1, false),
+            "new_length",
+        );
+
+        let new_data = self.alloc(binary, new_length);
+
+        binary.builder.build_call(
+            binary.module.get_function("__memcpy").unwrap(),
+            &[new_data.into(), data.into(), length.into()],
+            "",
+        );
+
+        let offset = unsafe { binary.builder.build_gep(new_data, &[length], "data_offset") };
+
+        binary.builder.build_store(offset, val);
+
+        self.set_state(binary, address, new_data);
+
+        val
     }
+
     fn storage_pop(
         &self,
-        _binary: &Binary<'a>,
-        _function: FunctionValue,
+        binary: &Binary<'a>,
+        function: FunctionValue,
         _ty: &ast::Type,
-        _slot: IntValue<'a>,
+        slot: IntValue<'a>,
         _ns: &ast::Namespace,
     ) -> BasicValueEnum<'a> {
-        unimplemented!();
+        let address = self.slot_value_address(binary, slot);
+
+        let (data, length) = self.get_state(binary, address);
+
+        // pop from an empty bytes is an error
+        self.bounds_check(
+            binary,
+            function,
+            binary.context.i32_type().const_zero(),
+            length,
+        );
+
+        let new_length = binary.builder.build_int_sub(
+            length,
+            binary.context.i32_type().const_int(1, false),
+            "new_length",
+        );
+
+        let offset = unsafe { binary.builder.build_gep(data, &[new_length], "data_offset") };
+
+        let val = binary.builder.build_load(offset, "popped_value");
+
+        // the host rejects zero length state, so delete it once the last byte is popped
+        let is_empty = binary.builder.build_int_compare(
+            IntPredicate::EQ,
+            new_length,
+            binary.context.i32_type().const_zero(),
+            "is_empty",
+        );
+
+        let delete_block = binary.context.append_basic_block(function, "delete_block");
+        let set_block = binary.context.append_basic_block(function, "set_block");
+        let done_storage = binary.context.append_basic_block(function, "done_storage");
+
+        binary
+            .builder
+            .build_conditional_branch(is_empty, delete_block, set_block);
+
+        binary.builder.position_at_end(set_block);
+
+        let new_data = self.alloc(binary, new_length);
+
+        binary.builder.build_call(
+            binary.module.get_function("__memcpy").unwrap(),
+            &[new_data.into(), data.into(), new_length.into()],
+            "",
+        );
+
+        self.set_state(binary, address, new_data);
+
+        binary.builder.build_unconditional_branch(done_storage);
+
+        binary.builder.position_at_end(delete_block);
+
+        self.delete_state(binary, address);
+
+        binary.builder.build_unconditional_branch(done_storage);
+
+        binary.builder.position_at_end(done_storage);
+
+        val
+    }
+
+    /// Calculate length of storage dynamic bytes
+    fn storage_array_length(
+        &self,
+        binary: &Binary<'a>,
+        _function: FunctionValue,
+        slot: IntValue<'a>,
+        _ty: &ast::Type,
+        _ns: &ast::Namespace,
+    ) -> IntValue<'a> {
+        let address = self.slot_value_address(binary, slot);
+
+        let (_, length) = self.get_state(binary, address);
+
+        length
     }
 
     fn get_storage_int(
@@ -453,73 +828,16 @@ impl<'a> TargetRuntime<'a> for SabreTarget {
         slot: PointerValue<'a>,
         ty: IntType<'a>,
     ) -> IntValue<'a> {
-        let address = binary
-            .builder
-            .build_call(
-                binary.module.get_function("alloc").unwrap(),
-                &[binary.context.i32_type().const_int(64, false).into()],
-                "address",
-            )
-            .try_as_basic_value()
-            .left()
-            .unwrap()
-            .into_pointer_value();
-
-        // convert slot to address
-        binary.builder.build_call(
-            binary.module.get_function("__u256ptohex").unwrap(),
-            &[
-                binary
-                    .builder
-                    .build_pointer_cast(
-                        slot,
-                        binary.context.i8_type().ptr_type(AddressSpace::Generic),
-                        "slot",
-                    )
-                    .into(),
-                address.into(),
-            ],
-            "address_from_slot",
-        );
-
-        // create collection for set_state
-        binary.builder.build_call(
-            binary.module.get_function("create_collection").unwrap(),
-            &[address.into()],
-            "",
-        );
-        let res = binary
-            .builder
-            .build_call(
-                binary.module.get_function("get_state").unwrap(),
-                &[address.into()],
-                "",
-            )
-            .try_as_basic_value()
-            .left()
-            .unwrap()
-            .into_pointer_value();
+        let address = self.slot_address(binary, slot);
 
-        let state_size = binary
-            .builder
-            .build_call(
-                binary.module.get_function("get_ptr_len").unwrap(),
-                &[res.into()],
-                "",
-            )
-            .try_as_basic_value()
-            .left()
-            .unwrap()
-            .into_int_value();
+        let (data, length) = self.get_state(binary, address);
 
-        let data_size = ty.size_of();
+        let data_size = ty.size_of().const_cast(binary.context.i32_type(), false);
 
-        let exists = binary.builder.build_int_compare(
-            IntPredicate::EQ,
-            state_size,
-            data_size,
-            "storage_exists",
-        );
+        let exists =
+            binary
+                .builder
+                .build_int_compare(IntPredicate::EQ, length, data_size, "storage_exists");
 
         let entry = binary.builder.get_insert_block().unwrap();
 
@@ -535,12 +853,14 @@ impl<'a> TargetRuntime<'a> for SabreTarget {
         let loaded_int = binary.builder.build_load(
             binary
                 .builder
-                .build_pointer_cast(res, ty.ptr_type(AddressSpace::Generic), ""),
+                .build_pointer_cast(data, ty.ptr_type(AddressSpace::Generic), ""),
             "loaded_int",
         );
 
         binary.builder.build_unconditional_branch(done_storage);
 
+        binary.builder.position_at_end(done_storage);
+
         let res = binary.builder.build_phi(ty, "storage_res");
 
         res.add_incoming(&[(&loaded_int, retrieve_block), (&ty.const_zero(), entry)]);
@@ -589,8 +909,62 @@ impl<'a> TargetRuntime<'a> for SabreTarget {
             .build_return(Some(&binary.context.i32_type().const_int(1, false)));
     }
 
-    fn return_abi<'b>(&self, binary: &'b Binary, _data: PointerValue<'b>, _length: IntValue) {
-        // FIXME: how to return abi encoded return data?
+    /// Sabre has no return data, so the abi encoded return values are passed to the
+    /// caller as the data of an event of type `solang/return`, without attributes.
+    fn return_abi<'b>(&self, binary: &'b Binary, data: PointerValue<'b>, length: IntValue) {
+        let event_type = binary.emit_global_string("event_type", RETURN_EVENT, true);
+        let event_type_len = binary
+            .context
+            .i32_type()
+            .const_int(RETURN_EVENT.len() as u64, false);
+
+        // the host only accepts pointers it allocated
+        let event_type_data = self.alloc(binary, event_type_len);
+
+        binary.builder.build_call(
+            binary.module.get_function("__memcpy").unwrap(),
+            &[
+                event_type_data.into(),
+                event_type.into(),
+                event_type_len.into(),
+            ],
+            "",
+        );
+
+        let return_data = self.alloc(binary, length);
+
+        binary.builder.build_call(
+            binary.module.get_function("__memcpy").unwrap(),
+            &[
+                return_data.into(),
+                binary
+                    .builder
+                    .build_pointer_cast(
+                        data,
+                        binary.context.i8_type().ptr_type(AddressSpace::Generic),
+                        "data",
+                    )
+                    .into(),
+                length.into(),
+            ],
+            "",
+        );
+
+        binary.builder.build_call(
+            binary.module.get_function("add_event").unwrap(),
+            &[
+                event_type_data.into(),
+                binary
+                    .context
+                    .i8_type()
+                    .ptr_type(AddressSpace::Generic)
+                    .const_null()
+                    .into(),
+                return_data.into(),
+            ],
+            "",
+        );
+
         // return 1 for success
         binary
             .builder
@@ -604,14 +978,14 @@ impl<'a> TargetRuntime<'a> for SabreTarget {
     /// ABI encode into a vector for abi.encode* style builtin functions
     fn abi_encode_to_vector<'b>(
         &self,
-        _binary: &Binary<'b>,
-        _function: FunctionValue<'b>,
-        _packed: &[BasicValueEnum<'b>],
-        _args: &[BasicValueEnum<'b>],
-        _spec: &[ast::Type],
-        _ns: &ast::Namespace,
+        binary: &Binary<'b>,
+        function: FunctionValue<'b>,
+        packed: &[BasicValueEnum<'b>],
+        args: &[BasicValueEnum<'b>],
+        tys: &[ast::Type],
+        ns: &ast::Namespace,
     ) -> PointerValue<'b> {
-        unimplemented!();
+        ethabiencoder::encode_to_vector(binary, function, packed, args, tys, false, ns)
     }
 
     fn abi_encode<'b>(
@@ -746,30 +1120,75 @@ impl<'a> TargetRuntime<'a> for SabreTarget {
         _topics: Vec<(PointerValue<'b>, IntValue<'b>)>,
         _ns: &ast::Namespace,
     ) {
-        unimplemented!();
+        // sema does not allow events on Sabre
+        unreachable!();
     }
 
     /// builtin expressions
     fn builtin<'b>(
         &self,
-        _binary: &Binary<'b>,
-        _expr: &ast::Expression,
+        binary: &Binary<'b>,
+        expr: &ast::Expression,
         _vartab: &HashMap<usize, Variable<'b>>,
         _function: FunctionValue<'b>,
-        _ns: &ast::Namespace,
+        ns: &ast::Namespace,
     ) -> BasicValueEnum<'b> {
-        unimplemented!();
+        match expr {
+            // Sabre has no addresses, so `this` is only useful for its external functions
+            ast::Expression::Builtin(_, _, ast::Builtin::GetAddress, _) => {
+                binary.address_type(ns).const_zero().into()
+            }
+            // sema only allows the builtins which are available on Sabre
+            _ => unreachable!(),
+        }
     }
 
-    /// Crypto Hash
+    /// Crypto Hash. The host does not provide any hash functions, so keccak256 is our
+    /// implementation; sema does not allow the others
     fn hash<'b>(
         &self,
-        _binary: &Binary<'b>,
-        _hash: HashTy,
-        _input: PointerValue<'b>,
-        _input_len: IntValue<'b>,
-        _ns: &ast::Namespace,
+        binary: &Binary<'b>,
+        hash: HashTy,
+        input: PointerValue<'b>,
+        input_len: IntValue<'b>,
+        ns: &ast::Namespace,
     ) -> IntValue<'b> {
-        unimplemented!()
+        debug_assert!(hash == HashTy::Keccak256);
+
+        let res = binary.builder.build_array_alloca(
+            binary.context.i8_type(),
+            binary.context.i32_type().const_int(32, false),
+            "res",
+        );
+
+        binary.builder.build_call(
+            binary.module.get_function("keccak256").unwrap(),
+            &[input.into(), input_len.into(), res.into()],
+            "hash",
+        );
+
+        // bytes32 needs to reverse bytes
+        let temp = binary
+            .builder
+            .build_alloca(binary.llvm_type(&ast::Type::Bytes(32), ns), "hash");
+
+        binary.builder.build_call(
+            binary.module.get_function("__beNtoleN").unwrap(),
+            &[
+                res.into(),
+                binary
+                    .builder
+                    .build_pointer_cast(
+                        temp,
+                        binary.context.i8_type().ptr_type(AddressSpace::Generic),
+                        "",
+                    )
+                    .into(),
+                binary.context.i32_type().const_int(32, false).into(),
+            ],
+            "",
+        );
+
+        binary.builder.build_load(temp, "hash").into_int_value()
     }
 }
@@ -4285,6 +4285,116 @@ impl<'a> TargetRuntime<'a> for SubstrateTarget {
                     "balance",
                 )
             }
+            ast::Expression::Builtin(_, _, ast::Builtin::PredictAddress, args) => {
+                let codehash = self
+                    .expression(binary, &args[0], vartab, function, ns)
+                    .into_int_value();
+                let salt = self
+                    .expression(binary, &args[1], vartab, function, ns)
+                    .into_int_value();
+
+                // blake2_256(address ++ code_hash ++ salt)
+                let input_len = ns.address_length as u64 + 32 + 32;
+
+                let input = binary.builder.build_array_alloca(
+                    binary.context.i8_type(),
+                    binary.context.i32_type().const_int(input_len, false),
+                    "instantiate_input",
+                );
+
+                let scratch_len = binary.scratch_len.unwrap().as_pointer_value();
+
+                binary.builder.build_store(
+                    scratch_len,
+                    binary
+                        .context
+                        .i32_type()
+                        .const_int(ns.address_length as u64, false),
+                );
+
+                binary.builder.build_call(
+                    binary.module.get_function("seal_address").unwrap(),
+                    &[input.into(), scratch_len.into()],
+                    "address",
+                );
+
+                // the code hash is passed as bytes32, so it needs its bytes reversed
+                let codehash_buf = binary.builder.build_alloca(codehash.get_type(), "codehash");
+
+                binary.builder.build_store(codehash_buf, codehash);
+
+                let codehash_offset = unsafe {
+                    binary.builder.build_gep(
+                        input,
+                        &[binary
+                            .context
+                            .i32_type()
+                            .const_int(ns.address_length as u64, false)],
+                        "codehash_offset",
+                    )
+                };
+
+                binary.builder.build_call(
+                    binary.module.get_function("__leNtobeN").unwrap(),
+                    &[
+                        binary
+                            .builder
+                            .build_pointer_cast(
+                                codehash_buf,
+                                binary.context.i8_type().ptr_type(AddressSpace::Generic),
+                                "",
+                            )
+                            .into(),
+                        codehash_offset.into(),
+                        binary.context.i32_type().const_int(32, false).into(),
+                    ],
+                    "",
+                );
+
+                // the salt is passed to seal_instantiate in little endian, see create_contract()
+                let salt_offset = unsafe {
+                    binary.builder.build_gep(
+                        input,
+                        &[binary
+                            .context
+                            .i32_type()
+                            .const_int(ns.address_length as u64 + 32, false)],
+                        "salt_offset",
+                    )
+                };
+
+                binary.builder.build_store(
+                    binary.builder.build_pointer_cast(
+                        salt_offset,
+                        salt.get_type().ptr_type(AddressSpace::Generic),
+                        "salt",
+                    ),
+                    salt,
+                );
+
+                let address = binary
+                    .builder
+                    .build_alloca(binary.address_type(ns), "address");
+
+                binary.builder.build_call(
+                    binary.module.get_function("seal_hash_blake2_256").unwrap(),
+                    &[
+                        input.into(),
+                        binary.context.i32_type().const_int(input_len, false).into(),
+                        binary
+                            .builder
+                            .build_pointer_cast(
+                                address,
+                                binary.context.i8_type().ptr_type(AddressSpace::Generic),
+                                "",
+                            )
+                            .into(),
+                    ],
+                    "hash",
+                );
+
+                binary.builder.build_load(address, "address")
+            }
             _ => unimplemented!(),
         }
     }
@@ -1,16 +1,86 @@
+use crate::parser::{parse, pt};
+use crate::sema::ast::Diagnostic;
+use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
+use std::fmt;
 use std::fs::File;
+use std::hash::{Hash, Hasher};
 use std::io::prelude::*;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
 pub struct FileCache {
     /// Set of import paths search for imports
     import_paths: Vec<PathBuf>,
+    /// Import prefixes which should be replaced, e.g. `@openzeppelin/` by `lib/openzeppelin/`
+    remappings: Vec<Remapping>,
     /// List file by import path
     cached_paths: HashMap<PathBuf, usize>,
     /// The actual file contents
     files: Vec<Arc<str>>,
+    /// Parse trees from an earlier run which may be reused
+    previous_trees: ParseTrees,
+    /// Parse trees of the files parsed so far
+    parse_trees: ParseTrees,
+    /// Set when the result is no longer needed, so that resolving can stop early
+    cancelled: Option<Arc<AtomicBool>>,
+}
+
+/// The parse tree and comments of a file with the errors the parser recovered from, or the
+/// errors if the file could not be parsed
+pub type ParseResult = Result<(pt::SourceUnit, Vec<pt::Comment>, Vec<Diagnostic>), Vec<Diagnostic>>;
+
+/// Parse trees by file number and hash of the file contents. The file number is part of
+/// the key since every location in the tree refers to it.
+pub type ParseTrees = HashMap<(usize, u64), Arc<ParseResult>>;
+
+/// An import remapping, written like solc's `context:prefix=target`. Imports which start
+/// with the prefix, in files whose path starts with the context, are resolved as if they
+/// start with the target instead. An empty context matches all files.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Remapping {
+    pub context: String,
+    pub prefix: String,
+    pub target: String,
+}
+
+impl FromStr for Remapping {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (context, prefix, target) = match s.split_once('=') {
+            Some((from, target)) => match from.split_once(':') {
+                Some((context, prefix)) => (context, prefix, target),
+                None => ("", from, target),
+            },
+            None => ("", "", ""),
+        };
+
+        if prefix.is_empty() {
+            return Err(format!(
+                "remapping ‘{}’ should be of the form [context:]prefix=target",
+                s
+            ));
+        }
+
+        Ok(Remapping {
+            context: context.to_owned(),
+            prefix: prefix.to_owned(),
+            target: target.to_owned(),
+        })
+    }
+}
+
+impl fmt::Display for Remapping {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.context.is_empty() {
+            write!(f, "{}={}", self.prefix, self.target)
+        } else {
+            write!(f, "{}:{}={}", self.context, self.prefix, self.target)
+        }
+    }
 }
 
 /// When we resolve a file, we need to know its base compared to the import so
@@ -40,8 +110,12 @@ impl FileCache {
     pub fn new() -> Self {
         FileCache {
             import_paths: Vec::new(),
+            remappings: Vec::new(),
             cached_paths: HashMap::new(),
             files: Vec::new(),
+            previous_trees: HashMap::new(),
+            parse_trees: HashMap::new(),
+            cancelled: None,
         }
     }
 
@@ -50,6 +124,49 @@ impl FileCache {
         self.import_paths.push(path);
     }
 
+    /// Add a remapping. The target is searched for in the import paths.
+    pub fn add_remapping(&mut self, remapping: Remapping) {
+        self.remappings.push(remapping);
+    }
+
+    /// Apply the remapping to the import with the longest matching context, and then the
+    /// longest matching prefix, if any. The context is matched against the path of the
+    /// importing file relative to its import path, and against its full path.
+    fn remap(&self, parent: Option<&ResolvedFile>, filename: &str) -> Option<(String, Remapping)> {
+        let importer = parent.map(|parent| {
+            let relative = parent
+                .base
+                .join(parent.full_path.file_name().unwrap_or_default());
+
+            (
+                relative.to_string_lossy().to_string(),
+                parent.full_path.to_string_lossy().to_string(),
+            )
+        });
+
+        self.remappings
+            .iter()
+            .filter(|remapping| {
+                remapping.context.is_empty()
+                    || importer.as_ref().map_or(false, |(relative, full)| {
+                        relative.starts_with(&remapping.context)
+                            || full.starts_with(&remapping.context)
+                    })
+            })
+            .filter(|remapping| filename.starts_with(&remapping.prefix))
+            .max_by_key(|remapping| (remapping.context.len(), remapping.prefix.len()))
+            .map(|remapping| {
+                (
+                    format!(
+                        "{}{}",
+                        remapping.target,
+                        &filename[remapping.prefix.len()..]
+                    ),
+                    remapping.clone(),
+                )
+            })
+    }
+
     /// Update the cache for the filename with the given contents
     pub fn set_file_contents(&mut self, path: &str, contents: String) {
         let pos = self.files.len();
@@ -59,6 +176,59 @@ impl FileCache {
         self.cached_paths.insert(PathBuf::from(path), pos);
     }
 
+    /// Reuse the parse trees of an earlier run, so that files which have not changed
+    /// are not parsed again
+    pub fn set_parse_trees(&mut self, trees: ParseTrees) {
+        self.previous_trees = trees;
+    }
+
+    /// Take the parse trees of the files parsed by this run; trees from an earlier run
+    /// which were not needed are dropped, unless the run was cancelled before it got to them
+    pub fn take_parse_trees(&mut self) -> ParseTrees {
+        let mut trees = std::mem::take(&mut self.parse_trees);
+
+        if self.is_cancelled() {
+            trees.extend(self.previous_trees.drain());
+        } else {
+            self.previous_trees.clear();
+        }
+
+        trees
+    }
+
+    /// Resolving stops early once the flag is set, leaving an incomplete namespace
+    pub fn set_cancel_flag(&mut self, flag: Arc<AtomicBool>) {
+        self.cancelled = Some(flag);
+    }
+
+    /// Has the cancel flag been set
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled
+            .as_ref()
+            .map_or(false, |flag| flag.load(Ordering::Relaxed))
+    }
+
+    /// Parse the contents of a file, or return the parse tree of an earlier run if the
+    /// contents have not changed
+    pub fn parse(&mut self, file_no: usize, contents: &str) -> Arc<ParseResult> {
+        let mut hasher = DefaultHasher::new();
+        contents.hash(&mut hasher);
+        let key = (file_no, hasher.finish());
+
+        if let Some(tree) = self.parse_trees.get(&key) {
+            return tree.clone();
+        }
+
+        let tree = match self.previous_trees.remove(&key) {
+            Some(tree) => tree,
+            None => Arc::new(parse(contents, file_no)),
+        };
+
+        self.parse_trees.insert(key, tree.clone());
+
+        tree
+    }
+
     /// Get file with contents. This must be a file which was previously
     /// add to the cache
     pub fn get_file_contents(&mut self, file: &Path) -> Arc<str> {
@@ -111,7 +281,16 @@ impl FileCache {
         parent: Option<&ResolvedFile>,
         filename: &str,
     ) -> Result<ResolvedFile, String> {
-        let path = PathBuf::from(filename);
+        let import = filename;
+        let remapping = self.remap(parent, import);
+
+        // a remapped import is never relative to the importing file
+        let (filename, parent) = match &remapping {
+            Some((remapped, _)) => (remapped.to_owned(), None),
+            None => (import.to_owned(), parent),
+        };
+
+        let path = PathBuf::from(&filename);
         let mut start_import_no = 0;
 
         // first try relative to the parent
@@ -121,60 +300,63 @@ impl FileCache {
         {
             if self.import_paths.is_empty() {
                 // we have no import paths, resolve by what's in the cache
-                let full_path = base.join(path);
-                let base = (&full_path.parent())
-                    .expect("path should include filename")
-                    .to_path_buf();
-
-                let file_no = self.cached_paths[&full_path];
-
-                return Ok(ResolvedFile {
-                    full_path,
-                    base,
-                    import_no: 0,
-                    file_no,
-                });
-            }
-
-            let import_path = self.import_paths[*import_no].join(base);
+                let full_path = normalize(&base.join(&path));
 
-            if let Ok(full_path) = import_path.join(path.clone()).canonicalize() {
-                // strip the filename off and the import prefix for the base
-                if let Ok(base) = &full_path
-                    .parent()
-                    .expect("path should include filename")
-                    .strip_prefix(import_path)
-                {
-                    let file_no = self.load_file(&full_path)?;
-                    let base = base.to_path_buf();
+                if let Some(file_no) = self.cached_paths.get(&full_path) {
+                    let base = (&full_path.parent())
+                        .expect("path should include filename")
+                        .to_path_buf();
 
                     return Ok(ResolvedFile {
-                        full_path,
                         base,
-                        import_no: *import_no,
-                        file_no,
+                        import_no: 0,
+                        file_no: *file_no,
+                        full_path,
                     });
                 }
-            }
+            } else {
+                let import_path = self.import_paths[*import_no].join(base);
 
-            // start with the next import
-            start_import_no = *import_no + 1;
+                if let Ok(full_path) = import_path.join(path.clone()).canonicalize() {
+                    // strip the filename off and the import prefix for the base
+                    if let Ok(base) = &full_path
+                        .parent()
+                        .expect("path should include filename")
+                        .strip_prefix(import_path)
+                    {
+                        let file_no = self.load_file(&full_path)?;
+                        let base = base.to_path_buf();
+
+                        return Ok(ResolvedFile {
+                            full_path,
+                            base,
+                            import_no: *import_no,
+                            file_no,
+                        });
+                    }
+                }
+
+                // start with the next import
+                start_import_no = *import_no + 1;
+            }
         }
 
         if self.import_paths.is_empty() {
             // we have no import paths, resolve by what's in the cache
-            let full_path = path;
-            let base = (&full_path.parent())
-                .expect("path should include filename")
-                .to_path_buf();
-            let file_no = self.cached_paths[&full_path];
-
-            return Ok(ResolvedFile {
-                full_path,
-                base,
-                import_no: 0,
-                file_no,
-            });
+            let full_path = normalize(&path);
+
+            if let Some(file_no) = self.cached_paths.get(&full_path) {
+                let base = (&full_path.parent())
+                    .expect("path should include filename")
+                    .to_path_buf();
+
+                return Ok(ResolvedFile {
+                    base,
+                    import_no: 0,
+                    file_no: *file_no,
+                    full_path,
+                });
+            }
         }
 
         // walk over the import paths until we find one that resolves
@@ -203,6 +385,32 @@ impl FileCache {
             }
         }
 
-        Err(format!("file not found ‘{}’", filename))
+        match remapping {
+            Some((_, remapping)) => Err(format!(
+                "file not found ‘{}’, import ‘{}’ was remapped by ‘{}’",
+                filename, import, remapping
+            )),
+            None => Err(format!("file not found ‘{}’", filename)),
+        }
     }
 }
+
+/// Remove `.` and `..` from a path without looking at the filesystem, so that relative imports
+/// of files in the cache can be found
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::CurDir => (),
+            Component::ParentDir
+                if matches!(normalized.components().last(), Some(Component::Normal(_))) =>
+            {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+
+    normalized
+}
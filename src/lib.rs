@@ -30,6 +30,8 @@ pub enum Target {
     Generic,
     /// Solana, see <https://solana.com/>
     Solana,
+    /// Ethereum Virtual Machine, see <https://ethereum.org/en/developers/docs/evm/>
+    Evm,
 }
 
 impl fmt::Display for Target {
@@ -40,10 +42,18 @@ impl fmt::Display for Target {
             Target::Sabre => write!(f, "Sawtooth Sabre"),
             Target::Generic => write!(f, "generic"),
             Target::Solana => write!(f, "solana"),
+            Target::Evm => write!(f, "evm"),
         }
     }
 }
 
+impl Target {
+    /// Is this an Ethereum target, i.e. does it follow Ethereum semantics and ABI
+    pub fn is_ethereum(&self) -> bool {
+        matches!(self, Target::Ewasm | Target::Evm)
+    }
+}
+
 /// Compile a solidity file to list of wasm files and their ABIs. The filename is only used for error messages;
 /// the contents of the file is provided in the `src` argument.
 ///
@@ -71,6 +81,27 @@ pub fn compile(
         codegen::codegen(contract_no, &mut ns, &Default::default());
     }
 
+    if target == Target::Evm {
+        let mut results = Vec::new();
+
+        for c in 0..ns.contracts.len() {
+            if !ns.contracts[c].is_concrete() {
+                continue;
+            }
+
+            match emit::evm::build(c, &ns, math_overflow_check) {
+                Ok(code) => {
+                    let (abistr, _) = abi::generate_abi(c, &ns, &code, false);
+
+                    results.push((code, abistr));
+                }
+                Err(mut errors) => ns.diagnostics.append(&mut errors),
+            }
+        }
+
+        return (results, ns);
+    }
+
     let results = (0..ns.contracts.len())
         .filter(|c| ns.contracts[*c].is_concrete())
         .map(|c| {
@@ -115,7 +146,7 @@ pub fn parse_and_resolve(filename: &str, cache: &mut FileCache, target: Target)
     let mut ns = ast::Namespace::new(
         target,
         match target {
-            Target::Ewasm => 20,
+            Target::Ewasm | Target::Evm => 20,
             Target::Substrate => 32,
             Target::Sabre => 0,    // Sabre has no address type
             Target::Generic => 20, // Same as ethereum
@@ -132,10 +163,12 @@ pub fn parse_and_resolve(filename: &str, cache: &mut FileCache, target: Target)
         Err(message) => {
             ns.diagnostics.push(ast::Diagnostic {
                 ty: ast::ErrorType::ParserError,
+                code: sema::codes::PARSER_ERROR,
                 level: ast::Level::Error,
                 message,
                 pos: None,
                 notes: Vec::new(),
+                fixes: Vec::new(),
             });
         }
         Ok(file) => {
@@ -143,5 +176,7 @@ pub fn parse_and_resolve(filename: &str, cache: &mut FileCache, target: Target)
         }
     }
 
+    diagnostics::apply_suppressions(cache, &mut ns);
+
     ns
 }
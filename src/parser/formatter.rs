@@ -0,0 +1,1785 @@
+// Pretty printer for the parse tree
+//
+// The tree is converted to a document of text, line breaks, indentation and groups. A group
+// is printed on one line if it fits within the line width; otherwise its line breaks are
+// used. The comments are not part of the tree. They are placed between the definitions,
+// statements and list items by their location in the source; a comment within an expression
+// is placed after the statement.
+
+use super::lexer::{Lexer, Token};
+use super::parse;
+use super::pt::*;
+use crate::sema::ast::Diagnostic;
+use regex::Regex;
+
+/// How the source is formatted
+#[derive(Clone, Debug, PartialEq)]
+pub struct FormatOptions {
+    /// The number of columns of each level of indentation
+    pub indent: usize,
+    /// Indent with tabs rather than spaces
+    pub tabs: bool,
+    /// Lines longer than this are broken, where possible
+    pub line_width: usize,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        FormatOptions {
+            indent: 4,
+            tabs: false,
+            line_width: 80,
+        }
+    }
+}
+
+/// Format a source file. A file with syntax errors is not formatted; the errors are
+/// returned instead.
+pub fn format(src: &str, options: &FormatOptions) -> Result<String, Vec<Diagnostic>> {
+    let (tree, comments, errors) = parse(src, 0)?;
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let mut formatter = Formatter {
+        src,
+        comments: &comments,
+        next_comment: 0,
+        last_end: 0,
+    };
+
+    let doc = formatter.source_unit(&tree);
+
+    let out = Printer {
+        options,
+        out: String::new(),
+        column: 0,
+    }
+    .print(&doc);
+
+    // a mistake in the formatter must not change the meaning of the source or lose comments
+    match parse(&out, 0) {
+        Ok((formatted_tree, formatted_comments, errors))
+            if errors.is_empty()
+                && normalized(&formatted_tree) == normalized(&tree)
+                && comment_texts(&formatted_comments) == comment_texts(&comments) =>
+        {
+            Ok(out)
+        }
+        _ => Err(vec![Diagnostic::error(
+            Loc(0, 0, 0),
+            "formatting failed, the formatted source does not match the original".to_string(),
+        )]),
+    }
+}
+
+/// The parse tree as text, without what formatting changes: the locations, and the order of
+/// the attributes of functions and variables
+fn normalized(tree: &SourceUnit) -> String {
+    let locations = Regex::new(r"Loc\(\d+, \d+, \d+\)|offset: \d+").unwrap();
+    let attribute_lists = Regex::new(r"\b(?:trailing_attributes|attributes|attrs): \[").unwrap();
+
+    let mut tree = locations
+        .replace_all(&format!("{:?}", tree), "")
+        .into_owned();
+
+    let starts: Vec<usize> = attribute_lists
+        .find_iter(&tree)
+        .map(|list| list.end())
+        .collect();
+
+    // nested lists come later in the text, so they are sorted before the lists they are in
+    for start in starts.into_iter().rev() {
+        let (end, mut attributes) = list_items(&tree[start..]);
+
+        // the same order as the formatter uses; the sort is stable, like in the formatter
+        attributes.sort_by_key(|attr| {
+            [
+                "Visibility",
+                "StateMutability",
+                "Virtual",
+                "Override",
+                "BaseOrModifier",
+                "Constant",
+            ]
+            .iter()
+            .position(|kind| attr.starts_with(kind))
+        });
+
+        let attributes = attributes.join(", ");
+
+        tree.replace_range(start..start + end, &attributes);
+    }
+
+    tree
+}
+
+/// Split the debug text of a list into its items, up to the closing bracket. Returns the
+/// offset of the closing bracket and the items.
+fn list_items(text: &str) -> (usize, Vec<String>) {
+    let mut items = Vec::new();
+    let mut depth = 0;
+    let mut item_start = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (i, c) in text.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => (),
+            }
+
+            continue;
+        }
+
+        match c {
+            '"' => in_string = true,
+            '(' | '[' | '{' => depth += 1,
+            ')' | '}' => depth -= 1,
+            ']' if depth > 0 => depth -= 1,
+            ']' | ',' if depth == 0 => {
+                let item = text[item_start..i].trim();
+
+                if !item.is_empty() {
+                    items.push(item.to_owned());
+                }
+
+                if c == ']' {
+                    return (i, items);
+                }
+
+                item_start = i + 1;
+            }
+            _ => (),
+        }
+    }
+
+    (text.len(), items)
+}
+
+/// The text of the comments. Formatting may change the indentation of the lines of a block
+/// comment, and removes trailing whitespace.
+fn comment_texts(comments: &[Comment]) -> Vec<String> {
+    comments
+        .iter()
+        .map(|comment| {
+            let (kind, text) = match comment {
+                Comment::Line(_, text) => ("//", text),
+                Comment::DocLine(_, text) => ("///", text),
+                Comment::Block(_, text) => ("/*", text),
+                Comment::DocBlock(_, text) => ("/**", text),
+            };
+
+            let lines: Vec<&str> = text.lines().map(str::trim).collect();
+
+            format!("{}{}", kind, lines.join("\n"))
+        })
+        .collect()
+}
+
+/// A document to print
+#[derive(Clone)]
+enum Doc {
+    Text(String),
+    /// A space, or a line break if the group is broken
+    Line,
+    /// Nothing, or a line break if the group is broken
+    SoftLine,
+    /// A line break, which breaks all the groups it is in
+    HardLine,
+    Concat(Vec<Doc>),
+    /// The lines which start within are indented one more level
+    Indent(Vec<Doc>),
+    /// Printed on one line if it fits, otherwise its lines are broken
+    Group(Vec<Doc>),
+}
+
+fn text<S: Into<String>>(s: S) -> Doc {
+    Doc::Text(s.into())
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Mode {
+    Flat,
+    Break,
+}
+
+struct Printer<'a> {
+    options: &'a FormatOptions,
+    out: String,
+    column: usize,
+}
+
+impl<'a> Printer<'a> {
+    fn print(mut self, doc: &Doc) -> String {
+        let mut stack = vec![(0, Mode::Break, doc)];
+        // the indentation is written with the first text on the line, so that empty lines
+        // do not have trailing whitespace
+        let mut pending_indent = None;
+
+        while let Some((indent, mode, doc)) = stack.pop() {
+            match doc {
+                Doc::Text(s) => {
+                    if s.is_empty() {
+                        continue;
+                    }
+
+                    if let Some(indent) = pending_indent.take() {
+                        let indentation = if self.options.tabs {
+                            "\t".repeat(indent)
+                        } else {
+                            " ".repeat(indent * self.options.indent)
+                        };
+
+                        self.out.push_str(&indentation);
+                    }
+
+                    self.out.push_str(s);
+                    self.column += s.chars().count();
+                }
+                Doc::Line if mode == Mode::Flat => {
+                    self.out.push(' ');
+                    self.column += 1;
+                }
+                Doc::SoftLine if mode == Mode::Flat => (),
+                Doc::Line | Doc::SoftLine | Doc::HardLine => {
+                    let len = self.out.trim_end_matches(|c| c == ' ' || c == '\t').len();
+
+                    self.out.truncate(len);
+                    self.out.push('\n');
+
+                    pending_indent = Some(indent);
+                    self.column = indent * self.options.indent;
+                }
+                Doc::Concat(docs) => stack.extend(docs.iter().rev().map(|doc| (indent, mode, doc))),
+                Doc::Indent(docs) => {
+                    stack.extend(docs.iter().rev().map(|doc| (indent + 1, mode, doc)))
+                }
+                Doc::Group(docs) => {
+                    let mode = if mode == Mode::Flat || self.fits(docs, &stack) {
+                        Mode::Flat
+                    } else {
+                        Mode::Break
+                    };
+
+                    stack.extend(docs.iter().rev().map(|doc| (indent, mode, doc)));
+                }
+            }
+        }
+
+        let len = self.out.trim_end().len();
+
+        self.out.truncate(len);
+
+        if !self.out.is_empty() {
+            self.out.push('\n');
+        }
+
+        self.out
+    }
+
+    /// Does the group fit on the rest of the line on one line, along with what follows it
+    /// up to the next line break
+    fn fits(&self, docs: &[Doc], rest: &[(usize, Mode, &Doc)]) -> bool {
+        let mut width = self.options.line_width as isize - self.column as isize;
+        let mut stack: Vec<(Mode, &Doc)> = docs.iter().rev().map(|doc| (Mode::Flat, doc)).collect();
+        let mut rest = rest.iter().rev();
+        let mut in_rest = false;
+
+        loop {
+            if width < 0 {
+                return false;
+            }
+
+            let (mode, doc) = match stack.pop() {
+                Some(next) => next,
+                None => match rest.next() {
+                    Some((_, mode, doc)) => {
+                        in_rest = true;
+                        (*mode, *doc)
+                    }
+                    None => return true,
+                },
+            };
+
+            match doc {
+                Doc::Text(s) => width -= s.chars().count() as isize,
+                Doc::Line if mode == Mode::Flat => width -= 1,
+                Doc::SoftLine if mode == Mode::Flat => (),
+                Doc::HardLine if !in_rest => return false,
+                Doc::Line | Doc::SoftLine | Doc::HardLine => return true,
+                Doc::Concat(docs) | Doc::Indent(docs) | Doc::Group(docs) => {
+                    stack.extend(docs.iter().rev().map(|doc| (mode, doc)))
+                }
+            }
+        }
+    }
+}
+
+struct Formatter<'a> {
+    src: &'a str,
+    comments: &'a [Comment],
+    /// The first comment which has not been placed yet
+    next_comment: usize,
+    /// The end of the last definition, statement, list item or comment which was placed
+    last_end: usize,
+}
+
+impl<'a> Formatter<'a> {
+    fn source(&self, loc: &Loc) -> &'a str {
+        &self.src[loc.1..loc.2]
+    }
+
+    /// The offset of the first token after the offset which matches
+    fn find_token(&self, offset: usize, matches: fn(&Token) -> bool) -> usize {
+        Lexer::new(&self.src[offset..])
+            .filter_map(Result::ok)
+            .find(|(_, token, _)| matches(token))
+            .map_or(self.src.len(), |(start, _, _)| offset + start)
+    }
+
+    /// The offset of the `}` which closes the first `{` after the offset
+    fn closing_brace(&self, offset: usize) -> usize {
+        let mut depth = 0;
+
+        for (start, token, _) in Lexer::new(&self.src[offset..]).filter_map(Result::ok) {
+            match token {
+                Token::OpenCurlyBrace => depth += 1,
+                Token::CloseCurlyBrace => {
+                    depth -= 1;
+
+                    if depth == 0 {
+                        return offset + start;
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        self.src.len()
+    }
+
+    /// The next comment, if it starts before the offset
+    fn comment_before(&self, offset: usize) -> Option<&'a Comment> {
+        self.comments
+            .get(self.next_comment)
+            .filter(|comment| comment.loc().1 < offset)
+    }
+
+    /// Is there an empty line in the source between the last thing placed and the offset
+    fn blank_line(&self, offset: usize) -> bool {
+        if offset <= self.last_end {
+            return false;
+        }
+
+        let lines: Vec<&str> = self.src[self.last_end..offset].split('\n').collect();
+
+        lines.len() > 2
+            && lines[1..lines.len() - 1]
+                .iter()
+                .any(|l| l.trim().is_empty())
+    }
+
+    /// Start a new line in a sequence of definitions or statements, after an empty line
+    /// if required or if the source has one
+    fn separator(&self, docs: &mut Vec<Doc>, offset: usize, blank: bool) {
+        docs.push(Doc::HardLine);
+
+        if blank || self.blank_line(offset) {
+            docs.push(Doc::HardLine);
+        }
+    }
+
+    /// Place the next comment. The lines of a block comment are indented like its first
+    /// line was in the source.
+    fn comment(&mut self, comment: &Comment) -> Doc {
+        self.next_comment += 1;
+
+        let loc = comment.loc();
+
+        self.last_end = loc.2;
+
+        let (text, block) = match comment {
+            Comment::Line(_, text) => (format!("//{}", text), false),
+            Comment::DocLine(_, text) => (format!("///{}", text), false),
+            Comment::Block(_, text) => (format!("/*{}*/", text), true),
+            Comment::DocBlock(_, text) => (format!("/**{}*/", text), true),
+        };
+
+        if !block {
+            return Doc::Text(text.trim_end().to_owned());
+        }
+
+        let column = loc.1 - self.src[..loc.1].rfind('\n').map_or(0, |pos| pos + 1);
+        let mut docs = Vec::new();
+
+        for (no, line) in text.lines().enumerate() {
+            if no > 0 {
+                docs.push(Doc::HardLine);
+            }
+
+            let trimmed = line.trim_start();
+            let indent = line.len() - trimmed.len();
+
+            if no == 0 {
+                docs.push(self::text(line.trim_end()));
+            } else if trimmed.starts_with('*') {
+                docs.push(self::text(format!(" {}", trimmed.trim_end())));
+            } else {
+                docs.push(self::text(line[indent.min(column)..].trim_end()));
+            }
+        }
+
+        Doc::Concat(docs)
+    }
+
+    /// Place the comments after an item on the same line, and the comments within the item
+    /// which were not placed yet
+    fn trailing(&mut self, end: usize) -> Option<Doc> {
+        let mut docs = Vec::new();
+        let mut line_comment = false;
+
+        self.last_end = end;
+
+        while let Some(comment) = self.comments.get(self.next_comment) {
+            let loc = comment.loc();
+
+            let same_line = loc.1 >= end
+                && self.src[self.last_end.min(loc.1)..loc.1]
+                    .chars()
+                    .all(|c| c == ' ' || c == '\t' || c == ';' || c == ',');
+
+            if loc.1 >= end && !same_line {
+                break;
+            }
+
+            // nothing can follow a line comment on the same line
+            docs.push(if line_comment {
+                Doc::HardLine
+            } else {
+                text(" ")
+            });
+
+            line_comment = matches!(comment, Comment::Line(..) | Comment::DocLine(..));
+
+            docs.push(self.comment(comment));
+        }
+
+        self.last_end = self.last_end.max(end);
+
+        if docs.is_empty() {
+            None
+        } else {
+            Some(Doc::Concat(docs))
+        }
+    }
+
+    /// Print the definitions or statements of a block, one on each line with the comments
+    /// between them. The comments before the end of the block are placed after the last item.
+    fn items<T>(
+        &mut self,
+        items: &[&T],
+        span: fn(&Self, &T) -> (usize, usize),
+        print: fn(&mut Self, &T) -> Doc,
+        separate: fn(&T, &T) -> bool,
+        end: usize,
+    ) -> Vec<Doc> {
+        let mut docs = Vec::new();
+        let mut prev: Option<&T> = None;
+
+        for &item in items {
+            let (item_start, item_end) = span(self, item);
+            let mut blank = prev.map_or(false, |prev| separate(prev, item));
+            // a block comment on the same line as the item stays before it
+            let mut inline = false;
+
+            while let Some(comment) = self.comment_before(item_start) {
+                if !docs.is_empty() {
+                    self.separator(&mut docs, comment.loc().1, blank);
+                }
+
+                blank = false;
+
+                docs.push(self.comment(comment));
+
+                inline = matches!(comment, Comment::Block(..) | Comment::DocBlock(..))
+                    && !self.src[comment.loc().2..item_start].contains('\n')
+                    && self.comment_before(item_start).is_none();
+            }
+
+            if inline {
+                docs.push(text(" "));
+            } else if !docs.is_empty() {
+                self.separator(&mut docs, item_start, blank);
+            }
+
+            docs.push(print(self, item));
+
+            if let Some(trailing) = self.trailing(item_end) {
+                docs.push(trailing);
+            }
+
+            prev = Some(item);
+        }
+
+        while let Some(comment) = self.comment_before(end) {
+            if !docs.is_empty() {
+                self.separator(&mut docs, comment.loc().1, false);
+            }
+
+            docs.push(self.comment(comment));
+        }
+
+        docs
+    }
+
+    /// A block of definitions or statements, or `{}` if it is empty
+    fn block(&self, body: Vec<Doc>) -> Doc {
+        if body.is_empty() {
+            text("{}")
+        } else {
+            let mut indented = vec![Doc::HardLine];
+
+            indented.extend(body);
+
+            Doc::Concat(vec![
+                text("{"),
+                Doc::Indent(indented),
+                Doc::HardLine,
+                text("}"),
+            ])
+        }
+    }
+
+    /// Print a comma separated list between the delimiters. The list is broken with each item
+    /// on its own line if it does not fit on one line, or if it has comments. The end is the
+    /// offset of the closing delimiter.
+    #[allow(clippy::too_many_arguments)]
+    fn list<T>(
+        &mut self,
+        open: &str,
+        items: &[T],
+        span: fn(&T) -> (usize, usize),
+        print: fn(&mut Self, &T) -> Doc,
+        close: &str,
+        end: usize,
+        force_break: bool,
+    ) -> Doc {
+        if items.is_empty() {
+            return text(format!("{}{}", open, close));
+        }
+
+        let broken = force_break || self.comment_before(end).is_some();
+        let line = if broken { Doc::HardLine } else { Doc::Line };
+        let mut docs = Vec::new();
+
+        for (no, item) in items.iter().enumerate() {
+            let (item_start, item_end) = span(item);
+
+            while let Some(comment) = self.comment_before(item_start) {
+                docs.push(self.comment(comment));
+                docs.push(Doc::HardLine);
+            }
+
+            docs.push(print(self, item));
+
+            let last = no + 1 == items.len();
+
+            if !last {
+                docs.push(text(","));
+            }
+
+            if let Some(trailing) = self.trailing(item_end) {
+                docs.push(trailing);
+            }
+
+            if !last {
+                docs.push(line.clone());
+            }
+        }
+
+        while let Some(comment) = self.comment_before(end) {
+            docs.push(Doc::HardLine);
+            docs.push(self.comment(comment));
+        }
+
+        let edge = if broken { Doc::HardLine } else { Doc::SoftLine };
+
+        Doc::Group(vec![
+            text(open),
+            Doc::Indent(vec![edge.clone(), Doc::Concat(docs)]),
+            edge,
+            text(close),
+        ])
+    }
+
+    fn source_unit(&mut self, unit: &SourceUnit) -> Doc {
+        let parts: Vec<&SourceUnitPart> = unit
+            .0
+            .iter()
+            .filter(|part| !matches!(part, SourceUnitPart::StraySemicolon(_)))
+            .collect();
+
+        let docs = self.items(
+            &parts,
+            Self::source_unit_part_span,
+            Self::source_unit_part,
+            |prev, next| is_definition(prev) || is_definition(next),
+            self.src.len(),
+        );
+
+        Doc::Concat(docs)
+    }
+
+    fn source_unit_part_span(&self, part: &SourceUnitPart) -> (usize, usize) {
+        match part {
+            SourceUnitPart::ContractDefinition(contract) => {
+                (contract.loc.1, self.closing_brace(contract.loc.2) + 1)
+            }
+            SourceUnitPart::PragmaDirective(loc, _, _) | SourceUnitPart::StraySemicolon(loc) => {
+                (loc.1, loc.2)
+            }
+            SourceUnitPart::ImportDirective(import) => {
+                let path = match import {
+                    Import::Plain(path)
+                    | Import::GlobalSymbol(path, _)
+                    | Import::Rename(path, _) => path,
+                };
+
+                // the import has no location of its own
+                (
+                    self.src[..path.loc.1].rfind("import").unwrap_or(path.loc.1),
+                    self.find_token(path.loc.2, |token| *token == Token::Semicolon) + 1,
+                )
+            }
+            SourceUnitPart::EnumDefinition(def) => (def.loc.1, def.loc.2),
+            SourceUnitPart::StructDefinition(def) => (def.loc.1, def.loc.2),
+            SourceUnitPart::EventDefinition(def) => (def.loc.1, def.loc.2),
+            SourceUnitPart::FunctionDefinition(func) => (func.loc.1, function_end(func)),
+            SourceUnitPart::VariableDefinition(var) => (var.loc.1, var.loc.2),
+        }
+    }
+
+    fn source_unit_part(&mut self, part: &SourceUnitPart) -> Doc {
+        match part {
+            SourceUnitPart::ContractDefinition(contract) => self.contract(contract),
+            SourceUnitPart::PragmaDirective(_, name, value) => {
+                text(format!("pragma {} {};", name.name, value.string.trim()))
+            }
+            SourceUnitPart::ImportDirective(import) => self.import(import),
+            SourceUnitPart::EnumDefinition(def) => self.enum_definition(def),
+            SourceUnitPart::StructDefinition(def) => self.struct_definition(def),
+            SourceUnitPart::EventDefinition(def) => self.event(def),
+            SourceUnitPart::FunctionDefinition(func) => self.function(func),
+            SourceUnitPart::VariableDefinition(var) => self.variable(var),
+            SourceUnitPart::StraySemicolon(_) => text(""),
+        }
+    }
+
+    fn import(&mut self, import: &Import) -> Doc {
+        match import {
+            Import::Plain(path) => text(format!("import {};", self.source(&path.loc))),
+            Import::GlobalSymbol(path, symbol) => text(format!(
+                "import {} as {};",
+                self.source(&path.loc),
+                symbol.name
+            )),
+            Import::Rename(path, symbols) => {
+                let last = symbols.last().unwrap();
+                let end = self.find_token(last.1.as_ref().unwrap_or(&last.0).loc.2, |token| {
+                    *token == Token::CloseCurlyBrace
+                });
+
+                let symbols = self.list(
+                    "{",
+                    symbols,
+                    |(from, to)| (from.loc.1, to.as_ref().unwrap_or(from).loc.2),
+                    |_, (from, to)| match to {
+                        Some(to) => text(format!("{} as {}", from.name, to.name)),
+                        None => text(&from.name),
+                    },
+                    "}",
+                    end,
+                    false,
+                );
+
+                Doc::Concat(vec![
+                    text("import "),
+                    symbols,
+                    text(format!(" from {};", self.source(&path.loc))),
+                ])
+            }
+        }
+    }
+
+    fn contract(&mut self, contract: &ContractDefinition) -> Doc {
+        let close = self.closing_brace(contract.loc.2);
+
+        let header = if contract.base.is_empty() {
+            text(format!("{} {} ", contract.ty, contract.name.name))
+        } else {
+            let mut bases = Vec::new();
+
+            for (no, base) in contract.base.iter().enumerate() {
+                if no > 0 {
+                    bases.push(text(","));
+                }
+
+                bases.push(Doc::Line);
+                bases.push(self.base(base));
+            }
+
+            Doc::Group(vec![
+                text(format!("{} {} is", contract.ty, contract.name.name)),
+                Doc::Indent(bases),
+                Doc::Line,
+            ])
+        };
+
+        let parts: Vec<&ContractPart> = contract
+            .parts
+            .iter()
+            .filter(|part| !matches!(part, ContractPart::StraySemicolon(_)))
+            .collect();
+
+        let body = self.items(
+            &parts,
+            Self::contract_part_span,
+            Self::contract_part,
+            |prev, next| is_contract_definition(prev) || is_contract_definition(next),
+            close,
+        );
+
+        Doc::Concat(vec![header, self.block(body)])
+    }
+
+    fn contract_part_span(&self, part: &ContractPart) -> (usize, usize) {
+        match part {
+            ContractPart::StructDefinition(def) => (def.loc.1, def.loc.2),
+            ContractPart::EventDefinition(def) => (def.loc.1, def.loc.2),
+            ContractPart::EnumDefinition(def) => (def.loc.1, def.loc.2),
+            ContractPart::VariableDefinition(var) => (var.loc.1, var.loc.2),
+            ContractPart::FunctionDefinition(func) => (func.loc.1, function_end(func)),
+            ContractPart::StraySemicolon(loc) => (loc.1, loc.2),
+            ContractPart::Using(using) => (using.loc.1, using.loc.2),
+        }
+    }
+
+    fn contract_part(&mut self, part: &ContractPart) -> Doc {
+        match part {
+            ContractPart::StructDefinition(def) => self.struct_definition(def),
+            ContractPart::EventDefinition(def) => self.event(def),
+            ContractPart::EnumDefinition(def) => self.enum_definition(def),
+            ContractPart::VariableDefinition(var) => self.variable(var),
+            ContractPart::FunctionDefinition(func) => self.function(func),
+            ContractPart::StraySemicolon(_) => text(""),
+            ContractPart::Using(using) => {
+                let ty = match &using.ty {
+                    Some(ty) => self.expression(ty),
+                    None => text("*"),
+                };
+
+                Doc::Concat(vec![
+                    text(format!("using {} for ", using.library.name)),
+                    ty,
+                    text(";"),
+                ])
+            }
+        }
+    }
+
+    fn base(&mut self, base: &Base) -> Doc {
+        match &base.args {
+            None => text(&base.name.name),
+            Some(args) => Doc::Concat(vec![
+                text(&base.name.name),
+                self.list(
+                    "(",
+                    args,
+                    expression_span,
+                    Self::expression,
+                    ")",
+                    base.loc.2 - 1,
+                    false,
+                ),
+            ]),
+        }
+    }
+
+    fn struct_definition(&mut self, def: &StructDefinition) -> Doc {
+        let fields: Vec<&VariableDeclaration> = def.fields.iter().collect();
+
+        let body = self.items(
+            &fields,
+            |_, field| (field.loc.1, field.loc.2),
+            |formatter, field| Doc::Concat(vec![formatter.variable_declaration(field), text(";")]),
+            |_, _| false,
+            def.loc.2 - 1,
+        );
+
+        Doc::Concat(vec![
+            text(format!("struct {} ", def.name.name)),
+            self.block(body),
+        ])
+    }
+
+    fn enum_definition(&mut self, def: &EnumDefinition) -> Doc {
+        let values = self.list(
+            "{",
+            &def.values,
+            |value| (value.loc.1, value.loc.2),
+            |_, value| text(&value.name),
+            "}",
+            def.loc.2 - 1,
+            true,
+        );
+
+        Doc::Concat(vec![text(format!("enum {} ", def.name.name)), values])
+    }
+
+    fn event(&mut self, def: &EventDefinition) -> Doc {
+        let end = match def.fields.last() {
+            Some(field) => self.find_token(field.loc.2, |token| *token == Token::CloseParenthesis),
+            None => def.loc.2,
+        };
+
+        let fields = self.list(
+            "(",
+            &def.fields,
+            |field| (field.loc.1, field.loc.2),
+            |formatter, field| {
+                let mut docs = vec![formatter.expression(&field.ty)];
+
+                if field.indexed {
+                    docs.push(text(" indexed"));
+                }
+
+                if let Some(name) = &field.name {
+                    docs.push(text(format!(" {}", name.name)));
+                }
+
+                Doc::Concat(docs)
+            },
+            ")",
+            end,
+            false,
+        );
+
+        Doc::Concat(vec![
+            text(format!("event {}", def.name.name)),
+            fields,
+            text(if def.anonymous { " anonymous;" } else { ";" }),
+        ])
+    }
+
+    fn variable(&mut self, var: &VariableDefinition) -> Doc {
+        let mut docs = vec![self.expression(&var.ty)];
+
+        // the visibility comes before constant
+        let mut attrs: Vec<&VariableAttribute> = var.attrs.iter().collect();
+
+        attrs.sort_by_key(|attr| matches!(attr, VariableAttribute::Constant(_)));
+
+        for attr in attrs {
+            docs.push(text(match attr {
+                VariableAttribute::Visibility(visibility) => format!(" {}", visibility),
+                VariableAttribute::Constant(_) => " constant".to_owned(),
+            }));
+        }
+
+        docs.push(text(format!(" {}", var.name.name)));
+
+        if let Some(initializer) = &var.initializer {
+            docs.push(text(" = "));
+            docs.push(self.expression(initializer));
+        }
+
+        docs.push(text(";"));
+
+        Doc::Concat(docs)
+    }
+
+    fn function(&mut self, func: &FunctionDefinition) -> Doc {
+        let mut docs = vec![text(match (&func.ty, &func.name) {
+            (FunctionTy::Function, Some(name)) => format!("function {}", name.name),
+            (FunctionTy::Modifier, Some(name)) => format!("modifier {}", name.name),
+            (ty, _) => ty.to_string(),
+        })];
+
+        // a modifier without parameters is written without parentheses
+        if func.ty != FunctionTy::Modifier || !func.params.is_empty() {
+            docs.push(self.parameters(&func.params));
+        }
+
+        let mut attrs = Vec::new();
+
+        for attr in sorted_attributes(&func.attributes) {
+            attrs.push(Doc::Line);
+            attrs.push(self.function_attribute(attr));
+        }
+
+        if !func.returns.is_empty() {
+            attrs.push(Doc::Line);
+            attrs.push(text("returns "));
+            attrs.push(self.parameters(&func.returns));
+        }
+
+        match &func.body {
+            Some(body) => {
+                if attrs.is_empty() {
+                    docs.push(text(" "));
+                } else {
+                    // if the attributes do not fit, the body starts on its own line
+                    docs.push(Doc::Group(vec![Doc::Indent(attrs), Doc::Line]));
+                }
+
+                docs.push(self.statement(body));
+            }
+            None => {
+                docs.push(Doc::Group(vec![Doc::Indent(attrs)]));
+                docs.push(text(";"));
+            }
+        }
+
+        Doc::Concat(docs)
+    }
+
+    fn function_attribute(&mut self, attr: &FunctionAttribute) -> Doc {
+        match attr {
+            FunctionAttribute::StateMutability(StateMutability::Constant(_)) => text("constant"),
+            FunctionAttribute::StateMutability(mutability) => text(mutability.to_string()),
+            FunctionAttribute::Visibility(visibility) => text(visibility.to_string()),
+            FunctionAttribute::Virtual(_) => text("virtual"),
+            FunctionAttribute::Override(_, bases) if bases.is_empty() => text("override"),
+            FunctionAttribute::Override(_, bases) => text(format!(
+                "override({})",
+                bases
+                    .iter()
+                    .map(|base| base.name.as_str())
+                    .collect::<Vec<&str>>()
+                    .join(", ")
+            )),
+            FunctionAttribute::BaseOrModifier(_, base) => self.base(base),
+        }
+    }
+
+    fn parameters(&mut self, params: &[(Loc, Option<Parameter>)]) -> Doc {
+        let end = match params.last() {
+            Some((loc, _)) => self.find_token(loc.2, |token| *token == Token::CloseParenthesis),
+            None => 0,
+        };
+
+        self.list(
+            "(",
+            params,
+            |(loc, _)| (loc.1, loc.2),
+            Self::optional_parameter,
+            ")",
+            end,
+            false,
+        )
+    }
+
+    fn optional_parameter(&mut self, (_, param): &(Loc, Option<Parameter>)) -> Doc {
+        match param {
+            Some(param) => self.parameter(param),
+            None => text(""),
+        }
+    }
+
+    fn parameter(&mut self, param: &Parameter) -> Doc {
+        let mut docs = vec![self.expression(&param.ty)];
+
+        if let Some(storage) = &param.storage {
+            docs.push(text(format!(" {}", storage)));
+        }
+
+        if let Some(name) = &param.name {
+            docs.push(text(format!(" {}", name.name)));
+        }
+
+        Doc::Concat(docs)
+    }
+
+    fn variable_declaration(&mut self, decl: &VariableDeclaration) -> Doc {
+        let mut docs = vec![self.expression(&decl.ty)];
+
+        if let Some(storage) = &decl.storage {
+            docs.push(text(format!(" {}", storage)));
+        }
+
+        docs.push(text(format!(" {}", decl.name.name)));
+
+        Doc::Concat(docs)
+    }
+
+    /// The body of an if, while, for, or do while statement. A block starts on the same
+    /// line; another statement on the next line, unless it fits on the same line.
+    fn body(&mut self, stmt: &Statement) -> Doc {
+        if let Statement::Block(..) = stmt {
+            Doc::Concat(vec![text(" "), self.statement(stmt)])
+        } else {
+            Doc::Group(vec![Doc::Indent(vec![Doc::Line, self.statement(stmt)])])
+        }
+    }
+
+    fn statement(&mut self, stmt: &Statement) -> Doc {
+        match stmt {
+            Statement::Block(loc, stmts) => {
+                let stmts: Vec<&Statement> = stmts.iter().collect();
+
+                let body = self.items(
+                    &stmts,
+                    |_, stmt| (stmt.loc().1, stmt.loc().2),
+                    Self::statement,
+                    |_, _| false,
+                    loc.2 - 1,
+                );
+
+                self.block(body)
+            }
+            Statement::Args(loc, args) => self.list(
+                "{",
+                args,
+                |arg| (arg.loc.1, arg.loc.2),
+                Self::named_argument,
+                "}",
+                loc.2 - 1,
+                false,
+            ),
+            Statement::If(_, cond, then, otherwise) => {
+                let mut docs = vec![text("if ("), self.expression(cond), text(")")];
+
+                docs.push(self.body(then));
+
+                if let Some(otherwise) = otherwise {
+                    if let Statement::Block(..) = **then {
+                        docs.push(text(" else"));
+                    } else {
+                        docs.push(Doc::HardLine);
+                        docs.push(text("else"));
+                    }
+
+                    if let Statement::If(..) = **otherwise {
+                        docs.push(text(" "));
+                        docs.push(self.statement(otherwise));
+                    } else {
+                        docs.push(self.body(otherwise));
+                    }
+                }
+
+                Doc::Concat(docs)
+            }
+            Statement::While(_, cond, body) => Doc::Concat(vec![
+                text("while ("),
+                self.expression(cond),
+                text(")"),
+                self.body(body),
+            ]),
+            Statement::Expression(..) | Statement::VariableDefinition(..) => {
+                Doc::Concat(vec![self.simple_statement(stmt), text(";")])
+            }
+            Statement::For(_, init, cond, next, body) => {
+                let mut docs = vec![text("for (")];
+
+                if let Some(init) = init {
+                    docs.push(self.simple_statement(init));
+                }
+
+                docs.push(text(";"));
+
+                if let Some(cond) = cond {
+                    docs.push(text(" "));
+                    docs.push(self.expression(cond));
+                }
+
+                docs.push(text(";"));
+
+                if let Some(next) = next {
+                    docs.push(text(" "));
+                    docs.push(self.simple_statement(next));
+                }
+
+                docs.push(text(")"));
+
+                match body {
+                    Some(body) => docs.push(self.body(body)),
+                    None => docs.push(text(";")),
+                }
+
+                Doc::Concat(docs)
+            }
+            Statement::DoWhile(_, body, cond) => {
+                let mut docs = vec![text("do"), self.body(body)];
+
+                if let Statement::Block(..) = **body {
+                    docs.push(text(" "));
+                } else {
+                    docs.push(Doc::HardLine);
+                }
+
+                docs.push(text("while ("));
+                docs.push(self.expression(cond));
+                docs.push(text(");"));
+
+                Doc::Concat(docs)
+            }
+            Statement::Continue(_) => text("continue;"),
+            Statement::Break(_) => text("break;"),
+            Statement::Throw(_) => text("throw;"),
+            Statement::Return(_, None) => text("return;"),
+            Statement::Return(_, Some(expr)) => {
+                Doc::Concat(vec![text("return "), self.expression(expr), text(";")])
+            }
+            Statement::Emit(_, event) => {
+                Doc::Concat(vec![text("emit "), self.expression(event), text(";")])
+            }
+            Statement::Try(_, expr, returns, error, catch_all) => {
+                let mut docs = vec![text("try "), self.expression(expr)];
+
+                if let Some((params, ok)) = returns {
+                    docs.push(text(" returns "));
+                    docs.push(self.parameters(params));
+                    docs.push(text(" "));
+                    docs.push(self.statement(ok));
+                }
+
+                if let Some(error) = error {
+                    let (name, param, block) = error.as_ref();
+
+                    docs.push(text(format!(" catch {}(", name.name)));
+                    docs.push(self.parameter(param));
+                    docs.push(text(") "));
+                    docs.push(self.statement(block));
+                }
+
+                let (param, block) = catch_all.as_ref();
+
+                docs.push(text(" catch ("));
+                docs.push(self.parameter(param));
+                docs.push(text(") "));
+                docs.push(self.statement(block));
+
+                Doc::Concat(docs)
+            }
+            Statement::Error(loc) => text(self.source(loc)),
+        }
+    }
+
+    /// A variable definition or expression statement, without the semicolon
+    fn simple_statement(&mut self, stmt: &Statement) -> Doc {
+        match stmt {
+            Statement::VariableDefinition(_, decl, None) => self.variable_declaration(decl),
+            Statement::VariableDefinition(_, decl, Some(init)) => Doc::Concat(vec![
+                self.variable_declaration(decl),
+                text(" = "),
+                self.expression(init),
+            ]),
+            Statement::Expression(_, expr) => self.expression(expr),
+            _ => self.statement(stmt),
+        }
+    }
+
+    fn named_argument(&mut self, arg: &NamedArgument) -> Doc {
+        Doc::Concat(vec![
+            text(format!("{}: ", arg.name.name)),
+            self.expression(&arg.expr),
+        ])
+    }
+
+    /// An operand of an operator, in parentheses if it binds less tightly than the operator
+    /// allows there
+    fn operand(&mut self, expr: &Expression, precedence: u8) -> Doc {
+        if self::precedence(expr) > precedence {
+            Doc::Concat(vec![text("("), self.expression(expr), text(")")])
+        } else {
+            self.expression(expr)
+        }
+    }
+
+    fn binary(&mut self, left: &Expression, op: &str, right: &Expression) -> Doc {
+        let precedence = precedence_of(op);
+
+        Doc::Group(vec![
+            self.operand(left, precedence),
+            text(format!(" {}", op)),
+            Doc::Indent(vec![Doc::Line, self.operand(right, precedence - 1)]),
+        ])
+    }
+
+    fn assign(&mut self, left: &Expression, op: &str, right: &Expression) -> Doc {
+        Doc::Concat(vec![
+            self.operand(left, 14),
+            text(format!(" {} ", op)),
+            self.operand(right, 15),
+        ])
+    }
+
+    fn prefix(&mut self, op: &str, expr: &Expression) -> Doc {
+        // `- -a` must not become `--a`
+        let parenthesize = match op {
+            "-" => matches!(
+                expr,
+                Expression::UnaryMinus(..) | Expression::PreDecrement(..)
+            ),
+            "+" => matches!(
+                expr,
+                Expression::UnaryPlus(..) | Expression::PreIncrement(..)
+            ),
+            _ => false,
+        };
+
+        let operand = if parenthesize {
+            Doc::Concat(vec![text("("), self.expression(expr), text(")")])
+        } else {
+            self.operand(expr, 2)
+        };
+
+        Doc::Concat(vec![text(op), operand])
+    }
+
+    fn expression(&mut self, expr: &Expression) -> Doc {
+        match expr {
+            Expression::PostIncrement(_, expr) => {
+                Doc::Concat(vec![self.operand(expr, 0), text("++")])
+            }
+            Expression::PostDecrement(_, expr) => {
+                Doc::Concat(vec![self.operand(expr, 0), text("--")])
+            }
+            Expression::New(_, call) => Doc::Concat(vec![text("new "), self.expression(call)]),
+            Expression::ArraySubscript(_, array, index) => {
+                let mut docs = vec![self.operand(array, 0), text("[")];
+
+                if let Some(index) = index {
+                    docs.push(self.expression(index));
+                }
+
+                docs.push(text("]"));
+
+                Doc::Concat(docs)
+            }
+            Expression::MemberAccess(_, expr, member) => Doc::Concat(vec![
+                self.operand(expr, 0),
+                text(format!(".{}", member.name)),
+            ]),
+            Expression::FunctionCall(loc, function, args) => Doc::Concat(vec![
+                self.operand(function, 0),
+                self.list(
+                    "(",
+                    args,
+                    expression_span,
+                    Self::expression,
+                    ")",
+                    loc.2 - 1,
+                    false,
+                ),
+            ]),
+            Expression::FunctionCallBlock(_, function, block) => {
+                Doc::Concat(vec![self.operand(function, 0), self.statement(block)])
+            }
+            Expression::NamedFunctionCall(loc, function, args) => Doc::Concat(vec![
+                self.operand(function, 0),
+                self.list(
+                    "({",
+                    args,
+                    |arg| (arg.loc.1, arg.loc.2),
+                    Self::named_argument,
+                    "})",
+                    loc.2 - 1,
+                    false,
+                ),
+            ]),
+            Expression::Not(_, expr) => self.prefix("!", expr),
+            Expression::Complement(_, expr) => self.prefix("~", expr),
+            Expression::Delete(_, expr) => self.prefix("delete ", expr),
+            Expression::PreIncrement(_, expr) => self.prefix("++", expr),
+            Expression::PreDecrement(_, expr) => self.prefix("--", expr),
+            Expression::UnaryPlus(_, expr) => self.prefix("+", expr),
+            Expression::UnaryMinus(_, expr) => self.prefix("-", expr),
+            Expression::Power(_, l, r) => self.binary(l, "**", r),
+            Expression::Multiply(_, l, r) => self.binary(l, "*", r),
+            Expression::Divide(_, l, r) => self.binary(l, "/", r),
+            Expression::Modulo(_, l, r) => self.binary(l, "%", r),
+            Expression::Add(_, l, r) => self.binary(l, "+", r),
+            Expression::Subtract(_, l, r) => self.binary(l, "-", r),
+            Expression::ShiftLeft(_, l, r) => self.binary(l, "<<", r),
+            Expression::ShiftRight(_, l, r) => self.binary(l, ">>", r),
+            Expression::BitwiseAnd(_, l, r) => self.binary(l, "&", r),
+            Expression::BitwiseXor(_, l, r) => self.binary(l, "^", r),
+            Expression::BitwiseOr(_, l, r) => self.binary(l, "|", r),
+            Expression::Less(_, l, r) => self.binary(l, "<", r),
+            Expression::More(_, l, r) => self.binary(l, ">", r),
+            Expression::LessEqual(_, l, r) => self.binary(l, "<=", r),
+            Expression::MoreEqual(_, l, r) => self.binary(l, ">=", r),
+            Expression::Equal(_, l, r) => self.binary(l, "==", r),
+            Expression::NotEqual(_, l, r) => self.binary(l, "!=", r),
+            Expression::And(_, l, r) => self.binary(l, "&&", r),
+            Expression::Or(_, l, r) => self.binary(l, "||", r),
+            Expression::Ternary(_, cond, left, right) => Doc::Group(vec![
+                self.operand(cond, 14),
+                Doc::Indent(vec![
+                    Doc::Line,
+                    text("? "),
+                    self.operand(left, 13),
+                    Doc::Line,
+                    text(": "),
+                    self.operand(right, 13),
+                ]),
+            ]),
+            Expression::Assign(_, l, r) => self.assign(l, "=", r),
+            Expression::AssignOr(_, l, r) => self.assign(l, "|=", r),
+            Expression::AssignAnd(_, l, r) => self.assign(l, "&=", r),
+            Expression::AssignXor(_, l, r) => self.assign(l, "^=", r),
+            Expression::AssignShiftLeft(_, l, r) => self.assign(l, "<<=", r),
+            Expression::AssignShiftRight(_, l, r) => self.assign(l, ">>=", r),
+            Expression::AssignAdd(_, l, r) => self.assign(l, "+=", r),
+            Expression::AssignSubtract(_, l, r) => self.assign(l, "-=", r),
+            Expression::AssignMultiply(_, l, r) => self.assign(l, "*=", r),
+            Expression::AssignDivide(_, l, r) => self.assign(l, "/=", r),
+            Expression::AssignModulo(_, l, r) => self.assign(l, "%=", r),
+            Expression::BoolLiteral(_, value) => text(value.to_string()),
+            // literals are written as in the source
+            Expression::NumberLiteral(loc, _)
+            | Expression::HexNumberLiteral(loc, _)
+            | Expression::AddressLiteral(loc, _) => text(self.source(loc)),
+            Expression::StringLiteral(strings) => text(
+                strings
+                    .iter()
+                    .map(|s| self.source(&s.loc))
+                    .collect::<Vec<&str>>()
+                    .join(" "),
+            ),
+            Expression::HexLiteral(hex) => text(
+                hex.iter()
+                    .map(|s| self.source(&s.loc))
+                    .collect::<Vec<&str>>()
+                    .join(" "),
+            ),
+            Expression::Type(loc, ty) => self.ty(loc, ty),
+            Expression::Variable(id) => text(&id.name),
+            Expression::List(loc, params) => self.list(
+                "(",
+                params,
+                |(loc, _)| (loc.1, loc.2),
+                Self::optional_parameter,
+                ")",
+                loc.2 - 1,
+                false,
+            ),
+            Expression::ArrayLiteral(loc, values) => self.list(
+                "[",
+                values,
+                expression_span,
+                Self::expression,
+                "]",
+                loc.2 - 1,
+                false,
+            ),
+            Expression::Unit(_, expr, unit) => Doc::Concat(vec![
+                self.operand(expr, 0),
+                text(match unit {
+                    Unit::Seconds(_) => " seconds",
+                    Unit::Minutes(_) => " minutes",
+                    Unit::Hours(_) => " hours",
+                    Unit::Days(_) => " days",
+                    Unit::Weeks(_) => " weeks",
+                    Unit::Wei(_) => " wei",
+                    Unit::Szabo(_) => " szabo",
+                    Unit::Finney(_) => " finney",
+                    Unit::Ether(_) => " ether",
+                }),
+            ]),
+            Expression::This(_) => text("this"),
+        }
+    }
+
+    fn ty(&mut self, loc: &Loc, ty: &Type) -> Doc {
+        match ty {
+            Type::Mapping(_, key, value) => Doc::Concat(vec![
+                text("mapping("),
+                self.expression(key),
+                text(" => "),
+                self.expression(value),
+                text(")"),
+            ]),
+            Type::Function {
+                params,
+                attributes,
+                returns,
+                trailing_attributes,
+            } => {
+                let mut docs = vec![text("function"), self.parameters(params)];
+
+                for attr in sorted_attributes(attributes) {
+                    docs.push(text(" "));
+                    docs.push(self.function_attribute(attr));
+                }
+
+                if !returns.is_empty() {
+                    docs.push(text(" returns "));
+                    docs.push(self.parameters(returns));
+
+                    for attr in sorted_attributes(trailing_attributes) {
+                        docs.push(text(" "));
+                        docs.push(self.function_attribute(attr));
+                    }
+                }
+
+                Doc::Concat(docs)
+            }
+            Type::AddressPayable => text("address payable"),
+            // elementary types are written as in the source, e.g. `uint` is not changed
+            // to `uint256`
+            _ => text(self.source(loc)),
+        }
+    }
+}
+
+/// Is the part a definition with a body, which has an empty line before and after it
+fn is_definition(part: &SourceUnitPart) -> bool {
+    match part {
+        SourceUnitPart::ContractDefinition(_)
+        | SourceUnitPart::StructDefinition(_)
+        | SourceUnitPart::EnumDefinition(_) => true,
+        SourceUnitPart::FunctionDefinition(func) => func.body.is_some(),
+        _ => false,
+    }
+}
+
+/// Is the part a definition with a body, which has an empty line before and after it
+fn is_contract_definition(part: &ContractPart) -> bool {
+    match part {
+        ContractPart::StructDefinition(_) | ContractPart::EnumDefinition(_) => true,
+        ContractPart::FunctionDefinition(func) => func.body.is_some(),
+        _ => false,
+    }
+}
+
+/// The end of a function definition, including its body
+fn function_end(func: &FunctionDefinition) -> usize {
+    match &func.body {
+        Some(body) => body.loc().2,
+        None => func.loc.2,
+    }
+}
+
+/// The attributes in a stable order: visibility, mutability, virtual, override, and then the
+/// modifiers and base constructor calls. These keep their order, since that is the order in
+/// which the modifiers are applied.
+fn sorted_attributes(attributes: &[FunctionAttribute]) -> Vec<&FunctionAttribute> {
+    let mut attributes: Vec<&FunctionAttribute> = attributes.iter().collect();
+
+    attributes.sort_by_key(|attr| match attr {
+        FunctionAttribute::Visibility(_) => 0,
+        FunctionAttribute::StateMutability(_) => 1,
+        FunctionAttribute::Virtual(_) => 2,
+        FunctionAttribute::Override(..) => 3,
+        FunctionAttribute::BaseOrModifier(..) => 4,
+    });
+
+    attributes
+}
+
+/// The precedence of a binary operator; a higher number binds less tightly
+fn precedence_of(op: &str) -> u8 {
+    match op {
+        "**" => 3,
+        "*" | "/" | "%" => 4,
+        "+" | "-" => 5,
+        "<<" | ">>" => 6,
+        "&" => 7,
+        "^" => 8,
+        "|" => 9,
+        "<" | ">" | "<=" | ">=" => 10,
+        "==" | "!=" => 11,
+        "&&" => 12,
+        _ => 13,
+    }
+}
+
+/// The precedence of the expression, as in the grammar; a higher number binds less tightly
+fn precedence(expr: &Expression) -> u8 {
+    match expr {
+        Expression::Assign(..)
+        | Expression::AssignOr(..)
+        | Expression::AssignAnd(..)
+        | Expression::AssignXor(..)
+        | Expression::AssignShiftLeft(..)
+        | Expression::AssignShiftRight(..)
+        | Expression::AssignAdd(..)
+        | Expression::AssignSubtract(..)
+        | Expression::AssignMultiply(..)
+        | Expression::AssignDivide(..)
+        | Expression::AssignModulo(..) => 15,
+        Expression::Ternary(..) => 14,
+        Expression::Or(..) => 13,
+        Expression::And(..) => 12,
+        Expression::Equal(..) | Expression::NotEqual(..) => 11,
+        Expression::Less(..)
+        | Expression::More(..)
+        | Expression::LessEqual(..)
+        | Expression::MoreEqual(..) => 10,
+        Expression::BitwiseOr(..) => 9,
+        Expression::BitwiseXor(..) => 8,
+        Expression::BitwiseAnd(..) => 7,
+        Expression::ShiftLeft(..) | Expression::ShiftRight(..) => 6,
+        Expression::Add(..) | Expression::Subtract(..) => 5,
+        Expression::Multiply(..) | Expression::Divide(..) | Expression::Modulo(..) => 4,
+        Expression::Power(..) => 3,
+        Expression::Not(..)
+        | Expression::Complement(..)
+        | Expression::Delete(..)
+        | Expression::New(..)
+        | Expression::PreIncrement(..)
+        | Expression::PreDecrement(..)
+        | Expression::UnaryPlus(..)
+        | Expression::UnaryMinus(..) => 2,
+        _ => 0,
+    }
+}
+
+/// The start and end of the expression in the source. The location of an operator
+/// expression is only the operator.
+fn expression_span(expr: &Expression) -> (usize, usize) {
+    match expr {
+        Expression::Not(loc, expr)
+        | Expression::Complement(loc, expr)
+        | Expression::Delete(loc, expr)
+        | Expression::PreIncrement(loc, expr)
+        | Expression::PreDecrement(loc, expr)
+        | Expression::UnaryPlus(loc, expr)
+        | Expression::UnaryMinus(loc, expr) => (loc.1, expression_span(expr).1),
+        Expression::Power(_, l, r)
+        | Expression::Multiply(_, l, r)
+        | Expression::Divide(_, l, r)
+        | Expression::Modulo(_, l, r)
+        | Expression::Add(_, l, r)
+        | Expression::Subtract(_, l, r)
+        | Expression::ShiftLeft(_, l, r)
+        | Expression::ShiftRight(_, l, r)
+        | Expression::BitwiseAnd(_, l, r)
+        | Expression::BitwiseXor(_, l, r)
+        | Expression::BitwiseOr(_, l, r)
+        | Expression::Less(_, l, r)
+        | Expression::More(_, l, r)
+        | Expression::LessEqual(_, l, r)
+        | Expression::MoreEqual(_, l, r)
+        | Expression::Equal(_, l, r)
+        | Expression::NotEqual(_, l, r)
+        | Expression::And(_, l, r)
+        | Expression::Or(_, l, r)
+        | Expression::Ternary(_, l, _, r)
+        | Expression::Assign(_, l, r)
+        | Expression::AssignOr(_, l, r)
+        | Expression::AssignAnd(_, l, r)
+        | Expression::AssignXor(_, l, r)
+        | Expression::AssignShiftLeft(_, l, r)
+        | Expression::AssignShiftRight(_, l, r)
+        | Expression::AssignAdd(_, l, r)
+        | Expression::AssignSubtract(_, l, r)
+        | Expression::AssignMultiply(_, l, r)
+        | Expression::AssignDivide(_, l, r)
+        | Expression::AssignModulo(_, l, r) => (expression_span(l).0, expression_span(r).1),
+        Expression::FunctionCallBlock(loc, function, _) => (expression_span(function).0, loc.2),
+        Expression::StringLiteral(strings) => (strings[0].loc.1, strings[strings.len() - 1].loc.2),
+        Expression::HexLiteral(hex) => (hex[0].loc.1, hex[hex.len() - 1].loc.2),
+        _ => {
+            let loc = expr.loc();
+
+            (loc.1, loc.2)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{comment_texts, format, normalized, parse, FormatOptions};
+
+    fn check(src: &str, expected: &str) {
+        let formatted = format(src, &FormatOptions::default()).unwrap();
+
+        assert_eq!(formatted, expected);
+
+        // formatting again changes nothing
+        assert_eq!(
+            format(&formatted, &FormatOptions::default()).unwrap(),
+            formatted
+        );
+    }
+
+    #[test]
+    fn layout() {
+        check(
+            r#"pragma   solidity  ^0.8.0 ;
+import {x as y,z} from "b.sol";
+contract c is a(1,2), b {
+    uint  constant public  x=1;
+    mapping(address=>uint256) balances;
+    function f(uint a) view public returns(uint) {
+        if (a>1) return a*2+1; else { a++; }
+        for (uint i=0;i<10;i++) { a += i; }
+        return (a+1)-(a-1);
+    }
+    modifier m() { _; }
+}"#,
+            r#"pragma solidity ^0.8.0;
+import {x as y, z} from "b.sol";
+
+contract c is a(1, 2), b {
+    uint public constant x = 1;
+    mapping(address => uint256) balances;
+
+    function f(uint a) public view returns (uint) {
+        if (a > 1) return a * 2 + 1;
+        else {
+            a++;
+        }
+        for (uint i = 0; i < 10; i++) {
+            a += i;
+        }
+        return a + 1 - (a - 1);
+    }
+
+    modifier m {
+        _;
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn comments() {
+        check(
+            r#"// SPDX-License-Identifier: MIT
+
+/// A contract
+contract c {
+    enum E { A, B, // b
+     C }
+
+    /* block */ uint x; // the x
+
+
+    function f(
+        uint a, // first
+        uint b
+    ) public {
+        x = foo(a, /* inside */ b);
+        // last
+    }
+}
+// end"#,
+            r#"// SPDX-License-Identifier: MIT
+
+/// A contract
+contract c {
+    enum E {
+        A,
+        B, // b
+        C
+    }
+
+    /* block */ uint x; // the x
+
+    function f(
+        uint a, // first
+        uint b
+    ) public {
+        x = foo(
+            a, /* inside */
+            b
+        );
+        // last
+    }
+}
+// end
+"#,
+        );
+    }
+
+    #[test]
+    fn line_width() {
+        check(
+            r#"contract c {
+    function veryLongFunctionName(uint256 firstArgument, uint256 secondArgument) external virtual override onlyOwner returns (uint256);
+    function g() public {
+        x = someVeryLongFunctionName(argumentNumberOne, argumentNumberTwo) + anotherName(three);
+    }
+}"#,
+            r#"contract c {
+    function veryLongFunctionName(uint256 firstArgument, uint256 secondArgument)
+        external
+        virtual
+        override
+        onlyOwner
+        returns (uint256);
+
+    function g() public {
+        x = someVeryLongFunctionName(argumentNumberOne, argumentNumberTwo) +
+            anotherName(three);
+    }
+}
+"#,
+        );
+
+        let options = FormatOptions {
+            indent: 2,
+            tabs: false,
+            line_width: 30,
+        };
+
+        assert_eq!(
+            format("contract c { function f(uint a) public pure returns (uint) { return a; } }", &options).unwrap(),
+            "contract c {\n  function f(uint a)\n    public\n    pure\n    returns (uint)\n  {\n    return a;\n  }\n}\n"
+        );
+    }
+
+    #[test]
+    fn compare_with_original() {
+        let tree = |src: &str| parse(src, 0).unwrap().0;
+        let comments = |src: &str| parse(src, 0).unwrap().1;
+
+        // the locations and the order of the attributes are not compared
+        assert_eq!(
+            normalized(&tree("contract c { function f() view public {} uint constant public x = 1; }")),
+            normalized(&tree("contract c {\n    function f() public view {}\n\n    uint public constant x = 1;\n}\n"))
+        );
+
+        // a change in meaning is found
+        assert_ne!(
+            normalized(&tree(
+                "contract c { function f() public { x = a - (b - c); } }"
+            )),
+            normalized(&tree(
+                "contract c { function f() public { x = a - b - c; } }"
+            ))
+        );
+
+        // the indentation of block comments may change, but not their text
+        assert_eq!(
+            comment_texts(&comments("/* a\n       b */ contract c {}")),
+            comment_texts(&comments("/* a\n b */\ncontract c {}"))
+        );
+
+        assert_ne!(
+            comment_texts(&comments("// a\ncontract c {}")),
+            comment_texts(&comments("// b\ncontract c {}"))
+        );
+    }
+
+    #[test]
+    fn syntax_errors() {
+        let errors = format(
+            "contract c { function f() { x = ; } }",
+            &FormatOptions::default(),
+        )
+        .unwrap_err();
+
+        assert!(!errors.is_empty());
+    }
+}
@@ -304,6 +304,22 @@ pub struct Lexer<'input> {
     input: &'input str,
     chars: Peekable<CharIndices<'input>>,
     last_tokens: [Option<Token<'input>>; 2],
+    /// The comments which are not doc comments, in the order they were found
+    pub comments: Vec<Comment<'input>>,
+    /// The doc comments, which are passed to the parser as well
+    pub doc_comments: Vec<Comment<'input>>,
+}
+
+/// A comment as found in the source. Comments which are not doc comments are not passed to
+/// the parser; they are recorded for suppression comments and the formatter.
+#[derive(Debug, PartialEq)]
+pub struct Comment<'input> {
+    pub start: usize,
+    pub end: usize,
+    pub ty: CommentType,
+    /// The text without the `//`, or `/*` and `*/`; for doc comments, without the `///`,
+    /// or `/**` and `*/`
+    pub text: &'input str,
 }
 
 #[derive(Debug, PartialEq)]
@@ -526,6 +542,8 @@ impl<'input> Lexer<'input> {
             input,
             chars: input.char_indices().peekable(),
             last_tokens: [None, None],
+            comments: Vec::new(),
+            doc_comments: Vec::new(),
         }
     }
 
@@ -756,16 +774,25 @@ impl<'input> Lexer<'input> {
                             };
 
                             let mut last = start + 3;
+                            let mut end = start + 2;
 
                             while let Some((i, ch)) = self.chars.next() {
                                 if ch == '\n' || ch == '\r' {
                                     break;
                                 }
                                 last = i;
+                                end = i + ch.len_utf8();
                             }
 
                             if let Some(doc_start) = doc_comment_start {
                                 if last > doc_start {
+                                    self.doc_comments.push(Comment {
+                                        start,
+                                        end,
+                                        ty: CommentType::Line,
+                                        text: &self.input[start + 3..end],
+                                    });
+
                                     return Some(Ok((
                                         start + 3,
                                         Token::DocComment(
@@ -776,6 +803,13 @@ impl<'input> Lexer<'input> {
                                     )));
                                 }
                             }
+
+                            self.comments.push(Comment {
+                                start,
+                                end,
+                                ty: CommentType::Line,
+                                text: &self.input[start + 2..end],
+                            });
                         }
                         Some((_, '*')) => {
                             // multiline comment
@@ -788,10 +822,12 @@ impl<'input> Lexer<'input> {
 
                             let mut last = start + 3;
                             let mut seen_star = false;
+                            let end;
 
                             loop {
                                 if let Some((i, ch)) = self.chars.next() {
                                     if seen_star && ch == '/' {
+                                        end = i + 1;
                                         break;
                                     }
                                     seen_star = ch == '*';
@@ -806,6 +842,13 @@ impl<'input> Lexer<'input> {
 
                             if let Some(doc_start) = doc_comment_start {
                                 if last > doc_start {
+                                    self.doc_comments.push(Comment {
+                                        start,
+                                        end,
+                                        ty: CommentType::Block,
+                                        text: &self.input[start + 3..end - 2],
+                                    });
+
                                     return Some(Ok((
                                         start + 3,
                                         Token::DocComment(
@@ -816,6 +859,13 @@ impl<'input> Lexer<'input> {
                                     )));
                                 }
                             }
+
+                            self.comments.push(Comment {
+                                start,
+                                end,
+                                ty: CommentType::Block,
+                                text: &self.input[start + 2..end - 2],
+                            });
                         }
                         _ => {
                             return Some(Ok((start, Token::Divide, start + 1)));
@@ -1343,3 +1393,48 @@ fn lexertest() {
         )
     );
 }
+
+#[test]
+fn comments() {
+    let mut lex = Lexer::new("// foo\nbool /* bar */ x; /// doc\n//");
+
+    let tokens = lex
+        .by_ref()
+        .collect::<Vec<Result<(usize, Token, usize), LexicalError>>>();
+
+    assert_eq!(tokens.len(), 4);
+
+    assert_eq!(
+        lex.comments,
+        vec!(
+            Comment {
+                start: 0,
+                end: 6,
+                ty: CommentType::Line,
+                text: " foo"
+            },
+            Comment {
+                start: 12,
+                end: 21,
+                ty: CommentType::Block,
+                text: " bar "
+            },
+            Comment {
+                start: 33,
+                end: 35,
+                ty: CommentType::Line,
+                text: ""
+            },
+        )
+    );
+
+    assert_eq!(
+        lex.doc_comments,
+        vec!(Comment {
+            start: 25,
+            end: 32,
+            ty: CommentType::Line,
+            text: " doc"
+        })
+    );
+}
@@ -1,4 +1,5 @@
 mod doc;
+pub mod formatter;
 pub mod lexer;
 pub mod pt;
 
@@ -8,47 +9,98 @@ pub mod solidity {
 }
 
 use crate::sema::ast::Diagnostic;
-use lalrpop_util::ParseError;
+use crate::sema::codes;
+use lalrpop_util::{ErrorRecovery, ParseError};
 
-pub fn parse(src: &str, file_no: usize) -> Result<pt::SourceUnit, Vec<Diagnostic>> {
+/// Parse the source file. If the parser could recover from errors in statements, the partial
+/// parse tree is returned along with the errors, so that the rest of the file can still be used.
+/// The comments, including the doc comments, are returned too, in the order they were found.
+pub fn parse(
+    src: &str,
+    file_no: usize,
+) -> Result<(pt::SourceUnit, Vec<pt::Comment>, Vec<Diagnostic>), Vec<Diagnostic>> {
     // parse phase
-    let lex = lexer::Lexer::new(src);
+    let mut lex = lexer::Lexer::new(src);
 
-    let s = solidity::SourceUnitParser::new().parse(src, file_no, lex);
+    let mut parser_errors = Vec::new();
 
-    if let Err(e) = s {
-        let errors = vec![match e {
-            ParseError::InvalidToken { location } => Diagnostic::parser_error(
-                pt::Loc(file_no, location, location),
-                "invalid token".to_string(),
-            ),
-            ParseError::UnrecognizedToken {
-                token: (l, token, r),
-                expected,
-            } => Diagnostic::parser_error(
-                pt::Loc(file_no, l, r),
-                format!(
-                    "unrecognised token `{}', expected {}",
-                    token,
-                    expected.join(", ")
-                ),
-            ),
-            ParseError::User { error } => {
-                Diagnostic::parser_error(error.loc(file_no), error.to_string())
+    let s = solidity::SourceUnitParser::new().parse(src, file_no, &mut parser_errors, &mut lex);
+
+    let mut errors: Vec<Diagnostic> = parser_errors
+        .into_iter()
+        .map(|ErrorRecovery { error, .. }| parser_diagnostic(error, file_no))
+        .collect();
+
+    let mut comments: Vec<pt::Comment> = lex
+        .comments
+        .iter()
+        .map(|comment| {
+            let loc = pt::Loc(file_no, comment.start, comment.end);
+            let text = comment.text.to_owned();
+
+            match comment.ty {
+                lexer::CommentType::Line => pt::Comment::Line(loc, text),
+                lexer::CommentType::Block => pt::Comment::Block(loc, text),
             }
-            ParseError::ExtraToken { token } => Diagnostic::parser_error(
-                pt::Loc(file_no, token.0, token.2),
-                format!("extra token `{}' encountered", token.0),
-            ),
-            ParseError::UnrecognizedEOF { location, expected } => Diagnostic::parser_error(
-                pt::Loc(file_no, location, location),
-                format!("unexpected end of file, expecting {}", expected.join(", ")),
-            ),
-        }];
+        })
+        .chain(lex.doc_comments.iter().map(|comment| {
+            let loc = pt::Loc(file_no, comment.start, comment.end);
+            let text = comment.text.to_owned();
 
-        Err(errors)
-    } else {
-        Ok(s.unwrap())
+            match comment.ty {
+                lexer::CommentType::Line => pt::Comment::DocLine(loc, text),
+                lexer::CommentType::Block => pt::Comment::DocBlock(loc, text),
+            }
+        }))
+        .collect();
+
+    comments.sort_by_key(|comment| comment.loc().1);
+
+    match s {
+        Ok(s) => Ok((s, comments, errors)),
+        Err(e) => {
+            errors.push(parser_diagnostic(e, file_no));
+
+            Err(errors)
+        }
+    }
+}
+
+fn parser_diagnostic(
+    e: ParseError<usize, lexer::Token, lexer::LexicalError>,
+    file_no: usize,
+) -> Diagnostic {
+    match e {
+        ParseError::InvalidToken { location } => Diagnostic::parser_error(
+            pt::Loc(file_no, location, location),
+            "invalid token".to_string(),
+        )
+        .with_code(codes::INVALID_TOKEN),
+        ParseError::UnrecognizedToken {
+            token: (l, token, r),
+            expected,
+        } => Diagnostic::parser_error(
+            pt::Loc(file_no, l, r),
+            format!(
+                "unrecognised token `{}', expected {}",
+                token,
+                expected.join(", ")
+            ),
+        )
+        .with_code(codes::UNRECOGNISED_TOKEN),
+        ParseError::User { error } => {
+            Diagnostic::parser_error(error.loc(file_no), error.to_string())
+        }
+        ParseError::ExtraToken { token } => Diagnostic::parser_error(
+            pt::Loc(file_no, token.0, token.2),
+            format!("extra token `{}' encountered", token.0),
+        )
+        .with_code(codes::EXTRA_TOKEN),
+        ParseError::UnrecognizedEOF { location, expected } => Diagnostic::parser_error(
+            pt::Loc(file_no, location, location),
+            format!("unexpected end of file, expecting {}", expected.join(", ")),
+        )
+        .with_code(codes::UNEXPECTED_EOF),
     }
 }
 
@@ -78,7 +130,7 @@ mod test {
         let lex = lexer::Lexer::new(&src);
 
         let e = solidity::SourceUnitParser::new()
-            .parse(&src, 0, lex)
+            .parse(&src, 0, &mut Vec::new(), lex)
             .unwrap();
 
         let a = SourceUnit(vec![SourceUnitPart::ContractDefinition(Box::new(
@@ -166,4 +218,39 @@ mod test {
 
         assert_eq!(e, a);
     }
+    #[test]
+    fn error_recovery() {
+        let src = "contract c {
+                    function f() public {
+                        uint x = 1;
+                        x. ;
+                        return;
+                    }
+
+                    function g() public {}
+                }";
+
+        let (tree, _, errors) = super::parse(&src, 0).unwrap();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].pos, Some(Loc(0, 118, 119)));
+
+        if let SourceUnitPart::ContractDefinition(def) = &tree.0[0] {
+            assert_eq!(def.parts.len(), 2);
+
+            if let ContractPart::FunctionDefinition(f) = &def.parts[0] {
+                if let Some(Statement::Block(_, stmts)) = &f.body {
+                    assert_eq!(stmts.len(), 3);
+                    assert!(matches!(stmts[1], Statement::Error(_)));
+                    assert!(matches!(stmts[2], Statement::Return(_, None)));
+                } else {
+                    panic!("function body expected");
+                }
+            } else {
+                panic!("function expected");
+            }
+        } else {
+            panic!("contract expected");
+        }
+    }
 }
@@ -10,6 +10,27 @@ pub struct Identifier {
     pub name: String,
 }
 
+/// A comment as found in the source. The text is without the `//`, or `/*` and `*/`; for
+/// doc comments, without the `///`, or `/**` and `*/`
+#[derive(Debug, PartialEq, Clone)]
+pub enum Comment {
+    Line(Loc, String),
+    Block(Loc, String),
+    DocLine(Loc, String),
+    DocBlock(Loc, String),
+}
+
+impl Comment {
+    pub fn loc(&self) -> Loc {
+        match self {
+            Comment::Line(loc, _)
+            | Comment::Block(loc, _)
+            | Comment::DocLine(loc, _)
+            | Comment::DocBlock(loc, _) => *loc,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct DocComment {
     pub offset: usize,
@@ -23,7 +44,7 @@ pub struct SourceUnit(pub Vec<SourceUnitPart>);
 #[derive(Debug, PartialEq)]
 pub enum SourceUnitPart {
     ContractDefinition(Box<ContractDefinition>),
-    PragmaDirective(Identifier, StringLiteral),
+    PragmaDirective(Loc, Identifier, StringLiteral),
     ImportDirective(Import),
     EnumDefinition(Box<EnumDefinition>),
     StructDefinition(Box<StructDefinition>),
@@ -508,6 +529,8 @@ pub enum Statement {
     Break(Loc),
     Return(Loc, Option<Expression>),
     Emit(Loc, Expression),
+    /// `throw;` was removed in Solidity 0.5
+    Throw(Loc),
     Try(
         Loc,
         Expression,
@@ -515,6 +538,8 @@ pub enum Statement {
         Option<Box<(Identifier, Parameter, Statement)>>,
         Box<(Parameter, Statement)>,
     ),
+    /// A statement which failed to parse
+    Error(Loc),
 }
 
 impl Statement {
@@ -532,7 +557,9 @@ impl Statement {
             | Statement::Break(loc)
             | Statement::Return(loc, _)
             | Statement::Emit(loc, _)
-            | Statement::Try(loc, _, _, _, _) => *loc,
+            | Statement::Throw(loc)
+            | Statement::Try(loc, _, _, _, _)
+            | Statement::Error(loc) => *loc,
         }
     }
 }
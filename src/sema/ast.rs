@@ -1,4 +1,5 @@
 use super::symtable::Symtable;
+use super::version;
 use crate::codegen::cfg::ControlFlowGraph;
 use crate::parser::pt;
 use crate::Target;
@@ -217,6 +218,26 @@ impl Function {
         matches!(self.visibility, pt::Visibility::Private(_))
     }
 
+    /// Where a new attribute can be inserted into the function header, i.e. just after the
+    /// last visibility, mutability or override attribute. If none was specified, we do not
+    /// know where the parameter list ends.
+    pub fn attribute_insert_loc(&self) -> Option<pt::Loc> {
+        let mut locs = vec![self.visibility.loc()];
+
+        if let Some(mutability) = &self.mutability {
+            locs.push(mutability.loc());
+        }
+
+        if let Some((loc, _)) = &self.is_override {
+            locs.push(*loc);
+        }
+
+        locs.into_iter()
+            .filter(|loc| loc.0 == self.loc.0 && loc.2 > self.loc.1)
+            .max_by_key(|loc| loc.2)
+            .map(|loc| pt::Loc(loc.0, loc.2, loc.2))
+    }
+
     /// Return a unique string for this function which is a valid llvm symbol
     pub fn llvm_symbol(&self, ns: &Namespace) -> String {
         let mut sig = self.name.to_owned();
@@ -356,6 +377,60 @@ pub struct Namespace {
     pub var_constants: HashMap<pt::Loc, Expression>,
     /// Overrides for hover in the language server
     pub hover_overrides: HashMap<pt::Loc, String>,
+    /// Types and imported files referenced by name, keyed by the location of the name. This is
+    /// for use by the language server
+    pub name_references: HashMap<pt::Loc, Symbol>,
+    /// Comments like `// solang-disable-next-line S5004` which suppress warnings
+    pub suppressions: Vec<Suppression>,
+    /// The language version and ABI encoder selected by the pragmas of each file
+    pub pragmas: Vec<FilePragmas>,
+}
+
+/// Warnings which are suppressed by a comment, on the line of the comment or the line after it
+pub struct Suppression {
+    pub loc: pt::Loc,
+    pub next_line: bool,
+    /// Which warnings are suppressed; all warnings if empty
+    pub warnings: Vec<WarningSelector>,
+}
+
+/// Selects warnings by code or category, or all warnings
+#[derive(Debug, PartialEq, Clone)]
+pub enum WarningSelector {
+    All,
+    Category(WarningCategory),
+    Code(&'static str),
+}
+
+/// Solidity language version; the semantics only differ between minor versions, so the
+/// patch level is not recorded
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub struct LanguageVersion {
+    pub major: u64,
+    pub minor: u64,
+}
+
+impl fmt::Display for LanguageVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum AbiCoder {
+    V1,
+    V2,
+}
+
+/// The pragmas of a file which change how it is compiled
+#[derive(Debug, Default, Clone)]
+pub struct FilePragmas {
+    /// The highest version which solang implements and `pragma solidity` allows
+    pub version: Option<LanguageVersion>,
+    /// The first `pragma solidity`, and the versions which all of them allow
+    pub solidity: Option<(pt::Loc, version::Range)>,
+    /// Set by `pragma abicoder` or `pragma experimental ABIEncoderV2`
+    pub abicoder: Option<AbiCoder>,
 }
 
 pub struct Layout {
@@ -1189,6 +1264,7 @@ pub enum Builtin {
     AddMod,
     ExternalFunctionAddress,
     ExternalFunctionSelector,
+    PredictAddress,
 }
 
 #[derive(PartialEq, Clone, Debug)]
@@ -1353,7 +1429,7 @@ impl Statement {
     }
 }
 
-#[derive(Debug, Eq, Hash, PartialEq)]
+#[derive(Debug, Eq, Hash, PartialEq, Clone)]
 pub enum Level {
     Debug,
     Info,
@@ -1361,7 +1437,19 @@ pub enum Level {
     Error,
 }
 
-#[derive(Debug, Eq, Hash, PartialEq)]
+/// Warnings are grouped in categories, which can be disabled or made errors on the command line
+#[derive(Debug, Eq, Hash, PartialEq, Clone, Copy)]
+pub enum WarningCategory {
+    Deprecated,
+    Mutability,
+    Pragma,
+    Redundant,
+    Shadowing,
+    Target,
+    Other,
+}
+
+#[derive(Debug, Eq, Hash, PartialEq, Clone)]
 pub enum ErrorType {
     None,
     ParserError,
@@ -1371,19 +1459,30 @@ pub enum ErrorType {
     Warning,
 }
 
-#[derive(Debug, Eq, Hash, PartialEq)]
+#[derive(Debug, Eq, Hash, PartialEq, Clone)]
 pub struct Note {
     pub pos: pt::Loc,
     pub message: String,
 }
 
-#[derive(Debug, Eq, Hash, PartialEq)]
+#[derive(Debug, Eq, Hash, PartialEq, Clone)]
 pub struct Diagnostic {
     pub level: Level,
     pub ty: ErrorType,
+    /// The stable code of the diagnostic, e.g. `S5001`; see `sema::codes`
+    pub code: &'static str,
     pub pos: Option<pt::Loc>,
     pub message: String,
     pub notes: Vec<Note>,
+    pub fixes: Vec<Fix>,
+}
+
+/// A change to the source which resolves a diagnostic
+#[derive(Debug, Eq, Hash, PartialEq, Clone)]
+pub struct Fix {
+    pub message: String,
+    /// Replace the text at each location; an empty location is an insertion
+    pub edits: Vec<(pt::Loc, String)>,
 }
 
 #[derive(PartialEq, Clone, Debug)]
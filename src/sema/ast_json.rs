@@ -0,0 +1,2035 @@
+// solc compatible AST in the compact JSON format
+use super::ast::*;
+use super::builtin::{get_prototype, is_builtin_variable};
+use super::contracts::visit_bases;
+use crate::abi::storage_layout::{list_type_id, type_id};
+use crate::parser::pt;
+use num_bigint::BigInt;
+use num_traits::Zero;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// The node ids of the AST. These can be shared between the ASTs of several files, so that a
+/// declaration has the same id in the file which declares it and in the files which use it,
+/// even when the files are resolved separately.
+#[derive(Default)]
+pub struct NodeIds {
+    next: usize,
+    /// Declarations by file and location
+    declarations: HashMap<(PathBuf, usize, usize), usize>,
+}
+
+impl NodeIds {
+    fn next(&mut self) -> usize {
+        let id = self.next;
+        self.next += 1;
+        id
+    }
+
+    fn declaration(&mut self, loc: &pt::Loc, ns: &Namespace) -> usize {
+        let key = (ns.files[loc.0].clone(), loc.1, loc.2);
+
+        if let Some(id) = self.declarations.get(&key) {
+            return *id;
+        }
+
+        let id = self.next();
+
+        self.declarations.insert(key, id);
+
+        id
+    }
+}
+
+/// Generate the AST of a file as a solc `SourceUnit` node. The `src` of every node refers to
+/// the file as `source_id`.
+pub fn ast_json(file_no: usize, source_id: usize, ns: &Namespace, ids: &mut NodeIds) -> Value {
+    let mut builder = Builder { ns, ids, source_id };
+
+    builder.source_unit(file_no)
+}
+
+struct Builder<'a> {
+    ns: &'a Namespace,
+    ids: &'a mut NodeIds,
+    source_id: usize,
+}
+
+impl<'a> Builder<'a> {
+    fn src(&self, loc: &pt::Loc) -> String {
+        format!("{}:{}:{}", loc.1, loc.2 - loc.1, self.source_id)
+    }
+
+    fn declaration(&mut self, loc: &pt::Loc) -> usize {
+        self.ids.declaration(loc, self.ns)
+    }
+
+    /// Add the id, node type and source location to the fields of a node
+    fn node(&mut self, node_type: &str, loc: &pt::Loc, fields: Value) -> Value {
+        let id = self.ids.next();
+
+        self.node_with_id(id, node_type, loc, fields)
+    }
+
+    fn node_with_id(&self, id: usize, node_type: &str, loc: &pt::Loc, mut fields: Value) -> Value {
+        fields["id"] = json!(id);
+        fields["nodeType"] = json!(node_type);
+        fields["src"] = json!(self.src(loc));
+
+        fields
+    }
+
+    fn source_unit(&mut self, file_no: usize) -> Value {
+        let ns = self.ns;
+        let id = self.ids.next();
+        let mut exported = serde_json::Map::new();
+        // the nodes with their start offset, so they can be sorted in source order
+        let mut nodes = Vec::new();
+
+        for (contract_no, contract) in ns.contracts.iter().enumerate() {
+            if contract.loc.0 == file_no {
+                let node = self.contract(contract_no, id);
+
+                exported.insert(contract.name.to_owned(), json!([node["id"]]));
+                nodes.push((contract.loc.1, node));
+            }
+        }
+
+        for (struct_no, decl) in ns.structs.iter().enumerate() {
+            if decl.loc.0 == file_no && decl.contract.is_none() {
+                let node = self.struct_definition(struct_no, id);
+
+                exported.insert(decl.name.to_owned(), json!([node["id"]]));
+                nodes.push((decl.loc.1, node));
+            }
+        }
+
+        for (enum_no, decl) in ns.enums.iter().enumerate() {
+            if decl.loc.0 == file_no && decl.contract.is_none() {
+                let node = self.enum_definition(enum_no);
+
+                exported.insert(decl.name.to_owned(), json!([node["id"]]));
+                nodes.push((decl.loc.1, node));
+            }
+        }
+
+        for (event_no, decl) in ns.events.iter().enumerate() {
+            if decl.loc.0 == file_no && decl.contract.is_none() {
+                nodes.push((decl.loc.1, self.event_definition(event_no)));
+            }
+        }
+
+        for (function_no, func) in ns.functions.iter().enumerate() {
+            if func.loc.0 == file_no && func.contract_no.is_none() {
+                let node = self.function(function_no, id);
+
+                exported.insert(func.name.to_owned(), json!([node["id"]]));
+                nodes.push((func.loc.1, node));
+            }
+        }
+
+        for var in &ns.constants {
+            if var.loc.0 == file_no {
+                let node = self.variable(var, false, id);
+
+                exported.insert(var.name.to_owned(), json!([node["id"]]));
+                nodes.push((var.loc.1, node));
+            }
+        }
+
+        nodes.sort_by_key(|(start, _)| *start);
+
+        let end = nodes
+            .iter()
+            .filter_map(|(_, node)| node["src"].as_str())
+            .map(|src| {
+                let mut parts = src.split(':').map(|n| n.parse::<usize>().unwrap_or(0));
+
+                parts.next().unwrap_or(0) + parts.next().unwrap_or(0)
+            })
+            .max()
+            .unwrap_or(0);
+
+        self.node_with_id(
+            id,
+            "SourceUnit",
+            &pt::Loc(file_no, 0, end),
+            json!({
+                "absolutePath": ns.files[file_no].display().to_string(),
+                "exportedSymbols": exported,
+                "nodes": nodes.into_iter().map(|(_, node)| node).collect::<Vec<Value>>(),
+            }),
+        )
+    }
+
+    fn contract(&mut self, contract_no: usize, scope: usize) -> Value {
+        let ns = self.ns;
+        let contract = &ns.contracts[contract_no];
+        let id = self.declaration(&contract.loc);
+
+        let (kind, is_abstract) = match contract.ty {
+            pt::ContractTy::Abstract(_) => ("contract", true),
+            pt::ContractTy::Contract(_) => ("contract", false),
+            pt::ContractTy::Interface(_) => ("interface", false),
+            pt::ContractTy::Library(_) => ("library", false),
+        };
+
+        let base_contracts: Vec<Value> = contract
+            .bases
+            .iter()
+            .map(|base| {
+                let base_name = self.contract_name(base.contract_no, &base.loc);
+                let arguments = base.constructor.as_ref().map(|(_, args)| {
+                    args.iter()
+                        .map(|arg| self.expression(arg, None))
+                        .collect::<Vec<Value>>()
+                });
+
+                self.node(
+                    "InheritanceSpecifier",
+                    &base.loc,
+                    json!({
+                        "baseName": base_name,
+                        "arguments": arguments,
+                    }),
+                )
+            })
+            .collect();
+
+        // solc lists the contract itself first, and then its bases from the most derived
+        let linearized: Vec<usize> = visit_bases(contract_no, ns)
+            .into_iter()
+            .rev()
+            .map(|base_no| self.declaration(&ns.contracts[base_no].loc))
+            .collect();
+
+        let dependencies: Vec<usize> = contract
+            .creates
+            .iter()
+            .map(|created_no| self.declaration(&ns.contracts[*created_no].loc))
+            .collect();
+
+        let mut nodes = Vec::new();
+
+        for (struct_no, decl) in ns.structs.iter().enumerate() {
+            if decl.loc.0 == contract.loc.0 && decl.contract.as_ref() == Some(&contract.name) {
+                nodes.push((decl.loc.1, self.struct_definition(struct_no, id)));
+            }
+        }
+
+        for (enum_no, decl) in ns.enums.iter().enumerate() {
+            if decl.loc.0 == contract.loc.0 && decl.contract.as_ref() == Some(&contract.name) {
+                nodes.push((decl.loc.1, self.enum_definition(enum_no)));
+            }
+        }
+
+        for (event_no, decl) in ns.events.iter().enumerate() {
+            if decl.loc.0 == contract.loc.0 && decl.contract.as_ref() == Some(&contract.name) {
+                nodes.push((decl.loc.1, self.event_definition(event_no)));
+            }
+        }
+
+        for var in &contract.variables {
+            nodes.push((var.loc.1, self.variable(var, true, id)));
+        }
+
+        for function_no in &contract.functions {
+            let func = &ns.functions[*function_no];
+
+            // accessor functions of public variables are not in the source
+            if !func.is_accessor {
+                nodes.push((func.loc.1, self.function(*function_no, id)));
+            }
+        }
+
+        nodes.sort_by_key(|(start, _)| *start);
+
+        let fully_implemented = !is_abstract
+            && contract
+                .all_functions
+                .keys()
+                .all(|function_no| ns.functions[*function_no].has_body);
+
+        self.node_with_id(
+            id,
+            "ContractDefinition",
+            &contract.loc,
+            json!({
+                "name": contract.name,
+                "contractKind": kind,
+                "abstract": is_abstract,
+                "fullyImplemented": fully_implemented,
+                "baseContracts": base_contracts,
+                "contractDependencies": dependencies,
+                "linearizedBaseContracts": linearized,
+                "nodes": nodes.into_iter().map(|(_, node)| node).collect::<Vec<Value>>(),
+                "scope": scope,
+            }),
+        )
+    }
+
+    fn struct_definition(&mut self, struct_no: usize, scope: usize) -> Value {
+        let ns = self.ns;
+        let decl = &ns.structs[struct_no];
+        let id = self.declaration(&decl.loc);
+
+        let members: Vec<Value> = decl
+            .fields
+            .iter()
+            .map(|field| {
+                let field_id = self.parameter_id(field);
+
+                self.parameter(field_id, field, id)
+            })
+            .collect();
+
+        self.node_with_id(
+            id,
+            "StructDefinition",
+            &decl.loc,
+            json!({
+                "name": decl.name,
+                "canonicalName": decl.to_string(),
+                "members": members,
+                "visibility": "public",
+                "scope": scope,
+            }),
+        )
+    }
+
+    fn enum_definition(&mut self, enum_no: usize) -> Value {
+        let ns = self.ns;
+        let decl = &ns.enums[enum_no];
+        let id = self.declaration(&decl.loc);
+
+        let mut values: Vec<(&String, &(pt::Loc, usize))> = decl.values.iter().collect();
+
+        values.sort_by_key(|(_, (_, pos))| *pos);
+
+        let members: Vec<Value> = values
+            .into_iter()
+            .map(|(name, (loc, _))| {
+                let value_id = self.declaration(loc);
+
+                self.node_with_id(value_id, "EnumValue", loc, json!({ "name": name }))
+            })
+            .collect();
+
+        self.node_with_id(
+            id,
+            "EnumDefinition",
+            &decl.loc,
+            json!({
+                "name": decl.name,
+                "canonicalName": decl.to_string(),
+                "members": members,
+            }),
+        )
+    }
+
+    fn event_definition(&mut self, event_no: usize) -> Value {
+        let ns = self.ns;
+        let decl = &ns.events[event_no];
+        let id = self.declaration(&decl.loc);
+
+        let parameters = self.parameter_list(&decl.fields, &[], &decl.loc, id);
+
+        self.node_with_id(
+            id,
+            "EventDefinition",
+            &decl.loc,
+            json!({
+                "name": decl.name,
+                "anonymous": decl.anonymous,
+                "parameters": parameters,
+            }),
+        )
+    }
+
+    /// A state variable, or a constant outside a contract
+    fn variable(&mut self, var: &Variable, state_variable: bool, scope: usize) -> Value {
+        let id = self.declaration(&var.loc);
+        let type_name = self.type_name(&var.ty, &var.loc);
+        let type_descriptions = self.type_descriptions(&var.ty);
+        let value = var
+            .initializer
+            .as_ref()
+            .map(|init| self.expression(init, None));
+
+        self.node_with_id(
+            id,
+            "VariableDeclaration",
+            &var.loc,
+            json!({
+                "name": var.name,
+                "constant": var.constant,
+                "mutability": if var.constant { "constant" } else { "mutable" },
+                "stateVariable": state_variable,
+                "storageLocation": "default",
+                "visibility": var.visibility.to_string(),
+                "scope": scope,
+                "typeName": type_name,
+                "typeDescriptions": type_descriptions,
+                "value": value,
+            }),
+        )
+    }
+
+    fn function(&mut self, function_no: usize, scope: usize) -> Value {
+        let ns = self.ns;
+        let func = &ns.functions[function_no];
+        let id = self.declaration(&func.loc);
+
+        let param_locs: Vec<Option<pt::Loc>> =
+            func.params.iter().map(|param| param.name_loc).collect();
+        // unnamed return values are declared at the location of their type
+        let return_locs: Vec<Option<pt::Loc>> = func
+            .symtable
+            .returns
+            .iter()
+            .map(|pos| Some(func.symtable.vars[pos].id.loc))
+            .collect();
+
+        let parameters = self.parameter_list(&func.params, &param_locs, &func.loc, id);
+        let return_parameters = self.parameter_list(&func.returns, &return_locs, &func.loc, id);
+
+        let body = if func.has_body {
+            Some(self.block(
+                &func.body,
+                func,
+                &pt::Loc(func.loc.0, func.loc.2, func.loc.2),
+            ))
+        } else {
+            None
+        };
+
+        if func.ty == pt::FunctionTy::Modifier {
+            return self.node_with_id(
+                id,
+                "ModifierDefinition",
+                &func.loc,
+                json!({
+                    "name": func.name,
+                    "visibility": "internal",
+                    "virtual": func.is_virtual,
+                    "parameters": parameters,
+                    "body": body,
+                }),
+            );
+        }
+
+        let mut modifiers = Vec::new();
+
+        for modifier in &func.modifiers {
+            if let Expression::InternalFunctionCall {
+                loc,
+                function,
+                args,
+                ..
+            } = modifier
+            {
+                if let Expression::InternalFunction {
+                    loc: name_loc,
+                    function_no,
+                    ..
+                } = function.as_ref()
+                {
+                    let referenced = self.declaration(&ns.functions[*function_no].loc);
+                    let modifier_name = self.node(
+                        "Identifier",
+                        name_loc,
+                        json!({
+                            "name": ns.functions[*function_no].name,
+                            "referencedDeclaration": referenced,
+                        }),
+                    );
+
+                    let arguments: Vec<Value> = args
+                        .iter()
+                        .map(|arg| self.expression(arg, Some(func)))
+                        .collect();
+
+                    modifiers.push((
+                        loc.1,
+                        self.node(
+                            "ModifierInvocation",
+                            loc,
+                            json!({
+                                "kind": "modifierInvocation",
+                                "modifierName": modifier_name,
+                                "arguments": arguments,
+                            }),
+                        ),
+                    ));
+                }
+            }
+        }
+
+        // constructor arguments for base contracts
+        for (base_no, (loc, _, args)) in &func.bases {
+            let modifier_name = self.contract_name(*base_no, loc);
+
+            let arguments: Vec<Value> = args
+                .iter()
+                .map(|arg| self.expression(arg, Some(func)))
+                .collect();
+
+            modifiers.push((
+                loc.1,
+                self.node(
+                    "ModifierInvocation",
+                    loc,
+                    json!({
+                        "kind": "baseConstructorSpecifier",
+                        "modifierName": modifier_name,
+                        "arguments": arguments,
+                    }),
+                ),
+            ));
+        }
+
+        modifiers.sort_by_key(|(start, _)| *start);
+
+        let overrides = func.is_override.as_ref().map(|(loc, contracts)| {
+            let overrides: Vec<Value> = contracts
+                .iter()
+                .map(|contract_no| self.contract_name(*contract_no, loc))
+                .collect();
+
+            self.node("OverrideSpecifier", loc, json!({ "overrides": overrides }))
+        });
+
+        let mut node = json!({
+            "name": func.name,
+            "kind": func.ty.to_string(),
+            "stateMutability": func.print_mutability(),
+            "visibility": func.visibility.to_string(),
+            "virtual": func.is_virtual,
+            "implemented": func.has_body,
+            "modifiers": modifiers.into_iter().map(|(_, node)| node).collect::<Vec<Value>>(),
+            "overrides": overrides,
+            "parameters": parameters,
+            "returnParameters": return_parameters,
+            "body": body,
+            "scope": scope,
+        });
+
+        if func.ty == pt::FunctionTy::Function && func.is_public() {
+            node["functionSelector"] = json!(format!("{:08x}", func.selector()));
+        }
+
+        self.node_with_id(id, "FunctionDefinition", &func.loc, node)
+    }
+
+    /// A list of parameters, declared at the given locations. Parameters without a location
+    /// cannot be referenced, so they get a new id.
+    fn parameter_list(
+        &mut self,
+        params: &[Parameter],
+        decl_locs: &[Option<pt::Loc>],
+        loc: &pt::Loc,
+        scope: usize,
+    ) -> Value {
+        let parameters: Vec<Value> = params
+            .iter()
+            .enumerate()
+            .map(|(i, param)| {
+                let id = match decl_locs.get(i) {
+                    Some(Some(loc)) => self.declaration(loc),
+                    _ => self.parameter_id(param),
+                };
+
+                self.parameter(id, param, scope)
+            })
+            .collect();
+
+        let loc = span(params.iter().map(|param| param.loc), loc);
+
+        self.node("ParameterList", &loc, json!({ "parameters": parameters }))
+    }
+
+    fn parameter_id(&mut self, param: &Parameter) -> usize {
+        match &param.name_loc {
+            Some(loc) => self.declaration(loc),
+            None => self.ids.next(),
+        }
+    }
+
+    fn parameter(&mut self, id: usize, param: &Parameter, scope: usize) -> Value {
+        let type_name = self.type_name(&param.ty, &param.ty_loc);
+        let type_descriptions = self.type_descriptions(&param.ty);
+
+        let mut node = json!({
+            "name": param.name,
+            "constant": false,
+            "mutability": "mutable",
+            "stateVariable": false,
+            "storageLocation": storage_location(&param.ty),
+            "visibility": "internal",
+            "scope": scope,
+            "typeName": type_name,
+            "typeDescriptions": type_descriptions,
+        });
+
+        // only event fields can be indexed
+        if param.indexed {
+            node["indexed"] = json!(true);
+        }
+
+        self.node_with_id(id, "VariableDeclaration", &param.loc, node)
+    }
+
+    /// A local variable, with the id of its declaration in the symbol table
+    fn local_variable(&mut self, pos: usize, param: &Parameter, func: &Function) -> Value {
+        let id = self.declaration(&func.symtable.vars[&pos].id.loc);
+        let scope = self.declaration(&func.loc);
+
+        self.parameter(id, param, scope)
+    }
+
+    /// The name of a contract, as used for base contracts and overrides
+    fn contract_name(&mut self, contract_no: usize, loc: &pt::Loc) -> Value {
+        let ns = self.ns;
+        let contract = &ns.contracts[contract_no];
+        let referenced = self.declaration(&contract.loc);
+
+        self.node(
+            "UserDefinedTypeName",
+            loc,
+            json!({
+                "name": contract.name,
+                "referencedDeclaration": referenced,
+                "typeDescriptions": self.type_descriptions(&Type::Contract(contract_no)),
+            }),
+        )
+    }
+
+    fn type_string(&self, ty: &Type) -> String {
+        match ty {
+            Type::StorageRef(ty) => format!("{} storage ref", ty.to_string(self.ns)),
+            Type::Ref(ty) => format!("{} memory", ty.to_string(self.ns)),
+            _ => ty.to_string(self.ns),
+        }
+    }
+
+    fn type_descriptions(&self, ty: &Type) -> Value {
+        let type_string = self.type_string(ty);
+
+        json!({
+            "typeIdentifier": escape_type_id(&type_id(ty, self.ns)),
+            "typeString": type_string,
+        })
+    }
+
+    /// The type of an expression with any number of values, like a function call
+    fn tuple_descriptions(&self, tys: &[Type]) -> Value {
+        if tys.len() == 1 {
+            return self.type_descriptions(&tys[0]);
+        }
+
+        let type_string = format!(
+            "tuple({})",
+            tys.iter()
+                .map(|ty| self.type_string(ty))
+                .collect::<Vec<String>>()
+                .join(",")
+        );
+
+        json!({
+            "typeIdentifier": escape_type_id(&format!("t_tuple({})", list_type_id(tys, self.ns))),
+            "typeString": type_string,
+        })
+    }
+
+    /// The type name node for a type written at the location
+    fn type_name(&mut self, ty: &Type, loc: &pt::Loc) -> Value {
+        let ns = self.ns;
+        let type_descriptions = self.type_descriptions(ty);
+
+        match ty {
+            Type::Ref(ty) | Type::StorageRef(ty) => self.type_name(ty, loc),
+            Type::Mapping(key, value) => {
+                let key_type = self.type_name(key, loc);
+                let value_type = self.type_name(value, loc);
+
+                self.node(
+                    "Mapping",
+                    loc,
+                    json!({
+                        "keyType": key_type,
+                        "valueType": value_type,
+                        "typeDescriptions": type_descriptions,
+                    }),
+                )
+            }
+            Type::Array(elem, dims) => {
+                // the last dimension is the outermost
+                let base_type = if dims.len() > 1 {
+                    self.type_name(
+                        &Type::Array(elem.clone(), dims[..dims.len() - 1].to_vec()),
+                        loc,
+                    )
+                } else {
+                    self.type_name(elem, loc)
+                };
+
+                let length = dims.last().unwrap().as_ref().map(|length| {
+                    self.node(
+                        "Literal",
+                        loc,
+                        json!({
+                            "kind": "number",
+                            "value": length.to_string(),
+                        }),
+                    )
+                });
+
+                self.node(
+                    "ArrayTypeName",
+                    loc,
+                    json!({
+                        "baseType": base_type,
+                        "length": length,
+                        "typeDescriptions": type_descriptions,
+                    }),
+                )
+            }
+            Type::Struct(n) => {
+                let referenced = self.declaration(&ns.structs[*n].loc);
+
+                self.node(
+                    "UserDefinedTypeName",
+                    loc,
+                    json!({
+                        "name": ns.structs[*n].to_string(),
+                        "referencedDeclaration": referenced,
+                        "typeDescriptions": type_descriptions,
+                    }),
+                )
+            }
+            Type::Enum(n) => {
+                let referenced = self.declaration(&ns.enums[*n].loc);
+
+                self.node(
+                    "UserDefinedTypeName",
+                    loc,
+                    json!({
+                        "name": ns.enums[*n].to_string(),
+                        "referencedDeclaration": referenced,
+                        "typeDescriptions": type_descriptions,
+                    }),
+                )
+            }
+            Type::Contract(n) => self.contract_name(*n, loc),
+            Type::InternalFunction {
+                mutability,
+                params,
+                returns,
+            }
+            | Type::ExternalFunction {
+                mutability,
+                params,
+                returns,
+            } => {
+                let parameter_types = self.type_list(params, loc);
+                let return_parameter_types = self.type_list(returns, loc);
+
+                self.node(
+                    "FunctionTypeName",
+                    loc,
+                    json!({
+                        "visibility": if matches!(ty, Type::InternalFunction { .. }) {
+                            "internal"
+                        } else {
+                            "external"
+                        },
+                        "stateMutability": match mutability {
+                            Some(mutability) => mutability.to_string(),
+                            None => String::from("nonpayable"),
+                        },
+                        "parameterTypes": parameter_types,
+                        "returnParameterTypes": return_parameter_types,
+                        "typeDescriptions": type_descriptions,
+                    }),
+                )
+            }
+            Type::Address(true) => self.node(
+                "ElementaryTypeName",
+                loc,
+                json!({
+                    "name": "address",
+                    "stateMutability": "payable",
+                    "typeDescriptions": type_descriptions,
+                }),
+            ),
+            _ => self.node(
+                "ElementaryTypeName",
+                loc,
+                json!({
+                    "name": ty.to_string(ns),
+                    "typeDescriptions": type_descriptions,
+                }),
+            ),
+        }
+    }
+
+    /// The parameter list of a function type, which only has types
+    fn type_list(&mut self, tys: &[Type], loc: &pt::Loc) -> Value {
+        let parameters: Vec<Value> = tys
+            .iter()
+            .map(|ty| {
+                let type_name = self.type_name(ty, loc);
+                let type_descriptions = self.type_descriptions(ty);
+
+                self.node(
+                    "VariableDeclaration",
+                    loc,
+                    json!({
+                        "name": "",
+                        "constant": false,
+                        "mutability": "mutable",
+                        "stateVariable": false,
+                        "storageLocation": storage_location(ty),
+                        "visibility": "internal",
+                        "typeName": type_name,
+                        "typeDescriptions": type_descriptions,
+                    }),
+                )
+            })
+            .collect();
+
+        self.node("ParameterList", loc, json!({ "parameters": parameters }))
+    }
+
+    /// A list of statements as a block. The statements do not record the location of the
+    /// braces, so the block spans from the first to the last statement.
+    fn block(&mut self, stmts: &[Statement], func: &Function, loc: &pt::Loc) -> Value {
+        let statements: Vec<Value> = stmts
+            .iter()
+            .map(|stmt| self.statement(stmt, func))
+            .collect();
+
+        let loc = span(stmts.iter().map(statement_loc), loc);
+
+        self.node("Block", &loc, json!({ "statements": statements }))
+    }
+
+    fn statement(&mut self, stmt: &Statement, func: &Function) -> Value {
+        let ns = self.ns;
+
+        match stmt {
+            Statement::VariableDecl(loc, pos, param, init) => {
+                let decl = self.local_variable(*pos, param, func);
+                let initial_value = init.as_ref().map(|init| self.expression(init, Some(func)));
+
+                self.node(
+                    "VariableDeclarationStatement",
+                    loc,
+                    json!({
+                        "assignments": [decl["id"]],
+                        "declarations": [decl],
+                        "initialValue": initial_value,
+                    }),
+                )
+            }
+            Statement::If(loc, _, cond, then_stmt, else_stmt) => {
+                let condition = self.expression(cond, Some(func));
+                let true_body = self.block(then_stmt, func, &cond.loc());
+                let false_body = if else_stmt.is_empty() {
+                    None
+                } else {
+                    Some(self.block(else_stmt, func, &pt::Loc(loc.0, loc.2, loc.2)))
+                };
+
+                self.node(
+                    "IfStatement",
+                    loc,
+                    json!({
+                        "condition": condition,
+                        "trueBody": true_body,
+                        "falseBody": false_body,
+                    }),
+                )
+            }
+            Statement::While(loc, _, cond, body) => {
+                let condition = self.expression(cond, Some(func));
+                let body = self.block(body, func, &cond.loc());
+
+                self.node(
+                    "WhileStatement",
+                    loc,
+                    json!({
+                        "condition": condition,
+                        "body": body,
+                    }),
+                )
+            }
+            Statement::DoWhile(loc, _, body, cond) => {
+                let body = self.block(body, func, loc);
+                let condition = self.expression(cond, Some(func));
+
+                self.node(
+                    "DoWhileStatement",
+                    loc,
+                    json!({
+                        "condition": condition,
+                        "body": body,
+                    }),
+                )
+            }
+            Statement::For {
+                loc,
+                init,
+                cond,
+                next,
+                body,
+                ..
+            } => {
+                let initialization = init.first().map(|stmt| self.statement(stmt, func));
+                let condition = cond.as_ref().map(|cond| self.expression(cond, Some(func)));
+                let loop_expression = next.first().map(|stmt| self.statement(stmt, func));
+                let body = self.block(body, func, &pt::Loc(loc.0, loc.2, loc.2));
+
+                self.node(
+                    "ForStatement",
+                    loc,
+                    json!({
+                        "initializationExpression": initialization,
+                        "condition": condition,
+                        "loopExpression": loop_expression,
+                        "body": body,
+                    }),
+                )
+            }
+            Statement::Expression(loc, _, expr) => {
+                let expression = self.expression(expr, Some(func));
+
+                self.node(
+                    "ExpressionStatement",
+                    loc,
+                    json!({ "expression": expression }),
+                )
+            }
+            Statement::Delete(loc, _, expr) => {
+                let sub_expression = self.expression(expr, Some(func));
+                let expression = self.node(
+                    "UnaryOperation",
+                    loc,
+                    json!({
+                        "operator": "delete",
+                        "prefix": true,
+                        "subExpression": sub_expression,
+                        "typeDescriptions": self.tuple_descriptions(&[]),
+                    }),
+                );
+
+                self.node(
+                    "ExpressionStatement",
+                    loc,
+                    json!({ "expression": expression }),
+                )
+            }
+            Statement::Destructure(loc, fields, expr) => {
+                let initial_value = self.expression(expr, Some(func));
+
+                if fields
+                    .iter()
+                    .any(|field| matches!(field, DestructureField::VariableDecl(..)))
+                {
+                    let declarations: Vec<Value> = fields
+                        .iter()
+                        .map(|field| match field {
+                            DestructureField::VariableDecl(pos, param) => {
+                                self.local_variable(*pos, param, func)
+                            }
+                            _ => Value::Null,
+                        })
+                        .collect();
+
+                    let assignments: Vec<Value> =
+                        declarations.iter().map(|decl| decl["id"].clone()).collect();
+
+                    self.node(
+                        "VariableDeclarationStatement",
+                        loc,
+                        json!({
+                            "assignments": assignments,
+                            "declarations": declarations,
+                            "initialValue": initial_value,
+                        }),
+                    )
+                } else {
+                    let components: Vec<Value> = fields
+                        .iter()
+                        .map(|field| match field {
+                            DestructureField::Expression(expr) => self.expression(expr, Some(func)),
+                            _ => Value::Null,
+                        })
+                        .collect();
+
+                    let left = self.node(
+                        "TupleExpression",
+                        &span(fields.iter().filter_map(|field| field.loc()), loc),
+                        json!({
+                            "components": components,
+                            "isInlineArray": false,
+                            "typeDescriptions": self.tuple_descriptions(&[]),
+                        }),
+                    );
+
+                    let assignment = self.node(
+                        "Assignment",
+                        loc,
+                        json!({
+                            "operator": "=",
+                            "leftHandSide": left,
+                            "rightHandSide": initial_value,
+                            "typeDescriptions": self.tuple_descriptions(&[]),
+                        }),
+                    );
+
+                    self.node(
+                        "ExpressionStatement",
+                        loc,
+                        json!({ "expression": assignment }),
+                    )
+                }
+            }
+            Statement::Continue(loc) => self.node("Continue", loc, json!({})),
+            Statement::Break(loc) => self.node("Break", loc, json!({})),
+            Statement::Return(loc, values) => {
+                let expression = match values.len() {
+                    0 => None,
+                    1 => Some(self.expression(&values[0], Some(func))),
+                    _ => Some(self.tuple(loc, values, func)),
+                };
+
+                self.node("Return", loc, json!({ "expression": expression }))
+            }
+            Statement::Emit {
+                loc,
+                event_no,
+                event_loc,
+                args,
+            } => {
+                let event = &ns.events[*event_no];
+                let referenced = self.declaration(&event.loc);
+                let expression = self.node(
+                    "Identifier",
+                    event_loc,
+                    json!({
+                        "name": event.name,
+                        "referencedDeclaration": referenced,
+                        "typeDescriptions": self.tuple_descriptions(&[]),
+                    }),
+                );
+                let arguments: Vec<Value> = args
+                    .iter()
+                    .map(|arg| self.expression(arg, Some(func)))
+                    .collect();
+                let event_call = self.node(
+                    "FunctionCall",
+                    loc,
+                    json!({
+                        "kind": "functionCall",
+                        "expression": expression,
+                        "arguments": arguments,
+                        "typeDescriptions": self.tuple_descriptions(&[]),
+                    }),
+                );
+
+                self.node("EmitStatement", loc, json!({ "eventCall": event_call }))
+            }
+            Statement::TryCatch {
+                loc,
+                expr,
+                returns,
+                ok_stmt,
+                error,
+                catch_param,
+                catch_param_pos,
+                catch_stmt,
+                ..
+            } => {
+                let external_call = self.expression(expr, Some(func));
+                let scope = self.declaration(&func.loc);
+                let end = pt::Loc(loc.0, loc.2, loc.2);
+
+                let mut clauses = Vec::new();
+
+                let parameters: Vec<Value> = returns
+                    .iter()
+                    .map(|(pos, param)| match pos {
+                        Some(pos) => self.local_variable(*pos, param, func),
+                        None => {
+                            let id = self.ids.next();
+
+                            self.parameter(id, param, scope)
+                        }
+                    })
+                    .collect();
+                let parameters = if parameters.is_empty() {
+                    None
+                } else {
+                    Some(self.node(
+                        "ParameterList",
+                        &span(returns.iter().map(|(_, param)| param.loc), &end),
+                        json!({ "parameters": parameters }),
+                    ))
+                };
+                let block = self.block(ok_stmt, func, &end);
+
+                clauses.push(self.node(
+                    "TryCatchClause",
+                    &block_loc(&block, loc),
+                    json!({
+                        "errorName": "",
+                        "parameters": parameters,
+                        "block": block,
+                    }),
+                ));
+
+                if let Some((pos, param, stmts)) = error {
+                    let clause = self.catch_clause("Error", *pos, param, stmts, func, &end);
+
+                    clauses.push(clause);
+                }
+
+                let clause =
+                    self.catch_clause("", *catch_param_pos, catch_param, catch_stmt, func, &end);
+
+                clauses.push(clause);
+
+                self.node(
+                    "TryStatement",
+                    loc,
+                    json!({
+                        "externalCall": external_call,
+                        "clauses": clauses,
+                    }),
+                )
+            }
+            Statement::Underscore(loc) => self.node("PlaceholderStatement", loc, json!({})),
+        }
+    }
+
+    fn catch_clause(
+        &mut self,
+        error_name: &str,
+        pos: Option<usize>,
+        param: &Parameter,
+        stmts: &[Statement],
+        func: &Function,
+        loc: &pt::Loc,
+    ) -> Value {
+        let parameters = pos.map(|pos| {
+            let decl = self.local_variable(pos, param, func);
+
+            self.node("ParameterList", &param.loc, json!({ "parameters": [decl] }))
+        });
+        let block = self.block(stmts, func, loc);
+
+        self.node(
+            "TryCatchClause",
+            &block_loc(&block, loc),
+            json!({
+                "errorName": error_name,
+                "parameters": parameters,
+                "block": block,
+            }),
+        )
+    }
+
+    fn tuple(&mut self, loc: &pt::Loc, exprs: &[Expression], func: &Function) -> Value {
+        let components: Vec<Value> = exprs
+            .iter()
+            .map(|expr| self.expression(expr, Some(func)))
+            .collect();
+        let tys: Vec<Type> = exprs.iter().map(|expr| expr.ty()).collect();
+
+        self.node(
+            "TupleExpression",
+            loc,
+            json!({
+                "components": components,
+                "isInlineArray": false,
+                "typeDescriptions": self.tuple_descriptions(&tys),
+            }),
+        )
+    }
+
+    fn expressions(&mut self, exprs: &[Expression], func: Option<&Function>) -> Vec<Value> {
+        exprs
+            .iter()
+            .map(|expr| self.expression(expr, func))
+            .collect()
+    }
+
+    fn expression(&mut self, expr: &Expression, func: Option<&Function>) -> Value {
+        let ns = self.ns;
+
+        match expr {
+            Expression::BoolLiteral(loc, value) => {
+                let value = if *value { "true" } else { "false" };
+
+                self.literal(loc, "bool", Some(value), value.as_bytes(), &Type::Bool)
+            }
+            Expression::BytesLiteral(loc, ty @ Type::String, bs) => {
+                let value = String::from_utf8_lossy(bs);
+
+                self.literal(loc, "string", Some(&*value), bs, ty)
+            }
+            Expression::BytesLiteral(loc, ty, bs) => self.literal(loc, "hexString", None, bs, ty),
+            Expression::AllocDynamicArray(loc, ty, _, Some(bs)) => {
+                let value = String::from_utf8_lossy(bs);
+
+                self.literal(loc, "string", Some(&*value), bs, ty)
+            }
+            Expression::NumberLiteral(loc, Type::Enum(enum_no), value) => {
+                let decl = &ns.enums[*enum_no];
+                let ty = &Type::Enum(*enum_no);
+
+                match decl
+                    .values
+                    .iter()
+                    .find(|(_, (_, pos))| BigInt::from(*pos) == *value)
+                {
+                    Some((name, (value_loc, _))) => {
+                        let referenced = self.declaration(value_loc);
+                        let enum_referenced = self.declaration(&decl.loc);
+                        let enum_expression =
+                            self.identifier(loc, &decl.name, Some(enum_referenced), ty);
+
+                        self.member_access(loc, enum_expression, name, Some(referenced), ty)
+                    }
+                    None => self.literal(loc, "number", Some(value.to_string().as_str()), &[], ty),
+                }
+            }
+            Expression::NumberLiteral(loc, ty @ Type::Address(_), value) => {
+                let value = format!("{:#01$x}", value, ns.address_length * 2 + 2);
+
+                self.literal(loc, "number", Some(&*value), &[], ty)
+            }
+            Expression::NumberLiteral(loc, ty, value) => {
+                self.literal(loc, "number", Some(value.to_string().as_str()), &[], ty)
+            }
+            Expression::CodeLiteral(loc, contract_no, runtime) => {
+                let contract = self.contract_identifier(loc, *contract_no);
+                let type_function = self.identifier(loc, "type", None, &Type::Void);
+                let type_call = self.function_call(
+                    loc,
+                    "functionCall",
+                    type_function,
+                    vec![contract],
+                    &Type::Contract(*contract_no),
+                );
+                let member = if *runtime {
+                    "runtimeCode"
+                } else {
+                    "creationCode"
+                };
+
+                self.member_access(loc, type_call, member, None, &Type::DynamicBytes)
+            }
+            Expression::StructLiteral(loc, ty, fields) => {
+                let arguments = self.expressions(fields, func);
+                let name = match ty {
+                    Type::Struct(struct_no) => {
+                        let referenced = self.declaration(&ns.structs[*struct_no].loc);
+
+                        self.identifier(loc, &ns.structs[*struct_no].name, Some(referenced), ty)
+                    }
+                    _ => self.identifier(loc, &ty.to_string(ns), None, ty),
+                };
+
+                self.function_call(loc, "structConstructorCall", name, arguments, ty)
+            }
+            Expression::ArrayLiteral(loc, ty, dims, values)
+            | Expression::ConstArrayLiteral(loc, ty, dims, values) => {
+                self.array_literal(loc, ty, dims, values, func)
+            }
+            Expression::Add(loc, ty, left, right) => self.binary(loc, "+", ty, left, right, func),
+            Expression::Subtract(loc, ty, left, right) => {
+                self.binary(loc, "-", ty, left, right, func)
+            }
+            Expression::Multiply(loc, ty, left, right) => {
+                self.binary(loc, "*", ty, left, right, func)
+            }
+            Expression::Divide(loc, ty, left, right) => {
+                self.binary(loc, "/", ty, left, right, func)
+            }
+            Expression::Modulo(loc, ty, left, right) => {
+                self.binary(loc, "%", ty, left, right, func)
+            }
+            Expression::Power(loc, ty, left, right) => {
+                self.binary(loc, "**", ty, left, right, func)
+            }
+            Expression::BitwiseOr(loc, ty, left, right) => {
+                self.binary(loc, "|", ty, left, right, func)
+            }
+            Expression::BitwiseAnd(loc, ty, left, right) => {
+                self.binary(loc, "&", ty, left, right, func)
+            }
+            Expression::BitwiseXor(loc, ty, left, right) => {
+                self.binary(loc, "^", ty, left, right, func)
+            }
+            Expression::ShiftLeft(loc, ty, left, right) => {
+                self.binary(loc, "<<", ty, left, right, func)
+            }
+            Expression::ShiftRight(loc, ty, left, right, _) => {
+                self.binary(loc, ">>", ty, left, right, func)
+            }
+            Expression::More(loc, left, right) => {
+                self.binary(loc, ">", &Type::Bool, left, right, func)
+            }
+            Expression::Less(loc, left, right) => {
+                self.binary(loc, "<", &Type::Bool, left, right, func)
+            }
+            Expression::MoreEqual(loc, left, right) => {
+                self.binary(loc, ">=", &Type::Bool, left, right, func)
+            }
+            Expression::LessEqual(loc, left, right) => {
+                self.binary(loc, "<=", &Type::Bool, left, right, func)
+            }
+            Expression::Equal(loc, left, right) => {
+                self.binary(loc, "==", &Type::Bool, left, right, func)
+            }
+            Expression::NotEqual(loc, left, right) => {
+                self.binary(loc, "!=", &Type::Bool, left, right, func)
+            }
+            Expression::Or(loc, left, right) => {
+                self.binary(loc, "||", &Type::Bool, left, right, func)
+            }
+            Expression::And(loc, left, right) => {
+                self.binary(loc, "&&", &Type::Bool, left, right, func)
+            }
+            Expression::StringCompare(loc, left, right) => {
+                let left = self.string_location(loc, left, func);
+                let right = self.string_location(loc, right, func);
+
+                self.binary_operation(loc, "==", &Type::Bool, &Type::String, left, right)
+            }
+            Expression::StringConcat(loc, ty, left, right) => {
+                let left = self.string_location(loc, left, func);
+                let right = self.string_location(loc, right, func);
+
+                self.binary_operation(loc, "+", ty, ty, left, right)
+            }
+            Expression::Variable(loc, ty, pos) => {
+                let var = &func.unwrap().symtable.vars[pos];
+                let referenced = self.declaration(&var.id.loc);
+
+                self.identifier(loc, &var.id.name, Some(referenced), ty)
+            }
+            Expression::ConstantVariable(loc, ty, Some(contract_no), var_no)
+            | Expression::StorageVariable(loc, ty, contract_no, var_no) => {
+                let var = &ns.contracts[*contract_no].variables[*var_no];
+                let referenced = self.declaration(&var.loc);
+
+                self.identifier(loc, &var.name, Some(referenced), ty)
+            }
+            Expression::ConstantVariable(loc, ty, None, var_no) => {
+                let var = &ns.constants[*var_no];
+                let referenced = self.declaration(&var.loc);
+
+                self.identifier(loc, &var.name, Some(referenced), ty)
+            }
+            // loads are implicit
+            Expression::Load(_, _, expr) | Expression::StorageLoad(_, _, expr) => {
+                self.expression(expr, func)
+            }
+            Expression::ZeroExt(loc, ty, expr)
+            | Expression::SignExt(loc, ty, expr)
+            | Expression::Trunc(loc, ty, expr)
+            | Expression::Cast(loc, ty, expr)
+            | Expression::BytesCast(loc, ty, _, expr) => {
+                let inner = expr.loc();
+
+                // an implicit conversion has the location of the converted expression, or of
+                // the expression it is part of; an explicit one like `uint8(x)` encloses it
+                if loc.0 == inner.0 && loc.1 < inner.1 && loc.2 > inner.2 {
+                    let type_loc = pt::Loc(loc.0, loc.1, inner.1 - 1);
+                    let type_name = self.type_name(ty, &type_loc);
+                    let type_expression = self.node(
+                        "ElementaryTypeNameExpression",
+                        &type_loc,
+                        json!({
+                            "typeName": type_name,
+                            "typeDescriptions": self.type_descriptions(ty),
+                        }),
+                    );
+                    let argument = self.expression(expr, func);
+
+                    self.function_call(loc, "typeConversion", type_expression, vec![argument], ty)
+                } else {
+                    self.expression(expr, func)
+                }
+            }
+            Expression::PreIncrement(loc, ty, expr) => self.unary(loc, "++", true, ty, expr, func),
+            Expression::PreDecrement(loc, ty, expr) => self.unary(loc, "--", true, ty, expr, func),
+            Expression::PostIncrement(loc, ty, expr) => {
+                self.unary(loc, "++", false, ty, expr, func)
+            }
+            Expression::PostDecrement(loc, ty, expr) => {
+                self.unary(loc, "--", false, ty, expr, func)
+            }
+            Expression::Not(loc, expr) => self.unary(loc, "!", true, &Type::Bool, expr, func),
+            Expression::Complement(loc, ty, expr) => self.unary(loc, "~", true, ty, expr, func),
+            Expression::UnaryMinus(loc, ty, expr) => self.unary(loc, "-", true, ty, expr, func),
+            Expression::Assign(loc, ty, left, right) => self.assignment(loc, ty, left, right, func),
+            Expression::Ternary(loc, ty, cond, left, right) => {
+                let condition = self.expression(cond, func);
+                let true_expression = self.expression(left, func);
+                let false_expression = self.expression(right, func);
+
+                self.node(
+                    "Conditional",
+                    loc,
+                    json!({
+                        "condition": condition,
+                        "trueExpression": true_expression,
+                        "falseExpression": false_expression,
+                        "typeDescriptions": self.type_descriptions(ty),
+                    }),
+                )
+            }
+            Expression::Subscript(loc, _, array, index)
+            | Expression::DynamicArraySubscript(loc, _, array, index)
+            | Expression::StorageBytesSubscript(loc, array, index) => {
+                let base_expression = self.expression(array, func);
+                let index_expression = self.expression(index, func);
+
+                self.node(
+                    "IndexAccess",
+                    loc,
+                    json!({
+                        "baseExpression": base_expression,
+                        "indexExpression": index_expression,
+                        "typeDescriptions": self.type_descriptions(&expr.ty()),
+                    }),
+                )
+            }
+            Expression::StructMember(loc, ty, struct_expr, field_no) => {
+                let expression = self.expression(struct_expr, func);
+
+                match struct_expr.ty().deref_any() {
+                    Type::Struct(struct_no) => {
+                        let field = &ns.structs[*struct_no].fields[*field_no];
+                        let referenced = field.name_loc.map(|loc| self.declaration(&loc));
+
+                        self.member_access(loc, expression, &field.name, referenced, ty)
+                    }
+                    _ => self.member_access(loc, expression, "", None, ty),
+                }
+            }
+            Expression::DynamicArrayLength(loc, array)
+            | Expression::StorageArrayLength { loc, array, .. } => {
+                let expression = self.expression(array, func);
+
+                self.member_access(loc, expression, "length", None, &expr.ty())
+            }
+            Expression::DynamicArrayPush(loc, array, _, value) => {
+                let array = self.expression(array, func);
+                let push = self.member_access(loc, array, "push", None, &Type::Void);
+                let value = self.expression(value, func);
+
+                self.function_call(loc, "functionCall", push, vec![value], &expr.ty())
+            }
+            Expression::DynamicArrayPop(loc, array, _) => {
+                let array = self.expression(array, func);
+                let pop = self.member_access(loc, array, "pop", None, &Type::Void);
+
+                self.function_call(loc, "functionCall", pop, Vec::new(), &expr.ty())
+            }
+            Expression::AllocDynamicArray(loc, ty, size, None) => {
+                let type_name = self.type_name(ty, loc);
+                let new_expression = self.node(
+                    "NewExpression",
+                    loc,
+                    json!({
+                        "typeName": type_name,
+                        "typeDescriptions": self.type_descriptions(ty),
+                    }),
+                );
+                let size = self.expression(size, func);
+
+                self.function_call(loc, "functionCall", new_expression, vec![size], ty)
+            }
+            Expression::InternalFunction {
+                loc,
+                ty,
+                function_no,
+                ..
+            } => {
+                let function = &ns.functions[*function_no];
+                let referenced = self.declaration(&function.loc);
+
+                self.identifier(loc, &function.name, Some(referenced), ty)
+            }
+            Expression::ExternalFunction {
+                loc,
+                ty,
+                address,
+                function_no,
+            } => {
+                let function = &ns.functions[*function_no];
+                let referenced = self.declaration(&function.loc);
+                let expression = self.expression(address, func);
+
+                self.member_access(loc, expression, &function.name, Some(referenced), ty)
+            }
+            Expression::InternalFunctionCall {
+                loc,
+                returns,
+                function,
+                args,
+            } => {
+                let expression = self.expression(function, func);
+                let arguments = self.expressions(args, func);
+
+                self.function_call_returns(loc, "functionCall", expression, arguments, returns)
+            }
+            Expression::ExternalFunctionCall {
+                loc,
+                returns,
+                function,
+                args,
+                value,
+                gas,
+            } => {
+                let expression = self.expression(function, func);
+                let expression = self.call_options(loc, expression, value, gas, func);
+                let arguments = self.expressions(args, func);
+
+                self.function_call_returns(loc, "functionCall", expression, arguments, returns)
+            }
+            Expression::ExternalFunctionCallRaw {
+                loc,
+                ty,
+                address,
+                args,
+                value,
+                gas,
+            } => {
+                let member = match ty {
+                    CallTy::Regular => "call",
+                    CallTy::Delegate => "delegatecall",
+                    CallTy::Static => "staticcall",
+                };
+                let address = self.expression(address, func);
+                let expression = self.member_access(loc, address, member, None, &Type::Void);
+                let expression = self.call_options(loc, expression, value, gas, func);
+                let arguments = vec![self.expression(args, func)];
+
+                self.function_call_returns(
+                    loc,
+                    "functionCall",
+                    expression,
+                    arguments,
+                    &[Type::Bool, Type::DynamicBytes],
+                )
+            }
+            Expression::Constructor {
+                loc,
+                contract_no,
+                args,
+                ..
+            } => {
+                let ty = Type::Contract(*contract_no);
+                let type_name = self.contract_name(*contract_no, loc);
+                let new_expression = self.node(
+                    "NewExpression",
+                    loc,
+                    json!({
+                        "typeName": type_name,
+                        "typeDescriptions": self.type_descriptions(&ty),
+                    }),
+                );
+                let arguments = self.expressions(args, func);
+
+                self.function_call(loc, "functionCall", new_expression, arguments, &ty)
+            }
+            Expression::FormatString(loc, args) => {
+                let expression = self.identifier(loc, "format", None, &Type::Void);
+                let arguments: Vec<Value> = args
+                    .iter()
+                    .map(|(_, arg)| self.expression(arg, func))
+                    .collect();
+
+                self.function_call(loc, "functionCall", expression, arguments, &Type::String)
+            }
+            Expression::Keccak256(loc, ty, args) => {
+                let expression = self.identifier(loc, "keccak256", None, &Type::Void);
+                let arguments = self.expressions(args, func);
+
+                self.function_call(loc, "functionCall", expression, arguments, ty)
+            }
+            Expression::Builtin(loc, tys, builtin, args) => {
+                self.builtin(loc, tys, *builtin, args, func)
+            }
+            Expression::List(loc, exprs) => {
+                let components = self.expressions(exprs, func);
+                let tys: Vec<Type> = exprs.iter().map(|expr| expr.ty()).collect();
+
+                self.node(
+                    "TupleExpression",
+                    loc,
+                    json!({
+                        "components": components,
+                        "isInlineArray": false,
+                        "typeDescriptions": self.tuple_descriptions(&tys),
+                    }),
+                )
+            }
+            // these are only created by codegen
+            Expression::FunctionArg(..)
+            | Expression::ReturnData(_)
+            | Expression::AbiEncode { .. }
+            | Expression::InternalFunctionCfg(_)
+            | Expression::Poison => unreachable!(),
+        }
+    }
+
+    fn builtin(
+        &mut self,
+        loc: &pt::Loc,
+        tys: &[Type],
+        builtin: Builtin,
+        args: &[Expression],
+        func: Option<&Function>,
+    ) -> Value {
+        let member = match builtin {
+            Builtin::GetAddress if args.is_empty() => {
+                return self.identifier(loc, "this", None, &tys[0]);
+            }
+            Builtin::Balance => Some("balance"),
+            Builtin::PayableSend => Some("send"),
+            Builtin::PayableTransfer => Some("transfer"),
+            Builtin::ArrayPush => Some("push"),
+            Builtin::ArrayPop => Some("pop"),
+            Builtin::ExternalFunctionAddress => Some("address"),
+            Builtin::ExternalFunctionSelector => Some("selector"),
+            _ => None,
+        };
+
+        // members of the first argument, like `a.balance` or `a.send(v)`
+        if let (Some(member), Some((object, args))) = (member, args.split_first()) {
+            let object = self.expression(object, func);
+
+            return if matches!(
+                builtin,
+                Builtin::Balance
+                    | Builtin::ExternalFunctionAddress
+                    | Builtin::ExternalFunctionSelector
+            ) {
+                self.member_access(loc, object, member, None, &tys[0])
+            } else {
+                let expression = self.member_access(loc, object, member, None, &Type::Void);
+                let arguments = self.expressions(args, func);
+
+                self.function_call_returns(loc, "functionCall", expression, arguments, tys)
+            };
+        }
+
+        let (namespace, name) = match get_prototype(builtin) {
+            Some(prototype) => (prototype.namespace, prototype.name),
+            None => (None, ""),
+        };
+
+        let mut expression = match namespace {
+            Some(namespace) => {
+                let object = self.identifier(loc, namespace, None, &Type::Void);
+
+                self.member_access(loc, object, name, None, &Type::Void)
+            }
+            None => self.identifier(loc, name, None, &Type::Void),
+        };
+
+        // variables like `msg.sender` are not called
+        if args.is_empty() && is_builtin_variable(builtin) {
+            expression["typeDescriptions"] = self.tuple_descriptions(tys);
+
+            return expression;
+        }
+
+        let arguments = self.expressions(args, func);
+
+        self.function_call_returns(loc, "functionCall", expression, arguments, tys)
+    }
+
+    fn literal(
+        &mut self,
+        loc: &pt::Loc,
+        kind: &str,
+        value: Option<&str>,
+        bytes: &[u8],
+        ty: &Type,
+    ) -> Value {
+        let hex_value = match value {
+            Some(value) if bytes.is_empty() => hex::encode(value),
+            _ => hex::encode(bytes),
+        };
+
+        self.node(
+            "Literal",
+            loc,
+            json!({
+                "kind": kind,
+                "value": value,
+                "hexValue": hex_value,
+                "typeDescriptions": self.type_descriptions(ty),
+            }),
+        )
+    }
+
+    fn identifier(
+        &mut self,
+        loc: &pt::Loc,
+        name: &str,
+        referenced: Option<usize>,
+        ty: &Type,
+    ) -> Value {
+        self.node(
+            "Identifier",
+            loc,
+            json!({
+                "name": name,
+                "referencedDeclaration": referenced,
+                "overloadedDeclarations": [],
+                "typeDescriptions": self.type_descriptions(ty),
+            }),
+        )
+    }
+
+    fn contract_identifier(&mut self, loc: &pt::Loc, contract_no: usize) -> Value {
+        let ns = self.ns;
+        let contract = &ns.contracts[contract_no];
+        let referenced = self.declaration(&contract.loc);
+
+        self.identifier(
+            loc,
+            &contract.name,
+            Some(referenced),
+            &Type::Contract(contract_no),
+        )
+    }
+
+    fn member_access(
+        &mut self,
+        loc: &pt::Loc,
+        expression: Value,
+        member: &str,
+        referenced: Option<usize>,
+        ty: &Type,
+    ) -> Value {
+        self.node(
+            "MemberAccess",
+            loc,
+            json!({
+                "expression": expression,
+                "memberName": member,
+                "referencedDeclaration": referenced,
+                "typeDescriptions": self.type_descriptions(ty),
+            }),
+        )
+    }
+
+    fn function_call(
+        &mut self,
+        loc: &pt::Loc,
+        kind: &str,
+        expression: Value,
+        arguments: Vec<Value>,
+        ty: &Type,
+    ) -> Value {
+        self.function_call_returns(loc, kind, expression, arguments, &[ty.clone()])
+    }
+
+    fn function_call_returns(
+        &mut self,
+        loc: &pt::Loc,
+        kind: &str,
+        expression: Value,
+        arguments: Vec<Value>,
+        returns: &[Type],
+    ) -> Value {
+        self.node(
+            "FunctionCall",
+            loc,
+            json!({
+                "kind": kind,
+                "expression": expression,
+                "arguments": arguments,
+                "names": [],
+                "typeDescriptions": self.tuple_descriptions(returns),
+            }),
+        )
+    }
+
+    /// The `{value: v, gas: g}` options of an external call, if any were given
+    fn call_options(
+        &mut self,
+        loc: &pt::Loc,
+        expression: Value,
+        value: &Expression,
+        gas: &Expression,
+        func: Option<&Function>,
+    ) -> Value {
+        let mut names = Vec::new();
+        let mut options = Vec::new();
+
+        for (name, option) in &[("value", value), ("gas", gas)] {
+            if !is_zero(option) {
+                names.push(*name);
+                options.push(self.expression(option, func));
+            }
+        }
+
+        if options.is_empty() {
+            return expression;
+        }
+
+        let type_descriptions = expression["typeDescriptions"].clone();
+
+        self.node(
+            "FunctionCallOptions",
+            loc,
+            json!({
+                "expression": expression,
+                "names": names,
+                "options": options,
+                "typeDescriptions": type_descriptions,
+            }),
+        )
+    }
+
+    fn binary(
+        &mut self,
+        loc: &pt::Loc,
+        operator: &str,
+        ty: &Type,
+        left: &Expression,
+        right: &Expression,
+        func: Option<&Function>,
+    ) -> Value {
+        let common_type = left.ty();
+        let left = self.expression(left, func);
+        let right = self.expression(right, func);
+
+        self.binary_operation(loc, operator, ty, &common_type, left, right)
+    }
+
+    fn binary_operation(
+        &mut self,
+        loc: &pt::Loc,
+        operator: &str,
+        ty: &Type,
+        common_type: &Type,
+        left: Value,
+        right: Value,
+    ) -> Value {
+        self.node(
+            "BinaryOperation",
+            loc,
+            json!({
+                "operator": operator,
+                "leftExpression": left,
+                "rightExpression": right,
+                "commonType": self.type_descriptions(common_type.deref_any()),
+                "typeDescriptions": self.type_descriptions(ty),
+            }),
+        )
+    }
+
+    fn unary(
+        &mut self,
+        loc: &pt::Loc,
+        operator: &str,
+        prefix: bool,
+        ty: &Type,
+        expr: &Expression,
+        func: Option<&Function>,
+    ) -> Value {
+        let sub_expression = self.expression(expr, func);
+
+        self.node(
+            "UnaryOperation",
+            loc,
+            json!({
+                "operator": operator,
+                "prefix": prefix,
+                "subExpression": sub_expression,
+                "typeDescriptions": self.type_descriptions(ty),
+            }),
+        )
+    }
+
+    /// An assignment. A compound assignment like `a += b` is resolved as `a = a + b`, where the
+    /// operation has the location of the whole assignment.
+    fn assignment(
+        &mut self,
+        loc: &pt::Loc,
+        ty: &Type,
+        left: &Expression,
+        right: &Expression,
+        func: Option<&Function>,
+    ) -> Value {
+        let (operator, right) = match right {
+            Expression::Add(op_loc, _, _, value) if op_loc == loc => ("+=", value.as_ref()),
+            Expression::Subtract(op_loc, _, _, value) if op_loc == loc => ("-=", value.as_ref()),
+            Expression::Multiply(op_loc, _, _, value) if op_loc == loc => ("*=", value.as_ref()),
+            Expression::Divide(op_loc, _, _, value) if op_loc == loc => ("/=", value.as_ref()),
+            Expression::Modulo(op_loc, _, _, value) if op_loc == loc => ("%=", value.as_ref()),
+            Expression::BitwiseOr(op_loc, _, _, value) if op_loc == loc => ("|=", value.as_ref()),
+            Expression::BitwiseAnd(op_loc, _, _, value) if op_loc == loc => ("&=", value.as_ref()),
+            Expression::BitwiseXor(op_loc, _, _, value) if op_loc == loc => ("^=", value.as_ref()),
+            Expression::ShiftLeft(op_loc, _, _, value) if op_loc == loc => ("<<=", value.as_ref()),
+            Expression::ShiftRight(op_loc, _, _, value, _) if op_loc == loc => {
+                (">>=", value.as_ref())
+            }
+            _ => ("=", right),
+        };
+
+        let left_hand_side = self.expression(left, func);
+        let right_hand_side = self.expression(right, func);
+
+        self.node(
+            "Assignment",
+            loc,
+            json!({
+                "operator": operator,
+                "leftHandSide": left_hand_side,
+                "rightHandSide": right_hand_side,
+                "typeDescriptions": self.type_descriptions(ty),
+            }),
+        )
+    }
+
+    fn string_location(
+        &mut self,
+        loc: &pt::Loc,
+        string: &StringLocation,
+        func: Option<&Function>,
+    ) -> Value {
+        match string {
+            StringLocation::CompileTime(bs) => {
+                let value = String::from_utf8_lossy(bs);
+
+                self.literal(loc, "string", Some(&*value), bs, &Type::String)
+            }
+            StringLocation::RunTime(expr) => self.expression(expr, func),
+        }
+    }
+
+    /// An array literal like `[[1, 2], [3, 4]]`. The values of all dimensions are in one list,
+    /// so the inner arrays are recreated from the dimensions, innermost first.
+    fn array_literal(
+        &mut self,
+        loc: &pt::Loc,
+        ty: &Type,
+        dims: &[u32],
+        values: &[Expression],
+        func: Option<&Function>,
+    ) -> Value {
+        let components = if dims.len() > 1 {
+            let inner_dims = &dims[..dims.len() - 1];
+            let chunk = inner_dims.iter().product::<u32>().max(1) as usize;
+            let inner_ty = ty.array_deref();
+
+            values
+                .chunks(chunk)
+                .map(|values| {
+                    let inner_loc = span(values.iter().map(|value| value.loc()), loc);
+
+                    self.array_literal(&inner_loc, &inner_ty, inner_dims, values, func)
+                })
+                .collect()
+        } else {
+            self.expressions(values, func)
+        };
+
+        self.node(
+            "TupleExpression",
+            loc,
+            json!({
+                "components": components,
+                "isInlineArray": true,
+                "typeDescriptions": self.type_descriptions(ty),
+            }),
+        )
+    }
+}
+
+/// The storage location of a parameter or local variable
+fn storage_location(ty: &Type) -> &'static str {
+    match ty {
+        Type::StorageRef(_) => "storage",
+        Type::Ref(_)
+        | Type::Struct(_)
+        | Type::Array(..)
+        | Type::DynamicBytes
+        | Type::String
+        | Type::Mapping(..) => "memory",
+        _ => "default",
+    }
+}
+
+fn statement_loc(stmt: &Statement) -> pt::Loc {
+    match stmt {
+        Statement::VariableDecl(loc, ..)
+        | Statement::If(loc, ..)
+        | Statement::While(loc, ..)
+        | Statement::For { loc, .. }
+        | Statement::DoWhile(loc, ..)
+        | Statement::Expression(loc, ..)
+        | Statement::Delete(loc, ..)
+        | Statement::Destructure(loc, ..)
+        | Statement::Continue(loc)
+        | Statement::Break(loc)
+        | Statement::Return(loc, _)
+        | Statement::Emit { loc, .. }
+        | Statement::TryCatch { loc, .. }
+        | Statement::Underscore(loc) => *loc,
+    }
+}
+
+/// The location from the start of the first location to the end of the last, or the default
+/// if there are none
+fn span<I: Iterator<Item = pt::Loc>>(mut locs: I, default: &pt::Loc) -> pt::Loc {
+    match locs.next() {
+        Some(first) => {
+            let end = locs.fold(first.2, |end, loc| end.max(loc.2));
+
+            pt::Loc(first.0, first.1, end)
+        }
+        None => pt::Loc(default.0, default.1, default.1),
+    }
+}
+
+/// The location of a block node, for a clause which consists of the block
+fn block_loc(block: &Value, default: &pt::Loc) -> pt::Loc {
+    let mut parts = block["src"]
+        .as_str()
+        .unwrap_or("")
+        .split(':')
+        .map(|n| n.parse::<usize>().ok());
+
+    match (parts.next().flatten(), parts.next().flatten()) {
+        (Some(start), Some(length)) => pt::Loc(default.0, start, start + length),
+        _ => *default,
+    }
+}
+
+/// Is this the default value or gas of an external call, i.e. not given
+fn is_zero(expr: &Expression) -> bool {
+    match expr {
+        Expression::NumberLiteral(_, _, value) => value.is_zero(),
+        _ => false,
+    }
+}
+
+/// Type identifiers in the AST escape the brackets and commas, like solc does
+fn escape_type_id(id: &str) -> String {
+    id.replace('(', "$_").replace(')', "_$").replace(',', "_$_")
+}
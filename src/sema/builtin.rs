@@ -1,4 +1,5 @@
 use super::ast::{Builtin, Diagnostic, Expression, Namespace, Type};
+use super::codes;
 use super::eval::eval_const_number;
 use super::expression::{cast, expression};
 use super::symtable::Symtable;
@@ -20,7 +21,7 @@ pub struct Prototype {
 }
 
 // A list of all Solidity builtins functions
-static BUILTIN_FUNCTIONS: [Prototype; 23] = [
+static BUILTIN_FUNCTIONS: [Prototype; 25] = [
     Prototype {
         builtin: Builtin::Assert,
         namespace: None,
@@ -257,6 +258,26 @@ static BUILTIN_FUNCTIONS: [Prototype; 23] = [
         // it should be allowed in constant context, but we don't supported that yet
         constant: false,
     },
+    Prototype {
+        builtin: Builtin::PredictAddress,
+        namespace: None,
+        name: "predict_address",
+        args: &[Type::Bytes(32), Type::Uint(256)],
+        ret: &[Type::Address(false)],
+        target: Some(Target::Ewasm),
+        doc: "Calculate the address of a contract created by this contract with the given salt, given the keccak256 hash of its init code",
+        constant: false,
+    },
+    Prototype {
+        builtin: Builtin::PredictAddress,
+        namespace: None,
+        name: "predict_address",
+        args: &[Type::Bytes(32), Type::Uint(256)],
+        ret: &[Type::Address(false)],
+        target: Some(Target::Substrate),
+        doc: "Calculate the address of a contract instantiated by this contract with the given salt, given its code hash",
+        constant: false,
+    },
 ];
 
 // A list of all Solidity builtins variables
@@ -393,13 +414,55 @@ static BUILTIN_VARIABLE: [Prototype; 13] = [
     },
 ];
 
+/// Is the builtin available on the target. Builtins for ewasm are available on every
+/// Ethereum target.
+fn target_matches(target: Option<Target>, ns: &Namespace) -> bool {
+    match target {
+        None => true,
+        Some(Target::Ewasm) => ns.target.is_ethereum(),
+        Some(target) => target == ns.target,
+    }
+}
+
+/// Can the builtin be compiled for the target. Sawtooth Sabre has no accounts, value, gas
+/// or blocks, and only provides keccak256 for hashing.
+pub fn target_supports(builtin: Builtin, ns: &Namespace) -> bool {
+    ns.target != Target::Sabre
+        || !matches!(
+            builtin,
+            Builtin::Balance
+                | Builtin::PayableSend
+                | Builtin::PayableTransfer
+                | Builtin::SelfDestruct
+                | Builtin::Ripemd160
+                | Builtin::Sha256
+                | Builtin::Gasleft
+                | Builtin::BlockCoinbase
+                | Builtin::BlockDifficulty
+                | Builtin::GasLimit
+                | Builtin::BlockNumber
+                | Builtin::Timestamp
+                | Builtin::Sender
+                | Builtin::Value
+                | Builtin::Gasprice
+                | Builtin::Origin
+                | Builtin::BlockHash
+        )
+}
+
+/// The name of a builtin as it is written, e.g. `msg.sender`
+fn full_name(p: &Prototype) -> String {
+    match p.namespace {
+        Some(namespace) => format!("{}.{}", namespace, p.name),
+        None => p.name.to_owned(),
+    }
+}
+
 /// Does function call match builtin
 pub fn is_builtin_call(namespace: Option<&str>, fname: &str, ns: &Namespace) -> bool {
-    BUILTIN_FUNCTIONS.iter().any(|p| {
-        p.name == fname
-            && p.namespace == namespace
-            && (p.target.is_none() || p.target == Some(ns.target))
-    })
+    BUILTIN_FUNCTIONS
+        .iter()
+        .any(|p| p.name == fname && p.namespace == namespace && target_matches(p.target, ns))
 }
 
 /// Get the prototype for a builtin. If the prototype has arguments, it is a function else
@@ -411,6 +474,21 @@ pub fn get_prototype(builtin: Builtin) -> Option<&'static Prototype> {
         .or_else(|| BUILTIN_VARIABLE.iter().find(|p| p.builtin == builtin))
 }
 
+/// Is the builtin a variable like `msg.sender`, rather than a function
+pub fn is_builtin_variable(builtin: Builtin) -> bool {
+    BUILTIN_VARIABLE.iter().any(|p| p.builtin == builtin)
+}
+
+/// All the builtin functions and variables which are available on the target. This is for
+/// use by the language server
+pub fn available_builtins(ns: &Namespace) -> Vec<&'static Prototype> {
+    BUILTIN_FUNCTIONS
+        .iter()
+        .chain(BUILTIN_VARIABLE.iter())
+        .filter(|p| target_matches(p.target, ns))
+        .collect()
+}
+
 /// Does variable name match builtin
 pub fn builtin_var(
     loc: &pt::Loc,
@@ -419,19 +497,40 @@ pub fn builtin_var(
     ns: &Namespace,
     diagnostics: &mut Vec<Diagnostic>,
 ) -> Option<(Builtin, Type)> {
+    // ‘now’ is an alias for block.timestamp before Solidity 0.7
+    if namespace.is_none() && fname == "now" && !ns.version_at_least(loc.0, 7) {
+        return builtin_var(loc, Some("block"), "timestamp", ns, diagnostics);
+    }
+
     if let Some(p) = BUILTIN_VARIABLE
         .iter()
         .find(|p| p.name == fname && p.namespace == namespace)
     {
-        if p.target.is_none() || p.target == Some(ns.target) {
+        if target_matches(p.target, ns) {
+            if !target_supports(p.builtin, ns) {
+                diagnostics.push(
+                    Diagnostic::error(
+                        *loc,
+                        format!("‘{}’ not supported on {}", full_name(p), ns.target),
+                    )
+                    .with_code(codes::TARGET_NOT_SUPPORTED),
+                );
+            }
             if ns.target == Target::Substrate && p.builtin == Builtin::Gasprice {
                 diagnostics.push(Diagnostic::error(
                     *loc,
                     String::from(
                         "use the function ‘tx.gasprice(gas)’ in stead, as ‘tx.gasprice’ may round down to zero. See https://solang.readthedocs.io/en/latest/language.html#gasprice",
                     ),
-                ));
+                ).with_code(codes::TARGET_NOT_SUPPORTED));
             }
+            // msg.sender and tx.origin are no longer payable from Solidity 0.8
+            if matches!(p.builtin, Builtin::Sender | Builtin::Origin)
+                && ns.pragma_at_least(loc.0, 8)
+            {
+                return Some((p.builtin, Type::Address(false)));
+            }
+
             return Some((p.builtin, p.ret[0].clone()));
         }
     }
@@ -473,33 +572,52 @@ pub fn resolve_call(
 ) -> Result<Expression, ()> {
     let matches = BUILTIN_FUNCTIONS
         .iter()
-        .filter(|p| p.name == id && p.namespace == namespace)
+        .filter(|p| p.name == id && p.namespace == namespace && target_matches(p.target, ns))
         .collect::<Vec<&Prototype>>();
 
+    if let Some(func) = matches.first() {
+        if !target_supports(func.builtin, ns) {
+            diagnostics.push(
+                Diagnostic::error(
+                    *loc,
+                    format!("‘{}’ not supported on {}", full_name(func), ns.target),
+                )
+                .with_code(codes::TARGET_NOT_SUPPORTED),
+            );
+            return Err(());
+        }
+    }
+
     let marker = diagnostics.len();
 
     for func in &matches {
         if is_constant && !func.constant {
-            diagnostics.push(Diagnostic::error(
-                *loc,
-                format!(
-                    "cannot call function ‘{}’ in constant expression",
-                    func.name
-                ),
-            ));
+            diagnostics.push(
+                Diagnostic::error(
+                    *loc,
+                    format!(
+                        "cannot call function ‘{}’ in constant expression",
+                        func.name
+                    ),
+                )
+                .with_code(codes::CONSTANT_EXPRESSION),
+            );
             return Err(());
         }
 
         if func.args.len() != args.len() {
-            diagnostics.push(Diagnostic::error(
-                *loc,
-                format!(
-                    "builtin function ‘{}’ expects {} arguments, {} provided",
-                    func.name,
-                    func.args.len(),
-                    args.len()
-                ),
-            ));
+            diagnostics.push(
+                Diagnostic::error(
+                    *loc,
+                    format!(
+                        "builtin function ‘{}’ expects {} arguments, {} provided",
+                        func.name,
+                        func.args.len(),
+                        args.len()
+                    ),
+                )
+                .with_code(codes::WRONG_ARGUMENTS),
+            );
             continue;
         }
 
@@ -553,7 +671,7 @@ pub fn resolve_call(
                             String::from(
                                 "the function call ‘tx.gasprice(1)’ may round down to zero. See https://solang.readthedocs.io/en/latest/language.html#gasprice",
                             ),
-                        ));
+                        ).with_code(codes::GASPRICE));
                     }
                 }
             }
@@ -569,10 +687,13 @@ pub fn resolve_call(
 
     if matches.len() != 1 {
         diagnostics.truncate(marker);
-        diagnostics.push(Diagnostic::error(
-            *loc,
-            "cannot find overloaded function which matches signature".to_string(),
-        ));
+        diagnostics.push(
+            Diagnostic::error(
+                *loc,
+                "cannot find overloaded function which matches signature".to_string(),
+            )
+            .with_code(codes::NO_MATCHING_OVERLOAD),
+        );
     }
 
     Err(())
@@ -620,10 +741,13 @@ pub fn resolve_method_call(
 
     if builtin == Builtin::AbiDecode {
         if args.len() != 2 {
-            diagnostics.push(Diagnostic::error(
-                *loc,
-                format!("function expects {} arguments, {} provided", 2, args.len()),
-            ));
+            diagnostics.push(
+                Diagnostic::error(
+                    *loc,
+                    format!("function expects {} arguments, {} provided", 2, args.len()),
+                )
+                .with_code(codes::WRONG_ARGUMENTS),
+            );
 
             return Err(());
         }
@@ -658,32 +782,44 @@ pub fn resolve_method_call(
                             ns.resolve_type(file_no, contract_no, false, &param.ty, diagnostics)?;
 
                         if let Some(storage) = &param.storage {
-                            diagnostics.push(Diagnostic::error(
-                                *storage.loc(),
-                                format!("storage modifier ‘{}’ not allowed", storage),
-                            ));
+                            diagnostics.push(
+                                Diagnostic::error(
+                                    *storage.loc(),
+                                    format!("storage modifier ‘{}’ not allowed", storage),
+                                )
+                                .with_code(codes::DATA_LOCATION),
+                            );
                             broken = true;
                         }
 
                         if let Some(name) = &param.name {
-                            diagnostics.push(Diagnostic::error(
-                                name.loc,
-                                format!("unexpected identifier ‘{}’ in type", name.name),
-                            ));
+                            diagnostics.push(
+                                Diagnostic::error(
+                                    name.loc,
+                                    format!("unexpected identifier ‘{}’ in type", name.name),
+                                )
+                                .with_code(codes::ABI_ENCODING),
+                            );
                             broken = true;
                         }
 
                         if ty.is_mapping() {
-                            diagnostics.push(Diagnostic::error(
-                                *loc,
-                                "mapping cannot be abi decoded or encoded".to_string(),
-                            ));
+                            diagnostics.push(
+                                Diagnostic::error(
+                                    *loc,
+                                    "mapping cannot be abi decoded or encoded".to_string(),
+                                )
+                                .with_code(codes::ABI_ENCODING),
+                            );
                             broken = true;
                         }
 
                         tys.push(ty);
                     } else {
-                        diagnostics.push(Diagnostic::error(*loc, "missing type".to_string()));
+                        diagnostics.push(
+                            Diagnostic::error(*loc, "missing type".to_string())
+                                .with_code(codes::ABI_ENCODING),
+                        );
 
                         broken = true;
                     }
@@ -693,10 +829,13 @@ pub fn resolve_method_call(
                 let ty = ns.resolve_type(file_no, contract_no, false, &args[1], diagnostics)?;
 
                 if ty.is_mapping() {
-                    diagnostics.push(Diagnostic::error(
-                        *loc,
-                        "mapping cannot be abi decoded or encoded".to_string(),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(
+                            *loc,
+                            "mapping cannot be abi decoded or encoded".to_string(),
+                        )
+                        .with_code(codes::ABI_ENCODING),
+                    );
                     broken = true;
                 }
 
@@ -746,10 +885,13 @@ pub fn resolve_method_call(
                     )?,
                 );
             } else {
-                diagnostics.push(Diagnostic::error(
-                    *loc,
-                    "function requires one ‘bytes4’ selector argument".to_string(),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(
+                        *loc,
+                        "function requires one ‘bytes4’ selector argument".to_string(),
+                    )
+                    .with_code(codes::WRONG_ARGUMENTS),
+                );
 
                 return Err(());
             }
@@ -780,10 +922,13 @@ pub fn resolve_method_call(
                     )?,
                 );
             } else {
-                diagnostics.push(Diagnostic::error(
-                    *loc,
-                    "function requires one ‘string’ signature argument".to_string(),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(
+                        *loc,
+                        "function requires one ‘string’ signature argument".to_string(),
+                    )
+                    .with_code(codes::WRONG_ARGUMENTS),
+                );
 
                 return Err(());
             }
@@ -805,10 +950,10 @@ pub fn resolve_method_call(
         let ty = expr.ty();
 
         if ty.is_mapping() {
-            diagnostics.push(Diagnostic::error(
-                arg.loc(),
-                "mapping type not permitted".to_string(),
-            ));
+            diagnostics.push(
+                Diagnostic::error(arg.loc(), "mapping type not permitted".to_string())
+                    .with_code(codes::ABI_ENCODING),
+            );
 
             return Err(());
         }
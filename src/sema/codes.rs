@@ -0,0 +1,567 @@
+// Stable codes for diagnostics, and their explanations for `solang --explain`
+//
+// Codes are never reused; when a diagnostic is removed, its code should stay in the list
+// so that `--explain` keeps working for older output. The first digit gives the class:
+// 0 informational, 1 parser, 2 syntax, 3 declaration, 4 type, 5 warning.
+
+use super::ast::WarningCategory;
+
+pub const INFO: &str = "S0000";
+
+pub const PARSER_ERROR: &str = "S1000";
+pub const INVALID_TOKEN: &str = "S1001";
+pub const UNRECOGNISED_TOKEN: &str = "S1002";
+pub const EXTRA_TOKEN: &str = "S1003";
+pub const UNEXPECTED_EOF: &str = "S1004";
+
+pub const ERROR: &str = "S2000";
+pub const STRAY_SEMICOLON: &str = "S2001";
+pub const DIVIDE_BY_ZERO: &str = "S2002";
+pub const STORAGE_LAYOUT: &str = "S2003";
+pub const LITERAL: &str = "S2004";
+pub const CALL_ARGUMENTS: &str = "S2005";
+pub const WRONG_ARGUMENTS: &str = "S2006";
+pub const NO_MATCHING_OVERLOAD: &str = "S2007";
+pub const CONSTANT_EXPRESSION: &str = "S2008";
+pub const NOT_ASSIGNABLE: &str = "S2009";
+pub const STATEMENT_NOT_ALLOWED: &str = "S2010";
+pub const FORMAT_STRING: &str = "S2011";
+pub const TRY_CATCH: &str = "S2012";
+pub const CONTRACT_CREATION: &str = "S2013";
+pub const TARGET_NOT_SUPPORTED: &str = "S2014";
+pub const OUT_OF_RANGE: &str = "S2015";
+pub const DOC_TAG: &str = "S2016";
+pub const STATE_MUTABILITY: &str = "S2017";
+pub const ABI_ENCODING: &str = "S2018";
+pub const PRAGMA_SOLIDITY: &str = "S2019";
+pub const ARRAY: &str = "S2020";
+
+pub const DECLARATION_ERROR: &str = "S3000";
+pub const NOT_FOUND: &str = "S3001";
+pub const ALREADY_DEFINED: &str = "S3002";
+pub const WRONG_KIND_OF_NAME: &str = "S3003";
+pub const OVERRIDE: &str = "S3004";
+pub const INHERITANCE: &str = "S3005";
+pub const FUNCTION_DECLARATION: &str = "S3006";
+pub const CONTRACT_KIND: &str = "S3007";
+pub const DATA_LOCATION: &str = "S3008";
+pub const VARIABLE_DECLARATION: &str = "S3009";
+pub const TYPE_DECLARATION: &str = "S3010";
+pub const NOT_ACCESSIBLE: &str = "S3011";
+pub const IMPORT: &str = "S3012";
+
+pub const TYPE_ERROR: &str = "S4000";
+pub const IMPLICIT_CONVERSION: &str = "S4001";
+pub const EXPLICIT_CONVERSION: &str = "S4002";
+pub const MAPPING_TYPE: &str = "S4003";
+pub const RETURN_VALUES: &str = "S4004";
+pub const TYPE_NOT_ALLOWED: &str = "S4005";
+
+pub const WARNING: &str = "S5000";
+pub const DEPRECATED_CONSTANT: &str = "S5001";
+pub const CONSTRUCTOR_VISIBILITY: &str = "S5002";
+pub const INTERFACE_VIRTUAL: &str = "S5003";
+pub const MUTABILITY: &str = "S5004";
+pub const GASPRICE: &str = "S5005";
+pub const UNKNOWN_PRAGMA: &str = "S5006";
+pub const SHADOWING: &str = "S5007";
+pub const SHADOWS_BUILTIN: &str = "S5008";
+pub const ETHEREUM_UNITS: &str = "S5009";
+pub const UNKNOWN_WARNING: &str = "S5010";
+pub const STORAGE_RENAMED: &str = "S5011";
+
+pub struct Explanation {
+    pub code: &'static str,
+    pub summary: &'static str,
+    /// The category of a warning; errors have none
+    pub category: Option<WarningCategory>,
+    pub description: &'static str,
+}
+
+static EXPLANATIONS: &[Explanation] = &[
+    Explanation {
+        code: INFO,
+        summary: "informational message",
+        category: None,
+        description: "Messages at the info and debug level report what the compiler found or \
+did; they never cause compilation to fail. Debug messages are only printed with --verbose.",
+    },
+    Explanation {
+        code: PARSER_ERROR,
+        summary: "the source file could not be read or parsed",
+        category: None,
+        description: "The file does not exist, cannot be read, or the lexer found something \
+which is not valid Solidity, such as an unterminated string or comment, or a character \
+which is not allowed outside a string.",
+    },
+    Explanation {
+        code: INVALID_TOKEN,
+        summary: "invalid token",
+        category: None,
+        description: "The parser found a character sequence which is not a Solidity token. \
+Check for stray characters, or characters copied from a word processor such as curly quotes.",
+    },
+    Explanation {
+        code: UNRECOGNISED_TOKEN,
+        summary: "unrecognised token",
+        category: None,
+        description: "The token is valid Solidity, but it is not allowed at this position. The \
+message lists the tokens which the parser expected. A common cause is a missing semicolon \
+or closing bracket on the line before.",
+    },
+    Explanation {
+        code: EXTRA_TOKEN,
+        summary: "extra token",
+        category: None,
+        description: "The parser found a complete source unit, but there is more input after it. \
+This is usually caused by an extra closing brace.",
+    },
+    Explanation {
+        code: UNEXPECTED_EOF,
+        summary: "unexpected end of file",
+        category: None,
+        description: "The file ended while the parser expected more input, usually because a \
+brace or bracket is not closed.",
+    },
+    Explanation {
+        code: ERROR,
+        summary: "error",
+        category: None,
+        description: "The source is not valid Solidity, or uses a feature which is not \
+supported on the selected target. The message describes the problem; any notes point at the \
+related declarations.",
+    },
+    Explanation {
+        code: STRAY_SEMICOLON,
+        summary: "stray semicolon",
+        category: None,
+        description: "A semicolon was found at the top level of a file or contract, where \
+it has no meaning. Remove it.",
+    },
+    Explanation {
+        code: DIVIDE_BY_ZERO,
+        summary: "divide by zero",
+        category: None,
+        description: "The divisor of a division or modulo operation is known to be zero at \
+compile time, so the operation would always fail at runtime.
+
+    uint x = 10 / 0;",
+    },
+    Explanation {
+        code: STORAGE_LAYOUT,
+        summary: "storage layout is not compatible with the previous version",
+        category: None,
+        description: "An upgraded contract keeps the storage of the previous version, so every \
+state variable must stay in the same slot with the same type. Variables cannot be removed, \
+reordered, or have their type changed or made smaller, and no variables can be inserted \
+before existing ones, also not by adding a base contract. New variables must be added after \
+all existing variables of the most derived contract.",
+    },
+    Explanation {
+        code: LITERAL,
+        summary: "invalid literal",
+        category: None,
+        description: "A number, string, hex or address literal is not valid, or does not fit \
+the type it is used as. Check for invalid escape sequences in strings, an odd number of hex \
+digits, and the checksum of address literals; a wrong checksum often means a typo in the \
+address. Unit denominations like ‘ether’ can only follow decimal number literals.
+
+    bytes4 b = hex\"0102\";  // needs 8 hex digits",
+    },
+    Explanation {
+        code: CALL_ARGUMENTS,
+        summary: "call arguments not allowed",
+        category: None,
+        description: "Call arguments like ‘{value: 1, gas: 5000}’ can only be given for \
+external calls and contract creation, and only those which the target supports. Value can \
+only be sent to payable functions. Each call argument can be given once.
+
+    c.transfer{value: 1}(to);  // transfer must be declared payable",
+    },
+    Explanation {
+        code: WRONG_ARGUMENTS,
+        summary: "arguments do not match the parameters",
+        category: None,
+        description: "The number of arguments is not the number of parameters of the \
+function, constructor, event, struct or cast, or named arguments are missing, duplicated or \
+do not match a parameter name. For library functions used with ‘using for’, the value before \
+the dot is the first argument.",
+    },
+    Explanation {
+        code: NO_MATCHING_OVERLOAD,
+        summary: "no overload matches the arguments",
+        category: None,
+        description: "The function, constructor or event is overloaded, and none of the \
+overloads accepts the arguments, or more than one does and the call is ambiguous. The notes \
+point at the candidates; an explicit conversion of an argument usually selects one.",
+    },
+    Explanation {
+        code: CONSTANT_EXPRESSION,
+        summary: "not allowed in a constant expression",
+        category: None,
+        description: "Constants, array dimensions and other values which are evaluated at \
+compile time can only use literals, other constants and pure builtins like ‘keccak256’. \
+They cannot read variables, call functions, create contracts or assign.",
+    },
+    Explanation {
+        code: NOT_ASSIGNABLE,
+        summary: "cannot be assigned to",
+        category: None,
+        description: "The left hand side of an assignment, or the operand of ‘++’, ‘--’ or \
+‘delete’, must be a variable, struct field or array element which can be modified. \
+Constants, function calls and literals cannot be assigned to.",
+    },
+    Explanation {
+        code: STATEMENT_NOT_ALLOWED,
+        summary: "statement not allowed here",
+        category: None,
+        description: "The statement is not valid at this position: ‘break’ and ‘continue’ \
+must be in a loop, ‘_’ must be in a modifier and every modifier needs one, every path \
+through a function with return values must end in ‘return’, and destructuring needs as many \
+variables on the left as there are values on the right.",
+    },
+    Explanation {
+        code: FORMAT_STRING,
+        summary: "invalid format string",
+        category: None,
+        description: "String formatting with ‘\"...{}...\".format(args)’ needs a string \
+literal with one ‘{}’ for each argument. Braces are escaped by doubling them, and the \
+specifiers ‘{:x}’ and ‘{:b}’ are only allowed for integers.
+
+    print(\"x = {}\".format(x));",
+    },
+    Explanation {
+        code: TRY_CATCH,
+        summary: "invalid try or catch",
+        category: None,
+        description: "‘try’ can only be used with an external call or contract creation. The \
+returns list must match the return values of the function, ‘catch Error(string memory)’ \
+catches revert reasons, and ‘catch (bytes memory)’ catches everything else.",
+    },
+    Explanation {
+        code: CONTRACT_CREATION,
+        summary: "invalid ‘new’",
+        category: None,
+        description: "‘new’ creates a contract or allocates a dynamic array. A contract \
+cannot create itself, and contracts cannot create each other in a cycle, since the code of \
+the created contract is part of the creating contract. Dynamic arrays take a single unsigned \
+length argument.",
+    },
+    Explanation {
+        code: TARGET_NOT_SUPPORTED,
+        summary: "not supported on this target",
+        category: None,
+        description: "The feature is valid Solidity, but not available on the selected \
+target, such as address literals in another chain's format, the ‘salt’ or ‘gas’ call \
+arguments, or a construct which the EVM backend cannot compile yet. Select another target \
+with --target, or avoid the feature.",
+    },
+    Explanation {
+        code: OUT_OF_RANGE,
+        summary: "value out of range",
+        category: None,
+        description: "A value which is known at compile time is out of range for how it is \
+used, like a shift by more bits than the type has, a negative exponent, a conversion to an \
+enum value which does not exist, or an array size which is zero or negative.",
+    },
+    Explanation {
+        code: DOC_TAG,
+        summary: "invalid documentation tag",
+        category: None,
+        description: "A NatSpec tag in a doc comment is not valid for the declaration it \
+documents: ‘@param’ and ‘@return’ must name an existing parameter or return value and \
+appear once for each, and ‘@inheritdoc’ must name a base contract.",
+    },
+    Explanation {
+        code: STATE_MUTABILITY,
+        summary: "function mutability violated",
+        category: None,
+        description: "A function declared ‘view’ cannot write to state, and a function \
+declared ‘pure’ cannot read or write state. State includes contract storage, balances and \
+the environment, like ‘block.number’. Remove the ‘view’ or ‘pure’, or change the function.",
+    },
+    Explanation {
+        code: ABI_ENCODING,
+        summary: "type cannot be ABI encoded",
+        category: None,
+        description: "Public and external functions, and the ‘abi.encode’ and ‘abi.decode’ \
+builtins, can only use types which can be ABI encoded. Mappings, storage references and \
+internal function types cannot be encoded; structs and nested arrays need \
+‘pragma abicoder v2’.",
+    },
+    Explanation {
+        code: PRAGMA_SOLIDITY,
+        summary: "invalid solidity version pragma",
+        category: None,
+        description: "The ‘pragma solidity’ version requirement could not be parsed, or it \
+does not match any version of Solidity which Solang implements.
+
+    pragma solidity ^0.8.0;",
+    },
+    Explanation {
+        code: ARRAY,
+        summary: "invalid array operation",
+        category: None,
+        description: "Array literals need at least one element, and all elements must have \
+the same type and length. Subscripts must be unsigned integers, and ‘push()’ and ‘pop()’ \
+are only available on dynamic arrays.",
+    },
+    Explanation {
+        code: DECLARATION_ERROR,
+        summary: "declaration error",
+        category: None,
+        description: "A declaration is not valid, for example because the name is already in \
+use, or the declaration is not allowed in this context.",
+    },
+    Explanation {
+        code: NOT_FOUND,
+        summary: "name not found",
+        category: None,
+        description: "The name does not refer to any declaration which is visible here. Check \
+the spelling, and that the file which declares it is imported. Variables declared in a \
+block are not visible outside that block.",
+    },
+    Explanation {
+        code: ALREADY_DEFINED,
+        summary: "already defined",
+        category: None,
+        description: "The name is already declared in the same scope, or the attribute is \
+given more than once. The note points at the previous declaration. Functions can be \
+overloaded, but only with different parameter types.",
+    },
+    Explanation {
+        code: WRONG_KIND_OF_NAME,
+        summary: "name refers to the wrong kind of declaration",
+        category: None,
+        description: "The name is declared, but as something which cannot be used here, \
+such as a function where a type is expected, or a variable where an event is expected.",
+    },
+    Explanation {
+        code: OVERRIDE,
+        summary: "invalid override",
+        category: None,
+        description: "A function which overrides a function from a base contract must be \
+declared ‘override’, and list the bases in ‘override(A, B)’ if it overrides functions from \
+more than one. Only ‘virtual’ functions can be overridden, and the parameters and return \
+values must stay the same.
+
+    function f() public override(A, B) {}",
+    },
+    Explanation {
+        code: INHERITANCE,
+        summary: "invalid base contract",
+        category: None,
+        description: "The base contracts are not valid: a contract cannot inherit from \
+itself, directly or through a cycle, list a base twice, or inherit from a library. \
+Interfaces can only inherit from interfaces. Arguments to a base constructor can be given \
+once, either in the contract declaration or in the constructor.",
+    },
+    Explanation {
+        code: FUNCTION_DECLARATION,
+        summary: "invalid function declaration",
+        category: None,
+        description: "The attributes of the function are not valid. For example, functions \
+in contracts need a visibility, constructors cannot be ‘view’ or ‘pure’, ‘receive’ must be \
+‘external payable’ without parameters, and a contract with functions without a body must be \
+declared ‘abstract’.",
+    },
+    Explanation {
+        code: CONTRACT_KIND,
+        summary: "not allowed in this kind of contract",
+        category: None,
+        description: "Interfaces cannot have state variables, constructors or function \
+bodies, and their functions must be ‘external’. Libraries cannot have state variables \
+other than constants, and their functions cannot be payable or virtual.",
+    },
+    Explanation {
+        code: DATA_LOCATION,
+        summary: "invalid data location",
+        category: None,
+        description: "‘storage’, ‘memory’ and ‘calldata’ can only be given for reference \
+types like arrays, structs and mappings, and only where the location is not fixed; for \
+example struct fields have no data location of their own.",
+    },
+    Explanation {
+        code: VARIABLE_DECLARATION,
+        summary: "invalid variable declaration",
+        category: None,
+        description: "The attributes of the variable are not valid. Variables outside \
+contracts must be constants and have no visibility, constants need an initializer, and \
+variables cannot be ‘external’.",
+    },
+    Explanation {
+        code: TYPE_DECLARATION,
+        summary: "invalid struct, event or enum",
+        category: None,
+        description: "Structs, events and enums need at least one field or value. A struct \
+cannot contain itself except through a mapping or dynamic array, since it would be \
+infinitely large. Events can have at most three indexed fields, or four if anonymous.",
+    },
+    Explanation {
+        code: NOT_ACCESSIBLE,
+        summary: "not accessible from here",
+        category: None,
+        description: "The declaration exists but cannot be used from here, like a private \
+function from a derived contract, an internal function through an external call, or \
+‘this’ and ‘super’ outside a contract.",
+    },
+    Explanation {
+        code: IMPORT,
+        summary: "import not found",
+        category: None,
+        description: "The imported file could not be found or read. Imports are searched \
+relative to the importing file, and in the import paths given with --importpath or in the \
+project manifest, after applying any remappings.",
+    },
+    Explanation {
+        code: TYPE_ERROR,
+        summary: "type error",
+        category: None,
+        description: "The types in an expression or declaration do not match. Solidity only \
+converts between types implicitly when no information can be lost; otherwise an explicit \
+conversion such as uint8(x) is needed.",
+    },
+    Explanation {
+        code: IMPLICIT_CONVERSION,
+        summary: "implicit conversion not allowed",
+        category: None,
+        description: "The value would be converted implicitly to another type, but that \
+could lose information: it may truncate, change the sign, or convert between unrelated \
+types. Use an explicit conversion if this is intended.
+
+    int64 a;
+    int32 b = a;         // error
+    int32 c = int32(a);  // truncates explicitly",
+    },
+    Explanation {
+        code: EXPLICIT_CONVERSION,
+        summary: "conversion not possible",
+        category: None,
+        description: "There is no conversion between these types, not even an explicit \
+one. Some conversions need an intermediate step, such as converting a contract to \
+‘address’ first; function types can only be converted when their parameters and returns \
+are the same.",
+    },
+    Explanation {
+        code: MAPPING_TYPE,
+        summary: "invalid use of a mapping",
+        category: None,
+        description: "Mappings can only exist in contract storage. Their keys cannot be \
+mappings, arrays or structs, and they cannot be deleted, copied to memory or used as \
+event fields. Parameters and return values which are mappings must be storage references.",
+    },
+    Explanation {
+        code: RETURN_VALUES,
+        summary: "return values do not match",
+        category: None,
+        description: "The number or types of the values returned do not match the \
+declaration of the function, or an expression which returns no value or more than one \
+value is used where a single value is expected.",
+    },
+    Explanation {
+        code: TYPE_NOT_ALLOWED,
+        summary: "type not allowed here",
+        category: None,
+        description: "A value of this type cannot be used in this position, for example a \
+struct or enum as the operand of an arithmetic operator, or a signed integer with ‘**’.",
+    },
+    Explanation {
+        code: WARNING,
+        summary: "warning",
+        category: Some(WarningCategory::Other),
+        description: "The source is valid, but probably does not do what was intended.",
+    },
+    Explanation {
+        code: DEPRECATED_CONSTANT,
+        summary: "‘constant’ is deprecated for functions",
+        category: Some(WarningCategory::Deprecated),
+        description: "Older versions of Solidity used ‘constant’ for functions which do not \
+modify state. Use ‘view’ instead.
+
+    function get() public constant returns (uint)  // deprecated
+    function get() public view returns (uint)",
+    },
+    Explanation {
+        code: CONSTRUCTOR_VISIBILITY,
+        summary: "visibility for constructors is ignored",
+        category: Some(WarningCategory::Redundant),
+        description: "Constructors are always called when the contract is deployed, so a \
+visibility such as ‘public’ or ‘internal’ has no effect. Remove the visibility; to prevent a \
+contract from being deployed, declare it ‘abstract’.",
+    },
+    Explanation {
+        code: INTERFACE_VIRTUAL,
+        summary: "functions in an interface are implicitly virtual",
+        category: Some(WarningCategory::Redundant),
+        description: "Every function in an interface must be implemented, so it is always \
+virtual. The ‘virtual’ keyword can be removed.",
+    },
+    Explanation {
+        code: MUTABILITY,
+        summary: "function mutability can be restricted",
+        category: Some(WarningCategory::Mutability),
+        description: "The function does not modify state, or does not read it either, so it \
+can be declared ‘view’ or ‘pure’. Callers can then call it without a transaction, and the \
+compiler checks that later changes do not modify state by accident.",
+    },
+    Explanation {
+        code: GASPRICE,
+        summary: "gas price may round down to zero",
+        category: Some(WarningCategory::Target),
+        description: "On Substrate, the price of a single unit of gas is usually less than \
+one, so ‘tx.gasprice(1)’ rounds down to zero. Ask for the price of a larger amount of gas.",
+    },
+    Explanation {
+        code: UNKNOWN_PRAGMA,
+        summary: "unknown pragma",
+        category: Some(WarningCategory::Pragma),
+        description: "The pragma is not known to Solang and has no effect.",
+    },
+    Explanation {
+        code: SHADOWING,
+        summary: "declaration shadows another declaration",
+        category: Some(WarningCategory::Shadowing),
+        description: "The name is already declared in an enclosing scope, for example as a \
+state variable, contract or import. Inside this declaration, the name refers to the new \
+declaration only, which is easily confused. Rename one of the declarations.",
+    },
+    Explanation {
+        code: SHADOWS_BUILTIN,
+        summary: "declaration shadows a builtin",
+        category: Some(WarningCategory::Shadowing),
+        description: "The name is also the name of a builtin function or variable, such as \
+‘msg’ or ‘assert’, which can no longer be used in this scope.",
+    },
+    Explanation {
+        code: ETHEREUM_UNITS,
+        summary: "ethereum currency unit used while not targetting ethereum",
+        category: Some(WarningCategory::Target),
+        description: "Units such as ‘ether’ and ‘gwei’ multiply by powers of ten which match \
+the ethereum currency. Other chains have different currencies and denominations, so the \
+value is probably not what was intended.",
+    },
+    Explanation {
+        code: UNKNOWN_WARNING,
+        summary: "unknown warning in suppression comment",
+        category: Some(WarningCategory::Other),
+        description: "A ‘solang-disable-line’ or ‘solang-disable-next-line’ comment lists \
+warnings which are not known. Warnings are given by code, like S5004, or by category: \
+deprecated, mutability, pragma, redundant, shadowing, target or other.",
+    },
+    Explanation {
+        code: STORAGE_RENAMED,
+        summary: "state variable renamed in upgrade",
+        category: Some(WarningCategory::Other),
+        description: "A state variable has a different name than in the previous version, but \
+the same slot and type. The upgraded contract reads the existing value under the new name; \
+check that this is intended, and that the variable was not removed and another one added \
+in its place.",
+    },
+];
+
+/// Find the explanation for a code; the code is case insensitive
+pub fn explain(code: &str) -> Option<&'static Explanation> {
+    EXPLANATIONS
+        .iter()
+        .find(|explanation| explanation.code.eq_ignore_ascii_case(code))
+}
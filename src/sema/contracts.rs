@@ -7,6 +7,7 @@ use std::collections::HashSet;
 use std::convert::TryInto;
 use tiny_keccak::{Hasher, Keccak};
 
+use super::codes;
 use super::expression::match_constructor_to_args;
 use super::functions;
 use super::statements;
@@ -52,6 +53,11 @@ impl ast::Contract {
         emit::Binary::build(context, self, ns, filename, opt, math_overflow_check)
     }
 
+    /// Generate the C header for using this contract with the generic target
+    pub fn c_header(&self, ns: &ast::Namespace) -> String {
+        emit::generic::c_header(self, ns)
+    }
+
     /// Print the entire contract; storage initializers, constructors and functions and their CFGs
     pub fn print_cfg(&self, ns: &ast::Namespace) -> String {
         let mut out = format!("#\n# Contract: {}\n#\n\n", self.name);
@@ -147,66 +153,84 @@ fn resolve_base_contracts(
     for (contract_no, def) in contracts {
         for base in &def.base {
             if ns.contracts[*contract_no].is_library() {
-                ns.diagnostics.push(ast::Diagnostic::error(
-                    base.loc,
-                    format!(
-                        "library ‘{}’ cannot have a base contract",
-                        ns.contracts[*contract_no].name
-                    ),
-                ));
+                ns.diagnostics.push(
+                    ast::Diagnostic::error(
+                        base.loc,
+                        format!(
+                            "library ‘{}’ cannot have a base contract",
+                            ns.contracts[*contract_no].name
+                        ),
+                    )
+                    .with_code(codes::INHERITANCE),
+                );
                 continue;
             }
             let name = &base.name;
             match ns.resolve_contract(file_no, name) {
                 Some(no) => {
                     if no == *contract_no {
-                        ns.diagnostics.push(ast::Diagnostic::error(
-                            name.loc,
-                            format!(
-                                "contract ‘{}’ cannot have itself as a base contract",
-                                name.name
-                            ),
-                        ));
+                        ns.diagnostics.push(
+                            ast::Diagnostic::error(
+                                name.loc,
+                                format!(
+                                    "contract ‘{}’ cannot have itself as a base contract",
+                                    name.name
+                                ),
+                            )
+                            .with_code(codes::INHERITANCE),
+                        );
                     } else if ns.contracts[*contract_no]
                         .bases
                         .iter()
                         .any(|e| e.contract_no == no)
                     {
-                        ns.diagnostics.push(ast::Diagnostic::error(
-                            name.loc,
-                            format!(
-                                "contract ‘{}’ duplicate base ‘{}’",
-                                ns.contracts[*contract_no].name, name.name
-                            ),
-                        ));
+                        ns.diagnostics.push(
+                            ast::Diagnostic::error(
+                                name.loc,
+                                format!(
+                                    "contract ‘{}’ duplicate base ‘{}’",
+                                    ns.contracts[*contract_no].name, name.name
+                                ),
+                            )
+                            .with_code(codes::INHERITANCE),
+                        );
                     } else if is_base(*contract_no, no, ns) {
-                        ns.diagnostics.push(ast::Diagnostic::error(
-                            name.loc,
-                            format!(
-                                "base ‘{}’ from contract ‘{}’ is cyclic",
-                                name.name, ns.contracts[*contract_no].name
-                            ),
-                        ));
+                        ns.diagnostics.push(
+                            ast::Diagnostic::error(
+                                name.loc,
+                                format!(
+                                    "base ‘{}’ from contract ‘{}’ is cyclic",
+                                    name.name, ns.contracts[*contract_no].name
+                                ),
+                            )
+                            .with_code(codes::INHERITANCE),
+                        );
                     } else if ns.contracts[*contract_no].is_interface()
                         && !ns.contracts[no].is_interface()
                     {
-                        ns.diagnostics.push(ast::Diagnostic::error(
-                            name.loc,
-                            format!(
-                                "interface ‘{}’ cannot have {} ‘{}’ as a base",
-                                ns.contracts[*contract_no].name, ns.contracts[no].ty, name.name
-                            ),
-                        ));
+                        ns.diagnostics.push(
+                            ast::Diagnostic::error(
+                                name.loc,
+                                format!(
+                                    "interface ‘{}’ cannot have {} ‘{}’ as a base",
+                                    ns.contracts[*contract_no].name, ns.contracts[no].ty, name.name
+                                ),
+                            )
+                            .with_code(codes::INHERITANCE),
+                        );
                     } else if ns.contracts[no].is_library() {
                         let contract = &ns.contracts[*contract_no];
 
-                        ns.diagnostics.push(ast::Diagnostic::error(
-                            name.loc,
-                            format!(
-                                "library ‘{}’ cannot be used as base contract for {} ‘{}’",
-                                name.name, contract.ty, contract.name,
-                            ),
-                        ));
+                        ns.diagnostics.push(
+                            ast::Diagnostic::error(
+                                name.loc,
+                                format!(
+                                    "library ‘{}’ cannot be used as base contract for {} ‘{}’",
+                                    name.name, contract.ty, contract.name,
+                                ),
+                            )
+                            .with_code(codes::INHERITANCE),
+                        );
                     } else {
                         // We do not resolve the constructor arguments here, since we have not
                         // resolved any variables. This means no constants can be used on base
@@ -219,10 +243,13 @@ fn resolve_base_contracts(
                     }
                 }
                 None => {
-                    ns.diagnostics.push(ast::Diagnostic::error(
-                        name.loc,
-                        format!("contract ‘{}’ not found", name.name),
-                    ));
+                    ns.diagnostics.push(
+                        ast::Diagnostic::error(
+                            name.loc,
+                            format!("contract ‘{}’ not found", name.name),
+                        )
+                        .with_code(codes::NOT_FOUND),
+                    );
                 }
             }
         }
@@ -348,12 +375,15 @@ fn layout_contract(contract_no: usize, ns: &mut ast::Namespace) {
                         || sym.has_accessor(ns)
                         || prev.is_event() && sym.is_event())
                     {
-                        ns.diagnostics.push(ast::Diagnostic::error_with_note(
-                            *sym.loc(),
-                            format!("already defined ‘{}’", name),
-                            *prev.loc(),
-                            format!("previous definition of ‘{}’", name),
-                        ));
+                        ns.diagnostics.push(
+                            ast::Diagnostic::error_with_note(
+                                *sym.loc(),
+                                format!("already defined ‘{}’", name),
+                                *prev.loc(),
+                                format!("previous definition of ‘{}’", name),
+                            )
+                            .with_code(codes::ALREADY_DEFINED),
+                        );
                     }
                 }
             }
@@ -424,14 +454,30 @@ fn layout_contract(contract_no: usize, ns: &mut ast::Namespace) {
                     .collect::<Vec<ast::Note>>();
 
                 if !non_virtual.is_empty() {
-                    ns.diagnostics.push(ast::Diagnostic::error_with_notes(
+                    let edits: Vec<_> = entry
+                        .iter()
+                        .map(|(_, function_no)| &ns.functions[*function_no])
+                        .filter(|func| !func.is_virtual)
+                        .filter_map(|func| func.attribute_insert_loc())
+                        .map(|loc| (loc, String::from(" virtual")))
+                        .collect();
+
+                    let mut diagnostic = ast::Diagnostic::error_with_notes(
                         cur.loc,
                         format!(
                             "function ‘{}’ overrides functions which are not ‘virtual’",
                             cur.name
                         ),
                         non_virtual,
-                    ));
+                    )
+                    .with_code(codes::OVERRIDE);
+
+                    if !edits.is_empty() {
+                        diagnostic =
+                            diagnostic.with_fix(String::from("declare functions ‘virtual’"), edits);
+                    }
+
+                    ns.diagnostics.push(diagnostic);
                 }
 
                 let source_override = entry
@@ -440,15 +486,23 @@ fn layout_contract(contract_no: usize, ns: &mut ast::Namespace) {
                     .collect::<Vec<&str>>()
                     .join(",");
 
+                let override_fix = format!("specify ‘override({})’", source_override);
+
                 if let Some((loc, override_specified)) = &cur.is_override {
+                    let edits = vec![(*loc, format!("override({})", source_override))];
+
                     if override_specified.is_empty() && entry.len() > 1 {
-                        ns.diagnostics.push(ast::Diagnostic::error(
-                            *loc,
-                            format!(
-                                "function ‘{}’ should specify override list ‘override({})’",
-                                cur.name, source_override
-                            ),
-                        ));
+                        ns.diagnostics.push(
+                            ast::Diagnostic::error(
+                                *loc,
+                                format!(
+                                    "function ‘{}’ should specify override list ‘override({})’",
+                                    cur.name, source_override
+                                ),
+                            )
+                            .with_code(codes::OVERRIDE)
+                            .with_fix(override_fix, edits),
+                        );
                     } else {
                         let override_specified: HashSet<usize> =
                             override_specified.iter().cloned().collect();
@@ -462,15 +516,18 @@ fn layout_contract(contract_no: usize, ns: &mut ast::Namespace) {
                             .collect();
 
                         if !missing.is_empty() && override_needed.len() >= 2 {
-                            ns.diagnostics.push(ast::Diagnostic::error(
-                                *loc,
-                                format!(
-                                    "function ‘{}’ missing overrides ‘{}’, specify ‘override({})’",
-                                    cur.name,
-                                    missing.join(","),
-                                    source_override
-                                ),
-                            ));
+                            ns.diagnostics.push(
+                                ast::Diagnostic::error(
+                                    *loc,
+                                    format!(
+                                        "function ‘{}’ missing overrides ‘{}’, specify ‘override({})’",
+                                        cur.name,
+                                        missing.join(","),
+                                        source_override
+                                    ),
+                                ).with_code(codes::OVERRIDE)
+                                .with_fix(override_fix.clone(), edits.clone()),
+                            );
                         }
 
                         // List of contract which should not have been specified
@@ -480,15 +537,18 @@ fn layout_contract(contract_no: usize, ns: &mut ast::Namespace) {
                             .collect();
 
                         if !extra.is_empty() {
-                            ns.diagnostics.push(ast::Diagnostic::error(
-                                *loc,
-                                format!(
-                                    "function ‘{}’ includes extraneous overrides ‘{}’, specify ‘override({})’",
-                                    cur.name,
-                                    extra.join(","),
-                                    source_override
-                                ),
-                            ));
+                            ns.diagnostics.push(
+                                ast::Diagnostic::error(
+                                    *loc,
+                                    format!(
+                                        "function ‘{}’ includes extraneous overrides ‘{}’, specify ‘override({})’",
+                                        cur.name,
+                                        extra.join(","),
+                                        source_override
+                                    ),
+                                ).with_code(codes::OVERRIDE)
+                                .with_fix(override_fix, edits),
+                            );
                         }
                     }
 
@@ -500,23 +560,43 @@ fn layout_contract(contract_no: usize, ns: &mut ast::Namespace) {
                     // not specify override for implementing interfaces. As a compromise, only require override when
                     // not implementing an interface
                     if !ns.contracts[entry[0].0].is_interface() {
-                        ns.diagnostics.push(ast::Diagnostic::error(
+                        let mut diagnostic = ast::Diagnostic::error(
                             cur.loc,
                             format!("function ‘{}’ should specify ‘override’", cur.name),
-                        ));
+                        )
+                        .with_code(codes::OVERRIDE);
+
+                        if let Some(loc) = cur.attribute_insert_loc() {
+                            diagnostic = diagnostic.with_fix(
+                                String::from("specify ‘override’"),
+                                vec![(loc, String::from(" override"))],
+                            );
+                        }
+
+                        ns.diagnostics.push(diagnostic);
                     }
 
                     // FIXME: check override visibility/mutability
 
                     override_needed.remove(&signature);
                 } else {
-                    ns.diagnostics.push(ast::Diagnostic::error(
+                    let mut diagnostic = ast::Diagnostic::error(
                         cur.loc,
                         format!(
                             "function ‘{}’ should specify override list ‘override({})’",
                             cur.name, source_override
                         ),
-                    ));
+                    )
+                    .with_code(codes::OVERRIDE);
+
+                    if let Some(loc) = cur.attribute_insert_loc() {
+                        diagnostic = diagnostic.with_fix(
+                            override_fix,
+                            vec![(loc, format!(" override({})", source_override))],
+                        );
+                    }
+
+                    ns.diagnostics.push(diagnostic);
                 }
             } else {
                 let previous_defs = ns.contracts[contract_no]
@@ -531,10 +611,13 @@ fn layout_contract(contract_no: usize, ns: &mut ast::Namespace) {
                     .collect::<Vec<usize>>();
 
                 if previous_defs.is_empty() && cur.is_override.is_some() {
-                    ns.diagnostics.push(ast::Diagnostic::error(
-                        cur.loc,
-                        format!("function ‘{}’ does not override anything", cur.name),
-                    ));
+                    ns.diagnostics.push(
+                        ast::Diagnostic::error(
+                            cur.loc,
+                            format!("function ‘{}’ does not override anything", cur.name),
+                        )
+                        .with_code(codes::OVERRIDE),
+                    );
                     continue;
                 }
 
@@ -549,26 +632,32 @@ fn layout_contract(contract_no: usize, ns: &mut ast::Namespace) {
                     let func_prev = &ns.functions[prev];
 
                     if Some(base_contract_no) == func_prev.contract_no {
-                        ns.diagnostics.push(ast::Diagnostic::error_with_note(
-                            cur.loc,
-                            format!(
-                                "function ‘{}’ overrides function in same contract",
-                                cur.name
-                            ),
-                            func_prev.loc,
-                            format!("previous definition of ‘{}’", func_prev.name),
-                        ));
+                        ns.diagnostics.push(
+                            ast::Diagnostic::error_with_note(
+                                cur.loc,
+                                format!(
+                                    "function ‘{}’ overrides function in same contract",
+                                    cur.name
+                                ),
+                                func_prev.loc,
+                                format!("previous definition of ‘{}’", func_prev.name),
+                            )
+                            .with_code(codes::OVERRIDE),
+                        );
 
                         continue;
                     }
 
                     if func_prev.ty != cur.ty {
-                        ns.diagnostics.push(ast::Diagnostic::error_with_note(
-                            cur.loc,
-                            format!("{} ‘{}’ overrides {}", cur.ty, cur.name, func_prev.ty,),
-                            func_prev.loc,
-                            format!("previous definition of ‘{}’", func_prev.name),
-                        ));
+                        ns.diagnostics.push(
+                            ast::Diagnostic::error_with_note(
+                                cur.loc,
+                                format!("{} ‘{}’ overrides {}", cur.ty, cur.name, func_prev.ty,),
+                                func_prev.loc,
+                                format!("previous definition of ‘{}’", func_prev.name),
+                            )
+                            .with_code(codes::OVERRIDE),
+                        );
 
                         continue;
                     }
@@ -579,15 +668,18 @@ fn layout_contract(contract_no: usize, ns: &mut ast::Namespace) {
                         .zip(cur.params.iter())
                         .any(|(a, b)| a.ty != b.ty)
                     {
-                        ns.diagnostics.push(ast::Diagnostic::error_with_note(
-                            cur.loc,
-                            format!(
-                                "{} ‘{}’ overrides {} with different argument types",
-                                cur.ty, cur.name, func_prev.ty,
-                            ),
-                            func_prev.loc,
-                            format!("previous definition of ‘{}’", func_prev.name),
-                        ));
+                        ns.diagnostics.push(
+                            ast::Diagnostic::error_with_note(
+                                cur.loc,
+                                format!(
+                                    "{} ‘{}’ overrides {} with different argument types",
+                                    cur.ty, cur.name, func_prev.ty,
+                                ),
+                                func_prev.loc,
+                                format!("previous definition of ‘{}’", func_prev.name),
+                            )
+                            .with_code(codes::OVERRIDE),
+                        );
 
                         continue;
                     }
@@ -598,15 +690,18 @@ fn layout_contract(contract_no: usize, ns: &mut ast::Namespace) {
                         .zip(cur.returns.iter())
                         .any(|(a, b)| a.ty != b.ty)
                     {
-                        ns.diagnostics.push(ast::Diagnostic::error_with_note(
-                            cur.loc,
-                            format!(
-                                "{} ‘{}’ overrides {} with different return types",
-                                cur.ty, cur.name, func_prev.ty,
-                            ),
-                            func_prev.loc,
-                            format!("previous definition of ‘{}’", func_prev.name),
-                        ));
+                        ns.diagnostics.push(
+                            ast::Diagnostic::error_with_note(
+                                cur.loc,
+                                format!(
+                                    "{} ‘{}’ overrides {} with different return types",
+                                    cur.ty, cur.name, func_prev.ty,
+                                ),
+                                func_prev.loc,
+                                format!("previous definition of ‘{}’", func_prev.name),
+                            )
+                            .with_code(codes::OVERRIDE),
+                        );
 
                         continue;
                     }
@@ -616,29 +711,35 @@ fn layout_contract(contract_no: usize, ns: &mut ast::Namespace) {
 
                     if let Some((loc, override_list)) = &cur.is_override {
                         if !func_prev.is_virtual {
-                            ns.diagnostics.push(ast::Diagnostic::error_with_note(
-                                cur.loc,
-                                format!(
-                                    "function ‘{}’ overrides function which is not virtual",
-                                    cur.name
-                                ),
-                                func_prev.loc,
-                                format!("previous definition of function ‘{}’", func_prev.name),
-                            ));
+                            ns.diagnostics.push(
+                                ast::Diagnostic::error_with_note(
+                                    cur.loc,
+                                    format!(
+                                        "function ‘{}’ overrides function which is not virtual",
+                                        cur.name
+                                    ),
+                                    func_prev.loc,
+                                    format!("previous definition of function ‘{}’", func_prev.name),
+                                )
+                                .with_code(codes::OVERRIDE),
+                            );
 
                             continue;
                         }
 
                         if !override_list.is_empty() && !override_list.contains(&prev_contract_no) {
-                            ns.diagnostics.push(ast::Diagnostic::error_with_note(
-                                *loc,
-                                format!(
-                                    "function ‘{}’ override list does not contain ‘{}’",
-                                    cur.name, ns.contracts[prev_contract_no].name
-                                ),
-                                func_prev.loc,
-                                format!("previous definition of function ‘{}’", func_prev.name),
-                            ));
+                            ns.diagnostics.push(
+                                ast::Diagnostic::error_with_note(
+                                    *loc,
+                                    format!(
+                                        "function ‘{}’ override list does not contain ‘{}’",
+                                        cur.name, ns.contracts[prev_contract_no].name
+                                    ),
+                                    func_prev.loc,
+                                    format!("previous definition of function ‘{}’", func_prev.name),
+                                )
+                                .with_code(codes::OVERRIDE),
+                            );
                             continue;
                         }
                     } else if cur.has_body {
@@ -682,25 +783,31 @@ fn layout_contract(contract_no: usize, ns: &mut ast::Namespace) {
             let loc = ns.contracts[contract_no].loc;
             match func.ty {
                 pt::FunctionTy::Fallback | pt::FunctionTy::Receive => {
-                    ns.diagnostics.push(ast::Diagnostic::error_with_note(
+                    ns.diagnostics.push(
+                        ast::Diagnostic::error_with_note(
+                            loc,
+                            format!(
+                                "contract ‘{}’ missing override for ‘{}’ function",
+                                ns.contracts[contract_no].name, func.ty
+                            ),
+                            func.loc,
+                            format!("declaration of ‘{}’ function", func.ty),
+                        )
+                        .with_code(codes::OVERRIDE),
+                    );
+                }
+                _ => ns.diagnostics.push(
+                    ast::Diagnostic::error_with_note(
                         loc,
                         format!(
-                            "contract ‘{}’ missing override for ‘{}’ function",
-                            ns.contracts[contract_no].name, func.ty
+                            "contract ‘{}’ missing override for function ‘{}’",
+                            ns.contracts[contract_no].name, func.name
                         ),
                         func.loc,
-                        format!("declaration of ‘{}’ function", func.ty),
-                    ));
-                }
-                _ => ns.diagnostics.push(ast::Diagnostic::error_with_note(
-                    loc,
-                    format!(
-                        "contract ‘{}’ missing override for function ‘{}’",
-                        ns.contracts[contract_no].name, func.name
-                    ),
-                    func.loc,
-                    format!("declaration of function ‘{}’", func.name),
-                )),
+                        format!("declaration of function ‘{}’", func.name),
+                    )
+                    .with_code(codes::OVERRIDE),
+                ),
             }
 
             continue;
@@ -719,14 +826,17 @@ fn layout_contract(contract_no: usize, ns: &mut ast::Namespace) {
             })
             .collect();
 
-        ns.diagnostics.push(ast::Diagnostic::error_with_notes(
-            func.loc,
-            format!(
-                "function ‘{}’ with this signature already defined",
-                func.name
-            ),
-            notes,
-        ));
+        ns.diagnostics.push(
+            ast::Diagnostic::error_with_notes(
+                func.loc,
+                format!(
+                    "function ‘{}’ with this signature already defined",
+                    func.name
+                ),
+                notes,
+            )
+            .with_code(codes::ALREADY_DEFINED),
+        );
     }
 }
 
@@ -781,7 +891,7 @@ fn resolve_declarations<'a>(
                         notes.len()
                     ),
                     notes,
-                ));
+                ).with_code(codes::FUNCTION_DECLARATION));
         }
     }
 
@@ -802,13 +912,16 @@ fn resolve_using(
             if let pt::ContractPart::Using(using) = part {
                 if let Some(library_no) = ns.resolve_contract(file_no, &using.library) {
                     if !ns.contracts[library_no].is_library() {
-                        ns.diagnostics.push(ast::Diagnostic::error(
-                            using.library.loc,
-                            format!(
-                                "library expected but {} ‘{}’ found",
-                                ns.contracts[library_no].ty, using.library.name
-                            ),
-                        ));
+                        ns.diagnostics.push(
+                            ast::Diagnostic::error(
+                                using.library.loc,
+                                format!(
+                                    "library expected but {} ‘{}’ found",
+                                    ns.contracts[library_no].ty, using.library.name
+                                ),
+                            )
+                            .with_code(codes::WRONG_KIND_OF_NAME),
+                        );
 
                         continue;
                     }
@@ -824,13 +937,16 @@ fn resolve_using(
                             &mut diagnostics,
                         ) {
                             Ok(ast::Type::Contract(contract_no)) => {
-                                ns.diagnostics.push(ast::Diagnostic::error(
-                                    using.library.loc,
-                                    format!(
-                                        "using library ‘{}’ to extend {} type not possible",
-                                        using.library.name, ns.contracts[contract_no].ty
-                                    ),
-                                ));
+                                ns.diagnostics.push(
+                                    ast::Diagnostic::error(
+                                        using.library.loc,
+                                        format!(
+                                            "using library ‘{}’ to extend {} type not possible",
+                                            using.library.name, ns.contracts[contract_no].ty
+                                        ),
+                                    )
+                                    .with_code(codes::TYPE_NOT_ALLOWED),
+                                );
                                 continue;
                             }
                             Ok(ty) => Some(ty),
@@ -845,10 +961,13 @@ fn resolve_using(
 
                     ns.contracts[*contract_no].using.push((library_no, ty));
                 } else {
-                    ns.diagnostics.push(ast::Diagnostic::error(
-                        using.library.loc,
-                        format!("library ‘{}’ not found", using.library.name),
-                    ));
+                    ns.diagnostics.push(
+                        ast::Diagnostic::error(
+                            using.library.loc,
+                            format!("library ‘{}’ not found", using.library.name),
+                        )
+                        .with_code(codes::NOT_FOUND),
+                    );
                 }
             }
         }
@@ -897,18 +1016,21 @@ pub fn collect_base_args<'a>(
 
         for (base_no, (loc, constructor_no, args)) in &constructor.bases {
             if let Some(prev_args) = base_args.get(base_no) {
-                diagnostics.insert(ast::Diagnostic::error_with_note(
-                    *loc,
-                    format!(
-                        "duplicate argument for base contract ‘{}’",
-                        ns.contracts[*base_no].name
-                    ),
-                    *prev_args.loc,
-                    format!(
-                        "previous argument for base contract ‘{}’",
-                        ns.contracts[*base_no].name
-                    ),
-                ));
+                diagnostics.insert(
+                    ast::Diagnostic::error_with_note(
+                        *loc,
+                        format!(
+                            "duplicate argument for base contract ‘{}’",
+                            ns.contracts[*base_no].name
+                        ),
+                        *prev_args.loc,
+                        format!(
+                            "previous argument for base contract ‘{}’",
+                            ns.contracts[*base_no].name
+                        ),
+                    )
+                    .with_code(codes::INHERITANCE),
+                );
             } else {
                 base_args.insert(
                     *base_no,
@@ -928,18 +1050,21 @@ pub fn collect_base_args<'a>(
     for base in &contract.bases {
         if let Some((constructor_no, args)) = &base.constructor {
             if let Some(prev_args) = base_args.get(&base.contract_no) {
-                diagnostics.insert(ast::Diagnostic::error_with_note(
-                    base.loc,
-                    format!(
-                        "duplicate argument for base contract ‘{}’",
-                        ns.contracts[base.contract_no].name
-                    ),
-                    *prev_args.loc,
-                    format!(
-                        "previous argument for base contract ‘{}’",
-                        ns.contracts[base.contract_no].name
-                    ),
-                ));
+                diagnostics.insert(
+                    ast::Diagnostic::error_with_note(
+                        base.loc,
+                        format!(
+                            "duplicate argument for base contract ‘{}’",
+                            ns.contracts[base.contract_no].name
+                        ),
+                        *prev_args.loc,
+                        format!(
+                            "previous argument for base contract ‘{}’",
+                            ns.contracts[base.contract_no].name
+                        ),
+                    )
+                    .with_code(codes::INHERITANCE),
+                );
             } else {
                 base_args.insert(
                     base.contract_no,
@@ -1005,13 +1130,16 @@ fn check_base_args(contract_no: usize, ns: &mut ast::Namespace) {
 
             for base_no in &base_args_needed {
                 if !base_args.contains_key(base_no) {
-                    diagnostics.insert(ast::Diagnostic::error(
-                        contract.loc,
-                        format!(
-                            "missing arguments to base contract ‘{}’ constructor",
-                            ns.contracts[*base_no].name
-                        ),
-                    ));
+                    diagnostics.insert(
+                        ast::Diagnostic::error(
+                            contract.loc,
+                            format!(
+                                "missing arguments to base contract ‘{}’ constructor",
+                                ns.contracts[*base_no].name
+                            ),
+                        )
+                        .with_code(codes::WRONG_ARGUMENTS),
+                    );
                 }
             }
         }
@@ -1022,13 +1150,16 @@ fn check_base_args(contract_no: usize, ns: &mut ast::Namespace) {
 
         for base_no in &base_args_needed {
             if !base_args.contains_key(base_no) {
-                diagnostics.insert(ast::Diagnostic::error(
-                    contract.loc,
-                    format!(
-                        "missing arguments to base contract ‘{}’ constructor",
-                        ns.contracts[*base_no].name
-                    ),
-                ));
+                diagnostics.insert(
+                    ast::Diagnostic::error(
+                        contract.loc,
+                        format!(
+                            "missing arguments to base contract ‘{}’ constructor",
+                            ns.contracts[*base_no].name
+                        ),
+                    )
+                    .with_code(codes::WRONG_ARGUMENTS),
+                );
             }
         }
     }
@@ -1,7 +1,13 @@
-use super::ast::{Diagnostic, ErrorType, Level, Namespace, Note};
+use super::ast::{
+    Diagnostic, ErrorType, Fix, Level, Namespace, Note, Suppression, WarningCategory,
+    WarningSelector,
+};
+use super::codes;
 use crate::file_cache::FileCache;
-use crate::parser::pt::Loc;
+use crate::parser::pt::{self, Loc};
 use serde::Serialize;
+use std::path::PathBuf;
+use std::str::FromStr;
 
 impl Level {
     pub fn to_string(&self) -> &'static str {
@@ -14,14 +20,74 @@ impl Level {
     }
 }
 
+const WARNING_CATEGORIES: [WarningCategory; 7] = [
+    WarningCategory::Deprecated,
+    WarningCategory::Mutability,
+    WarningCategory::Pragma,
+    WarningCategory::Redundant,
+    WarningCategory::Shadowing,
+    WarningCategory::Target,
+    WarningCategory::Other,
+];
+
+impl WarningCategory {
+    pub fn name(&self) -> &'static str {
+        match self {
+            WarningCategory::Deprecated => "deprecated",
+            WarningCategory::Mutability => "mutability",
+            WarningCategory::Pragma => "pragma",
+            WarningCategory::Redundant => "redundant",
+            WarningCategory::Shadowing => "shadowing",
+            WarningCategory::Target => "target",
+            WarningCategory::Other => "other",
+        }
+    }
+}
+
+impl FromStr for WarningSelector {
+    type Err = String;
+
+    /// A warning is selected by code, like `S5004`, by category, like `mutability`, or `all`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "all" {
+            return Ok(WarningSelector::All);
+        }
+
+        if let Some(category) = WARNING_CATEGORIES.iter().find(|c| c.name() == s) {
+            return Ok(WarningSelector::Category(*category));
+        }
+
+        match codes::explain(s) {
+            Some(explanation) if explanation.category.is_some() => {
+                Ok(WarningSelector::Code(explanation.code))
+            }
+            _ => Err(format!("unknown warning ‘{}’", s)),
+        }
+    }
+}
+
+impl WarningSelector {
+    /// Does the selector match the diagnostic; only warnings are ever matched
+    pub fn matches(&self, diagnostic: &Diagnostic) -> bool {
+        match (self, diagnostic.category()) {
+            (_, None) => false,
+            (WarningSelector::All, _) => true,
+            (WarningSelector::Category(c), Some(category)) => *c == category,
+            (WarningSelector::Code(code), _) => *code == diagnostic.code,
+        }
+    }
+}
+
 impl Diagnostic {
     pub fn debug(pos: Loc, message: String) -> Self {
         Diagnostic {
             level: Level::Debug,
             ty: ErrorType::None,
+            code: codes::INFO,
             pos: Some(pos),
             message,
             notes: Vec::new(),
+            fixes: Vec::new(),
         }
     }
 
@@ -29,9 +95,11 @@ impl Diagnostic {
         Diagnostic {
             level: Level::Info,
             ty: ErrorType::None,
+            code: codes::INFO,
             pos: Some(pos),
             message,
             notes: Vec::new(),
+            fixes: Vec::new(),
         }
     }
 
@@ -39,9 +107,11 @@ impl Diagnostic {
         Diagnostic {
             level: Level::Error,
             ty: ErrorType::ParserError,
+            code: codes::PARSER_ERROR,
             pos: Some(pos),
             message,
             notes: Vec::new(),
+            fixes: Vec::new(),
         }
     }
 
@@ -49,9 +119,11 @@ impl Diagnostic {
         Diagnostic {
             level: Level::Error,
             ty: ErrorType::SyntaxError,
+            code: codes::ERROR,
             pos: Some(pos),
             message,
             notes: Vec::new(),
+            fixes: Vec::new(),
         }
     }
 
@@ -59,9 +131,11 @@ impl Diagnostic {
         Diagnostic {
             level: Level::Error,
             ty: ErrorType::DeclarationError,
+            code: codes::DECLARATION_ERROR,
             pos: Some(pos),
             message,
             notes: Vec::new(),
+            fixes: Vec::new(),
         }
     }
 
@@ -69,9 +143,11 @@ impl Diagnostic {
         Diagnostic {
             level: Level::Error,
             ty: ErrorType::TypeError,
+            code: codes::TYPE_ERROR,
             pos: Some(pos),
             message,
             notes: Vec::new(),
+            fixes: Vec::new(),
         }
     }
 
@@ -79,9 +155,11 @@ impl Diagnostic {
         Diagnostic {
             level: Level::Warning,
             ty: ErrorType::Warning,
+            code: codes::WARNING,
             pos: Some(pos),
             message,
             notes: Vec::new(),
+            fixes: Vec::new(),
         }
     }
 
@@ -89,12 +167,14 @@ impl Diagnostic {
         Diagnostic {
             level: Level::Warning,
             ty: ErrorType::Warning,
+            code: codes::WARNING,
             pos: Some(pos),
             message,
             notes: vec![Note {
                 pos: note_pos,
                 message: note,
             }],
+            fixes: Vec::new(),
         }
     }
 
@@ -102,9 +182,11 @@ impl Diagnostic {
         Diagnostic {
             level: Level::Warning,
             ty: ErrorType::Warning,
+            code: codes::WARNING,
             pos: Some(pos),
             message,
             notes,
+            fixes: Vec::new(),
         }
     }
 
@@ -112,12 +194,14 @@ impl Diagnostic {
         Diagnostic {
             level: Level::Error,
             ty: ErrorType::None,
+            code: codes::ERROR,
             pos: Some(pos),
             message,
             notes: vec![Note {
                 pos: note_pos,
                 message: note,
             }],
+            fixes: Vec::new(),
         }
     }
 
@@ -125,10 +209,37 @@ impl Diagnostic {
         Diagnostic {
             level: Level::Error,
             ty: ErrorType::None,
+            code: codes::ERROR,
             pos: Some(pos),
             message,
             notes,
+            fixes: Vec::new(),
+        }
+    }
+
+    /// Attach a fix to the diagnostic
+    pub fn with_fix(mut self, message: String, edits: Vec<(Loc, String)>) -> Self {
+        self.fixes.push(Fix { message, edits });
+        self
+    }
+
+    /// Give the diagnostic a more specific code than the default for its type
+    pub fn with_code(mut self, code: &'static str) -> Self {
+        self.code = code;
+        self
+    }
+
+    /// The category of a warning, or None if the diagnostic is not a warning
+    pub fn category(&self) -> Option<WarningCategory> {
+        if self.level != Level::Warning {
+            return None;
         }
+
+        Some(
+            codes::explain(self.code)
+                .and_then(|explanation| explanation.category)
+                .unwrap_or(WarningCategory::Other),
+        )
     }
 
     fn formated_message(&self, file_offsets: &FileOffsets, ns: &Namespace) -> String {
@@ -162,17 +273,393 @@ impl Diagnostic {
 
         s
     }
+
+    /// Render the diagnostic with the source lines it refers to. The location is underlined
+    /// with carets, and each note is shown at its own location.
+    fn rendered_message(
+        &self,
+        file_offsets: &FileOffsets,
+        ns: &Namespace,
+        cache: &mut FileCache,
+        colour: bool,
+    ) -> String {
+        let style = Style(colour);
+        let level_colour = match self.level {
+            Level::Error => RED,
+            Level::Warning => YELLOW,
+            Level::Info | Level::Debug => CYAN,
+        };
+
+        let mut s = format!(
+            "{}: {}\n",
+            style.paint(
+                level_colour,
+                &format!("{}[{}]", self.level.to_string(), self.code)
+            ),
+            style.paint(BOLD, &self.message)
+        );
+
+        // the line numbers in the gutter are right aligned
+        let width = self
+            .pos
+            .iter()
+            .chain(self.notes.iter().map(|note| &note.pos))
+            .map(|pos| (file_offsets.convert(pos.0, pos.2).0 + 1).to_string().len())
+            .max()
+            .unwrap_or(0);
+
+        if let Some(pos) = self.pos {
+            snippet(
+                &mut s,
+                pos,
+                ('^', level_colour),
+                "",
+                width,
+                file_offsets,
+                ns,
+                cache,
+                &style,
+            );
+        }
+
+        for note in &self.notes {
+            snippet(
+                &mut s,
+                note.pos,
+                ('-', CYAN),
+                &format!("note: {}", note.message),
+                width,
+                file_offsets,
+                ns,
+                cache,
+                &style,
+            );
+        }
+
+        s
+    }
+}
+
+const BOLD: &str = "1";
+const RED: &str = "1;31";
+const YELLOW: &str = "1;33";
+const BLUE: &str = "1;34";
+const CYAN: &str = "1;36";
+
+/// Colour text with ANSI escape codes, if enabled
+struct Style(bool);
+
+impl Style {
+    fn paint(&self, colour: &str, text: &str) -> String {
+        if self.0 && !text.is_empty() {
+            format!("\x1b[{}m{}\x1b[0m", colour, text)
+        } else {
+            text.to_owned()
+        }
+    }
+}
+
+/// Spans over more lines than this are shortened
+const MAX_SNIPPET_LINES: usize = 4;
+
+/// Add the source lines of the location to the rendered message, with the location
+/// underlined and the label after the underline
+fn snippet(
+    s: &mut String,
+    loc: Loc,
+    (marker, marker_colour): (char, &str),
+    label: &str,
+    width: usize,
+    file_offsets: &FileOffsets,
+    ns: &Namespace,
+    cache: &mut FileCache,
+    style: &Style,
+) {
+    let contents = cache.get_file_contents(&ns.files[loc.0]);
+    let (from_line, _) = file_offsets.convert(loc.0, loc.1);
+    let (to_line, _) = file_offsets.convert(loc.0, loc.2);
+
+    let gutter = |line: &str| style.paint(BLUE, &format!("{:>1$} |", line, width));
+
+    s.push_str(&format!(
+        "{}{} {}:{}\n",
+        " ".repeat(width),
+        style.paint(BLUE, "-->"),
+        ns.files[loc.0].display(),
+        file_offsets.to_string(loc)
+    ));
+    s.push_str(&gutter(""));
+    s.push('\n');
+
+    for line_no in from_line..=to_line {
+        let lines = to_line - from_line + 1;
+
+        // show the first and last lines of a long span
+        if lines > MAX_SNIPPET_LINES && line_no > from_line + MAX_SNIPPET_LINES - 2 {
+            if line_no == from_line + MAX_SNIPPET_LINES - 1 {
+                s.push_str(&style.paint(BLUE, &format!("{:>1$}", "...", width + 2)));
+                s.push('\n');
+            }
+
+            if line_no != to_line {
+                continue;
+            }
+        }
+
+        let start = file_offsets
+            .get_offset(loc.0, line_no, 0)
+            .min(contents.len());
+        let end = contents[start..]
+            .find('\n')
+            .map_or(contents.len(), |end| start + end);
+        let text = contents[start..end].trim_end();
+
+        // the part of the line which is within the location
+        let from = if line_no == from_line {
+            loc.1 - start
+        } else {
+            text.len() - text.trim_start().len()
+        }
+        .min(text.len());
+        let to = if line_no == to_line {
+            loc.2 - start
+        } else {
+            text.len()
+        }
+        .min(text.len())
+        .max(from);
+
+        // keep tabs in the indentation of the underline, so it lines up with the text
+        let indent: String = text[..from]
+            .chars()
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        let underline = marker
+            .to_string()
+            .repeat(text[from..to].chars().count().max(1));
+
+        s.push_str(&format!(
+            "{} {}\n",
+            gutter(&(line_no + 1).to_string()),
+            text
+        ));
+        s.push_str(&format!(
+            "{} {}{}",
+            gutter(""),
+            indent,
+            style.paint(marker_colour, &underline)
+        ));
+
+        if line_no == to_line && !label.is_empty() {
+            s.push(' ');
+            s.push_str(&style.paint(marker_colour, label));
+        }
+
+        s.push('\n');
+    }
+}
+
+/// How diagnostics are printed
+#[derive(Clone, Copy, PartialEq)]
+pub enum MessageFormat {
+    /// With the source lines of each location; in colour if stderr is a terminal
+    Human,
+    /// One line per diagnostic and note, as `file:line:column: level: message`
+    Plain,
 }
 
 pub fn print_messages(cache: &mut FileCache, ns: &Namespace, debug: bool) {
+    print_messages_with_format(cache, ns, debug, MessageFormat::Human);
+}
+
+pub fn print_messages_with_format(
+    cache: &mut FileCache,
+    ns: &Namespace,
+    debug: bool,
+    format: MessageFormat,
+) {
     let file_offsets = ns.file_offset(cache);
+    let colour = atty::is(atty::Stream::Stderr) && std::env::var_os("NO_COLOR").is_none();
 
     for msg in &ns.diagnostics {
         if !debug && msg.level == Level::Debug {
             continue;
         }
 
-        eprintln!("{}", msg.formated_message(&file_offsets, ns));
+        match format {
+            MessageFormat::Human => {
+                eprintln!("{}", msg.rendered_message(&file_offsets, ns, cache, colour));
+            }
+            MessageFormat::Plain => {
+                eprintln!("{}", msg.formated_message(&file_offsets, ns));
+            }
+        }
+    }
+}
+
+/// Find the comments like `// solang-disable-next-line S5004, shadowing` and add them to the
+/// suppressions of the namespace. Without a list of warnings, all warnings are suppressed.
+pub fn resolve_suppressions(comments: &[pt::Comment], ns: &mut Namespace) {
+    for comment in comments {
+        let (loc, text) = match comment {
+            pt::Comment::Line(loc, text) | pt::Comment::Block(loc, text) => (*loc, text.trim()),
+            pt::Comment::DocLine(..) | pt::Comment::DocBlock(..) => continue,
+        };
+
+        let (next_line, rest) = if let Some(rest) = text.strip_prefix("solang-disable-next-line") {
+            (true, rest)
+        } else if let Some(rest) = text.strip_prefix("solang-disable-line") {
+            (false, rest)
+        } else {
+            continue;
+        };
+
+        if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+            continue;
+        }
+
+        let mut warnings = Vec::new();
+
+        for s in rest.split(|c: char| c == ',' || c.is_whitespace()) {
+            if s.is_empty() {
+                continue;
+            }
+
+            match s.parse() {
+                Ok(selector) => warnings.push(selector),
+                Err(message) => ns
+                    .diagnostics
+                    .push(Diagnostic::warning(loc, message).with_code(codes::UNKNOWN_WARNING)),
+            }
+        }
+
+        ns.suppressions.push(Suppression {
+            loc,
+            next_line,
+            warnings,
+        });
+    }
+}
+
+/// Remove the warnings which are suppressed by comments
+pub fn apply_suppressions(cache: &mut FileCache, ns: &mut Namespace) {
+    if ns.suppressions.is_empty() {
+        return;
+    }
+
+    let file_offsets = ns.file_offset(cache);
+
+    // the file and line of each suppression
+    let lines: Vec<(usize, usize, &Suppression)> = ns
+        .suppressions
+        .iter()
+        .map(|suppression| {
+            let loc = suppression.loc;
+
+            let line = if suppression.next_line {
+                file_offsets.convert(loc.0, loc.2).0 + 1
+            } else {
+                file_offsets.convert(loc.0, loc.1).0
+            };
+
+            (loc.0, line, suppression)
+        })
+        .collect();
+
+    ns.diagnostics.retain(|diagnostic| {
+        let pos = match (diagnostic.pos, diagnostic.category()) {
+            (Some(pos), Some(_)) => pos,
+            _ => return true,
+        };
+
+        let line = file_offsets.convert(pos.0, pos.1).0;
+
+        !lines.iter().any(|(file_no, suppressed_line, suppression)| {
+            *file_no == pos.0
+                && *suppressed_line == line
+                && (suppression.warnings.is_empty()
+                    || suppression
+                        .warnings
+                        .iter()
+                        .any(|selector| selector.matches(diagnostic)))
+        })
+    });
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum WarningAction {
+    Enable,
+    Disable,
+    Error,
+    NoError,
+}
+
+/// What to do with warnings, as given with `-W` on the command line. When more than one
+/// option selects a warning, the last one wins.
+#[derive(Default)]
+pub struct WarningControl {
+    options: Vec<(WarningSelector, WarningAction)>,
+}
+
+impl WarningControl {
+    /// Add an option, which is one of `error`, `no-error`, `error=<warning>`,
+    /// `no-error=<warning>`, `no-<warning>` or `<warning>`
+    pub fn add(&mut self, option: &str) -> Result<(), String> {
+        let (action, warning) = if option == "error" {
+            (WarningAction::Error, "all")
+        } else if option == "no-error" {
+            (WarningAction::NoError, "all")
+        } else if let Some(warning) = option.strip_prefix("error=") {
+            (WarningAction::Error, warning)
+        } else if let Some(warning) = option.strip_prefix("no-error=") {
+            (WarningAction::NoError, warning)
+        } else if let Some(warning) = option.strip_prefix("no-") {
+            (WarningAction::Disable, warning)
+        } else {
+            (WarningAction::Enable, option)
+        };
+
+        self.options.push((warning.parse()?, action));
+
+        Ok(())
+    }
+
+    /// Remove the disabled warnings, and turn warnings into errors where requested
+    pub fn apply(&self, ns: &mut Namespace) {
+        if self.options.is_empty() {
+            return;
+        }
+
+        let diagnostics = std::mem::take(&mut ns.diagnostics);
+
+        ns.diagnostics = diagnostics
+            .into_iter()
+            .filter_map(|mut diagnostic| {
+                let mut enabled = true;
+                let mut error = false;
+
+                for (selector, action) in &self.options {
+                    if selector.matches(&diagnostic) {
+                        match action {
+                            WarningAction::Enable => enabled = true,
+                            WarningAction::Disable => enabled = false,
+                            WarningAction::Error => error = true,
+                            WarningAction::NoError => error = false,
+                        }
+                    }
+                }
+
+                if !enabled {
+                    return None;
+                }
+
+                if error {
+                    diagnostic.level = Level::Error;
+                }
+
+                Some(diagnostic)
+            })
+            .collect();
     }
 }
 
@@ -181,6 +668,52 @@ pub fn any_errors(diagnotic: &[Diagnostic]) -> bool {
     diagnotic.iter().any(|m| m.level == Level::Error)
 }
 
+/// Apply the first fix of every diagnostic to the file which was resolved, and return its new
+/// contents if it changes. Imported files are left alone, since they may well be libraries
+/// which are not ours to change. A fix is skipped if any of its edits overlaps with a fix
+/// which was already applied, or if it edits an imported file.
+pub fn apply_fixes(cache: &mut FileCache, ns: &Namespace) -> Vec<(PathBuf, String)> {
+    let mut edits: Vec<&(Loc, String)> = Vec::new();
+
+    for fix in ns.diagnostics.iter().filter_map(|msg| msg.fixes.first()) {
+        if fix.edits.iter().any(|(loc, _)| loc.0 != 0) {
+            continue;
+        }
+
+        let overlaps = fix.edits.iter().any(|(loc, text)| {
+            edits.iter().any(|(l, t)| {
+                loc.0 == l.0
+                    && ((loc.1 < l.2 && l.1 < loc.2) || loc.1 == l.1)
+                    && (loc != l || text != t)
+            })
+        });
+
+        if !overlaps {
+            for edit in &fix.edits {
+                if !edits.contains(&edit) {
+                    edits.push(edit);
+                }
+            }
+        }
+    }
+
+    if edits.is_empty() {
+        return Vec::new();
+    }
+
+    // apply the edits from the end of the file, so that the offsets remain valid
+    edits.sort_by_key(|(loc, _)| std::cmp::Reverse(loc.1));
+
+    let path = &ns.files[0];
+    let mut contents = cache.get_file_contents(path).to_string();
+
+    for (loc, text) in edits {
+        contents.replace_range(loc.1..loc.2, text);
+    }
+
+    vec![(path.clone(), contents)]
+}
+
 #[derive(Serialize)]
 pub struct LocJson {
     pub file: String,
@@ -196,6 +729,8 @@ pub struct OutputJson {
     pub ty: String,
     pub component: String,
     pub severity: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub errorCode: Option<String>,
     pub message: String,
     pub formattedMessage: String,
 }
@@ -221,6 +756,7 @@ pub fn message_as_json(cache: &mut FileCache, ns: &Namespace) -> Vec<OutputJson>
             ty: format!("{:?}", msg.ty),
             component: "general".to_owned(),
             severity: msg.level.to_string().to_owned(),
+            errorCode: Some(msg.code.to_owned()),
             message: msg.message.to_owned(),
             formattedMessage: msg.formated_message(&file_offsets, ns),
         });
@@ -277,7 +813,10 @@ impl FileOffsets {
         if line_no == 0 {
             column_no
         } else {
-            self.files[file_no][line_no - 1] + column_no
+            // a line past the end of the file gives an offset past the end
+            self.files[file_no]
+                .get(line_no - 1)
+                .map_or(usize::MAX, |offset| offset + column_no)
         }
     }
 }
@@ -5,6 +5,7 @@ use num_traits::ToPrimitive;
 use num_traits::Zero;
 
 use super::ast::{Diagnostic, Expression, Namespace};
+use super::codes;
 use crate::parser::pt;
 
 /// Resolve an expression where a compile-time constant is expected
@@ -30,7 +31,8 @@ pub fn eval_const_number(
             let divisor = eval_const_number(r, contract_no, ns)?.1;
 
             if divisor.is_zero() {
-                Err(Diagnostic::error(*loc, "divide by zero".to_string()))
+                Err(Diagnostic::error(*loc, "divide by zero".to_string())
+                    .with_code(codes::DIVIDE_BY_ZERO))
             } else {
                 Ok((*loc, eval_const_number(l, contract_no, ns)?.1 / divisor))
             }
@@ -39,7 +41,8 @@ pub fn eval_const_number(
             let divisor = eval_const_number(r, contract_no, ns)?.1;
 
             if divisor.is_zero() {
-                Err(Diagnostic::error(*loc, "divide by zero".to_string()))
+                Err(Diagnostic::error(*loc, "divide by zero".to_string())
+                    .with_code(codes::DIVIDE_BY_ZERO))
             } else {
                 Ok((*loc, eval_const_number(l, contract_no, ns)?.1 % divisor))
             }
@@ -64,7 +67,8 @@ pub fn eval_const_number(
                 Err(Diagnostic::error(
                     expr.loc(),
                     "power cannot take negative number as exponent".to_string(),
-                ))
+                )
+                .with_code(codes::OUT_OF_RANGE))
             } else if e.sign() == Sign::NoSign {
                 Ok((*loc, BigInt::one()))
             } else {
@@ -86,7 +90,8 @@ pub fn eval_const_number(
                     return Err(Diagnostic::error(
                         expr.loc(),
                         format!("cannot left shift by {}", r),
-                    ));
+                    )
+                    .with_code(codes::OUT_OF_RANGE));
                 }
             };
             Ok((*loc, l << r))
@@ -100,7 +105,8 @@ pub fn eval_const_number(
                     return Err(Diagnostic::error(
                         expr.loc(),
                         format!("cannot right shift by {}", r),
-                    ));
+                    )
+                    .with_code(codes::OUT_OF_RANGE));
                 }
             };
             Ok((*loc, l >> r))
@@ -129,6 +135,7 @@ pub fn eval_const_number(
         _ => Err(Diagnostic::error(
             expr.loc(),
             "expression not allowed in constant number expression".to_string(),
-        )),
+        )
+        .with_code(codes::CONSTANT_EXPRESSION)),
     }
 }
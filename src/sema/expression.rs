@@ -14,9 +14,10 @@ use std::ops::{Add, Shl, Sub};
 
 use super::address::to_hexstr_eip55;
 use super::ast::{
-    Builtin, CallTy, Diagnostic, Expression, Function, Namespace, StringLocation, Symbol, Type,
+    Builtin, CallTy, Diagnostic, Expression, Fix, Function, Namespace, StringLocation, Symbol, Type,
 };
 use super::builtin;
+use super::codes;
 use super::contracts::{is_base, visit_bases};
 use super::eval::eval_const_number;
 use super::format::string_format;
@@ -260,49 +261,64 @@ fn unescape(
                 Ok(ch) => match std::char::from_u32(ch) {
                     Some(ch) => s.push(ch),
                     None => {
-                        diagnostics.push(Diagnostic::error(
-                            pt::Loc(file_no, start + i, start + i + 4),
-                            format!("\\x{:02x} is not a valid unicode character", ch),
-                        ));
+                        diagnostics.push(
+                            Diagnostic::error(
+                                pt::Loc(file_no, start + i, start + i + 4),
+                                format!("\\x{:02x} is not a valid unicode character", ch),
+                            )
+                            .with_code(codes::LITERAL),
+                        );
                     }
                 },
                 Err(offset) => {
-                    diagnostics.push(Diagnostic::error(
-                        pt::Loc(
-                            file_no,
-                            start + i,
-                            start + std::cmp::min(literal.len(), offset),
-                        ),
-                        "\\x escape should be followed by two hex digits".to_string(),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(
+                            pt::Loc(
+                                file_no,
+                                start + i,
+                                start + std::cmp::min(literal.len(), offset),
+                            ),
+                            "\\x escape should be followed by two hex digits".to_string(),
+                        )
+                        .with_code(codes::LITERAL),
+                    );
                 }
             },
             Some((i, 'u')) => match get_digits(&mut indeces, 4) {
                 Ok(ch) => match std::char::from_u32(ch) {
                     Some(ch) => s.push(ch),
                     None => {
-                        diagnostics.push(Diagnostic::error(
-                            pt::Loc(file_no, start + i, start + i + 6),
-                            format!("\\u{:04x} is not a valid unicode character", ch),
-                        ));
+                        diagnostics.push(
+                            Diagnostic::error(
+                                pt::Loc(file_no, start + i, start + i + 6),
+                                format!("\\u{:04x} is not a valid unicode character", ch),
+                            )
+                            .with_code(codes::LITERAL),
+                        );
                     }
                 },
                 Err(offset) => {
-                    diagnostics.push(Diagnostic::error(
-                        pt::Loc(
-                            file_no,
-                            start + i,
-                            start + std::cmp::min(literal.len(), offset),
-                        ),
-                        "\\u escape should be followed by four hex digits".to_string(),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(
+                            pt::Loc(
+                                file_no,
+                                start + i,
+                                start + std::cmp::min(literal.len(), offset),
+                            ),
+                            "\\u escape should be followed by four hex digits".to_string(),
+                        )
+                        .with_code(codes::LITERAL),
+                    );
                 }
             },
             Some((i, ch)) => {
-                diagnostics.push(Diagnostic::error(
-                    pt::Loc(file_no, start + i, start + i + ch.len_utf8()),
-                    format!("unknown escape character '{}'", ch),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(
+                        pt::Loc(file_no, start + i, start + i + ch.len_utf8()),
+                        format!("unknown escape character '{}'", ch),
+                    )
+                    .with_code(codes::LITERAL),
+                );
             }
             None => unreachable!(),
         }
@@ -383,33 +399,42 @@ fn get_int_length(
         Type::Value => Ok((ns.value_length as u16 * 8, false)),
         Type::Bytes(n) if allow_bytes => Ok((*n as u16 * 8, false)),
         Type::Enum(n) => {
-            diagnostics.push(Diagnostic::error(
-                *l_loc,
-                format!("type enum {} not allowed", ns.enums[*n]),
-            ));
+            diagnostics.push(
+                Diagnostic::error(*l_loc, format!("type enum {} not allowed", ns.enums[*n]))
+                    .with_code(codes::TYPE_NOT_ALLOWED),
+            );
             Err(())
         }
         Type::Struct(n) => {
-            diagnostics.push(Diagnostic::error(
-                *l_loc,
-                format!("type struct {} not allowed", ns.structs[*n]),
-            ));
+            diagnostics.push(
+                Diagnostic::error(
+                    *l_loc,
+                    format!("type struct {} not allowed", ns.structs[*n]),
+                )
+                .with_code(codes::TYPE_NOT_ALLOWED),
+            );
             Err(())
         }
         Type::Array(_, _) => {
-            diagnostics.push(Diagnostic::error(
-                *l_loc,
-                format!("type array {} not allowed", l.to_string(ns)),
-            ));
+            diagnostics.push(
+                Diagnostic::error(
+                    *l_loc,
+                    format!("type array {} not allowed", l.to_string(ns)),
+                )
+                .with_code(codes::TYPE_NOT_ALLOWED),
+            );
             Err(())
         }
         Type::Ref(n) => get_int_length(n, l_loc, allow_bytes, ns, diagnostics),
         Type::StorageRef(n) => get_int_length(n, l_loc, allow_bytes, ns, diagnostics),
         _ => {
-            diagnostics.push(Diagnostic::error(
-                *l_loc,
-                format!("expression of type {} not allowed", l.to_string(ns)),
-            ));
+            diagnostics.push(
+                Diagnostic::error(
+                    *l_loc,
+                    format!("expression of type {} not allowed", l.to_string(ns)),
+                )
+                .with_code(codes::TYPE_NOT_ALLOWED),
+            );
             Err(())
         }
     }
@@ -474,10 +499,13 @@ pub fn bigint_to_expression(
 
     if let Some(resolve_to) = resolve_to {
         if !resolve_to.is_integer() {
-            diagnostics.push(Diagnostic::error(
-                *loc,
-                format!("expected ‘{}’, found integer", resolve_to.to_string(ns)),
-            ));
+            diagnostics.push(
+                Diagnostic::error(
+                    *loc,
+                    format!("expected ‘{}’, found integer", resolve_to.to_string(ns)),
+                )
+                .with_code(codes::IMPLICIT_CONVERSION),
+            );
             return Err(());
         }
 
@@ -489,24 +517,30 @@ pub fn bigint_to_expression(
 
         return if n.sign() == Sign::Minus {
             if !resolve_to.is_signed_int() {
-                diagnostics.push(Diagnostic::error(
-                    *loc,
-                    format!(
-                        "negative literal {} not allowed for unsigned type ‘{}’",
-                        n,
-                        resolve_to.to_string(ns)
-                    ),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(
+                        *loc,
+                        format!(
+                            "negative literal {} not allowed for unsigned type ‘{}’",
+                            n,
+                            resolve_to.to_string(ns)
+                        ),
+                    )
+                    .with_code(codes::LITERAL),
+                );
                 Err(())
             } else if n.add(1u32).bits() > permitted_bits {
-                diagnostics.push(Diagnostic::error(
-                    *loc,
-                    format!(
-                        "literal {} is too large to fit into type ‘{}’",
-                        n,
-                        resolve_to.to_string(ns)
-                    ),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(
+                        *loc,
+                        format!(
+                            "literal {} is too large to fit into type ‘{}’",
+                            n,
+                            resolve_to.to_string(ns)
+                        ),
+                    )
+                    .with_code(codes::LITERAL),
+                );
                 Err(())
             } else {
                 Ok(Expression::NumberLiteral(
@@ -516,14 +550,17 @@ pub fn bigint_to_expression(
                 ))
             }
         } else if bits > permitted_bits {
-            diagnostics.push(Diagnostic::error(
-                *loc,
-                format!(
-                    "literal {} is too large to fit into type ‘{}’",
-                    n,
-                    resolve_to.to_string(ns)
-                ),
-            ));
+            diagnostics.push(
+                Diagnostic::error(
+                    *loc,
+                    format!(
+                        "literal {} is too large to fit into type ‘{}’",
+                        n,
+                        resolve_to.to_string(ns)
+                    ),
+                )
+                .with_code(codes::LITERAL),
+            );
             Err(())
         } else {
             Ok(Expression::NumberLiteral(
@@ -540,7 +577,10 @@ pub fn bigint_to_expression(
 
     if n.sign() == Sign::Minus {
         if bits > 255 {
-            diagnostics.push(Diagnostic::error(*loc, format!("{} is too large", n)));
+            diagnostics.push(
+                Diagnostic::error(*loc, format!("{} is too large", n))
+                    .with_code(codes::OUT_OF_RANGE),
+            );
             Err(())
         } else {
             Ok(Expression::NumberLiteral(
@@ -550,7 +590,9 @@ pub fn bigint_to_expression(
             ))
         }
     } else if bits > 256 {
-        diagnostics.push(Diagnostic::error(*loc, format!("{} is too large", n)));
+        diagnostics.push(
+            Diagnostic::error(*loc, format!("{} is too large", n)).with_code(codes::OUT_OF_RANGE),
+        );
         Err(())
     } else {
         Ok(Expression::NumberLiteral(
@@ -570,6 +612,57 @@ pub fn cast(
     implicit: bool,
     ns: &Namespace,
     diagnostics: &mut Vec<Diagnostic>,
+) -> Result<Expression, ()> {
+    let start = diagnostics.len();
+    let expr_loc = expr.loc();
+    // an explicit cast does not help a literal which does not fit
+    let literal = matches!(expr, Expression::NumberLiteral(..));
+
+    let res = cast_expression(loc, expr, to, implicit, ns, diagnostics);
+
+    if res.is_err() && implicit && !literal {
+        let cast_to = match to {
+            Type::Address(true) => Some(String::from("payable")),
+            Type::Contract(contract_no) => Some(ns.contracts[*contract_no].name.to_owned()),
+            Type::Address(false) | Type::Int(_) | Type::Uint(_) | Type::Bytes(_) => {
+                Some(to.to_string(ns))
+            }
+            _ => None,
+        };
+
+        if let Some(cast_to) = cast_to {
+            for diagnostic in &mut diagnostics[start..] {
+                if diagnostic.message.starts_with("implicit conversion")
+                    && diagnostic.fixes.is_empty()
+                {
+                    diagnostic.fixes.push(Fix {
+                        message: format!("convert explicitly to ‘{}’", cast_to),
+                        edits: vec![
+                            (
+                                pt::Loc(expr_loc.0, expr_loc.1, expr_loc.1),
+                                format!("{}(", cast_to),
+                            ),
+                            (
+                                pt::Loc(expr_loc.0, expr_loc.2, expr_loc.2),
+                                String::from(")"),
+                            ),
+                        ],
+                    });
+                }
+            }
+        }
+    }
+
+    res
+}
+
+fn cast_expression(
+    loc: &pt::Loc,
+    expr: Expression,
+    to: &Type,
+    implicit: bool,
+    ns: &Namespace,
+    diagnostics: &mut Vec<Diagnostic>,
 ) -> Result<Expression, ()> {
     let from = expr.ty();
 
@@ -579,7 +672,7 @@ pub fn cast(
 
     // First of all, if we have a ref then derefence it
     if let Type::Ref(r) = from {
-        return cast(
+        return cast_expression(
             loc,
             Expression::Load(*loc, r.as_ref().clone(), Box::new(expr)),
             to,
@@ -594,7 +687,7 @@ pub fn cast(
         if let Expression::StorageBytesSubscript(_, _, _) = expr {
             return Ok(expr);
         } else {
-            return cast(
+            return cast_expression(
                 loc,
                 Expression::StorageLoad(*loc, *r, Box::new(expr)),
                 to,
@@ -610,13 +703,16 @@ pub fn cast(
         (&Expression::NumberLiteral(_, _, ref n), p, &Type::Uint(to_len)) if p.is_primitive() => {
             return if n.sign() == Sign::Minus {
                 if implicit {
-                    diagnostics.push(Diagnostic::type_error(
-                        *loc,
-                        format!(
-                            "implicit conversion cannot change negative number to {}",
-                            to.to_string(ns)
-                        ),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::type_error(
+                            *loc,
+                            format!(
+                                "implicit conversion cannot change negative number to {}",
+                                to.to_string(ns)
+                            ),
+                        )
+                        .with_code(codes::IMPLICIT_CONVERSION),
+                    );
                     Err(())
                 } else {
                     // Convert to little endian so most significant bytes are at the end; that way
@@ -632,14 +728,17 @@ pub fn cast(
                     ))
                 }
             } else if n.bits() >= to_len as u64 {
-                diagnostics.push(Diagnostic::type_error(
-                    *loc,
-                    format!(
-                        "implicit conversion would truncate from {} to {}",
-                        from.to_string(ns),
-                        to.to_string(ns)
-                    ),
-                ));
+                diagnostics.push(
+                    Diagnostic::type_error(
+                        *loc,
+                        format!(
+                            "implicit conversion would truncate from {} to {}",
+                            from.to_string(ns),
+                            to.to_string(ns)
+                        ),
+                    )
+                    .with_code(codes::IMPLICIT_CONVERSION),
+                );
                 Err(())
             } else {
                 Ok(Expression::NumberLiteral(
@@ -651,14 +750,17 @@ pub fn cast(
         }
         (&Expression::NumberLiteral(_, _, ref n), p, &Type::Int(to_len)) if p.is_primitive() => {
             return if n.bits() >= to_len as u64 {
-                diagnostics.push(Diagnostic::type_error(
-                    *loc,
-                    format!(
-                        "implicit conversion would truncate from {} to {}",
-                        from.to_string(ns),
-                        to.to_string(ns)
-                    ),
-                ));
+                diagnostics.push(
+                    Diagnostic::type_error(
+                        *loc,
+                        format!(
+                            "implicit conversion would truncate from {} to {}",
+                            from.to_string(ns),
+                            to.to_string(ns)
+                        ),
+                    )
+                    .with_code(codes::IMPLICIT_CONVERSION),
+                );
                 Err(())
             } else {
                 Ok(Expression::NumberLiteral(
@@ -671,14 +773,17 @@ pub fn cast(
         // Literal strings can be implicitly lengthened
         (&Expression::BytesLiteral(_, _, ref bs), p, &Type::Bytes(to_len)) if p.is_primitive() => {
             return if bs.len() > to_len as usize && implicit {
-                diagnostics.push(Diagnostic::type_error(
-                    *loc,
-                    format!(
-                        "implicit conversion would truncate from {} to {}",
-                        from.to_string(ns),
-                        to.to_string(ns)
-                    ),
-                ));
+                diagnostics.push(
+                    Diagnostic::type_error(
+                        *loc,
+                        format!(
+                            "implicit conversion would truncate from {} to {}",
+                            from.to_string(ns),
+                            to.to_string(ns)
+                        ),
+                    )
+                    .with_code(codes::IMPLICIT_CONVERSION),
+                );
                 Err(())
             } else {
                 let mut bs = bs.to_owned();
@@ -725,14 +830,17 @@ fn cast_types(
         (Type::Uint(from_width), Type::Enum(enum_no))
         | (Type::Int(from_width), Type::Enum(enum_no)) => {
             if implicit {
-                diagnostics.push(Diagnostic::type_error(
-                    *loc,
-                    format!(
-                        "implicit conversion from {} to {} not allowed",
-                        from.to_string(ns),
-                        to.to_string(ns)
-                    ),
-                ));
+                diagnostics.push(
+                    Diagnostic::type_error(
+                        *loc,
+                        format!(
+                            "implicit conversion from {} to {} not allowed",
+                            from.to_string(ns),
+                            to.to_string(ns)
+                        ),
+                    )
+                    .with_code(codes::IMPLICIT_CONVERSION),
+                );
                 return Err(());
             }
 
@@ -750,14 +858,17 @@ fn cast_types(
                     }
                 }
 
-                diagnostics.push(Diagnostic::type_error(
-                    *loc,
-                    format!(
-                        "enum {} has no value with ordinal {}",
-                        to.to_string(ns),
-                        big_number
-                    ),
-                ));
+                diagnostics.push(
+                    Diagnostic::type_error(
+                        *loc,
+                        format!(
+                            "enum {} has no value with ordinal {}",
+                            to.to_string(ns),
+                            big_number
+                        ),
+                    )
+                    .with_code(codes::OUT_OF_RANGE),
+                );
                 return Err(());
             }
 
@@ -773,14 +884,17 @@ fn cast_types(
         (Type::Enum(enum_no), Type::Uint(to_width))
         | (Type::Enum(enum_no), Type::Int(to_width)) => {
             if implicit {
-                diagnostics.push(Diagnostic::type_error(
-                    *loc,
-                    format!(
-                        "implicit conversion from {} to {} not allowed",
-                        from.to_string(ns),
-                        to.to_string(ns)
-                    ),
-                ));
+                diagnostics.push(
+                    Diagnostic::type_error(
+                        *loc,
+                        format!(
+                            "implicit conversion from {} to {} not allowed",
+                            from.to_string(ns),
+                            to.to_string(ns)
+                        ),
+                    )
+                    .with_code(codes::IMPLICIT_CONVERSION),
+                );
                 return Err(());
             }
 
@@ -798,14 +912,17 @@ fn cast_types(
         (Type::Uint(from_len), Type::Uint(to_len)) => match from_len.cmp(&to_len) {
             Ordering::Greater => {
                 if implicit {
-                    diagnostics.push(Diagnostic::type_error(
-                        *loc,
-                        format!(
-                            "implicit conversion would truncate from {} to {}",
-                            from.to_string(ns),
-                            to.to_string(ns)
-                        ),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::type_error(
+                            *loc,
+                            format!(
+                                "implicit conversion would truncate from {} to {}",
+                                from.to_string(ns),
+                                to.to_string(ns)
+                            ),
+                        )
+                        .with_code(codes::IMPLICIT_CONVERSION),
+                    );
                     Err(())
                 } else {
                     Ok(Expression::Trunc(*loc, to.clone(), Box::new(expr)))
@@ -817,14 +934,17 @@ fn cast_types(
         (Type::Int(from_len), Type::Int(to_len)) => match from_len.cmp(&to_len) {
             Ordering::Greater => {
                 if implicit {
-                    diagnostics.push(Diagnostic::type_error(
-                        *loc,
-                        format!(
-                            "implicit conversion would truncate from {} to {}",
-                            from.to_string(ns),
-                            to.to_string(ns)
-                        ),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::type_error(
+                            *loc,
+                            format!(
+                                "implicit conversion would truncate from {} to {}",
+                                from.to_string(ns),
+                                to.to_string(ns)
+                            ),
+                        )
+                        .with_code(codes::IMPLICIT_CONVERSION),
+                    );
                     Err(())
                 } else {
                     Ok(Expression::Trunc(*loc, to.clone(), Box::new(expr)))
@@ -838,14 +958,17 @@ fn cast_types(
         }
         (Type::Int(from_len), Type::Uint(to_len)) => {
             if implicit {
-                diagnostics.push(Diagnostic::type_error(
-                    *loc,
-                    format!(
-                        "implicit conversion would change sign from {} to {}",
-                        from.to_string(ns),
-                        to.to_string(ns)
-                    ),
-                ));
+                diagnostics.push(
+                    Diagnostic::type_error(
+                        *loc,
+                        format!(
+                            "implicit conversion would change sign from {} to {}",
+                            from.to_string(ns),
+                            to.to_string(ns)
+                        ),
+                    )
+                    .with_code(codes::IMPLICIT_CONVERSION),
+                );
                 Err(())
             } else if from_len > to_len {
                 Ok(Expression::Trunc(*loc, to.clone(), Box::new(expr)))
@@ -857,14 +980,17 @@ fn cast_types(
         }
         (Type::Uint(from_len), Type::Int(to_len)) => {
             if implicit {
-                diagnostics.push(Diagnostic::type_error(
-                    *loc,
-                    format!(
-                        "implicit conversion would change sign from {} to {}",
-                        from.to_string(ns),
-                        to.to_string(ns)
-                    ),
-                ));
+                diagnostics.push(
+                    Diagnostic::type_error(
+                        *loc,
+                        format!(
+                            "implicit conversion would change sign from {} to {}",
+                            from.to_string(ns),
+                            to.to_string(ns)
+                        ),
+                    )
+                    .with_code(codes::IMPLICIT_CONVERSION),
+                );
                 Err(())
             } else if from_len > to_len {
                 Ok(Expression::Trunc(*loc, to.clone(), Box::new(expr)))
@@ -882,14 +1008,17 @@ fn cast_types(
             match from_len.cmp(&to_len) {
                 Ordering::Greater => {
                     if implicit {
-                        diagnostics.push(Diagnostic::type_error(
-                            *loc,
-                            format!(
-                                "implicit conversion would truncate from {} to {}",
-                                from.to_string(ns),
-                                to.to_string(ns)
-                            ),
-                        ));
+                        diagnostics.push(
+                            Diagnostic::type_error(
+                                *loc,
+                                format!(
+                                    "implicit conversion would truncate from {} to {}",
+                                    from.to_string(ns),
+                                    to.to_string(ns)
+                                ),
+                            )
+                            .with_code(codes::IMPLICIT_CONVERSION),
+                        );
                         Err(())
                     } else {
                         Ok(Expression::Trunc(*loc, to.clone(), Box::new(expr)))
@@ -904,14 +1033,17 @@ fn cast_types(
             let to_len = *to_len as usize;
 
             if implicit {
-                diagnostics.push(Diagnostic::type_error(
-                    *loc,
-                    format!(
-                        "implicit conversion would change sign from {} to {}",
-                        from.to_string(ns),
-                        to.to_string(ns)
-                    ),
-                ));
+                diagnostics.push(
+                    Diagnostic::type_error(
+                        *loc,
+                        format!(
+                            "implicit conversion would change sign from {} to {}",
+                            from.to_string(ns),
+                            to.to_string(ns)
+                        ),
+                    )
+                    .with_code(codes::IMPLICIT_CONVERSION),
+                );
                 Err(())
             } else if from_len > to_len {
                 Ok(Expression::Trunc(*loc, to.clone(), Box::new(expr)))
@@ -929,16 +1061,19 @@ fn cast_types(
             match from_len.cmp(&to_len) {
                 Ordering::Greater => {
                     if implicit {
-                        diagnostics.push(Diagnostic::type_error(
-                            *loc,
-                            format!(
-                                "conversion truncates {} to {}, as value is type {} on {}",
-                                from.to_string(ns),
-                                to.to_string(ns),
-                                Type::Value.to_string(ns),
-                                ns.target
-                            ),
-                        ));
+                        diagnostics.push(
+                            Diagnostic::type_error(
+                                *loc,
+                                format!(
+                                    "conversion truncates {} to {}, as value is type {} on {}",
+                                    from.to_string(ns),
+                                    to.to_string(ns),
+                                    Type::Value.to_string(ns),
+                                    ns.target
+                                ),
+                            )
+                            .with_code(codes::TARGET_NOT_SUPPORTED),
+                        );
                         Err(())
                     } else {
                         Ok(Expression::Trunc(*loc, to.clone(), Box::new(expr)))
@@ -951,13 +1086,16 @@ fn cast_types(
         // Casting int to address
         (Type::Uint(from_len), Type::Address(_)) | (Type::Int(from_len), Type::Address(_)) => {
             if implicit {
-                diagnostics.push(Diagnostic::type_error(
-                    *loc,
-                    format!(
-                        "implicit conversion from {} to address not allowed",
-                        from.to_string(ns)
-                    ),
-                ));
+                diagnostics.push(
+                    Diagnostic::type_error(
+                        *loc,
+                        format!(
+                            "implicit conversion from {} to address not allowed",
+                            from.to_string(ns)
+                        ),
+                    )
+                    .with_code(codes::IMPLICIT_CONVERSION),
+                );
                 Err(())
             } else if *from_len > address_bits {
                 Ok(Expression::Trunc(*loc, to.clone(), Box::new(expr)))
@@ -970,14 +1108,17 @@ fn cast_types(
         // Casting int address to int
         (Type::Address(_), Type::Uint(to_len)) | (Type::Address(_), Type::Int(to_len)) => {
             if implicit {
-                diagnostics.push(Diagnostic::type_error(
-                    *loc,
-                    format!(
-                        "implicit conversion to {} from {} not allowed",
-                        from.to_string(ns),
-                        to.to_string(ns)
-                    ),
-                ));
+                diagnostics.push(
+                    Diagnostic::type_error(
+                        *loc,
+                        format!(
+                            "implicit conversion to {} from {} not allowed",
+                            from.to_string(ns),
+                            to.to_string(ns)
+                        ),
+                    )
+                    .with_code(codes::IMPLICIT_CONVERSION),
+                );
                 Err(())
             } else if *to_len < address_bits {
                 Ok(Expression::Trunc(*loc, to.clone(), Box::new(expr)))
@@ -990,14 +1131,17 @@ fn cast_types(
         // Lengthing or shorting a fixed bytes array
         (Type::Bytes(from_len), Type::Bytes(to_len)) => {
             if implicit {
-                diagnostics.push(Diagnostic::type_error(
-                    *loc,
-                    format!(
-                        "implicit conversion would truncate from {} to {}",
-                        from.to_string(ns),
-                        to.to_string(ns)
-                    ),
-                ));
+                diagnostics.push(
+                    Diagnostic::type_error(
+                        *loc,
+                        format!(
+                            "implicit conversion would truncate from {} to {}",
+                            from.to_string(ns),
+                            to.to_string(ns)
+                        ),
+                    )
+                    .with_code(codes::IMPLICIT_CONVERSION),
+                );
                 Err(())
             } else if to_len > from_len {
                 let shift = (to_len - from_len) * 8;
@@ -1040,24 +1184,30 @@ fn cast_types(
         (Type::Bytes(from_len), Type::Uint(to_len))
         | (Type::Bytes(from_len), Type::Int(to_len)) => {
             if implicit {
-                diagnostics.push(Diagnostic::type_error(
-                    *loc,
-                    format!(
-                        "implicit conversion to {} from {} not allowed",
-                        to.to_string(ns),
-                        from.to_string(ns)
-                    ),
-                ));
+                diagnostics.push(
+                    Diagnostic::type_error(
+                        *loc,
+                        format!(
+                            "implicit conversion to {} from {} not allowed",
+                            to.to_string(ns),
+                            from.to_string(ns)
+                        ),
+                    )
+                    .with_code(codes::IMPLICIT_CONVERSION),
+                );
                 Err(())
             } else if *from_len as u16 * 8 != *to_len {
-                diagnostics.push(Diagnostic::type_error(
-                    *loc,
-                    format!(
-                        "conversion to {} from {} not allowed",
-                        to.to_string(ns),
-                        from.to_string(ns)
-                    ),
-                ));
+                diagnostics.push(
+                    Diagnostic::type_error(
+                        *loc,
+                        format!(
+                            "conversion to {} from {} not allowed",
+                            to.to_string(ns),
+                            from.to_string(ns)
+                        ),
+                    )
+                    .with_code(codes::EXPLICIT_CONVERSION),
+                );
                 Err(())
             } else {
                 Ok(Expression::Cast(*loc, to.clone(), Box::new(expr)))
@@ -1068,24 +1218,30 @@ fn cast_types(
         (Type::Uint(from_len), Type::Bytes(to_len))
         | (Type::Int(from_len), Type::Bytes(to_len)) => {
             if implicit {
-                diagnostics.push(Diagnostic::type_error(
-                    *loc,
-                    format!(
-                        "implicit conversion to {} from {} not allowed",
-                        to.to_string(ns),
-                        from.to_string(ns)
-                    ),
-                ));
+                diagnostics.push(
+                    Diagnostic::type_error(
+                        *loc,
+                        format!(
+                            "implicit conversion to {} from {} not allowed",
+                            to.to_string(ns),
+                            from.to_string(ns)
+                        ),
+                    )
+                    .with_code(codes::IMPLICIT_CONVERSION),
+                );
                 Err(())
             } else if *to_len as u16 * 8 != *from_len {
-                diagnostics.push(Diagnostic::type_error(
-                    *loc,
-                    format!(
-                        "conversion to {} from {} not allowed",
-                        to.to_string(ns),
-                        from.to_string(ns)
-                    ),
-                ));
+                diagnostics.push(
+                    Diagnostic::type_error(
+                        *loc,
+                        format!(
+                            "conversion to {} from {} not allowed",
+                            to.to_string(ns),
+                            from.to_string(ns)
+                        ),
+                    )
+                    .with_code(codes::EXPLICIT_CONVERSION),
+                );
                 Err(())
             } else {
                 Ok(Expression::Cast(*loc, to.clone(), Box::new(expr)))
@@ -1095,42 +1251,55 @@ fn cast_types(
         // cast and if it is the same size (i.e. no conversion required)
         (Type::Bytes(from_len), Type::Address(_)) => {
             if implicit {
-                diagnostics.push(Diagnostic::type_error(
-                    *loc,
-                    format!(
-                        "implicit conversion to {} from {} not allowed",
-                        to.to_string(ns),
-                        from.to_string(ns)
-                    ),
-                ));
+                diagnostics.push(
+                    Diagnostic::type_error(
+                        *loc,
+                        format!(
+                            "implicit conversion to {} from {} not allowed",
+                            to.to_string(ns),
+                            from.to_string(ns)
+                        ),
+                    )
+                    .with_code(codes::IMPLICIT_CONVERSION),
+                );
                 Err(())
             } else if *from_len as usize != ns.address_length {
-                diagnostics.push(Diagnostic::type_error(
-                    *loc,
-                    format!(
-                        "conversion to {} from {} not allowed",
-                        to.to_string(ns),
-                        from.to_string(ns)
-                    ),
-                ));
+                diagnostics.push(
+                    Diagnostic::type_error(
+                        *loc,
+                        format!(
+                            "conversion to {} from {} not allowed",
+                            to.to_string(ns),
+                            from.to_string(ns)
+                        ),
+                    )
+                    .with_code(codes::EXPLICIT_CONVERSION),
+                );
                 Err(())
             } else {
                 Ok(Expression::Cast(*loc, to.clone(), Box::new(expr)))
             }
         }
+        // before Solidity 0.5, every address is payable
+        (Type::Address(false), Type::Address(true)) if !ns.version_at_least(loc.0, 5) => {
+            Ok(Expression::Cast(*loc, to.clone(), Box::new(expr)))
+        }
         // Explicit conversion between contract and address is allowed
         (Type::Address(false), Type::Address(true))
         | (Type::Address(_), Type::Contract(_))
         | (Type::Contract(_), Type::Address(_)) => {
             if implicit {
-                diagnostics.push(Diagnostic::type_error(
-                    *loc,
-                    format!(
-                        "implicit conversion to {} from {} not allowed",
-                        to.to_string(ns),
-                        from.to_string(ns)
-                    ),
-                ));
+                diagnostics.push(
+                    Diagnostic::type_error(
+                        *loc,
+                        format!(
+                            "implicit conversion to {} from {} not allowed",
+                            to.to_string(ns),
+                            from.to_string(ns)
+                        ),
+                    )
+                    .with_code(codes::IMPLICIT_CONVERSION),
+                );
                 Err(())
             } else {
                 Ok(Expression::Cast(*loc, to.clone(), Box::new(expr)))
@@ -1139,14 +1308,17 @@ fn cast_types(
         // Conversion between contracts is allowed if it is a base
         (Type::Contract(contract_no_from), Type::Contract(contract_no_to)) => {
             if implicit && !is_base(*contract_no_to, *contract_no_from, ns) {
-                diagnostics.push(Diagnostic::type_error(
-                    *loc,
-                    format!(
-                        "implicit conversion not allowed since {} is not a base contract of {}",
-                        to.to_string(ns),
-                        from.to_string(ns)
-                    ),
-                ));
+                diagnostics.push(
+                    Diagnostic::type_error(
+                        *loc,
+                        format!(
+                            "implicit conversion not allowed since {} is not a base contract of {}",
+                            to.to_string(ns),
+                            from.to_string(ns)
+                        ),
+                    )
+                    .with_code(codes::IMPLICIT_CONVERSION),
+                );
                 Err(())
             } else {
                 Ok(Expression::Cast(*loc, to.clone(), Box::new(expr)))
@@ -1160,24 +1332,30 @@ fn cast_types(
         // cast and if it is the same size (i.e. no conversion required)
         (Type::Address(_), Type::Bytes(to_len)) => {
             if implicit {
-                diagnostics.push(Diagnostic::type_error(
-                    *loc,
-                    format!(
-                        "implicit conversion to {} from {} not allowed",
-                        to.to_string(ns),
-                        from.to_string(ns)
-                    ),
-                ));
+                diagnostics.push(
+                    Diagnostic::type_error(
+                        *loc,
+                        format!(
+                            "implicit conversion to {} from {} not allowed",
+                            to.to_string(ns),
+                            from.to_string(ns)
+                        ),
+                    )
+                    .with_code(codes::IMPLICIT_CONVERSION),
+                );
                 Err(())
             } else if *to_len as usize != ns.address_length {
-                diagnostics.push(Diagnostic::type_error(
-                    *loc,
-                    format!(
-                        "conversion to {} from {} not allowed",
-                        to.to_string(ns),
-                        from.to_string(ns)
-                    ),
-                ));
+                diagnostics.push(
+                    Diagnostic::type_error(
+                        *loc,
+                        format!(
+                            "conversion to {} from {} not allowed",
+                            to.to_string(ns),
+                            from.to_string(ns)
+                        ),
+                    )
+                    .with_code(codes::EXPLICIT_CONVERSION),
+                );
                 Err(())
             } else {
                 Ok(Expression::Cast(*loc, to.clone(), Box::new(expr)))
@@ -1207,10 +1385,13 @@ fn cast_types(
         }
         */
         (Type::Void, _) => {
-            diagnostics.push(Diagnostic::type_error(
-                *loc,
-                "function or method does not return a value".to_string(),
-            ));
+            diagnostics.push(
+                Diagnostic::type_error(
+                    *loc,
+                    "function or method does not return a value".to_string(),
+                )
+                .with_code(codes::RETURN_VALUES),
+            );
             Err(())
         }
         (
@@ -1238,48 +1419,60 @@ fn cast_types(
             },
         ) => {
             if from_params != to_params {
-                diagnostics.push(Diagnostic::type_error(
-                    *loc,
-                    format!(
-                        "function arguments do not match in conversion from ‘{}’ to ‘{}’",
-                        to.to_string(ns),
-                        from.to_string(ns)
-                    ),
-                ));
+                diagnostics.push(
+                    Diagnostic::type_error(
+                        *loc,
+                        format!(
+                            "function arguments do not match in conversion from ‘{}’ to ‘{}’",
+                            to.to_string(ns),
+                            from.to_string(ns)
+                        ),
+                    )
+                    .with_code(codes::EXPLICIT_CONVERSION),
+                );
                 Err(())
             } else if from_returns != to_returns {
-                diagnostics.push(Diagnostic::type_error(
-                    *loc,
-                    format!(
-                        "function returns do not match in conversion from ‘{}’ to ‘{}’",
-                        to.to_string(ns),
-                        from.to_string(ns)
-                    ),
-                ));
+                diagnostics.push(
+                    Diagnostic::type_error(
+                        *loc,
+                        format!(
+                            "function returns do not match in conversion from ‘{}’ to ‘{}’",
+                            to.to_string(ns),
+                            from.to_string(ns)
+                        ),
+                    )
+                    .with_code(codes::EXPLICIT_CONVERSION),
+                );
                 Err(())
             } else if !compatible_mutability(from_mutablity, to_mutablity) {
-                diagnostics.push(Diagnostic::type_error(
-                    *loc,
-                    format!(
-                        "function mutability not compatible in conversion from ‘{}’ to ‘{}’",
-                        from.to_string(ns),
-                        to.to_string(ns),
-                    ),
-                ));
+                diagnostics.push(
+                    Diagnostic::type_error(
+                        *loc,
+                        format!(
+                            "function mutability not compatible in conversion from ‘{}’ to ‘{}’",
+                            from.to_string(ns),
+                            to.to_string(ns),
+                        ),
+                    )
+                    .with_code(codes::EXPLICIT_CONVERSION),
+                );
                 Err(())
             } else {
                 Ok(Expression::Cast(*loc, to.clone(), Box::new(expr)))
             }
         }
         _ => {
-            diagnostics.push(Diagnostic::type_error(
-                *loc,
-                format!(
-                    "conversion from {} to {} not possible",
-                    from.to_string(ns),
-                    to.to_string(ns)
-                ),
-            ));
+            diagnostics.push(
+                Diagnostic::type_error(
+                    *loc,
+                    format!(
+                        "conversion from {} to {} not possible",
+                        from.to_string(ns),
+                        to.to_string(ns)
+                    ),
+                )
+                .with_code(codes::EXPLICIT_CONVERSION),
+            );
             Err(())
         }
     }
@@ -1356,10 +1549,13 @@ pub fn expression(
 
             for s in v {
                 if (s.hex.len() % 2) != 0 {
-                    diagnostics.push(Diagnostic::error(
-                        s.loc,
-                        format!("hex string \"{}\" has odd number of characters", s.hex),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(
+                            s.loc,
+                            format!("hex string \"{}\" has odd number of characters", s.hex),
+                        )
+                        .with_code(codes::LITERAL),
+                    );
                     return Err(());
                 } else {
                     result.extend_from_slice(&hex::decode(&s.hex).unwrap());
@@ -1383,7 +1579,7 @@ pub fn expression(
             if n.starts_with("0x") && !n.chars().any(|c| c == '_') && n.len() == 42 {
                 let address = to_hexstr_eip55(n);
 
-                if ns.target == Target::Ewasm {
+                if ns.target.is_ethereum() {
                     return if address == *n {
                         let s: String = address.chars().skip(2).collect();
 
@@ -1393,24 +1589,30 @@ pub fn expression(
                             BigInt::from_str_radix(&s, 16).unwrap(),
                         ))
                     } else {
-                        diagnostics.push(Diagnostic::error(
-                            *loc,
-                            format!(
-                                "address literal has incorrect checksum, expected ‘{}’",
-                                address
-                            ),
-                        ));
+                        diagnostics.push(
+                            Diagnostic::error(
+                                *loc,
+                                format!(
+                                    "address literal has incorrect checksum, expected ‘{}’",
+                                    address
+                                ),
+                            )
+                            .with_code(codes::LITERAL),
+                        );
                         Err(())
                     };
                 } else if address == *n {
                     // looks like ethereum address
-                    diagnostics.push(Diagnostic::error(
-                        *loc,
-                        format!(
-                            "ethereum address literal ‘{}’ not supported on target {}",
-                            n, ns.target
-                        ),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(
+                            *loc,
+                            format!(
+                                "ethereum address literal ‘{}’ not supported on target {}",
+                                n, ns.target
+                            ),
+                        )
+                        .with_code(codes::TARGET_NOT_SUPPORTED),
+                    );
                     return Err(());
                 }
             }
@@ -1423,13 +1625,16 @@ pub fn expression(
                 let expected_length = *length as usize * 2;
 
                 return if s.len() != expected_length {
-                    diagnostics.push(Diagnostic::error(
-                        *loc,
-                        format!(
-                            "hex literal {} must be {} digits for type ‘bytes{}’",
-                            n, expected_length, length,
-                        ),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(
+                            *loc,
+                            format!(
+                                "hex literal {} must be {} digits for type ‘bytes{}’",
+                                n, expected_length, length,
+                            ),
+                        )
+                        .with_code(codes::LITERAL),
+                    );
                     Err(())
                 } else {
                     Ok(Expression::NumberLiteral(
@@ -1453,14 +1658,17 @@ pub fn expression(
                 match address.from_base58() {
                     Ok(v) => {
                         if v.len() != 35 {
-                            diagnostics.push(Diagnostic::error(
-                                *loc,
-                                format!(
-                                    "address literal {} incorrect length of {}",
-                                    address,
-                                    v.len()
-                                ),
-                            ));
+                            diagnostics.push(
+                                Diagnostic::error(
+                                    *loc,
+                                    format!(
+                                        "address literal {} incorrect length of {}",
+                                        address,
+                                        v.len()
+                                    ),
+                                )
+                                .with_code(codes::LITERAL),
+                            );
                             return Err(());
                         }
 
@@ -1471,10 +1679,13 @@ pub fn expression(
                         let hash = hash.as_bytes();
 
                         if v[33] != hash[0] || v[34] != hash[1] {
-                            diagnostics.push(Diagnostic::error(
-                                *loc,
-                                format!("address literal {} hash incorrect checksum", address,),
-                            ));
+                            diagnostics.push(
+                                Diagnostic::error(
+                                    *loc,
+                                    format!("address literal {} hash incorrect checksum", address,),
+                                )
+                                .with_code(codes::LITERAL),
+                            );
                             return Err(());
                         }
 
@@ -1485,20 +1696,26 @@ pub fn expression(
                         ))
                     }
                     Err(FromBase58Error::InvalidBase58Length) => {
-                        diagnostics.push(Diagnostic::error(
-                            *loc,
-                            format!("address literal {} invalid base58 length", address),
-                        ));
+                        diagnostics.push(
+                            Diagnostic::error(
+                                *loc,
+                                format!("address literal {} invalid base58 length", address),
+                            )
+                            .with_code(codes::LITERAL),
+                        );
                         Err(())
                     }
                     Err(FromBase58Error::InvalidBase58Character(ch, pos)) => {
                         let mut loc = *loc;
                         loc.1 += pos;
                         loc.2 = loc.1;
-                        diagnostics.push(Diagnostic::error(
-                            loc,
-                            format!("address literal {} invalid character '{}'", address, ch),
-                        ));
+                        diagnostics.push(
+                            Diagnostic::error(
+                                loc,
+                                format!("address literal {} invalid character '{}'", address, ch),
+                            )
+                            .with_code(codes::LITERAL),
+                        );
                         Err(())
                     }
                 }
@@ -1506,14 +1723,17 @@ pub fn expression(
                 match address.from_base58() {
                     Ok(v) => {
                         if v.len() != 32 {
-                            diagnostics.push(Diagnostic::error(
-                                *loc,
-                                format!(
-                                    "address literal {} incorrect length of {}",
-                                    address,
-                                    v.len()
-                                ),
-                            ));
+                            diagnostics.push(
+                                Diagnostic::error(
+                                    *loc,
+                                    format!(
+                                        "address literal {} incorrect length of {}",
+                                        address,
+                                        v.len()
+                                    ),
+                                )
+                                .with_code(codes::LITERAL),
+                            );
                             Err(())
                         } else {
                             Ok(Expression::NumberLiteral(
@@ -1524,38 +1744,50 @@ pub fn expression(
                         }
                     }
                     Err(FromBase58Error::InvalidBase58Length) => {
-                        diagnostics.push(Diagnostic::error(
-                            *loc,
-                            format!("address literal {} invalid base58 length", address),
-                        ));
+                        diagnostics.push(
+                            Diagnostic::error(
+                                *loc,
+                                format!("address literal {} invalid base58 length", address),
+                            )
+                            .with_code(codes::LITERAL),
+                        );
                         Err(())
                     }
                     Err(FromBase58Error::InvalidBase58Character(ch, pos)) => {
                         let mut loc = *loc;
                         loc.1 += pos;
                         loc.2 = loc.1;
-                        diagnostics.push(Diagnostic::error(
-                            loc,
-                            format!("address literal {} invalid character '{}'", address, ch),
-                        ));
+                        diagnostics.push(
+                            Diagnostic::error(
+                                loc,
+                                format!("address literal {} invalid character '{}'", address, ch),
+                            )
+                            .with_code(codes::LITERAL),
+                        );
                         Err(())
                     }
                 }
             } else {
-                diagnostics.push(Diagnostic::error(
-                    *loc,
-                    format!("address literal {} not supported on {}", address, ns.target),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(
+                        *loc,
+                        format!("address literal {} not supported on {}", address, ns.target),
+                    )
+                    .with_code(codes::TARGET_NOT_SUPPORTED),
+                );
                 Err(())
             }
         }
         pt::Expression::Variable(id) => {
             if let Some(v) = symtable.find(&id.name) {
                 return if is_constant {
-                    diagnostics.push(Diagnostic::error(
-                        id.loc,
-                        format!("cannot read variable ‘{}’ in constant expression", id.name),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(
+                            id.loc,
+                            format!("cannot read variable ‘{}’ in constant expression", id.name),
+                        )
+                        .with_code(codes::CONSTANT_EXPRESSION),
+                    );
                     Err(())
                 } else {
                     Ok(Expression::Variable(id.loc, v.ty.clone(), v.pos))
@@ -1593,13 +1825,16 @@ pub fn expression(
                             var_no,
                         ))
                     } else if is_constant {
-                        diagnostics.push(Diagnostic::error(
-                            id.loc,
-                            format!(
-                                "cannot read contract variable ‘{}’ in constant expression",
-                                id.name
-                            ),
-                        ));
+                        diagnostics.push(
+                            Diagnostic::error(
+                                id.loc,
+                                format!(
+                                    "cannot read contract variable ‘{}’ in constant expression",
+                                    id.name
+                                ),
+                            )
+                            .with_code(codes::CONSTANT_EXPRESSION),
+                        );
                         Err(())
                     } else {
                         Ok(Expression::StorageVariable(
@@ -1656,13 +1891,27 @@ pub fn expression(
                     if name_matches == 1 {
                         Ok(expr.unwrap())
                     } else {
-                        diagnostics.push(Diagnostic::error(
-                            id.loc,
-                            format!("function ‘{}’ is overloaded", id.name),
-                        ));
+                        diagnostics.push(
+                            Diagnostic::error(
+                                id.loc,
+                                format!("function ‘{}’ is overloaded", id.name),
+                            )
+                            .with_code(codes::NO_MATCHING_OVERLOAD),
+                        );
                         Err(())
                     }
                 }
+                None if id.name == "now" => {
+                    diagnostics.push(
+                        Diagnostic::decl_error(
+                            id.loc,
+                            "‘now’ was removed in Solidity 0.7, use ‘block.timestamp’ instead"
+                                .to_string(),
+                        )
+                        .with_code(codes::NOT_FOUND),
+                    );
+                    Err(())
+                }
                 sym => {
                     let error = Namespace::wrong_symbol(sym, id);
                     diagnostics.push(error);
@@ -2119,10 +2368,13 @@ pub fn expression(
 
             // solc-0.5.13 does not allow either base or exp to be signed
             if base_type.is_signed_int() || exp_type.is_signed_int() {
-                diagnostics.push(Diagnostic::error(
-                    *loc,
-                    "exponation (**) is not allowed with signed types".to_string(),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(
+                        *loc,
+                        "exponation (**) is not allowed with signed types".to_string(),
+                    )
+                    .with_code(codes::TYPE_NOT_ALLOWED),
+                );
                 return Err(());
             }
 
@@ -2468,10 +2720,10 @@ pub fn expression(
         | pt::Expression::PostDecrement(loc, var)
         | pt::Expression::PreDecrement(loc, var) => {
             if is_constant {
-                diagnostics.push(Diagnostic::error(
-                    *loc,
-                    "operator not allowed in constant context".to_string(),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(*loc, "operator not allowed in constant context".to_string())
+                        .with_code(codes::CONSTANT_EXPRESSION),
+                );
                 return Err(());
             };
 
@@ -2481,10 +2733,13 @@ pub fn expression(
         // assignment
         pt::Expression::Assign(loc, var, e) => {
             if is_constant {
-                diagnostics.push(Diagnostic::error(
-                    *loc,
-                    "assignment not allowed in constant context".to_string(),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(
+                        *loc,
+                        "assignment not allowed in constant context".to_string(),
+                    )
+                    .with_code(codes::CONSTANT_EXPRESSION),
+                );
                 return Err(());
             };
 
@@ -2502,10 +2757,13 @@ pub fn expression(
         | pt::Expression::AssignShiftLeft(loc, var, e)
         | pt::Expression::AssignShiftRight(loc, var, e) => {
             if is_constant {
-                diagnostics.push(Diagnostic::error(
-                    *loc,
-                    "assignment not allowed in constant context".to_string(),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(
+                        *loc,
+                        "assignment not allowed in constant context".to_string(),
+                    )
+                    .with_code(codes::CONSTANT_EXPRESSION),
+                );
                 return Err(());
             };
 
@@ -2540,10 +2798,10 @@ pub fn expression(
                     );
                 }
                 Ok(_) => {
-                    diagnostics.push(Diagnostic::error(
-                        *loc,
-                        "struct or function expected".to_string(),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(*loc, "struct or function expected".to_string())
+                            .with_code(codes::WRONG_KIND_OF_NAME),
+                    );
                     return Err(());
                 }
                 _ => {}
@@ -2551,10 +2809,13 @@ pub fn expression(
 
             // not a struct literal, remove those errors and try resolving as function call
             if is_constant {
-                diagnostics.push(Diagnostic::error(
-                    expr.loc(),
-                    "cannot call function in constant expression".to_string(),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(
+                        expr.loc(),
+                        "cannot call function in constant expression".to_string(),
+                    )
+                    .with_code(codes::CONSTANT_EXPRESSION),
+                );
                 return Err(());
             }
 
@@ -2570,11 +2831,14 @@ pub fn expression(
             )?;
 
             if expr.tys().len() > 1 {
-                diagnostics.push(Diagnostic::error(
-                    *loc,
-                    "destucturing statement needed for function that returns multiple values"
-                        .to_string(),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(
+                        *loc,
+                        "destucturing statement needed for function that returns multiple values"
+                            .to_string(),
+                    )
+                    .with_code(codes::STATEMENT_NOT_ALLOWED),
+                );
                 return Err(());
             }
 
@@ -2582,10 +2846,13 @@ pub fn expression(
         }
         pt::Expression::New(loc, call) => {
             if is_constant {
-                diagnostics.push(Diagnostic::error(
-                    expr.loc(),
-                    "new not allowed in constant expression".to_string(),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(
+                        expr.loc(),
+                        "new not allowed in constant expression".to_string(),
+                    )
+                    .with_code(codes::CONSTANT_EXPRESSION),
+                );
                 return Err(());
             }
 
@@ -2614,10 +2881,10 @@ pub fn expression(
             }
         }
         pt::Expression::Delete(loc, _) => {
-            diagnostics.push(Diagnostic::error(
-                *loc,
-                "delete not allowed in expression".to_string(),
-            ));
+            diagnostics.push(
+                Diagnostic::error(*loc, "delete not allowed in expression".to_string())
+                    .with_code(codes::STATEMENT_NOT_ALLOWED),
+            );
             Err(())
         }
         pt::Expression::FunctionCall(loc, ty, args) => {
@@ -2640,16 +2907,16 @@ pub fn expression(
                 Ok(to) => {
                     // Cast
                     return if args.is_empty() {
-                        diagnostics.push(Diagnostic::error(
-                            *loc,
-                            "missing argument to cast".to_string(),
-                        ));
+                        diagnostics.push(
+                            Diagnostic::error(*loc, "missing argument to cast".to_string())
+                                .with_code(codes::WRONG_ARGUMENTS),
+                        );
                         Err(())
                     } else if args.len() > 1 {
-                        diagnostics.push(Diagnostic::error(
-                            *loc,
-                            "too many arguments to cast".to_string(),
-                        ));
+                        diagnostics.push(
+                            Diagnostic::error(*loc, "too many arguments to cast".to_string())
+                                .with_code(codes::WRONG_ARGUMENTS),
+                        );
                         Err(())
                     } else {
                         let expr = expression(
@@ -2682,21 +2949,24 @@ pub fn expression(
             )?;
 
             if expr.tys().len() > 1 {
-                diagnostics.push(Diagnostic::error(
-                    *loc,
-                    "destucturing statement needed for function that returns multiple values"
-                        .to_string(),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(
+                        *loc,
+                        "destucturing statement needed for function that returns multiple values"
+                            .to_string(),
+                    )
+                    .with_code(codes::STATEMENT_NOT_ALLOWED),
+                );
                 return Err(());
             }
 
             Ok(expr)
         }
         pt::Expression::ArraySubscript(loc, _, None) => {
-            diagnostics.push(Diagnostic::error(
-                *loc,
-                "expected expression before ‘]’ token".to_string(),
-            ));
+            diagnostics.push(
+                Diagnostic::error(*loc, "expected expression before ‘]’ token".to_string())
+                    .with_code(codes::ARRAY),
+            );
 
             Err(())
         }
@@ -2802,38 +3072,50 @@ pub fn expression(
             Ok(Expression::And(*loc, Box::new(l), Box::new(r)))
         }
         pt::Expression::Type(loc, _) => {
-            diagnostics.push(Diagnostic::error(*loc, "type not expected".to_owned()));
+            diagnostics.push(
+                Diagnostic::error(*loc, "type not expected".to_owned())
+                    .with_code(codes::WRONG_KIND_OF_NAME),
+            );
             Err(())
         }
         pt::Expression::List(loc, _) => {
-            diagnostics.push(Diagnostic::error(
-                *loc,
-                "lists only permitted in destructure statements".to_owned(),
-            ));
+            diagnostics.push(
+                Diagnostic::error(
+                    *loc,
+                    "lists only permitted in destructure statements".to_owned(),
+                )
+                .with_code(codes::STATEMENT_NOT_ALLOWED),
+            );
             Err(())
         }
         pt::Expression::FunctionCallBlock(loc, _, _) => {
-            diagnostics.push(Diagnostic::error(
-                *loc,
-                "unexpect block encountered".to_owned(),
-            ));
+            diagnostics.push(
+                Diagnostic::error(*loc, "unexpect block encountered".to_owned())
+                    .with_code(codes::STATEMENT_NOT_ALLOWED),
+            );
             Err(())
         }
         pt::Expression::Unit(loc, expr, unit) => {
             let n = match expr.as_ref() {
                 pt::Expression::NumberLiteral(_, n) => n,
                 pt::Expression::HexNumberLiteral(loc, _) => {
-                    diagnostics.push(Diagnostic::error(
-                        *loc,
-                        "hexadecimal numbers cannot be used with unit denominations".to_owned(),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(
+                            *loc,
+                            "hexadecimal numbers cannot be used with unit denominations".to_owned(),
+                        )
+                        .with_code(codes::LITERAL),
+                    );
                     return Err(());
                 }
                 _ => {
-                    diagnostics.push(Diagnostic::error(
-                        *loc,
-                        "unit denominations can only be used with number literals".to_owned(),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(
+                            *loc,
+                            "unit denominations can only be used with number literals".to_owned(),
+                        )
+                        .with_code(codes::LITERAL),
+                    );
                     return Err(());
                 }
             };
@@ -2843,12 +3125,15 @@ pub fn expression(
                 | pt::Unit::Finney(loc)
                 | pt::Unit::Szabo(loc)
                 | pt::Unit::Ether(loc)
-                    if ns.target != crate::Target::Ewasm =>
+                    if !ns.target.is_ethereum() =>
                 {
-                    diagnostics.push(Diagnostic::warning(
-                        *loc,
-                        "ethereum currency unit used while not targetting ethereum".to_owned(),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::warning(
+                            *loc,
+                            "ethereum currency unit used while not targetting ethereum".to_owned(),
+                        )
+                        .with_code(codes::ETHEREUM_UNITS),
+                    );
                 }
                 _ => (),
             }
@@ -2879,10 +3164,10 @@ pub fn expression(
                 Vec::new(),
             )),
             None => {
-                diagnostics.push(Diagnostic::error(
-                    *loc,
-                    "this not allowed outside contract".to_owned(),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(*loc, "this not allowed outside contract".to_owned())
+                        .with_code(codes::NOT_ACCESSIBLE),
+                );
                 Err(())
             }
         },
@@ -2906,46 +3191,55 @@ fn constructor(
     // will be code we're emitted here. So we end up with a crypto puzzle.
     let contract_no = match contract_no {
         Some(n) if n == no => {
-            diagnostics.push(Diagnostic::error(
-                *loc,
-                format!(
-                    "new cannot construct current contract ‘{}’",
-                    ns.contracts[contract_no.unwrap()].name
-                ),
-            ));
+            diagnostics.push(
+                Diagnostic::error(
+                    *loc,
+                    format!(
+                        "new cannot construct current contract ‘{}’",
+                        ns.contracts[contract_no.unwrap()].name
+                    ),
+                )
+                .with_code(codes::CONTRACT_CREATION),
+            );
             return Err(());
         }
         Some(n) => n,
         None => {
-            diagnostics.push(Diagnostic::error(
-                *loc,
-                "new contract not allowed in this context".to_string(),
-            ));
+            diagnostics.push(
+                Diagnostic::error(*loc, "new contract not allowed in this context".to_string())
+                    .with_code(codes::CONTRACT_CREATION),
+            );
             return Err(());
         }
     };
 
     if !ns.contracts[no].is_concrete() {
-        diagnostics.push(Diagnostic::error(
-            *loc,
-            format!(
-                "cannot construct ‘{}’ of type ‘{}’",
-                ns.contracts[no].name, ns.contracts[no].ty
-            ),
-        ));
+        diagnostics.push(
+            Diagnostic::error(
+                *loc,
+                format!(
+                    "cannot construct ‘{}’ of type ‘{}’",
+                    ns.contracts[no].name, ns.contracts[no].ty
+                ),
+            )
+            .with_code(codes::TYPE_NOT_ALLOWED),
+        );
 
         return Err(());
     }
 
     // check for circular references
     if circular_reference(no, contract_no, ns) {
-        diagnostics.push(Diagnostic::error(
-            *loc,
-            format!(
-                "circular reference creating contract ‘{}’",
-                ns.contracts[no].name
-            ),
-        ));
+        diagnostics.push(
+            Diagnostic::error(
+                *loc,
+                format!(
+                    "circular reference creating contract ‘{}’",
+                    ns.contracts[no].name
+                ),
+            )
+            .with_code(codes::CONTRACT_CREATION),
+        );
         return Err(());
     }
 
@@ -3002,15 +3296,18 @@ pub fn match_constructor_to_args(
         // ideally we shouldn't be cloning, but expression() takes a mutable reference to the namespace
         let params = ns.functions[function_no].params.clone();
 
-        if params.len() != args.len() {
-            diagnostics.push(Diagnostic::error(
-                *loc,
-                format!(
-                    "constructor expects {} arguments, {} provided",
-                    params.len(),
-                    args.len()
-                ),
-            ));
+        if params.len() != args.len() {
+            diagnostics.push(
+                Diagnostic::error(
+                    *loc,
+                    format!(
+                        "constructor expects {} arguments, {} provided",
+                        params.len(),
+                        args.len()
+                    ),
+                )
+                .with_code(codes::WRONG_ARGUMENTS),
+            );
             continue;
         }
 
@@ -3063,10 +3360,13 @@ pub fn match_constructor_to_args(
 
     if constructor_count != 1 {
         diagnostics.truncate(marker);
-        diagnostics.push(Diagnostic::error(
-            *loc,
-            "cannot find overloaded constructor which matches signature".to_string(),
-        ));
+        diagnostics.push(
+            Diagnostic::error(
+                *loc,
+                "cannot find overloaded constructor which matches signature".to_string(),
+            )
+            .with_code(codes::NO_MATCHING_OVERLOAD),
+        );
     }
 
     Err(())
@@ -3110,7 +3410,10 @@ pub fn constructor_named_args(
     let no = match ns.resolve_type(file_no, contract_no, false, ty, diagnostics)? {
         Type::Contract(n) => n,
         _ => {
-            diagnostics.push(Diagnostic::error(*loc, "contract expected".to_string()));
+            diagnostics.push(
+                Diagnostic::error(*loc, "contract expected".to_string())
+                    .with_code(codes::WRONG_KIND_OF_NAME),
+            );
             return Err(());
         }
     };
@@ -3120,46 +3423,55 @@ pub fn constructor_named_args(
     // will be code we're emitted here. So we end up with a crypto puzzle.
     let contract_no = match contract_no {
         Some(n) if n == no => {
-            diagnostics.push(Diagnostic::error(
-                *loc,
-                format!(
-                    "new cannot construct current contract ‘{}’",
-                    ns.contracts[contract_no.unwrap()].name
-                ),
-            ));
+            diagnostics.push(
+                Diagnostic::error(
+                    *loc,
+                    format!(
+                        "new cannot construct current contract ‘{}’",
+                        ns.contracts[contract_no.unwrap()].name
+                    ),
+                )
+                .with_code(codes::CONTRACT_CREATION),
+            );
             return Err(());
         }
         Some(n) => n,
         None => {
-            diagnostics.push(Diagnostic::error(
-                *loc,
-                "new contract not allowed in this context".to_string(),
-            ));
+            diagnostics.push(
+                Diagnostic::error(*loc, "new contract not allowed in this context".to_string())
+                    .with_code(codes::CONTRACT_CREATION),
+            );
             return Err(());
         }
     };
 
     if !ns.contracts[no].is_concrete() {
-        diagnostics.push(Diagnostic::error(
-            *loc,
-            format!(
-                "cannot construct ‘{}’ of type ‘{}’",
-                ns.contracts[no].name, ns.contracts[no].ty
-            ),
-        ));
+        diagnostics.push(
+            Diagnostic::error(
+                *loc,
+                format!(
+                    "cannot construct ‘{}’ of type ‘{}’",
+                    ns.contracts[no].name, ns.contracts[no].ty
+                ),
+            )
+            .with_code(codes::TYPE_NOT_ALLOWED),
+        );
 
         return Err(());
     }
 
     // check for circular references
     if circular_reference(no, contract_no, ns) {
-        diagnostics.push(Diagnostic::error(
-            *loc,
-            format!(
-                "circular reference creating contract ‘{}’",
-                ns.contracts[no].name
-            ),
-        ));
+        diagnostics.push(
+            Diagnostic::error(
+                *loc,
+                format!(
+                    "circular reference creating contract ‘{}’",
+                    ns.contracts[no].name
+                ),
+            )
+            .with_code(codes::CONTRACT_CREATION),
+        );
         return Err(());
     }
 
@@ -3171,12 +3483,15 @@ pub fn constructor_named_args(
 
     for arg in args {
         if let Some(prev) = arguments.get(&arg.name.name) {
-            diagnostics.push(Diagnostic::error_with_note(
-                *loc,
-                format!("duplicate argument name ‘{}’", arg.name.name),
-                prev.loc(),
-                String::from("location of previous argument"),
-            ));
+            diagnostics.push(
+                Diagnostic::error_with_note(
+                    *loc,
+                    format!("duplicate argument name ‘{}’", arg.name.name),
+                    prev.loc(),
+                    String::from("location of previous argument"),
+                )
+                .with_code(codes::ALREADY_DEFINED),
+            );
             return Err(());
         }
         arguments.insert(&arg.name.name, &arg.expr);
@@ -3196,14 +3511,17 @@ pub fn constructor_named_args(
         let params_len = ns.functions[function_no].params.len();
 
         if params_len != args.len() {
-            diagnostics.push(Diagnostic::error(
-                *loc,
-                format!(
-                    "constructor expects {} arguments, {} provided",
-                    params_len,
-                    args.len()
-                ),
-            ));
+            diagnostics.push(
+                Diagnostic::error(
+                    *loc,
+                    format!(
+                        "constructor expects {} arguments, {} provided",
+                        params_len,
+                        args.len()
+                    ),
+                )
+                .with_code(codes::WRONG_ARGUMENTS),
+            );
             continue;
         }
 
@@ -3217,10 +3535,13 @@ pub fn constructor_named_args(
                 Some(a) => a,
                 None => {
                     matches = false;
-                    diagnostics.push(Diagnostic::error(
-                        *loc,
-                        format!("missing argument ‘{}’ to constructor", param.name),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(
+                            *loc,
+                            format!("missing argument ‘{}’ to constructor", param.name),
+                        )
+                        .with_code(codes::WRONG_ARGUMENTS),
+                    );
                     break;
                 }
             };
@@ -3284,10 +3605,13 @@ pub fn constructor_named_args(
         1 => Err(()),
         _ => {
             diagnostics.truncate(marker);
-            diagnostics.push(Diagnostic::error(
-                *loc,
-                "cannot find overloaded constructor which matches signature".to_string(),
-            ));
+            diagnostics.push(
+                Diagnostic::error(
+                    *loc,
+                    "cannot find overloaded constructor which matches signature".to_string(),
+                )
+                .with_code(codes::NO_MATCHING_OVERLOAD),
+            );
 
             Err(())
         }
@@ -3306,18 +3630,21 @@ pub fn type_name_expr(
     resolve_to: Option<&Type>,
 ) -> Result<Expression, ()> {
     if args.is_empty() {
-        diagnostics.push(Diagnostic::error(
-            *loc,
-            "missing argument to type()".to_string(),
-        ));
+        diagnostics.push(
+            Diagnostic::error(*loc, "missing argument to type()".to_string())
+                .with_code(codes::WRONG_ARGUMENTS),
+        );
         return Err(());
     }
 
     if args.len() > 1 {
-        diagnostics.push(Diagnostic::error(
-            *loc,
-            format!("got {} arguments to type(), only one expected", args.len(),),
-        ));
+        diagnostics.push(
+            Diagnostic::error(
+                *loc,
+                format!("got {} arguments to type(), only one expected", args.len(),),
+            )
+            .with_code(codes::WRONG_ARGUMENTS),
+        );
         return Err(());
     }
 
@@ -3348,13 +3675,16 @@ pub fn type_name_expr(
             let contract_no = match contract_no {
                 Some(contract_no) => contract_no,
                 None => {
-                    diagnostics.push(Diagnostic::error(
-                        *loc,
-                        format!(
-                            "type().{} not permitted outside of contract code",
-                            field.name
-                        ),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(
+                            *loc,
+                            format!(
+                                "type().{} not permitted outside of contract code",
+                                field.name
+                            ),
+                        )
+                        .with_code(codes::NOT_ACCESSIBLE),
+                    );
                     return Err(());
                 }
             };
@@ -3367,18 +3697,21 @@ pub fn type_name_expr(
                         "containing our own contract code for ‘{}’ would generate infinite size contract",
                         ns.contracts[*no].name
                     ),
-                ));
+                ).with_code(codes::CONTRACT_CREATION));
                 return Err(());
             }
 
             if circular_reference(*no, contract_no, ns) {
-                diagnostics.push(Diagnostic::error(
-                    *loc,
-                    format!(
-                        "circular reference creating contract code for ‘{}’",
-                        ns.contracts[*no].name
-                    ),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(
+                        *loc,
+                        format!(
+                            "circular reference creating contract code for ‘{}’",
+                            ns.contracts[*no].name
+                        ),
+                    )
+                    .with_code(codes::CONTRACT_CREATION),
+                );
                 return Err(());
             }
 
@@ -3393,14 +3726,17 @@ pub fn type_name_expr(
             ))
         }
         _ => {
-            diagnostics.push(Diagnostic::error(
-                *loc,
-                format!(
-                    "type ‘{}’ does not have type function {}",
-                    ty.to_string(ns),
-                    field.name
-                ),
-            ));
+            diagnostics.push(
+                Diagnostic::error(
+                    *loc,
+                    format!(
+                        "type ‘{}’ does not have type function {}",
+                        ty.to_string(ns),
+                        field.name
+                    ),
+                )
+                .with_code(codes::NOT_FOUND),
+            );
             Err(())
         }
     }
@@ -3424,21 +3760,27 @@ pub fn new(
     match &ty {
         Type::Array(ty, dim) => {
             if dim.last().unwrap().is_some() {
-                diagnostics.push(Diagnostic::error(
-                    *loc,
-                    format!(
-                        "new cannot allocate fixed array type ‘{}’",
-                        ty.to_string(ns)
-                    ),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(
+                        *loc,
+                        format!(
+                            "new cannot allocate fixed array type ‘{}’",
+                            ty.to_string(ns)
+                        ),
+                    )
+                    .with_code(codes::CONTRACT_CREATION),
+                );
                 return Err(());
             }
 
             if let Type::Contract(_) = ty.as_ref() {
-                diagnostics.push(Diagnostic::error(
-                    *loc,
-                    format!("new cannot construct array of ‘{}’", ty.to_string(ns)),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(
+                        *loc,
+                        format!("new cannot construct array of ‘{}’", ty.to_string(ns)),
+                    )
+                    .with_code(codes::CONTRACT_CREATION),
+                );
                 return Err(());
             }
         }
@@ -3467,27 +3809,36 @@ pub fn new(
             );
         }
         _ => {
-            diagnostics.push(Diagnostic::error(
-                *loc,
-                format!("new cannot allocate type ‘{}’", ty.to_string(ns)),
-            ));
+            diagnostics.push(
+                Diagnostic::error(
+                    *loc,
+                    format!("new cannot allocate type ‘{}’", ty.to_string(ns)),
+                )
+                .with_code(codes::CONTRACT_CREATION),
+            );
             return Err(());
         }
     };
 
     if let Some(loc) = call_args_loc {
-        diagnostics.push(Diagnostic::error(
-            loc,
-            "constructor arguments not permitted for allocation".to_string(),
-        ));
+        diagnostics.push(
+            Diagnostic::error(
+                loc,
+                "constructor arguments not permitted for allocation".to_string(),
+            )
+            .with_code(codes::CONTRACT_CREATION),
+        );
         return Err(());
     }
 
     if args.len() != 1 {
-        diagnostics.push(Diagnostic::error(
-            *loc,
-            "new dynamic array should have a single length argument".to_string(),
-        ));
+        diagnostics.push(
+            Diagnostic::error(
+                *loc,
+                "new dynamic array should have a single length argument".to_string(),
+            )
+            .with_code(codes::CONTRACT_CREATION),
+        );
         return Err(());
     }
     let size_loc = args[0].loc();
@@ -3507,13 +3858,16 @@ pub fn new(
     let size_width = match &size_ty {
         Type::Uint(n) => n,
         _ => {
-            diagnostics.push(Diagnostic::error(
-                size_loc,
-                format!(
-                    "new size argument must be unsigned integer, not ‘{}’",
-                    size_ty.to_string(ns)
-                ),
-            ));
+            diagnostics.push(
+                Diagnostic::error(
+                    size_loc,
+                    format!(
+                        "new size argument must be unsigned integer, not ‘{}’",
+                        size_ty.to_string(ns)
+                    ),
+                )
+                .with_code(codes::CONTRACT_CREATION),
+            );
             return Err(());
         }
     };
@@ -3821,20 +4175,26 @@ pub fn assign_single(
     )?;
     match &var {
         Expression::ConstantVariable(loc, _, Some(contract_no), var_no) => {
-            diagnostics.push(Diagnostic::error(
-                *loc,
-                format!(
-                    "cannot assign to constant ‘{}’",
-                    ns.contracts[*contract_no].variables[*var_no].name
-                ),
-            ));
+            diagnostics.push(
+                Diagnostic::error(
+                    *loc,
+                    format!(
+                        "cannot assign to constant ‘{}’",
+                        ns.contracts[*contract_no].variables[*var_no].name
+                    ),
+                )
+                .with_code(codes::NOT_ASSIGNABLE),
+            );
             Err(())
         }
         Expression::ConstantVariable(loc, _, None, var_no) => {
-            diagnostics.push(Diagnostic::error(
-                *loc,
-                format!("cannot assign to constant ‘{}’", ns.constants[*var_no].name),
-            ));
+            diagnostics.push(
+                Diagnostic::error(
+                    *loc,
+                    format!("cannot assign to constant ‘{}’", ns.constants[*var_no].name),
+                )
+                .with_code(codes::NOT_ASSIGNABLE),
+            );
             Err(())
         }
         Expression::StorageVariable(loc, ty, _, _) => Ok(Expression::Assign(
@@ -3864,10 +4224,10 @@ pub fn assign_single(
                 Box::new(cast(&right.loc(), val, r_ty, true, ns, diagnostics)?),
             )),
             _ => {
-                diagnostics.push(Diagnostic::error(
-                    var.loc(),
-                    "expression is not assignable".to_string(),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(var.loc(), "expression is not assignable".to_string())
+                        .with_code(codes::NOT_ASSIGNABLE),
+                );
                 Err(())
             }
         },
@@ -3985,34 +4345,43 @@ fn assign_expr(
 
     match &var {
         Expression::ConstantVariable(loc, _, Some(contract_no), var_no) => {
-            diagnostics.push(Diagnostic::error(
-                *loc,
-                format!(
-                    "cannot assign to constant ‘{}’",
-                    ns.contracts[*contract_no].variables[*var_no].name
-                ),
-            ));
+            diagnostics.push(
+                Diagnostic::error(
+                    *loc,
+                    format!(
+                        "cannot assign to constant ‘{}’",
+                        ns.contracts[*contract_no].variables[*var_no].name
+                    ),
+                )
+                .with_code(codes::NOT_ASSIGNABLE),
+            );
             Err(())
         }
         Expression::ConstantVariable(loc, _, None, var_no) => {
-            diagnostics.push(Diagnostic::error(
-                *loc,
-                format!("cannot assign to constant ‘{}’", ns.constants[*var_no].name),
-            ));
+            diagnostics.push(
+                Diagnostic::error(
+                    *loc,
+                    format!("cannot assign to constant ‘{}’", ns.constants[*var_no].name),
+                )
+                .with_code(codes::NOT_ASSIGNABLE),
+            );
             Err(())
         }
         Expression::Variable(_, _, n) => {
             match var_ty {
                 Type::Bytes(_) | Type::Int(_) | Type::Uint(_) => (),
                 _ => {
-                    diagnostics.push(Diagnostic::error(
-                        var.loc(),
-                        format!(
-                            "variable ‘{}’ of incorrect type {}",
-                            symtable.get_name(*n),
-                            var_ty.to_string(ns)
-                        ),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(
+                            var.loc(),
+                            format!(
+                                "variable ‘{}’ of incorrect type {}",
+                                symtable.get_name(*n),
+                                var_ty.to_string(ns)
+                            ),
+                        )
+                        .with_code(codes::TYPE_NOT_ALLOWED),
+                    );
                     return Err(());
                 }
             };
@@ -4037,18 +4406,21 @@ fn assign_expr(
                     )?),
                 )),
                 _ => {
-                    diagnostics.push(Diagnostic::error(
-                        var.loc(),
-                        format!("assigning to incorrect type {}", r_ty.to_string(ns)),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(
+                            var.loc(),
+                            format!("assigning to incorrect type {}", r_ty.to_string(ns)),
+                        )
+                        .with_code(codes::NOT_ASSIGNABLE),
+                    );
                     Err(())
                 }
             },
             _ => {
-                diagnostics.push(Diagnostic::error(
-                    var.loc(),
-                    "expression is not assignable".to_string(),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(var.loc(), "expression is not assignable".to_string())
+                        .with_code(codes::NOT_ASSIGNABLE),
+                );
                 Err(())
             }
         },
@@ -4093,34 +4465,43 @@ fn incr_decr(
 
     match &var {
         Expression::ConstantVariable(loc, _, Some(contract_no), var_no) => {
-            diagnostics.push(Diagnostic::error(
-                *loc,
-                format!(
-                    "cannot assign to constant ‘{}’",
-                    ns.contracts[*contract_no].variables[*var_no].name
-                ),
-            ));
+            diagnostics.push(
+                Diagnostic::error(
+                    *loc,
+                    format!(
+                        "cannot assign to constant ‘{}’",
+                        ns.contracts[*contract_no].variables[*var_no].name
+                    ),
+                )
+                .with_code(codes::NOT_ASSIGNABLE),
+            );
             Err(())
         }
         Expression::ConstantVariable(loc, _, None, var_no) => {
-            diagnostics.push(Diagnostic::error(
-                *loc,
-                format!("cannot assign to constant ‘{}’", ns.constants[*var_no].name),
-            ));
+            diagnostics.push(
+                Diagnostic::error(
+                    *loc,
+                    format!("cannot assign to constant ‘{}’", ns.constants[*var_no].name),
+                )
+                .with_code(codes::NOT_ASSIGNABLE),
+            );
             Err(())
         }
         Expression::Variable(_, ty, n) => {
             match ty {
                 Type::Int(_) | Type::Uint(_) => (),
                 _ => {
-                    diagnostics.push(Diagnostic::error(
-                        var.loc(),
-                        format!(
-                            "variable ‘{}’ of incorrect type {}",
-                            symtable.get_name(*n),
-                            var_ty.to_string(ns)
-                        ),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(
+                            var.loc(),
+                            format!(
+                                "variable ‘{}’ of incorrect type {}",
+                                symtable.get_name(*n),
+                                var_ty.to_string(ns)
+                            ),
+                        )
+                        .with_code(codes::TYPE_NOT_ALLOWED),
+                    );
                     return Err(());
                 }
             };
@@ -4130,18 +4511,21 @@ fn incr_decr(
             Type::Ref(r_ty) | Type::StorageRef(r_ty) => match r_ty.as_ref() {
                 Type::Int(_) | Type::Uint(_) => Ok(op(var, r_ty.as_ref().clone())),
                 _ => {
-                    diagnostics.push(Diagnostic::error(
-                        var.loc(),
-                        format!("assigning to incorrect type {}", r_ty.to_string(ns)),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(
+                            var.loc(),
+                            format!("assigning to incorrect type {}", r_ty.to_string(ns)),
+                        )
+                        .with_code(codes::NOT_ASSIGNABLE),
+                    );
                     Err(())
                 }
             },
             _ => {
-                diagnostics.push(Diagnostic::error(
-                    var.loc(),
-                    "expression is not modifiable".to_string(),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(var.loc(), "expression is not modifiable".to_string())
+                        .with_code(codes::NOT_ASSIGNABLE),
+                );
                 Err(())
             }
         },
@@ -4215,10 +4599,13 @@ fn enum_value(
                 BigInt::from_usize(*val).unwrap(),
             ))),
             None => {
-                diagnostics.push(Diagnostic::error(
-                    id.loc,
-                    format!("enum {} does not have value {}", ns.enums[e], id.name),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(
+                        id.loc,
+                        format!("enum {} does not have value {}", ns.enums[e], id.name),
+                    )
+                    .with_code(codes::NOT_FOUND),
+                );
                 Err(())
             }
         }
@@ -4283,35 +4670,45 @@ fn member_access(
 
                         return match name_matches {
                             0 => {
-                                diagnostics.push(Diagnostic::error(
-                                    e.loc(),
-                                    format!(
-                                        "contract ‘{}’ does not have a function called ‘{}’",
-                                        ns.contracts[call_contract_no].name, id.name,
-                                    ),
-                                ));
+                                diagnostics.push(
+                                    Diagnostic::error(
+                                        e.loc(),
+                                        format!(
+                                            "contract ‘{}’ does not have a function called ‘{}’",
+                                            ns.contracts[call_contract_no].name, id.name,
+                                        ),
+                                    )
+                                    .with_code(codes::NOT_FOUND),
+                                );
                                 Err(())
                             }
                             1 => expr,
                             _ => {
-                                diagnostics.push(Diagnostic::error(
-                                    e.loc(),
-                                    format!(
-                                        "function ‘{}’ of contract ‘{}’ is overloaded",
-                                        id.name, ns.contracts[call_contract_no].name,
-                                    ),
-                                ));
+                                diagnostics.push(
+                                    Diagnostic::error(
+                                        e.loc(),
+                                        format!(
+                                            "function ‘{}’ of contract ‘{}’ is overloaded",
+                                            id.name, ns.contracts[call_contract_no].name,
+                                        ),
+                                    )
+                                    .with_code(codes::NO_MATCHING_OVERLOAD),
+                                );
                                 Err(())
                             }
                         };
                     } else {
-                        diagnostics.push(Diagnostic::error(
-                            id.loc,
-                            format!(
-                                "contract ‘{}’ is not a base of ‘{}’",
-                                ns.contracts[call_contract_no].name, ns.contracts[contract_no].name,
-                            ),
-                        ));
+                        diagnostics.push(
+                            Diagnostic::error(
+                                id.loc,
+                                format!(
+                                    "contract ‘{}’ is not a base of ‘{}’",
+                                    ns.contracts[call_contract_no].name,
+                                    ns.contracts[contract_no].name,
+                                ),
+                            )
+                            .with_code(codes::INHERITANCE),
+                        );
                         return Err(());
                     }
                 }
@@ -4398,13 +4795,16 @@ fn member_access(
                         field_no,
                     ))
                 } else {
-                    diagnostics.push(Diagnostic::error(
-                        id.loc,
-                        format!(
-                            "struct ‘{}’ does not have a field called ‘{}’",
-                            ns.structs[n].name, id.name
-                        ),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(
+                            id.loc,
+                            format!(
+                                "struct ‘{}’ does not have a field called ‘{}’",
+                                ns.structs[n].name, id.name
+                            ),
+                        )
+                        .with_code(codes::NOT_FOUND),
+                    );
                     Err(())
                 }
             }
@@ -4460,13 +4860,16 @@ fn member_access(
                     i,
                 ));
             } else {
-                diagnostics.push(Diagnostic::error(
-                    id.loc,
-                    format!(
-                        "struct ‘{}’ does not have a field called ‘{}’",
-                        ns.structs[n], id.name
-                    ),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(
+                        id.loc,
+                        format!(
+                            "struct ‘{}’ does not have a field called ‘{}’",
+                            ns.structs[n], id.name
+                        ),
+                    )
+                    .with_code(codes::NOT_FOUND),
+                );
                 return Err(());
             }
         }
@@ -4485,11 +4888,22 @@ fn member_access(
                         diagnostics.push(Diagnostic::error(
                                     expr.loc(),
                                         "substrate can only retrieve balance of this, like ‘address(this).balance’".to_string(),
-                                ));
+                                ).with_code(codes::TARGET_NOT_SUPPORTED));
                         return Err(());
                     }
                 }
 
+                if !builtin::target_supports(Builtin::Balance, ns) {
+                    diagnostics.push(
+                        Diagnostic::error(
+                            *loc,
+                            format!("‘balance’ not supported on {}", ns.target),
+                        )
+                        .with_code(codes::TARGET_NOT_SUPPORTED),
+                    );
+                    return Err(());
+                }
+
                 return Ok(Expression::Builtin(
                     *loc,
                     vec![Type::Value],
@@ -4527,24 +4941,30 @@ fn member_access(
 
             #[allow(clippy::comparison_chain)]
             return if name_matches == 0 {
-                diagnostics.push(Diagnostic::error(
-                    id.loc,
-                    format!(
-                        "contract ‘{}’ has no public function ‘{}’",
-                        ns.contracts[ref_contract_no].name, id.name
-                    ),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(
+                        id.loc,
+                        format!(
+                            "contract ‘{}’ has no public function ‘{}’",
+                            ns.contracts[ref_contract_no].name, id.name
+                        ),
+                    )
+                    .with_code(codes::NOT_FOUND),
+                );
                 Err(())
             } else if name_matches == 1 {
                 ext_expr
             } else {
-                diagnostics.push(Diagnostic::error(
-                    id.loc,
-                    format!(
-                        "function ‘{}’ of contract ‘{}’ is overloaded",
-                        id.name, ns.contracts[ref_contract_no].name
-                    ),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(
+                        id.loc,
+                        format!(
+                            "function ‘{}’ of contract ‘{}’ is overloaded",
+                            id.name, ns.contracts[ref_contract_no].name
+                        ),
+                    )
+                    .with_code(codes::NO_MATCHING_OVERLOAD),
+                );
                 Err(())
             };
         }
@@ -4569,7 +4989,9 @@ fn member_access(
         _ => (),
     }
 
-    diagnostics.push(Diagnostic::error(*loc, format!("‘{}’ not found", id.name)));
+    diagnostics.push(
+        Diagnostic::error(*loc, format!("‘{}’ not found", id.name)).with_code(codes::NOT_FOUND),
+    );
 
     Err(())
 }
@@ -4632,13 +5054,16 @@ fn array_subscript(
     match index_expr.ty() {
         Type::Uint(_) => (),
         _ => {
-            diagnostics.push(Diagnostic::error(
-                *loc,
-                format!(
-                    "array subscript must be an unsigned integer, not ‘{}’",
-                    index_expr.ty().to_string(ns)
-                ),
-            ));
+            diagnostics.push(
+                Diagnostic::error(
+                    *loc,
+                    format!(
+                        "array subscript must be an unsigned integer, not ‘{}’",
+                        index_expr.ty().to_string(ns)
+                    ),
+                )
+                .with_code(codes::ARRAY),
+            );
             return Err(());
         }
     };
@@ -4686,17 +5111,20 @@ fn array_subscript(
             }
         }
         Type::String => {
-            diagnostics.push(Diagnostic::error(
-                array.loc(),
-                "array subscript is not permitted on string".to_string(),
-            ));
+            diagnostics.push(
+                Diagnostic::error(
+                    array.loc(),
+                    "array subscript is not permitted on string".to_string(),
+                )
+                .with_code(codes::ARRAY),
+            );
             Err(())
         }
         _ => {
-            diagnostics.push(Diagnostic::error(
-                array.loc(),
-                "expression is not an array".to_string(),
-            ));
+            diagnostics.push(
+                Diagnostic::error(array.loc(), "expression is not an array".to_string())
+                    .with_code(codes::ARRAY),
+            );
             Err(())
         }
     }
@@ -4717,15 +5145,18 @@ fn struct_literal(
     let struct_def = ns.structs[struct_no].clone();
 
     if args.len() != struct_def.fields.len() {
-        diagnostics.push(Diagnostic::error(
-            *loc,
-            format!(
-                "struct ‘{}’ has {} fields, not {}",
-                struct_def.name,
-                struct_def.fields.len(),
-                args.len()
-            ),
-        ));
+        diagnostics.push(
+            Diagnostic::error(
+                *loc,
+                format!(
+                    "struct ‘{}’ has {} fields, not {}",
+                    struct_def.name,
+                    struct_def.fields.len(),
+                    args.len()
+                ),
+            )
+            .with_code(codes::WRONG_ARGUMENTS),
+        );
         Err(())
     } else {
         let mut fields = Vec::new();
@@ -4798,21 +5229,27 @@ fn call_function_type(
     } = ty
     {
         if let Some(loc) = call_args_loc {
-            diagnostics.push(Diagnostic::error(
-                loc,
-                "call arguments not permitted for internal calls".to_string(),
-            ));
+            diagnostics.push(
+                Diagnostic::error(
+                    loc,
+                    "call arguments not permitted for internal calls".to_string(),
+                )
+                .with_code(codes::CALL_ARGUMENTS),
+            );
         }
 
         if params.len() != args.len() {
-            diagnostics.push(Diagnostic::error(
-                *loc,
-                format!(
-                    "function expects {} arguments, {} provided",
-                    params.len(),
-                    args.len()
-                ),
-            ));
+            diagnostics.push(
+                Diagnostic::error(
+                    *loc,
+                    format!(
+                        "function expects {} arguments, {} provided",
+                        params.len(),
+                        args.len()
+                    ),
+                )
+                .with_code(codes::WRONG_ARGUMENTS),
+            );
             return Err(());
         }
 
@@ -4871,13 +5308,16 @@ fn call_function_type(
             if !value.const_zero(contract_no, ns)
                 && !matches!(mutability, Some(pt::StateMutability::Payable(_)))
             {
-                diagnostics.push(Diagnostic::error(
-                    *loc,
-                    format!(
-                        "sending value to function type ‘{}’ which is not payable",
-                        function.ty().to_string(ns),
-                    ),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(
+                        *loc,
+                        format!(
+                            "sending value to function type ‘{}’ which is not payable",
+                            function.ty().to_string(ns),
+                        ),
+                    )
+                    .with_code(codes::CALL_ARGUMENTS),
+                );
                 return Err(());
             }
 
@@ -4891,14 +5331,17 @@ fn call_function_type(
         };
 
         if params.len() != args.len() {
-            diagnostics.push(Diagnostic::error(
-                *loc,
-                format!(
-                    "function expects {} arguments, {} provided",
-                    params.len(),
-                    args.len()
-                ),
-            ));
+            diagnostics.push(
+                Diagnostic::error(
+                    *loc,
+                    format!(
+                        "function expects {} arguments, {} provided",
+                        params.len(),
+                        args.len()
+                    ),
+                )
+                .with_code(codes::WRONG_ARGUMENTS),
+            );
             return Err(());
         }
 
@@ -4940,10 +5383,10 @@ fn call_function_type(
             value,
         })
     } else {
-        diagnostics.push(Diagnostic::error(
-            *loc,
-            "expression is not a function".to_string(),
-        ));
+        diagnostics.push(
+            Diagnostic::error(*loc, "expression is not a function".to_string())
+                .with_code(codes::WRONG_KIND_OF_NAME),
+        );
         Err(())
     }
 }
@@ -5041,15 +5484,18 @@ pub fn call_position_args(
         let params_len = func.params.len();
 
         if params_len != args.len() {
-            errors.push(Diagnostic::error(
-                *loc,
-                format!(
-                    "{} expects {} arguments, {} provided",
-                    func.ty,
-                    params_len,
-                    args.len()
-                ),
-            ));
+            errors.push(
+                Diagnostic::error(
+                    *loc,
+                    format!(
+                        "{} expects {} arguments, {} provided",
+                        func.ty,
+                        params_len,
+                        args.len()
+                    ),
+                )
+                .with_code(codes::WRONG_ARGUMENTS),
+            );
             continue;
         }
 
@@ -5093,12 +5539,15 @@ pub fn call_position_args(
         let func = &ns.functions[function_no];
 
         if func.contract_no != contract_no && func.is_private() {
-            errors.push(Diagnostic::error_with_note(
-                *loc,
-                format!("cannot call private {}", func.ty),
-                func.loc,
-                format!("declaration of {} ‘{}’", func.ty, func.name),
-            ));
+            errors.push(
+                Diagnostic::error_with_note(
+                    *loc,
+                    format!("cannot call private {}", func.ty),
+                    func.loc,
+                    format!("declaration of {} ‘{}’", func.ty, func.name),
+                )
+                .with_code(codes::NOT_ACCESSIBLE),
+            );
 
             continue;
         }
@@ -5126,23 +5575,26 @@ pub fn call_position_args(
     match name_matches {
         0 => {
             if func_ty == pt::FunctionTy::Modifier {
-                diagnostics.push(Diagnostic::error(
-                    id.loc,
-                    format!("unknown modifier ‘{}’", id.name),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(id.loc, format!("unknown modifier ‘{}’", id.name))
+                        .with_code(codes::NOT_FOUND),
+                );
             } else {
-                diagnostics.push(Diagnostic::error(
-                    id.loc,
-                    format!("unknown {} or type ‘{}’", func_ty, id.name),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(id.loc, format!("unknown {} or type ‘{}’", func_ty, id.name))
+                        .with_code(codes::NOT_FOUND),
+                );
             }
         }
         1 => diagnostics.extend(errors),
         _ => {
-            diagnostics.push(Diagnostic::error(
-                *loc,
-                format!("cannot find overloaded {} which matches signature", func_ty),
-            ));
+            diagnostics.push(
+                Diagnostic::error(
+                    *loc,
+                    format!("cannot find overloaded {} which matches signature", func_ty),
+                )
+                .with_code(codes::NO_MATCHING_OVERLOAD),
+            );
         }
     }
 
@@ -5166,10 +5618,13 @@ fn function_call_with_named_args(
 
     for arg in args {
         if arguments.contains_key(&arg.name.name) {
-            diagnostics.push(Diagnostic::error(
-                arg.name.loc,
-                format!("duplicate argument with name ‘{}’", arg.name.name),
-            ));
+            diagnostics.push(
+                Diagnostic::error(
+                    arg.name.loc,
+                    format!("duplicate argument with name ‘{}’", arg.name.name),
+                )
+                .with_code(codes::WRONG_ARGUMENTS),
+            );
             return Err(());
         }
 
@@ -5193,14 +5648,17 @@ fn function_call_with_named_args(
         let params_len = func.params.len();
 
         if params_len != args.len() {
-            errors.push(Diagnostic::error(
-                *loc,
-                format!(
-                    "function expects {} arguments, {} provided",
-                    params_len,
-                    args.len()
-                ),
-            ));
+            errors.push(
+                Diagnostic::error(
+                    *loc,
+                    format!(
+                        "function expects {} arguments, {} provided",
+                        params_len,
+                        args.len()
+                    ),
+                )
+                .with_code(codes::WRONG_ARGUMENTS),
+            );
             continue;
         }
 
@@ -5215,13 +5673,16 @@ fn function_call_with_named_args(
                 Some(a) => a,
                 None => {
                     matches = false;
-                    diagnostics.push(Diagnostic::error(
-                        *loc,
-                        format!(
-                            "missing argument ‘{}’ to function ‘{}’",
-                            param.name, id.name,
-                        ),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(
+                            *loc,
+                            format!(
+                                "missing argument ‘{}’ to function ‘{}’",
+                                param.name, id.name,
+                            ),
+                        )
+                        .with_code(codes::WRONG_ARGUMENTS),
+                    );
                     break;
                 }
             };
@@ -5261,12 +5722,15 @@ fn function_call_with_named_args(
         let func = &ns.functions[function_no];
 
         if func.contract_no != contract_no && func.is_private() {
-            errors.push(Diagnostic::error_with_note(
-                *loc,
-                "cannot call private function".to_string(),
-                func.loc,
-                format!("declaration of function ‘{}’", func.name),
-            ));
+            errors.push(
+                Diagnostic::error_with_note(
+                    *loc,
+                    "cannot call private function".to_string(),
+                    func.loc,
+                    format!("declaration of function ‘{}’", func.name),
+                )
+                .with_code(codes::NOT_ACCESSIBLE),
+            );
 
             continue;
         }
@@ -5293,17 +5757,20 @@ fn function_call_with_named_args(
 
     match name_matches {
         0 => {
-            diagnostics.push(Diagnostic::error(
-                id.loc,
-                format!("unknown function or type ‘{}’", id.name),
-            ));
+            diagnostics.push(
+                Diagnostic::error(id.loc, format!("unknown function or type ‘{}’", id.name))
+                    .with_code(codes::NOT_FOUND),
+            );
         }
         1 => diagnostics.extend(errors),
         _ => {
-            diagnostics.push(Diagnostic::error(
-                *loc,
-                "cannot find overloaded function which matches signature".to_string(),
-            ));
+            diagnostics.push(
+                Diagnostic::error(
+                    *loc,
+                    "cannot find overloaded function which matches signature".to_string(),
+                )
+                .with_code(codes::NO_MATCHING_OVERLOAD),
+            );
         }
     }
 
@@ -5325,15 +5792,18 @@ fn named_struct_literal(
     let struct_def = ns.structs[struct_no].clone();
 
     if args.len() != struct_def.fields.len() {
-        diagnostics.push(Diagnostic::error(
-            *loc,
-            format!(
-                "struct ‘{}’ has {} fields, not {}",
-                struct_def.name,
-                struct_def.fields.len(),
-                args.len()
-            ),
-        ));
+        diagnostics.push(
+            Diagnostic::error(
+                *loc,
+                format!(
+                    "struct ‘{}’ has {} fields, not {}",
+                    struct_def.name,
+                    struct_def.fields.len(),
+                    args.len()
+                ),
+            )
+            .with_code(codes::WRONG_ARGUMENTS),
+        );
         Err(())
     } else {
         let mut fields = Vec::new();
@@ -5360,13 +5830,16 @@ fn named_struct_literal(
                     fields[i] = cast(loc, expr, &f.ty, true, ns, diagnostics)?;
                 }
                 None => {
-                    diagnostics.push(Diagnostic::error(
-                        a.name.loc,
-                        format!(
-                            "struct ‘{}’ has no field ‘{}’",
-                            struct_def.name, a.name.name,
-                        ),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(
+                            a.name.loc,
+                            format!(
+                                "struct ‘{}’ has no field ‘{}’",
+                                struct_def.name, a.name.name,
+                            ),
+                        )
+                        .with_code(codes::NOT_FOUND),
+                    );
                     return Err(());
                 }
             }
@@ -5393,10 +5866,10 @@ fn method_call_pos_args(
     if let pt::Expression::Variable(namespace) = var {
         if builtin::is_builtin_call(Some(&namespace.name), &func.name, ns) {
             if let Some(loc) = call_args_loc {
-                diagnostics.push(Diagnostic::error(
-                    loc,
-                    "call arguments not allowed on builtins".to_string(),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(loc, "call arguments not allowed on builtins".to_string())
+                        .with_code(codes::CALL_ARGUMENTS),
+                );
                 return Err(());
             }
 
@@ -5417,10 +5890,13 @@ fn method_call_pos_args(
         if namespace.name == "super" {
             if let Some(cur_contract_no) = contract_no {
                 if let Some(loc) = call_args_loc {
-                    diagnostics.push(Diagnostic::error(
-                        loc,
-                        "call arguments not allowed on super calls".to_string(),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(
+                            loc,
+                            "call arguments not allowed on super calls".to_string(),
+                        )
+                        .with_code(codes::CALL_ARGUMENTS),
+                    );
                     return Err(());
                 }
 
@@ -5438,10 +5914,10 @@ fn method_call_pos_args(
                     diagnostics,
                 );
             } else {
-                diagnostics.push(Diagnostic::error(
-                    *loc,
-                    "super not available outside contracts".to_string(),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(*loc, "super not available outside contracts".to_string())
+                        .with_code(codes::NOT_ACCESSIBLE),
+                );
                 return Err(());
             }
         }
@@ -5450,10 +5926,13 @@ fn method_call_pos_args(
         if let Some(call_contract_no) = ns.resolve_contract(file_no, &namespace) {
             if ns.contracts[call_contract_no].is_library() {
                 if let Some(loc) = call_args_loc {
-                    diagnostics.push(Diagnostic::error(
-                        loc,
-                        "call arguments not allowed on library calls".to_string(),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(
+                            loc,
+                            "call arguments not allowed on library calls".to_string(),
+                        )
+                        .with_code(codes::CALL_ARGUMENTS),
+                    );
                     return Err(());
                 }
 
@@ -5476,10 +5955,13 @@ fn method_call_pos_args(
             if let Some(contract_no) = contract_no {
                 if is_base(call_contract_no, contract_no, ns) {
                     if let Some(loc) = call_args_loc {
-                        diagnostics.push(Diagnostic::error(
-                            loc,
-                            "call arguments not allowed on internal calls".to_string(),
-                        ));
+                        diagnostics.push(
+                            Diagnostic::error(
+                                loc,
+                                "call arguments not allowed on internal calls".to_string(),
+                            )
+                            .with_code(codes::CALL_ARGUMENTS),
+                        );
                         return Err(());
                     }
 
@@ -5516,10 +5998,10 @@ fn method_call_pos_args(
     if matches!(var_ty, Type::Bytes(_) | Type::String) && func.name == "format" {
         return if let pt::Expression::StringLiteral(bs) = var {
             if let Some(loc) = call_args_loc {
-                diagnostics.push(Diagnostic::error(
-                    loc,
-                    "call arguments not allowed on builtins".to_string(),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(loc, "call arguments not allowed on builtins".to_string())
+                        .with_code(codes::CALL_ARGUMENTS),
+                );
                 return Err(());
             }
 
@@ -5534,10 +6016,10 @@ fn method_call_pos_args(
                 diagnostics,
             )
         } else {
-            diagnostics.push(Diagnostic::error(
-                *loc,
-                "format only allowed on string literals".to_string(),
-            ));
+            diagnostics.push(
+                Diagnostic::error(*loc, "format only allowed on string literals".to_string())
+                    .with_code(codes::FORMAT_STRING),
+            );
             Err(())
         };
     }
@@ -5546,19 +6028,22 @@ fn method_call_pos_args(
         match ty.as_ref() {
             Type::Array(_, dim) => {
                 if let Some(loc) = call_args_loc {
-                    diagnostics.push(Diagnostic::error(
-                        loc,
-                        "call arguments not allowed on arrays".to_string(),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(loc, "call arguments not allowed on arrays".to_string())
+                            .with_code(codes::CALL_ARGUMENTS),
+                    );
                     return Err(());
                 }
 
                 if func.name == "push" {
                     if dim.last().unwrap().is_some() {
-                        diagnostics.push(Diagnostic::error(
-                            func.loc,
-                            "method ‘push()’ not allowed on fixed length array".to_string(),
-                        ));
+                        diagnostics.push(
+                            Diagnostic::error(
+                                func.loc,
+                                "method ‘push()’ not allowed on fixed length array".to_string(),
+                            )
+                            .with_code(codes::ARRAY),
+                        );
                         return Err(());
                     }
 
@@ -5597,10 +6082,13 @@ fn method_call_pos_args(
                             }
                         }
                         _ => {
-                            diagnostics.push(Diagnostic::error(
-                                func.loc,
-                                "method ‘push()’ takes at most 1 argument".to_string(),
-                            ));
+                            diagnostics.push(
+                                Diagnostic::error(
+                                    func.loc,
+                                    "method ‘push()’ takes at most 1 argument".to_string(),
+                                )
+                                .with_code(codes::WRONG_ARGUMENTS),
+                            );
                             return Err(());
                         }
                     };
@@ -5614,19 +6102,25 @@ fn method_call_pos_args(
                 }
                 if func.name == "pop" {
                     if dim.last().unwrap().is_some() {
-                        diagnostics.push(Diagnostic::error(
-                            func.loc,
-                            "method ‘pop()’ not allowed on fixed length array".to_string(),
-                        ));
+                        diagnostics.push(
+                            Diagnostic::error(
+                                func.loc,
+                                "method ‘pop()’ not allowed on fixed length array".to_string(),
+                            )
+                            .with_code(codes::ARRAY),
+                        );
 
                         return Err(());
                     }
 
                     if !args.is_empty() {
-                        diagnostics.push(Diagnostic::error(
-                            func.loc,
-                            "method ‘pop()’ does not take any arguments".to_string(),
-                        ));
+                        diagnostics.push(
+                            Diagnostic::error(
+                                func.loc,
+                                "method ‘pop()’ does not take any arguments".to_string(),
+                            )
+                            .with_code(codes::WRONG_ARGUMENTS),
+                        );
                         return Err(());
                     }
 
@@ -5643,10 +6137,10 @@ fn method_call_pos_args(
             }
             Type::DynamicBytes => {
                 if let Some(loc) = call_args_loc {
-                    diagnostics.push(Diagnostic::error(
-                        loc,
-                        "call arguments not allowed on bytes".to_string(),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(loc, "call arguments not allowed on bytes".to_string())
+                            .with_code(codes::CALL_ARGUMENTS),
+                    );
                     return Err(());
                 }
 
@@ -5681,10 +6175,13 @@ fn method_call_pos_args(
                         }
                         0 => elem_ty,
                         _ => {
-                            diagnostics.push(Diagnostic::error(
-                                func.loc,
-                                "method ‘push()’ takes at most 1 argument".to_string(),
-                            ));
+                            diagnostics.push(
+                                Diagnostic::error(
+                                    func.loc,
+                                    "method ‘push()’ takes at most 1 argument".to_string(),
+                                )
+                                .with_code(codes::WRONG_ARGUMENTS),
+                            );
                             return Err(());
                         }
                     };
@@ -5699,10 +6196,13 @@ fn method_call_pos_args(
 
                 if func.name == "pop" {
                     if !args.is_empty() {
-                        diagnostics.push(Diagnostic::error(
-                            func.loc,
-                            "method ‘pop()’ does not take any arguments".to_string(),
-                        ));
+                        diagnostics.push(
+                            Diagnostic::error(
+                                func.loc,
+                                "method ‘pop()’ does not take any arguments".to_string(),
+                            )
+                            .with_code(codes::WRONG_ARGUMENTS),
+                        );
                         return Err(());
                     }
 
@@ -5742,10 +6242,13 @@ fn method_call_pos_args(
                     cast(&args[0].loc(), val_expr, elem_ty, true, ns, diagnostics)?
                 }
                 _ => {
-                    diagnostics.push(Diagnostic::error(
-                        func.loc,
-                        "method ‘push()’ takes at most 1 argument".to_string(),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(
+                            func.loc,
+                            "method ‘push()’ takes at most 1 argument".to_string(),
+                        )
+                        .with_code(codes::WRONG_ARGUMENTS),
+                    );
                     return Err(());
                 }
             };
@@ -5759,10 +6262,13 @@ fn method_call_pos_args(
         }
         if func.name == "pop" {
             if !args.is_empty() {
-                diagnostics.push(Diagnostic::error(
-                    func.loc,
-                    "method ‘pop()’ does not take any arguments".to_string(),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(
+                        func.loc,
+                        "method ‘pop()’ does not take any arguments".to_string(),
+                    )
+                    .with_code(codes::WRONG_ARGUMENTS),
+                );
                 return Err(());
             }
 
@@ -5800,14 +6306,17 @@ fn method_call_pos_args(
             let params_len = ns.functions[function_no].params.len();
 
             if params_len != args.len() {
-                diagnostics.push(Diagnostic::error(
-                    *loc,
-                    format!(
-                        "function expects {} arguments, {} provided",
-                        params_len,
-                        args.len()
-                    ),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(
+                        *loc,
+                        format!(
+                            "function expects {} arguments, {} provided",
+                            params_len,
+                            args.len()
+                        ),
+                    )
+                    .with_code(codes::WRONG_ARGUMENTS),
+                );
                 continue;
             }
             let mut matches = true;
@@ -5845,10 +6354,13 @@ fn method_call_pos_args(
                 diagnostics.truncate(marker);
 
                 if !ns.functions[function_no].is_public() {
-                    diagnostics.push(Diagnostic::error(
-                        *loc,
-                        format!("function ‘{}’ is not ‘public’ or ‘external’", func.name),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(
+                            *loc,
+                            format!("function ‘{}’ is not ‘public’ or ‘external’", func.name),
+                        )
+                        .with_code(codes::NOT_ACCESSIBLE),
+                    );
                     return Err(());
                 }
 
@@ -5856,13 +6368,16 @@ fn method_call_pos_args(
                     if !value.const_zero(Some(*ext_contract_no), ns)
                         && !ns.functions[function_no].is_payable()
                     {
-                        diagnostics.push(Diagnostic::error(
-                            *loc,
-                            format!(
-                                "sending value to function ‘{}’ which is not payable",
-                                func.name
-                            ),
-                        ));
+                        diagnostics.push(
+                            Diagnostic::error(
+                                *loc,
+                                format!(
+                                    "sending value to function ‘{}’ which is not payable",
+                                    func.name
+                                ),
+                            )
+                            .with_code(codes::CALL_ARGUMENTS),
+                        );
                         return Err(());
                     }
 
@@ -5904,10 +6419,13 @@ fn method_call_pos_args(
 
         if name_match != 1 {
             diagnostics.truncate(marker);
-            diagnostics.push(Diagnostic::error(
-                *loc,
-                "cannot find overloaded function which matches signature".to_string(),
-            ));
+            diagnostics.push(
+                Diagnostic::error(
+                    *loc,
+                    "cannot find overloaded function which matches signature".to_string(),
+                )
+                .with_code(codes::NO_MATCHING_OVERLOAD),
+            );
         }
 
         return Err(());
@@ -5916,23 +6434,29 @@ fn method_call_pos_args(
     if let Type::Address(true) = &var_ty.deref_any() {
         if func.name == "transfer" || func.name == "send" {
             if args.len() != 1 {
-                diagnostics.push(Diagnostic::error(
-                    *loc,
-                    format!(
-                        "‘{}’ expects 1 argument, {} provided",
-                        func.name,
-                        args.len()
-                    ),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(
+                        *loc,
+                        format!(
+                            "‘{}’ expects 1 argument, {} provided",
+                            func.name,
+                            args.len()
+                        ),
+                    )
+                    .with_code(codes::WRONG_ARGUMENTS),
+                );
 
                 return Err(());
             }
 
             if let Some(loc) = call_args_loc {
-                diagnostics.push(Diagnostic::error(
-                    loc,
-                    format!("call arguments not allowed on ‘{}’", func.name),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(
+                        loc,
+                        format!("call arguments not allowed on ‘{}’", func.name),
+                    )
+                    .with_code(codes::CALL_ARGUMENTS),
+                );
                 return Err(());
             }
 
@@ -5949,6 +6473,17 @@ fn method_call_pos_args(
 
             let value = cast(&args[0].loc(), expr, &Type::Value, true, ns, diagnostics)?;
 
+            if !builtin::target_supports(Builtin::PayableTransfer, ns) {
+                diagnostics.push(
+                    Diagnostic::error(
+                        *loc,
+                        format!("‘{}’ not supported on {}", func.name, ns.target),
+                    )
+                    .with_code(codes::TARGET_NOT_SUPPORTED),
+                );
+                return Err(());
+            }
+
             return if func.name == "transfer" {
                 Ok(Expression::Builtin(
                     *loc,
@@ -5970,8 +6505,8 @@ fn method_call_pos_args(
     if let Type::Address(_) = &var_ty.deref_any() {
         let ty = match func.name.as_str() {
             "call" => Some(CallTy::Regular),
-            "delegatecall" if ns.target == Target::Ewasm => Some(CallTy::Delegate),
-            "staticcall" if ns.target == Target::Ewasm => Some(CallTy::Static),
+            "delegatecall" if ns.target.is_ethereum() => Some(CallTy::Delegate),
+            "staticcall" if ns.target.is_ethereum() => Some(CallTy::Static),
             _ => None,
         };
 
@@ -5987,23 +6522,26 @@ fn method_call_pos_args(
             )?;
 
             if ty != CallTy::Regular && call_args.value.is_some() {
-                diagnostics.push(Diagnostic::error(
-                    *loc,
-                    format!("‘{}’ cannot have value specifed", func.name,),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(*loc, format!("‘{}’ cannot have value specifed", func.name,))
+                        .with_code(codes::CALL_ARGUMENTS),
+                );
 
                 return Err(());
             }
 
             if args.len() != 1 {
-                diagnostics.push(Diagnostic::error(
-                    *loc,
-                    format!(
-                        "‘{}’ expects 1 argument, {} provided",
-                        func.name,
-                        args.len()
-                    ),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(
+                        *loc,
+                        format!(
+                            "‘{}’ expects 1 argument, {} provided",
+                            func.name,
+                            args.len()
+                        ),
+                    )
+                    .with_code(codes::WRONG_ARGUMENTS),
+                );
 
                 return Err(());
             }
@@ -6081,14 +6619,17 @@ fn method_call_pos_args(
                 let params_len = libfunc.params.len();
 
                 if params_len != args.len() + 1 {
-                    errors.push(Diagnostic::error(
-                        *loc,
-                        format!(
+                    errors.push(
+                        Diagnostic::error(
+                            *loc,
+                            format!(
                             "library function expects {} arguments, {} provided (including self)",
                             params_len,
                             args.len() + 1
                         ),
-                    ));
+                        )
+                        .with_code(codes::WRONG_ARGUMENTS),
+                    );
                     continue;
                 }
 
@@ -6144,12 +6685,15 @@ fn method_call_pos_args(
                 let libfunc = &ns.functions[function_no];
 
                 if libfunc.is_private() {
-                    errors.push(Diagnostic::error_with_note(
-                        *loc,
-                        "cannot call private library function".to_string(),
-                        libfunc.loc,
-                        format!("declaration of function ‘{}’", libfunc.name),
-                    ));
+                    errors.push(
+                        Diagnostic::error_with_note(
+                            *loc,
+                            "cannot call private library function".to_string(),
+                            libfunc.loc,
+                            format!("declaration of function ‘{}’", libfunc.name),
+                        )
+                        .with_code(codes::NOT_ACCESSIBLE),
+                    );
 
                     continue;
                 }
@@ -6179,19 +6723,23 @@ fn method_call_pos_args(
                 return Err(());
             }
             _ => {
-                diagnostics.push(Diagnostic::error(
-                    *loc,
-                    "cannot find overloaded library function which matches signature".to_string(),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(
+                        *loc,
+                        "cannot find overloaded library function which matches signature"
+                            .to_string(),
+                    )
+                    .with_code(codes::NO_MATCHING_OVERLOAD),
+                );
                 return Err(());
             }
         }
     }
 
-    diagnostics.push(Diagnostic::error(
-        func.loc,
-        format!("method ‘{}’ does not exist", func.name),
-    ));
+    diagnostics.push(
+        Diagnostic::error(func.loc, format!("method ‘{}’ does not exist", func.name))
+            .with_code(codes::NOT_FOUND),
+    );
 
     Err(())
 }
@@ -6214,10 +6762,13 @@ fn method_call_named_args(
         if namespace.name == "super" {
             if let Some(cur_contract_no) = contract_no {
                 if let Some(loc) = call_args_loc {
-                    diagnostics.push(Diagnostic::error(
-                        loc,
-                        "call arguments not allowed on super calls".to_string(),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(
+                            loc,
+                            "call arguments not allowed on super calls".to_string(),
+                        )
+                        .with_code(codes::CALL_ARGUMENTS),
+                    );
                     return Err(());
                 }
 
@@ -6234,10 +6785,10 @@ fn method_call_named_args(
                     diagnostics,
                 );
             } else {
-                diagnostics.push(Diagnostic::error(
-                    *loc,
-                    "super not available outside contracts".to_string(),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(*loc, "super not available outside contracts".to_string())
+                        .with_code(codes::NOT_ACCESSIBLE),
+                );
                 return Err(());
             }
         }
@@ -6246,10 +6797,13 @@ fn method_call_named_args(
         if let Some(call_contract_no) = ns.resolve_contract(file_no, &namespace) {
             if ns.contracts[call_contract_no].is_library() {
                 if let Some(loc) = call_args_loc {
-                    diagnostics.push(Diagnostic::error(
-                        loc,
-                        "call arguments not allowed on library calls".to_string(),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(
+                            loc,
+                            "call arguments not allowed on library calls".to_string(),
+                        )
+                        .with_code(codes::CALL_ARGUMENTS),
+                    );
                     return Err(());
                 }
 
@@ -6277,10 +6831,13 @@ fn method_call_named_args(
             if let Some(contract_no) = contract_no {
                 if is_base(call_contract_no, contract_no, ns) {
                     if let Some(loc) = call_args_loc {
-                        diagnostics.push(Diagnostic::error(
-                            loc,
-                            "call arguments not allowed on internal calls".to_string(),
-                        ));
+                        diagnostics.push(
+                            Diagnostic::error(
+                                loc,
+                                "call arguments not allowed on internal calls".to_string(),
+                            )
+                            .with_code(codes::CALL_ARGUMENTS),
+                        );
                         return Err(());
                     }
 
@@ -6334,10 +6891,13 @@ fn method_call_named_args(
 
         for arg in args {
             if arguments.contains_key(&arg.name.name) {
-                diagnostics.push(Diagnostic::error(
-                    arg.name.loc,
-                    format!("duplicate argument with name ‘{}’", arg.name.name),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(
+                        arg.name.loc,
+                        format!("duplicate argument with name ‘{}’", arg.name.name),
+                    )
+                    .with_code(codes::WRONG_ARGUMENTS),
+                );
                 return Err(());
             }
 
@@ -6360,14 +6920,17 @@ fn method_call_named_args(
             name_match += 1;
 
             if params_len != args.len() {
-                diagnostics.push(Diagnostic::error(
-                    *loc,
-                    format!(
-                        "function expects {} arguments, {} provided",
-                        params_len,
-                        args.len()
-                    ),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(
+                        *loc,
+                        format!(
+                            "function expects {} arguments, {} provided",
+                            params_len,
+                            args.len()
+                        ),
+                    )
+                    .with_code(codes::WRONG_ARGUMENTS),
+                );
                 continue;
             }
             let mut matches = true;
@@ -6380,13 +6943,16 @@ fn method_call_named_args(
                     Some(a) => a,
                     None => {
                         matches = false;
-                        diagnostics.push(Diagnostic::error(
-                            *loc,
-                            format!(
-                                "missing argument ‘{}’ to function ‘{}’",
-                                param.name, func_name.name,
-                            ),
-                        ));
+                        diagnostics.push(
+                            Diagnostic::error(
+                                *loc,
+                                format!(
+                                    "missing argument ‘{}’ to function ‘{}’",
+                                    param.name, func_name.name,
+                                ),
+                            )
+                            .with_code(codes::WRONG_ARGUMENTS),
+                        );
                         continue;
                     }
                 };
@@ -6426,26 +6992,32 @@ fn method_call_named_args(
 
             if matches {
                 if !ns.functions[function_no].is_public() {
-                    diagnostics.push(Diagnostic::error(
-                        *loc,
-                        format!(
-                            "function ‘{}’ is not ‘public’ or ‘external’",
-                            func_name.name
-                        ),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(
+                            *loc,
+                            format!(
+                                "function ‘{}’ is not ‘public’ or ‘external’",
+                                func_name.name
+                            ),
+                        )
+                        .with_code(codes::NOT_ACCESSIBLE),
+                    );
                     return Err(());
                 }
 
                 let value = if let Some(value) = call_args.value {
                     if !value.const_zero(contract_no, ns) && !ns.functions[function_no].is_payable()
                     {
-                        diagnostics.push(Diagnostic::error(
-                            *loc,
-                            format!(
-                                "sending value to function ‘{}’ which is not payable",
-                                func_name.name
-                            ),
-                        ));
+                        diagnostics.push(
+                            Diagnostic::error(
+                                *loc,
+                                format!(
+                                    "sending value to function ‘{}’ which is not payable",
+                                    func_name.name
+                                ),
+                            )
+                            .with_code(codes::CALL_ARGUMENTS),
+                        );
                         return Err(());
                     }
 
@@ -6487,31 +7059,40 @@ fn method_call_named_args(
 
         match name_match {
             0 => {
-                diagnostics.push(Diagnostic::error(
-                    *loc,
-                    format!(
-                        "contract ‘{}’ does not have function ‘{}’",
-                        var_ty.deref_any().to_string(ns),
-                        func_name.name
-                    ),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(
+                        *loc,
+                        format!(
+                            "contract ‘{}’ does not have function ‘{}’",
+                            var_ty.deref_any().to_string(ns),
+                            func_name.name
+                        ),
+                    )
+                    .with_code(codes::NOT_FOUND),
+                );
             }
             1 => {}
             _ => {
                 diagnostics.truncate(marker);
-                diagnostics.push(Diagnostic::error(
-                    *loc,
-                    "cannot find overloaded function which matches signature".to_string(),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(
+                        *loc,
+                        "cannot find overloaded function which matches signature".to_string(),
+                    )
+                    .with_code(codes::NO_MATCHING_OVERLOAD),
+                );
             }
         }
         return Err(());
     }
 
-    diagnostics.push(Diagnostic::error(
-        func_name.loc,
-        format!("method ‘{}’ does not exist", func_name.name),
-    ));
+    diagnostics.push(
+        Diagnostic::error(
+            func_name.loc,
+            format!("method ‘{}’ does not exist", func_name.name),
+        )
+        .with_code(codes::NOT_FOUND),
+    );
 
     Err(())
 }
@@ -6559,10 +7140,10 @@ fn resolve_array_literal(
     check_subarrays(exprs, &mut Some(&mut dims), &mut flattened, ns, diagnostics)?;
 
     if flattened.is_empty() {
-        diagnostics.push(Diagnostic::error(
-            *loc,
-            "array requires at least one element".to_string(),
-        ));
+        diagnostics.push(
+            Diagnostic::error(*loc, "array requires at least one element".to_string())
+                .with_code(codes::ARRAY),
+        );
         return Err(());
     }
 
@@ -6645,28 +7226,34 @@ fn check_subarrays<'a>(
                         format!(
                             "array elements should be identical, sub array {} has {} elements rather than {}", i + 1, other.len(), first.len()
                         ),
-                    ));
+                    ).with_code(codes::ARRAY));
                     return Err(());
                 }
                 check_subarrays(other, &mut None, flatten, ns, diagnostics)?;
             } else {
-                diagnostics.push(Diagnostic::error(
-                    e.loc(),
-                    format!("array element {} should also be an array", i + 1),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(
+                        e.loc(),
+                        format!("array element {} should also be an array", i + 1),
+                    )
+                    .with_code(codes::ARRAY),
+                );
                 return Err(());
             }
         }
     } else {
         for (i, e) in exprs.iter().enumerate().skip(1) {
             if let pt::Expression::ArrayLiteral(loc, _) = e {
-                diagnostics.push(Diagnostic::error(
-                    *loc,
-                    format!(
-                        "array elements should be of the type, element {} is unexpected array",
-                        i + 1
-                    ),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(
+                        *loc,
+                        format!(
+                            "array elements should be of the type, element {} is unexpected array",
+                            i + 1
+                        ),
+                    )
+                    .with_code(codes::ARRAY),
+                );
                 return Err(());
             }
         }
@@ -6709,10 +7296,10 @@ pub fn collect_call_args<'a>(
             }
             pt::Statement::Block(_, s) if s.is_empty() => {
                 // {}
-                diagnostics.push(Diagnostic::error(
-                    block.loc(),
-                    "missing call arguments".to_string(),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(block.loc(), "missing call arguments".to_string())
+                        .with_code(codes::CALL_ARGUMENTS),
+                );
                 return Err(());
             }
             _ => {
@@ -6720,7 +7307,7 @@ pub fn collect_call_args<'a>(
                     block.loc(),
                     "code block found where list of call arguments expected, like ‘{gas: 5000}’"
                         .to_string(),
-                ));
+                ).with_code(codes::CALL_ARGUMENTS));
                 return Err(());
             }
         }
@@ -6751,12 +7338,15 @@ fn parse_call_args(
 
     for arg in call_args {
         if let Some(prev) = args.get(&arg.name.name) {
-            diagnostics.push(Diagnostic::error_with_note(
-                arg.loc,
-                format!("‘{}’ specified multiple times", arg.name.name),
-                prev.loc,
-                format!("location of previous declaration of ‘{}’", arg.name.name),
-            ));
+            diagnostics.push(
+                Diagnostic::error_with_note(
+                    arg.loc,
+                    format!("‘{}’ specified multiple times", arg.name.name),
+                    prev.loc,
+                    format!("location of previous declaration of ‘{}’", arg.name.name),
+                )
+                .with_code(codes::CALL_ARGUMENTS),
+            );
             return Err(());
         }
 
@@ -6768,7 +7358,7 @@ fn parse_call_args(
             pt::Loc(0, 0, 0),
             Type::Uint(64),
             // See EIP150
-            if ns.target == Target::Ewasm {
+            if ns.target.is_ethereum() {
                 BigInt::from(i64::MAX)
             } else {
                 BigInt::zero()
@@ -6805,13 +7395,16 @@ fn parse_call_args(
             }
             "gas" => {
                 if ns.target == Target::Solana {
-                    diagnostics.push(Diagnostic::error(
-                        arg.loc,
-                        format!(
-                            "‘gas’ not permitted for external calls or constructors on {}",
-                            ns.target
-                        ),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(
+                            arg.loc,
+                            format!(
+                                "‘gas’ not permitted for external calls or constructors on {}",
+                                ns.target
+                            ),
+                        )
+                        .with_code(codes::TARGET_NOT_SUPPORTED),
+                    );
                     return Err(());
                 }
                 let ty = Type::Uint(64);
@@ -6831,21 +7424,27 @@ fn parse_call_args(
             }
             "salt" => {
                 if ns.target == Target::Solana {
-                    diagnostics.push(Diagnostic::error(
-                        arg.loc,
-                        format!(
-                            "‘salt’ not permitted for external calls or constructors on {}",
-                            ns.target
-                        ),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(
+                            arg.loc,
+                            format!(
+                                "‘salt’ not permitted for external calls or constructors on {}",
+                                ns.target
+                            ),
+                        )
+                        .with_code(codes::TARGET_NOT_SUPPORTED),
+                    );
                     return Err(());
                 }
 
                 if external_call {
-                    diagnostics.push(Diagnostic::error(
-                        arg.loc,
-                        "‘salt’ not valid for external calls".to_string(),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(
+                            arg.loc,
+                            "‘salt’ not valid for external calls".to_string(),
+                        )
+                        .with_code(codes::CALL_ARGUMENTS),
+                    );
                     return Err(());
                 }
 
@@ -6872,10 +7471,13 @@ fn parse_call_args(
                 )?));
             }
             _ => {
-                diagnostics.push(Diagnostic::error(
-                    arg.loc,
-                    format!("‘{}’ not a valid call parameter", arg.name.name),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(
+                        arg.loc,
+                        format!("‘{}’ not a valid call parameter", arg.name.name),
+                    )
+                    .with_code(codes::CALL_ARGUMENTS),
+                );
                 return Err(());
             }
         }
@@ -6901,10 +7503,13 @@ pub fn function_call_expr(
     match ty {
         pt::Expression::MemberAccess(_, member, func) => {
             if is_constant {
-                diagnostics.push(Diagnostic::error(
-                    *loc,
-                    "cannot call function in constant expression".to_string(),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(
+                        *loc,
+                        "cannot call function in constant expression".to_string(),
+                    )
+                    .with_code(codes::CONSTANT_EXPRESSION),
+                );
                 return Err(());
             }
 
@@ -6940,10 +7545,16 @@ pub fn function_call_expr(
                     )?;
 
                     if expr.tys().len() > 1 {
-                        diagnostics.push(Diagnostic::error(
-                            *loc,
-                            format!("builtin function ‘{}’ returns more than one value", id.name),
-                        ));
+                        diagnostics.push(
+                            Diagnostic::error(
+                                *loc,
+                                format!(
+                                    "builtin function ‘{}’ returns more than one value",
+                                    id.name
+                                ),
+                            )
+                            .with_code(codes::RETURN_VALUES),
+                        );
                         Err(())
                     } else {
                         Ok(expr)
@@ -6952,10 +7563,13 @@ pub fn function_call_expr(
             }
 
             if is_constant {
-                diagnostics.push(Diagnostic::error(
-                    *loc,
-                    "cannot call function in constant expression".to_string(),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(
+                        *loc,
+                        "cannot call function in constant expression".to_string(),
+                    )
+                    .with_code(codes::CONSTANT_EXPRESSION),
+                );
                 return Err(());
             }
 
@@ -6980,10 +7594,13 @@ pub fn function_call_expr(
                 )
             } else {
                 if let Some(loc) = call_args_loc {
-                    diagnostics.push(Diagnostic::error(
-                        loc,
-                        "call arguments not permitted for internal calls".to_string(),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(
+                            loc,
+                            "call arguments not permitted for internal calls".to_string(),
+                        )
+                        .with_code(codes::CALL_ARGUMENTS),
+                    );
                     return Err(());
                 }
 
@@ -7046,10 +7663,13 @@ pub fn named_function_call_expr(
         ),
         pt::Expression::Variable(id) => {
             if let Some(loc) = call_args_loc {
-                diagnostics.push(Diagnostic::error(
-                    loc,
-                    "call arguments not permitted for internal calls".to_string(),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(
+                        loc,
+                        "call arguments not permitted for internal calls".to_string(),
+                    )
+                    .with_code(codes::CALL_ARGUMENTS),
+                );
                 return Err(());
             }
 
@@ -7067,17 +7687,17 @@ pub fn named_function_call_expr(
             )
         }
         pt::Expression::ArraySubscript(_, _, _) => {
-            diagnostics.push(Diagnostic::error(
-                ty.loc(),
-                "unexpected array type".to_string(),
-            ));
+            diagnostics.push(
+                Diagnostic::error(ty.loc(), "unexpected array type".to_string())
+                    .with_code(codes::TYPE_NOT_ALLOWED),
+            );
             Err(())
         }
         _ => {
-            diagnostics.push(Diagnostic::error(
-                ty.loc(),
-                "expression not expected here".to_string(),
-            ));
+            diagnostics.push(
+                Diagnostic::error(ty.loc(), "expression not expected here".to_string())
+                    .with_code(codes::WRONG_KIND_OF_NAME),
+            );
             Err(())
         }
     }
@@ -1,4 +1,5 @@
 use super::ast::{Diagnostic, Expression, FormatArg, Namespace, Type};
+use super::codes;
 use super::expression::{cast, expression};
 use super::symtable::Symtable;
 use crate::parser::pt;
@@ -52,7 +53,10 @@ pub fn string_format(
                 // ok, let's skip over it
                 format_iterator.next();
             } else {
-                diagnostics.push(Diagnostic::error(loc, String::from("unmatched ‘}’")));
+                diagnostics.push(
+                    Diagnostic::error(loc, String::from("unmatched ‘}’"))
+                        .with_code(codes::FORMAT_STRING),
+                );
                 return Err(());
             }
         }
@@ -73,10 +77,10 @@ pub fn string_format(
                 let specifier = parse_format_specifier(loc, &mut format_iterator, diagnostics)?;
 
                 if resolved_args.is_empty() {
-                    diagnostics.push(Diagnostic::error(
-                        loc,
-                        String::from("missing argument to format"),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(loc, String::from("missing argument to format"))
+                            .with_code(codes::FORMAT_STRING),
+                    );
                     return Err(());
                 }
 
@@ -86,10 +90,13 @@ pub fn string_format(
 
                 if matches!(specifier, FormatArg::Binary | FormatArg::Hex) {
                     if !matches!(arg_ty, Type::Uint(_) | Type::Int(_)) {
-                        diagnostics.push(Diagnostic::error(
-                            arg.loc(),
-                            String::from("argument must be signed or unsigned integer type"),
-                        ));
+                        diagnostics.push(
+                            Diagnostic::error(
+                                arg.loc(),
+                                String::from("argument must be signed or unsigned integer type"),
+                            )
+                            .with_code(codes::FORMAT_STRING),
+                        );
                         return Err(());
                     }
                 } else if !matches!(
@@ -109,7 +116,7 @@ pub fn string_format(
                         String::from(
                             "argument must be a bool, enum, address, contract, string, or bytes",
                         ),
-                    ));
+                    ).with_code(codes::FORMAT_STRING));
                     return Err(());
                 }
 
@@ -121,10 +128,10 @@ pub fn string_format(
     }
 
     if !resolved_args.is_empty() {
-        diagnostics.push(Diagnostic::error(
-            *loc,
-            String::from("too many argument for format string"),
-        ));
+        diagnostics.push(
+            Diagnostic::error(*loc, String::from("too many argument for format string"))
+                .with_code(codes::FORMAT_STRING),
+        );
         return Err(());
     }
 
@@ -163,17 +170,17 @@ fn parse_format_specifier(
                     return Ok(FormatArg::Default);
                 }
                 Some((loc, ch)) => {
-                    diagnostics.push(Diagnostic::error(
-                        loc,
-                        format!("unexpected format char ‘{}’", ch),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(loc, format!("unexpected format char ‘{}’", ch))
+                            .with_code(codes::FORMAT_STRING),
+                    );
                     return Err(());
                 }
                 None => {
-                    diagnostics.push(Diagnostic::error(
-                        last_loc,
-                        String::from("missing format specifier"),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(last_loc, String::from("missing format specifier"))
+                            .with_code(codes::FORMAT_STRING),
+                    );
                     return Err(());
                 }
             }
@@ -181,33 +188,36 @@ fn parse_format_specifier(
             match format_iterator.next() {
                 Some((_, '}')) => Ok(arg),
                 Some((loc, ch)) => {
-                    diagnostics.push(Diagnostic::error(
-                        loc,
-                        format!("unexpected format char ‘{:}’, expected closing ‘}}’", ch),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(
+                            loc,
+                            format!("unexpected format char ‘{:}’, expected closing ‘}}’", ch),
+                        )
+                        .with_code(codes::FORMAT_STRING),
+                    );
                     Err(())
                 }
                 None => {
-                    diagnostics.push(Diagnostic::error(
-                        last_loc,
-                        String::from("missing closing ‘}’"),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(last_loc, String::from("missing closing ‘}’"))
+                            .with_code(codes::FORMAT_STRING),
+                    );
                     Err(())
                 }
             }
         }
         Some((loc, ch)) => {
-            diagnostics.push(Diagnostic::error(
-                loc,
-                format!("unexpected format char ‘{}’", ch),
-            ));
+            diagnostics.push(
+                Diagnostic::error(loc, format!("unexpected format char ‘{}’", ch))
+                    .with_code(codes::FORMAT_STRING),
+            );
             Err(())
         }
         None => {
-            diagnostics.push(Diagnostic::error(
-                last_loc,
-                String::from("missing closing ‘}’"),
-            ));
+            diagnostics.push(
+                Diagnostic::error(last_loc, String::from("missing closing ‘}’"))
+                    .with_code(codes::FORMAT_STRING),
+            );
             Err(())
         }
     }
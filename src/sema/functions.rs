@@ -1,4 +1,5 @@
-use super::ast::{Diagnostic, Function, Namespace, Parameter, Symbol, Type};
+use super::ast::{AbiCoder, Diagnostic, Function, Namespace, Parameter, Symbol, Type};
+use super::codes;
 use super::contracts::is_base;
 use super::tags::resolve_tags;
 use crate::parser::pt;
@@ -21,75 +22,93 @@ pub fn contract_function(
             // Function name cannot be the same as the contract name
             if let Some(n) = &func.name {
                 if n.name == ns.contracts[contract_no].name {
-                    ns.diagnostics.push(Diagnostic::error(
-                        func.loc,
-                        "function cannot have same name as the contract".to_string(),
-                    ));
+                    ns.diagnostics.push(
+                        Diagnostic::error(
+                            func.loc,
+                            "function cannot have same name as the contract".to_string(),
+                        )
+                        .with_code(codes::FUNCTION_DECLARATION),
+                    );
                     return None;
                 }
             } else {
                 ns.diagnostics.push(Diagnostic::error(
                     func.name_loc,
                     "function is missing a name. did you mean ‘fallback() external {…}’ or ‘receive() external {…}’?".to_string(),
-                ));
+                ).with_code(codes::FUNCTION_DECLARATION));
                 return None;
             }
         }
         pt::FunctionTy::Constructor => {
             if !func.returns.is_empty() {
-                ns.diagnostics.push(Diagnostic::error(
-                    func.loc,
-                    "constructor cannot have return values".to_string(),
-                ));
+                ns.diagnostics.push(
+                    Diagnostic::error(
+                        func.loc,
+                        "constructor cannot have return values".to_string(),
+                    )
+                    .with_code(codes::FUNCTION_DECLARATION),
+                );
                 return None;
             }
             if func.name.is_some() {
-                ns.diagnostics.push(Diagnostic::error(
-                    func.loc,
-                    "constructor cannot have a name".to_string(),
-                ));
+                ns.diagnostics.push(
+                    Diagnostic::error(func.loc, "constructor cannot have a name".to_string())
+                        .with_code(codes::FUNCTION_DECLARATION),
+                );
                 return None;
             }
         }
         pt::FunctionTy::Fallback | pt::FunctionTy::Receive => {
             if !func.returns.is_empty() {
-                ns.diagnostics.push(Diagnostic::error(
-                    func.loc,
-                    format!("{} function cannot have return values", func.ty),
-                ));
+                ns.diagnostics.push(
+                    Diagnostic::error(
+                        func.loc,
+                        format!("{} function cannot have return values", func.ty),
+                    )
+                    .with_code(codes::FUNCTION_DECLARATION),
+                );
                 success = false;
             }
             if !func.params.is_empty() {
-                ns.diagnostics.push(Diagnostic::error(
-                    func.loc,
-                    format!("{} function cannot have parameters", func.ty),
-                ));
+                ns.diagnostics.push(
+                    Diagnostic::error(
+                        func.loc,
+                        format!("{} function cannot have parameters", func.ty),
+                    )
+                    .with_code(codes::FUNCTION_DECLARATION),
+                );
                 success = false;
             }
             if func.name.is_some() {
-                ns.diagnostics.push(Diagnostic::error(
-                    func.loc,
-                    format!("{} function cannot have a name", func.ty),
-                ));
+                ns.diagnostics.push(
+                    Diagnostic::error(func.loc, format!("{} function cannot have a name", func.ty))
+                        .with_code(codes::FUNCTION_DECLARATION),
+                );
                 return None;
             }
         }
         pt::FunctionTy::Modifier => {
             if !func.returns.is_empty() {
-                ns.diagnostics.push(Diagnostic::error(
-                    func.loc,
-                    "constructor cannot have return values".to_string(),
-                ));
+                ns.diagnostics.push(
+                    Diagnostic::error(
+                        func.loc,
+                        "constructor cannot have return values".to_string(),
+                    )
+                    .with_code(codes::FUNCTION_DECLARATION),
+                );
                 return None;
             }
         }
     }
 
     if let Some(loc) = func.return_not_returns {
-        ns.diagnostics.push(Diagnostic::error(
-            loc,
-            "‘return’ unexpected. Did you mean ‘returns’?".to_string(),
-        ));
+        ns.diagnostics.push(
+            Diagnostic::error(
+                loc,
+                "‘return’ unexpected. Did you mean ‘returns’?".to_string(),
+            )
+            .with_code(codes::FUNCTION_DECLARATION),
+        );
         success = false;
     }
 
@@ -102,21 +121,27 @@ pub fn contract_function(
         match &a {
             pt::FunctionAttribute::StateMutability(m) => {
                 if let Some(e) = &mutability {
-                    ns.diagnostics.push(Diagnostic::error_with_note(
-                        m.loc(),
-                        format!("function redeclared `{}'", m.to_string()),
-                        e.loc(),
-                        format!("location of previous declaration of `{}'", e.to_string()),
-                    ));
+                    ns.diagnostics.push(
+                        Diagnostic::error_with_note(
+                            m.loc(),
+                            format!("function redeclared `{}'", m.to_string()),
+                            e.loc(),
+                            format!("location of previous declaration of `{}'", e.to_string()),
+                        )
+                        .with_code(codes::ALREADY_DEFINED),
+                    );
                     success = false;
                     continue;
                 }
 
                 if let pt::StateMutability::Constant(loc) = m {
-                    ns.diagnostics.push(Diagnostic::warning(
-                        *loc,
-                        "‘constant’ is deprecated. Use ‘view’ instead".to_string(),
-                    ));
+                    ns.diagnostics.push(
+                        Diagnostic::warning(
+                            *loc,
+                            "‘constant’ is deprecated. Use ‘view’ instead".to_string(),
+                        )
+                        .with_code(codes::DEPRECATED_CONSTANT),
+                    );
 
                     mutability = Some(pt::StateMutability::View(*loc));
                 } else {
@@ -125,12 +150,15 @@ pub fn contract_function(
             }
             pt::FunctionAttribute::Visibility(v) => {
                 if let Some(e) = &visibility {
-                    ns.diagnostics.push(Diagnostic::error_with_note(
-                        v.loc(),
-                        format!("function redeclared `{}'", v.to_string()),
-                        e.loc(),
-                        format!("location of previous declaration of `{}'", e.to_string()),
-                    ));
+                    ns.diagnostics.push(
+                        Diagnostic::error_with_note(
+                            v.loc(),
+                            format!("function redeclared `{}'", v.to_string()),
+                            e.loc(),
+                            format!("location of previous declaration of `{}'", e.to_string()),
+                        )
+                        .with_code(codes::ALREADY_DEFINED),
+                    );
                     success = false;
                     continue;
                 }
@@ -139,12 +167,15 @@ pub fn contract_function(
             }
             pt::FunctionAttribute::Virtual(loc) => {
                 if let Some(prev_loc) = &is_virtual {
-                    ns.diagnostics.push(Diagnostic::error_with_note(
-                        *loc,
-                        "function redeclared ‘virtual’".to_string(),
-                        *prev_loc,
-                        "location of previous declaration of ‘virtual’".to_string(),
-                    ));
+                    ns.diagnostics.push(
+                        Diagnostic::error_with_note(
+                            *loc,
+                            "function redeclared ‘virtual’".to_string(),
+                            *prev_loc,
+                            "location of previous declaration of ‘virtual’".to_string(),
+                        )
+                        .with_code(codes::OVERRIDE),
+                    );
                     success = false;
                     continue;
                 }
@@ -153,12 +184,15 @@ pub fn contract_function(
             }
             pt::FunctionAttribute::Override(loc, bases) => {
                 if let Some((prev_loc, _)) = &is_override {
-                    ns.diagnostics.push(Diagnostic::error_with_note(
-                        *loc,
-                        "function redeclared ‘override’".to_string(),
-                        *prev_loc,
-                        "location of previous declaration of ‘override’".to_string(),
-                    ));
+                    ns.diagnostics.push(
+                        Diagnostic::error_with_note(
+                            *loc,
+                            "function redeclared ‘override’".to_string(),
+                            *prev_loc,
+                            "location of previous declaration of ‘override’".to_string(),
+                        )
+                        .with_code(codes::OVERRIDE),
+                    );
                     success = false;
                     continue;
                 }
@@ -169,27 +203,36 @@ pub fn contract_function(
                     match ns.resolve_contract(file_no, name) {
                         Some(no) => {
                             if list.contains(&no) {
-                                ns.diagnostics.push(Diagnostic::error(
-                                    name.loc,
-                                    format!("function duplicate override ‘{}’", name.name),
-                                ));
+                                ns.diagnostics.push(
+                                    Diagnostic::error(
+                                        name.loc,
+                                        format!("function duplicate override ‘{}’", name.name),
+                                    )
+                                    .with_code(codes::OVERRIDE),
+                                );
                             } else if !is_base(no, contract_no, ns) {
-                                ns.diagnostics.push(Diagnostic::error(
-                                    name.loc,
-                                    format!(
-                                        "override ‘{}’ is not a base contract of ‘{}’",
-                                        name.name, ns.contracts[contract_no].name
-                                    ),
-                                ));
+                                ns.diagnostics.push(
+                                    Diagnostic::error(
+                                        name.loc,
+                                        format!(
+                                            "override ‘{}’ is not a base contract of ‘{}’",
+                                            name.name, ns.contracts[contract_no].name
+                                        ),
+                                    )
+                                    .with_code(codes::OVERRIDE),
+                                );
                             } else {
                                 list.push(no);
                             }
                         }
                         None => {
-                            ns.diagnostics.push(Diagnostic::error(
-                                name.loc,
-                                format!("contract ‘{}’ in override list not found", name.name),
-                            ));
+                            ns.diagnostics.push(
+                                Diagnostic::error(
+                                    name.loc,
+                                    format!("contract ‘{}’ in override list not found", name.name),
+                                )
+                                .with_code(codes::OVERRIDE),
+                            );
                         }
                     }
                 }
@@ -201,13 +244,16 @@ pub fn contract_function(
                 // once we have resolved all the constructors, this is not done here yet
                 // so we fully resolve these along with the constructor body
                 if func.ty != pt::FunctionTy::Constructor && func.ty != pt::FunctionTy::Function {
-                    ns.diagnostics.push(Diagnostic::error(
-                        *loc,
-                        format!(
-                            "function modifiers or base contracts are not allowed on {}",
-                            func.ty
-                        ),
-                    ));
+                    ns.diagnostics.push(
+                        Diagnostic::error(
+                            *loc,
+                            format!(
+                                "function modifiers or base contracts are not allowed on {}",
+                                func.ty
+                            ),
+                        )
+                        .with_code(codes::INHERITANCE),
+                    );
                 }
             }
         }
@@ -216,17 +262,23 @@ pub fn contract_function(
     let visibility = match visibility {
         Some(v) => {
             if func.ty == pt::FunctionTy::Modifier {
-                ns.diagnostics.push(Diagnostic::error(
-                    v.loc(),
-                    format!("‘{}’: modifiers can not have visibility", v),
-                ));
+                ns.diagnostics.push(
+                    Diagnostic::error(
+                        v.loc(),
+                        format!("‘{}’: modifiers can not have visibility", v),
+                    )
+                    .with_code(codes::FUNCTION_DECLARATION),
+                );
 
                 pt::Visibility::Internal(v.loc())
             } else if func.ty == pt::FunctionTy::Constructor {
-                ns.diagnostics.push(Diagnostic::warning(
-                    v.loc(),
-                    format!("‘{}’: visibility for constructors is ignored", v),
-                ));
+                ns.diagnostics.push(
+                    Diagnostic::warning(
+                        v.loc(),
+                        format!("‘{}’: visibility for constructors is ignored", v),
+                    )
+                    .with_code(codes::CONSTRUCTOR_VISIBILITY),
+                );
 
                 pt::Visibility::Public(v.loc())
             } else {
@@ -238,10 +290,10 @@ pub fn contract_function(
                 pt::FunctionTy::Constructor => pt::Visibility::Public(pt::Loc(0, 0, 0)),
                 pt::FunctionTy::Modifier => pt::Visibility::Internal(pt::Loc(0, 0, 0)),
                 _ => {
-                    ns.diagnostics.push(Diagnostic::error(
-                        func.loc,
-                        "no visibility specified".to_string(),
-                    ));
+                    ns.diagnostics.push(
+                        Diagnostic::error(func.loc, "no visibility specified".to_string())
+                            .with_code(codes::FUNCTION_DECLARATION),
+                    );
 
                     success = false;
                     // continue processing while assuming it's a public
@@ -253,10 +305,13 @@ pub fn contract_function(
 
     if let Some(m) = &mutability {
         if func.ty == pt::FunctionTy::Modifier {
-            ns.diagnostics.push(Diagnostic::error(
-                m.loc(),
-                "modifier cannot have mutability specifier".to_string(),
-            ));
+            ns.diagnostics.push(
+                Diagnostic::error(
+                    m.loc(),
+                    "modifier cannot have mutability specifier".to_string(),
+                )
+                .with_code(codes::FUNCTION_DECLARATION),
+            );
             success = false;
             mutability = None;
         }
@@ -267,10 +322,13 @@ pub fn contract_function(
     let storage_allowed = match visibility {
         pt::Visibility::Internal(_) | pt::Visibility::Private(_) => {
             if let Some(pt::StateMutability::Payable(loc)) = mutability {
-                ns.diagnostics.push(Diagnostic::error(
-                    loc,
-                    "internal or private function cannot be payable".to_string(),
-                ));
+                ns.diagnostics.push(
+                    Diagnostic::error(
+                        loc,
+                        "internal or private function cannot be payable".to_string(),
+                    )
+                    .with_code(codes::FUNCTION_DECLARATION),
+                );
                 success = false;
             }
             true
@@ -305,75 +363,96 @@ pub fn contract_function(
 
     if ns.contracts[contract_no].is_interface() {
         if func.ty == pt::FunctionTy::Constructor {
-            ns.diagnostics.push(Diagnostic::error(
-                func.loc,
-                "constructor not allowed in an interface".to_string(),
-            ));
+            ns.diagnostics.push(
+                Diagnostic::error(
+                    func.loc,
+                    "constructor not allowed in an interface".to_string(),
+                )
+                .with_code(codes::CONTRACT_KIND),
+            );
             success = false;
         } else if func.body.is_some() {
-            ns.diagnostics.push(Diagnostic::error(
-                func.loc,
-                "function in an interface cannot have a body".to_string(),
-            ));
+            ns.diagnostics.push(
+                Diagnostic::error(
+                    func.loc,
+                    "function in an interface cannot have a body".to_string(),
+                )
+                .with_code(codes::CONTRACT_KIND),
+            );
             success = false;
         } else if let pt::Visibility::External(_) = visibility {
             // ok
         } else {
-            ns.diagnostics.push(Diagnostic::error(
-                func.loc,
-                "functions must be declared ‘external’ in an interface".to_string(),
-            ));
+            ns.diagnostics.push(
+                Diagnostic::error(
+                    func.loc,
+                    "functions must be declared ‘external’ in an interface".to_string(),
+                )
+                .with_code(codes::CONTRACT_KIND),
+            );
             success = false;
         }
     } else if ns.contracts[contract_no].is_library() {
         if func.ty != pt::FunctionTy::Function {
-            ns.diagnostics.push(Diagnostic::error(
-                func.loc,
-                format!("{} not allowed in a library", func.ty),
-            ));
+            ns.diagnostics.push(
+                Diagnostic::error(func.loc, format!("{} not allowed in a library", func.ty))
+                    .with_code(codes::CONTRACT_KIND),
+            );
             success = false;
         } else if func.body.is_none() {
-            ns.diagnostics.push(Diagnostic::error(
-                func.loc,
-                "function in a library must have a body".to_string(),
-            ));
+            ns.diagnostics.push(
+                Diagnostic::error(
+                    func.loc,
+                    "function in a library must have a body".to_string(),
+                )
+                .with_code(codes::CONTRACT_KIND),
+            );
             success = false;
         } else if let Some((loc, _)) = is_override {
-            ns.diagnostics.push(Diagnostic::error(
-                loc,
-                "function in a library cannot override".to_string(),
-            ));
+            ns.diagnostics.push(
+                Diagnostic::error(loc, "function in a library cannot override".to_string())
+                    .with_code(codes::OVERRIDE),
+            );
             success = false;
         } else if let Some(pt::StateMutability::Payable(_)) = mutability {
-            ns.diagnostics.push(Diagnostic::error(
-                func.loc,
-                "function in a library cannot be payable".to_string(),
-            ));
+            ns.diagnostics.push(
+                Diagnostic::error(
+                    func.loc,
+                    "function in a library cannot be payable".to_string(),
+                )
+                .with_code(codes::CONTRACT_KIND),
+            );
             success = false;
         }
     } else if func.ty == pt::FunctionTy::Constructor && is_virtual.is_some() {
-        ns.diagnostics.push(Diagnostic::error(
-            func.loc,
-            "constructors cannot be declared ‘virtual’".to_string(),
-        ));
+        ns.diagnostics.push(
+            Diagnostic::error(
+                func.loc,
+                "constructors cannot be declared ‘virtual’".to_string(),
+            )
+            .with_code(codes::OVERRIDE),
+        );
     }
 
     // all functions in an interface are implicitly virtual
     let is_virtual = if ns.contracts[contract_no].is_interface() {
         if let Some(loc) = is_virtual {
-            ns.diagnostics.push(Diagnostic::warning(
-                loc,
-                "functions in an interface are implicitly virtual".to_string(),
-            ));
+            ns.diagnostics.push(
+                Diagnostic::warning(
+                    loc,
+                    "functions in an interface are implicitly virtual".to_string(),
+                )
+                .with_code(codes::INTERFACE_VIRTUAL),
+            );
         }
 
         true
     } else if ns.contracts[contract_no].is_library() {
         if let Some(loc) = is_virtual {
-            ns.diagnostics.push(Diagnostic::error(
-                loc,
-                "functions in a library cannot be virtual".to_string(),
-            ));
+            ns.diagnostics.push(
+                Diagnostic::error(loc, "functions in a library cannot be virtual".to_string())
+                    .with_code(codes::OVERRIDE),
+            );
         }
 
         false
@@ -382,19 +461,25 @@ pub fn contract_function(
     };
 
     if !is_virtual && func.body.is_none() {
-        ns.diagnostics.push(Diagnostic::error(
-            func.loc,
-            "function with no body must be marked ‘virtual’".to_string(),
-        ));
+        ns.diagnostics.push(
+            Diagnostic::error(
+                func.loc,
+                "function with no body must be marked ‘virtual’".to_string(),
+            )
+            .with_code(codes::OVERRIDE),
+        );
         success = false;
     }
 
     if let pt::Visibility::Private(_) = visibility {
         if is_virtual {
-            ns.diagnostics.push(Diagnostic::error(
-                func.loc,
-                "function marked ‘virtual’ cannot also be ‘private’".to_string(),
-            ));
+            ns.diagnostics.push(
+                Diagnostic::error(
+                    func.loc,
+                    "function marked ‘virtual’ cannot also be ‘private’".to_string(),
+                )
+                .with_code(codes::OVERRIDE),
+            );
             success = false;
         }
     }
@@ -443,7 +528,7 @@ pub fn contract_function(
 
     if func.ty == pt::FunctionTy::Constructor {
         // In the eth solidity, only one constructor is allowed
-        if ns.target == Target::Ewasm {
+        if ns.target.is_ethereum() {
             if let Some(prev_func_no) = ns.contracts[contract_no]
                 .functions
                 .iter()
@@ -451,12 +536,15 @@ pub fn contract_function(
             {
                 let prev_loc = ns.functions[*prev_func_no].loc;
 
-                ns.diagnostics.push(Diagnostic::error_with_note(
-                    func.loc,
-                    "constructor already defined".to_string(),
-                    prev_loc,
-                    "location of previous definition".to_string(),
-                ));
+                ns.diagnostics.push(
+                    Diagnostic::error_with_note(
+                        func.loc,
+                        "constructor already defined".to_string(),
+                        prev_loc,
+                        "location of previous definition".to_string(),
+                    )
+                    .with_code(codes::ALREADY_DEFINED),
+                );
                 return None;
             }
         } else {
@@ -469,29 +557,32 @@ pub fn contract_function(
             }) {
                 let prev_loc = ns.functions[*prev_func_no].loc;
 
-                ns.diagnostics.push(Diagnostic::error_with_note(
-                    func.loc,
-                    "all constructors should be defined ‘payable’ or not".to_string(),
-                    prev_loc,
-                    "location of previous definition".to_string(),
-                ));
+                ns.diagnostics.push(
+                    Diagnostic::error_with_note(
+                        func.loc,
+                        "all constructors should be defined ‘payable’ or not".to_string(),
+                        prev_loc,
+                        "location of previous definition".to_string(),
+                    )
+                    .with_code(codes::FUNCTION_DECLARATION),
+                );
                 return None;
             }
         }
 
         match fdecl.mutability {
             Some(pt::StateMutability::Pure(loc)) => {
-                ns.diagnostics.push(Diagnostic::error(
-                    loc,
-                    "constructor cannot be declared pure".to_string(),
-                ));
+                ns.diagnostics.push(
+                    Diagnostic::error(loc, "constructor cannot be declared pure".to_string())
+                        .with_code(codes::FUNCTION_DECLARATION),
+                );
                 return None;
             }
             Some(pt::StateMutability::View(loc)) => {
-                ns.diagnostics.push(Diagnostic::error(
-                    loc,
-                    "constructor cannot be declared view".to_string(),
-                ));
+                ns.diagnostics.push(
+                    Diagnostic::error(loc, "constructor cannot be declared view".to_string())
+                        .with_code(codes::FUNCTION_DECLARATION),
+                );
                 return None;
             }
             _ => (),
@@ -501,12 +592,15 @@ pub fn contract_function(
             let v = &ns.functions[*prev_func_no];
 
             if v.is_constructor() && v.signature == fdecl.signature {
-                ns.diagnostics.push(Diagnostic::error_with_note(
-                    func.loc,
-                    "constructor with this signature already exists".to_string(),
-                    v.loc,
-                    "location of previous definition".to_string(),
-                ));
+                ns.diagnostics.push(
+                    Diagnostic::error_with_note(
+                        func.loc,
+                        "constructor with this signature already exists".to_string(),
+                        v.loc,
+                        "location of previous definition".to_string(),
+                    )
+                    .with_code(codes::ALREADY_DEFINED),
+                );
 
                 return None;
             }
@@ -526,22 +620,28 @@ pub fn contract_function(
         {
             let prev_loc = ns.functions[*prev_func_no].loc;
 
-            ns.diagnostics.push(Diagnostic::error_with_note(
-                func.loc,
-                format!("{} function already defined", func.ty),
-                prev_loc,
-                "location of previous definition".to_string(),
-            ));
+            ns.diagnostics.push(
+                Diagnostic::error_with_note(
+                    func.loc,
+                    format!("{} function already defined", func.ty),
+                    prev_loc,
+                    "location of previous definition".to_string(),
+                )
+                .with_code(codes::ALREADY_DEFINED),
+            );
             return None;
         }
 
         if let pt::Visibility::External(_) = fdecl.visibility {
             // ok
         } else {
-            ns.diagnostics.push(Diagnostic::error(
-                func.loc,
-                format!("{} function must be declared external", func.ty),
-            ));
+            ns.diagnostics.push(
+                Diagnostic::error(
+                    func.loc,
+                    format!("{} function must be declared external", func.ty),
+                )
+                .with_code(codes::FUNCTION_DECLARATION),
+            );
             return None;
         }
 
@@ -550,14 +650,17 @@ pub fn contract_function(
                 ns.diagnostics.push(Diagnostic::error(
                     func.loc,
                     format!("{} function must not be declare payable, use ‘receive() external payable’ instead", func.ty),
-                ));
+                ).with_code(codes::FUNCTION_DECLARATION));
                 return None;
             }
         } else if func.ty == pt::FunctionTy::Receive {
-            ns.diagnostics.push(Diagnostic::error(
-                func.loc,
-                format!("{} function must be declared payable", func.ty),
-            ));
+            ns.diagnostics.push(
+                Diagnostic::error(
+                    func.loc,
+                    format!("{} function must be declared payable", func.ty),
+                )
+                .with_code(codes::FUNCTION_DECLARATION),
+            );
             return None;
         }
 
@@ -579,12 +682,15 @@ pub fn contract_function(
                 func.signature == fdecl.signature
             })
         {
-            ns.diagnostics.push(Diagnostic::error_with_note(
-                func.loc,
-                format!("overloaded {} with this signature already exist", func.ty),
-                ns.functions[*func_no].loc,
-                "location of previous definition".to_string(),
-            ));
+            ns.diagnostics.push(
+                Diagnostic::error_with_note(
+                    func.loc,
+                    format!("overloaded {} with this signature already exist", func.ty),
+                    ns.functions[*func_no].loc,
+                    "location of previous definition".to_string(),
+                )
+                .with_code(codes::ALREADY_DEFINED),
+            );
 
             return None;
         }
@@ -626,21 +732,27 @@ pub fn function(
         match &a {
             pt::FunctionAttribute::StateMutability(m) => {
                 if let Some(e) = &mutability {
-                    ns.diagnostics.push(Diagnostic::error_with_note(
-                        m.loc(),
-                        format!("function redeclared `{}'", m.to_string()),
-                        e.loc(),
-                        format!("location of previous declaration of `{}'", e.to_string()),
-                    ));
+                    ns.diagnostics.push(
+                        Diagnostic::error_with_note(
+                            m.loc(),
+                            format!("function redeclared `{}'", m.to_string()),
+                            e.loc(),
+                            format!("location of previous declaration of `{}'", e.to_string()),
+                        )
+                        .with_code(codes::ALREADY_DEFINED),
+                    );
                     success = false;
                     continue;
                 }
 
                 if let pt::StateMutability::Constant(loc) = m {
-                    ns.diagnostics.push(Diagnostic::warning(
-                        *loc,
-                        "‘constant’ is deprecated. Use ‘view’ instead".to_string(),
-                    ));
+                    ns.diagnostics.push(
+                        Diagnostic::warning(
+                            *loc,
+                            "‘constant’ is deprecated. Use ‘view’ instead".to_string(),
+                        )
+                        .with_code(codes::DEPRECATED_CONSTANT),
+                    );
 
                     mutability = Some(pt::StateMutability::View(*loc));
                 } else {
@@ -648,27 +760,36 @@ pub fn function(
                 }
             }
             pt::FunctionAttribute::Visibility(v) => {
-                ns.diagnostics.push(Diagnostic::error(
-                    v.loc(),
-                    format!(
-                        "‘{}’: only functions in contracts can have a visibility specifier",
-                        v
-                    ),
-                ));
+                ns.diagnostics.push(
+                    Diagnostic::error(
+                        v.loc(),
+                        format!(
+                            "‘{}’: only functions in contracts can have a visibility specifier",
+                            v
+                        ),
+                    )
+                    .with_code(codes::FUNCTION_DECLARATION),
+                );
                 success = false;
             }
             pt::FunctionAttribute::Virtual(loc) => {
-                ns.diagnostics.push(Diagnostic::error(
-                    *loc,
-                    String::from("only functions in contracts can be virtual"),
-                ));
+                ns.diagnostics.push(
+                    Diagnostic::error(
+                        *loc,
+                        String::from("only functions in contracts can be virtual"),
+                    )
+                    .with_code(codes::OVERRIDE),
+                );
                 success = false;
             }
             pt::FunctionAttribute::Override(loc, _) => {
-                ns.diagnostics.push(Diagnostic::error(
-                    *loc,
-                    String::from("only functions in contracts can override"),
-                ));
+                ns.diagnostics.push(
+                    Diagnostic::error(
+                        *loc,
+                        String::from("only functions in contracts can override"),
+                    )
+                    .with_code(codes::OVERRIDE),
+                );
                 success = false;
             }
             pt::FunctionAttribute::BaseOrModifier(loc, _) => {
@@ -680,7 +801,7 @@ pub fn function(
                     String::from(
                         "function modifiers or base contracts are only allowed on functions in contracts",
                     ),
-                ));
+                ).with_code(codes::INHERITANCE));
                 success = false;
             }
         }
@@ -697,10 +818,10 @@ pub fn function(
     ns.diagnostics.extend(diagnostics);
 
     if func.body.is_none() {
-        ns.diagnostics.push(Diagnostic::error(
-            func.loc,
-            String::from("missing function body"),
-        ));
+        ns.diagnostics.push(
+            Diagnostic::error(func.loc, String::from("missing function body"))
+                .with_code(codes::FUNCTION_DECLARATION),
+        );
         success = false;
     }
 
@@ -711,10 +832,10 @@ pub fn function(
     let name = match &func.name {
         Some(s) => s.name.to_owned(),
         None => {
-            ns.diagnostics.push(Diagnostic::error(
-                func.loc,
-                String::from("missing function name"),
-            ));
+            ns.diagnostics.push(
+                Diagnostic::error(func.loc, String::from("missing function name"))
+                    .with_code(codes::FUNCTION_DECLARATION),
+            );
             return None;
         }
     };
@@ -747,12 +868,15 @@ pub fn function(
     let id = func.name.as_ref().unwrap();
 
     if let Some(prev) = ns.functions.iter().find(|f| fdecl.signature == f.signature) {
-        ns.diagnostics.push(Diagnostic::error_with_note(
-            func.loc,
-            format!("overloaded {} with this signature already exist", func.ty),
-            prev.loc,
-            "location of previous definition".to_string(),
-        ));
+        ns.diagnostics.push(
+            Diagnostic::error_with_note(
+                func.loc,
+                format!("overloaded {} with this signature already exist", func.ty),
+                prev.loc,
+                "location of previous definition".to_string(),
+            )
+            .with_code(codes::ALREADY_DEFINED),
+        );
 
         return None;
     }
@@ -789,7 +913,10 @@ pub fn resolve_params(
         let p = match p {
             Some(p) => p,
             None => {
-                diagnostics.push(Diagnostic::error(*loc, "missing parameter type".to_owned()));
+                diagnostics.push(
+                    Diagnostic::error(*loc, "missing parameter type".to_owned())
+                        .with_code(codes::FUNCTION_DECLARATION),
+                );
                 success = false;
                 continue;
             }
@@ -803,7 +930,18 @@ pub fn resolve_params(
                     diagnostics.push(Diagnostic::error(
                         p.ty.loc(),
                         "parameter of type ‘function internal’ not allowed public or external functions".to_string(),
-                    ));
+                    ).with_code(codes::ABI_ENCODING));
+                    success = false;
+                }
+
+                if !is_internal && needs_abicoder_v2(&ty, file_no, ns) {
+                    diagnostics.push(Diagnostic::error(
+                        p.ty.loc(),
+                        format!(
+                            "parameter of type ‘{}’ in public or external function requires ‘pragma abicoder v2’",
+                            ty.to_string(ns)
+                        ),
+                    ).with_code(codes::ABI_ENCODING));
                     success = false;
                 }
 
@@ -813,7 +951,7 @@ pub fn resolve_params(
                             *storage.loc(),
                                 format!("data location ‘{}’ can only be specified for array, struct or mapping",
                                 storage)
-                            ));
+                            ).with_code(codes::DATA_LOCATION));
                         success = false;
                     }
 
@@ -824,7 +962,7 @@ pub fn resolve_params(
                             loc,
                             "parameter of type ‘storage’ not allowed public or external functions"
                                 .to_string(),
-                        ));
+                        ).with_code(codes::ABI_ENCODING));
                         success = false;
                     }
 
@@ -833,10 +971,13 @@ pub fn resolve_params(
                     Type::StorageRef(Box::new(ty))
                 } else {
                     if ty.contains_mapping(ns) {
-                        diagnostics.push(Diagnostic::error(
-                            p.ty.loc(),
-                            "parameter with mapping type must be of type ‘storage’".to_string(),
-                        ));
+                        diagnostics.push(
+                            Diagnostic::error(
+                                p.ty.loc(),
+                                "parameter with mapping type must be of type ‘storage’".to_string(),
+                            )
+                            .with_code(codes::MAPPING_TYPE),
+                        );
                         success = false;
                     }
 
@@ -878,7 +1019,10 @@ pub fn resolve_returns(
         let r = match r {
             Some(r) => r,
             None => {
-                diagnostics.push(Diagnostic::error(*loc, "missing return type".to_owned()));
+                diagnostics.push(
+                    Diagnostic::error(*loc, "missing return type".to_owned())
+                        .with_code(codes::FUNCTION_DECLARATION),
+                );
                 success = false;
                 continue;
             }
@@ -893,7 +1037,18 @@ pub fn resolve_returns(
                         r.ty.loc(),
                         "return type ‘function internal’ not allowed public or external functions"
                             .to_string(),
-                    ));
+                    ).with_code(codes::ABI_ENCODING));
+                    success = false;
+                }
+
+                if !is_internal && needs_abicoder_v2(&ty, file_no, ns) {
+                    diagnostics.push(Diagnostic::error(
+                        r.ty.loc(),
+                        format!(
+                            "return type ‘{}’ in public or external function requires ‘pragma abicoder v2’",
+                            ty.to_string(ns)
+                        ),
+                    ).with_code(codes::ABI_ENCODING));
                     success = false;
                 }
 
@@ -903,7 +1058,7 @@ pub fn resolve_returns(
                             *storage.loc(),
                                 format!("data location ‘{}’ can only be specified for array, struct or mapping",
                                 storage)
-                            ));
+                            ).with_code(codes::DATA_LOCATION));
                         success = false;
                     }
 
@@ -911,11 +1066,14 @@ pub fn resolve_returns(
                 } else {
                     match r.storage {
                         Some(pt::StorageLocation::Calldata(loc)) => {
-                            diagnostics.push(Diagnostic::error(
-                                loc,
-                                "data location ‘calldata’ can not be used for return types"
-                                    .to_string(),
-                            ));
+                            diagnostics.push(
+                                Diagnostic::error(
+                                    loc,
+                                    "data location ‘calldata’ can not be used for return types"
+                                        .to_string(),
+                                )
+                                .with_code(codes::DATA_LOCATION),
+                            );
                             success = false;
 
                             ty_loc.2 = loc.2;
@@ -928,7 +1086,7 @@ pub fn resolve_returns(
                                     loc,
                                     "return type of type ‘storage’ not allowed public or external functions"
                                         .to_string(),
-                                ));
+                                ).with_code(codes::ABI_ENCODING));
                                 success = false;
                             }
 
@@ -938,11 +1096,14 @@ pub fn resolve_returns(
                         }
                         _ => {
                             if ty.contains_mapping(ns) {
-                                diagnostics.push(Diagnostic::error(
-                                    r.ty.loc(),
-                                    "return type containing mapping must be of type ‘storage’"
-                                        .to_string(),
-                                ));
+                                diagnostics.push(
+                                    Diagnostic::error(
+                                        r.ty.loc(),
+                                        "return type containing mapping must be of type ‘storage’"
+                                            .to_string(),
+                                    )
+                                    .with_code(codes::MAPPING_TYPE),
+                                );
                                 success = false;
                             }
 
@@ -970,6 +1131,12 @@ pub fn resolve_returns(
     (resolved_returns, success)
 }
 
+/// ABI coder v1 cannot encode structs or nested dynamic arrays. This only matters on
+/// targets which use the Ethereum ABI.
+fn needs_abicoder_v2(ty: &Type, file_no: usize, ns: &Namespace) -> bool {
+    ns.target.is_ethereum() && ns.abicoder(file_no) == AbiCoder::V1 && ty.requires_abicoder_v2(ns)
+}
+
 #[test]
 fn signatures() {
     use super::*;
@@ -1,4 +1,4 @@
-use crate::parser::{parse, pt};
+use crate::parser::pt;
 use crate::Target;
 use ast::Diagnostic;
 use num_bigint::BigInt;
@@ -8,7 +8,9 @@ use std::collections::HashMap;
 
 mod address;
 pub mod ast;
+pub mod ast_json;
 pub mod builtin;
+pub mod codes;
 pub mod contracts;
 pub mod diagnostics;
 pub mod eval;
@@ -22,6 +24,7 @@ pub mod symtable;
 pub mod tags;
 mod types;
 mod variables;
+mod version;
 
 use self::contracts::visit_bases;
 use self::eval::eval_const_number;
@@ -47,16 +50,53 @@ pub fn sema(file: ResolvedFile, cache: &mut FileCache, ns: &mut ast::Namespace)
     let source_code = cache.get_file_contents(&file.full_path);
 
     ns.files.push(file.full_path.clone());
+    ns.pragmas.push(ast::FilePragmas::default());
 
-    let pt = match parse(&source_code, file_no) {
+    // nobody is waiting for the result anymore
+    if cache.is_cancelled() {
+        return;
+    }
+
+    let tree = cache.parse(file_no, &source_code);
+
+    let (pt, comments, parser_errors) = match tree.as_ref() {
         Ok(s) => s,
         Err(errors) => {
-            ns.diagnostics.extend(errors);
+            ns.diagnostics.extend(errors.iter().cloned());
 
             return;
         }
     };
 
+    diagnostics::resolve_suppressions(comments, ns);
+
+    let start = ns.diagnostics.len();
+
+    sema_file(pt, &file, file_no, cache, ns);
+
+    // The parser recovered from these errors, so the file was resolved as far as possible.
+    // They are only added now so that the resolving does not give up early, but they are
+    // placed before any later diagnostics for the same file.
+    for error in parser_errors {
+        let offset = error.pos.map_or(0, |pos| pos.1);
+
+        let index = ns.diagnostics[start..]
+            .iter()
+            .position(|diag| matches!(diag.pos, Some(pos) if pos.0 == file_no && pos.1 > offset))
+            .map_or(ns.diagnostics.len(), |index| start + index);
+
+        ns.diagnostics.insert(index, error.clone());
+    }
+}
+
+/// Resolve a parsed file
+fn sema_file(
+    pt: &pt::SourceUnit,
+    file: &ResolvedFile,
+    file_no: usize,
+    cache: &mut FileCache,
+    ns: &mut ast::Namespace,
+) {
     // We need to iterate over the parsed contracts a few times, so create a temporary vector
     // This should be done before the contract types are created so the contract type numbers line up
     let contracts_to_resolve =
@@ -73,16 +113,16 @@ pub fn sema(file: ResolvedFile, cache: &mut FileCache, ns: &mut ast::Namespace)
             .collect::<Vec<(usize, &pt::ContractDefinition)>>();
 
     // first resolve all the types we can find
-    let fields = types::resolve_typenames(&pt, file_no, ns);
+    let fields = types::resolve_typenames(pt, file_no, ns);
 
     // resolve pragmas and imports
     for part in &pt.0 {
         match part {
-            pt::SourceUnitPart::PragmaDirective(name, value) => {
-                resolve_pragma(name, value, ns);
+            pt::SourceUnitPart::PragmaDirective(loc, name, value) => {
+                resolve_pragma(loc, name, value, ns);
             }
             pt::SourceUnitPart::ImportDirective(import) => {
-                resolve_import(import, Some(&file), file_no, cache, ns);
+                resolve_import(import, Some(file), file_no, cache, ns);
             }
             _ => (),
         }
@@ -92,8 +132,8 @@ pub fn sema(file: ResolvedFile, cache: &mut FileCache, ns: &mut ast::Namespace)
     // struct fields or event fields can have types defined elsewhere.
     types::resolve_fields(fields, file_no, ns);
 
-    // give up if we failed
-    if diagnostics::any_errors(&ns.diagnostics) {
+    // give up if we failed, or if the result is no longer needed
+    if diagnostics::any_errors(&ns.diagnostics) || cache.is_cancelled() {
         return;
     }
 
@@ -126,14 +166,18 @@ pub fn sema(file: ResolvedFile, cache: &mut FileCache, ns: &mut ast::Namespace)
     for part in &pt.0 {
         match part {
             pt::SourceUnitPart::StraySemicolon(loc) => {
-                ns.diagnostics
-                    .push(ast::Diagnostic::error(*loc, "stray semicolon".to_string()));
+                ns.diagnostics.push(
+                    ast::Diagnostic::error(*loc, "stray semicolon".to_string())
+                        .with_code(codes::STRAY_SEMICOLON),
+                );
             }
             pt::SourceUnitPart::ContractDefinition(contract) => {
                 for part in &contract.parts {
                     if let pt::ContractPart::StraySemicolon(loc) = part {
-                        ns.diagnostics
-                            .push(ast::Diagnostic::error(*loc, "stray semicolon".to_string()));
+                        ns.diagnostics.push(
+                            ast::Diagnostic::error(*loc, "stray semicolon".to_string())
+                                .with_code(codes::STRAY_SEMICOLON),
+                        );
                     }
                 }
             }
@@ -162,7 +206,7 @@ fn resolve_import(
     let import_file_no = match cache.resolve_file(parent, &filename.string) {
         Err(message) => {
             ns.diagnostics
-                .push(ast::Diagnostic::error(filename.loc, message));
+                .push(ast::Diagnostic::error(filename.loc, message).with_code(codes::IMPORT));
 
             return;
         }
@@ -183,6 +227,11 @@ fn resolve_import(
         }
     };
 
+    ns.name_references.insert(
+        filename.loc,
+        ast::Symbol::Import(filename.loc, import_file_no),
+    );
+
     match import {
         pt::Import::Rename(_, renames) => {
             for (from, rename_to) in renames {
@@ -208,14 +257,17 @@ fn resolve_import(
 
                     ns.add_symbol(file_no, None, new_symbol, import);
                 } else {
-                    ns.diagnostics.push(ast::Diagnostic::error(
-                        from.loc,
-                        format!(
-                            "import ‘{}’ does not export ‘{}’",
-                            filename.string,
-                            from.name.to_string()
-                        ),
-                    ));
+                    ns.diagnostics.push(
+                        ast::Diagnostic::error(
+                            from.loc,
+                            format!(
+                                "import ‘{}’ does not export ‘{}’",
+                                filename.string,
+                                from.name.to_string()
+                            ),
+                        )
+                        .with_code(codes::NOT_FOUND),
+                    );
                 }
             }
         }
@@ -267,31 +319,95 @@ fn resolve_import(
     }
 }
 
-/// Resolve pragma. We don't do anything with pragmas for now
-fn resolve_pragma(name: &pt::Identifier, value: &pt::StringLiteral, ns: &mut ast::Namespace) {
+/// Resolve pragma. `pragma solidity` selects the language version of the file, and
+/// `pragma abicoder` the ABI encoder
+fn resolve_pragma(
+    loc: &pt::Loc,
+    name: &pt::Identifier,
+    value: &pt::StringLiteral,
+    ns: &mut ast::Namespace,
+) {
+    let file_no = loc.0;
+    let pragma_loc = pt::Loc(name.loc.0, name.loc.1, value.loc.2);
+
     if name.name == "solidity" {
-        ns.diagnostics.push(ast::Diagnostic::debug(
-            pt::Loc(name.loc.0, name.loc.1, value.loc.2),
-            "pragma ‘solidity’ is ignored".to_string(),
-        ));
-    } else if name.name == "experimental" && value.string == "ABIEncoderV2" {
-        ns.diagnostics.push(ast::Diagnostic::debug(
-            pt::Loc(name.loc.0, name.loc.1, value.loc.2),
-            "pragma ‘experimental’ with value ‘ABIEncoderV2’ is ignored".to_string(),
-        ));
-    } else if name.name == "abicoder" && value.string == "v2" {
-        ns.diagnostics.push(ast::Diagnostic::debug(
-            pt::Loc(name.loc.0, name.loc.1, value.loc.2),
-            "pragma ‘abicoder’ with value ‘v2’ is ignored".to_string(),
-        ));
+        let range = match version::parse_range(&value.string) {
+            Ok(range) => range,
+            Err(message) => {
+                ns.diagnostics.push(
+                    ast::Diagnostic::error(value.loc, format!("pragma ‘solidity’: {}", message))
+                        .with_code(codes::PRAGMA_SOLIDITY),
+                );
+                return;
+            }
+        };
+
+        let earlier = ns.pragmas[file_no].solidity.clone();
+
+        // every pragma must match, so only versions which all of them allow can be selected
+        let range = match &earlier {
+            Some((_, earlier_range)) => earlier_range.intersect(&range),
+            None => range,
+        };
+
+        if let Some((earlier_loc, _)) = earlier.as_ref().filter(|_| range.is_empty()) {
+            ns.diagnostics.push(
+                ast::Diagnostic::error_with_note(
+                    pragma_loc,
+                    format!(
+                        "pragma ‘solidity’ with value ‘{}’ conflicts with an earlier pragma ‘solidity’",
+                        value.string
+                    ),
+                    *earlier_loc,
+                    String::from("earlier pragma ‘solidity’"),
+                )
+                .with_code(codes::PRAGMA_SOLIDITY),
+            );
+            return;
+        }
+
+        match range.highest_supported() {
+            Some(version) => {
+                let pragmas = &mut ns.pragmas[file_no];
+
+                pragmas.version = Some(version);
+                pragmas.solidity = Some((earlier.map_or(pragma_loc, |(loc, _)| loc), range));
+
+                ns.diagnostics.push(ast::Diagnostic::debug(
+                    pragma_loc,
+                    format!("pragma ‘solidity’ selects Solidity {}", version),
+                ));
+            }
+            None => {
+                ns.diagnostics.push(ast::Diagnostic::error(
+                    pragma_loc,
+                    format!(
+                        "pragma ‘solidity’ with value ‘{}’ does not match Solidity 0.{} to 0.{}, which Solang implements",
+                        value.string,
+                        version::SUPPORTED_MINOR.start(),
+                        version::SUPPORTED_MINOR.end()
+                    ),
+                ).with_code(codes::PRAGMA_SOLIDITY));
+            }
+        }
+    } else if (name.name == "experimental" && value.string == "ABIEncoderV2")
+        || (name.name == "abicoder" && value.string == "v2")
+    {
+        ns.pragmas[file_no].abicoder = Some(ast::AbiCoder::V2);
+    } else if name.name == "abicoder" && value.string == "v1" {
+        ns.pragmas[file_no].abicoder = Some(ast::AbiCoder::V1);
     } else {
-        ns.diagnostics.push(ast::Diagnostic::warning(
-            pt::Loc(name.loc.0, name.loc.1, value.loc.2),
-            format!(
-                "unknown pragma ‘{}’ with value ‘{}’ ignored",
-                name.name, value.string
-            ),
-        ));
+        ns.diagnostics.push(
+            ast::Diagnostic::warning(
+                pragma_loc,
+                format!(
+                    "unknown pragma ‘{}’ with value ‘{}’ ignored",
+                    name.name, value.string
+                ),
+            )
+            .with_code(codes::UNKNOWN_PRAGMA)
+            .with_fix(String::from("remove pragma"), vec![(*loc, String::new())]),
+        );
     }
 }
 
@@ -315,6 +431,49 @@ impl ast::Namespace {
             next_id: 0,
             var_constants: HashMap::new(),
             hover_overrides: HashMap::new(),
+            name_references: HashMap::new(),
+            suppressions: Vec::new(),
+            pragmas: Vec::new(),
+        }
+    }
+
+    /// The language version selected by `pragma solidity` in the file, if any
+    pub fn language_version(&self, file_no: usize) -> Option<ast::LanguageVersion> {
+        self.pragmas
+            .get(file_no)
+            .and_then(|pragmas| pragmas.version)
+    }
+
+    /// Is the file written for at least the given version of Solidity. Without a version
+    /// pragma, the latest version is assumed.
+    pub fn version_at_least(&self, file_no: usize, minor: u64) -> bool {
+        self.language_version(file_no)
+            .map_or(true, |v| v >= ast::LanguageVersion { major: 0, minor })
+    }
+
+    /// Does the file have a version pragma which selects at least the given version of
+    /// Solidity. Unlike `version_at_least`, this is false without a version pragma, so
+    /// changes which break existing code only apply when asked for.
+    pub fn pragma_at_least(&self, file_no: usize, minor: u64) -> bool {
+        self.language_version(file_no) >= Some(ast::LanguageVersion { major: 0, minor })
+    }
+
+    /// Arithmetic reverts on overflow from Solidity 0.8. Without a version pragma, this
+    /// depends on the --math-overflow option only.
+    pub fn checked_arithmetic(&self, file_no: usize) -> bool {
+        self.pragma_at_least(file_no, 8)
+    }
+
+    /// The ABI encoder for the file. ABI coder v2 is the default from Solidity 0.8, or if
+    /// there is no version pragma.
+    pub fn abicoder(&self, file_no: usize) -> ast::AbiCoder {
+        match self.pragmas.get(file_no) {
+            Some(ast::FilePragmas {
+                abicoder: Some(abicoder),
+                ..
+            }) => *abicoder,
+            _ if self.version_at_least(file_no, 8) => ast::AbiCoder::V2,
+            _ => ast::AbiCoder::V1,
         }
     }
 
@@ -327,10 +486,13 @@ impl ast::Namespace {
         symbol: ast::Symbol,
     ) -> bool {
         if builtin::is_reserved(&id.name) {
-            self.diagnostics.push(ast::Diagnostic::error(
-                id.loc,
-                format!("‘{}’ shadows name of a builtin", id.name.to_string()),
-            ));
+            self.diagnostics.push(
+                ast::Diagnostic::error(
+                    id.loc,
+                    format!("‘{}’ shadows name of a builtin", id.name.to_string()),
+                )
+                .with_code(codes::ALREADY_DEFINED),
+            );
 
             return false;
         }
@@ -347,11 +509,14 @@ impl ast::Namespace {
                 })
                 .collect();
 
-            self.diagnostics.push(ast::Diagnostic::error_with_notes(
-                id.loc,
-                format!("{} is already defined as a function", id.name.to_string()),
-                notes,
-            ));
+            self.diagnostics.push(
+                ast::Diagnostic::error_with_notes(
+                    id.loc,
+                    format!("{} is already defined as a function", id.name.to_string()),
+                    notes,
+                )
+                .with_code(codes::ALREADY_DEFINED),
+            );
 
             return false;
         }
@@ -362,58 +527,76 @@ impl ast::Namespace {
         {
             match sym {
                 ast::Symbol::Contract(c, _) => {
-                    self.diagnostics.push(ast::Diagnostic::error_with_note(
-                        id.loc,
-                        format!(
-                            "{} is already defined as a contract name",
-                            id.name.to_string()
-                        ),
-                        *c,
-                        "location of previous definition".to_string(),
-                    ));
+                    self.diagnostics.push(
+                        ast::Diagnostic::error_with_note(
+                            id.loc,
+                            format!(
+                                "{} is already defined as a contract name",
+                                id.name.to_string()
+                            ),
+                            *c,
+                            "location of previous definition".to_string(),
+                        )
+                        .with_code(codes::ALREADY_DEFINED),
+                    );
                 }
                 ast::Symbol::Enum(c, _) => {
-                    self.diagnostics.push(ast::Diagnostic::error_with_note(
-                        id.loc,
-                        format!("{} is already defined as an enum", id.name.to_string()),
-                        *c,
-                        "location of previous definition".to_string(),
-                    ));
+                    self.diagnostics.push(
+                        ast::Diagnostic::error_with_note(
+                            id.loc,
+                            format!("{} is already defined as an enum", id.name.to_string()),
+                            *c,
+                            "location of previous definition".to_string(),
+                        )
+                        .with_code(codes::ALREADY_DEFINED),
+                    );
                 }
                 ast::Symbol::Struct(c, _) => {
-                    self.diagnostics.push(ast::Diagnostic::error_with_note(
-                        id.loc,
-                        format!("{} is already defined as a struct", id.name.to_string()),
-                        *c,
-                        "location of previous definition".to_string(),
-                    ));
+                    self.diagnostics.push(
+                        ast::Diagnostic::error_with_note(
+                            id.loc,
+                            format!("{} is already defined as a struct", id.name.to_string()),
+                            *c,
+                            "location of previous definition".to_string(),
+                        )
+                        .with_code(codes::ALREADY_DEFINED),
+                    );
                 }
                 ast::Symbol::Event(events) => {
-                    self.diagnostics.push(ast::Diagnostic::error_with_note(
-                        id.loc,
-                        format!("{} is already defined as an event", id.name.to_string()),
-                        events[0].0,
-                        "location of previous definition".to_string(),
-                    ));
+                    self.diagnostics.push(
+                        ast::Diagnostic::error_with_note(
+                            id.loc,
+                            format!("{} is already defined as an event", id.name.to_string()),
+                            events[0].0,
+                            "location of previous definition".to_string(),
+                        )
+                        .with_code(codes::ALREADY_DEFINED),
+                    );
                 }
                 ast::Symbol::Variable(c, _, _) => {
-                    self.diagnostics.push(ast::Diagnostic::error_with_note(
-                        id.loc,
-                        format!(
-                            "{} is already defined as a contract variable",
-                            id.name.to_string()
-                        ),
-                        *c,
-                        "location of previous definition".to_string(),
-                    ));
+                    self.diagnostics.push(
+                        ast::Diagnostic::error_with_note(
+                            id.loc,
+                            format!(
+                                "{} is already defined as a contract variable",
+                                id.name.to_string()
+                            ),
+                            *c,
+                            "location of previous definition".to_string(),
+                        )
+                        .with_code(codes::ALREADY_DEFINED),
+                    );
                 }
                 ast::Symbol::Import(loc, _) => {
-                    self.diagnostics.push(ast::Diagnostic::error_with_note(
-                        id.loc,
-                        format!("{} is already defined as an import", id.name.to_string()),
-                        *loc,
-                        "location of previous definition".to_string(),
-                    ));
+                    self.diagnostics.push(
+                        ast::Diagnostic::error_with_note(
+                            id.loc,
+                            format!("{} is already defined as an import", id.name.to_string()),
+                            *loc,
+                            "location of previous definition".to_string(),
+                        )
+                        .with_code(codes::ALREADY_DEFINED),
+                    );
                 }
                 ast::Symbol::Function(_) => unreachable!(),
             }
@@ -437,11 +620,14 @@ impl ast::Namespace {
                     })
                     .collect();
 
-                self.diagnostics.push(ast::Diagnostic::warning_with_notes(
-                    id.loc,
-                    format!("{} is already defined as a function", id.name.to_string()),
-                    notes,
-                ));
+                self.diagnostics.push(
+                    ast::Diagnostic::warning_with_notes(
+                        id.loc,
+                        format!("{} is already defined as a function", id.name.to_string()),
+                        notes,
+                    )
+                    .with_code(codes::SHADOWING),
+                );
             }
 
             if let Some(sym) = self
@@ -450,59 +636,77 @@ impl ast::Namespace {
             {
                 match sym {
                     ast::Symbol::Contract(c, _) => {
-                        self.diagnostics.push(ast::Diagnostic::warning_with_note(
-                            id.loc,
-                            format!(
-                                "{} is already defined as a contract name",
-                                id.name.to_string()
-                            ),
-                            *c,
-                            "location of previous definition".to_string(),
-                        ));
+                        self.diagnostics.push(
+                            ast::Diagnostic::warning_with_note(
+                                id.loc,
+                                format!(
+                                    "{} is already defined as a contract name",
+                                    id.name.to_string()
+                                ),
+                                *c,
+                                "location of previous definition".to_string(),
+                            )
+                            .with_code(codes::SHADOWING),
+                        );
                     }
                     ast::Symbol::Enum(c, _) => {
-                        self.diagnostics.push(ast::Diagnostic::warning_with_note(
-                            id.loc,
-                            format!("{} is already defined as an enum", id.name),
-                            *c,
-                            "location of previous definition".to_string(),
-                        ));
+                        self.diagnostics.push(
+                            ast::Diagnostic::warning_with_note(
+                                id.loc,
+                                format!("{} is already defined as an enum", id.name),
+                                *c,
+                                "location of previous definition".to_string(),
+                            )
+                            .with_code(codes::SHADOWING),
+                        );
                     }
                     ast::Symbol::Struct(c, _) => {
-                        self.diagnostics.push(ast::Diagnostic::warning_with_note(
-                            id.loc,
-                            format!("{} is already defined as a struct", id.name),
-                            *c,
-                            "location of previous definition".to_string(),
-                        ));
+                        self.diagnostics.push(
+                            ast::Diagnostic::warning_with_note(
+                                id.loc,
+                                format!("{} is already defined as a struct", id.name),
+                                *c,
+                                "location of previous definition".to_string(),
+                            )
+                            .with_code(codes::SHADOWING),
+                        );
                     }
                     ast::Symbol::Event(e) => {
-                        self.diagnostics.push(ast::Diagnostic::warning_with_note(
-                            id.loc,
-                            format!("{} is already defined as an event", id.name),
-                            e[0].0,
-                            "location of previous definition".to_string(),
-                        ));
+                        self.diagnostics.push(
+                            ast::Diagnostic::warning_with_note(
+                                id.loc,
+                                format!("{} is already defined as an event", id.name),
+                                e[0].0,
+                                "location of previous definition".to_string(),
+                            )
+                            .with_code(codes::SHADOWING),
+                        );
                     }
                     ast::Symbol::Variable(c, _, _) => {
-                        self.diagnostics.push(ast::Diagnostic::warning_with_note(
-                            id.loc,
-                            format!(
-                                "{} is already defined as a contract variable",
-                                id.name.to_string()
-                            ),
-                            *c,
-                            "location of previous definition".to_string(),
-                        ));
+                        self.diagnostics.push(
+                            ast::Diagnostic::warning_with_note(
+                                id.loc,
+                                format!(
+                                    "{} is already defined as a contract variable",
+                                    id.name.to_string()
+                                ),
+                                *c,
+                                "location of previous definition".to_string(),
+                            )
+                            .with_code(codes::SHADOWING),
+                        );
                     }
                     ast::Symbol::Function(_) => unreachable!(),
                     ast::Symbol::Import(loc, _) => {
-                        self.diagnostics.push(ast::Diagnostic::warning_with_note(
-                            id.loc,
-                            format!("{} is already defined as an import", id.name),
-                            *loc,
-                            "location of previous definition".to_string(),
-                        ));
+                        self.diagnostics.push(
+                            ast::Diagnostic::warning_with_note(
+                                id.loc,
+                                format!("{} is already defined as an import", id.name),
+                                *loc,
+                                "location of previous definition".to_string(),
+                            )
+                            .with_code(codes::SHADOWING),
+                        );
                     }
                 }
             }
@@ -573,20 +777,26 @@ impl ast::Namespace {
             self.expr_to_type(file_no, contract_no, expr, diagnostics)?;
 
         if !dimensions.is_empty() {
-            diagnostics.push(ast::Diagnostic::decl_error(
-                expr.loc(),
-                "array type found where event type expected".to_string(),
-            ));
+            diagnostics.push(
+                ast::Diagnostic::decl_error(
+                    expr.loc(),
+                    "array type found where event type expected".to_string(),
+                )
+                .with_code(codes::WRONG_KIND_OF_NAME),
+            );
             return Err(());
         }
 
         let id = match id {
             pt::Expression::Variable(id) => id,
             _ => {
-                diagnostics.push(ast::Diagnostic::decl_error(
-                    expr.loc(),
-                    "expression found where event type expected".to_string(),
-                ));
+                diagnostics.push(
+                    ast::Diagnostic::decl_error(
+                        expr.loc(),
+                        "expression found where event type expected".to_string(),
+                    )
+                    .with_code(codes::WRONG_KIND_OF_NAME),
+                );
                 return Err(());
             }
         };
@@ -648,10 +858,13 @@ impl ast::Namespace {
                 .get(&(file_no, None, id.name.to_owned()))
             {
                 None if events.is_empty() => {
-                    diagnostics.push(ast::Diagnostic::decl_error(
-                        id.loc,
-                        format!("event ‘{}’ not found", id.name),
-                    ));
+                    diagnostics.push(
+                        ast::Diagnostic::decl_error(
+                            id.loc,
+                            format!("event ‘{}’ not found", id.name),
+                        )
+                        .with_code(codes::NOT_FOUND),
+                    );
                     Err(())
                 }
                 None => Ok(events),
@@ -686,27 +899,35 @@ impl ast::Namespace {
 
     pub fn wrong_symbol(sym: Option<&ast::Symbol>, id: &pt::Identifier) -> ast::Diagnostic {
         match sym {
-            None => ast::Diagnostic::decl_error(id.loc, format!("`{}' is not found", id.name)),
+            None => ast::Diagnostic::decl_error(id.loc, format!("`{}' is not found", id.name))
+                .with_code(codes::NOT_FOUND),
             Some(ast::Symbol::Enum(_, _)) => {
                 ast::Diagnostic::decl_error(id.loc, format!("`{}' is an enum", id.name))
+                    .with_code(codes::WRONG_KIND_OF_NAME)
             }
             Some(ast::Symbol::Struct(_, _)) => {
                 ast::Diagnostic::decl_error(id.loc, format!("`{}' is a struct", id.name))
+                    .with_code(codes::WRONG_KIND_OF_NAME)
             }
             Some(ast::Symbol::Event(_)) => {
                 ast::Diagnostic::decl_error(id.loc, format!("`{}' is an event", id.name))
+                    .with_code(codes::WRONG_KIND_OF_NAME)
             }
             Some(ast::Symbol::Function(_)) => {
                 ast::Diagnostic::decl_error(id.loc, format!("`{}' is a function", id.name))
+                    .with_code(codes::WRONG_KIND_OF_NAME)
             }
             Some(ast::Symbol::Contract(_, _)) => {
                 ast::Diagnostic::decl_error(id.loc, format!("`{}' is a contract", id.name))
+                    .with_code(codes::WRONG_KIND_OF_NAME)
             }
             Some(ast::Symbol::Import(_, _)) => {
                 ast::Diagnostic::decl_error(id.loc, format!("`{}' is an import", id.name))
+                    .with_code(codes::WRONG_KIND_OF_NAME)
             }
             Some(ast::Symbol::Variable(_, _, _)) => {
                 ast::Diagnostic::decl_error(id.loc, format!("`{}' is a contract variable", id.name))
+                    .with_code(codes::WRONG_KIND_OF_NAME)
             }
         }
     }
@@ -833,10 +1054,13 @@ impl ast::Namespace {
         id: &pt::Identifier,
     ) {
         if builtin::is_reserved(&id.name) {
-            self.diagnostics.push(ast::Diagnostic::warning(
-                id.loc,
-                format!("‘{}’ shadows name of a builtin", id.name.to_string()),
-            ));
+            self.diagnostics.push(
+                ast::Diagnostic::warning(
+                    id.loc,
+                    format!("‘{}’ shadows name of a builtin", id.name.to_string()),
+                )
+                .with_code(codes::SHADOWS_BUILTIN),
+            );
             return;
         }
 
@@ -859,21 +1083,27 @@ impl ast::Namespace {
         match s {
             Some(ast::Symbol::Enum(loc, _)) => {
                 let loc = *loc;
-                self.diagnostics.push(ast::Diagnostic::warning_with_note(
-                    id.loc,
-                    format!("declaration of `{}' shadows enum definition", id.name),
-                    loc,
-                    "previous definition of enum".to_string(),
-                ));
+                self.diagnostics.push(
+                    ast::Diagnostic::warning_with_note(
+                        id.loc,
+                        format!("declaration of `{}' shadows enum definition", id.name),
+                        loc,
+                        "previous definition of enum".to_string(),
+                    )
+                    .with_code(codes::SHADOWING),
+                );
             }
             Some(ast::Symbol::Struct(loc, _)) => {
                 let loc = *loc;
-                self.diagnostics.push(ast::Diagnostic::warning_with_note(
-                    id.loc,
-                    format!("declaration of `{}' shadows struct definition", id.name),
-                    loc,
-                    "previous definition of struct".to_string(),
-                ));
+                self.diagnostics.push(
+                    ast::Diagnostic::warning_with_note(
+                        id.loc,
+                        format!("declaration of `{}' shadows struct definition", id.name),
+                        loc,
+                        "previous definition of struct".to_string(),
+                    )
+                    .with_code(codes::SHADOWING),
+                );
             }
             Some(ast::Symbol::Event(events)) => {
                 let notes = events
@@ -884,11 +1114,14 @@ impl ast::Namespace {
                     })
                     .collect();
 
-                self.diagnostics.push(ast::Diagnostic::warning_with_notes(
-                    id.loc,
-                    format!("declaration of `{}' shadows event definition", id.name),
-                    notes,
-                ));
+                self.diagnostics.push(
+                    ast::Diagnostic::warning_with_notes(
+                        id.loc,
+                        format!("declaration of `{}' shadows event definition", id.name),
+                        notes,
+                    )
+                    .with_code(codes::SHADOWING),
+                );
             }
             Some(ast::Symbol::Function(v)) => {
                 let notes = v
@@ -898,38 +1131,50 @@ impl ast::Namespace {
                         message: "previous declaration of function".to_owned(),
                     })
                     .collect();
-                self.diagnostics.push(ast::Diagnostic::warning_with_notes(
-                    id.loc,
-                    format!("declaration of ‘{}’ shadows function", id.name),
-                    notes,
-                ));
+                self.diagnostics.push(
+                    ast::Diagnostic::warning_with_notes(
+                        id.loc,
+                        format!("declaration of ‘{}’ shadows function", id.name),
+                        notes,
+                    )
+                    .with_code(codes::SHADOWING),
+                );
             }
             Some(ast::Symbol::Variable(loc, _, _)) => {
                 let loc = *loc;
-                self.diagnostics.push(ast::Diagnostic::warning_with_note(
-                    id.loc,
-                    format!("declaration of ‘{}’ shadows state variable", id.name),
-                    loc,
-                    "previous declaration of state variable".to_string(),
-                ));
+                self.diagnostics.push(
+                    ast::Diagnostic::warning_with_note(
+                        id.loc,
+                        format!("declaration of ‘{}’ shadows state variable", id.name),
+                        loc,
+                        "previous declaration of state variable".to_string(),
+                    )
+                    .with_code(codes::SHADOWING),
+                );
             }
             Some(ast::Symbol::Contract(loc, _)) => {
                 let loc = *loc;
-                self.diagnostics.push(ast::Diagnostic::warning_with_note(
-                    id.loc,
-                    format!("declaration of ‘{}’ shadows contract name", id.name),
-                    loc,
-                    "previous declaration of contract name".to_string(),
-                ));
+                self.diagnostics.push(
+                    ast::Diagnostic::warning_with_note(
+                        id.loc,
+                        format!("declaration of ‘{}’ shadows contract name", id.name),
+                        loc,
+                        "previous declaration of contract name".to_string(),
+                    )
+                    .with_code(codes::SHADOWING),
+                );
             }
             Some(ast::Symbol::Import(loc, _)) => {
                 let loc = *loc;
-                self.diagnostics.push(ast::Diagnostic::warning_with_note(
-                    id.loc,
-                    format!("declaration of ‘{}’ shadows import", id.name),
-                    loc,
-                    "previous declaration of import".to_string(),
-                ));
+                self.diagnostics.push(
+                    ast::Diagnostic::warning_with_note(
+                        id.loc,
+                        format!("declaration of ‘{}’ shadows import", id.name),
+                        loc,
+                        "previous declaration of import".to_string(),
+                    )
+                    .with_code(codes::SHADOWING),
+                );
             }
             None => (),
         }
@@ -955,16 +1200,22 @@ impl ast::Namespace {
             for d in ast_dimensions.iter().rev() {
                 if let Some((loc, n)) = d {
                     if n.is_zero() {
-                        diagnostics.push(ast::Diagnostic::decl_error(
-                            *loc,
-                            "zero size array not permitted".to_string(),
-                        ));
+                        diagnostics.push(
+                            ast::Diagnostic::decl_error(
+                                *loc,
+                                "zero size array not permitted".to_string(),
+                            )
+                            .with_code(codes::OUT_OF_RANGE),
+                        );
                         return Err(());
                     } else if n.is_negative() {
-                        diagnostics.push(ast::Diagnostic::decl_error(
-                            *loc,
-                            "negative size of array declared".to_string(),
-                        ));
+                        diagnostics.push(
+                            ast::Diagnostic::decl_error(
+                                *loc,
+                                "negative size of array declared".to_string(),
+                            )
+                            .with_code(codes::OUT_OF_RANGE),
+                        );
                         return Err(());
                     }
                     dimensions.push(Some(n.clone()));
@@ -989,24 +1240,33 @@ impl ast::Namespace {
 
                     match key {
                         ast::Type::Mapping(_, _) => {
-                            diagnostics.push(ast::Diagnostic::decl_error(
-                                k.loc(),
-                                "key of mapping cannot be another mapping type".to_string(),
-                            ));
+                            diagnostics.push(
+                                ast::Diagnostic::decl_error(
+                                    k.loc(),
+                                    "key of mapping cannot be another mapping type".to_string(),
+                                )
+                                .with_code(codes::MAPPING_TYPE),
+                            );
                             return Err(());
                         }
                         ast::Type::Struct(_) => {
-                            diagnostics.push(ast::Diagnostic::decl_error(
-                                k.loc(),
-                                "key of mapping cannot be struct type".to_string(),
-                            ));
+                            diagnostics.push(
+                                ast::Diagnostic::decl_error(
+                                    k.loc(),
+                                    "key of mapping cannot be struct type".to_string(),
+                                )
+                                .with_code(codes::MAPPING_TYPE),
+                            );
                             return Err(());
                         }
                         ast::Type::Array(_, _) => {
-                            diagnostics.push(ast::Diagnostic::decl_error(
-                                k.loc(),
-                                "key of mapping cannot be array type".to_string(),
-                            ));
+                            diagnostics.push(
+                                ast::Diagnostic::decl_error(
+                                    k.loc(),
+                                    "key of mapping cannot be array type".to_string(),
+                                )
+                                .with_code(codes::MAPPING_TYPE),
+                            );
                             return Err(());
                         }
                         _ => ast::Type::Mapping(Box::new(key), Box::new(value)),
@@ -1027,27 +1287,34 @@ impl ast::Namespace {
                         match a {
                             pt::FunctionAttribute::StateMutability(m) => {
                                 if let Some(e) = &mutability {
-                                    diagnostics.push(ast::Diagnostic::error_with_note(
-                                        m.loc(),
-                                        format!(
-                                            "function type mutability redeclared `{}'",
-                                            m.to_string()
-                                        ),
-                                        e.loc(),
-                                        format!(
+                                    diagnostics.push(
+                                        ast::Diagnostic::error_with_note(
+                                            m.loc(),
+                                            format!(
+                                                "function type mutability redeclared `{}'",
+                                                m.to_string()
+                                            ),
+                                            e.loc(),
+                                            format!(
                                             "location of previous mutability declaration of `{}'",
                                             e.to_string()
                                         ),
-                                    ));
+                                        )
+                                        .with_code(codes::ALREADY_DEFINED),
+                                    );
                                     success = false;
                                     continue;
                                 }
 
                                 if let pt::StateMutability::Constant(loc) = m {
-                                    diagnostics.push(ast::Diagnostic::warning(
-                                        *loc,
-                                        "‘constant’ is deprecated. Use ‘view’ instead".to_string(),
-                                    ));
+                                    diagnostics.push(
+                                        ast::Diagnostic::warning(
+                                            *loc,
+                                            "‘constant’ is deprecated. Use ‘view’ instead"
+                                                .to_string(),
+                                        )
+                                        .with_code(codes::DEPRECATED_CONSTANT),
+                                    );
 
                                     mutability = Some(pt::StateMutability::View(*loc));
                                 } else {
@@ -1056,18 +1323,21 @@ impl ast::Namespace {
                             }
                             pt::FunctionAttribute::Visibility(v) => {
                                 if let Some(e) = &visibility {
-                                    diagnostics.push(ast::Diagnostic::error_with_note(
-                                        v.loc(),
-                                        format!(
-                                            "function type visibility redeclared `{}'",
-                                            v.to_string()
-                                        ),
-                                        e.loc(),
-                                        format!(
+                                    diagnostics.push(
+                                        ast::Diagnostic::error_with_note(
+                                            v.loc(),
+                                            format!(
+                                                "function type visibility redeclared `{}'",
+                                                v.to_string()
+                                            ),
+                                            e.loc(),
+                                            format!(
                                             "location of previous visibility declaration of `{}'",
                                             e.to_string()
                                         ),
-                                    ));
+                                        )
+                                        .with_code(codes::ALREADY_DEFINED),
+                                    );
                                     success = false;
                                     continue;
                                 }
@@ -1082,10 +1352,16 @@ impl ast::Namespace {
                         None | Some(pt::Visibility::Internal(_)) => false,
                         Some(pt::Visibility::External(_)) => true,
                         Some(v) => {
-                            diagnostics.push(ast::Diagnostic::error(
-                                v.loc(),
-                                format!("function type cannot have visibility attribute `{}'", v),
-                            ));
+                            diagnostics.push(
+                                ast::Diagnostic::error(
+                                    v.loc(),
+                                    format!(
+                                        "function type cannot have visibility attribute `{}'",
+                                        v
+                                    ),
+                                )
+                                .with_code(codes::FUNCTION_DECLARATION),
+                            );
                             success = false;
                             false
                         }
@@ -1114,23 +1390,29 @@ impl ast::Namespace {
                     for a in trailing_attributes {
                         match a {
                             pt::FunctionAttribute::StateMutability(m) => {
-                                diagnostics.push(ast::Diagnostic::error(
-                                    m.loc(),
-                                    format!(
-                                        "mutability `{}' cannot be declared after returns",
-                                        m.to_string()
-                                    ),
-                                ));
+                                diagnostics.push(
+                                    ast::Diagnostic::error(
+                                        m.loc(),
+                                        format!(
+                                            "mutability `{}' cannot be declared after returns",
+                                            m.to_string()
+                                        ),
+                                    )
+                                    .with_code(codes::FUNCTION_DECLARATION),
+                                );
                                 success = false;
                             }
                             pt::FunctionAttribute::Visibility(v) => {
-                                diagnostics.push(ast::Diagnostic::error(
-                                    v.loc(),
-                                    format!(
-                                        "visibility `{}' cannot be declared after returns",
-                                        v.to_string()
-                                    ),
-                                ));
+                                diagnostics.push(
+                                    ast::Diagnostic::error(
+                                        v.loc(),
+                                        format!(
+                                            "visibility `{}' cannot be declared after returns",
+                                            v.to_string()
+                                        ),
+                                    )
+                                    .with_code(codes::FUNCTION_DECLARATION),
+                                );
                                 success = false;
                             }
                             _ => unreachable!(),
@@ -1145,10 +1427,13 @@ impl ast::Namespace {
                         .into_iter()
                         .map(|p| {
                             if !p.name.is_empty() {
-                                diagnostics.push(ast::Diagnostic::error(
-                                    p.name_loc.unwrap(),
-                                    "function type parameters cannot be named".to_string(),
-                                ));
+                                diagnostics.push(
+                                    ast::Diagnostic::error(
+                                        p.name_loc.unwrap(),
+                                        "function type parameters cannot be named".to_string(),
+                                    )
+                                    .with_code(codes::FUNCTION_DECLARATION),
+                                );
                                 success = false;
                             }
                             p.ty
@@ -1159,10 +1444,13 @@ impl ast::Namespace {
                         .into_iter()
                         .map(|p| {
                             if !p.name.is_empty() {
-                                diagnostics.push(ast::Diagnostic::error(
-                                    p.name_loc.unwrap(),
-                                    "function type returns cannot be named".to_string(),
-                                ));
+                                diagnostics.push(
+                                    ast::Diagnostic::error(
+                                        p.name_loc.unwrap(),
+                                        "function type returns cannot be named".to_string(),
+                                    )
+                                    .with_code(codes::FUNCTION_DECLARATION),
+                                );
                                 success = false;
                             }
                             p.ty
@@ -1189,7 +1477,7 @@ impl ast::Namespace {
                             id.loc(),
                             "‘payable’ cannot be used for type declarations, only casting. use ‘address payable’"
                                 .to_string(),
-                        ));
+                        ).with_code(codes::TYPE_NOT_ALLOWED));
                         return Err(());
                     } else {
                         ast::Type::Address(true)
@@ -1213,14 +1501,20 @@ impl ast::Namespace {
             _ => unreachable!(),
         };
 
-        let s = self.resolve_namespace(namespace, file_no, contract_no, &id, diagnostics)?;
+        let s = self
+            .resolve_namespace(namespace, file_no, contract_no, &id, diagnostics)?
+            .cloned();
 
-        match s {
+        if let Some(symbol) = &s {
+            self.name_references.insert(id.loc, symbol.clone());
+        }
+
+        match &s {
             None => {
-                diagnostics.push(ast::Diagnostic::decl_error(
-                    id.loc,
-                    format!("type ‘{}’ not found", id.name),
-                ));
+                diagnostics.push(
+                    ast::Diagnostic::decl_error(id.loc, format!("type ‘{}’ not found", id.name))
+                        .with_code(codes::NOT_FOUND),
+                );
                 Err(())
             }
             Some(ast::Symbol::Enum(_, n)) if dimensions.is_empty() => Ok(ast::Type::Enum(*n)),
@@ -1241,31 +1535,37 @@ impl ast::Namespace {
                 resolve_dimensions(&dimensions, diagnostics)?,
             )),
             Some(ast::Symbol::Event(_)) => {
-                diagnostics.push(ast::Diagnostic::decl_error(
-                    id.loc,
-                    format!("‘{}’ is an event", id.name),
-                ));
+                diagnostics.push(
+                    ast::Diagnostic::decl_error(id.loc, format!("‘{}’ is an event", id.name))
+                        .with_code(codes::WRONG_KIND_OF_NAME),
+                );
                 Err(())
             }
             Some(ast::Symbol::Function(_)) => {
-                diagnostics.push(ast::Diagnostic::decl_error(
-                    id.loc,
-                    format!("‘{}’ is a function", id.name),
-                ));
+                diagnostics.push(
+                    ast::Diagnostic::decl_error(id.loc, format!("‘{}’ is a function", id.name))
+                        .with_code(codes::WRONG_KIND_OF_NAME),
+                );
                 Err(())
             }
             Some(ast::Symbol::Variable(_, _, _)) => {
-                diagnostics.push(ast::Diagnostic::decl_error(
-                    id.loc,
-                    format!("‘{}’ is a contract variable", id.name),
-                ));
+                diagnostics.push(
+                    ast::Diagnostic::decl_error(
+                        id.loc,
+                        format!("‘{}’ is a contract variable", id.name),
+                    )
+                    .with_code(codes::WRONG_KIND_OF_NAME),
+                );
                 Err(())
             }
             Some(ast::Symbol::Import(_, _)) => {
-                diagnostics.push(ast::Diagnostic::decl_error(
-                    id.loc,
-                    format!("‘{}’ is an import variable", id.name),
-                ));
+                diagnostics.push(
+                    ast::Diagnostic::decl_error(
+                        id.loc,
+                        format!("‘{}’ is an import variable", id.name),
+                    )
+                    .with_code(codes::WRONG_KIND_OF_NAME),
+                );
                 Err(())
             }
         }
@@ -1305,55 +1605,67 @@ impl ast::Namespace {
                         .get(&(import_file_no, None, contract_name.name.clone()))
                 }) {
                 None => {
-                    diagnostics.push(ast::Diagnostic::decl_error(
-                        id.loc,
-                        format!("contract type ‘{}’ not found", id.name),
-                    ));
+                    diagnostics.push(
+                        ast::Diagnostic::decl_error(
+                            id.loc,
+                            format!("contract type ‘{}’ not found", id.name),
+                        )
+                        .with_code(codes::NOT_FOUND),
+                    );
                     return Err(());
                 }
                 Some(ast::Symbol::Contract(_, n)) => {
                     if namespace.len() > 1 {
-                        diagnostics.push(ast::Diagnostic::decl_error(
-                            id.loc,
-                            format!("‘{}’ not found", namespace[1].name),
-                        ));
+                        diagnostics.push(
+                            ast::Diagnostic::decl_error(
+                                id.loc,
+                                format!("‘{}’ not found", namespace[1].name),
+                            )
+                            .with_code(codes::NOT_FOUND),
+                        );
                         return Err(());
                     };
                     Some(*n)
                 }
                 Some(ast::Symbol::Function(_)) => {
-                    diagnostics.push(ast::Diagnostic::decl_error(
-                        id.loc,
-                        format!("‘{}’ is a function", id.name),
-                    ));
+                    diagnostics.push(
+                        ast::Diagnostic::decl_error(id.loc, format!("‘{}’ is a function", id.name))
+                            .with_code(codes::WRONG_KIND_OF_NAME),
+                    );
                     return Err(());
                 }
                 Some(ast::Symbol::Variable(_, _, _)) => {
-                    diagnostics.push(ast::Diagnostic::decl_error(
-                        id.loc,
-                        format!("‘{}’ is a contract variable", id.name),
-                    ));
+                    diagnostics.push(
+                        ast::Diagnostic::decl_error(
+                            id.loc,
+                            format!("‘{}’ is a contract variable", id.name),
+                        )
+                        .with_code(codes::WRONG_KIND_OF_NAME),
+                    );
                     return Err(());
                 }
                 Some(ast::Symbol::Event(_)) => {
-                    diagnostics.push(ast::Diagnostic::decl_error(
-                        id.loc,
-                        format!("‘{}’ is an event", id.name),
-                    ));
+                    diagnostics.push(
+                        ast::Diagnostic::decl_error(id.loc, format!("‘{}’ is an event", id.name))
+                            .with_code(codes::WRONG_KIND_OF_NAME),
+                    );
                     return Err(());
                 }
                 Some(ast::Symbol::Struct(_, _)) => {
-                    diagnostics.push(ast::Diagnostic::decl_error(
-                        id.loc,
-                        format!("‘{}’ is a struct", id.name),
-                    ));
+                    diagnostics.push(
+                        ast::Diagnostic::decl_error(id.loc, format!("‘{}’ is a struct", id.name))
+                            .with_code(codes::WRONG_KIND_OF_NAME),
+                    );
                     return Err(());
                 }
                 Some(ast::Symbol::Enum(_, _)) => {
-                    diagnostics.push(ast::Diagnostic::decl_error(
-                        id.loc,
-                        format!("‘{}’ is an enum variable", id.name),
-                    ));
+                    diagnostics.push(
+                        ast::Diagnostic::decl_error(
+                            id.loc,
+                            format!("‘{}’ is an enum variable", id.name),
+                        )
+                        .with_code(codes::WRONG_KIND_OF_NAME),
+                    );
                     return Err(());
                 }
                 Some(ast::Symbol::Import(_, _)) => unreachable!(),
@@ -1443,18 +1755,24 @@ impl ast::Namespace {
 
                         return Ok((names, pt::Expression::Variable(id.clone()), dimensions));
                     } else {
-                        diagnostics.push(ast::Diagnostic::decl_error(
-                            namespace.loc(),
-                            "expression found where type expected".to_string(),
-                        ));
+                        diagnostics.push(
+                            ast::Diagnostic::decl_error(
+                                namespace.loc(),
+                                "expression found where type expected".to_string(),
+                            )
+                            .with_code(codes::WRONG_KIND_OF_NAME),
+                        );
                         return Err(());
                     }
                 }
                 _ => {
-                    diagnostics.push(ast::Diagnostic::decl_error(
-                        expr.loc(),
-                        "expression found where type expected".to_string(),
-                    ));
+                    diagnostics.push(
+                        ast::Diagnostic::decl_error(
+                            expr.loc(),
+                            "expression found where type expected".to_string(),
+                        )
+                        .with_code(codes::WRONG_KIND_OF_NAME),
+                    );
                     return Err(());
                 }
             }
@@ -1485,10 +1803,13 @@ impl ast::Namespace {
         match size_expr.ty() {
             ast::Type::Uint(_) | ast::Type::Int(_) => {}
             _ => {
-                diagnostics.push(ast::Diagnostic::decl_error(
-                    expr.loc(),
-                    "expression is not a number".to_string(),
-                ));
+                diagnostics.push(
+                    ast::Diagnostic::decl_error(
+                        expr.loc(),
+                        "expression is not a number".to_string(),
+                    )
+                    .with_code(codes::TYPE_NOT_ALLOWED),
+                );
                 return Err(());
             }
         }
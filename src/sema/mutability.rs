@@ -1,6 +1,7 @@
 use super::ast::{
     Builtin, DestructureField, Diagnostic, Expression, Function, Namespace, Statement, Type,
 };
+use super::codes;
 use crate::parser::pt;
 
 /// check state mutablity
@@ -30,13 +31,16 @@ struct StateCheck<'a> {
 impl<'a> StateCheck<'a> {
     fn write(&mut self, loc: &pt::Loc) {
         if !self.can_write_state {
-            self.diagnostics.push(Diagnostic::error(
-                *loc,
-                format!(
-                    "function declared ‘{}’ but this expression writes to state",
-                    self.func.print_mutability()
-                ),
-            ));
+            self.diagnostics.push(
+                Diagnostic::error(
+                    *loc,
+                    format!(
+                        "function declared ‘{}’ but this expression writes to state",
+                        self.func.print_mutability()
+                    ),
+                )
+                .with_code(codes::STATE_MUTABILITY),
+            );
         }
 
         self.does_write_state = true;
@@ -44,13 +48,16 @@ impl<'a> StateCheck<'a> {
 
     fn read(&mut self, loc: &pt::Loc) {
         if !self.can_read_state {
-            self.diagnostics.push(Diagnostic::error(
-                *loc,
-                format!(
-                    "function declared ‘{}’ but this expression reads from state",
-                    self.func.print_mutability()
-                ),
-            ));
+            self.diagnostics.push(
+                Diagnostic::error(
+                    *loc,
+                    format!(
+                        "function declared ‘{}’ but this expression reads from state",
+                        self.func.print_mutability()
+                    ),
+                )
+                .with_code(codes::STATE_MUTABILITY),
+            );
         }
 
         self.does_read_state = true;
@@ -119,30 +126,44 @@ fn check_mutability(func: &Function, ns: &Namespace) -> Vec<Diagnostic> {
 
     if pt::FunctionTy::Function == func.ty {
         if !state.does_write_state && !state.does_read_state {
-            match func.mutability {
+            match &func.mutability {
                 Some(pt::StateMutability::Payable(_)) | Some(pt::StateMutability::Pure(_)) => (),
                 None => {
-                    state.diagnostics.push(Diagnostic::warning(
-                        func.loc,
-                        "function can be declared ‘pure’".to_string(),
+                    state.diagnostics.push(with_mutability_fix(
+                        Diagnostic::warning(
+                            func.loc,
+                            "function can be declared ‘pure’".to_string(),
+                        )
+                        .with_code(codes::MUTABILITY),
+                        func,
+                        "pure",
                     ));
                 }
-                _ => {
-                    state.diagnostics.push(Diagnostic::warning(
-                        func.loc,
-                        format!(
-                            "function declared ‘{}’ can be declared ‘pure’",
-                            func.print_mutability()
+                Some(mutability) => {
+                    state.diagnostics.push(
+                        Diagnostic::warning(
+                            func.loc,
+                            format!(
+                                "function declared ‘{}’ can be declared ‘pure’",
+                                func.print_mutability()
+                            ),
+                        )
+                        .with_code(codes::MUTABILITY)
+                        .with_fix(
+                            String::from("declare function ‘pure’"),
+                            vec![(mutability.loc(), String::from("pure"))],
                         ),
-                    ));
+                    );
                 }
             }
         }
 
         if !state.does_write_state && state.does_read_state && func.mutability.is_none() {
-            state.diagnostics.push(Diagnostic::warning(
-                func.loc,
-                "function can be declared ‘view’".to_string(),
+            state.diagnostics.push(with_mutability_fix(
+                Diagnostic::warning(func.loc, "function can be declared ‘view’".to_string())
+                    .with_code(codes::MUTABILITY),
+                func,
+                "view",
             ));
         }
     }
@@ -150,6 +171,17 @@ fn check_mutability(func: &Function, ns: &Namespace) -> Vec<Diagnostic> {
     state.diagnostics
 }
 
+/// Suggest adding the mutability to a function which has none
+fn with_mutability_fix(diagnostic: Diagnostic, func: &Function, mutability: &str) -> Diagnostic {
+    match func.attribute_insert_loc() {
+        Some(loc) => diagnostic.with_fix(
+            format!("declare function ‘{}’", mutability),
+            vec![(loc, format!(" {}", mutability))],
+        ),
+        None => diagnostic,
+    }
+}
+
 fn recurse_statements(stmts: &[Statement], state: &mut StateCheck) {
     for stmt in stmts.iter() {
         match stmt {
@@ -249,7 +281,8 @@ fn read_expression(expr: &Expression, state: &mut StateCheck) -> bool {
         | Expression::Builtin(loc, _, Builtin::GasLimit, _)
         | Expression::Builtin(loc, _, Builtin::TombstoneDeposit, _)
         | Expression::Builtin(loc, _, Builtin::MinimumBalance, _)
-        | Expression::Builtin(loc, _, Builtin::Random, _) => state.read(loc),
+        | Expression::Builtin(loc, _, Builtin::Random, _)
+        | Expression::Builtin(loc, _, Builtin::PredictAddress, _) => state.read(loc),
         Expression::Builtin(loc, _, Builtin::PayableSend, _)
         | Expression::Builtin(loc, _, Builtin::PayableTransfer, _)
         | Expression::Builtin(loc, _, Builtin::ArrayPush, _)
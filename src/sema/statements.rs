@@ -1,4 +1,5 @@
 use super::ast::*;
+use super::codes;
 use super::contracts::is_base;
 use super::expression::{
     available_functions, call_position_args, cast, constructor_named_args, expression,
@@ -6,6 +7,7 @@ use super::expression::{
 };
 use super::symtable::{LoopScopes, Symtable};
 use crate::parser::pt;
+use crate::Target;
 use num_bigint::BigInt;
 use std::collections::HashMap;
 
@@ -48,21 +50,27 @@ pub fn resolve_function_body(
                 match ns.resolve_contract(file_no, &base.name) {
                     Some(base_no) => {
                         if base_no == contract_no || !is_base(base_no, contract_no, ns) {
-                            ns.diagnostics.push(Diagnostic::error(
-                                *loc,
-                                format!(
-                                    "contract ‘{}’ is not a base contract of ‘{}’",
-                                    base.name.name, ns.contracts[contract_no].name,
-                                ),
-                            ));
+                            ns.diagnostics.push(
+                                Diagnostic::error(
+                                    *loc,
+                                    format!(
+                                        "contract ‘{}’ is not a base contract of ‘{}’",
+                                        base.name.name, ns.contracts[contract_no].name,
+                                    ),
+                                )
+                                .with_code(codes::INHERITANCE),
+                            );
                             all_ok = false;
                         } else if let Some(prev) = resolve_bases.get(&base_no) {
-                            ns.diagnostics.push(Diagnostic::error_with_note(
-                                *loc,
-                                format!("duplicate base contract ‘{}’", base.name.name),
-                                *prev,
-                                format!("previous base contract ‘{}’", base.name.name),
-                            ));
+                            ns.diagnostics.push(
+                                Diagnostic::error_with_note(
+                                    *loc,
+                                    format!("duplicate base contract ‘{}’", base.name.name),
+                                    *prev,
+                                    format!("previous base contract ‘{}’", base.name.name),
+                                )
+                                .with_code(codes::INHERITANCE),
+                            );
                             all_ok = false;
                         } else if let Some(args) = &base.args {
                             let mut diagnostics = Vec::new();
@@ -87,27 +95,36 @@ pub fn resolve_function_body(
 
                             ns.diagnostics.extend(diagnostics);
                         } else {
-                            ns.diagnostics.push(Diagnostic::error(
-                                *loc,
-                                format!(
-                                    "missing arguments to constructor of contract ‘{}’",
-                                    base.name.name
-                                ),
-                            ));
+                            ns.diagnostics.push(
+                                Diagnostic::error(
+                                    *loc,
+                                    format!(
+                                        "missing arguments to constructor of contract ‘{}’",
+                                        base.name.name
+                                    ),
+                                )
+                                .with_code(codes::WRONG_ARGUMENTS),
+                            );
                             all_ok = false;
                         }
                     }
                     None => {
                         if base.args.is_none() {
-                            ns.diagnostics.push(Diagnostic::error(
-                                *loc,
-                                format!("unknown function attribute ‘{}’", base.name.name),
-                            ));
+                            ns.diagnostics.push(
+                                Diagnostic::error(
+                                    *loc,
+                                    format!("unknown function attribute ‘{}’", base.name.name),
+                                )
+                                .with_code(codes::FUNCTION_DECLARATION),
+                            );
                         } else {
-                            ns.diagnostics.push(Diagnostic::error(
-                                base.name.loc,
-                                format!("contract ‘{}’ not found", base.name.name),
-                            ));
+                            ns.diagnostics.push(
+                                Diagnostic::error(
+                                    base.name.loc,
+                                    format!("contract ‘{}’ not found", base.name.name),
+                                )
+                                .with_code(codes::NOT_FOUND),
+                            );
                         }
                         all_ok = false;
                     }
@@ -124,13 +141,16 @@ pub fn resolve_function_body(
 
                 // does the contract require arguments
                 if ns.contracts[base.contract_no].constructor_needs_arguments(ns) {
-                    ns.diagnostics.push(Diagnostic::error(
-                        def.loc,
-                        format!(
-                            "missing arguments to contract ‘{}’ constructor",
-                            ns.contracts[base.contract_no].name
-                        ),
-                    ));
+                    ns.diagnostics.push(
+                        Diagnostic::error(
+                            def.loc,
+                            format!(
+                                "missing arguments to contract ‘{}’ constructor",
+                                ns.contracts[base.contract_no].name
+                            ),
+                        )
+                        .with_code(codes::WRONG_ARGUMENTS),
+                    );
                 }
             }
         }
@@ -217,15 +237,27 @@ pub fn resolve_function_body(
 
     ns.diagnostics.extend(diagnostics);
 
-    if reachable? {
+    let reachable = match reachable {
+        Ok(reachable) => reachable,
+        Err(()) => {
+            // keep what was resolved so far, so the language server knows about the variables
+            ns.functions[function_no].body = res;
+
+            std::mem::swap(&mut ns.functions[function_no].symtable, &mut symtable);
+
+            return Err(());
+        }
+    };
+
+    if reachable {
         // ensure we have a return instruction
         if let Some(Statement::Return(_, _)) = res.last() {
             // ok
         } else if return_required {
-            ns.diagnostics.push(Diagnostic::error(
-                body.loc(),
-                "missing return statement".to_string(),
-            ));
+            ns.diagnostics.push(
+                Diagnostic::error(body.loc(), "missing return statement".to_string())
+                    .with_code(codes::STATEMENT_NOT_ALLOWED),
+            );
             return Err(());
         } else {
             // add implicit return
@@ -261,10 +293,10 @@ pub fn resolve_function_body(
         }
 
         if !has_underscore {
-            ns.diagnostics.push(Diagnostic::error(
-                body.loc(),
-                "missing ‘_’ in modifier".to_string(),
-            ));
+            ns.diagnostics.push(
+                Diagnostic::error(body.loc(), "missing ‘_’ in modifier".to_string())
+                    .with_code(codes::STATEMENT_NOT_ALLOWED),
+            );
         }
     }
 
@@ -341,10 +373,10 @@ fn statement(
 
             for stmt in stmts {
                 if !reachable {
-                    ns.diagnostics.push(Diagnostic::error(
-                        stmt.loc(),
-                        "unreachable statement".to_string(),
-                    ));
+                    ns.diagnostics.push(
+                        Diagnostic::error(stmt.loc(), "unreachable statement".to_string())
+                            .with_code(codes::STATEMENT_NOT_ALLOWED),
+                    );
                     return Err(());
                 }
                 reachable = statement(
@@ -369,10 +401,10 @@ fn statement(
                 res.push(Statement::Break(*loc));
                 Ok(false)
             } else {
-                diagnostics.push(Diagnostic::error(
-                    stmt.loc(),
-                    "break statement not in loop".to_string(),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(stmt.loc(), "break statement not in loop".to_string())
+                        .with_code(codes::STATEMENT_NOT_ALLOWED),
+                );
                 Err(())
             }
         }
@@ -381,10 +413,10 @@ fn statement(
                 res.push(Statement::Continue(*loc));
                 Ok(false)
             } else {
-                diagnostics.push(Diagnostic::error(
-                    stmt.loc(),
-                    "continue statement not in loop".to_string(),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(stmt.loc(), "continue statement not in loop".to_string())
+                        .with_code(codes::STATEMENT_NOT_ALLOWED),
+                );
                 Err(())
             }
         }
@@ -511,10 +543,13 @@ fn statement(
             Ok(reachable)
         }
         pt::Statement::Args(loc, _) => {
-            ns.diagnostics.push(Diagnostic::error(
-                *loc,
-                "expected code block, not list of named arguments".to_string(),
-            ));
+            ns.diagnostics.push(
+                Diagnostic::error(
+                    *loc,
+                    "expected code block, not list of named arguments".to_string(),
+                )
+                .with_code(codes::STATEMENT_NOT_ALLOWED),
+            );
             Err(())
         }
         pt::Statement::For(loc, init_stmt, None, next_stmt, body_stmt) => {
@@ -676,13 +711,16 @@ fn statement(
             let no_returns = ns.functions[function_no].returns.len();
 
             if symtable.returns.len() != no_returns {
-                ns.diagnostics.push(Diagnostic::error(
-                    *loc,
-                    format!(
-                        "missing return value, {} return values expected",
-                        no_returns
-                    ),
-                ));
+                ns.diagnostics.push(
+                    Diagnostic::error(
+                        *loc,
+                        format!(
+                            "missing return value, {} return values expected",
+                            no_returns
+                        ),
+                    )
+                    .with_code(codes::RETURN_VALUES),
+                );
                 return Err(());
             }
 
@@ -731,10 +769,13 @@ fn statement(
 
                 return if let Type::StorageRef(ty) = expr.ty() {
                     if expr.ty().is_mapping() {
-                        ns.diagnostics.push(Diagnostic::error(
-                            *loc,
-                            "‘delete’ cannot be applied to mapping type".to_string(),
-                        ));
+                        ns.diagnostics.push(
+                            Diagnostic::error(
+                                *loc,
+                                "‘delete’ cannot be applied to mapping type".to_string(),
+                            )
+                            .with_code(codes::MAPPING_TYPE),
+                        );
                         return Err(());
                     }
 
@@ -742,10 +783,13 @@ fn statement(
 
                     Ok(true)
                 } else {
-                    ns.diagnostics.push(Diagnostic::error(
-                        *loc,
-                        "argument to ‘delete’ should be storage reference".to_string(),
-                    ));
+                    ns.diagnostics.push(
+                        Diagnostic::error(
+                            *loc,
+                            "argument to ‘delete’ should be storage reference".to_string(),
+                        )
+                        .with_code(codes::DATA_LOCATION),
+                    );
 
                     Err(())
                 };
@@ -759,10 +803,13 @@ fn statement(
 
                         Ok(true)
                     } else {
-                        ns.diagnostics.push(Diagnostic::error(
-                            *loc,
-                            "underscore statement only permitted in modifiers".to_string(),
-                        ));
+                        ns.diagnostics.push(
+                            Diagnostic::error(
+                                *loc,
+                                "underscore statement only permitted in modifiers".to_string(),
+                            )
+                            .with_code(codes::STATEMENT_NOT_ALLOWED),
+                        );
                         Err(())
                     };
                 }
@@ -824,7 +871,52 @@ fn statement(
 
             Ok(reachable)
         }
+        pt::Statement::Throw(loc) => {
+            if ns.version_at_least(file_no, 5) {
+                diagnostics.push(
+                    Diagnostic::error(
+                        *loc,
+                        "‘throw’ was removed in Solidity 0.5, use ‘revert()’ instead".to_string(),
+                    )
+                    .with_code(codes::STATEMENT_NOT_ALLOWED),
+                );
+                return Err(());
+            }
+
+            // before Solidity 0.5, ‘throw’ is the same as ‘revert()’
+            let revert = pt::Statement::Expression(
+                *loc,
+                pt::Expression::FunctionCall(
+                    *loc,
+                    Box::new(pt::Expression::Variable(pt::Identifier {
+                        loc: *loc,
+                        name: String::from("revert"),
+                    })),
+                    Vec::new(),
+                ),
+            );
+
+            statement(
+                &revert,
+                res,
+                file_no,
+                contract_no,
+                function_no,
+                symtable,
+                loops,
+                ns,
+                diagnostics,
+            )
+        }
         pt::Statement::Emit(loc, ty) => {
+            if ns.target == Target::Sabre {
+                diagnostics.push(
+                    Diagnostic::error(*loc, format!("events not supported on {}", ns.target))
+                        .with_code(codes::TARGET_NOT_SUPPORTED),
+                );
+                return Ok(true);
+            }
+
             if let Ok(emit) = emit_event(
                 loc,
                 ty,
@@ -840,6 +932,8 @@ fn statement(
 
             Ok(true)
         }
+        // the parser has already reported the error
+        pt::Statement::Error(_) => Err(()),
     }
 }
 
@@ -864,15 +958,18 @@ fn emit_event(
             for event_no in &event_nos {
                 let event = &ns.events[*event_no];
                 if args.len() != event.fields.len() {
-                    temp_diagnostics.push(Diagnostic::error(
-                        *loc,
-                        format!(
-                            "event type ‘{}’ has {} fields, {} provided",
-                            event.name,
-                            event.fields.len(),
-                            args.len()
-                        ),
-                    ));
+                    temp_diagnostics.push(
+                        Diagnostic::error(
+                            *loc,
+                            format!(
+                                "event type ‘{}’ has {} fields, {} provided",
+                                event.name,
+                                event.fields.len(),
+                                args.len()
+                            ),
+                        )
+                        .with_code(codes::WRONG_ARGUMENTS),
+                    );
                     continue;
                 }
                 let mut cast_args = Vec::new();
@@ -931,10 +1028,13 @@ fn emit_event(
             if event_nos.len() == 1 {
                 diagnostics.extend(temp_diagnostics);
             } else {
-                diagnostics.push(Diagnostic::error(
-                    *loc,
-                    "cannot find event which matches signature".to_string(),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(
+                        *loc,
+                        "cannot find event which matches signature".to_string(),
+                    )
+                    .with_code(codes::NO_MATCHING_OVERLOAD),
+                );
             }
         }
         pt::Expression::NamedFunctionCall(_, ty, args) => {
@@ -945,10 +1045,13 @@ fn emit_event(
 
             for arg in args {
                 if arguments.contains_key(&arg.name.name) {
-                    ns.diagnostics.push(Diagnostic::error(
-                        arg.name.loc,
-                        format!("duplicate argument with name ‘{}’", arg.name.name),
-                    ));
+                    ns.diagnostics.push(
+                        Diagnostic::error(
+                            arg.name.loc,
+                            format!("duplicate argument with name ‘{}’", arg.name.name),
+                        )
+                        .with_code(codes::WRONG_ARGUMENTS),
+                    );
                     return Err(());
                 }
                 arguments.insert(&arg.name.name, &arg.expr);
@@ -961,14 +1064,17 @@ fn emit_event(
                 let params_len = event.fields.len();
 
                 if params_len != arguments.len() {
-                    temp_diagnostics.push(Diagnostic::error(
-                        *loc,
-                        format!(
-                            "event expects {} arguments, {} provided",
-                            params_len,
-                            args.len()
-                        ),
-                    ));
+                    temp_diagnostics.push(
+                        Diagnostic::error(
+                            *loc,
+                            format!(
+                                "event expects {} arguments, {} provided",
+                                params_len,
+                                args.len()
+                            ),
+                        )
+                        .with_code(codes::WRONG_ARGUMENTS),
+                    );
                     continue;
                 }
 
@@ -986,7 +1092,7 @@ fn emit_event(
                             "event ‘{}’ cannot emitted by argument name since argument {} has no name",
                             ns.events[*event_no].name, i,
                         ),
-                    ));
+                    ).with_code(codes::WRONG_ARGUMENTS));
                         matches = false;
                         break;
                     }
@@ -995,13 +1101,16 @@ fn emit_event(
                         Some(a) => a,
                         None => {
                             matches = false;
-                            temp_diagnostics.push(Diagnostic::error(
-                                *loc,
-                                format!(
-                                    "missing argument ‘{}’ to event ‘{}’",
-                                    param.name, ns.events[*event_no].name,
-                                ),
-                            ));
+                            temp_diagnostics.push(
+                                Diagnostic::error(
+                                    *loc,
+                                    format!(
+                                        "missing argument ‘{}’ to event ‘{}’",
+                                        param.name, ns.events[*event_no].name,
+                                    ),
+                                )
+                                .with_code(codes::WRONG_ARGUMENTS),
+                            );
                             break;
                         }
                     };
@@ -1048,19 +1157,25 @@ fn emit_event(
             if event_nos.len() == 1 {
                 diagnostics.extend(temp_diagnostics);
             } else {
-                diagnostics.push(Diagnostic::error(
-                    *loc,
-                    "cannot find event which matches signature".to_string(),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(
+                        *loc,
+                        "cannot find event which matches signature".to_string(),
+                    )
+                    .with_code(codes::NO_MATCHING_OVERLOAD),
+                );
             }
         }
         pt::Expression::FunctionCallBlock(_, ty, block) => {
             let _ = ns.resolve_event(file_no, contract_no, ty, diagnostics);
 
-            diagnostics.push(Diagnostic::error(
-                block.loc(),
-                "expected event arguments, found code block".to_string(),
-            ));
+            diagnostics.push(
+                Diagnostic::error(
+                    block.loc(),
+                    "expected event arguments, found code block".to_string(),
+                )
+                .with_code(codes::STATEMENT_NOT_ALLOWED),
+            );
         }
         _ => unreachable!(),
     }
@@ -1096,10 +1211,13 @@ fn destructure(
                 name: None,
             }) => {
                 if let Some(storage) = storage {
-                    diagnostics.push(Diagnostic::error(
-                        *storage.loc(),
-                        format!("storage modifier ‘{}’ not permitted on assignment", storage),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(
+                            *storage.loc(),
+                            format!("storage modifier ‘{}’ not permitted on assignment", storage),
+                        )
+                        .with_code(codes::NOT_ASSIGNABLE),
+                    );
                     return Err(());
                 }
 
@@ -1117,30 +1235,39 @@ fn destructure(
 
                 match &e {
                     Expression::ConstantVariable(_, _, Some(contract_no), var_no) => {
-                        diagnostics.push(Diagnostic::error(
-                            *loc,
-                            format!(
-                                "cannot assign to constant ‘{}’",
-                                ns.contracts[*contract_no].variables[*var_no].name
-                            ),
-                        ));
+                        diagnostics.push(
+                            Diagnostic::error(
+                                *loc,
+                                format!(
+                                    "cannot assign to constant ‘{}’",
+                                    ns.contracts[*contract_no].variables[*var_no].name
+                                ),
+                            )
+                            .with_code(codes::NOT_ASSIGNABLE),
+                        );
                         return Err(());
                     }
                     Expression::ConstantVariable(_, _, None, var_no) => {
-                        diagnostics.push(Diagnostic::error(
-                            *loc,
-                            format!("cannot assign to constant ‘{}’", ns.constants[*var_no].name),
-                        ));
+                        diagnostics.push(
+                            Diagnostic::error(
+                                *loc,
+                                format!(
+                                    "cannot assign to constant ‘{}’",
+                                    ns.constants[*var_no].name
+                                ),
+                            )
+                            .with_code(codes::NOT_ASSIGNABLE),
+                        );
                         return Err(());
                     }
                     Expression::StorageVariable(_, _, _, _) | Expression::Variable(_, _, _) => (),
                     _ => match e.ty() {
                         Type::Ref(_) | Type::StorageRef(_) => (),
                         _ => {
-                            diagnostics.push(Diagnostic::error(
-                                *loc,
-                                "expression is not assignable".to_string(),
-                            ));
+                            diagnostics.push(
+                                Diagnostic::error(*loc, "expression is not assignable".to_string())
+                                    .with_code(codes::NOT_ASSIGNABLE),
+                            );
                             return Err(());
                         }
                     },
@@ -1276,14 +1403,17 @@ fn destructure_values(
             let exprs = parameter_list_to_expr_list(expr, diagnostics)?;
 
             if exprs.len() != left_tys.len() {
-                diagnostics.push(Diagnostic::error(
-                    *loc,
-                    format!(
+                diagnostics.push(
+                    Diagnostic::error(
+                        *loc,
+                        format!(
                         "destructuring assignment has {} elements on the left and {} on the right",
                         left_tys.len(),
                         exprs.len(),
                     ),
-                ));
+                    )
+                    .with_code(codes::STATEMENT_NOT_ALLOWED),
+                );
                 return Err(());
             }
 
@@ -1301,10 +1431,13 @@ fn destructure_values(
 
                 match e.ty() {
                     Type::Void | Type::Unreachable => {
-                        diagnostics.push(Diagnostic::error(
-                            e.loc(),
-                            "function does not return a value".to_string(),
-                        ));
+                        diagnostics.push(
+                            Diagnostic::error(
+                                e.loc(),
+                                "function does not return a value".to_string(),
+                            )
+                            .with_code(codes::RETURN_VALUES),
+                        );
                         return Err(());
                     }
                     _ => (),
@@ -1325,14 +1458,17 @@ fn destructure_values(
     }
 
     if left_tys.len() != right_tys.len() {
-        diagnostics.push(Diagnostic::error(
-            *loc,
-            format!(
-                "destructuring assignment has {} elements on the left and {} on the right",
-                left_tys.len(),
-                right_tys.len()
-            ),
-        ));
+        diagnostics.push(
+            Diagnostic::error(
+                *loc,
+                format!(
+                    "destructuring assignment has {} elements on the left and {} on the right",
+                    left_tys.len(),
+                    right_tys.len()
+                ),
+            )
+            .with_code(codes::STATEMENT_NOT_ALLOWED),
+        );
         return Err(());
     }
 
@@ -1368,13 +1504,16 @@ fn resolve_var_decl_ty(
 
     if let Some(storage) = storage {
         if !var_ty.can_have_data_location() {
-            diagnostics.push(Diagnostic::error(
-                *storage.loc(),
-                format!(
-                    "data location ‘{}’ only allowed for array, struct or mapping type",
-                    storage
-                ),
-            ));
+            diagnostics.push(
+                Diagnostic::error(
+                    *storage.loc(),
+                    format!(
+                        "data location ‘{}’ only allowed for array, struct or mapping type",
+                        storage
+                    ),
+                )
+                .with_code(codes::DATA_LOCATION),
+            );
             return Err(());
         }
 
@@ -1388,18 +1527,18 @@ fn resolve_var_decl_ty(
     }
 
     if var_ty.contains_mapping(ns) && !var_ty.is_contract_storage() {
-        diagnostics.push(Diagnostic::error(
-            ty.loc(),
-            "mapping only allowed in storage".to_string(),
-        ));
+        diagnostics.push(
+            Diagnostic::error(ty.loc(), "mapping only allowed in storage".to_string())
+                .with_code(codes::MAPPING_TYPE),
+        );
         return Err(());
     }
 
     if !var_ty.is_contract_storage() && var_ty.size_of(ns) > BigInt::from(1024 * 1024) {
-        diagnostics.push(Diagnostic::error(
-            ty.loc(),
-            "type to large to fit into memory".to_string(),
-        ));
+        diagnostics.push(
+            Diagnostic::error(ty.loc(), "type to large to fit into memory".to_string())
+                .with_code(codes::TYPE_NOT_ALLOWED),
+        );
         return Err(());
     }
 
@@ -1422,33 +1561,39 @@ fn return_with_values(
     let no_returns = ns.functions[function_no].returns.len();
 
     if no_returns > 0 && returns.is_empty() {
-        diagnostics.push(Diagnostic::error(
-            *loc,
-            format!(
-                "missing return value, {} return values expected",
-                no_returns
-            ),
-        ));
+        diagnostics.push(
+            Diagnostic::error(
+                *loc,
+                format!(
+                    "missing return value, {} return values expected",
+                    no_returns
+                ),
+            )
+            .with_code(codes::RETURN_VALUES),
+        );
         return Err(());
     }
 
     if no_returns == 0 && !returns.is_empty() {
-        diagnostics.push(Diagnostic::error(
-            *loc,
-            "function has no return values".to_string(),
-        ));
+        diagnostics.push(
+            Diagnostic::error(*loc, "function has no return values".to_string())
+                .with_code(codes::RETURN_VALUES),
+        );
         return Err(());
     }
 
     if no_returns != returns.len() {
-        diagnostics.push(Diagnostic::error(
-            *loc,
-            format!(
-                "incorrect number of return values, expected {} but got {}",
-                no_returns,
-                returns.len()
-            ),
-        ));
+        diagnostics.push(
+            Diagnostic::error(
+                *loc,
+                format!(
+                    "incorrect number of return values, expected {} but got {}",
+                    no_returns,
+                    returns.len()
+                ),
+            )
+            .with_code(codes::RETURN_VALUES),
+        );
         return Err(());
     }
 
@@ -1487,26 +1632,32 @@ pub fn parameter_list_to_expr_list<'a>(
         for e in v {
             match &e.1 {
                 None => {
-                    diagnostics.push(Diagnostic::error(e.0, "stray comma".to_string()));
+                    diagnostics.push(
+                        Diagnostic::error(e.0, "stray comma".to_string())
+                            .with_code(codes::STATEMENT_NOT_ALLOWED),
+                    );
                     broken = true;
                 }
                 Some(pt::Parameter {
                     name: Some(name), ..
                 }) => {
-                    diagnostics.push(Diagnostic::error(
-                        name.loc,
-                        "single value expected".to_string(),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(name.loc, "single value expected".to_string())
+                            .with_code(codes::RETURN_VALUES),
+                    );
                     broken = true;
                 }
                 Some(pt::Parameter {
                     storage: Some(storage),
                     ..
                 }) => {
-                    diagnostics.push(Diagnostic::error(
-                        *storage.loc(),
-                        "storage specified not permitted here".to_string(),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(
+                            *storage.loc(),
+                            "storage specified not permitted here".to_string(),
+                        )
+                        .with_code(codes::DATA_LOCATION),
+                    );
                     broken = true;
                 }
                 Some(pt::Parameter { ty, .. }) => {
@@ -1547,10 +1698,10 @@ fn try_catch(
 
     while let pt::Expression::FunctionCallBlock(_, e, block) = expr {
         if ok.is_some() {
-            diagnostics.push(Diagnostic::error(
-                block.loc(),
-                "unexpected code block".to_string(),
-            ));
+            diagnostics.push(
+                Diagnostic::error(block.loc(), "unexpected code block".to_string())
+                    .with_code(codes::STATEMENT_NOT_ALLOWED),
+            );
             return Err(());
         }
 
@@ -1586,10 +1737,10 @@ fn try_catch(
 
             while let pt::Expression::FunctionCallBlock(_, expr, block) = call {
                 if ok.is_some() {
-                    ns.diagnostics.push(Diagnostic::error(
-                        block.loc(),
-                        "unexpected code block".to_string(),
-                    ));
+                    ns.diagnostics.push(
+                        Diagnostic::error(block.loc(), "unexpected code block".to_string())
+                            .with_code(codes::STATEMENT_NOT_ALLOWED),
+                    );
                     return Err(());
                 }
 
@@ -1623,10 +1774,13 @@ fn try_catch(
             }
         }
         _ => {
-            diagnostics.push(Diagnostic::error(
-                expr.loc(),
-                "try only supports external calls or constructor calls".to_string(),
-            ));
+            diagnostics.push(
+                Diagnostic::error(
+                    expr.loc(),
+                    "try only supports external calls or constructor calls".to_string(),
+                )
+                .with_code(codes::TRY_CATCH),
+            );
             return Err(());
         }
     };
@@ -1635,10 +1789,10 @@ fn try_catch(
 
     if let Some((rets, block)) = returns_and_ok {
         if ok.is_some() {
-            diagnostics.push(Diagnostic::error(
-                block.loc(),
-                "unexpected code block".to_string(),
-            ));
+            diagnostics.push(
+                Diagnostic::error(block.loc(), "unexpected code block".to_string())
+                    .with_code(codes::STATEMENT_NOT_ALLOWED),
+            );
             return Err(());
         }
 
@@ -1653,10 +1807,13 @@ fn try_catch(
             // position after the expression
             let pos = expr.loc().1;
 
-            diagnostics.push(Diagnostic::error(
-                pt::Loc(file_no, pos, pos),
-                "code block missing for no catch".to_string(),
-            ));
+            diagnostics.push(
+                Diagnostic::error(
+                    pt::Loc(file_no, pos, pos),
+                    "code block missing for no catch".to_string(),
+                )
+                .with_code(codes::TRY_CATCH),
+            );
             return Err(());
         }
     };
@@ -1675,14 +1832,17 @@ fn try_catch(
             }
 
             if returns.len() != func_returns.len() {
-                diagnostics.push(Diagnostic::error(
-                    expr.loc(),
-                    format!(
-                        "try returns list has {} entries while function returns {} values",
-                        returns.len(),
-                        func_returns.len()
-                    ),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(
+                        expr.loc(),
+                        format!(
+                            "try returns list has {} entries while function returns {} values",
+                            returns.len(),
+                            func_returns.len()
+                        ),
+                    )
+                    .with_code(codes::TRY_CATCH),
+                );
                 return Err(());
             }
 
@@ -1692,21 +1852,27 @@ fn try_catch(
             0 => Vec::new(),
             1 => vec![Type::Contract(*contract_no)],
             _ => {
-                diagnostics.push(Diagnostic::error(
-                    expr.loc(),
-                    format!(
-                        "constructor returns single contract, not {} values",
-                        returns.len()
-                    ),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(
+                        expr.loc(),
+                        format!(
+                            "constructor returns single contract, not {} values",
+                            returns.len()
+                        ),
+                    )
+                    .with_code(codes::CONTRACT_CREATION),
+                );
                 return Err(());
             }
         },
         _ => {
-            diagnostics.push(Diagnostic::error(
-                expr.loc(),
-                "try only supports external calls or constructor calls".to_string(),
-            ));
+            diagnostics.push(
+                Diagnostic::error(
+                    expr.loc(),
+                    "try only supports external calls or constructor calls".to_string(),
+                )
+                .with_code(codes::TRY_CATCH),
+            );
             return Err(());
         }
     };
@@ -1726,14 +1892,17 @@ fn try_catch(
                     resolve_var_decl_ty(&ty, &storage, file_no, contract_no, ns, diagnostics)?;
 
                 if arg_ty != ret_ty {
-                    diagnostics.push(Diagnostic::error(
-                        ty.loc(),
-                        format!(
-                            "type ‘{}’ does not match return value of function ‘{}’",
-                            ret_ty.to_string(ns),
-                            arg_ty.to_string(ns)
-                        ),
-                    ));
+                    diagnostics.push(
+                        Diagnostic::error(
+                            ty.loc(),
+                            format!(
+                                "type ‘{}’ does not match return value of function ‘{}’",
+                                ret_ty.to_string(ns),
+                                arg_ty.to_string(ns)
+                            ),
+                        )
+                        .with_code(codes::RETURN_VALUES),
+                    );
                     broken = true;
                 }
 
@@ -1767,10 +1936,10 @@ fn try_catch(
                 }
             }
             None => {
-                diagnostics.push(Diagnostic::error(
-                    param.0,
-                    "missing return type".to_string(),
-                ));
+                diagnostics.push(
+                    Diagnostic::error(param.0, "missing return type".to_string())
+                        .with_code(codes::FUNCTION_DECLARATION),
+                );
                 broken = true;
             }
         }
@@ -1798,13 +1967,16 @@ fn try_catch(
 
     let error_resolved = if let Some(error_stmt) = error_stmt {
         if error_stmt.0.name != "Error" {
-            ns.diagnostics.push(Diagnostic::error(
-                error_stmt.0.loc,
-                format!(
-                    "only catch ‘Error’ is supported, not ‘{}’",
-                    error_stmt.0.name
-                ),
-            ));
+            ns.diagnostics.push(
+                Diagnostic::error(
+                    error_stmt.0.loc,
+                    format!(
+                        "only catch ‘Error’ is supported, not ‘{}’",
+                        error_stmt.0.name
+                    ),
+                )
+                .with_code(codes::TRY_CATCH),
+            );
             return Err(());
         }
 
@@ -1818,13 +1990,16 @@ fn try_catch(
         )?;
 
         if error_ty != Type::String {
-            ns.diagnostics.push(Diagnostic::error(
-                error_stmt.1.ty.loc(),
-                format!(
-                    "catch Error(...) can only take ‘string memory’, not ‘{}’",
-                    error_ty.to_string(ns)
-                ),
-            ));
+            ns.diagnostics.push(
+                Diagnostic::error(
+                    error_stmt.1.ty.loc(),
+                    format!(
+                        "catch Error(...) can only take ‘string memory’, not ‘{}’",
+                        error_ty.to_string(ns)
+                    ),
+                )
+                .with_code(codes::TRY_CATCH),
+            );
         }
 
         symtable.new_scope();
@@ -1881,13 +2056,16 @@ fn try_catch(
     )?;
 
     if catch_ty != Type::DynamicBytes {
-        diagnostics.push(Diagnostic::error(
-            catch_stmt.0.ty.loc(),
-            format!(
-                "catch can only take ‘bytes memory’, not ‘{}’",
-                catch_ty.to_string(ns)
-            ),
-        ));
+        diagnostics.push(
+            Diagnostic::error(
+                catch_stmt.0.ty.loc(),
+                format!(
+                    "catch can only take ‘bytes memory’, not ‘{}’",
+                    catch_ty.to_string(ns)
+                ),
+            )
+            .with_code(codes::TRY_CATCH),
+        );
         return Err(());
     }
 
@@ -4,6 +4,7 @@ use std::collections::LinkedList;
 use std::str;
 
 use super::ast::{Diagnostic, Namespace, Type};
+use super::codes;
 use crate::parser::pt;
 
 #[derive(Clone)]
@@ -53,12 +54,15 @@ impl Symtable {
         // the variable has no name, like unnamed return or parameters values
         if !id.name.is_empty() {
             if let Some(ref prev) = self.find(&id.name) {
-                ns.diagnostics.push(Diagnostic::error_with_note(
-                    id.loc,
-                    format!("{} is already declared", id.name.to_string()),
-                    prev.id.loc,
-                    "location of previous declaration".to_string(),
-                ));
+                ns.diagnostics.push(
+                    Diagnostic::error_with_note(
+                        id.loc,
+                        format!("{} is already declared", id.name.to_string()),
+                        prev.id.loc,
+                        "location of previous declaration".to_string(),
+                    )
+                    .with_code(codes::ALREADY_DEFINED),
+                );
                 return None;
             }
 
@@ -1,4 +1,5 @@
 use super::ast::{Diagnostic, Namespace, Parameter, Tag};
+use super::codes;
 use crate::parser::pt;
 
 /// Resolve the tags for a type
@@ -31,10 +32,13 @@ pub fn resolve_tags(
             "param" if params.is_some() => {
                 let v: Vec<&str> = c.value.splitn(2, char::is_whitespace).collect();
                 if v.is_empty() || v[0].is_empty() {
-                    ns.diagnostics.push(Diagnostic::error(
-                        pt::Loc(file_no, c.offset, c.offset + c.tag.len()),
-                        "tag ‘@param’ missing parameter name".to_string(),
-                    ));
+                    ns.diagnostics.push(
+                        Diagnostic::error(
+                            pt::Loc(file_no, c.offset, c.offset + c.tag.len()),
+                            "tag ‘@param’ missing parameter name".to_string(),
+                        )
+                        .with_code(codes::DOC_TAG),
+                    );
                     continue;
                 }
                 let name = v[0];
@@ -42,10 +46,13 @@ pub fn resolve_tags(
 
                 if let Some(no) = params.unwrap().iter().position(|p| p.name == name) {
                     if res.iter().any(|e| e.tag == "param" && e.no == no) {
-                        ns.diagnostics.push(Diagnostic::error(
-                            pt::Loc(file_no, c.offset, c.offset + c.tag.len()),
-                            format!("duplicate tag ‘@param’ for ‘{}’", name),
-                        ));
+                        ns.diagnostics.push(
+                            Diagnostic::error(
+                                pt::Loc(file_no, c.offset, c.offset + c.tag.len()),
+                                format!("duplicate tag ‘@param’ for ‘{}’", name),
+                            )
+                            .with_code(codes::DOC_TAG),
+                        );
                     } else {
                         res.push(Tag {
                             tag: String::from("param"),
@@ -54,26 +61,35 @@ pub fn resolve_tags(
                         });
                     }
                 } else {
-                    ns.diagnostics.push(Diagnostic::error(
-                        pt::Loc(file_no, c.offset, c.offset + c.tag.len()),
-                        format!("tag ‘@param’ no field ‘{}’", name),
-                    ));
+                    ns.diagnostics.push(
+                        Diagnostic::error(
+                            pt::Loc(file_no, c.offset, c.offset + c.tag.len()),
+                            format!("tag ‘@param’ no field ‘{}’", name),
+                        )
+                        .with_code(codes::DOC_TAG),
+                    );
                 }
             }
             "return" if returns.is_some() => {
                 let returns = returns.unwrap();
 
                 if returns.is_empty() {
-                    ns.diagnostics.push(Diagnostic::error(
-                        pt::Loc(file_no, c.offset, c.offset + c.tag.len()),
-                        "tag ‘@return’ for function with no return values".to_string(),
-                    ));
+                    ns.diagnostics.push(
+                        Diagnostic::error(
+                            pt::Loc(file_no, c.offset, c.offset + c.tag.len()),
+                            "tag ‘@return’ for function with no return values".to_string(),
+                        )
+                        .with_code(codes::DOC_TAG),
+                    );
                 } else if returns.len() == 1 {
                     if res.iter().any(|e| e.tag == "return") {
-                        ns.diagnostics.push(Diagnostic::error(
-                            pt::Loc(file_no, c.offset, c.offset + c.tag.len()),
-                            "duplicate tag ‘@return’".to_string(),
-                        ));
+                        ns.diagnostics.push(
+                            Diagnostic::error(
+                                pt::Loc(file_no, c.offset, c.offset + c.tag.len()),
+                                "duplicate tag ‘@return’".to_string(),
+                            )
+                            .with_code(codes::DOC_TAG),
+                        );
                     } else {
                         res.push(Tag {
                             tag: String::from("return"),
@@ -84,10 +100,13 @@ pub fn resolve_tags(
                 } else {
                     let v: Vec<&str> = c.value.splitn(2, char::is_whitespace).collect();
                     if v.is_empty() || v[0].is_empty() {
-                        ns.diagnostics.push(Diagnostic::error(
-                            pt::Loc(file_no, c.offset, c.offset + c.tag.len()),
-                            "tag ‘@return’ missing parameter name".to_string(),
-                        ));
+                        ns.diagnostics.push(
+                            Diagnostic::error(
+                                pt::Loc(file_no, c.offset, c.offset + c.tag.len()),
+                                "tag ‘@return’ missing parameter name".to_string(),
+                            )
+                            .with_code(codes::DOC_TAG),
+                        );
                         continue;
                     }
                     let name = v[0];
@@ -95,10 +114,13 @@ pub fn resolve_tags(
 
                     if let Some(no) = returns.iter().position(|p| p.name == name) {
                         if res.iter().any(|e| e.tag == "return" && e.no == no) {
-                            ns.diagnostics.push(Diagnostic::error(
-                                pt::Loc(file_no, c.offset, c.offset + c.tag.len()),
-                                format!("duplicate tag ‘@return’ for ‘{}’", name),
-                            ));
+                            ns.diagnostics.push(
+                                Diagnostic::error(
+                                    pt::Loc(file_no, c.offset, c.offset + c.tag.len()),
+                                    format!("duplicate tag ‘@return’ for ‘{}’", name),
+                                )
+                                .with_code(codes::DOC_TAG),
+                            );
                         } else {
                             res.push(Tag {
                                 tag: String::from("return"),
@@ -107,19 +129,25 @@ pub fn resolve_tags(
                             });
                         }
                     } else {
-                        ns.diagnostics.push(Diagnostic::error(
-                            pt::Loc(file_no, c.offset, c.offset + c.tag.len()),
-                            format!("tag ‘@return’ no field ‘{}’", name),
-                        ));
+                        ns.diagnostics.push(
+                            Diagnostic::error(
+                                pt::Loc(file_no, c.offset, c.offset + c.tag.len()),
+                                format!("tag ‘@return’ no field ‘{}’", name),
+                            )
+                            .with_code(codes::DOC_TAG),
+                        );
                     }
                 }
             }
             "inheritdoc" if bases.is_some() => {
                 if c.value.is_empty() {
-                    ns.diagnostics.push(Diagnostic::error(
-                        pt::Loc(file_no, c.offset, c.offset + c.tag.len()),
-                        "missing contract for tag ‘@inheritdoc’".to_string(),
-                    ));
+                    ns.diagnostics.push(
+                        Diagnostic::error(
+                            pt::Loc(file_no, c.offset, c.offset + c.tag.len()),
+                            "missing contract for tag ‘@inheritdoc’".to_string(),
+                        )
+                        .with_code(codes::DOC_TAG),
+                    );
                 } else if bases.unwrap().iter().any(|s| &c.value == s) {
                     res.push(Tag {
                         tag: String::from("inheritdoc"),
@@ -127,17 +155,23 @@ pub fn resolve_tags(
                         value: c.value.to_owned(),
                     });
                 } else {
-                    ns.diagnostics.push(Diagnostic::error(
-                        pt::Loc(file_no, c.offset, c.offset + c.tag.len()),
-                        format!("base contract ‘{}’ not found in tag ‘@inheritdoc’", c.value),
-                    ));
+                    ns.diagnostics.push(
+                        Diagnostic::error(
+                            pt::Loc(file_no, c.offset, c.offset + c.tag.len()),
+                            format!("base contract ‘{}’ not found in tag ‘@inheritdoc’", c.value),
+                        )
+                        .with_code(codes::DOC_TAG),
+                    );
                 }
             }
             _ => {
-                ns.diagnostics.push(Diagnostic::error(
-                    pt::Loc(file_no, c.offset, c.offset + c.tag.len()),
-                    format!("tag ‘@{}’ is not valid for {}", c.tag, ty),
-                ));
+                ns.diagnostics.push(
+                    Diagnostic::error(
+                        pt::Loc(file_no, c.offset, c.offset + c.tag.len()),
+                        format!("tag ‘@{}’ is not valid for {}", c.tag, ty),
+                    )
+                    .with_code(codes::DOC_TAG),
+                );
             }
         }
     }
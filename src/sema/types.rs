@@ -1,3 +1,4 @@
+use super::codes;
 use super::diagnostics::any_errors;
 use super::tags::resolve_tags;
 use super::SOLANA_BUCKET_SIZE;
@@ -121,12 +122,15 @@ pub fn resolve_fields(delay: ResolveFields, file_no: usize, ns: &mut Namespace)
                     types_seen.push(n);
 
                     if struct_fields.contains(&n) {
-                        ns.diagnostics.push(Diagnostic::error_with_note(
-                            def.loc,
-                            format!("struct ‘{}’ has infinite size", def.name),
-                            field.loc,
-                            format!("recursive field ‘{}’", field.name),
-                        ));
+                        ns.diagnostics.push(
+                            Diagnostic::error_with_note(
+                                def.loc,
+                                format!("struct ‘{}’ has infinite size", def.name),
+                                field.loc,
+                                format!("recursive field ‘{}’", field.name),
+                            )
+                            .with_code(codes::TYPE_DECLARATION),
+                        );
                     } else {
                         struct_fields.push(n);
                         check(n, file_no, struct_fields, ns);
@@ -316,15 +320,18 @@ pub fn struct_decl(
         };
 
         if let Some(other) = fields.iter().find(|f| f.name == field.name.name) {
-            ns.diagnostics.push(Diagnostic::error_with_note(
-                field.name.loc,
-                format!(
-                    "struct ‘{}’ has duplicate struct field ‘{}’",
-                    def.name.name, field.name.name
-                ),
-                other.loc,
-                format!("location of previous declaration of ‘{}’", other.name),
-            ));
+            ns.diagnostics.push(
+                Diagnostic::error_with_note(
+                    field.name.loc,
+                    format!(
+                        "struct ‘{}’ has duplicate struct field ‘{}’",
+                        def.name.name, field.name.name
+                    ),
+                    other.loc,
+                    format!("location of previous declaration of ‘{}’", other.name),
+                )
+                .with_code(codes::ALREADY_DEFINED),
+            );
             valid = false;
             continue;
         }
@@ -334,13 +341,16 @@ pub fn struct_decl(
         // in structs, but this is perfectly possible. The struct would not be
         // allowed as parameter/return types of public functions though.
         if let Some(storage) = &field.storage {
-            ns.diagnostics.push(Diagnostic::error(
-                *storage.loc(),
-                format!(
-                    "storage location ‘{}’ not allowed for struct field",
-                    storage
-                ),
-            ));
+            ns.diagnostics.push(
+                Diagnostic::error(
+                    *storage.loc(),
+                    format!(
+                        "storage location ‘{}’ not allowed for struct field",
+                        storage
+                    ),
+                )
+                .with_code(codes::DATA_LOCATION),
+            );
             valid = false;
         }
 
@@ -356,10 +366,13 @@ pub fn struct_decl(
 
     if fields.is_empty() {
         if valid {
-            ns.diagnostics.push(Diagnostic::error(
-                def.name.loc,
-                format!("struct definition for ‘{}’ has no fields", def.name.name),
-            ));
+            ns.diagnostics.push(
+                Diagnostic::error(
+                    def.name.loc,
+                    format!("struct definition for ‘{}’ has no fields", def.name.name),
+                )
+                .with_code(codes::TYPE_DECLARATION),
+            );
         }
 
         valid = false;
@@ -409,24 +422,30 @@ pub fn event_decl(
         };
 
         if ty.contains_mapping(ns) {
-            ns.diagnostics.push(Diagnostic::error(
-                field.loc,
-                "mapping type is not permitted as event field".to_string(),
-            ));
+            ns.diagnostics.push(
+                Diagnostic::error(
+                    field.loc,
+                    "mapping type is not permitted as event field".to_string(),
+                )
+                .with_code(codes::MAPPING_TYPE),
+            );
             valid = false;
         }
 
         let (name, name_loc) = if let Some(name) = &field.name {
             if let Some(other) = fields.iter().find(|f| f.name == name.name) {
-                ns.diagnostics.push(Diagnostic::error_with_note(
-                    name.loc,
-                    format!(
-                        "event ‘{}’ has duplicate field name ‘{}’",
-                        def.name.name, name.name
-                    ),
-                    other.loc,
-                    format!("location of previous declaration of ‘{}’", other.name),
-                ));
+                ns.diagnostics.push(
+                    Diagnostic::error_with_note(
+                        name.loc,
+                        format!(
+                            "event ‘{}’ has duplicate field name ‘{}’",
+                            def.name.name, name.name
+                        ),
+                        other.loc,
+                        format!("location of previous declaration of ‘{}’", other.name),
+                    )
+                    .with_code(codes::ALREADY_DEFINED),
+                );
                 valid = false;
                 continue;
             }
@@ -451,31 +470,40 @@ pub fn event_decl(
 
     if fields.is_empty() {
         if valid {
-            ns.diagnostics.push(Diagnostic::error(
-                def.name.loc,
-                format!("event definition for ‘{}’ has no fields", def.name.name),
-            ));
+            ns.diagnostics.push(
+                Diagnostic::error(
+                    def.name.loc,
+                    format!("event definition for ‘{}’ has no fields", def.name.name),
+                )
+                .with_code(codes::TYPE_DECLARATION),
+            );
         }
 
         valid = false;
     } else if def.anonymous && indexed_fields > 4 {
-        ns.diagnostics.push(Diagnostic::error(
-            def.name.loc,
-            format!(
-                "anonymous event definition for ‘{}’ has {} indexed fields where 4 permitted",
-                def.name.name, indexed_fields
-            ),
-        ));
+        ns.diagnostics.push(
+            Diagnostic::error(
+                def.name.loc,
+                format!(
+                    "anonymous event definition for ‘{}’ has {} indexed fields where 4 permitted",
+                    def.name.name, indexed_fields
+                ),
+            )
+            .with_code(codes::TYPE_DECLARATION),
+        );
 
         valid = false;
     } else if !def.anonymous && indexed_fields > 3 {
-        ns.diagnostics.push(Diagnostic::error(
-            def.name.loc,
-            format!(
-                "event definition for ‘{}’ has {} indexed fields where 3 permitted",
-                def.name.name, indexed_fields
-            ),
-        ));
+        ns.diagnostics.push(
+            Diagnostic::error(
+                def.name.loc,
+                format!(
+                    "event definition for ‘{}’ has {} indexed fields where 3 permitted",
+                    def.name.name, indexed_fields
+                ),
+            )
+            .with_code(codes::TYPE_DECLARATION),
+        );
 
         valid = false;
     }
@@ -508,10 +536,13 @@ fn enum_decl(
     let mut valid = true;
 
     let mut bits = if enum_.values.is_empty() {
-        ns.diagnostics.push(Diagnostic::error(
-            enum_.name.loc,
-            format!("enum ‘{}’ is missing fields", enum_.name.name),
-        ));
+        ns.diagnostics.push(
+            Diagnostic::error(
+                enum_.name.loc,
+                format!("enum ‘{}’ is missing fields", enum_.name.name),
+            )
+            .with_code(codes::TYPE_DECLARATION),
+        );
         valid = false;
 
         0
@@ -533,12 +564,15 @@ fn enum_decl(
 
     for (i, e) in enum_.values.iter().enumerate() {
         if let Some(prev) = entries.get(&e.name.to_string()) {
-            ns.diagnostics.push(Diagnostic::error_with_note(
-                e.loc,
-                format!("duplicate enum value {}", e.name),
-                prev.0,
-                "location of previous definition".to_string(),
-            ));
+            ns.diagnostics.push(
+                Diagnostic::error_with_note(
+                    e.loc,
+                    format!("duplicate enum value {}", e.name),
+                    prev.0,
+                    "location of previous definition".to_string(),
+                )
+                .with_code(codes::ALREADY_DEFINED),
+            );
             valid = false;
             continue;
         }
@@ -1020,6 +1054,21 @@ impl Type {
         }
     }
 
+    /// Does encoding this type need ABI coder v2, i.e. does it contain a struct or an array
+    /// of dynamic types
+    pub fn requires_abicoder_v2(&self, ns: &Namespace) -> bool {
+        match self {
+            Type::Struct(_) => true,
+            Type::Array(..) => {
+                let elem = self.array_elem();
+
+                elem.is_dynamic(ns) || elem.requires_abicoder_v2(ns)
+            }
+            Type::Ref(r) | Type::StorageRef(r) => r.requires_abicoder_v2(ns),
+            _ => false,
+        }
+    }
+
     /// Can this type have a calldata, memory, or storage location. This is to be
     /// compatible with ethereum solidity. Opinions on whether other types should be
     /// allowed be storage are welcome.
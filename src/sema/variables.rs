@@ -1,6 +1,7 @@
 use super::ast::{
     Diagnostic, Expression, Function, Namespace, Parameter, Statement, Symbol, Type, Variable,
 };
+use super::codes;
 use super::expression::{cast, expression};
 use super::symtable::Symtable;
 use super::tags::resolve_tags;
@@ -19,13 +20,16 @@ pub fn contract_variables(
         if let pt::ContractPart::VariableDefinition(ref s) = parts {
             // don't even attempt to parse contract variables for interfaces, they are never allowed
             if matches!(def.ty, pt::ContractTy::Interface(_)) {
-                ns.diagnostics.push(Diagnostic::error(
-                    s.loc,
-                    format!(
-                        "{} ‘{}’ is not allowed to have contract variable ‘{}’",
-                        def.ty, def.name.name, s.name.name
-                    ),
-                ));
+                ns.diagnostics.push(
+                    Diagnostic::error(
+                        s.loc,
+                        format!(
+                            "{} ‘{}’ is not allowed to have contract variable ‘{}’",
+                            def.ty, def.name.name, s.name.name
+                        ),
+                    )
+                    .with_code(codes::CONTRACT_KIND),
+                );
                 broken = true;
                 continue;
             }
@@ -35,13 +39,16 @@ pub fn contract_variables(
                     broken = true;
                 }
                 Some(false) if matches!(def.ty, pt::ContractTy::Library(_)) => {
-                    ns.diagnostics.push(Diagnostic::error(
-                        s.loc,
-                        format!(
-                            "{} ‘{}’ is not allowed to have state variable ‘{}’",
-                            def.ty, def.name.name, s.name.name
-                        ),
-                    ));
+                    ns.diagnostics.push(
+                        Diagnostic::error(
+                            s.loc,
+                            format!(
+                                "{} ‘{}’ is not allowed to have state variable ‘{}’",
+                                def.ty, def.name.name, s.name.name
+                            ),
+                        )
+                        .with_code(codes::CONTRACT_KIND),
+                    );
                 }
                 _ => (),
             }
@@ -103,35 +110,41 @@ pub fn var_decl(
         match &attr {
             pt::VariableAttribute::Constant(loc) => {
                 if is_constant {
-                    ns.diagnostics.push(Diagnostic::error(
-                        *loc,
-                        "duplicate constant attribute".to_string(),
-                    ));
+                    ns.diagnostics.push(
+                        Diagnostic::error(*loc, "duplicate constant attribute".to_string())
+                            .with_code(codes::ALREADY_DEFINED),
+                    );
                 }
                 is_constant = true;
             }
             pt::VariableAttribute::Visibility(v) if contract_no.is_none() => {
-                ns.diagnostics.push(Diagnostic::error(
-                    v.loc(),
-                    format!("‘{}’: global variable cannot have visibility specifier", v),
-                ));
+                ns.diagnostics.push(
+                    Diagnostic::error(
+                        v.loc(),
+                        format!("‘{}’: global variable cannot have visibility specifier", v),
+                    )
+                    .with_code(codes::VARIABLE_DECLARATION),
+                );
                 return None;
             }
             pt::VariableAttribute::Visibility(pt::Visibility::External(loc)) => {
-                ns.diagnostics.push(Diagnostic::error(
-                    *loc,
-                    "variable cannot be declared external".to_string(),
-                ));
+                ns.diagnostics.push(
+                    Diagnostic::error(*loc, "variable cannot be declared external".to_string())
+                        .with_code(codes::VARIABLE_DECLARATION),
+                );
                 return None;
             }
             pt::VariableAttribute::Visibility(v) => {
                 if let Some(e) = &visibility {
-                    ns.diagnostics.push(Diagnostic::error_with_note(
-                        v.loc(),
-                        format!("variable visibility redeclared `{}'", v.to_string()),
-                        e.loc(),
-                        format!("location of previous declaration of `{}'", e.to_string()),
-                    ));
+                    ns.diagnostics.push(
+                        Diagnostic::error_with_note(
+                            v.loc(),
+                            format!("variable visibility redeclared `{}'", v.to_string()),
+                            e.loc(),
+                            format!("location of previous declaration of `{}'", e.to_string()),
+                        )
+                        .with_code(codes::ALREADY_DEFINED),
+                    );
                     return None;
                 }
 
@@ -147,17 +160,20 @@ pub fn var_decl(
 
     if contract_no.is_none() {
         if !is_constant {
-            ns.diagnostics.push(Diagnostic::error(
-                s.ty.loc(),
-                "global variable must be constant".to_string(),
-            ));
+            ns.diagnostics.push(
+                Diagnostic::error(s.ty.loc(), "global variable must be constant".to_string())
+                    .with_code(codes::VARIABLE_DECLARATION),
+            );
             return None;
         }
         if ty.contains_internal_function(ns) {
-            ns.diagnostics.push(Diagnostic::error(
-                s.ty.loc(),
-                "global variable cannot be of type internal function".to_string(),
-            ));
+            ns.diagnostics.push(
+                Diagnostic::error(
+                    s.ty.loc(),
+                    "global variable cannot be of type internal function".to_string(),
+                )
+                .with_code(codes::VARIABLE_DECLARATION),
+            );
             return None;
         }
     } else if ty.contains_internal_function(ns)
@@ -166,13 +182,16 @@ pub fn var_decl(
             pt::Visibility::Public(_) | pt::Visibility::External(_)
         )
     {
-        ns.diagnostics.push(Diagnostic::error(
-            s.ty.loc(),
-            format!(
-                "variable of type internal function cannot be ‘{}’",
-                visibility
-            ),
-        ));
+        ns.diagnostics.push(
+            Diagnostic::error(
+                s.ty.loc(),
+                format!(
+                    "variable of type internal function cannot be ‘{}’",
+                    visibility
+                ),
+            )
+            .with_code(codes::VARIABLE_DECLARATION),
+        );
         return None;
     }
 
@@ -208,10 +227,10 @@ pub fn var_decl(
         Some(res)
     } else {
         if is_constant {
-            ns.diagnostics.push(Diagnostic::decl_error(
-                s.loc,
-                "missing initializer for constant".to_string(),
-            ));
+            ns.diagnostics.push(
+                Diagnostic::decl_error(s.loc, "missing initializer for constant".to_string())
+                    .with_code(codes::VARIABLE_DECLARATION),
+            );
             return None;
         }
 
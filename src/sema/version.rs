@@ -0,0 +1,218 @@
+// Version ranges as used by `pragma solidity`
+//
+// The syntax follows npm's semver ranges, like solc: comparators such as `>=0.4.22` or
+// `^0.8.0` separated by spaces must all match, and sets of them are separated by `||`.
+// A version may be partial (`0.8`) or end in a wildcard (`0.8.x`), which matches every
+// version with that prefix.
+
+use super::ast::LanguageVersion;
+
+/// The minor versions of Solidity 0.x whose semantics solang implements
+pub const SUPPORTED_MINOR: std::ops::RangeInclusive<u64> = 4..=8;
+
+type Version = (u64, u64, u64);
+
+/// A range is a union of half-open intervals of versions
+#[derive(Debug, Clone)]
+pub struct Range(Vec<(Version, Option<Version>)>);
+
+impl Range {
+    /// The versions which match both ranges
+    pub fn intersect(&self, other: &Range) -> Range {
+        let mut intervals = Vec::new();
+
+        for (low_a, high_a) in &self.0 {
+            for (low_b, high_b) in &other.0 {
+                let low = std::cmp::max(*low_a, *low_b);
+                let high = match (high_a, high_b) {
+                    (Some(a), Some(b)) => Some(std::cmp::min(*a, *b)),
+                    (a, b) => a.or(*b),
+                };
+
+                if high.map_or(true, |high| low < high) {
+                    intervals.push((low, high));
+                }
+            }
+        }
+
+        Range(intervals)
+    }
+
+    /// Does no version match the range
+    pub fn is_empty(&self) -> bool {
+        self.0
+            .iter()
+            .all(|(low, high)| high.map_or(false, |high| high <= *low))
+    }
+
+    /// Does any version with this major and minor number match the range
+    fn matches_minor(&self, major: u64, minor: u64) -> bool {
+        let start = (major, minor, 0);
+        let end = (major, minor + 1, 0);
+
+        self.0.iter().any(|(low, high)| {
+            let low = std::cmp::max(*low, start);
+            let high = match high {
+                Some(high) => std::cmp::min(*high, end),
+                None => end,
+            };
+
+            low < high
+        })
+    }
+
+    /// The highest language version implemented by solang which matches the range
+    pub fn highest_supported(&self) -> Option<LanguageVersion> {
+        SUPPORTED_MINOR
+            .rev()
+            .find(|minor| self.matches_minor(0, *minor))
+            .map(|minor| LanguageVersion { major: 0, minor })
+    }
+}
+
+/// Parse a version range like `>=0.4.22 <0.6.0 || ^0.8.0`
+pub fn parse_range(range: &str) -> Result<Range, String> {
+    let mut intervals = Vec::new();
+
+    for set in range.split("||") {
+        let mut low = (0, 0, 0);
+        let mut high = None;
+        let mut comparators = set.split_whitespace().peekable();
+
+        if comparators.peek().is_none() {
+            return Err("empty version range".to_string());
+        }
+
+        while let Some(mut comparator) = comparators.next() {
+            // solc allows a space between the operator and the version, like `>= 0.4.22`
+            let joined;
+
+            if comparator.chars().all(|c| "^~<>=".contains(c)) {
+                match comparators.next() {
+                    Some(version) => {
+                        joined = format!("{}{}", comparator, version);
+                        comparator = &joined;
+                    }
+                    None => return Err(format!("missing version after ‘{}’", comparator)),
+                }
+            }
+
+            let (comparator_low, comparator_high) = parse_comparator(comparator)?;
+
+            low = std::cmp::max(low, comparator_low);
+            high = match (high, comparator_high) {
+                (Some(a), Some(b)) => Some(std::cmp::min(a, b)),
+                (a, b) => a.or(b),
+            };
+        }
+
+        intervals.push((low, high));
+    }
+
+    Ok(Range(intervals))
+}
+
+/// Parse a single comparator into an interval of the versions it matches
+fn parse_comparator(comparator: &str) -> Result<(Version, Option<Version>), String> {
+    let (op, version) = match comparator.find(|c: char| !"^~<>=".contains(c)) {
+        Some(pos) => comparator.split_at(pos),
+        None => return Err(format!("missing version after ‘{}’", comparator)),
+    };
+
+    let parts = parse_version(version)?;
+
+    let major = parts[0].unwrap_or(0);
+    let minor = parts[1].unwrap_or(0);
+    let patch = parts[2].unwrap_or(0);
+
+    let lowest = (major, minor, patch);
+
+    // the first version after all the versions which match the partial version
+    let after = if parts[0].is_none() {
+        None
+    } else if parts[1].is_none() {
+        Some((next(major, version)?, 0, 0))
+    } else if parts[2].is_none() {
+        Some((major, next(minor, version)?, 0))
+    } else {
+        Some((major, minor, next(patch, version)?))
+    };
+
+    match op {
+        "" | "=" => Ok((lowest, after)),
+        ">=" => Ok((lowest, None)),
+        ">" => match after {
+            Some(after) => Ok((after, None)),
+            // nothing is greater than every version
+            None => Ok(((0, 0, 0), Some((0, 0, 0)))),
+        },
+        "<" => Ok(((0, 0, 0), Some(lowest))),
+        "<=" => Ok(((0, 0, 0), after)),
+        "^" => {
+            // allow changes which do not modify the left-most non-zero number
+            let high = if parts[0].is_none() {
+                None
+            } else if major != 0 || parts[1].is_none() {
+                Some((next(major, version)?, 0, 0))
+            } else if minor != 0 || parts[2].is_none() {
+                Some((0, next(minor, version)?, 0))
+            } else {
+                Some((0, 0, next(patch, version)?))
+            };
+
+            Ok((lowest, high))
+        }
+        "~" => {
+            // allow patch level changes if a minor version is given
+            let high = if parts[0].is_none() {
+                None
+            } else if parts[1].is_none() {
+                Some((next(major, version)?, 0, 0))
+            } else {
+                Some((major, next(minor, version)?, 0))
+            };
+
+            Ok((lowest, high))
+        }
+        _ => Err(format!("unknown version operator ‘{}’", op)),
+    }
+}
+
+/// The number after n, which is part of the version
+fn next(n: u64, version: &str) -> Result<u64, String> {
+    n.checked_add(1).ok_or_else(|| {
+        format!(
+            "‘{}’ is not a valid version, the number is too large",
+            version
+        )
+    })
+}
+
+/// Parse a version like `0.8.4`, `0.8` or `0.8.x`; wildcard and missing parts are None
+fn parse_version(version: &str) -> Result<[Option<u64>; 3], String> {
+    let version = version.strip_prefix('v').unwrap_or(version);
+    let mut parts = [None; 3];
+    let mut wildcard = false;
+
+    for (no, part) in version.split('.').enumerate() {
+        if no >= 3 {
+            return Err(format!("‘{}’ is not a valid version", version));
+        }
+
+        if part == "x" || part == "X" || part == "*" {
+            wildcard = true;
+        } else if wildcard {
+            return Err(format!(
+                "‘{}’ is not a valid version, a number cannot follow a wildcard",
+                version
+            ));
+        } else {
+            match part.parse() {
+                Ok(n) => parts[no] = Some(n),
+                Err(_) => return Err(format!("‘{}’ is not a valid version", version)),
+            }
+        }
+    }
+
+    Ok(parts)
+}
@@ -0,0 +1,144 @@
+use assert_cmd::Command;
+use serde_json::Value;
+use std::fs;
+
+const CONTRACTS: &str = r#"
+contract base {
+    uint64 count;
+}
+
+contract counter is base {
+    enum State { Off, On }
+
+    State state;
+
+    event Added(uint64 n);
+
+    function add(uint64 n) public returns (uint64) {
+        count += n;
+        state = State.On;
+        emit Added(n);
+        return count;
+    }
+}
+"#;
+
+/// Find the node with the given type and name
+fn find<'a>(nodes: &'a Value, node_type: &str, name: &str) -> &'a Value {
+    nodes
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|node| node["nodeType"] == node_type && node["name"] == name)
+        .unwrap()
+}
+
+/// The source text of a node
+fn source<'a>(node: &Value, src: &'a str) -> &'a str {
+    let loc: Vec<usize> = node["src"]
+        .as_str()
+        .unwrap()
+        .split(':')
+        .map(|n| n.parse().unwrap())
+        .collect();
+
+    assert_eq!(loc[2], 0);
+
+    &src[loc[0]..loc[0] + loc[1]]
+}
+
+#[test]
+fn emit_ast_json() {
+    let dir = tempfile::tempdir().unwrap();
+
+    fs::write(dir.path().join("counter.sol"), CONTRACTS).unwrap();
+
+    Command::cargo_bin("solang")
+        .unwrap()
+        .current_dir(dir.path())
+        .args(&["--target", "substrate", "--emit", "ast-json", "counter.sol"])
+        .assert()
+        .success();
+
+    let ast: Value =
+        serde_json::from_slice(&fs::read(dir.path().join("counter.ast.json")).unwrap()).unwrap();
+
+    assert_eq!(ast["nodeType"], "SourceUnit");
+
+    let base = find(&ast["nodes"], "ContractDefinition", "base");
+    let counter = find(&ast["nodes"], "ContractDefinition", "counter");
+
+    assert_eq!(ast["exportedSymbols"]["counter"][0], counter["id"]);
+    assert_eq!(counter["contractKind"], "contract");
+    assert_eq!(
+        counter["baseContracts"][0]["baseName"]["referencedDeclaration"],
+        base["id"]
+    );
+    assert_eq!(
+        counter["linearizedBaseContracts"],
+        Value::from(vec![counter["id"].clone(), base["id"].clone()])
+    );
+
+    let count = find(&base["nodes"], "VariableDeclaration", "count");
+
+    assert_eq!(count["stateVariable"], true);
+    assert_eq!(count["typeName"]["name"], "uint64");
+    assert_eq!(count["typeDescriptions"]["typeIdentifier"], "t_uint64");
+
+    let state = find(&counter["nodes"], "EnumDefinition", "State");
+
+    assert_eq!(state["members"][1]["name"], "On");
+
+    let add = find(&counter["nodes"], "FunctionDefinition", "add");
+
+    assert!(source(add, CONTRACTS).starts_with("function add"));
+    assert_eq!(add["kind"], "function");
+    assert_eq!(add["visibility"], "public");
+    assert_eq!(add["stateMutability"], "nonpayable");
+
+    let n = &add["parameters"]["parameters"][0];
+
+    assert_eq!(n["name"], "n");
+    assert_eq!(source(n, CONTRACTS), "uint64 n");
+
+    let statements = &add["body"]["statements"];
+
+    // count += n;
+    let assignment = &statements[0]["expression"];
+
+    assert_eq!(assignment["nodeType"], "Assignment");
+    assert_eq!(assignment["operator"], "+=");
+    assert_eq!(source(assignment, CONTRACTS), "count += n");
+    assert_eq!(
+        assignment["leftHandSide"]["referencedDeclaration"],
+        count["id"]
+    );
+    assert_eq!(
+        assignment["rightHandSide"]["referencedDeclaration"],
+        n["id"]
+    );
+
+    // state = State.On;
+    let value = &statements[1]["expression"]["rightHandSide"];
+
+    assert_eq!(value["nodeType"], "MemberAccess");
+    assert_eq!(value["memberName"], "On");
+    assert_eq!(value["referencedDeclaration"], state["members"][1]["id"]);
+    assert_eq!(value["expression"]["referencedDeclaration"], state["id"]);
+
+    // emit Added(n);
+    let event_call = &statements[2]["eventCall"];
+
+    assert_eq!(statements[2]["nodeType"], "EmitStatement");
+    assert_eq!(
+        event_call["expression"]["referencedDeclaration"],
+        find(&counter["nodes"], "EventDefinition", "Added")["id"]
+    );
+
+    // return count;
+    assert_eq!(statements[3]["nodeType"], "Return");
+    assert_eq!(
+        statements[3]["expression"]["referencedDeclaration"],
+        count["id"]
+    );
+}
@@ -0,0 +1,238 @@
+use assert_cmd::Command;
+use solang::file_cache::FileCache;
+use solang::sema::ast::Level;
+use solang::sema::codes;
+use solang::{parse_and_resolve, Target};
+use std::fs;
+
+const SHADOW: &str = r#"contract c {
+    int x;
+    function f() public pure {
+        int x = 1;
+    }
+}
+"#;
+
+#[test]
+fn message_format() {
+    let dir = tempfile::tempdir().unwrap();
+
+    fs::write(dir.path().join("shadow.sol"), SHADOW).unwrap();
+
+    let assert = Command::cargo_bin("solang")
+        .unwrap()
+        .current_dir(dir.path())
+        .args(&["--target", "evm", "shadow.sol"])
+        .assert()
+        .success();
+
+    let stderr = String::from_utf8_lossy(&assert.get_output().stderr).to_string();
+
+    assert!(stderr.contains("warning[S5007]: declaration of ‘x’ shadows state variable\n"));
+    assert!(stderr.contains(&format!("4 |         int x = 1;\n | {}^\n", " ".repeat(12))));
+    assert!(stderr.contains("2 |     int x;\n"));
+    assert!(stderr.contains("- note: previous declaration of state variable\n"));
+    // not in colour, since stderr is not a terminal
+    assert!(!stderr.contains('\x1b'));
+
+    let assert = Command::cargo_bin("solang")
+        .unwrap()
+        .current_dir(dir.path())
+        .args(&["--target", "evm", "--message-format", "plain", "shadow.sol"])
+        .assert()
+        .success();
+
+    let stderr = String::from_utf8_lossy(&assert.get_output().stderr).to_string();
+
+    assert!(stderr.contains(":4:13-14: warning: declaration of ‘x’ shadows state variable\n"));
+    assert!(stderr.contains(": note: previous declaration of state variable"));
+    assert!(!stderr.contains("S5007"));
+}
+
+#[test]
+fn explain() {
+    let assert = Command::cargo_bin("solang")
+        .unwrap()
+        .args(&["--explain", "s5007"])
+        .assert()
+        .success();
+
+    let stdout = String::from_utf8_lossy(&assert.get_output().stdout).to_string();
+
+    assert!(stdout.starts_with("S5007: declaration shadows another declaration\n\n"));
+
+    let assert = Command::cargo_bin("solang")
+        .unwrap()
+        .args(&["--explain", "S9999"])
+        .assert()
+        .failure();
+
+    let stderr = String::from_utf8_lossy(&assert.get_output().stderr).to_string();
+
+    assert!(stderr.contains("‘S9999’ is not a diagnostic code"));
+}
+
+const WARNINGS: &str = r#"contract c {
+    int x;
+    function f() public pure {
+        int x = 1;
+    }
+    // solang-disable-next-line mutability
+    function g() public returns (int) {
+        return 1;
+    }
+    function h() public returns (int) { // solang-disable-line S5004
+        return 2;
+    }
+    function i() public returns (int) {
+        return 3;
+    }
+}
+// solang-disable-line frobnicate
+"#;
+
+fn warnings(dir: &std::path::Path, args: &[&str], success: bool) -> String {
+    let mut cmd = Command::cargo_bin("solang").unwrap();
+
+    cmd.current_dir(dir)
+        .args(&["--target", "evm"])
+        .args(args)
+        .arg("warnings.sol");
+
+    let assert = if success {
+        cmd.assert().success()
+    } else {
+        cmd.assert().failure()
+    };
+
+    String::from_utf8_lossy(&assert.get_output().stderr).to_string()
+}
+
+#[test]
+fn warning_control() {
+    let dir = tempfile::tempdir().unwrap();
+
+    fs::write(dir.path().join("warnings.sol"), WARNINGS).unwrap();
+
+    // only function i is not suppressed in the source
+    let stderr = warnings(dir.path(), &[], true);
+
+    assert_eq!(stderr.matches("warning[S5004]").count(), 1);
+    assert!(stderr.contains("13 |     function i()"));
+    assert!(stderr.contains("warning[S5007]"));
+    assert!(stderr.contains("warning[S5010]: unknown warning ‘frobnicate’"));
+
+    let stderr = warnings(dir.path(), &["-W", "no-shadowing", "-Wno-S5010"], true);
+
+    assert!(stderr.contains("warning[S5004]"));
+    assert!(!stderr.contains("S5007"));
+    assert!(!stderr.contains("S5010"));
+
+    let stderr = warnings(dir.path(), &["-Werror=mutability"], false);
+
+    assert!(stderr.contains("error[S5004]"));
+    assert!(stderr.contains("warning[S5007]"));
+
+    // the last option which selects a warning wins
+    let stderr = warnings(
+        dir.path(),
+        &["-Werror", "-W", "no-error=shadowing", "-W", "no-all"],
+        true,
+    );
+
+    assert!(!stderr.contains("S50"));
+
+    let stderr = warnings(dir.path(), &["-W", "no-S2001"], false);
+
+    assert!(stderr.contains("error: -W no-S2001: unknown warning ‘S2001’"));
+}
+
+#[test]
+fn recovered_parser_errors_in_order() {
+    let src = r#"contract c {
+    function f() public {
+        x.;
+    }
+
+    function g() public returns (int64) {
+        return y;
+    }
+}
+"#;
+
+    let mut cache = FileCache::new();
+
+    cache.set_file_contents("test.sol", src.to_string());
+
+    let ns = parse_and_resolve("test.sol", &mut cache, Target::Evm);
+
+    let errors: Vec<usize> = ns
+        .diagnostics
+        .iter()
+        .filter(|diag| diag.level == Level::Error)
+        .map(|diag| diag.pos.unwrap().1)
+        .collect();
+
+    // the parser error comes first, even though g() was resolved after recovering from it
+    assert_eq!(errors.len(), 2);
+    assert!(errors[0] < src.find("return y").unwrap());
+    assert!(errors[1] > src.find("return y").unwrap());
+}
+
+#[test]
+fn specific_codes() {
+    let src = r#"contract c {
+    int64 constant C = 1;
+
+    function f(int64 a) public {
+        int32 b = a;
+    }
+
+    function g() public {
+        f(1, 2);
+    }
+
+    function h() public {
+        break;
+    }
+
+    function i() public {
+        C = 2;
+    }
+}
+"#;
+
+    let mut cache = FileCache::new();
+
+    cache.set_file_contents("test.sol", src.to_string());
+
+    let ns = parse_and_resolve("test.sol", &mut cache, Target::Evm);
+
+    let errors: Vec<&str> = ns
+        .diagnostics
+        .iter()
+        .filter(|diag| diag.level == Level::Error)
+        .map(|diag| diag.code)
+        .collect();
+
+    assert_eq!(errors.len(), 4);
+    assert_eq!(errors[0], codes::IMPLICIT_CONVERSION);
+    assert_eq!(errors[2], codes::STATEMENT_NOT_ALLOWED);
+    assert_eq!(errors[3], codes::NOT_ASSIGNABLE);
+
+    for code in errors {
+        // not just the code for the class of error
+        assert!(![codes::ERROR, codes::DECLARATION_ERROR, codes::TYPE_ERROR].contains(&code));
+        assert!(codes::explain(code).is_some());
+    }
+
+    let assert = Command::cargo_bin("solang")
+        .unwrap()
+        .args(&["--explain", "S4001"])
+        .assert()
+        .success();
+
+    let stdout = String::from_utf8_lossy(&assert.get_output().stdout).to_string();
+
+    assert!(stdout.starts_with("S4001: implicit conversion not allowed\n\n"));
+}
@@ -0,0 +1,285 @@
+use ethabi::{decode, RawLog, Token};
+use ethereum_types::{Address, U256};
+use rand::Rng;
+
+use solang::file_cache::FileCache;
+use solang::sema::{ast, diagnostics};
+use solang::{compile, Target};
+
+mod evm_interpreter;
+mod evm_tests;
+
+use evm_interpreter::Evm;
+
+fn address_new() -> Address {
+    let mut rng = rand::thread_rng();
+
+    let mut a = [0u8; 20];
+
+    rng.fill(&mut a[..]);
+
+    Address::from(a)
+}
+
+struct TestRuntime {
+    evm: Evm,
+    abi: ethabi::Contract,
+    code: Vec<u8>,
+    address: Address,
+    caller: Address,
+    value: U256,
+}
+
+impl TestRuntime {
+    fn constructor(&mut self, args: &[Token]) {
+        assert!(self.do_constructor(args));
+    }
+
+    fn constructor_expect_revert(&mut self, args: &[Token]) {
+        assert!(!self.do_constructor(args));
+    }
+
+    fn do_constructor(&mut self, args: &[Token]) -> bool {
+        let code = if let Some(constructor) = &self.abi.constructor {
+            constructor.encode_input(self.code.clone(), args).unwrap()
+        } else {
+            self.code.clone()
+        };
+
+        self.evm.account(self.caller).balance = U256::from(u128::MAX);
+
+        match self.evm.deploy(self.caller, code, self.value) {
+            Ok(address) => {
+                println!(
+                    "DEPLOYED: {} runtime size: {}",
+                    hex::encode(address),
+                    self.evm.account(address).code.len()
+                );
+
+                self.address = address;
+
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    fn raw_function(&mut self, calldata: Vec<u8>) -> (bool, Vec<u8>) {
+        println!("FUNCTION CALLDATA: {}", hex::encode(&calldata));
+
+        let (success, output) = self
+            .evm
+            .call(self.caller, self.address, calldata, self.value);
+
+        println!("RETURNDATA: {}", hex::encode(&output));
+
+        (success, output)
+    }
+
+    fn function(&mut self, name: &str, args: &[Token]) -> Vec<Token> {
+        let calldata = match self.abi.functions[name][0].encode_input(args) {
+            Ok(n) => n,
+            Err(x) => panic!("{}", x),
+        };
+
+        let (success, output) = self.raw_function(calldata);
+
+        assert!(success, "function {} reverted", name);
+
+        match self.abi.functions[name][0].decode_output(&output) {
+            Ok(n) => n,
+            Err(x) => panic!("{}", x),
+        }
+    }
+
+    fn function_revert(&mut self, name: &str, args: &[Token]) -> Option<String> {
+        let calldata = match self.abi.functions[name][0].encode_input(args) {
+            Ok(n) => n,
+            Err(x) => panic!("{}", x),
+        };
+
+        let (success, output) = self.raw_function(calldata);
+
+        assert!(!success, "function {} was supposed to revert", name);
+
+        if output.is_empty() {
+            return None;
+        }
+
+        assert_eq!(output[..4], 0x08c3_79a0u32.to_be_bytes());
+
+        if let Ok(v) = decode(&[ethabi::ParamType::String], &output[4..]) {
+            assert_eq!(v.len(), 1);
+
+            if let Token::String(r) = &v[0] {
+                return Some(r.to_owned());
+            }
+        }
+
+        panic!("failed to decode");
+    }
+
+    fn events(&self) -> Vec<RawLog> {
+        self.evm
+            .logs
+            .iter()
+            .map(|e| RawLog {
+                data: e.data.clone(),
+                topics: e.topics.clone(),
+            })
+            .collect()
+    }
+}
+
+fn build_solidity(src: &str) -> TestRuntime {
+    build_solidity_with_overflow_check(src, false)
+}
+
+fn build_solidity_with_overflow_check(src: &str, math_overflow_check: bool) -> TestRuntime {
+    let mut cache = FileCache::new();
+
+    cache.set_file_contents("test.sol", src.to_string());
+
+    let (res, ns) = compile(
+        "test.sol",
+        &mut cache,
+        inkwell::OptimizationLevel::Default,
+        Target::Evm,
+        math_overflow_check,
+    );
+
+    diagnostics::print_messages(&mut cache, &ns, false);
+
+    for v in &res {
+        println!("contract size:{}", v.0.len());
+    }
+
+    assert_eq!(res.is_empty(), false);
+
+    let (code, abi) = res.last().unwrap().clone();
+
+    TestRuntime {
+        evm: Evm::default(),
+        abi: ethabi::Contract::load(abi.as_bytes()).unwrap(),
+        code,
+        address: Address::zero(),
+        caller: address_new(),
+        value: U256::zero(),
+    }
+}
+
+/// Compile for the evm target, and return the namespace including any errors from the
+/// code generator
+fn build_errors(src: &str) -> ast::Namespace {
+    let mut cache = FileCache::new();
+
+    cache.set_file_contents("test.sol", src.to_string());
+
+    let (_, ns) = compile(
+        "test.sol",
+        &mut cache,
+        inkwell::OptimizationLevel::Default,
+        Target::Evm,
+        false,
+    );
+
+    ns
+}
+
+#[test]
+fn simple_solidity_compile_and_run() {
+    let mut runtime = build_solidity(
+        "
+        contract test {
+            function foo() public returns (uint32) {
+                return 2;
+            }
+        }",
+    );
+
+    runtime.constructor(&[]);
+
+    let returns = runtime.function("foo", &[]);
+
+    assert_eq!(returns, vec![Token::Uint(U256::from(2))]);
+}
+
+#[test]
+fn unknown_selector_reverts() {
+    let mut runtime = build_solidity(
+        "
+        contract test {
+            function foo() public returns (uint32) {
+                return 2;
+            }
+        }",
+    );
+
+    runtime.constructor(&[]);
+
+    let (success, _) = runtime.raw_function(vec![0xde, 0xad, 0xca, 0xfe]);
+
+    assert!(!success);
+
+    let (success, _) = runtime.raw_function(vec![0xde]);
+
+    assert!(!success);
+}
+
+#[test]
+fn unsupported_constructs() {
+    let src = r#"
+        contract test {
+            function foo(function(int32) external returns (bool) f) public returns (bool) {
+                return f(1);
+            }
+        }"#;
+
+    let ns = build_errors(src);
+
+    let error = ns
+        .diagnostics
+        .iter()
+        .find(|m| m.level == ast::Level::Error)
+        .unwrap();
+
+    assert_eq!(
+        error.message,
+        "ABI decoding of function types not supported on EVM"
+    );
+
+    // the error is reported at the parameter, not the contract
+    let loc = error.pos.unwrap();
+    assert!(src[loc.1..loc.2].starts_with("function(int32) external"));
+
+    let src = r#"
+        contract test {
+            function(int32) external returns (bool) f;
+
+            function set() public {
+                f = this.foo;
+            }
+
+            function foo(int32 x) public returns (bool) {
+                return x > 0;
+            }
+        }"#;
+
+    let ns = build_errors(src);
+
+    let error = ns
+        .diagnostics
+        .iter()
+        .find(|m| m.level == ast::Level::Error)
+        .unwrap();
+
+    assert_eq!(
+        error.message,
+        "external function in storage not supported on EVM"
+    );
+
+    let loc = error.pos.unwrap();
+    let set = src.find("function set").unwrap();
+    let foo = src.find("function foo").unwrap();
+    assert!(loc.1 > set && loc.2 < foo);
+}
@@ -0,0 +1,865 @@
+//! A small EVM interpreter for running the output of the evm target. There is no gas
+//! accounting; everything else needed by the generated code is implemented, including the
+//! sha256, ripemd160 and identity precompiles.
+use ethereum_types::{Address, H256, U256, U512};
+use ripemd160::Ripemd160;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use tiny_keccak::{Hasher, Keccak};
+
+const MAX_DEPTH: usize = 1024;
+const MAX_MEMORY: usize = 1 << 24;
+
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak::v256();
+    let mut hash = [0u8; 32];
+
+    hasher.update(data);
+    hasher.finalize(&mut hash);
+
+    hash
+}
+
+#[derive(Clone, Default)]
+pub struct Account {
+    pub code: Vec<u8>,
+    pub balance: U256,
+    pub nonce: u64,
+    pub storage: HashMap<U256, U256>,
+}
+
+#[derive(Clone)]
+pub struct Log {
+    pub address: Address,
+    pub topics: Vec<H256>,
+    pub data: Vec<u8>,
+}
+
+#[derive(Clone, Default)]
+pub struct Evm {
+    pub accounts: HashMap<Address, Account>,
+    pub logs: Vec<Log>,
+    pub origin: Address,
+    pub number: u64,
+    pub timestamp: u64,
+}
+
+/// The message being executed
+struct Message {
+    address: Address,
+    caller: Address,
+    value: U256,
+    code: Vec<u8>,
+    input: Vec<u8>,
+    is_static: bool,
+}
+
+fn word_to_address(w: U256) -> Address {
+    let mut bs = [0u8; 32];
+    w.to_big_endian(&mut bs);
+    Address::from_slice(&bs[12..])
+}
+
+fn address_to_word(a: Address) -> U256 {
+    U256::from_big_endian(a.as_bytes())
+}
+
+fn is_negative(v: U256) -> bool {
+    v.bit(255)
+}
+
+fn negate(v: U256) -> U256 {
+    (!v).overflowing_add(U256::one()).0
+}
+
+fn abs(v: U256) -> U256 {
+    if is_negative(v) {
+        negate(v)
+    } else {
+        v
+    }
+}
+
+fn to_u512(v: U256) -> U512 {
+    U512([v.0[0], v.0[1], v.0[2], v.0[3], 0, 0, 0, 0])
+}
+
+fn from_u512(v: U512) -> U256 {
+    U256([v.0[0], v.0[1], v.0[2], v.0[3]])
+}
+
+fn bool_word(b: bool) -> U256 {
+    if b {
+        U256::one()
+    } else {
+        U256::zero()
+    }
+}
+
+/// Copy len bytes from src at offset, padding with zeros
+fn copy_padded(src: &[u8], offset: U256, len: usize) -> Vec<u8> {
+    let mut res = vec![0u8; len];
+
+    if offset < U256::from(src.len()) {
+        let offset = offset.as_usize();
+        let n = std::cmp::min(len, src.len() - offset);
+
+        res[..n].copy_from_slice(&src[offset..offset + n]);
+    }
+
+    res
+}
+
+struct Failure;
+
+impl Evm {
+    /// Deploy a contract; the code should have the constructor arguments appended
+    pub fn deploy(
+        &mut self,
+        caller: Address,
+        code: Vec<u8>,
+        value: U256,
+    ) -> Result<Address, Vec<u8>> {
+        let nonce = self.account(caller).nonce;
+        let address = create_address(caller, nonce);
+
+        self.create(caller, address, code, value, 0)
+    }
+
+    /// Call a contract as a transaction
+    pub fn call(
+        &mut self,
+        caller: Address,
+        address: Address,
+        input: Vec<u8>,
+        value: U256,
+    ) -> (bool, Vec<u8>) {
+        self.origin = caller;
+
+        self.message_call(caller, address, address, input, value, false, 0)
+    }
+
+    pub fn account(&mut self, address: Address) -> &mut Account {
+        self.accounts.entry(address).or_default()
+    }
+
+    fn transfer(&mut self, from: Address, to: Address, value: U256) -> bool {
+        if value.is_zero() {
+            return true;
+        }
+
+        let balance = self.account(from).balance;
+
+        if balance < value {
+            return false;
+        }
+
+        self.account(from).balance = balance - value;
+        let account = self.account(to);
+        account.balance = account.balance.overflowing_add(value).0;
+
+        true
+    }
+
+    fn create(
+        &mut self,
+        caller: Address,
+        address: Address,
+        init: Vec<u8>,
+        value: U256,
+        depth: usize,
+    ) -> Result<Address, Vec<u8>> {
+        if depth >= MAX_DEPTH {
+            return Err(Vec::new());
+        }
+
+        self.account(caller).nonce += 1;
+
+        if let Some(account) = self.accounts.get(&address) {
+            if !account.code.is_empty() || account.nonce != 0 {
+                return Err(Vec::new());
+            }
+        }
+
+        let snapshot = (self.accounts.clone(), self.logs.len());
+
+        self.account(address).nonce = 1;
+
+        if !self.transfer(caller, address, value) {
+            self.accounts = snapshot.0;
+            return Err(Vec::new());
+        }
+
+        let msg = Message {
+            address,
+            caller,
+            value,
+            code: init,
+            input: Vec::new(),
+            is_static: false,
+        };
+
+        match self.execute(&msg, depth) {
+            (true, code) => {
+                self.account(address).code = code;
+                Ok(address)
+            }
+            (false, output) => {
+                self.accounts = snapshot.0;
+                self.logs.truncate(snapshot.1);
+                Err(output)
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn message_call(
+        &mut self,
+        caller: Address,
+        address: Address,
+        code_address: Address,
+        input: Vec<u8>,
+        value: U256,
+        is_static: bool,
+        depth: usize,
+    ) -> (bool, Vec<u8>) {
+        if depth >= MAX_DEPTH {
+            return (false, Vec::new());
+        }
+
+        if let Some(output) = precompile(code_address, &input) {
+            return (true, output);
+        }
+
+        let snapshot = (self.accounts.clone(), self.logs.len());
+
+        if address == code_address && !self.transfer(caller, address, value) {
+            return (false, Vec::new());
+        }
+
+        let code = self.account(code_address).code.clone();
+
+        let msg = Message {
+            address,
+            caller,
+            value,
+            code,
+            input,
+            is_static,
+        };
+
+        let (success, output) = self.execute(&msg, depth);
+
+        if !success {
+            self.accounts = snapshot.0;
+            self.logs.truncate(snapshot.1);
+        }
+
+        (success, output)
+    }
+
+    fn execute(&mut self, msg: &Message, depth: usize) -> (bool, Vec<u8>) {
+        let mut state = State {
+            stack: Vec::new(),
+            memory: Vec::new(),
+            returndata: Vec::new(),
+            jumpdests: jumpdests(&msg.code),
+        };
+
+        match self.run(msg, &mut state, depth) {
+            Ok(Exit::Return(output)) => (true, output),
+            Ok(Exit::Revert(output)) => (false, output),
+            Err(Failure) => (false, Vec::new()),
+        }
+    }
+
+    fn run(&mut self, msg: &Message, state: &mut State, depth: usize) -> Result<Exit, Failure> {
+        let code = &msg.code;
+        let mut pc = 0;
+
+        while pc < code.len() {
+            let op = code[pc];
+
+            pc += 1;
+
+            match op {
+                0x00 => return Ok(Exit::Return(Vec::new())),
+                0x01 => {
+                    let (a, b) = (state.pop()?, state.pop()?);
+                    state.push(a.overflowing_add(b).0)?;
+                }
+                0x02 => {
+                    let (a, b) = (state.pop()?, state.pop()?);
+                    state.push(a.overflowing_mul(b).0)?;
+                }
+                0x03 => {
+                    let (a, b) = (state.pop()?, state.pop()?);
+                    state.push(a.overflowing_sub(b).0)?;
+                }
+                0x04 => {
+                    let (a, b) = (state.pop()?, state.pop()?);
+                    state.push(if b.is_zero() { b } else { a / b })?;
+                }
+                0x05 => {
+                    let (a, b) = (state.pop()?, state.pop()?);
+                    let res = if b.is_zero() {
+                        b
+                    } else {
+                        let q = abs(a) / abs(b);
+
+                        if is_negative(a) != is_negative(b) {
+                            negate(q)
+                        } else {
+                            q
+                        }
+                    };
+                    state.push(res)?;
+                }
+                0x06 => {
+                    let (a, b) = (state.pop()?, state.pop()?);
+                    state.push(if b.is_zero() { b } else { a % b })?;
+                }
+                0x07 => {
+                    let (a, b) = (state.pop()?, state.pop()?);
+                    let res = if b.is_zero() {
+                        b
+                    } else {
+                        let r = abs(a) % abs(b);
+
+                        if is_negative(a) {
+                            negate(r)
+                        } else {
+                            r
+                        }
+                    };
+                    state.push(res)?;
+                }
+                0x08 | 0x09 => {
+                    let (a, b, n) = (state.pop()?, state.pop()?, state.pop()?);
+                    let res = if n.is_zero() {
+                        n
+                    } else if op == 0x08 {
+                        from_u512((to_u512(a) + to_u512(b)) % to_u512(n))
+                    } else {
+                        from_u512(a.full_mul(b) % to_u512(n))
+                    };
+                    state.push(res)?;
+                }
+                0x0a => {
+                    let (a, b) = (state.pop()?, state.pop()?);
+                    state.push(a.overflowing_pow(b).0)?;
+                }
+                0x0b => {
+                    let (b, x) = (state.pop()?, state.pop()?);
+                    let res = if b < U256::from(31) {
+                        let bit = b.as_usize() * 8 + 7;
+                        let mask = (U256::one() << (bit + 1)) - 1;
+
+                        if x.bit(bit) {
+                            x | !mask
+                        } else {
+                            x & mask
+                        }
+                    } else {
+                        x
+                    };
+                    state.push(res)?;
+                }
+                0x10 => {
+                    let (a, b) = (state.pop()?, state.pop()?);
+                    state.push(bool_word(a < b))?;
+                }
+                0x11 => {
+                    let (a, b) = (state.pop()?, state.pop()?);
+                    state.push(bool_word(a > b))?;
+                }
+                0x12 | 0x13 => {
+                    let sign = U256::one() << 255;
+                    let (a, b) = (state.pop()? ^ sign, state.pop()? ^ sign);
+                    state.push(bool_word(if op == 0x12 { a < b } else { a > b }))?;
+                }
+                0x14 => {
+                    let (a, b) = (state.pop()?, state.pop()?);
+                    state.push(bool_word(a == b))?;
+                }
+                0x15 => {
+                    let a = state.pop()?;
+                    state.push(bool_word(a.is_zero()))?;
+                }
+                0x16 => {
+                    let (a, b) = (state.pop()?, state.pop()?);
+                    state.push(a & b)?;
+                }
+                0x17 => {
+                    let (a, b) = (state.pop()?, state.pop()?);
+                    state.push(a | b)?;
+                }
+                0x18 => {
+                    let (a, b) = (state.pop()?, state.pop()?);
+                    state.push(a ^ b)?;
+                }
+                0x19 => {
+                    let a = state.pop()?;
+                    state.push(!a)?;
+                }
+                0x1a => {
+                    let (i, x) = (state.pop()?, state.pop()?);
+                    let res = if i < U256::from(32) {
+                        (x >> (8 * (31 - i.as_usize()))) & U256::from(0xff)
+                    } else {
+                        U256::zero()
+                    };
+                    state.push(res)?;
+                }
+                0x1b => {
+                    let (shift, x) = (state.pop()?, state.pop()?);
+                    let res = if shift < U256::from(256) {
+                        x << shift.as_usize()
+                    } else {
+                        U256::zero()
+                    };
+                    state.push(res)?;
+                }
+                0x1c => {
+                    let (shift, x) = (state.pop()?, state.pop()?);
+                    let res = if shift < U256::from(256) {
+                        x >> shift.as_usize()
+                    } else {
+                        U256::zero()
+                    };
+                    state.push(res)?;
+                }
+                0x1d => {
+                    let (shift, x) = (state.pop()?, state.pop()?);
+                    let res = if shift >= U256::from(256) {
+                        if is_negative(x) {
+                            U256::MAX
+                        } else {
+                            U256::zero()
+                        }
+                    } else if is_negative(x) {
+                        !((!x) >> shift.as_usize())
+                    } else {
+                        x >> shift.as_usize()
+                    };
+                    state.push(res)?;
+                }
+                0x20 => {
+                    let (offset, len) = (state.pop()?, state.pop()?);
+                    let data = state.read_memory(offset, len)?;
+                    state.push(U256::from_big_endian(&keccak256(&data)))?;
+                }
+                0x30 => state.push(address_to_word(msg.address))?,
+                0x31 => {
+                    let address = word_to_address(state.pop()?);
+                    let balance = self.account(address).balance;
+                    state.push(balance)?;
+                }
+                0x32 => state.push(address_to_word(self.origin))?,
+                0x33 => state.push(address_to_word(msg.caller))?,
+                0x34 => state.push(msg.value)?,
+                0x35 => {
+                    let offset = state.pop()?;
+                    state.push(U256::from_big_endian(&copy_padded(&msg.input, offset, 32)))?;
+                }
+                0x36 => state.push(U256::from(msg.input.len()))?,
+                0x37 | 0x39 => {
+                    let (dest, offset, len) = (state.pop()?, state.pop()?, state.pop()?);
+                    let src = if op == 0x37 { &msg.input } else { code };
+                    let len = state.check_len(len)?;
+                    let data = copy_padded(src, offset, len);
+                    state.write_memory(dest, &data)?;
+                }
+                0x38 => state.push(U256::from(code.len()))?,
+                0x3a => state.push(U256::one())?,
+                0x3d => state.push(U256::from(state.returndata.len()))?,
+                0x3e => {
+                    let (dest, offset, len) = (state.pop()?, state.pop()?, state.pop()?);
+                    let end = offset.overflowing_add(len);
+
+                    if end.1 || end.0 > U256::from(state.returndata.len()) {
+                        return Err(Failure);
+                    }
+
+                    let data = state.returndata[offset.as_usize()..end.0.as_usize()].to_vec();
+                    state.write_memory(dest, &data)?;
+                }
+                0x40 => {
+                    let number = state.pop()?;
+                    let mut bs = [0u8; 32];
+                    number.to_big_endian(&mut bs);
+                    state.push(U256::from_big_endian(&keccak256(&bs)))?;
+                }
+                0x41 => state.push(U256::from(0xc014_ba5e_u64))?,
+                0x42 => state.push(U256::from(self.timestamp))?,
+                0x43 => state.push(U256::from(self.number))?,
+                0x44 => state.push(U256::from(0x2000_0000_u64))?,
+                0x45 => state.push(U256::from(30_000_000_u64))?,
+                0x47 => {
+                    let balance = self.account(msg.address).balance;
+                    state.push(balance)?;
+                }
+                0x50 => {
+                    state.pop()?;
+                }
+                0x51 => {
+                    let offset = state.pop()?;
+                    let data = state.read_memory(offset, U256::from(32))?;
+                    state.push(U256::from_big_endian(&data))?;
+                }
+                0x52 => {
+                    let (offset, value) = (state.pop()?, state.pop()?);
+                    let mut bs = [0u8; 32];
+                    value.to_big_endian(&mut bs);
+                    state.write_memory(offset, &bs)?;
+                }
+                0x53 => {
+                    let (offset, value) = (state.pop()?, state.pop()?);
+                    state.write_memory(offset, &[value.low_u32() as u8])?;
+                }
+                0x54 => {
+                    let key = state.pop()?;
+                    let value = self
+                        .account(msg.address)
+                        .storage
+                        .get(&key)
+                        .cloned()
+                        .unwrap_or_default();
+                    state.push(value)?;
+                }
+                0x55 => {
+                    if msg.is_static {
+                        return Err(Failure);
+                    }
+
+                    let (key, value) = (state.pop()?, state.pop()?);
+                    let storage = &mut self.account(msg.address).storage;
+
+                    if value.is_zero() {
+                        storage.remove(&key);
+                    } else {
+                        storage.insert(key, value);
+                    }
+                }
+                0x56 => {
+                    let dest = state.pop()?;
+                    pc = state.jump(dest)?;
+                }
+                0x57 => {
+                    let (dest, cond) = (state.pop()?, state.pop()?);
+
+                    if !cond.is_zero() {
+                        pc = state.jump(dest)?;
+                    }
+                }
+                0x58 => state.push(U256::from(pc - 1))?,
+                0x59 => state.push(U256::from(state.memory.len()))?,
+                0x5a => state.push(U256::from(u64::MAX))?,
+                0x5b => (),
+                0x60..=0x7f => {
+                    let n = (op - 0x5f) as usize;
+                    let bs = copy_padded(code, U256::from(pc), n);
+                    state.push(U256::from_big_endian(&bs))?;
+                    pc += n;
+                }
+                0x80..=0x8f => {
+                    let n = (op - 0x7f) as usize;
+
+                    if state.stack.len() < n {
+                        return Err(Failure);
+                    }
+
+                    let v = state.stack[state.stack.len() - n];
+                    state.push(v)?;
+                }
+                0x90..=0x9f => {
+                    let n = (op - 0x8f) as usize;
+                    let len = state.stack.len();
+
+                    if len <= n {
+                        return Err(Failure);
+                    }
+
+                    state.stack.swap(len - 1, len - 1 - n);
+                }
+                0xa0..=0xa4 => {
+                    if msg.is_static {
+                        return Err(Failure);
+                    }
+
+                    let (offset, len) = (state.pop()?, state.pop()?);
+                    let mut topics = Vec::new();
+
+                    for _ in 0..(op - 0xa0) {
+                        let mut bs = [0u8; 32];
+                        state.pop()?.to_big_endian(&mut bs);
+                        topics.push(H256::from(bs));
+                    }
+
+                    let data = state.read_memory(offset, len)?;
+
+                    self.logs.push(Log {
+                        address: msg.address,
+                        topics,
+                        data,
+                    });
+                }
+                0xf0 | 0xf5 => {
+                    if msg.is_static {
+                        return Err(Failure);
+                    }
+
+                    let (value, offset, len) = (state.pop()?, state.pop()?, state.pop()?);
+                    let init = state.read_memory(offset, len)?;
+
+                    let address = if op == 0xf0 {
+                        let nonce = self.account(msg.address).nonce;
+                        create_address(msg.address, nonce)
+                    } else {
+                        let mut salt = [0u8; 32];
+                        state.pop()?.to_big_endian(&mut salt);
+                        create2_address(msg.address, &salt, &init)
+                    };
+
+                    match self.create(msg.address, address, init, value, depth + 1) {
+                        Ok(address) => {
+                            state.returndata = Vec::new();
+                            state.push(address_to_word(address))?;
+                        }
+                        Err(output) => {
+                            state.returndata = output;
+                            state.push(U256::zero())?;
+                        }
+                    }
+                }
+                0xf1 | 0xf4 | 0xfa => {
+                    let _gas = state.pop()?;
+                    let to = word_to_address(state.pop()?);
+                    let value = if op == 0xf1 {
+                        state.pop()?
+                    } else {
+                        U256::zero()
+                    };
+                    let (in_offset, in_len) = (state.pop()?, state.pop()?);
+                    let (out_offset, out_len) = (state.pop()?, state.pop()?);
+
+                    if msg.is_static && !value.is_zero() {
+                        return Err(Failure);
+                    }
+
+                    let input = state.read_memory(in_offset, in_len)?;
+
+                    let (success, output) = match op {
+                        0xf1 => self.message_call(
+                            msg.address,
+                            to,
+                            to,
+                            input,
+                            value,
+                            msg.is_static,
+                            depth + 1,
+                        ),
+                        0xf4 => self.message_call(
+                            msg.caller,
+                            msg.address,
+                            to,
+                            input,
+                            msg.value,
+                            msg.is_static,
+                            depth + 1,
+                        ),
+                        _ => self.message_call(
+                            msg.address,
+                            to,
+                            to,
+                            input,
+                            U256::zero(),
+                            true,
+                            depth + 1,
+                        ),
+                    };
+
+                    let out_len = state.check_len(out_len)?;
+                    let n = std::cmp::min(out_len, output.len());
+
+                    state.write_memory(out_offset, &output[..n])?;
+                    state.returndata = output;
+                    state.push(bool_word(success))?;
+                }
+                0xf3 | 0xfd => {
+                    let (offset, len) = (state.pop()?, state.pop()?);
+                    let data = state.read_memory(offset, len)?;
+
+                    return Ok(if op == 0xf3 {
+                        Exit::Return(data)
+                    } else {
+                        Exit::Revert(data)
+                    });
+                }
+                0xff => {
+                    if msg.is_static {
+                        return Err(Failure);
+                    }
+
+                    let beneficiary = word_to_address(state.pop()?);
+                    let balance = self.account(msg.address).balance;
+
+                    self.transfer(msg.address, beneficiary, balance);
+                    self.accounts.remove(&msg.address);
+
+                    return Ok(Exit::Return(Vec::new()));
+                }
+                _ => return Err(Failure),
+            }
+        }
+
+        Ok(Exit::Return(Vec::new()))
+    }
+}
+
+enum Exit {
+    Return(Vec<u8>),
+    Revert(Vec<u8>),
+}
+
+struct State {
+    stack: Vec<U256>,
+    memory: Vec<u8>,
+    returndata: Vec<u8>,
+    jumpdests: Vec<bool>,
+}
+
+impl State {
+    fn push(&mut self, v: U256) -> Result<(), Failure> {
+        if self.stack.len() >= 1024 {
+            return Err(Failure);
+        }
+
+        self.stack.push(v);
+
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Result<U256, Failure> {
+        self.stack.pop().ok_or(Failure)
+    }
+
+    fn jump(&self, dest: U256) -> Result<usize, Failure> {
+        if dest < U256::from(self.jumpdests.len()) && self.jumpdests[dest.as_usize()] {
+            Ok(dest.as_usize())
+        } else {
+            Err(Failure)
+        }
+    }
+
+    fn check_len(&self, len: U256) -> Result<usize, Failure> {
+        if len > U256::from(MAX_MEMORY) {
+            Err(Failure)
+        } else {
+            Ok(len.as_usize())
+        }
+    }
+
+    /// Grow memory to include the given range
+    fn expand(&mut self, offset: U256, len: usize) -> Result<usize, Failure> {
+        if len == 0 {
+            return Ok(0);
+        }
+
+        if offset > U256::from(MAX_MEMORY) || len > MAX_MEMORY {
+            return Err(Failure);
+        }
+
+        let offset = offset.as_usize();
+        let end = (offset + len + 31) & !31;
+
+        if end > self.memory.len() {
+            self.memory.resize(end, 0);
+        }
+
+        Ok(offset)
+    }
+
+    fn read_memory(&mut self, offset: U256, len: U256) -> Result<Vec<u8>, Failure> {
+        let len = self.check_len(len)?;
+        let offset = self.expand(offset, len)?;
+
+        Ok(if len == 0 {
+            Vec::new()
+        } else {
+            self.memory[offset..offset + len].to_vec()
+        })
+    }
+
+    fn write_memory(&mut self, offset: U256, data: &[u8]) -> Result<(), Failure> {
+        let offset = self.expand(offset, data.len())?;
+
+        if !data.is_empty() {
+            self.memory[offset..offset + data.len()].copy_from_slice(data);
+        }
+
+        Ok(())
+    }
+}
+
+/// Find the valid jump destinations, skipping push data
+fn jumpdests(code: &[u8]) -> Vec<bool> {
+    let mut res = vec![false; code.len()];
+    let mut pc = 0;
+
+    while pc < code.len() {
+        match code[pc] {
+            0x5b => res[pc] = true,
+            op @ 0x60..=0x7f => pc += (op - 0x5f) as usize,
+            _ => (),
+        }
+
+        pc += 1;
+    }
+
+    res
+}
+
+/// The address of a contract created with create is the last 20 bytes of
+/// keccak256(rlp([sender, nonce]))
+pub fn create_address(sender: Address, nonce: u64) -> Address {
+    assert!(nonce < 0x80, "large nonces not implemented");
+
+    let mut rlp = vec![0xd6, 0x94];
+
+    rlp.extend_from_slice(sender.as_bytes());
+    rlp.push(if nonce == 0 { 0x80 } else { nonce as u8 });
+
+    Address::from_slice(&keccak256(&rlp)[12..])
+}
+
+/// The address of contract created with create2 is the last 20 bytes of
+/// keccak256(0xff ++ deployer ++ salt ++ keccak256(init_code))
+pub fn create2_address(deployer: Address, salt: &[u8; 32], init_code: &[u8]) -> Address {
+    let mut input = vec![0xffu8];
+
+    input.extend_from_slice(deployer.as_bytes());
+    input.extend_from_slice(salt);
+    input.extend_from_slice(&keccak256(init_code));
+
+    Address::from_slice(&keccak256(&input)[12..])
+}
+
+fn precompile(address: Address, input: &[u8]) -> Option<Vec<u8>> {
+    let bs = address.as_bytes();
+
+    if bs[..19].iter().any(|b| *b != 0) {
+        return None;
+    }
+
+    match bs[19] {
+        2 => Some(Sha256::digest(input).to_vec()),
+        3 => {
+            let mut res = vec![0u8; 12];
+            res.extend_from_slice(&Ripemd160::digest(input));
+            Some(res)
+        }
+        4 => Some(input.to_vec()),
+        _ => None,
+    }
+}
@@ -0,0 +1,198 @@
+use crate::build_solidity;
+use ethabi::Token;
+use ethereum_types::U256;
+
+#[test]
+fn nested_dynamic_types() {
+    let mut vm = build_solidity(
+        r#"
+        contract test {
+            struct S {
+                bool f1;
+                string f2;
+                int64[] f3;
+            }
+
+            function echo(S memory s, string[] memory names) public pure returns (S memory, string[] memory) {
+                return (s, names);
+            }
+
+            function count(S[] memory list) public pure returns (uint256 total) {
+                for (uint32 i = 0; i < list.length; i++) {
+                    total += list[i].f3.length;
+                }
+            }
+        }"#,
+    );
+
+    vm.constructor(&[]);
+
+    let s = Token::Tuple(vec![
+        Token::Bool(true),
+        Token::String(String::from("foobar")),
+        Token::Array(vec![
+            Token::Int(U256::from(1)),
+            Token::Int(U256::MAX),
+            Token::Int(U256::from(3)),
+        ]),
+    ]);
+
+    let names = Token::Array(vec![
+        Token::String(String::from("alice")),
+        Token::String(String::from(
+            "a much longer name which spans more than one word",
+        )),
+        Token::String(String::new()),
+    ]);
+
+    let returns = vm.function("echo", &[s.clone(), names.clone()]);
+
+    assert_eq!(returns, vec![s.clone(), names]);
+
+    let returns = vm.function("count", &[Token::Array(vec![s.clone(), s])]);
+
+    assert_eq!(returns, vec![Token::Uint(U256::from(6))]);
+}
+
+#[test]
+fn fixed_arrays_and_bytes() {
+    let mut vm = build_solidity(
+        r#"
+        contract test {
+            function swap(uint8[3] memory a, bytes4 b) public pure returns (bytes4, uint8[3] memory) {
+                return (b, [a[2], a[1], a[0]]);
+            }
+        }"#,
+    );
+
+    vm.constructor(&[]);
+
+    let returns = vm.function(
+        "swap",
+        &[
+            Token::FixedArray(vec![
+                Token::Uint(U256::from(1)),
+                Token::Uint(U256::from(2)),
+                Token::Uint(U256::from(3)),
+            ]),
+            Token::FixedBytes(vec![0xde, 0xad, 0xbe, 0xef]),
+        ],
+    );
+
+    assert_eq!(
+        returns,
+        vec![
+            Token::FixedBytes(vec![0xde, 0xad, 0xbe, 0xef]),
+            Token::FixedArray(vec![
+                Token::Uint(U256::from(3)),
+                Token::Uint(U256::from(2)),
+                Token::Uint(U256::from(1)),
+            ]),
+        ]
+    );
+}
+
+#[test]
+fn invalid_input() {
+    let mut vm = build_solidity(
+        r#"
+        contract test {
+            function f(uint8 a, bool b) public pure returns (uint8) {
+                return b ? a : 0;
+            }
+
+            function g(string memory s) public pure returns (uint256) {
+                return bytes(s).length;
+            }
+        }"#,
+    );
+
+    vm.constructor(&[]);
+
+    let selector = vm.abi.functions["f"][0].short_signature().to_vec();
+
+    // uint8 out of range
+    let mut calldata = selector.clone();
+    calldata.extend_from_slice(&[0u8; 30]);
+    calldata.extend_from_slice(&[1, 0]);
+    calldata.extend_from_slice(&[0u8; 31]);
+    calldata.push(1);
+
+    let (success, _) = vm.raw_function(calldata);
+
+    assert!(!success);
+
+    // bool out of range
+    let mut calldata = selector.clone();
+    calldata.extend_from_slice(&[0u8; 31]);
+    calldata.push(1);
+    calldata.extend_from_slice(&[0u8; 31]);
+    calldata.push(2);
+
+    let (success, _) = vm.raw_function(calldata);
+
+    assert!(!success);
+
+    // too short
+    let mut calldata = selector;
+    calldata.extend_from_slice(&[0u8; 40]);
+
+    let (success, _) = vm.raw_function(calldata);
+
+    assert!(!success);
+
+    // string length goes beyond calldata
+    let mut calldata = vm.abi.functions["g"][0].short_signature().to_vec();
+    calldata.extend_from_slice(&[0u8; 31]);
+    calldata.push(0x20);
+    calldata.extend_from_slice(&[0u8; 31]);
+    calldata.push(0x40);
+    calldata.extend_from_slice(&[0u8; 32]);
+
+    let (success, _) = vm.raw_function(calldata);
+
+    assert!(!success);
+}
+
+#[test]
+fn encode_packed() {
+    let mut vm = build_solidity(
+        r#"
+        contract test {
+            function packed(uint16 a, bytes3 b, string memory c) public pure returns (bytes memory) {
+                return abi.encodePacked(a, b, c);
+            }
+
+            function encoded(uint16 a, string memory c) public pure returns (bytes memory) {
+                return abi.encode(a, c);
+            }
+        }"#,
+    );
+
+    vm.constructor(&[]);
+
+    let returns = vm.function(
+        "packed",
+        &[
+            Token::Uint(U256::from(0x1234)),
+            Token::FixedBytes(vec![0xab, 0xcd, 0xef]),
+            Token::String(String::from("xyz")),
+        ],
+    );
+
+    assert_eq!(
+        returns,
+        vec![Token::Bytes(vec![
+            0x12, 0x34, 0xab, 0xcd, 0xef, b'x', b'y', b'z'
+        ])]
+    );
+
+    let args = [
+        Token::Uint(U256::from(0x1234)),
+        Token::String(String::from("xyz")),
+    ];
+
+    let returns = vm.function("encoded", &args);
+
+    assert_eq!(returns, vec![Token::Bytes(ethabi::encode(&args))]);
+}
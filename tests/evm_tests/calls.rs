@@ -0,0 +1,140 @@
+use crate::build_solidity;
+use ethabi::Token;
+use ethereum_types::U256;
+
+#[test]
+fn create_and_call() {
+    let mut vm = build_solidity(
+        r#"
+        contract child {
+            uint32 value;
+
+            constructor(uint32 v) {
+                value = v;
+            }
+
+            function get() public view returns (uint32) {
+                return value;
+            }
+
+            function fail() public pure {
+                revert("child failed");
+            }
+        }
+
+        contract creator {
+            child c;
+
+            function create(uint32 v) public {
+                c = new child(v);
+            }
+
+            function get() public view returns (uint32) {
+                return c.get();
+            }
+
+            function tryFail() public returns (bool) {
+                try c.fail() {
+                    return true;
+                } catch Error(string reason) {
+                    return reason.length == 12;
+                }
+            }
+        }"#,
+    );
+
+    vm.constructor(&[]);
+
+    vm.function("create", &[Token::Uint(U256::from(102))]);
+
+    assert_eq!(vm.function("get", &[]), vec![Token::Uint(U256::from(102))]);
+
+    assert_eq!(vm.function("tryFail", &[]), vec![Token::Bool(true)]);
+}
+
+#[test]
+fn salted_create() {
+    let mut vm = build_solidity(
+        r#"
+        contract child {
+            function hello() public pure returns (uint32) {
+                return 7;
+            }
+        }
+
+        contract creator {
+            function create(uint256 salt) public returns (bool) {
+                bytes32 codehash = keccak256(type(child).creationCode);
+
+                address predicted = predict_address(codehash, salt);
+
+                child c = new child{salt: salt}();
+
+                assert(c.hello() == 7);
+
+                return address(c) == predicted;
+            }
+        }"#,
+    );
+
+    vm.constructor(&[]);
+
+    assert_eq!(
+        vm.function("create", &[Token::Uint(U256::from(0xdead))]),
+        vec![Token::Bool(true)]
+    );
+}
+
+#[test]
+fn payable_and_fallback() {
+    let mut vm = build_solidity(
+        r#"
+        contract test {
+            uint32 public calls;
+
+            function pay() public payable returns (uint128) {
+                return uint128(msg.value);
+            }
+
+            function nopay() public returns (uint32) {
+                return 1;
+            }
+
+            fallback() external {
+                calls += 1;
+            }
+        }"#,
+    );
+
+    vm.constructor(&[]);
+
+    vm.value = U256::from(1000);
+
+    assert_eq!(vm.function("pay", &[]), vec![Token::Uint(U256::from(1000))]);
+
+    assert_eq!(vm.function_revert("nopay", &[]), None);
+
+    vm.value = U256::zero();
+
+    let (success, _) = vm.raw_function(vec![1, 2, 3, 4, 5]);
+
+    assert!(success);
+
+    assert_eq!(vm.function("calls", &[]), vec![Token::Uint(U256::from(1))]);
+}
+
+#[test]
+fn constructor_revert() {
+    let mut vm = build_solidity(
+        r#"
+        contract test {
+            constructor(uint32 v) {
+                require(v != 0, "zero");
+            }
+        }"#,
+    );
+
+    vm.constructor_expect_revert(&[Token::Uint(U256::zero())]);
+
+    vm.constructor(&[Token::Uint(U256::from(1))]);
+}
@@ -0,0 +1,60 @@
+use crate::build_solidity;
+use ethabi::Token;
+use ethereum_types::U256;
+
+#[test]
+fn emit() {
+    let mut vm = build_solidity(
+        r#"
+        contract test {
+            event Transfer(address indexed from, address indexed to, uint256 value);
+            event Named(string indexed name, string description) anonymous;
+
+            function transfer(address to, uint256 value) public {
+                emit Transfer(msg.sender, to, value);
+            }
+
+            function named() public {
+                emit Named("foo", "bar");
+            }
+        }"#,
+    );
+
+    vm.constructor(&[]);
+
+    let to = ethereum_types::Address::repeat_byte(0x42);
+
+    vm.function(
+        "transfer",
+        &[Token::Address(to), Token::Uint(U256::from(500))],
+    );
+
+    let events = vm.events();
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(vm.evm.logs[0].address, vm.address);
+
+    let event = vm.abi.events_by_name("Transfer").unwrap()[0]
+        .parse_log(events[0].clone())
+        .unwrap();
+
+    assert_eq!(event.params[0].value, Token::Address(vm.caller));
+    assert_eq!(event.params[1].value, Token::Address(to));
+    assert_eq!(event.params[2].value, Token::Uint(U256::from(500)));
+
+    vm.function("named", &[]);
+
+    let events = vm.events();
+
+    assert_eq!(events.len(), 2);
+
+    let event = vm.abi.events_by_name("Named").unwrap()[0]
+        .parse_log(events[1].clone())
+        .unwrap();
+
+    assert_eq!(
+        event.params[0].value,
+        Token::FixedBytes(crate::evm_interpreter::keccak256(b"foo").to_vec())
+    );
+    assert_eq!(event.params[1].value, Token::String(String::from("bar")));
+}
@@ -0,0 +1,5 @@
+mod abi;
+mod calls;
+mod events;
+mod primitives;
+mod storage;
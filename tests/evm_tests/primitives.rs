@@ -0,0 +1,403 @@
+use crate::{build_solidity, build_solidity_with_overflow_check};
+use ethabi::Token;
+use ethereum_types::U256;
+
+#[test]
+fn arithmetic() {
+    let mut vm = build_solidity(
+        r#"
+        contract test {
+            function add(uint8 a, uint8 b) public returns (uint8) {
+                unchecked {
+                    return a + b;
+                }
+            }
+
+            function sub(int32 a, int32 b) public returns (int32) {
+                return a - b;
+            }
+
+            function div(int64 a, int64 b) public returns (int64) {
+                return a / b;
+            }
+
+            function rem(int64 a, int64 b) public returns (int64) {
+                return a % b;
+            }
+
+            function pow(uint64 a, uint64 b) public returns (uint64) {
+                return a ** b;
+            }
+
+            function shift(int16 a) public returns (int16, int16) {
+                return (a >> 2, a << 3);
+            }
+        }"#,
+    );
+
+    vm.constructor(&[]);
+
+    let returns = vm.function(
+        "add",
+        &[Token::Uint(U256::from(200)), Token::Uint(U256::from(100))],
+    );
+
+    assert_eq!(returns, vec![Token::Uint(U256::from(44))]);
+
+    let returns = vm.function(
+        "sub",
+        &[Token::Int(U256::from(5)), Token::Int(U256::from(7))],
+    );
+
+    assert_eq!(returns, vec![Token::Int(U256::MAX - 1)]);
+
+    let minus_seven = U256::MAX - 6;
+
+    let returns = vm.function("div", &[Token::Int(minus_seven), Token::Int(U256::from(2))]);
+
+    assert_eq!(returns, vec![Token::Int(U256::MAX - 2)]);
+
+    let returns = vm.function("rem", &[Token::Int(minus_seven), Token::Int(U256::from(2))]);
+
+    assert_eq!(returns, vec![Token::Int(U256::MAX)]);
+
+    assert_eq!(
+        vm.function_revert("div", &[Token::Int(minus_seven), Token::Int(U256::zero())]),
+        None
+    );
+
+    let returns = vm.function(
+        "pow",
+        &[Token::Uint(U256::from(3)), Token::Uint(U256::from(5))],
+    );
+
+    assert_eq!(returns, vec![Token::Uint(U256::from(243))]);
+
+    let returns = vm.function("shift", &[Token::Int(U256::MAX - 15)]);
+
+    assert_eq!(
+        returns,
+        vec![Token::Int(U256::MAX - 3), Token::Int(U256::MAX - 127)]
+    );
+}
+
+#[test]
+fn checked_arithmetic() {
+    let mut vm = build_solidity(
+        r#"
+        pragma solidity ^0.8.0;
+
+        contract test {
+            function add(uint8 a, uint8 b) public returns (uint8) {
+                return a + b;
+            }
+
+            function add_unchecked(uint8 a, uint8 b) public returns (uint8) {
+                unchecked {
+                    return a + b;
+                }
+            }
+
+            function sub(uint256 a, uint256 b) public returns (uint256) {
+                return a - b;
+            }
+
+            function sub_signed(int256 a, int256 b) public returns (int256) {
+                return a - b;
+            }
+
+            function mul(int16 a, int16 b) public returns (int16) {
+                return a * b;
+            }
+
+            function mul_wide(uint256 a, uint256 b) public returns (uint256) {
+                return a * b;
+            }
+
+            function pow(uint64 a, uint64 b) public returns (uint64) {
+                return a ** b;
+            }
+        }"#,
+    );
+
+    vm.constructor(&[]);
+
+    let returns = vm.function(
+        "add",
+        &[Token::Uint(U256::from(200)), Token::Uint(U256::from(55))],
+    );
+
+    assert_eq!(returns, vec![Token::Uint(U256::from(255))]);
+
+    assert_eq!(
+        vm.function_revert(
+            "add",
+            &[Token::Uint(U256::from(200)), Token::Uint(U256::from(56))]
+        ),
+        None
+    );
+
+    let returns = vm.function(
+        "add_unchecked",
+        &[Token::Uint(U256::from(200)), Token::Uint(U256::from(56))],
+    );
+
+    assert_eq!(returns, vec![Token::Uint(U256::zero())]);
+
+    let returns = vm.function(
+        "sub",
+        &[Token::Uint(U256::from(7)), Token::Uint(U256::from(7))],
+    );
+
+    assert_eq!(returns, vec![Token::Uint(U256::zero())]);
+
+    assert_eq!(
+        vm.function_revert(
+            "sub",
+            &[Token::Uint(U256::from(7)), Token::Uint(U256::from(8))]
+        ),
+        None
+    );
+
+    // -2**255
+    let min = U256::one() << 255;
+
+    let returns = vm.function(
+        "sub_signed",
+        &[Token::Int(U256::MAX), Token::Int(U256::MAX)],
+    );
+
+    assert_eq!(returns, vec![Token::Int(U256::zero())]);
+
+    assert_eq!(
+        vm.function_revert("sub_signed", &[Token::Int(min), Token::Int(U256::one())]),
+        None
+    );
+
+    // -128 * 256 fits, 128 * 256 does not
+    let returns = vm.function(
+        "mul",
+        &[Token::Int(U256::MAX - 127), Token::Int(U256::from(256))],
+    );
+
+    assert_eq!(returns, vec![Token::Int(U256::MAX - 32767)]);
+
+    assert_eq!(
+        vm.function_revert(
+            "mul",
+            &[Token::Int(U256::from(128)), Token::Int(U256::from(256))]
+        ),
+        None
+    );
+
+    let returns = vm.function(
+        "mul_wide",
+        &[
+            Token::Uint(U256::one() << 128),
+            Token::Uint(U256::MAX >> 128),
+        ],
+    );
+
+    assert_eq!(returns, vec![Token::Uint((U256::MAX >> 128) << 128)]);
+
+    assert_eq!(
+        vm.function_revert(
+            "mul_wide",
+            &[
+                Token::Uint(U256::one() << 128),
+                Token::Uint(U256::one() << 128)
+            ]
+        ),
+        None
+    );
+
+    let returns = vm.function(
+        "pow",
+        &[Token::Uint(U256::from(2)), Token::Uint(U256::from(63))],
+    );
+
+    assert_eq!(returns, vec![Token::Uint(U256::one() << 63)]);
+
+    assert_eq!(
+        vm.function_revert(
+            "pow",
+            &[Token::Uint(U256::from(2)), Token::Uint(U256::from(64))]
+        ),
+        None
+    );
+
+    // the base is only squared when there are more bits in the exponent
+    let returns = vm.function(
+        "pow",
+        &[Token::Uint(U256::one() << 32), Token::Uint(U256::one())],
+    );
+
+    assert_eq!(returns, vec![Token::Uint(U256::one() << 32)]);
+}
+
+#[test]
+fn math_overflow_option() {
+    let src = r#"
+        contract test {
+            function add(uint8 a, uint8 b) public returns (uint8) {
+                return a + b;
+            }
+        }"#;
+
+    let args = [Token::Uint(U256::from(200)), Token::Uint(U256::from(56))];
+
+    let mut vm = build_solidity(src);
+
+    vm.constructor(&[]);
+
+    let returns = vm.function("add", &args);
+
+    assert_eq!(returns, vec![Token::Uint(U256::zero())]);
+
+    let mut vm = build_solidity_with_overflow_check(src, true);
+
+    vm.constructor(&[]);
+
+    assert_eq!(vm.function_revert("add", &args), None);
+}
+
+#[test]
+fn loops_and_calls() {
+    let mut vm = build_solidity(
+        r#"
+        contract test {
+            function fib(uint32 n) public returns (uint32) {
+                if (n < 2) {
+                    return n;
+                }
+
+                return fib(n - 1) + fib(n - 2);
+            }
+
+            function sum(uint64 n) public returns (uint64 total) {
+                for (uint64 i = 1; i <= n; i++) {
+                    total += i;
+                }
+            }
+        }"#,
+    );
+
+    vm.constructor(&[]);
+
+    let returns = vm.function("fib", &[Token::Uint(U256::from(12))]);
+
+    assert_eq!(returns, vec![Token::Uint(U256::from(144))]);
+
+    let returns = vm.function("sum", &[Token::Uint(U256::from(100))]);
+
+    assert_eq!(returns, vec![Token::Uint(U256::from(5050))]);
+}
+
+#[test]
+fn require_reason() {
+    let mut vm = build_solidity(
+        r#"
+        contract test {
+            function check(uint32 n) public returns (uint32) {
+                require(n > 10, "too small");
+
+                return n * 2;
+            }
+        }"#,
+    );
+
+    vm.constructor(&[]);
+
+    let returns = vm.function("check", &[Token::Uint(U256::from(11))]);
+
+    assert_eq!(returns, vec![Token::Uint(U256::from(22))]);
+
+    assert_eq!(
+        vm.function_revert("check", &[Token::Uint(U256::from(10))]),
+        Some(String::from("too small"))
+    );
+}
+
+#[test]
+fn memory_arrays_and_strings() {
+    let mut vm = build_solidity(
+        r#"
+        contract test {
+            function squares(uint32 n) public returns (uint32[] memory) {
+                uint32[] memory res = new uint32[](n);
+
+                for (uint32 i = 0; i < n; i++) {
+                    res[i] = i * i;
+                }
+
+                return res;
+            }
+
+            function greet(string memory name) public returns (string memory) {
+                return string(abi.encodePacked("Hello, ", name, "!"));
+            }
+
+            function second(bytes memory bs) public returns (bytes1) {
+                return bs[1];
+            }
+        }"#,
+    );
+
+    vm.constructor(&[]);
+
+    let returns = vm.function("squares", &[Token::Uint(U256::from(4))]);
+
+    assert_eq!(
+        returns,
+        vec![Token::Array(vec![
+            Token::Uint(U256::from(0)),
+            Token::Uint(U256::from(1)),
+            Token::Uint(U256::from(4)),
+            Token::Uint(U256::from(9)),
+        ])]
+    );
+
+    let returns = vm.function("greet", &[Token::String(String::from("World"))]);
+
+    assert_eq!(returns, vec![Token::String(String::from("Hello, World!"))]);
+
+    let returns = vm.function("second", &[Token::Bytes(vec![0xca, 0xfe, 0x01])]);
+
+    assert_eq!(returns, vec![Token::FixedBytes(vec![0xfe])]);
+
+    assert_eq!(
+        vm.function_revert("second", &[Token::Bytes(vec![0xca])]),
+        None
+    );
+}
+
+#[test]
+fn hashes() {
+    let mut vm = build_solidity(
+        r#"
+        contract test {
+            function hashes(bytes memory bs) public returns (bytes32, bytes32, bytes20) {
+                return (keccak256(bs), sha256(bs), ripemd160(bs));
+            }
+        }"#,
+    );
+
+    vm.constructor(&[]);
+
+    let returns = vm.function("hashes", &[Token::Bytes(b"Hello, World!".to_vec())]);
+
+    assert_eq!(
+        returns,
+        vec![
+            Token::FixedBytes(
+                hex::decode("acaf3289d7b601cbd114fb36c4d29c85bbfd5e133f14cb355c3fd8d99367964f")
+                    .unwrap()
+            ),
+            Token::FixedBytes(
+                hex::decode("dffd6021bb2bd5b0af676290809ec3a53191dd81c7f70a4b28688a362182986f")
+                    .unwrap()
+            ),
+            Token::FixedBytes(hex::decode("527a6a4b9a6da75607546842e0e00105350b1aaf").unwrap()),
+        ]
+    );
+}
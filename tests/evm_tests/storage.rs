@@ -0,0 +1,230 @@
+use crate::build_solidity;
+use ethabi::Token;
+use ethereum_types::U256;
+
+#[test]
+fn value_types() {
+    let mut vm = build_solidity(
+        r#"
+        contract test {
+            uint64 count;
+            int8 small = -3;
+            bool flag;
+
+            constructor(uint64 start) {
+                count = start;
+            }
+
+            function inc() public {
+                count += 1;
+                flag = !flag;
+            }
+
+            function get() public view returns (uint64, int8, bool) {
+                return (count, small, flag);
+            }
+        }"#,
+    );
+
+    vm.constructor(&[Token::Uint(U256::from(41))]);
+
+    vm.function("inc", &[]);
+
+    let returns = vm.function("get", &[]);
+
+    assert_eq!(
+        returns,
+        vec![
+            Token::Uint(U256::from(42)),
+            Token::Int(U256::MAX - 2),
+            Token::Bool(true)
+        ]
+    );
+}
+
+#[test]
+fn strings_and_bytes() {
+    let mut vm = build_solidity(
+        r#"
+        contract test {
+            string name;
+            bytes data;
+
+            function set(string memory n) public {
+                name = n;
+            }
+
+            function get() public view returns (string memory) {
+                return name;
+            }
+
+            function push(bytes1 b) public {
+                data.push(b);
+            }
+
+            function byteAt(uint32 i) public view returns (bytes1) {
+                return data[i];
+            }
+
+            function length() public view returns (uint256) {
+                return data.length;
+            }
+        }"#,
+    );
+
+    vm.constructor(&[]);
+
+    let long = "The quick brown fox jumps over the lazy dog, and then some more text";
+
+    vm.function("set", &[Token::String(String::from(long))]);
+
+    assert_eq!(
+        vm.function("get", &[]),
+        vec![Token::String(String::from(long))]
+    );
+
+    vm.function("set", &[Token::String(String::from("short"))]);
+
+    assert_eq!(
+        vm.function("get", &[]),
+        vec![Token::String(String::from("short"))]
+    );
+
+    for b in 0..40u8 {
+        vm.function("push", &[Token::FixedBytes(vec![b])]);
+    }
+
+    assert_eq!(
+        vm.function("length", &[]),
+        vec![Token::Uint(U256::from(40))]
+    );
+
+    assert_eq!(
+        vm.function("byteAt", &[Token::Uint(U256::from(33))]),
+        vec![Token::FixedBytes(vec![33])]
+    );
+
+    assert_eq!(
+        vm.function_revert("byteAt", &[Token::Uint(U256::from(40))]),
+        None
+    );
+}
+
+#[test]
+fn mappings_arrays_structs() {
+    let mut vm = build_solidity(
+        r#"
+        contract test {
+            struct S {
+                uint32 a;
+                string b;
+            }
+
+            mapping(address => uint256) balances;
+            uint16[] list;
+            S s;
+
+            function deposit(uint256 amount) public {
+                balances[msg.sender] += amount;
+            }
+
+            function balance(address a) public view returns (uint256) {
+                return balances[a];
+            }
+
+            function add(uint16 v) public {
+                list.push(v);
+            }
+
+            function total() public view returns (uint16 t) {
+                for (uint32 i = 0; i < list.length; i++) {
+                    t += list[i];
+                }
+            }
+
+            function setS(uint32 a, string memory b) public {
+                s = S(a, b);
+            }
+
+            function getS() public view returns (uint32, string memory) {
+                return (s.a, s.b);
+            }
+        }"#,
+    );
+
+    vm.constructor(&[]);
+
+    vm.function("deposit", &[Token::Uint(U256::from(100))]);
+    vm.function("deposit", &[Token::Uint(U256::from(23))]);
+
+    let caller = vm.caller;
+
+    assert_eq!(
+        vm.function("balance", &[Token::Address(caller)]),
+        vec![Token::Uint(U256::from(123))]
+    );
+
+    for v in &[3, 5, 7] {
+        vm.function("add", &[Token::Uint(U256::from(*v))]);
+    }
+
+    assert_eq!(vm.function("total", &[]), vec![Token::Uint(U256::from(15))]);
+
+    vm.function(
+        "setS",
+        &[
+            Token::Uint(U256::from(7)),
+            Token::String(String::from("seven")),
+        ],
+    );
+
+    assert_eq!(
+        vm.function("getS", &[]),
+        vec![
+            Token::Uint(U256::from(7)),
+            Token::String(String::from("seven"))
+        ]
+    );
+}
+
+#[test]
+fn constructor_stores_function_pointer() {
+    let mut vm = build_solidity(
+        r#"
+        contract test {
+            function(int64) internal pure returns (int64) op;
+            int64 first;
+
+            constructor(bool double) {
+                if (double) {
+                    op = twice;
+                } else {
+                    op = thrice;
+                }
+
+                first = op(7);
+            }
+
+            function twice(int64 x) internal pure returns (int64) {
+                return x * 2;
+            }
+
+            function thrice(int64 x) internal pure returns (int64) {
+                return x * 3;
+            }
+
+            function apply(int64 x) public view returns (int64, int64) {
+                return (op(x), first);
+            }
+        }"#,
+    );
+
+    vm.constructor(&[Token::Bool(false)]);
+
+    let returns = vm.function("apply", &[Token::Int(U256::from(5))]);
+
+    assert_eq!(
+        returns,
+        vec![Token::Int(U256::from(15)), Token::Int(U256::from(21))]
+    );
+}
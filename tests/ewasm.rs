@@ -28,6 +28,32 @@ fn address_new() -> Address {
     a
 }
 
+fn keccak256(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak::v256();
+    let mut hash = [0u8; 32];
+
+    hasher.update(data);
+    hasher.finalize(&mut hash);
+
+    hash
+}
+
+/// The address of contract created with create2 is the last 20 bytes of
+/// keccak256(0xff ++ deployer ++ salt ++ keccak256(init_code))
+fn create2_address(deployer: &Address, salt: &[u8; 32], init_code_hash: &[u8; 32]) -> Address {
+    let mut input = vec![0xffu8];
+
+    input.extend_from_slice(deployer);
+    input.extend_from_slice(salt);
+    input.extend_from_slice(init_code_hash);
+
+    let mut address = [0u8; 20];
+
+    address.copy_from_slice(&keccak256(&input)[12..]);
+
+    address
+}
+
 struct VirtualMachine {
     memory: MemoryRef,
     cur: Address,
@@ -86,6 +112,7 @@ pub enum Extern {
     getExternalBalance,
     selfDestruct,
     log,
+    create2,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -275,11 +302,15 @@ impl Externals for TestRuntime {
 
                 Ok(None)
             }
-            Some(Extern::create) => {
+            Some(extern_ @ Extern::create) | Some(extern_ @ Extern::create2) => {
                 //let balance_ptr: u32 = args.nth_checked(0)?;
                 let input_ptr: u32 = args.nth_checked(1)?;
                 let input_len: u32 = args.nth_checked(2)?;
-                let address_ptr: u32 = args.nth_checked(3)?;
+                let address_ptr: u32 = if let Extern::create2 = extern_ {
+                    args.nth_checked(4)?
+                } else {
+                    args.nth_checked(3)?
+                };
 
                 let mut buf = Vec::new();
                 buf.resize(input_len as usize, 0u8);
@@ -290,9 +321,26 @@ impl Externals for TestRuntime {
 
                 println!("create code: {}", hex::encode(&buf));
 
-                let addr = address_new();
+                let addr = if let Extern::create2 = extern_ {
+                    let salt_ptr: u32 = args.nth_checked(3)?;
+
+                    let mut salt = [0u8; 32];
+
+                    if let Err(e) = self.vm.memory.get_into(salt_ptr, &mut salt) {
+                        panic!("create2: {}", e);
+                    }
+
+                    create2_address(&self.vm.cur, &salt, &keccak256(&buf))
+                } else {
+                    address_new()
+                };
+
                 println!("create address: {}", hex::encode(&addr));
 
+                if self.accounts.contains_key(&addr) {
+                    return Ok(Some(RuntimeValue::I32(1)));
+                }
+
                 // when ewasm creates a contract, the abi encoded args are concatenated to the
                 // code. So, find which code is was and use that instead. Otherwise, the
                 // wasm validator will trip
@@ -570,6 +618,7 @@ impl ModuleImportResolver for TestRuntime {
             "getCodeSize" => Extern::getCodeSize,
             "codeCopy" => Extern::codeCopy,
             "create" => Extern::create,
+            "create2" => Extern::create2,
             "call" => Extern::call,
             "returnDataCopy" => Extern::returnDataCopy,
             "getReturnDataSize" => Extern::getReturnDataSize,
@@ -2341,3 +2390,107 @@ fn event() {
         }
     }
 }
+
+#[test]
+fn deployer_calls_internal_functions() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            uint64 public x;
+            uint64 y = 10;
+
+            constructor(uint64 a) {
+                x = double(a) + y;
+            }
+
+            function double(uint64 a) internal returns (uint64) {
+                return a * 2;
+            }
+
+            function quad(uint64 a) public returns (uint64) {
+                require(a < 1000, "quad argument is too large");
+
+                return double(double(a));
+            }
+        }"##,
+    );
+
+    let deployer = runtime.contracts.last().unwrap().clone();
+
+    runtime.constructor(&[ethabi::Token::Uint(ethereum_types::U256::from(7))]);
+
+    // the runtime code is embedded in the deployer; the rest of the deployer should not
+    // contain the functions which only the runtime code calls
+    let code = runtime.accounts[&runtime.vm.cur].0.clone();
+    let contains = |haystack: &[u8], needle: &[u8]| {
+        haystack
+            .windows(needle.len())
+            .position(|window| window == needle)
+    };
+
+    let start = contains(&deployer, &code).unwrap();
+    let mut rest = deployer[..start].to_vec();
+    rest.extend_from_slice(&deployer[start + code.len()..]);
+
+    assert!(contains(&code, b"quad argument is too large").is_some());
+    assert!(contains(&rest, b"quad argument is too large").is_none());
+
+    let returns = runtime.function("x", &[]);
+
+    assert_eq!(
+        returns,
+        vec![ethabi::Token::Uint(ethereum_types::U256::from(24))]
+    );
+
+    let returns = runtime.function(
+        "quad",
+        &[ethabi::Token::Uint(ethereum_types::U256::from(3))],
+    );
+
+    assert_eq!(
+        returns,
+        vec![ethabi::Token::Uint(ethereum_types::U256::from(12))]
+    );
+}
+
+#[test]
+fn constructor_stores_function_pointer() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            function(uint64) internal returns (uint64) op;
+
+            constructor(bool double) {
+                if (double) {
+                    op = twice;
+                } else {
+                    op = thrice;
+                }
+            }
+
+            function twice(uint64 a) internal returns (uint64) {
+                return a * 2;
+            }
+
+            function thrice(uint64 a) internal returns (uint64) {
+                return a * 3;
+            }
+
+            function apply(uint64 a) public returns (uint64) {
+                return op(a);
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[ethabi::Token::Bool(false)]);
+
+    let returns = runtime.function(
+        "apply",
+        &[ethabi::Token::Uint(ethereum_types::U256::from(5))],
+    );
+
+    assert_eq!(
+        returns,
+        vec![ethabi::Token::Uint(ethereum_types::U256::from(15))]
+    );
+}
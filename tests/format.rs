@@ -0,0 +1,92 @@
+use assert_cmd::Command;
+use std::fs;
+
+const UNFORMATTED: &str = r#"contract c {
+    // the value
+    uint  public x;
+    function f(uint a) view public returns(uint) { return a+x; }
+}
+"#;
+
+const FORMATTED: &str = r#"contract c {
+    // the value
+    uint public x;
+
+    function f(uint a) public view returns (uint) {
+        return a + x;
+    }
+}
+"#;
+
+#[test]
+fn format_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("c.sol");
+
+    fs::write(&file, UNFORMATTED).unwrap();
+
+    let assert = Command::cargo_bin("solang")
+        .unwrap()
+        .current_dir(dir.path())
+        .args(&["fmt", "--check", "c.sol"])
+        .assert()
+        .failure();
+
+    let stderr = String::from_utf8_lossy(&assert.get_output().stderr).to_string();
+
+    assert!(stderr.contains("c.sol:3: error: file is not formatted"));
+
+    // --check does not change the file
+    assert_eq!(fs::read_to_string(&file).unwrap(), UNFORMATTED);
+
+    Command::cargo_bin("solang")
+        .unwrap()
+        .current_dir(dir.path())
+        .args(&["fmt", "c.sol"])
+        .assert()
+        .success();
+
+    assert_eq!(fs::read_to_string(&file).unwrap(), FORMATTED);
+
+    Command::cargo_bin("solang")
+        .unwrap()
+        .current_dir(dir.path())
+        .args(&["fmt", "--check", "c.sol"])
+        .assert()
+        .success();
+
+    Command::cargo_bin("solang")
+        .unwrap()
+        .current_dir(dir.path())
+        .args(&["fmt", "--tabs", "c.sol"])
+        .assert()
+        .success();
+
+    assert_eq!(
+        fs::read_to_string(&file).unwrap(),
+        FORMATTED.replace("    ", "\t")
+    );
+}
+
+#[test]
+fn syntax_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("c.sol");
+    let src = "contract c {\n    function f() public { x = ; }\n}\n";
+
+    fs::write(&file, src).unwrap();
+
+    let assert = Command::cargo_bin("solang")
+        .unwrap()
+        .current_dir(dir.path())
+        .args(&["--message-format", "plain", "fmt", "c.sol"])
+        .assert()
+        .failure();
+
+    let stderr = String::from_utf8_lossy(&assert.get_output().stderr).to_string();
+
+    assert!(stderr.contains("c.sol:2:"));
+
+    // a file with errors is not changed
+    assert_eq!(fs::read_to_string(&file).unwrap(), src);
+}
@@ -0,0 +1,489 @@
+use ethabi::Token;
+use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
+use std::collections::HashMap;
+use std::ffi::CString;
+use std::fs;
+use wasmi::memory_units::Pages;
+use wasmi::*;
+
+use solang::file_cache::FileCache;
+use solang::linker::wasm_linker;
+use solang::sema::diagnostics;
+use solang::{compile, Target};
+
+fn build_solidity(src: &str) -> (Vec<(Vec<u8>, String)>, solang::sema::ast::Namespace) {
+    let mut cache = FileCache::new();
+
+    cache.set_file_contents("test.sol", src.to_string());
+
+    let (res, ns) = compile(
+        "test.sol",
+        &mut cache,
+        inkwell::OptimizationLevel::Default,
+        Target::Generic,
+        false,
+    );
+
+    diagnostics::print_messages(&mut cache, &ns, false);
+
+    assert_eq!(res.is_empty(), false);
+
+    (res, ns)
+}
+
+#[test]
+fn c_header() {
+    let (_, ns) = build_solidity(
+        r##"
+        contract incrementer {
+            uint32 private value;
+
+            constructor(uint32 initvalue) {
+                value = initvalue;
+            }
+
+            function inc(uint32 by) public {
+                value += by;
+            }
+
+            function add(uint32 a) public view returns (uint32) {
+                return value + a;
+            }
+
+            function add(uint32 a, uint32 b) public view returns (uint32) {
+                return value + a + b;
+            }
+
+            function get() public view returns (uint32) {
+                return value;
+            }
+        }"##,
+    );
+
+    let header = ns.contracts[0].c_header(&ns);
+
+    assert!(header.contains("#ifndef SOLANG_INCREMENTER_H"));
+    assert!(header.contains("int32_t solang_constructor(uint8_t *input, uint32_t input_size);"));
+    assert!(header.contains("int32_t solang_function(uint8_t *input, uint32_t input_size);"));
+    assert!(header.contains("#define INCREMENTER_INC_SELECTOR 0x"));
+    assert!(header.contains("#define INCREMENTER_GET_SELECTOR 0x6d4ce63c /* get() */"));
+    // overloaded functions are named after their signature
+    assert!(header.contains("#define INCREMENTER_ADD_UINT32_SELECTOR"));
+    assert!(header.contains("#define INCREMENTER_ADD_UINT32_UINT32_SELECTOR"));
+
+    // host interface
+    assert!(header.contains("void *solang_malloc(uint32_t size);"));
+    assert!(header.contains("uint32_t solang_storage_size(uint8_t key[32]);"));
+    assert!(
+        header.contains("void solang_storage_set(uint8_t key[32], uint8_t *data, uint32_t size);")
+    );
+    assert!(header.contains("uint64_t solang_block_number(void);"));
+    assert!(header.contains(
+        "uint32_t solang_transfer(uint8_t address[SOLANG_ADDRESS_LENGTH], uint8_t value[SOLANG_VALUE_LENGTH]);"
+    ));
+}
+
+#[test]
+fn storage_and_calls() {
+    // this should compile without hitting any unimplemented target hooks
+    build_solidity(
+        r##"
+        interface other {
+            function foo(bytes bs) external returns (string);
+        }
+
+        contract c {
+            string s;
+            bytes bs;
+            function() external f;
+
+            event Set(string indexed s, uint64 when);
+
+            function set(string v) public {
+                s = v;
+                emit Set(v, block.timestamp);
+            }
+
+            function push(bytes1 b) public returns (uint32) {
+                bs.push(b);
+                bs[0] = b;
+                return bs.length;
+            }
+
+            function pop() public returns (bytes1) {
+                return bs.pop();
+            }
+
+            function call(other o) public returns (string) {
+                f = this.get;
+                return o.foo(bs);
+            }
+
+            function get() public view returns (address, uint128, uint128, bytes32) {
+                return (msg.sender, msg.value, address(this).balance, sha256(bs));
+            }
+
+            function pay(address payable a) public {
+                a.transfer(1);
+            }
+        }"##,
+    );
+}
+
+/// A host program for the generic target, like examples/generic/incrementer.c
+struct MockHost {
+    abi: ethabi::Contract,
+    memory: MemoryRef,
+    /// Lengths of all the heap allocations
+    allocations: HashMap<u32, u32>,
+    storage: HashMap<Vec<u8>, Vec<u8>>,
+    output: Vec<u8>,
+}
+
+#[derive(FromPrimitive)]
+#[allow(non_camel_case_types)]
+enum Extern {
+    solang_malloc = 1,
+    solang_realloc,
+    solang_free,
+    solang_storage_size,
+    solang_storage_get,
+    solang_storage_set,
+    solang_storage_delete,
+    solang_set_return,
+    solang_print,
+    solang_caller,
+    solang_value_transferred,
+    solang_gasprice,
+}
+
+impl MockHost {
+    /// Allocate memory at the end of the linear memory
+    fn malloc(&mut self, size: u32) -> u32 {
+        let pages = (size as usize + 0xffff) / 0x10000;
+
+        let Pages(previous) = self
+            .memory
+            .grow(Pages(std::cmp::max(pages, 1)))
+            .expect("memory should grow");
+
+        let ptr = (previous * 0x10000) as u32;
+
+        self.allocations.insert(ptr, size);
+
+        ptr
+    }
+
+    fn read(&self, ptr: u32, size: u32) -> Vec<u8> {
+        self.memory
+            .get(ptr, size as usize)
+            .expect("pointer should be in memory")
+    }
+
+    fn write(&self, ptr: u32, data: &[u8]) {
+        self.memory.set(ptr, data).expect("memory should be set");
+    }
+}
+
+impl Externals for MockHost {
+    fn invoke_index(
+        &mut self,
+        index: usize,
+        args: RuntimeArgs,
+    ) -> Result<Option<RuntimeValue>, Trap> {
+        match FromPrimitive::from_usize(index) {
+            Some(Extern::solang_malloc) => {
+                let size: u32 = args.nth_checked(0)?;
+
+                Ok(Some(RuntimeValue::I32(self.malloc(size) as i32)))
+            }
+            Some(Extern::solang_realloc) => {
+                let ptr: u32 = args.nth_checked(0)?;
+                let size: u32 = args.nth_checked(1)?;
+
+                let new_ptr = self.malloc(size);
+
+                if let Some(old_size) = self.allocations.remove(&ptr) {
+                    let data = self.read(ptr, std::cmp::min(old_size, size));
+
+                    self.write(new_ptr, &data);
+                }
+
+                Ok(Some(RuntimeValue::I32(new_ptr as i32)))
+            }
+            Some(Extern::solang_free) => {
+                let ptr: u32 = args.nth_checked(0)?;
+
+                self.allocations.remove(&ptr);
+
+                Ok(None)
+            }
+            Some(Extern::solang_storage_size) => {
+                let key: u32 = args.nth_checked(0)?;
+
+                let size = match self.storage.get(&self.read(key, 32)) {
+                    Some(value) => value.len() as i32,
+                    None => 0,
+                };
+
+                Ok(Some(RuntimeValue::I32(size)))
+            }
+            Some(Extern::solang_storage_get) => {
+                let key: u32 = args.nth_checked(0)?;
+                let data: u32 = args.nth_checked(1)?;
+
+                let value = self.storage[&self.read(key, 32)].clone();
+
+                self.write(data, &value);
+
+                Ok(None)
+            }
+            Some(Extern::solang_storage_set) => {
+                let key: u32 = args.nth_checked(0)?;
+                let data: u32 = args.nth_checked(1)?;
+                let size: u32 = args.nth_checked(2)?;
+
+                let key = self.read(key, 32);
+                let value = self.read(data, size);
+
+                println!(
+                    "solang_storage_set key:{} value:{}",
+                    hex::encode(&key),
+                    hex::encode(&value)
+                );
+
+                self.storage.insert(key, value);
+
+                Ok(None)
+            }
+            Some(Extern::solang_storage_delete) => {
+                let key: u32 = args.nth_checked(0)?;
+
+                self.storage.remove(&self.read(key, 32));
+
+                Ok(None)
+            }
+            Some(Extern::solang_set_return) => {
+                let data: u32 = args.nth_checked(0)?;
+                let size: u32 = args.nth_checked(1)?;
+
+                self.output = self.read(data, size);
+
+                Ok(None)
+            }
+            Some(Extern::solang_print) => {
+                let data: u32 = args.nth_checked(0)?;
+                let size: u32 = args.nth_checked(1)?;
+
+                println!(
+                    "solang_print: {}",
+                    String::from_utf8_lossy(&self.read(data, size))
+                );
+
+                Ok(None)
+            }
+            Some(Extern::solang_caller) => {
+                let address: u32 = args.nth_checked(0)?;
+
+                self.write(address, &[0x02; 20]);
+
+                Ok(None)
+            }
+            Some(Extern::solang_value_transferred) => {
+                let value: u32 = args.nth_checked(0)?;
+
+                self.write(value, &[0; 16]);
+
+                Ok(None)
+            }
+            Some(Extern::solang_gasprice) => {
+                let value: u32 = args.nth_checked(0)?;
+
+                // values are little endian
+                self.write(value, &7u128.to_le_bytes());
+
+                Ok(None)
+            }
+            _ => panic!("external {} unknown", index),
+        }
+    }
+}
+
+impl ModuleImportResolver for MockHost {
+    fn resolve_func(&self, field_name: &str, signature: &Signature) -> Result<FuncRef, Error> {
+        let index = match field_name {
+            "solang_malloc" => Extern::solang_malloc,
+            "solang_realloc" => Extern::solang_realloc,
+            "solang_free" => Extern::solang_free,
+            "solang_storage_size" => Extern::solang_storage_size,
+            "solang_storage_get" => Extern::solang_storage_get,
+            "solang_storage_set" => Extern::solang_storage_set,
+            "solang_storage_delete" => Extern::solang_storage_delete,
+            "solang_set_return" => Extern::solang_set_return,
+            "solang_print" => Extern::solang_print,
+            "solang_caller" => Extern::solang_caller,
+            "solang_value_transferred" => Extern::solang_value_transferred,
+            "solang_gasprice" => Extern::solang_gasprice,
+            _ => {
+                panic!("{} not implemented", field_name);
+            }
+        };
+
+        Ok(FuncInstance::alloc_host(signature.clone(), index as usize))
+    }
+}
+
+impl MockHost {
+    /// Call solang_constructor or solang_function and return the return code
+    fn invoke(&mut self, module: &ModuleRef, entry: &str, input: &[u8]) -> i32 {
+        self.output.clear();
+
+        let ptr = self.malloc(input.len() as u32);
+
+        self.write(ptr, input);
+
+        match module.invoke_export(
+            entry,
+            &[
+                RuntimeValue::I32(ptr as i32),
+                RuntimeValue::I32(input.len() as i32),
+            ],
+            self,
+        ) {
+            Ok(Some(RuntimeValue::I32(ret))) => ret,
+            Ok(ret) => panic!("{} returns: {:?}", entry, ret),
+            Err(e) => panic!("fail to invoke {}: {}", entry, e),
+        }
+    }
+
+    fn function(&mut self, module: &ModuleRef, name: &str, args: &[Token]) -> Vec<Token> {
+        let input = self.abi.functions[name][0].encode_input(args).unwrap();
+
+        assert_eq!(self.invoke(module, "solang_function", &input), 0);
+
+        self.abi.functions[name][0]
+            .decode_output(&self.output)
+            .unwrap()
+    }
+}
+
+/// Link the wasm object file produced for the generic target, like a host program would
+fn link(object: &[u8]) -> Vec<u8> {
+    let dir = tempfile::tempdir().unwrap();
+
+    let object_file = dir.path().join("contract.o");
+    let wasm_file = dir.path().join("contract.wasm");
+
+    fs::write(&object_file, object).unwrap();
+
+    let args: Vec<CString> = vec![
+        "--no-entry",
+        "--allow-undefined",
+        "--export",
+        "solang_constructor",
+        "--export",
+        "solang_function",
+        object_file.to_str().unwrap(),
+        "-o",
+        wasm_file.to_str().unwrap(),
+    ]
+    .into_iter()
+    .map(|arg| CString::new(arg).unwrap())
+    .collect();
+
+    if wasm_linker(&args) {
+        panic!("linker failed");
+    }
+
+    fs::read(wasm_file).unwrap()
+}
+
+#[test]
+fn mock_host() {
+    let (res, _) = build_solidity(
+        r##"
+        contract incrementer {
+            uint32 private value;
+
+            constructor(uint32 initvalue) {
+                value = initvalue;
+            }
+
+            function inc(uint32 by) public {
+                value += by;
+            }
+
+            function get() public view returns (uint32) {
+                return value;
+            }
+
+            function price(uint64 gas) public view returns (uint128, uint128) {
+                return (tx.gasprice, tx.gasprice(gas));
+            }
+        }"##,
+    );
+
+    let (object, abi) = res.last().unwrap();
+
+    let code = link(object);
+
+    let mut host = MockHost {
+        abi: ethabi::Contract::load(abi.as_bytes()).unwrap(),
+        memory: MemoryInstance::alloc(Pages(2), None).unwrap(),
+        allocations: HashMap::new(),
+        storage: HashMap::new(),
+        output: Vec::new(),
+    };
+
+    let module = wasmi::Module::from_buffer(&code).expect("parse wasm should work");
+
+    let module = ModuleInstance::new(&module, &ImportsBuilder::new().with_resolver("env", &host))
+        .expect("Failed to instantiate module")
+        .run_start(&mut NopExternals)
+        .expect("Failed to run start function in module");
+
+    if let Some(ExternVal::Memory(memory_ref)) = module.export_by_name("memory") {
+        host.memory = memory_ref;
+    }
+
+    let input = host
+        .abi
+        .constructor
+        .as_ref()
+        .unwrap()
+        .encode_input(Vec::new(), &[Token::Uint(ethereum_types::U256::from(102))])
+        .unwrap();
+
+    assert_eq!(host.invoke(&module, "solang_constructor", &input), 0);
+
+    host.function(
+        &module,
+        "inc",
+        &[Token::Uint(ethereum_types::U256::from(102))],
+    );
+
+    let returns = host.function(&module, "get", &[]);
+
+    assert_eq!(returns, vec![Token::Uint(ethereum_types::U256::from(204))]);
+
+    let returns = host.function(
+        &module,
+        "price",
+        &[Token::Uint(ethereum_types::U256::from(3))],
+    );
+
+    assert_eq!(
+        returns,
+        vec![
+            Token::Uint(ethereum_types::U256::from(7)),
+            Token::Uint(ethereum_types::U256::from(21))
+        ]
+    );
+
+    // an unknown function selector
+    assert_eq!(
+        host.invoke(&module, "solang_function", &[0xde, 0xad, 0xca, 0xfe]),
+        3
+    );
+}
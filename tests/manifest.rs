@@ -0,0 +1,161 @@
+use assert_cmd::Command;
+use std::fs;
+
+#[test]
+fn build_targets() {
+    let dir = tempfile::tempdir().unwrap();
+
+    fs::create_dir_all(dir.path().join("contracts/lib")).unwrap();
+    fs::create_dir_all(dir.path().join("src")).unwrap();
+
+    fs::write(
+        dir.path().join("solang.toml"),
+        r#"
+        sources = [ "contracts/*.sol" ]
+        import-paths = [ "contracts/lib" ]
+        remappings = [ "@lib/=contracts/lib/" ]
+
+        [[target]]
+        name = "evm"
+        output = "build/evm"
+        constant-folding = false
+
+        [[target]]
+        name = "evm"
+        output = "build/evm-overflow"
+        math-overflow = true
+        "#,
+    )
+    .unwrap();
+
+    fs::write(
+        dir.path().join("contracts/a.sol"),
+        r#"
+        import "@lib/b.sol";
+
+        contract a is b {
+            function f() public returns (bool) {
+                return flag;
+            }
+        }
+        "#,
+    )
+    .unwrap();
+
+    fs::write(
+        dir.path().join("contracts/lib/b.sol"),
+        "abstract contract b { bool flag; }",
+    )
+    .unwrap();
+
+    // the manifest is found in a parent directory
+    Command::cargo_bin("solang")
+        .unwrap()
+        .current_dir(dir.path().join("src"))
+        .arg("build")
+        .assert()
+        .success();
+
+    assert!(dir.path().join("build/evm/a.bin").exists());
+    assert!(dir.path().join("build/evm/a.abi").exists());
+    assert!(dir.path().join("build/evm-overflow/a.bin").exists());
+
+    // the output directory on the command line overrides the manifest, with a directory
+    // for each target named after its configured output directory
+    Command::cargo_bin("solang")
+        .unwrap()
+        .current_dir(dir.path())
+        .args(&["--output", "out"])
+        .assert()
+        .success();
+
+    assert!(dir.path().join("out/evm/a.bin").exists());
+    assert!(dir.path().join("out/evm-overflow/a.bin").exists());
+
+    // a target on the command line which is not in the manifest is built with the defaults
+    Command::cargo_bin("solang")
+        .unwrap()
+        .current_dir(dir.path())
+        .args(&["--target", "ewasm", "--emit", "cfg", "build"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn manifest_errors() {
+    let dir = tempfile::tempdir().unwrap();
+
+    Command::cargo_bin("solang")
+        .unwrap()
+        .current_dir(dir.path())
+        .assert()
+        .failure();
+
+    fs::write(
+        dir.path().join("solang.toml"),
+        r#"
+        sources = [ "*.sol" ]
+
+        [[target]]
+        name = "cobol"
+        "#,
+    )
+    .unwrap();
+
+    let assert = Command::cargo_bin("solang")
+        .unwrap()
+        .current_dir(dir.path())
+        .arg("build")
+        .assert()
+        .failure();
+
+    let stderr = String::from_utf8_lossy(&assert.get_output().stderr).to_string();
+
+    assert!(stderr.contains("unknown target ‘cobol’"));
+}
+
+#[test]
+fn shared_output_directory() {
+    let dir = tempfile::tempdir().unwrap();
+
+    fs::write(dir.path().join("a.sol"), "contract a {}").unwrap();
+
+    fs::write(
+        dir.path().join("solang.toml"),
+        r#"
+        sources = [ "*.sol" ]
+
+        [[target]]
+        name = "evm"
+
+        [[target]]
+        name = "evm"
+        math-overflow = true
+        "#,
+    )
+    .unwrap();
+
+    let assert = Command::cargo_bin("solang")
+        .unwrap()
+        .current_dir(dir.path())
+        .arg("build")
+        .assert()
+        .failure();
+
+    let stderr = String::from_utf8_lossy(&assert.get_output().stderr).to_string();
+
+    assert!(stderr.contains("targets ‘evm’ and ‘evm’ are both written to"));
+
+    // the same goes for the subdirectories of --output
+    let assert = Command::cargo_bin("solang")
+        .unwrap()
+        .current_dir(dir.path())
+        .args(&["--output", "out", "build"])
+        .assert()
+        .failure();
+
+    let stderr = String::from_utf8_lossy(&assert.get_output().stderr).to_string();
+
+    assert!(stderr.contains("are both written to"));
+    assert!(!dir.path().join("out").exists());
+}
@@ -0,0 +1,718 @@
+use ethabi::Token;
+use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
+use std::collections::HashMap;
+use tiny_keccak::{Hasher, Keccak};
+use wasmi::memory_units::Pages;
+use wasmi::*;
+
+use solang::file_cache::FileCache;
+use solang::sema::ast::Level;
+use solang::sema::diagnostics;
+use solang::{compile, Target};
+
+/// The event type used by solang to pass return data back to the caller
+const RETURN_EVENT: &str = "solang/return";
+
+struct TestRuntime {
+    abi: ethabi::Contract,
+    code: Vec<u8>,
+    memory: MemoryRef,
+    /// Lengths of all the pointers allocated by the host
+    pointers: HashMap<u32, u32>,
+    /// Collections created with create_collection(), keyed by their head pointer
+    collections: HashMap<u32, Vec<u32>>,
+    state: HashMap<String, Vec<u8>>,
+    output: Vec<u8>,
+}
+
+#[derive(FromPrimitive)]
+#[allow(non_camel_case_types)]
+enum Extern {
+    get_ptr_len = 1,
+    alloc,
+    create_collection,
+    add_to_collection,
+    get_state,
+    set_state,
+    delete_state,
+    add_event,
+    log_buffer,
+}
+
+impl TestRuntime {
+    /// Allocate memory on behalf of the contract, like sabre does
+    fn alloc(&mut self, len: u32) -> u32 {
+        let pages = (len as usize + 0xffff) / 0x10000;
+
+        let Pages(previous) = self
+            .memory
+            .grow(Pages(std::cmp::max(pages, 1)))
+            .expect("memory should grow");
+
+        let ptr = (previous * 0x10000) as u32;
+
+        self.pointers.insert(ptr, len);
+
+        ptr
+    }
+
+    /// Read the contents of a host allocated pointer
+    fn read_ptr(&self, ptr: u32) -> Vec<u8> {
+        let len = self.pointers[&ptr];
+
+        self.memory
+            .get(ptr, len as usize)
+            .expect("pointer should be in memory")
+    }
+
+    /// Copy the data into memory allocated by the host
+    fn write_ptr(&mut self, data: &[u8]) -> u32 {
+        let ptr = self.alloc(data.len() as u32);
+
+        self.memory.set(ptr, data).expect("memory should be set");
+
+        ptr
+    }
+
+    /// Get the state address for a collection
+    fn state_address(&self, head: u32) -> String {
+        let address = self.read_ptr(self.collections[&head][0]);
+
+        String::from_utf8(address).expect("state address should be hex")
+    }
+}
+
+impl Externals for TestRuntime {
+    fn invoke_index(
+        &mut self,
+        index: usize,
+        args: RuntimeArgs,
+    ) -> Result<Option<RuntimeValue>, Trap> {
+        match FromPrimitive::from_usize(index) {
+            Some(Extern::get_ptr_len) => {
+                let ptr: u32 = args.nth_checked(0)?;
+
+                let len = match self.pointers.get(&ptr) {
+                    Some(len) => *len as i32,
+                    None => -1,
+                };
+
+                Ok(Some(RuntimeValue::I32(len)))
+            }
+            Some(Extern::alloc) => {
+                let len: u32 = args.nth_checked(0)?;
+
+                Ok(Some(RuntimeValue::I32(self.alloc(len) as i32)))
+            }
+            Some(Extern::create_collection) => {
+                let head: u32 = args.nth_checked(0)?;
+
+                self.collections.insert(head, vec![head]);
+
+                Ok(Some(RuntimeValue::I32(head as i32)))
+            }
+            Some(Extern::add_to_collection) => {
+                let head: u32 = args.nth_checked(0)?;
+                let ptr: u32 = args.nth_checked(1)?;
+
+                self.collections
+                    .get_mut(&head)
+                    .expect("collection should exist")
+                    .push(ptr);
+
+                Ok(Some(RuntimeValue::I32(head as i32)))
+            }
+            Some(Extern::get_state) => {
+                let head: u32 = args.nth_checked(0)?;
+
+                let address = self.state_address(head);
+
+                println!("get_state {}", address);
+
+                let ptr = match self.state.get(&address).cloned() {
+                    Some(data) => self.write_ptr(&data),
+                    None => 0,
+                };
+
+                Ok(Some(RuntimeValue::I32(ptr as i32)))
+            }
+            Some(Extern::set_state) => {
+                let head: u32 = args.nth_checked(0)?;
+
+                let address = self.state_address(head);
+                let data = self.read_ptr(self.collections[&head][1]);
+
+                println!("set_state {} {}", address, hex::encode(&data));
+
+                self.state.insert(address, data);
+
+                Ok(Some(RuntimeValue::I32(head as i32)))
+            }
+            Some(Extern::delete_state) => {
+                let head: u32 = args.nth_checked(0)?;
+
+                let address = self.state_address(head);
+
+                println!("delete_state {}", address);
+
+                self.state.remove(&address);
+
+                Ok(Some(RuntimeValue::I32(head as i32)))
+            }
+            Some(Extern::add_event) => {
+                let event_type: u32 = args.nth_checked(0)?;
+                let data: u32 = args.nth_checked(2)?;
+
+                let event_type = String::from_utf8(self.read_ptr(event_type)).unwrap();
+
+                assert_eq!(event_type, RETURN_EVENT);
+
+                self.output = self.read_ptr(data);
+
+                Ok(Some(RuntimeValue::I32(0)))
+            }
+            Some(Extern::log_buffer) => {
+                let ptr: u32 = args.nth_checked(1)?;
+                let len: u32 = args.nth_checked(2)?;
+
+                let buf = self.memory.get(ptr, len as usize).unwrap();
+
+                println!("log_buffer: {}", String::from_utf8_lossy(&buf));
+
+                Ok(None)
+            }
+            _ => panic!("external {} unknown", index),
+        }
+    }
+}
+
+impl ModuleImportResolver for TestRuntime {
+    fn resolve_func(&self, field_name: &str, signature: &Signature) -> Result<FuncRef, Error> {
+        let index = match field_name {
+            "get_ptr_len" => Extern::get_ptr_len,
+            "alloc" => Extern::alloc,
+            "create_collection" => Extern::create_collection,
+            "add_to_collection" => Extern::add_to_collection,
+            "get_state" => Extern::get_state,
+            "set_state" => Extern::set_state,
+            "delete_state" => Extern::delete_state,
+            "add_event" => Extern::add_event,
+            "log_buffer" => Extern::log_buffer,
+            _ => {
+                panic!("{} not implemented", field_name);
+            }
+        };
+
+        Ok(FuncInstance::alloc_host(signature.clone(), index as usize))
+    }
+}
+
+impl TestRuntime {
+    /// Invoke the entrypoint with the given payload. Returns false if the contract failed
+    fn invoke(&mut self, payload: &[u8]) -> bool {
+        let module = Module::from_buffer(&self.code).expect("parse wasm should work");
+
+        let module =
+            ModuleInstance::new(&module, &ImportsBuilder::new().with_resolver("env", self))
+                .expect("Failed to instantiate module")
+                .run_start(&mut NopExternals)
+                .expect("Failed to run start function in module");
+
+        if let Some(ExternVal::Memory(memory_ref)) = module.export_by_name("memory") {
+            self.memory = memory_ref;
+        }
+
+        // pointers are only valid for a single invocation
+        self.pointers.clear();
+        self.collections.clear();
+        self.output.clear();
+
+        let payload = self.write_ptr(payload);
+        let signer = self.write_ptr(b"signer");
+        let signature = self.write_ptr(b"signature");
+
+        println!("PAYLOAD: {}", hex::encode(self.read_ptr(payload)));
+
+        match module.invoke_export(
+            "entrypoint",
+            &[
+                RuntimeValue::I32(payload as i32),
+                RuntimeValue::I32(signer as i32),
+                RuntimeValue::I32(signature as i32),
+            ],
+            self,
+        ) {
+            Ok(Some(RuntimeValue::I32(1))) => true,
+            Ok(ret) => panic!("entrypoint returns: {:?}", ret),
+            Err(wasmi::Error::Trap(trap)) => match trap.kind() {
+                TrapKind::Unreachable => false,
+                _ => panic!("fail to invoke entrypoint: {}", trap),
+            },
+            Err(e) => panic!("fail to invoke entrypoint: {}", e),
+        }
+    }
+
+    fn constructor(&mut self, args: &[Token]) {
+        let payload = if let Some(constructor) = &self.abi.constructor {
+            constructor.encode_input(Vec::new(), args).unwrap()
+        } else {
+            Vec::new()
+        };
+
+        assert!(self.invoke(&payload));
+    }
+
+    fn function(&mut self, name: &str, args: &[Token]) -> Vec<Token> {
+        let payload = match self.abi.functions[name][0].encode_input(args) {
+            Ok(n) => n,
+            Err(x) => panic!("{}", x),
+        };
+
+        if !self.invoke(&payload) {
+            panic!("function {} failed", name);
+        }
+
+        println!("RETURNDATA: {}", hex::encode(&self.output));
+
+        self.abi.functions[name][0]
+            .decode_output(&self.output)
+            .unwrap()
+    }
+
+    fn function_expect_failure(&mut self, name: &str, args: &[Token]) {
+        let payload = self.abi.functions[name][0].encode_input(args).unwrap();
+
+        assert!(!self.invoke(&payload));
+    }
+}
+
+fn build_solidity(src: &str) -> TestRuntime {
+    let mut cache = FileCache::new();
+
+    cache.set_file_contents("test.sol", src.to_string());
+
+    let (res, ns) = compile(
+        "test.sol",
+        &mut cache,
+        inkwell::OptimizationLevel::Default,
+        Target::Sabre,
+        false,
+    );
+
+    diagnostics::print_messages(&mut cache, &ns, false);
+
+    assert_eq!(res.is_empty(), false);
+
+    let (code, abi) = res.last().unwrap().clone();
+
+    TestRuntime {
+        abi: ethabi::Contract::load(abi.as_bytes()).unwrap(),
+        code,
+        memory: MemoryInstance::alloc(Pages(2), None).unwrap(),
+        pointers: HashMap::new(),
+        collections: HashMap::new(),
+        state: HashMap::new(),
+        output: Vec::new(),
+    }
+}
+
+#[test]
+fn return_values() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            uint64 count = 102;
+
+            function get() public view returns (uint64, bool) {
+                return (count, count > 100);
+            }
+
+            function inc(uint64 by) public {
+                count += by;
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    let returns = runtime.function("get", &[]);
+
+    assert_eq!(
+        returns,
+        vec![
+            Token::Uint(ethereum_types::U256::from(102)),
+            Token::Bool(true)
+        ]
+    );
+
+    runtime.function("inc", &[Token::Uint(ethereum_types::U256::from(5))]);
+
+    let returns = runtime.function("get", &[]);
+
+    assert_eq!(
+        returns,
+        vec![
+            Token::Uint(ethereum_types::U256::from(107)),
+            Token::Bool(true)
+        ]
+    );
+}
+
+#[test]
+fn string_storage() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            string s;
+
+            function set(string v) public {
+                s = v;
+            }
+
+            function get() public view returns (string) {
+                return s;
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    let returns = runtime.function("get", &[]);
+
+    assert_eq!(returns, vec![Token::String(String::new())]);
+
+    runtime.function("set", &[Token::String(String::from("Hello, World!"))]);
+
+    let returns = runtime.function("get", &[]);
+
+    assert_eq!(returns, vec![Token::String(String::from("Hello, World!"))]);
+
+    // setting an empty string removes the state
+    runtime.function("set", &[Token::String(String::new())]);
+
+    assert!(runtime.state.is_empty());
+
+    let returns = runtime.function("get", &[]);
+
+    assert_eq!(returns, vec![Token::String(String::new())]);
+}
+
+#[test]
+fn bytes_storage() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            bytes bs = hex"0e0d0a";
+
+            function get() public view returns (bytes) {
+                return bs;
+            }
+
+            function len() public view returns (uint32) {
+                return bs.length;
+            }
+
+            function get_index(uint32 index) public view returns (bytes1) {
+                return bs[index];
+            }
+
+            function set_index(uint32 index, bytes1 value) public {
+                bs[index] = value;
+            }
+
+            function push(bytes1 value) public {
+                bs.push(value);
+            }
+
+            function pop() public returns (bytes1) {
+                return bs.pop();
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    let returns = runtime.function("get", &[]);
+
+    assert_eq!(returns, vec![Token::Bytes(vec![0x0e, 0x0d, 0x0a])]);
+
+    let returns = runtime.function("len", &[]);
+
+    assert_eq!(returns, vec![Token::Uint(ethereum_types::U256::from(3))]);
+
+    let returns = runtime.function("get_index", &[Token::Uint(ethereum_types::U256::from(1))]);
+
+    assert_eq!(returns, vec![Token::FixedBytes(vec![0x0d])]);
+
+    runtime.function(
+        "set_index",
+        &[
+            Token::Uint(ethereum_types::U256::from(2)),
+            Token::FixedBytes(vec![0xff]),
+        ],
+    );
+
+    runtime.function("push", &[Token::FixedBytes(vec![0x41])]);
+
+    let returns = runtime.function("get", &[]);
+
+    assert_eq!(returns, vec![Token::Bytes(vec![0x0e, 0x0d, 0xff, 0x41])]);
+
+    runtime.function_expect_failure("get_index", &[Token::Uint(ethereum_types::U256::from(4))]);
+
+    runtime.function_expect_failure(
+        "set_index",
+        &[
+            Token::Uint(ethereum_types::U256::from(4)),
+            Token::FixedBytes(vec![0x01]),
+        ],
+    );
+
+    for expected in &[0x41u8, 0xff, 0x0d, 0x0e] {
+        let returns = runtime.function("pop", &[]);
+
+        assert_eq!(returns, vec![Token::FixedBytes(vec![*expected])]);
+    }
+
+    let returns = runtime.function("len", &[]);
+
+    assert_eq!(returns, vec![Token::Uint(ethereum_types::U256::from(0))]);
+
+    // popping from empty bytes fails
+    runtime.function_expect_failure("pop", &[]);
+}
+
+#[test]
+fn dynamic_array_storage() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            uint[] arr;
+
+            function len() public view returns (uint) {
+                return arr.length;
+            }
+
+            function get(uint index) public view returns (uint) {
+                return arr[index];
+            }
+
+            function push(uint value) public {
+                arr.push(value);
+            }
+
+            function pop() public returns (uint) {
+                return arr.pop();
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    let returns = runtime.function("len", &[]);
+
+    assert_eq!(returns, vec![Token::Uint(ethereum_types::U256::from(0))]);
+
+    for value in &[102u64, 7, 0xdead] {
+        runtime.function("push", &[Token::Uint(ethereum_types::U256::from(*value))]);
+    }
+
+    let returns = runtime.function("len", &[]);
+
+    assert_eq!(returns, vec![Token::Uint(ethereum_types::U256::from(3))]);
+
+    let returns = runtime.function("get", &[Token::Uint(ethereum_types::U256::from(1))]);
+
+    assert_eq!(returns, vec![Token::Uint(ethereum_types::U256::from(7))]);
+
+    runtime.function_expect_failure("get", &[Token::Uint(ethereum_types::U256::from(3))]);
+
+    for expected in &[0xdeadu64, 7, 102] {
+        let returns = runtime.function("pop", &[]);
+
+        assert_eq!(
+            returns,
+            vec![Token::Uint(ethereum_types::U256::from(*expected))]
+        );
+    }
+
+    let returns = runtime.function("len", &[]);
+
+    assert_eq!(returns, vec![Token::Uint(ethereum_types::U256::from(0))]);
+
+    // popping from an empty array fails
+    runtime.function_expect_failure("pop", &[]);
+}
+
+#[test]
+fn external_function_array_storage() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            function() external returns (uint64)[] fns;
+
+            function one() public returns (uint64) {
+                return 1;
+            }
+
+            function two() public returns (uint64) {
+                return 2;
+            }
+
+            function len() public view returns (uint) {
+                return fns.length;
+            }
+
+            function get(uint index) public view returns (bytes4) {
+                return fns[index].selector;
+            }
+
+            function push(bool first) public {
+                if (first) {
+                    fns.push(this.one);
+                } else {
+                    fns.push(this.two);
+                }
+            }
+
+            function pop() public returns (bytes4) {
+                function() external returns (uint64) f = fns.pop();
+
+                return f.selector;
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    let one = runtime.abi.functions["one"][0].short_signature().to_vec();
+    let two = runtime.abi.functions["two"][0].short_signature().to_vec();
+
+    runtime.function("push", &[Token::Bool(true)]);
+    runtime.function("push", &[Token::Bool(false)]);
+
+    let returns = runtime.function("len", &[]);
+
+    assert_eq!(returns, vec![Token::Uint(ethereum_types::U256::from(2))]);
+
+    let returns = runtime.function("get", &[Token::Uint(ethereum_types::U256::from(0))]);
+
+    assert_eq!(returns, vec![Token::FixedBytes(one.clone())]);
+
+    let returns = runtime.function("get", &[Token::Uint(ethereum_types::U256::from(1))]);
+
+    assert_eq!(returns, vec![Token::FixedBytes(two.clone())]);
+
+    let returns = runtime.function("pop", &[]);
+
+    assert_eq!(returns, vec![Token::FixedBytes(two)]);
+
+    let returns = runtime.function("pop", &[]);
+
+    assert_eq!(returns, vec![Token::FixedBytes(one)]);
+
+    let returns = runtime.function("len", &[]);
+
+    assert_eq!(returns, vec![Token::Uint(ethereum_types::U256::from(0))]);
+
+    runtime.function_expect_failure("pop", &[]);
+}
+
+#[test]
+fn keccak256_and_abi_encode() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            function hash(bytes memory data) public pure returns (bytes32) {
+                return keccak256(data);
+            }
+
+            function encode(uint64 a, bool b) public pure returns (bytes memory) {
+                return abi.encode(a, b);
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    let returns = runtime.function("hash", &[Token::Bytes(b"sabre".to_vec())]);
+
+    let mut hasher = Keccak::v256();
+    let mut hash = [0u8; 32];
+    hasher.update(b"sabre");
+    hasher.finalize(&mut hash);
+
+    assert_eq!(returns, vec![Token::FixedBytes(hash.to_vec())]);
+
+    let returns = runtime.function(
+        "encode",
+        &[
+            Token::Uint(ethereum_types::U256::from(7)),
+            Token::Bool(true),
+        ],
+    );
+
+    assert_eq!(
+        returns,
+        vec![Token::Bytes(ethabi::encode(&[
+            Token::Uint(ethereum_types::U256::from(7)),
+            Token::Bool(true)
+        ]))]
+    );
+}
+
+#[test]
+fn not_supported() {
+    let errors = |src: &str| {
+        let mut cache = FileCache::new();
+
+        cache.set_file_contents("test.sol", src.to_string());
+
+        let ns = solang::parse_and_resolve("test.sol", &mut cache, Target::Sabre);
+
+        ns.diagnostics
+            .into_iter()
+            .filter(|d| d.level == Level::Error)
+            .map(|d| d.message)
+            .collect::<Vec<String>>()
+    };
+
+    assert_eq!(
+        errors(
+            r##"
+            contract c {
+                event Counted(uint64 count);
+
+                function count(uint64 n) public {
+                    emit Counted(n);
+                }
+            }"##
+        ),
+        vec!["events not supported on Sawtooth Sabre"]
+    );
+
+    assert_eq!(
+        errors(
+            r##"
+            contract c {
+                function hash(bytes memory data) public pure returns (bytes32) {
+                    return sha256(data);
+                }
+            }"##
+        ),
+        vec!["‘sha256’ not supported on Sawtooth Sabre"]
+    );
+
+    assert_eq!(
+        errors(
+            r##"
+            contract c {
+                function when() public view returns (uint64) {
+                    return block.timestamp;
+                }
+            }"##
+        ),
+        vec!["‘block.timestamp’ not supported on Sawtooth Sabre"]
+    );
+}
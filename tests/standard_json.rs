@@ -0,0 +1,174 @@
+use assert_cmd::Command;
+use serde_json::{json, Value};
+
+fn compile(target: &str, input: Value) -> Value {
+    let mut cmd = Command::cargo_bin("solang").unwrap();
+
+    let assert = cmd
+        .args(&["--standard-json", "--target", target])
+        .write_stdin(input.to_string())
+        .assert()
+        .success();
+
+    serde_json::from_slice(&assert.get_output().stdout).unwrap()
+}
+
+#[test]
+fn inline_sources() {
+    let output = compile(
+        "evm",
+        json!({
+            "language": "Solidity",
+            "sources": {
+                "contracts/a.sol": {
+                    "content": r#"
+                        import "./lib/b.sol";
+
+                        /// @title A counter
+                        contract a is b {
+                            uint64 count;
+
+                            /// @dev add to the counter
+                            /// @param n how much to add
+                            function add(uint64 n) public {
+                                count += n;
+                            }
+                        }"#
+                },
+                "contracts/lib/b.sol": {
+                    "content": "abstract contract b { bool flag; }"
+                }
+            },
+            "settings": {
+                "outputSelection": {
+                    "*": {
+                        "*": [ "abi", "evm.bytecode.object", "storageLayout", "devdoc" ]
+                    }
+                }
+            }
+        }),
+    );
+
+    assert_eq!(output["errors"], json!([]));
+    assert_eq!(output["sources"]["contracts/a.sol"]["id"], 0);
+
+    let a = &output["contracts"]["contracts/a.sol"]["a"];
+
+    assert_eq!(a["abi"][0]["name"], "add");
+    assert!(a["evm"]["bytecode"]["object"].as_str().unwrap().len() > 2);
+    assert_eq!(a["devdoc"]["title"], "A counter");
+    assert_eq!(
+        a["devdoc"]["methods"]["add(uint64)"],
+        json!({ "details": "add to the counter", "params": { "n": "how much to add" } })
+    );
+
+    let storage = a["storageLayout"]["storage"].as_array().unwrap();
+
+    assert_eq!(storage.len(), 2);
+    assert_eq!(storage[0]["label"], "flag");
+    assert_eq!(storage[0]["contract"], "contracts/lib/b.sol:b");
+    assert_eq!(storage[1]["label"], "count");
+    assert_eq!(storage[1]["slot"], "1");
+    assert_eq!(
+        a["storageLayout"]["types"][storage[1]["type"].as_str().unwrap()]["label"],
+        "uint64"
+    );
+
+    // imported contracts are listed under their own source, and abstract contracts have no code
+    let b = &output["contracts"]["contracts/lib/b.sol"]["b"];
+
+    assert!(output["contracts"]["contracts/a.sol"].get("b").is_none());
+    assert!(b.get("evm").is_none());
+    assert!(b.get("abi").is_some());
+}
+
+#[test]
+fn output_selection() {
+    let output = compile(
+        "evm",
+        json!({
+            "sources": {
+                "a.sol": { "content": "contract a { function f() public {} }" },
+                "b.sol": { "content": "contract b { function g() public {} }" }
+            },
+            "settings": {
+                "outputSelection": {
+                    "a.sol": { "a": [ "abi" ] }
+                }
+            }
+        }),
+    );
+
+    let a = &output["contracts"]["a.sol"]["a"];
+
+    assert!(a.get("abi").is_some());
+    assert!(a.get("evm").is_none());
+    assert!(a.get("metadata").is_none());
+    assert_eq!(output["contracts"]["b.sol"], json!({}));
+}
+
+#[test]
+fn input_errors() {
+    let output = compile("evm", json!({ "sources": { "a.sol": { "urls": [] } } }));
+
+    assert_eq!(output["errors"][0]["type"], "IOError");
+    assert_eq!(output["errors"][0]["message"], "cannot read source ‘a.sol’");
+
+    let output = compile(
+        "evm",
+        json!({
+            "sources": { "a.sol": { "content": "contract a { b x; }" } },
+            "settings": { "outputSelection": { "*": { "*": [ "abi" ] } } }
+        }),
+    );
+
+    assert_eq!(output["errors"][0]["severity"], "error");
+    assert_eq!(output["errors"][0]["sourceLocation"]["file"], "a.sol");
+    assert_eq!(output["contracts"], json!({}));
+}
+
+#[test]
+fn source_ast() {
+    let output = compile(
+        "evm",
+        json!({
+            "sources": {
+                "a.sol": { "content": "import \"b.sol\";\ncontract a is b { function f() public { g(); } }" },
+                "b.sol": { "content": "contract b { function g() internal {} }" }
+            },
+            "settings": {
+                "outputSelection": {
+                    "*": { "": [ "ast" ], "*": [ "abi" ] }
+                }
+            }
+        }),
+    );
+
+    assert_eq!(output["errors"], json!([]));
+
+    let a = &output["sources"]["a.sol"]["ast"];
+    let b = &output["sources"]["b.sol"]["ast"];
+
+    assert_eq!(a["nodeType"], "SourceUnit");
+    assert_eq!(a["absolutePath"], "a.sol");
+    assert_eq!(a["nodes"][0]["src"], "16:15:0");
+    assert_eq!(b["nodes"][0]["src"], "0:10:1");
+
+    // declarations in imported files have the same id in every ast
+    let contract_b = &b["nodes"][0];
+    let g = &contract_b["nodes"][0];
+
+    assert_eq!(
+        a["nodes"][0]["baseContracts"][0]["baseName"]["referencedDeclaration"],
+        contract_b["id"]
+    );
+
+    let call = &a["nodes"][0]["nodes"][0]["body"]["statements"][0]["expression"];
+
+    assert_eq!(call["nodeType"], "FunctionCall");
+    assert_eq!(call["expression"]["name"], "g");
+    assert_eq!(call["expression"]["referencedDeclaration"], g["id"]);
+
+    // contract outputs do not include the ast
+    assert!(output["contracts"]["a.sol"]["a"].get("ast").is_none());
+}
@@ -0,0 +1,263 @@
+use assert_cmd::Command;
+use serde_json::Value;
+use std::fs;
+
+const CONTRACTS: &str = r#"
+contract base {
+    bool flag;
+}
+
+contract token is base {
+    struct Account {
+        uint64 balance;
+        address[] delegates;
+    }
+
+    mapping(address => Account) accounts;
+    int32[4] history;
+}
+"#;
+
+#[test]
+fn emit_storage_layout() {
+    let dir = tempfile::tempdir().unwrap();
+
+    fs::write(dir.path().join("token.sol"), CONTRACTS).unwrap();
+
+    Command::cargo_bin("solang")
+        .unwrap()
+        .current_dir(dir.path())
+        .args(&[
+            "--target",
+            "substrate",
+            "--emit",
+            "storage-layout",
+            "token.sol",
+        ])
+        .assert()
+        .success();
+
+    let layout: Value =
+        serde_json::from_slice(&fs::read(dir.path().join("token.storage.json")).unwrap()).unwrap();
+
+    let storage = layout["storage"].as_array().unwrap();
+
+    assert_eq!(storage.len(), 3);
+    assert_eq!(storage[0]["label"], "flag");
+    assert_eq!(storage[0]["contract"], "token.sol:base");
+    assert_eq!(storage[0]["slot"], "0");
+    assert_eq!(storage[1]["label"], "accounts");
+    assert_eq!(storage[1]["contract"], "token.sol:token");
+    assert_eq!(storage[1]["slot"], "1");
+    assert_eq!(storage[2]["label"], "history");
+    assert_eq!(storage[2]["slot"], "2");
+
+    let types = &layout["types"];
+
+    let mapping = &types[storage[1]["type"].as_str().unwrap()];
+
+    assert_eq!(mapping["encoding"], "mapping");
+    assert_eq!(types[mapping["key"].as_str().unwrap()]["label"], "address");
+
+    let account = &types[mapping["value"].as_str().unwrap()];
+
+    assert_eq!(account["label"], "struct token.Account");
+    assert_eq!(account["members"][1]["label"], "delegates");
+    assert_eq!(account["members"][1]["slot"], "1");
+    assert_eq!(
+        types[account["members"][1]["type"].as_str().unwrap()]["encoding"],
+        "dynamic_array"
+    );
+
+    let history = &types[storage[2]["type"].as_str().unwrap()];
+
+    assert_eq!(history["numberOfBytes"], "128");
+    assert_eq!(types[history["base"].as_str().unwrap()]["label"], "int32");
+    assert!(layout.get("accountDataSize").is_none());
+}
+
+#[test]
+fn type_ids() {
+    let dir = tempfile::tempdir().unwrap();
+
+    fs::write(
+        dir.path().join("ids.sol"),
+        r#"
+        contract ids {
+            uint256[2] a;
+            uint256[2][] b;
+            mapping(address => uint256) c;
+            mapping(address => uint256[]) d;
+        }"#,
+    )
+    .unwrap();
+
+    Command::cargo_bin("solang")
+        .unwrap()
+        .current_dir(dir.path())
+        .args(&[
+            "--target",
+            "substrate",
+            "--emit",
+            "storage-layout",
+            "ids.sol",
+        ])
+        .assert()
+        .success();
+
+    let layout: Value =
+        serde_json::from_slice(&fs::read(dir.path().join("ids.storage.json")).unwrap()).unwrap();
+
+    let ids: Vec<&str> = layout["storage"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entry| entry["type"].as_str().unwrap())
+        .collect();
+
+    assert_eq!(
+        ids,
+        vec![
+            "t_array(t_uint256)2_storage",
+            "t_array(t_array(t_uint256)2_storage)dyn_storage",
+            "t_mapping(t_address,t_uint256)",
+            "t_mapping(t_address,t_array(t_uint256)dyn_storage)",
+        ]
+    );
+
+    let types = &layout["types"];
+
+    assert_eq!(types[ids[0]]["label"], "uint256[2]");
+    assert_eq!(types[ids[1]]["label"], "uint256[2][]");
+    assert_eq!(types[ids[2]]["label"], "mapping(address => uint256)");
+    assert_eq!(types[ids[3]]["label"], "mapping(address => uint256[])");
+}
+
+fn check_upgrade(dir: &std::path::Path, args: &[&str], success: bool) -> String {
+    let mut cmd = Command::cargo_bin("solang").unwrap();
+
+    cmd.current_dir(dir)
+        .args(&["--target", "substrate", "check-upgrade"])
+        .args(args);
+
+    let assert = if success {
+        cmd.assert().success()
+    } else {
+        cmd.assert().failure()
+    };
+
+    String::from_utf8_lossy(&assert.get_output().stderr).to_string()
+}
+
+#[test]
+fn upgrade() {
+    let dir = tempfile::tempdir().unwrap();
+
+    fs::create_dir(dir.path().join("v1")).unwrap();
+    fs::write(dir.path().join("v1/token.sol"), CONTRACTS).unwrap();
+
+    // appending a variable to the most derived contract is fine
+    fs::write(
+        dir.path().join("token.sol"),
+        CONTRACTS.replace("int32[4] history;", "int32[4] history;\n    string name;"),
+    )
+    .unwrap();
+
+    let stderr = check_upgrade(dir.path(), &["--previous", "v1", "token.sol"], true);
+
+    assert!(stderr.contains("variable ‘name’ of ‘token’ is appended in slot 6"));
+
+    // a new variable in a base contract moves all the variables of the derived contract
+    fs::write(
+        dir.path().join("token.sol"),
+        CONTRACTS
+            .replace("bool flag;", "bool flag;\n    uint64 count;")
+            .replace("int32[4] history;", "int64[4] history;"),
+    )
+    .unwrap();
+
+    let stderr = check_upgrade(
+        dir.path(),
+        &["--previous", "v1/token.sol", "token.sol"],
+        false,
+    );
+
+    assert!(
+        stderr.contains("error[S2003]: variable ‘accounts’ of ‘token’ moved from slot 1 to slot 2")
+    );
+    assert!(stderr.contains(
+        "error[S2003]: variable ‘count’ of ‘base’ is inserted in slot 1 before existing variables"
+    ));
+
+    // compare with a saved layout, where only the type changed
+    Command::cargo_bin("solang")
+        .unwrap()
+        .current_dir(dir.path())
+        .args(&[
+            "--target",
+            "substrate",
+            "--emit",
+            "storage-layout",
+            "--output",
+            "v1",
+            "v1/token.sol",
+        ])
+        .assert()
+        .success();
+
+    fs::write(
+        dir.path().join("token.sol"),
+        CONTRACTS
+            .replace("int32[4] history;", "int32[2] history;")
+            .replace("uint64 balance;", "uint32 balance;"),
+    )
+    .unwrap();
+
+    let stderr = check_upgrade(
+        dir.path(),
+        &["--previous", "v1/token.storage.json", "token.sol"],
+        false,
+    );
+
+    assert!(stderr.contains(
+        "variable ‘history’ of ‘token’: type shrunk from ‘int32[4]’ of 128 bytes to ‘int32[2]’ of 64 bytes"
+    ));
+    assert!(stderr.contains("variable ‘accounts’ of ‘token’: mapping value field ‘balance’ of ‘struct token.Account’: type changed from ‘uint64’ to ‘uint32’"));
+}
+
+#[test]
+fn upgrade_recursive_struct() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let v1 = r#"
+        contract tree {
+            struct Node {
+                uint64 value;
+                mapping(uint64 => Node) kids;
+            }
+
+            Node root;
+        }"#;
+
+    fs::create_dir(dir.path().join("v1")).unwrap();
+    fs::write(dir.path().join("v1/tree.sol"), v1).unwrap();
+    fs::write(dir.path().join("tree.sol"), v1).unwrap();
+
+    check_upgrade(dir.path(), &["--previous", "v1/tree.sol", "tree.sol"], true);
+
+    fs::write(
+        dir.path().join("tree.sol"),
+        v1.replace("uint64 value;", "uint32 value;"),
+    )
+    .unwrap();
+
+    let stderr = check_upgrade(
+        dir.path(),
+        &["--previous", "v1/tree.sol", "tree.sol"],
+        false,
+    );
+
+    assert!(stderr.contains(
+        "variable ‘root’ of ‘tree’: field ‘value’ of ‘struct tree.Node’: type changed from ‘uint64’ to ‘uint32’"
+    ));
+}
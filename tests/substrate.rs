@@ -620,7 +620,14 @@ impl Externals for TestRuntime {
 
                 let mut address = [0u8; 32];
 
-                let hash_data: Vec<u8> = input.iter().chain(salt.iter()).cloned().collect();
+                let hash_data: Vec<u8> = self
+                    .vm
+                    .address
+                    .iter()
+                    .chain(codehash.iter())
+                    .chain(salt.iter())
+                    .cloned()
+                    .collect();
 
                 address
                     .copy_from_slice(blake2_rfc::blake2b::blake2b(32, &[], &hash_data).as_bytes());
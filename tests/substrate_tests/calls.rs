@@ -172,6 +172,33 @@ fn contract_already_exists() {
     runtime.function("test", Vec::new());
 }
 
+#[test]
+fn predict_address() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            function test() public {
+                bytes32 codehash = blake2_256(type(other).creationCode);
+
+                address predicted = predict_address(codehash, 102);
+
+                other o = new other{salt: 102}();
+
+                assert(address(o) == predicted);
+                assert(predicted != predict_address(codehash, 103));
+            }
+        }
+
+        contract other {
+            function test() public {
+
+            }
+        }"##,
+    );
+
+    runtime.function("test", Vec::new());
+}
+
 #[test]
 fn try_catch_external_calls() {
     let ns = parse_and_resolve(
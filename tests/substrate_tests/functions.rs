@@ -2,6 +2,8 @@ use parity_scale_codec::Encode;
 use parity_scale_codec_derive::{Decode, Encode};
 
 use crate::{build_solidity, first_error, first_warning, no_warnings_errors, parse_and_resolve};
+use solang::file_cache::FileCache;
+use solang::sema::diagnostics;
 use solang::Target;
 
 #[test]
@@ -353,6 +355,106 @@ fn mutability() {
     );
 }
 
+#[test]
+fn diagnostic_fixes() {
+    let mut cache = FileCache::new();
+
+    cache.set_file_contents(
+        "test.sol",
+        r#"pragma foo bar;
+        contract test {
+            int64 state = 1;
+
+            function foo() public returns (int64) {
+                return state;
+            }
+
+            function bar() public view returns (int64) {
+                return 102;
+            }
+
+            function baz(int64 a) public pure returns (int32) {
+                return a;
+            }
+        }"#
+        .to_string(),
+    );
+
+    let ns = solang::parse_and_resolve("test.sol", &mut cache, Target::Substrate);
+
+    assert!(ns
+        .diagnostics
+        .iter()
+        .any(|diag| diag.message == "implicit conversion would truncate from int64 to int32"));
+
+    let files = diagnostics::apply_fixes(&mut cache, &ns);
+
+    assert_eq!(files.len(), 1);
+    assert_eq!(
+        files[0].1,
+        r#"
+        contract test {
+            int64 state = 1;
+
+            function foo() public view returns (int64) {
+                return state;
+            }
+
+            function bar() public pure returns (int64) {
+                return 102;
+            }
+
+            function baz(int64 a) public pure returns (int32) {
+                return int32(a);
+            }
+        }"#
+    );
+}
+
+#[test]
+fn diagnostic_fixes_imported_file() {
+    let mut cache = FileCache::new();
+
+    cache.set_file_contents(
+        "lib.sol",
+        r#"
+        contract lib {
+            function bar() public view returns (int64) {
+                return 102;
+            }
+        }"#
+        .to_string(),
+    );
+
+    cache.set_file_contents(
+        "test.sol",
+        r#"import "lib.sol";
+        contract test is lib {
+            function baz() public view returns (int64) {
+                return 1;
+            }
+        }"#
+        .to_string(),
+    );
+
+    let ns = solang::parse_and_resolve("test.sol", &mut cache, Target::Substrate);
+
+    // both files have a function which can be declared pure, but only the input file changes
+    let files = diagnostics::apply_fixes(&mut cache, &ns);
+
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].0, ns.files[0]);
+    assert_eq!(
+        files[0].1,
+        r#"import "lib.sol";
+        contract test is lib {
+            function baz() public pure returns (int64) {
+                return 1;
+            }
+        }"#
+    );
+}
+
 #[test]
 fn shadowing() {
     #[derive(Debug, PartialEq, Encode, Decode)]
@@ -679,3 +679,220 @@ fn import_base_dir() {
 
     no_errors(ns.diagnostics);
 }
+
+#[test]
+fn remapped_import() {
+    let mut cache = FileCache::new();
+
+    cache.add_remapping("@openzeppelin/=lib/openzeppelin/".parse().unwrap());
+
+    cache.set_file_contents(
+        "a.sol",
+        r#"
+        import "@openzeppelin/token/b.sol";
+
+        contract foo {
+            enum_b bar;
+        }
+        "#
+        .to_string(),
+    );
+
+    cache.set_file_contents(
+        "lib/openzeppelin/token/b.sol",
+        r#"
+        enum enum_b { b1 }
+        "#
+        .to_string(),
+    );
+
+    let ns = solang::parse_and_resolve("a.sol", &mut cache, Target::Substrate);
+
+    no_errors(ns.diagnostics);
+}
+
+#[test]
+fn remapped_import_context() {
+    let mut cache = FileCache::new();
+
+    cache.add_remapping("@openzeppelin/=lib/openzeppelin/".parse().unwrap());
+    cache.add_remapping(
+        "lib/old:@openzeppelin/=lib/openzeppelin-v3/"
+            .parse()
+            .unwrap(),
+    );
+
+    cache.set_file_contents(
+        "a.sol",
+        r#"
+        import "@openzeppelin/token/b.sol";
+        import "lib/old/c.sol";
+
+        contract foo {
+            enum_b bar;
+            enum_c baz;
+        }
+        "#
+        .to_string(),
+    );
+
+    cache.set_file_contents(
+        "lib/openzeppelin/token/b.sol",
+        r#"
+        enum enum_b { b1 }
+        "#
+        .to_string(),
+    );
+
+    cache.set_file_contents(
+        "lib/old/c.sol",
+        r#"
+        import "@openzeppelin/token/b.sol";
+
+        enum enum_c { c1 }
+        "#
+        .to_string(),
+    );
+
+    let ns = solang::parse_and_resolve("a.sol", &mut cache, Target::Substrate);
+
+    assert_eq!(
+        first_error(ns.diagnostics),
+        "file not found ‘lib/openzeppelin-v3/token/b.sol’, import ‘@openzeppelin/token/b.sol’ was remapped by ‘lib/old:@openzeppelin/=lib/openzeppelin-v3/’"
+    );
+
+    cache.set_file_contents(
+        "lib/openzeppelin-v3/token/b.sol",
+        r#"
+        enum enum_b { b3 }
+        "#
+        .to_string(),
+    );
+
+    let ns = solang::parse_and_resolve("a.sol", &mut cache, Target::Substrate);
+
+    no_errors(ns.diagnostics);
+
+    assert_eq!(
+        "context:prefix".parse::<solang::file_cache::Remapping>(),
+        Err(String::from(
+            "remapping ‘context:prefix’ should be of the form [context:]prefix=target"
+        ))
+    );
+}
+
+#[test]
+fn reuse_parse_trees() {
+    let files = |a: &str| {
+        let mut cache = FileCache::new();
+
+        cache.set_file_contents("a.sol", a.to_string());
+
+        cache.set_file_contents(
+            "b.sol",
+            r#"
+            enum enum_b { b1 }
+            "#
+            .to_string(),
+        );
+
+        cache
+    };
+
+    let mut cache = files(
+        r#"
+        import "b.sol";
+
+        contract foo {
+            enum_b bar;
+        }
+        "#,
+    );
+
+    let ns = solang::parse_and_resolve("a.sol", &mut cache, Target::Substrate);
+
+    no_errors(ns.diagnostics);
+
+    let trees = cache.take_parse_trees();
+
+    assert_eq!(trees.len(), 2);
+
+    // only the changed file is parsed again
+    let mut cache = files(
+        r#"
+        import "b.sol";
+
+        contract foo {
+            enum_c bar;
+        }
+        "#,
+    );
+
+    cache.set_parse_trees(trees.clone());
+
+    let ns = solang::parse_and_resolve("a.sol", &mut cache, Target::Substrate);
+
+    assert_eq!(first_error(ns.diagnostics), "type ‘enum_c’ not found");
+
+    let new_trees = cache.take_parse_trees();
+
+    assert_eq!(new_trees.len(), 2);
+    assert_eq!(
+        new_trees
+            .iter()
+            .filter(|(key, tree)| trees
+                .get(key)
+                .map_or(false, |old| std::sync::Arc::ptr_eq(old, tree)))
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn cancelled_resolve() {
+    let files = || {
+        let mut cache = FileCache::new();
+
+        cache.set_file_contents(
+            "a.sol",
+            r#"
+            import "b.sol";
+
+            contract foo {
+                enum_b bar;
+            }
+            "#
+            .to_string(),
+        );
+
+        cache.set_file_contents(
+            "b.sol",
+            r#"
+            enum enum_b { b1 }
+            "#
+            .to_string(),
+        );
+
+        cache
+    };
+
+    let mut cache = files();
+
+    solang::parse_and_resolve("a.sol", &mut cache, Target::Substrate);
+
+    let trees = cache.take_parse_trees();
+
+    // once cancelled, nothing is parsed or resolved
+    let mut cache = files();
+    let cancel = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(true));
+
+    cache.set_cancel_flag(cancel);
+    cache.set_parse_trees(trees);
+
+    let ns = solang::parse_and_resolve("a.sol", &mut cache, Target::Substrate);
+
+    assert!(ns.contracts.is_empty());
+
+    // the trees which were not needed are kept for the next run
+    assert_eq!(cache.take_parse_trees().len(), 2);
+}
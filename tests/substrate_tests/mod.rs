@@ -18,6 +18,7 @@ mod libraries;
 mod loops;
 mod mappings;
 mod modifier;
+mod pragma;
 mod primitives;
 mod strings;
 mod structs;
@@ -0,0 +1,359 @@
+use crate::{build_solidity, first_error, no_errors, parse_and_resolve};
+use solang::sema::ast::LanguageVersion;
+use solang::Target;
+
+#[test]
+fn version_range() {
+    for src in &[
+        "pragma solidity 0; contract c {}",
+        "pragma solidity ^0.8.0; contract c {}",
+        "pragma solidity >=0.4.22 <0.6.0; contract c {}",
+        "pragma solidity >= 0.5.0 < 0.7.0; contract c {}",
+        "pragma solidity 0.7.x; contract c {}",
+        "pragma solidity ~0.6.12; contract c {}",
+        "pragma solidity ^0.9.0 || ^0.4; contract c {}",
+        "pragma solidity *; contract c {}",
+    ] {
+        let ns = parse_and_resolve(src, Target::Substrate);
+
+        no_errors(ns.diagnostics);
+    }
+
+    let ns = parse_and_resolve(
+        r##"
+        pragma solidity ^0.3.6;
+
+        contract c {}"##,
+        Target::Substrate,
+    );
+
+    assert_eq!(
+        first_error(ns.diagnostics),
+        "pragma ‘solidity’ with value ‘^0.3.6’ does not match Solidity 0.4 to 0.8, which Solang implements"
+    );
+
+    let ns = parse_and_resolve(
+        r##"
+        pragma solidity >0.8 <1.0.0;
+
+        contract c {}"##,
+        Target::Substrate,
+    );
+
+    assert_eq!(
+        first_error(ns.diagnostics),
+        "pragma ‘solidity’ with value ‘>0.8 <1.0.0’ does not match Solidity 0.4 to 0.8, which Solang implements"
+    );
+
+    let ns = parse_and_resolve(
+        r##"
+        pragma solidity >=0.4.x.1;
+
+        contract c {}"##,
+        Target::Substrate,
+    );
+
+    assert_eq!(
+        first_error(ns.diagnostics),
+        "pragma ‘solidity’: ‘0.4.x.1’ is not a valid version"
+    );
+
+    let ns = parse_and_resolve(
+        r##"
+        pragma solidity 0.0.18446744073709551615;
+
+        contract c {}"##,
+        Target::Substrate,
+    );
+
+    assert_eq!(
+        first_error(ns.diagnostics),
+        "pragma ‘solidity’: ‘0.0.18446744073709551615’ is not a valid version, the number is too large"
+    );
+
+    // every pragma must match
+    let ns = parse_and_resolve(
+        r##"
+        pragma solidity >=0.5.0;
+        pragma solidity <0.7.0;
+
+        contract c {}"##,
+        Target::Substrate,
+    );
+
+    assert_eq!(
+        ns.language_version(0),
+        Some(LanguageVersion { major: 0, minor: 6 })
+    );
+
+    no_errors(ns.diagnostics);
+
+    let ns = parse_and_resolve(
+        r##"
+        pragma solidity ^0.8.0;
+        pragma solidity ^0.5.0;
+
+        contract c {}"##,
+        Target::Substrate,
+    );
+
+    assert_eq!(
+        first_error(ns.diagnostics),
+        "pragma ‘solidity’ with value ‘^0.5.0’ conflicts with an earlier pragma ‘solidity’"
+    );
+}
+
+#[test]
+fn now_and_throw() {
+    let ns = parse_and_resolve(
+        r##"
+        pragma solidity ^0.4.24;
+
+        contract c {
+            function f(uint64 t) public view {
+                if (t > now) {
+                    throw;
+                }
+            }
+        }"##,
+        Target::Substrate,
+    );
+
+    no_errors(ns.diagnostics);
+
+    let ns = parse_and_resolve(
+        r##"
+        pragma solidity ^0.6.0;
+
+        contract c {
+            function f(uint64 t) public view {
+                if (t > now) {
+                    throw;
+                }
+            }
+        }"##,
+        Target::Substrate,
+    );
+
+    assert_eq!(
+        first_error(ns.diagnostics),
+        "‘throw’ was removed in Solidity 0.5, use ‘revert()’ instead"
+    );
+
+    let ns = parse_and_resolve(
+        r##"
+        pragma solidity ^0.7.0;
+
+        contract c {
+            function f() public view returns (uint64) {
+                return now;
+            }
+        }"##,
+        Target::Substrate,
+    );
+
+    assert_eq!(
+        first_error(ns.diagnostics),
+        "‘now’ was removed in Solidity 0.7, use ‘block.timestamp’ instead"
+    );
+
+    // without a version pragma, the latest version is assumed
+    let ns = parse_and_resolve(
+        r##"
+        contract c {
+            function f() public view returns (uint64) {
+                return now;
+            }
+        }"##,
+        Target::Substrate,
+    );
+
+    assert_eq!(
+        first_error(ns.diagnostics),
+        "‘now’ was removed in Solidity 0.7, use ‘block.timestamp’ instead"
+    );
+}
+
+#[test]
+fn address_payable() {
+    let ns = parse_and_resolve(
+        r##"
+        pragma solidity ^0.7.0;
+
+        contract c {
+            function f() public view returns (address payable) {
+                return msg.sender;
+            }
+        }"##,
+        Target::Substrate,
+    );
+
+    no_errors(ns.diagnostics);
+
+    let ns = parse_and_resolve(
+        r##"
+        pragma solidity ^0.8.0;
+
+        contract c {
+            function f() public view returns (address payable) {
+                return msg.sender;
+            }
+        }"##,
+        Target::Substrate,
+    );
+
+    assert_eq!(
+        first_error(ns.diagnostics),
+        "implicit conversion to address payable from address not allowed"
+    );
+
+    // without a version pragma, msg.sender remains payable
+    let ns = parse_and_resolve(
+        r##"
+        contract c {
+            function f() public view returns (address payable) {
+                return msg.sender;
+            }
+        }"##,
+        Target::Substrate,
+    );
+
+    no_errors(ns.diagnostics);
+
+    // before 0.5, every address is payable
+    let ns = parse_and_resolve(
+        r##"
+        pragma solidity ^0.4.24;
+
+        contract c {
+            function f(address a) public pure returns (address payable) {
+                return a;
+            }
+        }"##,
+        Target::Substrate,
+    );
+
+    no_errors(ns.diagnostics);
+}
+
+#[test]
+#[should_panic]
+fn checked_arithmetic() {
+    let mut runtime = build_solidity(
+        r##"
+        pragma solidity ^0.8.0;
+
+        contract overflow {
+            function foo(uint8 x) internal returns (uint8) {
+                return x + 1;
+            }
+
+            function bar() public {
+                foo(255);
+            }
+        }"##,
+    );
+
+    runtime.function("bar", Vec::new());
+}
+
+#[test]
+fn unchecked_arithmetic() {
+    let mut runtime = build_solidity(
+        r##"
+        pragma solidity ^0.7.0;
+
+        contract overflow {
+            function foo(uint8 x) internal returns (uint8) {
+                return x + 1;
+            }
+
+            function bar() public {
+                assert(foo(255) == 0);
+            }
+        }"##,
+    );
+
+    runtime.function("bar", Vec::new());
+}
+
+#[test]
+fn abicoder() {
+    let ns = parse_and_resolve(
+        r##"
+        pragma solidity ^0.7.0;
+
+        contract c {
+            struct S {
+                int32 f1;
+            }
+
+            function f(S memory s) public returns (int32) {
+                return s.f1;
+            }
+        }"##,
+        Target::Ewasm,
+    );
+
+    assert_eq!(
+        first_error(ns.diagnostics),
+        "parameter of type ‘struct c.S’ in public or external function requires ‘pragma abicoder v2’"
+    );
+
+    let ns = parse_and_resolve(
+        r##"
+        pragma solidity ^0.8.0;
+        pragma abicoder v1;
+
+        contract c {
+            function f() public returns (string[] memory s) {
+            }
+        }"##,
+        Target::Ewasm,
+    );
+
+    assert_eq!(
+        first_error(ns.diagnostics),
+        "return type ‘string[]’ in public or external function requires ‘pragma abicoder v2’"
+    );
+
+    let ns = parse_and_resolve(
+        r##"
+        pragma solidity ^0.7.0;
+        pragma abicoder v2;
+
+        contract c {
+            struct S {
+                int32 f1;
+            }
+
+            function f(S memory s) public returns (int32 r, string[] memory t) {
+            }
+
+            function g(string[] memory s) internal {
+            }
+        }"##,
+        Target::Ewasm,
+    );
+
+    no_errors(ns.diagnostics);
+
+    // substrate does not use the ethereum abi
+    let ns = parse_and_resolve(
+        r##"
+        pragma solidity ^0.7.0;
+
+        contract c {
+            struct S {
+                int32 f1;
+            }
+
+            function f(S memory s) public returns (int32) {
+                return s.f1;
+            }
+        }"##,
+        Target::Substrate,
+    );
+
+    no_errors(ns.diagnostics);
+}